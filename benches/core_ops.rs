@@ -0,0 +1,177 @@
+//! Benchmarks for core git operations on synthetic repositories
+//!
+//! Fixture repositories come from the `testkit` feature. Run with
+//! `cargo bench --features testkit`; compare against a baseline with
+//! `-- --save-baseline before` / `-- --baseline before`.
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use futures::StreamExt;
+use kodegen_tools_git::testkit::{self, HOT_FILE, RepoSpec, SyntheticRepo};
+use kodegen_tools_git::{
+    AddOpts, DiffOpts, HistoryOpts, LogOpts, MidxOpts, RepoHandle, add, diff, history, is_clean,
+    is_clean_fast, log, reachable_count, write_midx,
+};
+use tokio::runtime::Runtime;
+
+const SEED: u64 = 0x006b_6f64_6567_656e;
+
+fn runtime() -> Runtime {
+    Runtime::new().expect("create tokio runtime")
+}
+
+fn fixture(rt: &Runtime, spec: RepoSpec) -> SyntheticRepo {
+    rt.block_on(testkit::generate(spec)).expect("generate fixture repo")
+}
+
+fn bench_log_walk(c: &mut Criterion) {
+    let rt = runtime();
+    let fx = fixture(&rt, RepoSpec::new(SEED).files(200).commits(500).files_per_commit(3));
+
+    c.bench_function("log_walk_500", |b| {
+        b.to_async(&rt).iter(|| async {
+            let count = log(fx.repo.clone(), LogOpts::new(), None).count().await;
+            assert_eq!(count, fx.commits.len());
+        });
+    });
+}
+
+fn bench_diff_large_commit(c: &mut Criterion) {
+    let rt = runtime();
+    let fx = fixture(&rt, RepoSpec::new(SEED).files(5_000).commits(1).files_per_commit(2_000));
+    let from = fx.commits[0].to_string();
+    let to = fx.commits[1].to_string();
+
+    c.bench_function("diff_large_commit", |b| {
+        b.to_async(&rt).iter(|| async {
+            diff(fx.repo.clone(), DiffOpts::new(from.clone()).to(to.clone()))
+                .await
+                .expect("diff");
+        });
+    });
+}
+
+fn bench_history_hot_file(c: &mut Criterion) {
+    let rt = runtime();
+    let fx = fixture(&rt, RepoSpec::new(SEED).files(500).commits(200).files_per_commit(5));
+
+    c.bench_function("history_hot_file", |b| {
+        b.to_async(&rt).iter(|| async {
+            history(fx.repo.clone(), HistoryOpts::new(HOT_FILE))
+                .await
+                .expect("history");
+        });
+    });
+}
+
+fn bench_status_large_worktree(c: &mut Criterion) {
+    let rt = runtime();
+    let fx = fixture(&rt, RepoSpec::new(SEED).files(20_000));
+
+    let mut group = c.benchmark_group("status_20k_files");
+    group.sample_size(20);
+    group.bench_function("is_clean", |b| {
+        b.to_async(&rt)
+            .iter(|| async { assert!(is_clean(fx.repo.clone()).await.expect("status")) });
+    });
+    group.bench_function("is_clean_fast", |b| {
+        b.to_async(&rt)
+            .iter(|| async { assert!(is_clean_fast(fx.repo.clone()).await.expect("status")) });
+    });
+    group.finish();
+}
+
+fn bench_add_many_files(c: &mut Criterion) {
+    let rt = runtime();
+
+    let mut group = c.benchmark_group("add");
+    group.sample_size(10);
+    group.bench_function("add_5000_files", |b| {
+        b.to_async(&rt).iter_batched(
+            || {
+                let dir = tempfile::TempDir::new().expect("create temp dir");
+                testkit::write_files(dir.path(), SEED, 5_000).expect("write files");
+                // Setup runs inside the runtime, so init synchronously
+                let repo = RepoHandle::new(gix::init(dir.path()).expect("init repo"));
+                (dir, repo)
+            },
+            |(dir, repo)| async move {
+                add(repo, AddOpts::new(["src"])).await.expect("add");
+                drop(dir);
+            },
+            BatchSize::PerIteration,
+        );
+    });
+    group.finish();
+}
+
+fn bench_add_50k_files(c: &mut Criterion) {
+    let rt = runtime();
+
+    // Single-threaded hashing against the default pool
+    let mut group = c.benchmark_group("add_50000_files");
+    group.sample_size(10);
+    for (name, jobs) in [("serial", 1), ("parallel", 0)] {
+        group.bench_function(name, |b| {
+            b.to_async(&rt).iter_batched(
+                || {
+                    let dir = tempfile::TempDir::new().expect("create temp dir");
+                    testkit::write_files(dir.path(), SEED, 50_000).expect("write files");
+                    let repo = RepoHandle::new(gix::init(dir.path()).expect("init repo"));
+                    (dir, repo)
+                },
+                |(dir, repo)| async move {
+                    add(repo, AddOpts::new(["src"]).jobs(jobs))
+                        .await
+                        .expect("add");
+                    drop(dir);
+                },
+                BatchSize::PerIteration,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_reachable_count(c: &mut Criterion) {
+    let rt = runtime();
+    let fx = fixture(
+        &rt,
+        RepoSpec::new(SEED)
+            .files(2_000)
+            .commits(2_000)
+            .files_per_commit(5),
+    );
+    // Bitmaps cover packed objects only
+    let repacked = std::process::Command::new("git")
+        .args(["repack", "-adq"])
+        .current_dir(fx.dir.path())
+        .status()
+        .expect("run git repack");
+    assert!(repacked.success(), "git repack failed");
+
+    let mut group = c.benchmark_group("reachability");
+    group.sample_size(10);
+    group.bench_function("walk", |b| {
+        b.to_async(&rt)
+            .iter(|| async { reachable_count(fx.repo.clone()).await.expect("count") });
+    });
+    rt.block_on(write_midx(fx.repo.clone(), MidxOpts::new().bitmap(true)))
+        .expect("write bitmap");
+    group.bench_function("bitmap", |b| {
+        b.to_async(&rt)
+            .iter(|| async { reachable_count(fx.repo.clone()).await.expect("count") });
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_log_walk,
+    bench_diff_large_commit,
+    bench_history_hot_file,
+    bench_status_large_worktree,
+    bench_add_many_files,
+    bench_add_50k_files,
+    bench_reachable_count,
+);
+criterion_main!(benches);
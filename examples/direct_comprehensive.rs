@@ -1,18 +1,30 @@
 //! Comprehensive Git Operations Integration Example
 //!
-//! This example creates a real git repository and exercises all operations
-//! that were fixed for index corruption bugs:
+//! This example generates a repository with `testkit` (a few hundred files,
+//! history, branches and binary files) and exercises all operations that were
+//! fixed for index corruption bugs:
 //! - add (staging files)
 //! - commit (creating commits)
 //! - branch (creating/switching branches)
 //! - reset (soft/mixed/hard)
 //! - checkout (branches/commits)
 //! - worktree (linked worktrees)
+//! - merge (conflicting branches are rejected without side effects)
 //!
 //! After each operation, we verify that the git index maintains a valid
 //! SHA-1 checksum and correct structure, proving our fixes work.
+//!
+//! Needs the `testkit` feature (declared as a required feature in
+//! `Cargo.toml`, so default builds skip this example):
+//!
+//! ```bash
+//! cargo run --example direct_comprehensive --features testkit
+//! ```
 
 use anyhow::{Context, Result};
+use kodegen_tools_git::testkit::{
+    RepoSpec, SyntheticRepo, fabricate_merge_conflict, generate, write_files,
+};
 use kodegen_tools_git::{
     self as git, AddOpts, BranchOpts, CheckoutOpts, CommitOpts, RepoHandle, ResetMode, ResetOpts,
     WorktreeAddOpts,
@@ -23,7 +35,7 @@ use std::time::{Duration, Instant};
 /// Type alias for scenario function signature
 type ScenarioFn =
     fn(
-        &TestRepository,
+        &SyntheticRepo,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<ScenarioStats>> + '_>>;
 
 /// Statistics from running a test scenario
@@ -55,133 +67,19 @@ struct IndexStats {
     version: u32,
 }
 
-/// Test repository with RAII cleanup
-struct TestRepository {
-    repo: RepoHandle,
-    path: PathBuf,
-    start_time: Instant,
-}
-
-impl TestRepository {
-    /// Create a new test repository in workspace `tmp/` directory
-    async fn new() -> Result<Self> {
-        let workspace_root = std::env::current_dir()
-            .context("Failed to get current directory")?
-            .ancestors()
-            .find(|p| p.join("Cargo.toml").exists() && p.join("packages").exists())
-            .context("Failed to find workspace root")?
-            .to_path_buf();
-        
-        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let path = workspace_root.join(format!("tmp/git_ops_test_{timestamp}"));
-
-        // Create directory
-        std::fs::create_dir_all(&path)
-            .with_context(|| format!("Failed to create test directory: {}", path.display()))?;
-
-        // Initialize git repository
-        let repo = git::init_repo(&path)
-            .await
-            .context("Failed to initialize git repository")?
-            .map_err(|e| anyhow::anyhow!("Git error: {e}"))?;
-
-        println!("[TEST] Created test repository at: {}", path.display());
-
-        // Configure git identity (required for commits and reflog)
-        std::process::Command::new("git")
-            .args(["config", "user.name", "Test User"])
-            .current_dir(&path)
-            .output()
-            .context("Failed to configure git user.name")?;
-
-        std::process::Command::new("git")
-            .args(["config", "user.email", "test@example.com"])
-            .current_dir(&path)
-            .output()
-            .context("Failed to configure git user.email")?;
-
-        let test_repo = Self {
-            repo: repo.clone(),
-            path: path.clone(),
-            start_time: Instant::now(),
-        };
-
-        // Create initial commit to initialize index
-        // This is necessary because gix::init() doesn't create the index file
-        test_repo.create_file(".gitignore", b"# Git ignore file\n")?;
-
-        git::add(
-            repo.clone(),
-            AddOpts {
-                paths: vec![path.join(".gitignore")],
-                update_only: false,
-                force: false,
-            },
-        )
-        .await
-        .context("Failed to add .gitignore")?;
-
-        git::commit(
-            repo.clone(),
-            CommitOpts {
-                message: "Initial commit".to_string(),
-                amend: false,
-                all: false,
-                author: None,
-                committer: None,
-            },
-        )
-        .await
-        .context("Failed to create initial commit")?;
-
-        // Verify index was created correctly
-        test_repo.verify_index().await?;
-
-        Ok(test_repo)
-    }
-
-    /// Create a file in the repository
-    fn create_file(&self, name: &str, content: &[u8]) -> Result<PathBuf> {
-        let file_path = self.path.join(name);
+/// Write a file into the repository's worktree
+fn create_file(repo: &SyntheticRepo, name: &str, content: &[u8]) -> Result<PathBuf> {
+    let file_path = repo.path().join(name);
 
-        // Create parent directories if needed
-        if let Some(parent) = file_path.parent() {
-            std::fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create parent directory for {name}"))?;
-        }
-
-        std::fs::write(&file_path, content)
-            .with_context(|| format!("Failed to write file: {name}"))?;
-
-        Ok(file_path)
-    }
-
-    /// Verify git index integrity
-    async fn verify_index(&self) -> Result<IndexStats> {
-        verify_index_integrity(&self.repo).await
+    // Create parent directories if needed
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create parent directory for {name}"))?;
     }
 
-    /// Clean up test repository
-    fn cleanup(&self) -> Result<()> {
-        println!("[TEST] Cleaning up repository at: {}", self.path.display());
-        std::fs::remove_dir_all(&self.path)
-            .with_context(|| format!("Failed to remove test directory: {}", self.path.display()))?;
-        Ok(())
-    }
+    std::fs::write(&file_path, content).with_context(|| format!("Failed to write file: {name}"))?;
 
-    /// Get elapsed time since repo creation
-    fn elapsed(&self) -> Duration {
-        self.start_time.elapsed()
-    }
-}
-
-impl Drop for TestRepository {
-    fn drop(&mut self) {
-        // Attempt cleanup, but don't panic if it fails during drop
-        if let Err(e) = self.cleanup() {
-            eprintln!("[WARN] Failed to cleanup test repository: {e}");
-        }
-    }
+    Ok(file_path)
 }
 
 /// Verify index file integrity using gix
@@ -215,32 +113,6 @@ async fn verify_index_integrity(repo: &RepoHandle) -> Result<IndexStats> {
     .context("Task join error")?
 }
 
-/// Generate small text file content (100 bytes)
-fn generate_small_text() -> Vec<u8> {
-    b"Hello from kodegen_git test!\nThis is a small file.\nLine 3\nLine 4\nLine 5\n".to_vec()
-}
-
-/// Generate medium text file content (50KB)
-fn generate_medium_text() -> Vec<u8> {
-    let line = "The quick brown fox jumps over the lazy dog. 1234567890\n";
-    let repeat_count = (50 * 1024) / line.len();
-    line.repeat(repeat_count).into_bytes()
-}
-
-/// Generate large text file content (2MB)
-fn generate_large_text() -> Vec<u8> {
-    let line = "[2025-01-20 12:34:56] INFO: Processing request from client 192.168.1.100\n";
-    let repeat_count = (2 * 1024 * 1024) / line.len();
-    line.repeat(repeat_count).into_bytes()
-}
-
-/// Generate binary file content (1MB of patterned data)
-fn generate_binary() -> Vec<u8> {
-    let pattern = [0xDE, 0xAD, 0xBE, 0xEF, 0xCA, 0xFE, 0xBA, 0xBE];
-    let repeat_count = (1024 * 1024) / pattern.len();
-    pattern.repeat(repeat_count)
-}
-
 /// Performance tracker for operations
 struct PerformanceTracker {
     operations: Vec<(String, Duration)>,
@@ -301,67 +173,35 @@ impl PerformanceTracker {
 }
 
 /// Scenario: Test add operations
-async fn scenario_add(repo: &TestRepository) -> Result<ScenarioStats> {
+async fn scenario_add(repo: &SyntheticRepo) -> Result<ScenarioStats> {
     println!("\n[SCENARIO] Testing ADD operations...");
     let mut stats = ScenarioStats::new();
     let start = Instant::now();
 
-    // Create files with different content types
-    repo.create_file("file1.txt", &generate_small_text())?;
-    repo.create_file("file2.txt", &generate_medium_text())?;
-    repo.create_file("file3.log", &generate_large_text())?;
-    repo.create_file("data/binary.bin", &generate_binary())?;
-    repo.create_file("src/main.rs", b"fn main() {\n    println!(\"Hello\");\n}\n")?;
-    stats.files_created = 5;
+    // Generated source files, spread over src/m<n>/ directories
+    write_files(&repo.path().join("added"), 7, 50)?;
+    stats.files_created = 50;
 
-    // Add files one by one
-    println!("[ADD] Adding file1.txt...");
-    git::add(
-        repo.repo.clone(),
-        AddOpts {
-            paths: vec![repo.path.join("file1.txt")],
-            update_only: false,
-            force: false,
-        },
-    )
-    .await
-    .context("Failed to add file1.txt")?;
-    stats.operations_count += 1;
-
-    repo.verify_index().await?;
-    stats.index_verifications += 1;
-
-    println!("[ADD] Adding file2.txt...");
-    git::add(
-        repo.repo.clone(),
-        AddOpts {
-            paths: vec![repo.path.join("file2.txt")],
-            update_only: false,
-            force: false,
-        },
-    )
-    .await
-    .context("Failed to add file2.txt")?;
-    stats.operations_count += 1;
+    // Add directories one by one
+    for dir in ["added/src/m0", "added/src/m1"] {
+        println!("[ADD] Adding {dir}...");
+        git::add(repo.repo.clone(), AddOpts::new([repo.path().join(dir)]))
+            .await
+            .with_context(|| format!("Failed to add {dir}"))?;
+        stats.operations_count += 1;
 
-    repo.verify_index().await?;
-    stats.index_verifications += 1;
+        verify_index_integrity(&repo.repo).await?;
+        stats.index_verifications += 1;
+    }
 
     // Add multiple files in batch
     println!("[ADD] Adding remaining files in batch...");
-    git::add(
-        repo.repo.clone(),
-        AddOpts {
-            paths: vec![repo.path.clone()],
-            update_only: false,
-            force: false,
-        },
-    )
-    .await
-    .context("Failed to add remaining files")?;
+    git::add(repo.repo.clone(), AddOpts::new([repo.path().to_path_buf()]))
+        .await
+        .context("Failed to add remaining files")?;
     stats.operations_count += 1;
 
-    let index_stats = repo.verify_index().await?;
+    let index_stats = verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     println!(
@@ -374,43 +214,31 @@ async fn scenario_add(repo: &TestRepository) -> Result<ScenarioStats> {
 }
 
 /// Scenario: Test commit operations
-async fn scenario_commit(repo: &TestRepository) -> Result<ScenarioStats> {
+async fn scenario_commit(repo: &SyntheticRepo) -> Result<ScenarioStats> {
     println!("\n[SCENARIO] Testing COMMIT operations...");
     let mut stats = ScenarioStats::new();
     let start = Instant::now();
 
     // Create and stage files
-    repo.create_file("README.md", b"# Test Project\n\nThis is a test.\n")?;
-    repo.create_file("LICENSE", b"MIT License\n\nCopyright 2025\n")?;
-    repo.create_file(
+    create_file(repo, "README.md", b"# Test Project\n\nThis is a test.\n")?;
+    create_file(repo, "LICENSE", b"MIT License\n\nCopyright 2025\n")?;
+    create_file(
+        repo,
         "src/lib.rs",
         b"pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
     )?;
     stats.files_created = 3;
 
-    git::add(
-        repo.repo.clone(),
-        AddOpts {
-            paths: vec![repo.path.clone()],
-            update_only: false,
-            force: false,
-        },
-    )
-    .await
-    .context("Failed to stage files")?;
+    git::add(repo.repo.clone(), AddOpts::new([repo.path().to_path_buf()]))
+        .await
+        .context("Failed to stage files")?;
     stats.operations_count += 1;
 
     // Create initial commit
     println!("[COMMIT] Creating initial commit...");
     let commit_id = git::commit(
         repo.repo.clone(),
-        CommitOpts {
-            message: "Initial commit\n\nAdded README, LICENSE, and lib.rs".to_string(),
-            amend: false,
-            all: false,
-            author: None,
-            committer: None,
-        },
+        CommitOpts::message("Initial commit\n\nAdded README, LICENSE, and lib.rs".to_string()),
     )
     .await
     .context("Failed to create initial commit")?;
@@ -418,7 +246,7 @@ async fn scenario_commit(repo: &TestRepository) -> Result<ScenarioStats> {
 
     println!("[COMMIT] Created commit: {:?}", commit_id);
 
-    let index_stats = repo.verify_index().await?;
+    let index_stats = verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
     println!(
         "[COMMIT] ✓ Index valid after commit: {} entries",
@@ -426,38 +254,27 @@ async fn scenario_commit(repo: &TestRepository) -> Result<ScenarioStats> {
     );
 
     // Modify files and commit again
-    repo.create_file(
+    create_file(
+        repo,
         "README.md",
         b"# Test Project\n\nUpdated readme.\n\n## Features\n",
     )?;
-    repo.create_file(
+    create_file(
+        repo,
         "CHANGELOG.md",
         b"# Changelog\n\n## v0.1.0\n- Initial release\n",
     )?;
     stats.files_created += 1;
 
-    git::add(
-        repo.repo.clone(),
-        AddOpts {
-            paths: vec![repo.path.clone()],
-            update_only: false,
-            force: false,
-        },
-    )
-    .await
-    .context("Failed to stage modified files")?;
+    git::add(repo.repo.clone(), AddOpts::new([repo.path().to_path_buf()]))
+        .await
+        .context("Failed to stage modified files")?;
     stats.operations_count += 1;
 
     println!("[COMMIT] Creating second commit...");
     let commit_id2 = git::commit(
         repo.repo.clone(),
-        CommitOpts {
-            message: "Update README and add CHANGELOG".to_string(),
-            amend: false,
-            all: false,
-            author: None,
-            committer: None,
-        },
+        CommitOpts::message("Update README and add CHANGELOG".to_string()),
     )
     .await
     .context("Failed to create second commit")?;
@@ -465,7 +282,7 @@ async fn scenario_commit(repo: &TestRepository) -> Result<ScenarioStats> {
 
     println!("[COMMIT] Created commit: {:?}", commit_id2);
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     println!("[COMMIT] ✓ Commit chain verified");
@@ -475,7 +292,7 @@ async fn scenario_commit(repo: &TestRepository) -> Result<ScenarioStats> {
 }
 
 /// Scenario: Test branch operations
-async fn scenario_branch(repo: &TestRepository) -> Result<ScenarioStats> {
+async fn scenario_branch(repo: &SyntheticRepo) -> Result<ScenarioStats> {
     println!("\n[SCENARIO] Testing BRANCH operations...");
     let mut stats = ScenarioStats::new();
     let start = Instant::now();
@@ -497,7 +314,7 @@ async fn scenario_branch(repo: &TestRepository) -> Result<ScenarioStats> {
     .context("Failed to create feature branch")?;
     stats.operations_count += 1;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     // Create develop branch
@@ -517,11 +334,11 @@ async fn scenario_branch(repo: &TestRepository) -> Result<ScenarioStats> {
     .context("Failed to create develop branch")?;
     stats.operations_count += 1;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     // Get current branch
-    let current = git::current_branch(&repo.repo)
+    let current = git::current_branch(repo.repo.clone())
         .await
         .context("Failed to get current branch")?;
     println!("[BRANCH] Current branch: {}", current.name);
@@ -541,39 +358,27 @@ async fn scenario_branch(repo: &TestRepository) -> Result<ScenarioStats> {
 }
 
 /// Scenario: Test reset operations
-async fn scenario_reset(repo: &TestRepository) -> Result<ScenarioStats> {
+async fn scenario_reset(repo: &SyntheticRepo) -> Result<ScenarioStats> {
     println!("\n[SCENARIO] Testing RESET operations...");
     let mut stats = ScenarioStats::new();
     let start = Instant::now();
 
     // Create some commits to reset
     for i in 1..=3 {
-        repo.create_file(
+        create_file(
+            repo,
             &format!("reset_test_{i}.txt"),
             format!("Content {i}\n").as_bytes(),
         )?;
         stats.files_created += 1;
 
-        git::add(
-            repo.repo.clone(),
-            AddOpts {
-                paths: vec![repo.path.clone()],
-                update_only: false,
-                force: false,
-            },
-        )
-        .await
-        .with_context(|| format!("Failed to stage reset_test_{i}.txt"))?;
+        git::add(repo.repo.clone(), AddOpts::new([repo.path().to_path_buf()]))
+            .await
+            .with_context(|| format!("Failed to stage reset_test_{i}.txt"))?;
 
         git::commit(
             repo.repo.clone(),
-            CommitOpts {
-                message: format!("Add reset_test_{i}.txt"),
-                amend: false,
-                all: false,
-                author: None,
-                committer: None,
-            },
+            CommitOpts::message(format!("Add reset_test_{i}.txt")),
         )
         .await
         .with_context(|| format!("Failed to commit reset_test_{i}.txt"))?;
@@ -581,7 +386,7 @@ async fn scenario_reset(repo: &TestRepository) -> Result<ScenarioStats> {
     }
 
     // Get current commit
-    let before_reset = git::current_branch(&repo.repo)
+    let before_reset = git::current_branch(repo.repo.clone())
         .await
         .context("Failed to get current branch before reset")?;
     println!("[RESET] Current commit: {}", before_reset.commit_hash);
@@ -589,18 +394,19 @@ async fn scenario_reset(repo: &TestRepository) -> Result<ScenarioStats> {
     // Soft reset - keeps index and working directory
     println!("[RESET] Performing soft reset to HEAD~1...");
     git::reset(
-        &repo.repo,
+        repo.repo.clone(),
         ResetOpts {
             target: "HEAD~1".to_string(),
             mode: ResetMode::Soft,
             cancel_token: None,
+            progress: None,
         },
     )
     .await
     .context("Failed to perform soft reset")?;
     stats.operations_count += 1;
 
-    let index_stats = repo.verify_index().await?;
+    let index_stats = verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
     println!(
         "[RESET] ✓ Soft reset complete, index has {} entries",
@@ -610,18 +416,19 @@ async fn scenario_reset(repo: &TestRepository) -> Result<ScenarioStats> {
     // Mixed reset - updates index but keeps working directory
     println!("[RESET] Performing mixed reset to HEAD~1...");
     git::reset(
-        &repo.repo,
+        repo.repo.clone(),
         ResetOpts {
             target: "HEAD~1".to_string(),
             mode: ResetMode::Mixed,
             cancel_token: None,
+            progress: None,
         },
     )
     .await
     .context("Failed to perform mixed reset")?;
     stats.operations_count += 1;
 
-    let index_stats = repo.verify_index().await?;
+    let index_stats = verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
     println!(
         "[RESET] ✓ Mixed reset complete, index has {} entries",
@@ -631,18 +438,19 @@ async fn scenario_reset(repo: &TestRepository) -> Result<ScenarioStats> {
     // Hard reset - resets index and working directory
     println!("[RESET] Performing hard reset to HEAD...");
     git::reset(
-        &repo.repo,
+        repo.repo.clone(),
         ResetOpts {
             target: "HEAD".to_string(),
             mode: ResetMode::Hard,
             cancel_token: None,
+            progress: None,
         },
     )
     .await
     .context("Failed to perform hard reset")?;
     stats.operations_count += 1;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
     println!("[RESET] ✓ Hard reset complete");
 
@@ -651,7 +459,7 @@ async fn scenario_reset(repo: &TestRepository) -> Result<ScenarioStats> {
 }
 
 /// Scenario: Test checkout operations
-async fn scenario_checkout(repo: &TestRepository) -> Result<ScenarioStats> {
+async fn scenario_checkout(repo: &SyntheticRepo) -> Result<ScenarioStats> {
     println!("\n[SCENARIO] Testing CHECKOUT operations...");
     let mut stats = ScenarioStats::new();
     let start = Instant::now();
@@ -676,17 +484,13 @@ async fn scenario_checkout(repo: &TestRepository) -> Result<ScenarioStats> {
     println!("[CHECKOUT] Switching to feature/checkout-test...");
     git::checkout(
         repo.repo.clone(),
-        CheckoutOpts {
-            reference: "feature/checkout-test".to_string(),
-            force: false,
-            paths: None,
-        },
+        CheckoutOpts::new("feature/checkout-test"),
     )
     .await
     .context("Failed to checkout feature branch")?;
     stats.operations_count += 1;
 
-    let index_stats = repo.verify_index().await?;
+    let index_stats = verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
     println!(
         "[CHECKOUT] ✓ Switched to feature branch, index has {} entries",
@@ -694,56 +498,39 @@ async fn scenario_checkout(repo: &TestRepository) -> Result<ScenarioStats> {
     );
 
     // Add some files on this branch
-    repo.create_file("feature.txt", b"Feature work\n")?;
+    create_file(repo, "feature.txt", b"Feature work\n")?;
     stats.files_created += 1;
 
     git::add(
         repo.repo.clone(),
-        AddOpts {
-            paths: vec![repo.path.join("feature.txt")],
-            update_only: false,
-            force: false,
-        },
+        AddOpts::new([repo.path().join("feature.txt")]),
     )
     .await
     .context("Failed to add feature.txt")?;
 
     git::commit(
         repo.repo.clone(),
-        CommitOpts {
-            message: "Add feature work".to_string(),
-            amend: false,
-            all: false,
-            author: None,
-            committer: None,
-        },
+        CommitOpts::message("Add feature work".to_string()),
     )
     .await
     .context("Failed to commit on feature branch")?;
     stats.operations_count += 2;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     // Checkout back to main
     println!("[CHECKOUT] Switching back to main...");
-    git::checkout(
-        repo.repo.clone(),
-        CheckoutOpts {
-            reference: "main".to_string(),
-            force: false,
-            paths: None,
-        },
-    )
-    .await
-    .context("Failed to checkout main")?;
+    git::checkout(repo.repo.clone(), CheckoutOpts::new("main"))
+        .await
+        .context("Failed to checkout main")?;
     stats.operations_count += 1;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     // Verify feature.txt doesn't exist on main
-    let feature_file = repo.path.join("feature.txt");
+    let feature_file = repo.path().join("feature.txt");
     if feature_file.exists() {
         anyhow::bail!("feature.txt should not exist on main branch!");
     }
@@ -754,7 +541,7 @@ async fn scenario_checkout(repo: &TestRepository) -> Result<ScenarioStats> {
 }
 
 /// Scenario: Test worktree operations
-async fn scenario_worktree(repo: &TestRepository) -> Result<ScenarioStats> {
+async fn scenario_worktree(repo: &SyntheticRepo) -> Result<ScenarioStats> {
     println!("\n[SCENARIO] Testing WORKTREE operations...");
     let mut stats = ScenarioStats::new();
     let start = Instant::now();
@@ -775,22 +562,9 @@ async fn scenario_worktree(repo: &TestRepository) -> Result<ScenarioStats> {
     .context("Failed to create worktree test branch")?;
     stats.operations_count += 1;
 
-    // Add a worktree
-    let worktree_path = repo
-        .path
-        .parent()
-        .ok_or_else(|| anyhow::anyhow!("Failed to get parent directory"))?
-        .join("worktree_branch");
-
-    // Clean up worktree path if it exists from previous run
-    if worktree_path.exists() {
-        std::fs::remove_dir_all(&worktree_path).with_context(|| {
-            format!(
-                "Failed to cleanup old worktree: {}",
-                worktree_path.display()
-            )
-        })?;
-    }
+    // Add a worktree outside the repository, removed with its directory
+    let worktree_dir = tempfile::TempDir::new().context("Failed to create worktree directory")?;
+    let worktree_path = worktree_dir.path().join("worktree_branch");
 
     println!(
         "[WORKTREE] Creating worktree at {}...",
@@ -820,7 +594,7 @@ async fn scenario_worktree(repo: &TestRepository) -> Result<ScenarioStats> {
     let worktree_name = worktree_path
         .file_name()
         .ok_or_else(|| anyhow::anyhow!("Invalid worktree path"))?;
-    let worktree_admin_dir = repo.path.join(".git/worktrees").join(worktree_name);
+    let worktree_admin_dir = repo.path().join(".git/worktrees").join(worktree_name);
     let worktree_index = worktree_admin_dir.join("index");
 
     if !worktree_index.exists() {
@@ -832,11 +606,7 @@ async fn scenario_worktree(repo: &TestRepository) -> Result<ScenarioStats> {
 
     println!("[WORKTREE] ✓ Worktree created with index");
 
-    // Cleanup worktree
-    std::fs::remove_dir_all(&worktree_path)
-        .with_context(|| format!("Failed to cleanup worktree: {}", worktree_path.display()))?;
-
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     stats.duration = start.elapsed();
@@ -844,13 +614,13 @@ async fn scenario_worktree(repo: &TestRepository) -> Result<ScenarioStats> {
 }
 
 /// Scenario: Test `open_repo` operations
-async fn scenario_open_repo(repo: &TestRepository) -> Result<ScenarioStats> {
+async fn scenario_open_repo(repo: &SyntheticRepo) -> Result<ScenarioStats> {
     println!("\n[SCENARIO] Testing OPEN_REPO operations...");
     let mut stats = ScenarioStats::new();
     let start = Instant::now();
 
     // Get the repository path
-    let repo_path = repo.path.clone();
+    let repo_path = repo.path().to_path_buf();
 
     println!(
         "[OPEN_REPO] Testing opening existing repository at: {}",
@@ -868,7 +638,7 @@ async fn scenario_open_repo(repo: &TestRepository) -> Result<ScenarioStats> {
     println!("[OPEN_REPO] ✓ Successfully opened repository");
 
     // Verify the opened repository works by reading current branch
-    let current = git::current_branch(&opened_repo)
+    let current = git::current_branch(opened_repo.clone())
         .await
         .context("Failed to get current branch from opened repo")?;
     println!("[OPEN_REPO] ✓ Current branch: {}", current.name);
@@ -907,43 +677,34 @@ async fn scenario_open_repo(repo: &TestRepository) -> Result<ScenarioStats> {
 }
 
 /// Scenario: Complex workflow combining multiple operations
-async fn scenario_complex_workflow(repo: &TestRepository) -> Result<ScenarioStats> {
+async fn scenario_complex_workflow(repo: &SyntheticRepo) -> Result<ScenarioStats> {
     println!("\n[SCENARIO] Testing COMPLEX WORKFLOW...");
     let mut stats = ScenarioStats::new();
     let start = Instant::now();
 
     // Create main branch with baseline
-    repo.create_file("app/main.rs", b"fn main() {\n    println!(\"v1.0\");\n}\n")?;
-    repo.create_file(
+    create_file(
+        repo,
+        "app/main.rs",
+        b"fn main() {\n    println!(\"v1.0\");\n}\n",
+    )?;
+    create_file(
+        repo,
         "app/lib.rs",
         b"pub fn version() -> &'static str { \"1.0\" }\n",
     )?;
     stats.files_created += 2;
 
-    git::add(
-        repo.repo.clone(),
-        AddOpts {
-            paths: vec![repo.path.clone()],
-            update_only: false,
-            force: false,
-        },
-    )
-    .await?;
+    git::add(repo.repo.clone(), AddOpts::new([repo.path().to_path_buf()])).await?;
 
     git::commit(
         repo.repo.clone(),
-        CommitOpts {
-            message: "Baseline v1.0".to_string(),
-            amend: false,
-            all: false,
-            author: None,
-            committer: None,
-        },
+        CommitOpts::message("Baseline v1.0".to_string()),
     )
     .await?;
     stats.operations_count += 2;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     // Create feature branch and make changes
@@ -960,72 +721,48 @@ async fn scenario_complex_workflow(repo: &TestRepository) -> Result<ScenarioStat
     .await
     .map_err(|e| anyhow::anyhow!("Channel error: {e}"))??;
 
-    git::checkout(
-        repo.repo.clone(),
-        CheckoutOpts {
-            reference: "feature/v2".to_string(),
-            force: false,
-            paths: None,
-        },
-    )
-    .await?;
+    git::checkout(repo.repo.clone(), CheckoutOpts::new("feature/v2")).await?;
     stats.operations_count += 2;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     // Make 3 commits on feature branch
     for i in 1..=3 {
-        repo.create_file(
+        create_file(
+            repo,
             "app/lib.rs",
             format!("pub fn version() -> &'static str {{ \"2.{i}\" }}\n").as_bytes(),
         )?;
 
         git::add(
             repo.repo.clone(),
-            AddOpts {
-                paths: vec![repo.path.join("app/lib.rs")],
-                update_only: false,
-                force: false,
-            },
+            AddOpts::new([repo.path().join("app/lib.rs")]),
         )
         .await?;
 
         git::commit(
             repo.repo.clone(),
-            CommitOpts {
-                message: format!("Update to v2.{i}"),
-                amend: false,
-                all: false,
-                author: None,
-                committer: None,
-            },
+            CommitOpts::message(format!("Update to v2.{i}")),
         )
         .await?;
         stats.operations_count += 2;
 
-        repo.verify_index().await?;
+        verify_index_integrity(&repo.repo).await?;
         stats.index_verifications += 1;
     }
 
     println!("[COMPLEX] Created 3 commits on feature/v2");
 
     // Switch back to main and make conflicting changes
-    git::checkout(
-        repo.repo.clone(),
-        CheckoutOpts {
-            reference: "main".to_string(),
-            force: false,
-            paths: None,
-        },
-    )
-    .await?;
+    git::checkout(repo.repo.clone(), CheckoutOpts::new("main")).await?;
     stats.operations_count += 1;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
-    repo.create_file(
+    create_file(
+        repo,
         "app/main.rs",
         b"fn main() {\n    println!(\"v1.5 - stable\");\n}\n",
     )?;
@@ -1033,28 +770,18 @@ async fn scenario_complex_workflow(repo: &TestRepository) -> Result<ScenarioStat
 
     git::add(
         repo.repo.clone(),
-        AddOpts {
-            paths: vec![repo.path.join("app/main.rs")],
-            update_only: false,
-            force: false,
-        },
+        AddOpts::new([repo.path().join("app/main.rs")]),
     )
     .await?;
 
     git::commit(
         repo.repo.clone(),
-        CommitOpts {
-            message: "Update main to v1.5".to_string(),
-            amend: false,
-            all: false,
-            author: None,
-            committer: None,
-        },
+        CommitOpts::message("Update main to v1.5".to_string()),
     )
     .await?;
     stats.operations_count += 2;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     println!("[COMPLEX] ✓ Created divergent history");
@@ -1062,17 +789,18 @@ async fn scenario_complex_workflow(repo: &TestRepository) -> Result<ScenarioStat
     // Demonstrate reset in complex scenario
     println!("[COMPLEX] Resetting to demonstrate state management...");
     git::reset(
-        &repo.repo,
+        repo.repo.clone(),
         ResetOpts {
             target: "HEAD".to_string(),
             mode: ResetMode::Mixed,
             cancel_token: None,
+            progress: None,
         },
     )
     .await?;
     stats.operations_count += 1;
 
-    repo.verify_index().await?;
+    verify_index_integrity(&repo.repo).await?;
     stats.index_verifications += 1;
 
     println!("[COMPLEX] ✓ Complex workflow completed successfully");
@@ -1081,6 +809,47 @@ async fn scenario_complex_workflow(repo: &TestRepository) -> Result<ScenarioStat
     Ok(stats)
 }
 
+/// Scenario: Merge conflicts are reported without touching the repository
+async fn scenario_merge_conflict(repo: &SyntheticRepo) -> Result<ScenarioStats> {
+    println!("\n[SCENARIO] Testing MERGE CONFLICT handling...");
+    let mut stats = ScenarioStats::new();
+    let start = Instant::now();
+
+    let conflict = fabricate_merge_conflict(repo.repo.clone(), "example")
+        .await
+        .context("Failed to fabricate merge conflict")?;
+    stats.operations_count += 1;
+    stats.files_created += 1;
+    println!(
+        "[CONFLICT] Created {} changed on HEAD and on {}",
+        conflict.path, conflict.theirs
+    );
+
+    let result = git::merge(repo.repo.clone(), git::MergeOpts::new(&conflict.theirs)).await;
+    stats.operations_count += 1;
+    match result {
+        Err(git::GitError::MergeConflict { message: msg, .. }) => {
+            println!("[CONFLICT] ✓ Merge reported conflict: {msg}");
+        }
+        Ok(outcome) => anyhow::bail!("Expected a merge conflict, got {outcome:?}"),
+        Err(e) => anyhow::bail!("Expected a merge conflict, got error: {e}"),
+    }
+
+    let head = git::head_commit(repo.repo.clone())
+        .await
+        .context("Failed to read HEAD")?;
+    if head != conflict.ours.to_string() {
+        anyhow::bail!("HEAD moved after failed merge: {head}");
+    }
+    println!("[CONFLICT] ✓ HEAD unchanged after failed merge");
+
+    verify_index_integrity(&repo.repo).await?;
+    stats.index_verifications += 1;
+
+    stats.duration = start.elapsed();
+    Ok(stats)
+}
+
 /// Main entry point - runs all test scenarios
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -1093,17 +862,30 @@ async fn main() -> Result<()> {
 
     let mut perf_tracker = PerformanceTracker::new();
 
-    // Create test repository
-    println!("[INIT] Creating test repository...");
-    let start = Instant::now();
-    let repo = TestRepository::new().await?;
-    perf_tracker.track("Repository initialization".to_string(), start.elapsed());
+    // Generate test repository: 300 files, 50 commits, 3 merged branches
+    println!("[INIT] Generating test repository...");
+    let started = Instant::now();
+    let spec = RepoSpec::new(42)
+        .files(300)
+        .commits(50)
+        .branches(3, 5)
+        .merge_branches(true)
+        .binary_files(4, 256 * 1024);
+    let repo = generate(spec)
+        .await
+        .context("Failed to generate test repository")?;
+    perf_tracker.track("Repository generation".to_string(), started.elapsed());
 
-    println!("[INIT] ✓ Repository created at: {}", repo.path.display());
     println!(
-        "[INIT] ✓ Total elapsed: {:.3}s\n",
-        repo.elapsed().as_secs_f64()
+        "[INIT] ✓ Repository generated at: {}",
+        repo.path().display()
+    );
+    println!(
+        "[INIT] ✓ {} commits, branches: {}",
+        repo.commits.len(),
+        repo.branches.join(", ")
     );
+    verify_index_integrity(&repo.repo).await?;
 
     // Run all scenarios
     let scenarios: Vec<(&str, ScenarioFn)> = vec![
@@ -1117,6 +899,7 @@ async fn main() -> Result<()> {
         ("COMPLEX_WORKFLOW", |r| {
             Box::pin(scenario_complex_workflow(r))
         }),
+        ("MERGE_CONFLICT", |r| Box::pin(scenario_merge_conflict(r))),
     ];
 
     let mut total_stats = ScenarioStats::new();
@@ -1154,7 +937,7 @@ async fn main() -> Result<()> {
 
     // Final verification
     println!("\n[FINAL] Performing final index verification...");
-    let final_index = repo.verify_index().await?;
+    let final_index = verify_index_integrity(&repo.repo).await?;
     println!(
         "[FINAL] ✓ Index is valid: {} entries (version {})",
         final_index.entry_count, final_index.version
@@ -1177,7 +960,7 @@ async fn main() -> Result<()> {
     );
     println!(
         "Total elapsed time:      {:.3}s",
-        repo.elapsed().as_secs_f64()
+        started.elapsed().as_secs_f64()
     );
 
     // Performance report
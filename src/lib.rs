@@ -6,13 +6,15 @@
 
 use std::path::PathBuf;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use gix::hash::ObjectId;
 use thiserror::Error;
 
 // Module declarations
 pub mod operations;
 pub mod runtime;
+#[cfg(feature = "testkit")]
+pub mod testkit;
 pub mod tools;
 
 // Re-export runtime types
@@ -20,37 +22,99 @@ pub use runtime::{AsyncStream, AsyncTask, EmitterBuilder};
 
 // Re-export Git operations
 pub use operations::{
-    AddOpts, BranchInfo, BranchOpts, ChangeType, CheckoutOpts, CloneOpts, CommitOpts, CommitResult,
-    DetailedCommitInfo, DiffOpts, DiffStats, FetchOpts, FileDiffStats, GitUrl, HistoryCommit,
-    HistoryOpts, HistoryResult, LogOpts, MergeOpts, MergeOutcome, PullOpts, PullResult, PushOpts,
-    PushResult, RemoteAddOpts, RemoteInfo, RepoPaths, RepositoryInfo, ResetMode, ResetOpts,
-    Signature, TagInfo, TagOpts, WorktreeAddOpts, WorktreeInfo, WorktreeLockOpts,
-    WorktreeRemoveOpts, add, add_remote, branch, check_remote_branch_exists,
-    check_remote_tag_exists, checkout, clone_repo, commit, create_tag, current_branch, delete_branch,
-    delete_remote_branch, delete_remote_tag, delete_tag, diff, discover_repo, fetch,
-    get_commit_details, get_repo_paths, head_commit, history, init_bare_repo, init_repo, is_clean,
-    is_detached, is_repository, list_branches, list_remotes, list_tags, list_worktrees, log, merge,
-    open_repo, parse_git_url, probe_repository, pull, push, push_current_branch, push_tags,
-    remote_exists, remove_remote, rename_branch, reset, reset_hard, reset_mixed, reset_soft,
-    stash_pop, stash_save, StashInfo, StashOpts, tag_exists, worktree_add, worktree_lock,
-    worktree_prune, worktree_remove, worktree_unlock,
+    AddOpts, AddResult, ApplyOpts, ApplyResult, AttributeRule, AttributesEdit, AuthorMapping,
+    BackportOpts, BackportOutcome, BackportResult, BlameLine, BlameOpts, BlameResult,
+    BranchComparison, BranchInfo, BranchOpts, ChangeType, CheckoutOpts, CheckoutProgress,
+    CherryCommit, CherryPickOpts, CherryPickResult, CleanupPolicy, CleanupReason, CleanupReport,
+    CloneOpts, ClonePreflight, CloneResult, Codeowners, CodeownersIssue, CodeownersRule,
+    CombinedFileDiff, CommitMatch, CommitOpts, CommitQuery, CommitResult, CommitSearchResult,
+    ConflictKind, ConflictedFile, Decoration, DecorationKind, Deepen, DetailedCommitInfo, DiffHunk,
+    DiffLine, DiffOpts, DiffStats, Discovery, DiscoveryBoundary, EolIssue, ExportOverwrite,
+    ExportResult, FetchHeadEntry, FetchOpts, FetchResult, FetchVerification, FileDiffStats,
+    FilePatch, GitCapabilities, GitEnvironment, GitUrl, GrepMatch, GrepOpts, GrepResult,
+    HistoryCommit, HistoryOpts, HistoryResult, HostKind, IdentityRule, IgnoreEdit, IgnorePattern,
+    ImpactReport, ImpactRule, ImpactedTarget, IndexSeed, InitOpts, LFS_ATTRIBUTES, LineEnding,
+    LineOrigin, LintCheck, LintFinding, LintReport, LintRules, LintSeverity, LockCleanupReport,
+    LockFile, LogOpts, MergeDiff, MergeOpts, MergeOutcome, MergeState, Mergeability, MidxOpts,
+    MidxResult, ModeChange, NestedRepo, NestedRepoKind, NestedScanOpts, OpenOpts, OperationWarning,
+    OwnerCandidate, PathIssue, PathIssueKind, PathOwners, PreflightIssue, ProgressCallback,
+    PruneReason, PrunedWorktree, PullOpts, PullResult, PushOpts, PushResult, RebaseOpts,
+    RebaseResult, RefUpdate, RefUpdateKind, ReleaseOpts, ReleaseResult, RemoteAddOpts, RemoteInfo,
+    ReplaceRef, RepoAlias, RepoPath, RepoPaths, RepositoryInfo, ResetMode, ResetOpts, ReviewCommit,
+    ReviewPacket, RewriteResult, RewrittenRef, RiskFlag, RiskKind, ScaffoldOpts, ScaffoldResult,
+    Signature, SkippedWorktree, SnapshotInfo, SnapshotRestoreResult, SquashMergeResult,
+    StaleBranch, StashInfo, StashOpts, SubtreeMergeResult, SubtreeSplit, SyncAction, SyncOpts,
+    SyncResult, TagInfo, TagOpts, TempIndex, Trailer, UpdateBranchOpts, UpdateBranchResult,
+    UpdateOutcome, UpdateStrategy, UpstreamStatus, WarningKind, WorkspaceCommit,
+    WorkspaceCommitOutcome, WorkspaceCommitPolicy, WorkspaceCommitResult, WorkspaceRepoStatus,
+    WorktreeAddOpts, WorktreeInfo, WorktreeLockOpts, WorktreePruneOpts, WorktreePruneReport,
+    WorktreeRemoveOpts, WorktreeStatus, add, add_patterns, add_remote, ancestry_path,
+    apply_to_index, backport, blame, branch, branches_containing, capabilities,
+    check_remote_branch_exists, check_remote_tag_exists, checkout, cherry, cherry_pick,
+    children_of, cleanup_branches, clear_remote_refs_cache, clear_stale_locks, clear_status_cache,
+    clone_preflight, clone_repo, commit, compare_branches, create_replace, create_tag,
+    current_branch, deepen, delete_branch, delete_remote_branch, delete_remote_tag, delete_replace,
+    delete_tag, detect_case_collisions, diff, discover_repo, discover_repo_report,
+    discover_repo_with, drop_snapshot, ensure_up_to_date, eol_audit, export_tree, fetch,
+    fetch_head, file_changed_between, file_exists_at, find_locks, find_nested_repos,
+    get_commit_details, get_repo_paths, graft, grep, head_commit, history, impacted_paths,
+    init_bare_repo, init_repo, init_repo_with, is_clean, is_clean_fast, is_detached, is_reachable,
+    is_repository, lfs_track, lfs_untrack, list_attributes, list_branches, list_patterns,
+    list_remotes, list_replace, list_snapshots, list_tags, list_worktrees, log, lookup_repo, merge,
+    merge_conflicts, merge_diff, merge_state, object_exists, open_repo, open_repo_with,
+    open_worktree, parse_date, parse_git_url, parse_patch, patch_id, previous_branch,
+    probe_repository, probe_repository_with, pull, push, push_current_branch, push_tags,
+    reachable_count, read_codeowners, rebase, register_repo, registered_repos,
+    remote_default_branch, remote_exists, remote_refs_exist, remote_refs_exist_cached,
+    remove_attributes, remove_paths, remove_patterns, remove_remote, rename_branch, render_graph,
+    renormalize, reset, reset_hard, reset_mixed, reset_soft, resolve_repo_path, restore_snapshot,
+    rewrite_authors, scaffold, set_attributes, set_lock_recovery, snapshot_worktree, squash_merge,
+    stash_pop, stash_save, subtree_add, subtree_merge, subtree_split, suggest_owners, sync_repo,
+    tag_exists, tags_containing, temporary_index, unregister_repo, update_branch, upstream_status,
+    workspace_commit, workspace_status, worktree_add, worktree_lock, worktree_prune,
+    worktree_prune_with, worktree_remove, worktree_statuses, worktree_unlock, write_midx,
 };
 
 // Re-export MCP tools
 pub use tools::{
-    GitAddTool, GitBranchCreateTool, GitBranchDeleteTool, GitBranchListTool, GitBranchRenameTool,
-    GitCheckoutTool, GitCloneTool, GitCommitTool, GitDiffTool, GitDiscoverTool, GitFetchTool,
-    GitHistoryTool, GitInitTool, GitLogTool, GitMergeTool, GitOpenTool, GitPullTool, GitPushTool,
-    GitRemoteAddTool, GitRemoteListTool, GitRemoteRemoveTool, GitResetTool, GitStashTool,
-    GitStatusTool, GitTagTool, GitWorktreeAddTool, GitWorktreeListTool, GitWorktreeLockTool,
-    GitWorktreePruneTool, GitWorktreeRemoveTool, GitWorktreeUnlockTool,
+    GitAddTool, GitApplyTool, GitAttributesEditTool, GitBlameTool, GitBranchCleanupTool,
+    GitBranchCreateTool, GitBranchDeleteTool, GitBranchListTool, GitBranchRenameTool,
+    GitCapabilitiesTool, GitCheckoutTool, GitCherryPickTool, GitCloneTool, GitCodeownersTool,
+    GitCommitTool, GitCompareTool, GitContainsTool, GitDiffTool, GitDiscoverTool, GitFetchTool,
+    GitFileInfoTool, GitHistoryTool, GitIgnoreEditTool, GitImpactTool, GitInitTool, GitLintTool,
+    GitLogTool, GitMaintenanceTool, GitMergeTool, GitMultiRepoTool, GitOpenTool, GitPullTool,
+    GitPushTool, GitRebaseTool, GitReleaseTool, GitRemoteAddTool, GitRemoteListTool,
+    GitRemoteRemoveTool, GitReplaceTool, GitRepoRegistryTool, GitResetTool, GitReviewPacketTool,
+    GitScaffoldTool, GitSearchCommitsTool, GitShowCommitTool, GitSnapshotTool, GitStashTool,
+    GitStatusTool, GitSubtreeTool, GitSuggestReviewersTool, GitSyncTool, GitTagTool,
+    GitWorktreeAddTool, GitWorktreeListTool, GitWorktreeLockTool, GitWorktreePruneTool,
+    GitWorktreeRemoveTool, GitWorktreeUnlockTool,
 };
 
 /// Error types for `GitGix` operations
 #[derive(Debug, Error)]
 pub enum GitError {
+    /// Any other gix error; the box holds the original error, so it can be
+    /// inspected with `downcast_ref`
     #[error("Gix error: {0}")]
-    Gix(#[from] Box<dyn std::error::Error + Send + Sync>),
+    Gix(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Object {id} not found")]
+    ObjectNotFound {
+        id: ObjectId,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// A ref, index or config lock file is held by another process
+    #[error("Lock held by another process: {0}")]
+    Locked(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Transport error: {0}")]
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Configuration error: {0}")]
+    Config(#[source] Box<dyn std::error::Error + Send + Sync>),
 
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -67,8 +131,18 @@ pub enum GitError {
     #[error("Reference `{0}` not found")]
     ReferenceNotFound(String),
 
-    #[error("Merge conflict: {0}")]
-    MergeConflict(String),
+    #[error("Merge conflict: {message}")]
+    MergeConflict { message: String, paths: Vec<String> },
+
+    /// Checkout could not write `count` files; `source` is the error for
+    /// `path`, the first of them
+    #[error("Checkout failed for {count} file(s), first `{path}`: {source}")]
+    CheckoutFailed {
+        path: String,
+        count: usize,
+        #[source]
+        source: Box<GitError>,
+    },
 
     #[error("Unsupported operation: {0}")]
     Unsupported(&'static str),
@@ -102,29 +176,133 @@ pub enum GitError {
 
     #[error("Invalid worktree name: {0}")]
     InvalidWorktreeName(String),
+
+    #[error("Branch '{branch}' is {behind} commit(s) behind {upstream} and {ahead} ahead")]
+    BehindUpstream {
+        branch: String,
+        upstream: String,
+        ahead: usize,
+        behind: usize,
+    },
+
+    #[error("Autostash reapplied with conflicts in {} (changes kept in {stash})", paths.join(", "))]
+    AutostashConflict { stash: String, paths: Vec<String> },
+
+    #[error("Fetched data failed verification: {0}")]
+    VerificationFailed(Box<FetchVerification>),
+
+    #[error("Clone preflight failed: {0}")]
+    PreflightFailed(Box<ClonePreflight>),
+}
+
+impl GitError {
+    /// Wrap a gix error, sorting it into [`GitError::ObjectNotFound`],
+    /// [`GitError::Locked`], [`GitError::Transport`] or [`GitError::Config`]
+    /// by the errors in its source chain, or [`GitError::Gix`] otherwise.
+    ///
+    /// The original error is kept in every case. A `GitError` passed in is
+    /// returned as is.
+    pub fn gix(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        let error = match error.into().downcast::<GitError>() {
+            Ok(error) => return *error,
+            Err(error) => error,
+        };
+        match GixErrorKind::of(&*error) {
+            Some(GixErrorKind::ObjectNotFound(id)) => {
+                GitError::ObjectNotFound { id, source: error }
+            }
+            Some(GixErrorKind::Locked) => GitError::Locked(error),
+            Some(GixErrorKind::Transport) => GitError::Transport(error),
+            Some(GixErrorKind::Config) => GitError::Config(error),
+            None => GitError::Gix(error),
+        }
+    }
+
+    /// The gix error behind this error, for `downcast_ref` to a concrete type
+    pub fn gix_source(&self) -> Option<&(dyn std::error::Error + Send + Sync + 'static)> {
+        match self {
+            GitError::Gix(error)
+            | GitError::ObjectNotFound { source: error, .. }
+            | GitError::Locked(error)
+            | GitError::Transport(error)
+            | GitError::Config(error) => Some(&**error),
+            GitError::CheckoutFailed { source, .. } => source.gix_source(),
+            _ => None,
+        }
+    }
+}
+
+/// Categories of gix errors with a dedicated [`GitError`] variant
+enum GixErrorKind {
+    ObjectNotFound(ObjectId),
+    Locked,
+    Transport,
+    Config,
+}
+
+impl GixErrorKind {
+    /// Category of the first error in the source chain of `error` that has one
+    fn of(error: &(dyn std::error::Error + 'static)) -> Option<Self> {
+        use gix::object::find::existing;
+
+        let mut current = Some(error);
+        while let Some(error) = current {
+            let not_found = match error.downcast_ref::<existing::Error>() {
+                Some(existing::Error::NotFound { oid }) => Some(*oid),
+                _ => match error.downcast_ref::<existing::with_conversion::Error>() {
+                    Some(existing::with_conversion::Error::Find(existing::Error::NotFound {
+                        oid,
+                    })) => Some(*oid),
+                    _ => None,
+                },
+            };
+            if let Some(oid) = not_found {
+                return Some(Self::ObjectNotFound(oid));
+            }
+            if let Some(gix::lock::acquire::Error::PermanentlyLocked { .. }) =
+                error.downcast_ref::<gix::lock::acquire::Error>()
+            {
+                return Some(Self::Locked);
+            }
+            if error.is::<gix::protocol::transport::client::Error>() {
+                return Some(Self::Transport);
+            }
+            if error.is::<gix::config::Error>() || error.is::<gix::config::parse::Error>() {
+                return Some(Self::Config);
+            }
+            current = error.source();
+        }
+        None
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for GitError {
+    fn from(e: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        GitError::gix(e)
+    }
 }
 
 impl From<gix::open::Error> for GitError {
     fn from(e: gix::open::Error) -> Self {
-        GitError::Gix(Box::new(e))
+        GitError::gix(e)
     }
 }
 
 impl From<gix::discover::Error> for GitError {
     fn from(e: gix::discover::Error) -> Self {
-        GitError::Gix(Box::new(e))
+        GitError::gix(e)
     }
 }
 
 impl From<gix::init::Error> for GitError {
     fn from(e: gix::init::Error) -> Self {
-        GitError::Gix(Box::new(e))
+        GitError::gix(e)
     }
 }
 
 impl From<gix::clone::Error> for GitError {
     fn from(e: gix::clone::Error) -> Self {
-        GitError::Gix(Box::new(e))
+        GitError::gix(e)
     }
 }
 
@@ -149,6 +327,10 @@ pub type GitResult<T> = Result<T, GitError>;
 #[derive(Debug, Clone)]
 pub struct RepoHandle {
     inner: gix::Repository,
+    /// Index file used instead of the repository's, see [`Self::with_index_file`]
+    index_file: Option<PathBuf>,
+    /// Keep sparse index entries collapsed, see [`Self::with_sparse_index`]
+    sparse_index: bool,
 }
 
 impl RepoHandle {
@@ -163,7 +345,11 @@ impl RepoHandle {
     /// ```
     #[inline]
     pub fn new(inner: gix::Repository) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            index_file: None,
+            sparse_index: false,
+        }
     }
 
     /// Access the underlying `gix::Repository` with zero cost.
@@ -203,6 +389,117 @@ impl RepoHandle {
     pub fn clone_inner(&self) -> gix::Repository {
         self.inner.clone()
     }
+
+    /// Cache up to `bytes` of decoded objects, shared by all clones of this handle.
+    ///
+    /// Trees and commits decoded by one operation stay warm for the next, which
+    /// helps agents that repeatedly walk history or diff on the same repository.
+    /// Pass `0` to disable the object cache.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use kodegen_tools_git::RepoHandle;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let handle = RepoHandle::new(gix::open("/path/to/repo")?)
+    ///     .with_object_cache(128 * 1024 * 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_object_cache(mut self, bytes: usize) -> Self {
+        operations::cache::set_object_cache(&mut self.inner, bytes);
+        self
+    }
+
+    /// Cache up to `bytes` of decoded pack entries (delta bases), shared by all clones.
+    ///
+    /// Pass `0` to disable the pack cache.
+    #[must_use]
+    pub fn with_pack_cache(mut self, bytes: usize) -> Self {
+        operations::cache::set_pack_cache(&mut self.inner, bytes);
+        self
+    }
+
+    /// Honor (or ignore) replace refs in object lookups made through this handle.
+    ///
+    /// With replacements honored, history walks follow grafts created with
+    /// [`graft`](operations::replace::graft). Enabling reopens the repository,
+    /// so call this before installing caches.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use kodegen_tools_git::RepoHandle;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let handle = RepoHandle::new(gix::open("/path/to/repo")?).with_replace_refs(true)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_replace_refs(mut self, enabled: bool) -> GitResult<Self> {
+        operations::replace::set_replace_refs(&mut self.inner, enabled)?;
+        Ok(self)
+    }
+
+    /// Clone the repository for history walks and tree diffs.
+    ///
+    /// Like [`clone_inner`](Self::clone_inner), but adds an object cache of
+    /// [`DEFAULT_TRAVERSAL_OBJECT_CACHE_BYTES`](operations::cache::DEFAULT_TRAVERSAL_OBJECT_CACHE_BYTES)
+    /// if none was configured with [`with_object_cache`](Self::with_object_cache).
+    /// Stage to and commit from the index file at `path` instead of the
+    /// repository's, like `GIT_INDEX_FILE`.
+    ///
+    /// `add`, `commit` and `apply_to_index` through this handle and its
+    /// clones use that index; a missing file is treated as an empty index.
+    /// See [`temporary_index`](operations::index::temporary_index) for a
+    /// private index that is removed when dropped.
+    #[must_use]
+    pub fn with_index_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.index_file = Some(path.into());
+        self
+    }
+
+    /// The index file set with [`with_index_file`](Self::with_index_file), if any
+    #[inline]
+    pub fn index_file(&self) -> Option<&std::path::Path> {
+        self.index_file.as_deref()
+    }
+
+    /// Work with a sparse index as it is, directories outside the sparse
+    /// checkout cone collapsed into one entry each.
+    ///
+    /// `add`, `commit`, `apply_to_index` and status then cost time in the
+    /// populated paths rather than the repository's size, which matters for
+    /// large monorepos. Off by default: a sparse index is expanded to one entry
+    /// per file when read. See [`operations::sparse`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use kodegen_tools_git::RepoHandle;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let handle = RepoHandle::new(gix::open("/path/to/monorepo")?).with_sparse_index(true);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_sparse_index(mut self, enabled: bool) -> Self {
+        self.sparse_index = enabled;
+        self
+    }
+
+    /// Whether sparse index entries stay collapsed, see
+    /// [`with_sparse_index`](Self::with_sparse_index)
+    #[inline]
+    pub fn sparse_index(&self) -> bool {
+        self.sparse_index
+    }
+
+    pub(crate) fn clone_for_traversal(&self) -> gix::Repository {
+        let mut repo = self.inner.clone();
+        operations::cache::ensure_traversal_cache(&mut repo);
+        repo
+    }
 }
 
 /// A unique commit identifier.
@@ -212,9 +509,22 @@ pub type CommitId = ObjectId;
 #[derive(Debug, Clone)]
 pub struct CommitInfo {
     pub id: CommitId,
+    /// Parent ids, first parent first; empty for root commits.
+    pub parents: Vec<CommitId>,
     pub author: Signature,
     pub summary: String,
+    /// Commit time in UTC
     pub time: DateTime<Utc>,
+    /// UTC offset the commit time was recorded in
+    pub offset: FixedOffset,
+    /// The commit's parents are missing because the repository is shallow;
+    /// history continues beyond it on the remote.
+    pub shallow_boundary: bool,
+    /// Branches, tags and `HEAD` pointing at the commit; only filled in when
+    /// requested, see [`LogOpts::decorate`].
+    pub decorations: Vec<Decoration>,
+    /// Problems reading the commit, such as a time that fell back to the epoch
+    pub warnings: Vec<OperationWarning>,
 }
 
 /// Backward compatibility module providing nested namespace for git operations.
@@ -324,32 +634,45 @@ pub async fn start_server_with_listener(
             let mut prompt_router = PromptRouter::new();
             let managers = Managers::new();
 
-            // Register all 27 git tools (zero-state structs, no constructors)
+            // Register all git tools (zero-state structs, no constructors)
 
-            // Repository initialization (4 tools)
+            // Repository initialization (7 tools)
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitInitTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitOpenTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCloneTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitDiscoverTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitScaffoldTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitRepoRegistryTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSyncTool);
 
-            // Branch operations (4 tools)
+            // Branch operations (5 tools)
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchCreateTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchDeleteTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchListTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchRenameTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchCleanupTool);
 
-            // Core git operations (6 tools)
+            // Core git operations (14 tools)
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCommitTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitLogTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBlameTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitHistoryTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSearchCommitsTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitShowCommitTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitDiffTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitFileInfoTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitAddTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitApplyTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitIgnoreEditTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitAttributesEditTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCheckoutTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCherryPickTool);
 
-            // Remote operations (7 tools)
+            // Remote operations (8 tools)
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitFetchTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitMergeTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitPullTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitRebaseTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitPushTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitRemoteAddTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitRemoteListTool);
@@ -363,11 +686,31 @@ pub async fn start_server_with_listener(
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitWorktreeUnlockTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitWorktreePruneTool);
 
-            // Other operations (4 tools)
+            // Review operations (6 tools)
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSuggestReviewersTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCodeownersTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCompareTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitContainsTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitImpactTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitReviewPacketTool);
+
+            // Maintenance operations (1 tool)
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSubtreeTool);
+
+            // Workspace operations (1 tool)
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitMultiRepoTool);
+
+            // Other operations (10 tools)
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitResetTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitStashTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSnapshotTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitStatusTool);
             (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitTagTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitReleaseTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitMaintenanceTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCapabilitiesTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitLintTool);
+            (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitReplaceTool);
 
             Ok(RouterSet::new(tool_router, prompt_router, managers))
         })
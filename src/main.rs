@@ -20,35 +20,50 @@ async fn main() -> Result<()> {
             // Register all git tools (zero-state structs, no constructors)
             use kodegen_tools_git::*;
 
-            // Repository initialization (4 tools)
+            // Repository initialization
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitInitTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitOpenTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCloneTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitDiscoverTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitScaffoldTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitRepoRegistryTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSyncTool);
 
-            // Branch operations (4 tools)
+            // Branch operations
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchCreateTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchDeleteTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchListTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchRenameTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBranchCleanupTool);
 
-            // Core git operations (9 tools)
+            // Core git operations
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCommitTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitLogTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitBlameTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitDiffTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitFileInfoTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitHistoryTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSearchCommitsTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitShowCommitTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitAddTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitApplyTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitIgnoreEditTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitAttributesEditTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCheckoutTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCherryPickTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitResetTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitStatusTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitStashTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSnapshotTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitTagTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitReleaseTool);
 
-            // Remote operations (7 tools)
+            // Remote operations
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitFetchTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitMergeTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitRebaseTool);
 
-            // Worktree operations (6 tools)
+            // Worktree operations
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitWorktreeAddTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitWorktreeRemoveTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitWorktreeListTool);
@@ -56,6 +71,24 @@ async fn main() -> Result<()> {
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitWorktreeUnlockTool);
             let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitWorktreePruneTool);
 
+            // Review operations
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSuggestReviewersTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCodeownersTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCompareTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitContainsTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitImpactTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitReviewPacketTool);
+
+            // Workspace operations
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitMultiRepoTool);
+
+            // Maintenance operations
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitMaintenanceTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitCapabilitiesTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitLintTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitReplaceTool);
+            let (tool_router, prompt_router) = register_tool(tool_router, prompt_router, GitSubtreeTool);
+
             Ok(RouterSet::new(tool_router, prompt_router, managers))
         })
         .run()
@@ -3,26 +3,67 @@
 //! This module provides the `AddOpts` builder pattern and add operation
 //! implementation for the `GitGix` service. Supports:
 //! - Individual files and directories (recursive)
-//! - Simple glob patterns (*, ?)
-//! - .gitignore respect (force flag to override)
+//! - Simple glob patterns (*, ?) matched against the repository-relative
+//!   path, so `*` also matches `/` as in git pathspecs
+//! - .gitignore respect for untracked files (force flag to override), with
+//!   skipped and force-added files reported in [`AddResult`]
 //! - Symlink handling per core.symlinks config
-//! - Update-only mode for tracked files
+//! - Update mode for tracked files and all mode, both staging deletions
+//! - Intent-to-add entries for new files
+//! - Blobs hashed and written by a bounded pool of threads for large adds
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-use gix::bstr::ByteSlice;
+use gix::bstr::{BString, ByteSlice};
+use gix::index::entry::{Flags, Mode, Stat};
 use walkdir::WalkDir;
 
+use super::index::open_index;
+use super::repo_path::RepoPath;
 use crate::{GitError, GitResult, RepoHandle};
 
 /// Options for `add` operation with builder pattern.
+///
+/// Paths are relative to the repository root, or absolute inside the
+/// worktree; `.` names the whole worktree.
 #[derive(Debug, Clone)]
 pub struct AddOpts {
     pub paths: Vec<PathBuf>,
     pub update_only: bool,
     pub force: bool,
+    /// Also stage new files and deletions, like `git add --all`.
+    pub all: bool,
+    /// Record new files without their content, like `git add -N`.
+    pub intent_to_add: bool,
+    /// Threads hashing files; 0 picks one per available CPU.
+    pub jobs: usize,
+}
+
+/// What an [`add`] staged and what it left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddResult {
+    /// Files staged, or recorded as intended to add.
+    pub added: Vec<RepoPath>,
+    /// Tracked files staged as deleted.
+    pub deleted: Vec<RepoPath>,
+    /// Untracked files skipped because they are ignored.
+    pub ignored: Vec<RepoPath>,
+    /// Ignored files staged anyway because of [`AddOpts::force`].
+    pub force_added: Vec<RepoPath>,
+    /// Paths given that matched no file.
+    pub missing: Vec<PathBuf>,
+    /// Files in directories collapsed by a sparse index, which are outside
+    /// the sparse checkout and left alone (see
+    /// [`RepoHandle::with_sparse_index`]).
+    pub sparse_skipped: Vec<RepoPath>,
 }
 
+/// Files below which an add is hashed on the calling thread
+const PARALLEL_THRESHOLD: usize = 256;
+/// Fewest files worth a thread of their own
+const FILES_PER_JOB: usize = 64;
+
 impl AddOpts {
     /// Create new add options with the given paths.
     #[inline]
@@ -35,6 +76,9 @@ impl AddOpts {
             paths: paths.into_iter().map(Into::into).collect(),
             update_only: false,
             force: false,
+            all: false,
+            intent_to_add: false,
+            jobs: 0,
         }
     }
 
@@ -45,7 +89,9 @@ impl AddOpts {
         self
     }
 
-    /// Enable update-only mode (only add already tracked files).
+    /// Enable update-only mode, like `git add --update`: stage changes and
+    /// deletions of tracked files only. Without paths it covers the whole
+    /// worktree.
     #[inline]
     #[must_use]
     pub fn update_only(mut self, yes: bool) -> Self {
@@ -60,16 +106,42 @@ impl AddOpts {
         self.force = yes;
         self
     }
+
+    /// Stage every change under the paths, new files and deletions
+    /// included, like `git add --all`. Without paths it covers the whole
+    /// worktree.
+    #[inline]
+    #[must_use]
+    pub fn all(mut self, yes: bool) -> Self {
+        self.all = yes;
+        self
+    }
+
+    /// Record untracked files as intended to be added, like `git add -N`:
+    /// they show up in diffs but are left out of commits until staged.
+    /// Tracked files are left as they are.
+    #[inline]
+    #[must_use]
+    pub fn intent_to_add(mut self, yes: bool) -> Self {
+        self.intent_to_add = yes;
+        self
+    }
+
+    /// Limit the threads hashing files; 0, the default, uses one per
+    /// available CPU and 1 hashes on a single thread.
+    #[inline]
+    #[must_use]
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
 }
 
 /// Check if a path string contains glob pattern characters.
 /// Single-pass check with zero allocations.
 #[inline]
-fn has_glob_pattern(path: &Path) -> bool {
-    path.as_os_str()
-        .as_encoded_bytes()
-        .iter()
-        .any(|&b| b == b'*' || b == b'?')
+fn has_glob_pattern(path: &[u8]) -> bool {
+    path.iter().any(|&b| b == b'*' || b == b'?')
 }
 
 /// Simple glob pattern matching for * and ? wildcards.
@@ -129,136 +201,171 @@ fn simple_glob_match_impl(
     pat_idx >= pattern.len() && text_idx >= text.len()
 }
 
-/// Expand input paths to concrete file paths.
+/// Turn an input path into a pathspec relative to the repository root.
+///
+/// `.` and the root itself become the empty pathspec, which matches
+/// everything.
+fn pathspec(input: &Path, repo_path: &Path) -> GitResult<BString> {
+    let relative = if input.is_absolute() {
+        input.strip_prefix(repo_path).map_err(|_| {
+            GitError::InvalidInput(format!("Path {} is not within repository", input.display()))
+        })?
+    } else {
+        input
+    };
+    let mut spec = relative.as_os_str().as_encoded_bytes();
+    while let Some(rest) = spec.strip_prefix(b"./") {
+        spec = rest;
+    }
+    if spec == b"." {
+        spec = b"";
+    }
+    Ok(spec.trim_end_with(|c| c == '/').into())
+}
+
+/// Whether `path` is selected by `spec`: a glob matching the whole path, or
+/// the path itself or a directory above it.
+fn pathspec_matches(spec: &[u8], path: &[u8]) -> bool {
+    if has_glob_pattern(spec) {
+        return simple_glob_match(spec, path);
+    }
+    spec.is_empty()
+        || path == spec
+        || (path.starts_with(spec) && path.get(spec.len()) == Some(&b'/'))
+}
+
+/// Files below `dir`, skipping `.git` directories and files
+fn walk_files(dir: &Path, result: &mut Vec<PathBuf>) -> GitResult<()> {
+    // Skip .git directories to avoid walking internal git files, and the
+    // `.git` files of submodules and separate git dirs, which aren't content
+    for entry in WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+    {
+        let entry = entry.map_err(|e| GitError::Io(e.into()))?;
+        if entry.file_type().is_file() {
+            result.push(entry.path().to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// Expand pathspecs to concrete file paths.
 /// Handles directories (recursive), glob patterns, and individual files.
+/// Also returns the indices of the pathspecs that matched no file.
 ///
 /// # Contract
 /// All returned paths are absolute. Callers can rely on this guarantee.
 #[inline]
-fn expand_paths(paths: &[PathBuf], repo_path: &Path) -> GitResult<Vec<PathBuf>> {
-    let mut result = Vec::with_capacity(paths.len() * 4);
-
-    for input_path in paths {
-        let full_path = if input_path.is_absolute() {
-            input_path.clone()
-        } else {
-            repo_path.join(input_path)
-        };
-
-        if has_glob_pattern(input_path) {
-            // Glob pattern: walk parent directory and match
-            let parent = full_path.parent().ok_or_else(|| {
-                GitError::InvalidInput(format!("Invalid glob pattern: {}", input_path.display()))
-            })?;
-
-            let pattern_bytes = full_path
-                .file_name()
-                .ok_or_else(|| {
-                    GitError::InvalidInput(format!("Invalid pattern: {}", input_path.display()))
-                })?
-                .as_encoded_bytes();
-
-            if !parent.exists() {
-                return Err(GitError::InvalidInput(format!(
-                    "Pattern parent directory does not exist: {}",
-                    parent.display()
-                )));
-            }
-
-            for entry in WalkDir::new(parent)
-                .max_depth(1)
-                .min_depth(1)
-                .into_iter()
-                .filter_entry(|e| e.file_type().is_file())
-            {
-                let entry = entry.map_err(|e| GitError::Io(e.into()))?;
-                let filename_bytes = entry.file_name().as_encoded_bytes();
-                if simple_glob_match(pattern_bytes, filename_bytes) {
-                    result.push(entry.path().to_path_buf());
-                }
+fn expand_paths(specs: &[BString], repo_path: &Path) -> GitResult<(Vec<PathBuf>, Vec<usize>)> {
+    let mut result = Vec::with_capacity(specs.len() * 4);
+    let mut unmatched = Vec::new();
+
+    for (i, spec) in specs.iter().enumerate() {
+        let matched_before = result.len();
+        let spec = spec.as_bytes();
+        let full_path = repo_path.join(gix::path::from_byte_slice(spec));
+
+        if has_glob_pattern(spec) {
+            // Glob pattern: walk the directory above the first wildcard and
+            // match the repository-relative path of every file in it
+            let wildcard = spec.find_byteset(b"*?").unwrap_or(spec.len());
+            let base = spec[..wildcard]
+                .rfind_byte(b'/')
+                .map_or(&b""[..], |slash| &spec[..slash]);
+            let base = repo_path.join(gix::path::from_byte_slice(base));
+            if base.is_dir() {
+                let mut files = Vec::new();
+                walk_files(&base, &mut files)?;
+                result.extend(files.into_iter().filter(|file| {
+                    file.strip_prefix(repo_path).is_ok_and(|relative| {
+                        simple_glob_match(spec, relative.as_os_str().as_encoded_bytes())
+                    })
+                }));
             }
         } else if full_path.is_dir() {
-            // Directory: recursively collect all files (skip .git directory)
-            for entry in WalkDir::new(&full_path).into_iter().filter_entry(|e| {
-                // Skip .git directory to avoid walking internal git files
-                if e.file_type().is_dir() {
-                    e.file_name() != ".git"
-                } else {
-                    true
-                }
-            }) {
-                let entry = entry.map_err(|e| GitError::Io(e.into()))?;
-                if entry.file_type().is_file() {
-                    result.push(entry.path().to_path_buf());
-                }
-            }
-        } else {
+            // Directory: recursively collect all files
+            walk_files(&full_path, &mut result)?;
+        } else if full_path.symlink_metadata().is_ok() {
             // Regular file or symlink
             result.push(full_path);
         }
+
+        if result.len() == matched_before {
+            unmatched.push(i);
+        }
     }
 
-    Ok(result)
+    Ok((result, unmatched))
 }
 
-/// Process a single file: handle symlinks, read content, create blob, add to index.
+/// A file to stage: its absolute path and its path in the index
+struct PendingFile {
+    file_path: PathBuf,
+    path: BString,
+}
+
+/// A file whose blob is written, ready for the index
+struct HashedFile {
+    stat: Stat,
+    id: gix::ObjectId,
+    mode: Mode,
+}
+
+/// Index mode of a file, following symlinks if `core.symlinks` is off
+fn entry_mode(
+    file_path: &Path,
+    fs_metadata: &gix::index::fs::Metadata,
+    symlinks_enabled: bool,
+) -> GitResult<Mode> {
+    if fs_metadata.is_symlink() && symlinks_enabled {
+        return Ok(Mode::SYMLINK);
+    }
+    let is_executable = if fs_metadata.is_symlink() {
+        // Check executable bit from target file's metadata (Unix only)
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::metadata(file_path)?.permissions().mode() & 0o111 != 0
+        }
+        #[cfg(not(unix))]
+        {
+            false // Windows doesn't have Unix executable bits
+        }
+    } else {
+        fs_metadata.is_executable()
+    };
+    Ok(if is_executable {
+        Mode::FILE_EXECUTABLE
+    } else {
+        Mode::FILE
+    })
+}
+
+/// Read a single file, handling symlinks, and write its blob.
 #[inline]
-fn process_single_file(
+fn hash_file(
     repo: &gix::Repository,
-    index: &mut gix::index::File,
     file_path: &Path,
-    relative_path: &Path,
     symlinks_enabled: bool,
-) -> GitResult<()> {
-    use gix::index::entry::{Flags, Mode, Stat};
-
+) -> GitResult<HashedFile> {
     // Get file metadata once (lstat - doesn't follow symlinks)
     let fs_metadata = gix::index::fs::Metadata::from_path_no_follow(file_path)?;
+    let mode = entry_mode(file_path, &fs_metadata, symlinks_enabled)?;
 
-    let (blob_data, mode) = if fs_metadata.is_symlink() {
-        if symlinks_enabled {
-            // Store symlink target
-            let target = std::fs::read_link(file_path)?;
-            let target_bytes = target.as_os_str().as_encoded_bytes().to_vec();
-            (target_bytes, Mode::SYMLINK)
-        } else {
-            // Follow symlink and store content from target file
-            let content = std::fs::read(file_path)?;
-            // Check executable bit from target file's metadata (Unix only)
-            #[cfg(unix)]
-            let is_executable = {
-                use std::os::unix::fs::PermissionsExt;
-                let target_metadata = std::fs::metadata(file_path)?;
-                target_metadata.permissions().mode() & 0o111 != 0
-            };
-            #[cfg(not(unix))]
-            let is_executable = false; // Windows doesn't have Unix executable bits
-
-            let mode = if is_executable {
-                Mode::FILE_EXECUTABLE
-            } else {
-                Mode::FILE
-            };
-            (content, mode)
-        }
+    let blob_data = if mode == Mode::SYMLINK {
+        // Store symlink target
+        let target = std::fs::read_link(file_path)?;
+        target.as_os_str().as_encoded_bytes().to_vec()
     } else {
-        // Regular file - reuse metadata for executable check
-        let content = std::fs::read(file_path)?;
-        let mode = if fs_metadata.is_executable() {
-            Mode::FILE_EXECUTABLE
-        } else {
-            Mode::FILE
-        };
-        (content, mode)
+        // Regular file, or the target of a symlink when symlinks are off
+        std::fs::read(file_path)?
     };
 
     // Write blob to ODB
-    let blob_id = repo
-        .write_blob(&blob_data)
-        .map_err(|e| GitError::Gix(e.into()))?
-        .detach();
+    let blob_id = repo.write_blob(&blob_data).map_err(GitError::gix)?.detach();
 
-    // Reuse metadata for stat (already have it from line 211)
+    // Reuse metadata for stat
     let stat = Stat::from_fs(&fs_metadata).map_err(|e| {
         GitError::InvalidInput(format!(
             "Failed to create stat for {}: {}",
@@ -267,27 +374,103 @@ fn process_single_file(
         ))
     })?;
 
-    // Convert path to BStr for gix API
-    let path_bstr = relative_path.as_os_str().as_encoded_bytes().as_bstr();
+    Ok(HashedFile {
+        stat,
+        id: blob_id,
+        mode,
+    })
+}
 
-    // Add to index
-    index.dangerously_push_entry(stat, blob_id, Flags::empty(), mode, path_bstr);
+/// Hash and write the blobs of `files`, in order.
+///
+/// Large sets are split into contiguous chunks over up to `jobs` threads,
+/// each with its own thread-local repository; object writes are safe to run
+/// concurrently. The first error wins.
+fn hash_files(
+    repo: &gix::Repository,
+    files: &[PendingFile],
+    symlinks_enabled: bool,
+    jobs: usize,
+) -> GitResult<Vec<HashedFile>> {
+    let jobs = match jobs {
+        0 => std::thread::available_parallelism().map_or(1, usize::from),
+        jobs => jobs,
+    }
+    .min(files.len().div_ceil(FILES_PER_JOB));
+    if jobs <= 1 || files.len() < PARALLEL_THRESHOLD {
+        return files
+            .iter()
+            .map(|file| hash_file(repo, &file.file_path, symlinks_enabled))
+            .collect();
+    }
 
-    Ok(())
+    let shared = repo.clone().into_sync();
+    let chunk_size = files.len().div_ceil(jobs);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let shared = &shared;
+                scope.spawn(move || {
+                    let repo = shared.to_thread_local();
+                    chunk
+                        .iter()
+                        .map(|file| hash_file(&repo, &file.file_path, symlinks_enabled))
+                        .collect::<GitResult<Vec<_>>>()
+                })
+            })
+            .collect();
+
+        let mut hashed = Vec::with_capacity(files.len());
+        for worker in workers {
+            let chunk = worker
+                .join()
+                .map_err(|_| GitError::InvalidInput("Hashing thread panicked".to_string()))??;
+            hashed.extend(chunk);
+        }
+        Ok(hashed)
+    })
 }
 
 /// Execute add operation with the given options.
-pub async fn add(repo: RepoHandle, opts: AddOpts) -> GitResult<()> {
+///
+/// Paths that match nothing and ignored files are reported in the result
+/// rather than failing the add, unless nothing at all matched. Retries once
+/// after clearing stale lock files when lock recovery is enabled (see
+/// [`super::maintenance::set_lock_recovery`]).
+pub async fn add(repo: RepoHandle, opts: AddOpts) -> GitResult<AddResult> {
+    super::maintenance::with_lock_recovery(repo, |repo| add_once(repo, opts.clone())).await
+}
+
+/// Single add attempt
+async fn add_once(repo: RepoHandle, opts: AddOpts) -> GitResult<AddResult> {
     let repo_clone = repo.clone_inner();
+    let index_file = repo.index_file().map(Path::to_path_buf);
+    let sparse_index = repo.sparse_index();
 
     tokio::task::spawn_blocking(move || {
         let AddOpts {
             paths,
             update_only,
             force,
+            all,
+            intent_to_add,
+            jobs,
         } = opts;
 
-        if paths.is_empty() {
+        if all && update_only {
+            return Err(GitError::InvalidInput(
+                "all and update_only cannot be combined".to_string(),
+            ));
+        }
+        if intent_to_add && update_only {
+            return Err(GitError::InvalidInput(
+                "intent_to_add and update_only cannot be combined".to_string(),
+            ));
+        }
+        // Like git, --all and --update without paths cover the whole worktree
+        let stage_deletions = all || update_only;
+        if paths.is_empty() && !stage_deletions {
             return Err(GitError::InvalidInput(
                 "No paths specified for add".to_string(),
             ));
@@ -303,49 +486,71 @@ pub async fn add(repo: RepoHandle, opts: AddOpts) -> GitResult<()> {
         let symlinks_enabled = config.boolean("core.symlinks").unwrap_or(true);
 
         // Expand input paths to concrete file paths
-        let expanded_paths = expand_paths(&paths, repo_path)?;
+        let specs = if paths.is_empty() {
+            vec![BString::default()]
+        } else {
+            paths
+                .iter()
+                .map(|path| pathspec(path, repo_path))
+                .collect::<GitResult<Vec<_>>>()?
+        };
+        let (expanded_paths, unmatched) = expand_paths(&specs, repo_path)?;
 
-        if expanded_paths.is_empty() {
+        if expanded_paths.is_empty() && !stage_deletions {
             return Err(GitError::InvalidInput(
                 "No files matched the given patterns".to_string(),
             ));
         }
 
         // Open mutable index (create empty one if it doesn't exist)
-        let mut index = if let Ok(idx) = repo_clone.open_index() {
-            idx
-        } else {
-            // Index doesn't exist yet (freshly initialized repo)
-            // Create an empty index
-            let index_path = repo_clone.index_path();
-            let object_hash = repo_clone.object_hash();
-            let mut new_index =
-                gix::index::File::from_state(gix::index::State::new(object_hash), index_path);
-            // Write the empty index to disk
-            new_index
-                .write(gix::index::write::Options::default())
-                .map_err(|e| GitError::Gix(e.into()))?;
-            // Re-open it
-            repo_clone
-                .open_index()
-                .map_err(|e| GitError::Gix(e.into()))?
-        };
+        let mut index =
+            if let Ok(idx) = open_index(&repo_clone, index_file.as_deref(), sparse_index) {
+                idx
+            } else {
+                // Index doesn't exist yet (freshly initialized repo)
+                // Create an empty index
+                let index_path = repo_clone.index_path();
+                let object_hash = repo_clone.object_hash();
+                let mut new_index =
+                    gix::index::File::from_state(gix::index::State::new(object_hash), index_path);
+                // Write the empty index to disk
+                new_index
+                    .write(gix::index::write::Options::default())
+                    .map_err(GitError::gix)?;
+                // Re-open it
+                repo_clone.open_index().map_err(GitError::gix)?
+            };
 
-        // Setup .gitignore checking if not forcing
-        let mut excludes = if force {
-            None
-        } else {
-            Some(
-                repo_clone.excludes(
-                    &index,
-                    None,
-                    gix::worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
-                )
-                .map_err(|e| GitError::Gix(e.into()))?,
+        // Pathspecs that matched no file, nor a tracked file to delete
+        let mut result = AddResult::default();
+        if !paths.is_empty() {
+            result.missing = unmatched
+                .into_iter()
+                .filter(|&i| {
+                    !stage_deletions
+                        || !index
+                            .entries()
+                            .iter()
+                            .any(|entry| pathspec_matches(&specs[i], entry.path(&index)))
+                })
+                .map(|i| paths[i].clone())
+                .collect();
+        }
+
+        // Setup .gitignore checking, also when forcing to report what it overrides
+        let mut excludes = repo_clone
+            .excludes(
+                &index,
+                None,
+                gix::worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
             )
-        };
+            .map_err(GitError::gix)?;
+
+        // Entries read from disk, so files added again can drop their old entry
+        let tracked = index.entries().len();
+        let mut pending = Vec::with_capacity(expanded_paths.len());
 
-        // Process each file (all paths from expand_paths are absolute)
+        // Select the files to stage (all paths from expand_paths are absolute)
         for file_path in expanded_paths {
             // Convert to relative path (file_path is guaranteed absolute by expand_paths)
             let relative_path = file_path
@@ -361,42 +566,103 @@ pub async fn add(repo: RepoHandle, opts: AddOpts) -> GitResult<()> {
             // Convert path to BStr for gix operations
             let path_bstr = relative_path.as_os_str().as_encoded_bytes().as_bstr();
 
-            // Check update_only mode
-            if update_only && index.entry_by_path(path_bstr).is_none() {
-                // Skip files not already in index
+            // Directories collapsed by a sparse index are outside the sparse
+            // checkout; like git without --sparse, leave them alone
+            if super::sparse::in_sparse_dir(&index, path_bstr) {
+                result.sparse_skipped.push(path_bstr.into());
                 continue;
             }
 
-            // Check .gitignore (if not forcing) - reuse path_bstr
-            if let Some(ref mut exc) = excludes {
-                let platform = exc.at_entry(path_bstr, None)?;
+            // Check update_only mode; intent-to-add leaves tracked files alone
+            let is_tracked = index.entry_by_path(path_bstr).is_some();
+            if (update_only && !is_tracked) || (intent_to_add && is_tracked) {
+                continue;
+            }
 
-                if platform.is_excluded() {
-                    // Skip ignored files unless force=true
+            // Ignore rules only apply to untracked files
+            if !is_tracked && excludes.at_entry(path_bstr, None)?.is_excluded() {
+                if !force {
+                    result.ignored.push(path_bstr.into());
                     continue;
                 }
+                result.force_added.push(path_bstr.into());
             }
 
-            // Process the file (file_path is already absolute per expand_paths contract)
-            process_single_file(
-                &repo_clone,
-                &mut index,
-                &file_path,
-                &relative_path,
-                symlinks_enabled,
-            )?;
+            let path = path_bstr.to_owned();
+            pending.push(PendingFile { file_path, path });
+        }
+
+        // Hash in parallel, then update the index in one batch
+        let mut added = HashSet::with_capacity(pending.len());
+        if intent_to_add {
+            // An empty blob with the intent-to-add flag, as git records it
+            let empty_blob = repo_clone.write_blob(b"").map_err(GitError::gix)?.detach();
+            for file in pending {
+                let fs_metadata = gix::index::fs::Metadata::from_path_no_follow(&file.file_path)?;
+                let mode = entry_mode(&file.file_path, &fs_metadata, symlinks_enabled)?;
+                index.dangerously_push_entry(
+                    Stat::default(),
+                    empty_blob,
+                    Flags::INTENT_TO_ADD | Flags::EXTENDED,
+                    mode,
+                    file.path.as_bstr(),
+                );
+                added.insert(file.path);
+            }
+        } else {
+            let hashed = hash_files(&repo_clone, &pending, symlinks_enabled, jobs)?;
+            for (file, blob) in pending.into_iter().zip(hashed) {
+                index.dangerously_push_entry(
+                    blob.stat,
+                    blob.id,
+                    Flags::empty(),
+                    blob.mode,
+                    file.path.as_bstr(),
+                );
+                added.insert(file.path);
+            }
         }
 
+        // Tracked files under the paths that are gone from the worktree;
+        // skip-worktree entries, sparse directories included, are absent on
+        // purpose
+        let deleted: HashSet<BString> = if stage_deletions {
+            index.entries()[..tracked]
+                .iter()
+                .filter(|entry| !entry.flags.contains(Flags::SKIP_WORKTREE))
+                .map(|entry| entry.path(&index))
+                .filter(|path| specs.iter().any(|spec| pathspec_matches(spec, path)))
+                .filter(|path| {
+                    repo_path
+                        .join(gix::path::from_bstr(*path))
+                        .symlink_metadata()
+                        .is_err()
+                })
+                .map(ToOwned::to_owned)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+
+        index.remove_entries(|idx, path, _| {
+            idx < tracked && (added.contains(path) || deleted.contains(path))
+        });
+
+        result.added = added.into_iter().map(RepoPath::from).collect();
+        result.added.sort();
+        result.deleted = deleted.into_iter().map(RepoPath::from).collect();
+        result.deleted.sort();
+        result.ignored.sort();
+        result.force_added.sort();
+
         // CRITICAL: Sort entries to maintain invariants
         index.sort_entries();
 
         // Write index to disk with proper locking and checksum
         use gix::index::write::Options;
-        index
-            .write(Options::default())
-            .map_err(|e| GitError::Gix(e.into()))?;
+        index.write(Options::default()).map_err(GitError::gix)?;
 
-        Ok(())
+        Ok(result)
     })
     .await
     .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
@@ -0,0 +1,235 @@
+//! Ancestry queries: how commits connect through the history graph.
+//!
+//! [`ancestry_path`] is `git log --ancestry-path from..to`: only the commits
+//! that descend from `from` and lead to `to`, which is how a fix can be
+//! traced into a release branch without the unrelated work merged alongside
+//! it. [`children_of`] inverts the parent links of everything reachable from
+//! the repository's refs, and [`branches_containing`] and [`tags_containing`]
+//! answer whether a commit has shipped.
+
+use std::collections::{HashMap, HashSet};
+
+use super::compare::{commit_info, resolve_commit};
+use crate::{CommitId, CommitInfo, GitError, GitResult, RepoHandle};
+
+/// Commits on the ancestry path from `from` to `to`, newest first.
+///
+/// These are the commits reachable from `to` that have `from` as an
+/// ancestor; `to` is included, `from` is not. The result is empty when
+/// `from` is not an ancestor of `to`.
+pub async fn ancestry_path(repo: RepoHandle, from: &str, to: &str) -> GitResult<Vec<CommitInfo>> {
+    let repo_inner = repo.clone_for_traversal();
+    let from = from.to_string();
+    let to = to.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let from_id = resolve_commit(&repo_inner, &from)?;
+        let to_id = resolve_commit(&repo_inner, &to)?;
+
+        // Everything in from..to, with the parent links inside that range
+        let walk = repo_inner
+            .rev_walk([to_id])
+            .with_hidden([from_id])
+            .all()
+            .map_err(GitError::gix)?;
+        let mut range = Vec::new();
+        let mut children: HashMap<CommitId, Vec<CommitId>> = HashMap::new();
+        for info in walk {
+            let info = info.map_err(GitError::gix)?;
+            for parent in &info.parent_ids {
+                children.entry(*parent).or_default().push(info.id);
+            }
+            range.push(info.id);
+        }
+
+        // Descendants of `from` within the range
+        let mut on_path = HashSet::new();
+        let mut pending = vec![from_id];
+        while let Some(id) = pending.pop() {
+            for child in children.get(&id).into_iter().flatten() {
+                if on_path.insert(*child) {
+                    pending.push(*child);
+                }
+            }
+        }
+
+        let boundary = super::shallow::shallow_boundary(&repo_inner)?;
+        range
+            .into_iter()
+            .filter(|id| on_path.contains(id))
+            .map(|id| commit_info(&repo_inner, id, &boundary))
+            .collect()
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Commits that have `commit` (a revision or object id) as a parent.
+///
+/// Only commits reachable from a ref or `HEAD` are found, newest first.
+pub async fn children_of(repo: RepoHandle, commit: &str) -> GitResult<Vec<CommitId>> {
+    let repo_inner = repo.clone_for_traversal();
+    let commit = commit.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let target = resolve_commit(&repo_inner, &commit)?;
+
+        let walk = repo_inner
+            .rev_walk(ref_tips(&repo_inner)?)
+            .all()
+            .map_err(GitError::gix)?;
+        let mut children = Vec::new();
+        for info in walk {
+            let info = info.map_err(GitError::gix)?;
+            if info.parent_ids.contains(&target) {
+                children.push(info.id);
+            }
+        }
+        Ok(children)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Local and remote-tracking branches whose history contains `commit`.
+///
+/// Local branches come first as `main`, remote-tracking ones as
+/// `origin/main`; each group is sorted by name.
+pub async fn branches_containing(repo: RepoHandle, commit: &str) -> GitResult<Vec<String>> {
+    let repo_inner = repo.clone_for_traversal();
+    let commit = commit.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let mut names = refs_containing(&repo_inner, &commit, "refs/heads/")?;
+        names.extend(refs_containing(&repo_inner, &commit, "refs/remotes/")?);
+        Ok(names)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Tags whose target commit contains `commit`, sorted by name.
+pub async fn tags_containing(repo: RepoHandle, commit: &str) -> GitResult<Vec<String>> {
+    let repo_inner = repo.clone_for_traversal();
+    let commit = commit.to_string();
+
+    tokio::task::spawn_blocking(move || refs_containing(&repo_inner, &commit, "refs/tags/"))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Short names of the refs under `prefix` that reach `commit`
+fn refs_containing(repo: &gix::Repository, commit: &str, prefix: &str) -> GitResult<Vec<String>> {
+    let target = resolve_commit(repo, commit)?;
+
+    let platform = repo.references().map_err(GitError::gix)?;
+    let references = platform.prefixed(prefix).map_err(GitError::gix)?;
+    let mut tips = Vec::new();
+    for reference in references {
+        let mut reference = reference.map_err(GitError::gix)?;
+        // Symbolic refs such as origin/HEAD would repeat their target
+        if reference.target().try_id().is_none() {
+            continue;
+        }
+        if let Ok(tip) = reference.peel_to_commit() {
+            let name = reference.name();
+            tips.push((
+                name.as_bstr().to_string(),
+                name.shorten().to_string(),
+                tip.id,
+            ));
+        }
+    }
+
+    let mut names: Vec<String> = match super::midx::bitmap_reaching(repo, target, prefix) {
+        Some(reaching) => tips
+            .into_iter()
+            .filter(|(full, _, _)| reaching.contains(full))
+            .map(|(_, name, _)| name)
+            .collect(),
+        None => {
+            let tip_ids: Vec<CommitId> = tips.iter().map(|(_, _, id)| *id).collect();
+            let reaching = reaching_commits(repo, target, tip_ids)?;
+            tips.into_iter()
+                .filter(|(_, _, id)| reaching.contains(id))
+                .map(|(_, name, _)| name)
+                .collect()
+        }
+    };
+    names.sort();
+    Ok(names)
+}
+
+/// The commits among `tips` that have `target` in their history
+///
+/// Walks down from all tips at once. With a commit-graph, commits whose
+/// generation number is not above the target's cannot reach it and are not
+/// expanded, so the walk stops near the target instead of at the roots.
+fn reaching_commits(
+    repo: &gix::Repository,
+    target: CommitId,
+    tips: Vec<CommitId>,
+) -> GitResult<HashSet<CommitId>> {
+    let graph = repo.commit_graph_if_enabled().ok().flatten();
+    // The commit-graph holds all ancestors of its commits, so a target
+    // missing from it is unreachable from every commit in it
+    let target_generation = graph
+        .as_ref()
+        .map(|graph| graph.commit_by_id(target).map(|c| c.generation()));
+    let cannot_reach = |id: CommitId| match (&graph, target_generation) {
+        (Some(graph), Some(target_generation)) => graph
+            .commit_by_id(id)
+            .is_some_and(|c| target_generation.is_none_or(|t| c.generation() <= t)),
+        _ => false,
+    };
+
+    let mut children: HashMap<CommitId, Vec<CommitId>> = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut pending = tips.clone();
+    while let Some(id) = pending.pop() {
+        if id == target || !visited.insert(id) || cannot_reach(id) {
+            continue;
+        }
+        // Parents beyond a shallow boundary are not available
+        let Ok(commit) = repo.find_commit(id) else {
+            continue;
+        };
+        for parent in commit.parent_ids() {
+            children.entry(parent.detach()).or_default().push(id);
+            pending.push(parent.detach());
+        }
+    }
+
+    // Everything that reaches the target descends from it
+    let mut reaching = HashSet::from([target]);
+    let mut pending = vec![target];
+    while let Some(id) = pending.pop() {
+        for child in children.get(&id).into_iter().flatten() {
+            if reaching.insert(*child) {
+                pending.push(*child);
+            }
+        }
+    }
+    Ok(reaching)
+}
+
+/// Commits pointed to by `HEAD` and all refs, with tags peeled
+fn ref_tips(repo: &gix::Repository) -> GitResult<Vec<CommitId>> {
+    let mut tips = Vec::new();
+    if let Ok(head) = repo.head_id() {
+        tips.push(head.detach());
+    }
+
+    let platform = repo.references().map_err(GitError::gix)?;
+    for reference in platform.all().map_err(GitError::gix)? {
+        let mut reference = reference.map_err(GitError::gix)?;
+        // Refs to trees or blobs have no history
+        if let Ok(commit) = reference.peel_to_commit() {
+            tips.push(commit.id);
+        }
+    }
+
+    tips.sort();
+    tips.dedup();
+    Ok(tips)
+}
@@ -0,0 +1,530 @@
+//! Applying patches to the index.
+//!
+//! [`apply_to_index`] stages the changes of a unified diff without reading or
+//! writing the working tree, like `git apply --cached`. Generated changes can
+//! be committed while the working tree keeps the user's edits, which then
+//! show up as unstaged differences from the new index.
+
+use std::collections::BTreeMap;
+
+use gix::bstr::{BStr, BString};
+
+use super::commit::{CommitOpts, CommitResult, commit};
+use super::diff::{DiffHunk, DiffLine, LineOrigin};
+use super::index::open_index;
+use crate::{GitError, GitResult, RepoHandle};
+
+/// The changes of a unified diff to one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    /// Path before the change; `None` for a new file
+    pub old_path: Option<String>,
+    /// Path after the change; `None` for a deleted file
+    pub new_path: Option<String>,
+    /// Mode after the change if the patch sets one, e.g. `0o100755`
+    pub new_mode: Option<u32>,
+    /// Whether `new_path` is a copy of `old_path`, which stays
+    pub copy: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl FilePatch {
+    /// The path after the change, or before it for a deleted file
+    #[must_use]
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// Options for [`apply_to_index`]
+#[derive(Debug, Clone)]
+pub struct ApplyOpts {
+    /// Unified diff, as written by `git diff` or `diff -u`
+    pub patch: String,
+    /// Only check that the patch applies
+    pub check: bool,
+    /// Commit the index once the patch is applied
+    pub commit: Option<CommitOpts>,
+}
+
+impl ApplyOpts {
+    /// Create options to apply `patch`.
+    #[inline]
+    pub fn new(patch: impl Into<String>) -> Self {
+        Self {
+            patch: patch.into(),
+            check: false,
+            commit: None,
+        }
+    }
+
+    /// Only check that the patch applies, changing nothing.
+    #[inline]
+    #[must_use]
+    pub fn check(mut self, yes: bool) -> Self {
+        self.check = yes;
+        self
+    }
+
+    /// Commit the result. The index is committed as is; `all` is ignored.
+    #[inline]
+    #[must_use]
+    pub fn commit(mut self, opts: CommitOpts) -> Self {
+        self.commit = Some(opts);
+        self
+    }
+}
+
+/// Result of [`apply_to_index`]
+#[derive(Debug, Clone)]
+pub struct ApplyResult {
+    /// Paths the patch changed, in patch order
+    pub files: Vec<String>,
+    /// The commit made, if requested
+    pub commit: Option<CommitResult>,
+}
+
+/// Apply a unified diff to the index, leaving the working tree alone, like
+/// `git apply --cached`.
+///
+/// Hunks must match the staged content exactly but may have moved; each is
+/// applied where its context is found closest to its line number. Nothing is
+/// staged unless every file applies. Binary patches are not supported.
+pub async fn apply_to_index(repo: RepoHandle, opts: ApplyOpts) -> GitResult<ApplyResult> {
+    let ApplyOpts {
+        patch,
+        check,
+        commit: commit_opts,
+    } = opts;
+    let patches = parse_patch(&patch)?;
+    if patches.is_empty() {
+        return Err(GitError::InvalidInput(
+            "Patch contains no changes".to_string(),
+        ));
+    }
+    let repo_inner = repo.clone_inner();
+    let index_file = repo.index_file().map(std::path::Path::to_path_buf);
+    let sparse_index = repo.sparse_index();
+
+    let files = tokio::task::spawn_blocking(move || {
+        let mut index = if index_file.is_some() || repo_inner.index_path().exists() {
+            open_index(&repo_inner, index_file.as_deref(), sparse_index)?
+        } else {
+            gix::index::File::from_state(
+                gix::index::State::new(repo_inner.object_hash()),
+                repo_inner.index_path(),
+            )
+        };
+
+        // New content by path, `None` for removed files
+        let mut staged: BTreeMap<BString, Option<(Vec<u8>, gix::index::entry::Mode)>> =
+            BTreeMap::new();
+        let mut files = Vec::with_capacity(patches.len());
+        for file in &patches {
+            let old = match &file.old_path {
+                Some(path) => {
+                    let path = BString::from(path.as_str());
+                    let current = match staged.get(&path) {
+                        Some(current) => current.clone(),
+                        None => staged_content(&repo_inner, &index, path.as_ref())?,
+                    };
+                    Some(current.ok_or_else(|| {
+                        GitError::InvalidInput(format!("{path} does not exist in the index"))
+                    })?)
+                }
+                None => None,
+            };
+            if let Some(path) = &file.new_path
+                && file.old_path.as_ref() != Some(path)
+            {
+                let path = BString::from(path.as_str());
+                let exists = match staged.get(&path) {
+                    Some(current) => current.is_some(),
+                    None => staged_content(&repo_inner, &index, path.as_ref())?.is_some(),
+                };
+                if exists {
+                    return Err(GitError::InvalidInput(format!(
+                        "{path} already exists in the index"
+                    )));
+                }
+            }
+
+            let (content, mode) = old.unwrap_or((Vec::new(), gix::index::entry::Mode::FILE));
+            let content = apply_hunks(&content, &file.hunks).map_err(|hunk| {
+                GitError::InvalidInput(format!(
+                    "Patch does not apply to {}: hunk {} ({}) doesn't match",
+                    file.path(),
+                    hunk + 1,
+                    file.hunks[hunk].header()
+                ))
+            })?;
+            let mode = match file.new_mode {
+                Some(bits) => index_mode(bits)?,
+                None => mode,
+            };
+
+            if let Some(path) = file.old_path.as_ref().filter(|_| !file.copy) {
+                staged.insert(BString::from(path.as_str()), None);
+            }
+            if let Some(path) = &file.new_path {
+                staged.insert(BString::from(path.as_str()), Some((content, mode)));
+            }
+            files.push(file.path().to_string());
+        }
+        if check {
+            return Ok(files);
+        }
+
+        index.remove_entries(|_, path, _| staged.contains_key(path));
+        for (path, change) in staged {
+            let Some((content, mode)) = change else {
+                continue;
+            };
+            let id = repo_inner
+                .write_blob(&content)
+                .map_err(GitError::gix)?
+                .detach();
+            // A zeroed stat makes status compare the working tree by content
+            index.dangerously_push_entry(
+                gix::index::entry::Stat::default(),
+                id,
+                gix::index::entry::Flags::empty(),
+                mode,
+                path.as_ref(),
+            );
+        }
+        index.sort_entries();
+        index
+            .write(gix::index::write::Options::default())
+            .map_err(GitError::gix)?;
+        Ok(files)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    let commit = match commit_opts {
+        // Committing with `all` would stage the working tree too
+        Some(opts) if !check => Some(commit(repo.clone(), opts.all(false)).await?),
+        _ => None,
+    };
+    Ok(ApplyResult { files, commit })
+}
+
+/// Content and mode of the file staged at `path`
+fn staged_content(
+    repo: &gix::Repository,
+    index: &gix::index::State,
+    path: &BStr,
+) -> GitResult<Option<(Vec<u8>, gix::index::entry::Mode)>> {
+    let Some(entry) = index.entry_by_path(path) else {
+        return Ok(None);
+    };
+    if entry.stage_raw() != 0 {
+        return Err(GitError::InvalidInput(format!(
+            "{path} has unresolved conflicts"
+        )));
+    }
+    if entry.mode.is_submodule() {
+        return Err(GitError::InvalidInput(format!(
+            "{path} is a submodule and can't be patched"
+        )));
+    }
+    let data = repo
+        .find_blob(entry.id)
+        .map_err(GitError::gix)?
+        .detach()
+        .data;
+    Ok(Some((data, entry.mode)))
+}
+
+/// The index mode for the file mode `bits` of a patch
+fn index_mode(bits: u32) -> GitResult<gix::index::entry::Mode> {
+    use gix::index::entry::Mode;
+
+    match bits {
+        0o100644 => Ok(Mode::FILE),
+        0o100755 => Ok(Mode::FILE_EXECUTABLE),
+        0o120000 => Ok(Mode::SYMLINK),
+        _ => Err(GitError::InvalidInput(format!(
+            "Unsupported file mode in patch: {bits:06o}"
+        ))),
+    }
+}
+
+/// Apply `hunks` to `content`; the index of the first hunk that doesn't
+/// match on error
+pub(crate) fn apply_hunks(content: &[u8], hunks: &[DiffHunk]) -> Result<Vec<u8>, usize> {
+    let lines: Vec<&[u8]> = content.split_inclusive(|&b| b == b'\n').collect();
+    let mut result = Vec::with_capacity(content.len());
+    let mut cursor = 0;
+    for (number, hunk) in hunks.iter().enumerate() {
+        let old: Vec<&[u8]> = hunk
+            .lines
+            .iter()
+            .filter(|line| line.origin != LineOrigin::Addition)
+            .map(|line| line.content.as_bytes())
+            .collect();
+        let expected = if hunk.old_lines == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let position = (cursor..=lines.len().saturating_sub(old.len()))
+            .filter(|&at| lines[at..].starts_with(&old))
+            .min_by_key(|&at| at.abs_diff(expected))
+            .ok_or(number)?;
+
+        for line in &lines[cursor..position] {
+            result.extend_from_slice(line);
+        }
+        for line in &hunk.lines {
+            if line.origin != LineOrigin::Deletion {
+                result.extend_from_slice(line.content.as_bytes());
+            }
+        }
+        cursor = position + old.len();
+    }
+    for line in &lines[cursor..] {
+        result.extend_from_slice(line);
+    }
+    Ok(result)
+}
+
+/// Parse a unified diff into its files.
+///
+/// Understands `git diff` output, including new, deleted, renamed, copied
+/// and mode-changed files, and plain `diff -u` output. Text before and between
+/// files is ignored.
+pub fn parse_patch(patch: &str) -> GitResult<Vec<FilePatch>> {
+    let mut files: Vec<FilePatch> = Vec::new();
+    // Whether the last file came from a `diff --git` header and still
+    // expects its `---`/`+++` lines
+    let mut git_header = false;
+    let mut lines = patch.split_inclusive('\n').peekable();
+
+    while let Some(line) = lines.next() {
+        let text = line.trim_end_matches(['\n', '\r']);
+
+        if let Some(paths) = text.strip_prefix("diff --git ") {
+            let (old, new) = git_header_paths(paths);
+            files.push(FilePatch {
+                old_path: Some(old),
+                new_path: Some(new),
+                new_mode: None,
+                copy: false,
+                hunks: Vec::new(),
+            });
+            git_header = true;
+        } else if let Some(old) = text.strip_prefix("--- ") {
+            let new = lines
+                .next()
+                .map(|line| line.trim_end_matches(['\n', '\r']))
+                .and_then(|line| line.strip_prefix("+++ "))
+                .ok_or_else(|| {
+                    GitError::InvalidInput(format!("Expected '+++' after '{text}' in patch"))
+                })?;
+            let (old, new) = (marker_path(old), marker_path(new));
+            match files.last_mut() {
+                Some(file) if git_header => {
+                    file.old_path = old;
+                    file.new_path = new;
+                }
+                _ => files.push(FilePatch {
+                    old_path: old,
+                    new_path: new,
+                    new_mode: None,
+                    copy: false,
+                    hunks: Vec::new(),
+                }),
+            }
+            git_header = false;
+        } else if text.starts_with("@@ ") {
+            let file = files.last_mut().ok_or_else(|| {
+                GitError::InvalidInput("Hunk without a file header in patch".to_string())
+            })?;
+            file.hunks.push(parse_hunk(text, &mut lines)?);
+            git_header = false;
+        } else if text.starts_with("Binary files ") || text == "GIT binary patch" {
+            return Err(GitError::InvalidInput(
+                "Binary patches are not supported".to_string(),
+            ));
+        } else if let Some(file) = files.last_mut().filter(|_| git_header) {
+            if let Some(mode) = text.strip_prefix("new file mode ") {
+                file.old_path = None;
+                file.new_mode = Some(parse_mode(mode)?);
+            } else if text.starts_with("deleted file mode ") {
+                file.new_path = None;
+            } else if let Some(mode) = text.strip_prefix("new mode ") {
+                file.new_mode = Some(parse_mode(mode)?);
+            } else if let Some(path) = text.strip_prefix("rename from ") {
+                file.old_path = Some(unquote(path));
+            } else if let Some(path) = text.strip_prefix("rename to ") {
+                file.new_path = Some(unquote(path));
+            } else if let Some(path) = text.strip_prefix("copy from ") {
+                file.old_path = Some(unquote(path));
+                file.copy = true;
+            } else if let Some(path) = text.strip_prefix("copy to ") {
+                file.new_path = Some(unquote(path));
+                file.copy = true;
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Parse the hunk starting with the header `header`, consuming its lines
+fn parse_hunk<'a>(
+    header: &str,
+    lines: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>,
+) -> GitResult<DiffHunk> {
+    let invalid = || GitError::InvalidInput(format!("Invalid hunk header in patch: {header}"));
+    let (old, rest) = header
+        .strip_prefix("@@ -")
+        .and_then(|rest| rest.split_once(" +"))
+        .ok_or_else(invalid)?;
+    let (new, _) = rest.split_once(" @@").ok_or_else(invalid)?;
+    let (old_start, old_lines) = parse_range(old).ok_or_else(invalid)?;
+    let (new_start, new_lines) = parse_range(new).ok_or_else(invalid)?;
+
+    let mut hunk = DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines: Vec::new(),
+    };
+    let (mut old_left, mut new_left) = (old_lines, new_lines);
+    while old_left > 0 || new_left > 0 {
+        let line = lines
+            .next()
+            .ok_or_else(|| GitError::InvalidInput(format!("Patch ends inside hunk {header}")))?;
+        let (origin, content) = match line.split_at_checked(1) {
+            Some((" ", content)) => (LineOrigin::Context, content),
+            Some(("-", content)) => (LineOrigin::Deletion, content),
+            Some(("+", content)) => (LineOrigin::Addition, content),
+            Some(("\\", _)) => {
+                strip_newline(&mut hunk);
+                continue;
+            }
+            // Some editors strip the space of empty context lines
+            _ if line.trim_end_matches(['\n', '\r']).is_empty() => (LineOrigin::Context, line),
+            _ => {
+                return Err(GitError::InvalidInput(format!(
+                    "Unexpected line in hunk {header}: {}",
+                    line.trim_end()
+                )));
+            }
+        };
+        match origin {
+            LineOrigin::Context => {
+                old_left = old_left.saturating_sub(1);
+                new_left = new_left.saturating_sub(1);
+            }
+            LineOrigin::Deletion => old_left = old_left.saturating_sub(1),
+            LineOrigin::Addition => new_left = new_left.saturating_sub(1),
+        }
+        hunk.lines.push(DiffLine {
+            origin,
+            content: content.to_string(),
+        });
+    }
+    if lines.peek().is_some_and(|line| line.starts_with('\\')) {
+        lines.next();
+        strip_newline(&mut hunk);
+    }
+    Ok(hunk)
+}
+
+/// Handle `\ No newline at end of file` after the last line of `hunk`
+fn strip_newline(hunk: &mut DiffHunk) {
+    if let Some(line) = hunk.lines.last_mut()
+        && let Some(content) = line.content.strip_suffix('\n')
+    {
+        line.content = content.strip_suffix('\r').unwrap_or(content).to_string();
+    }
+}
+
+/// `start,len` or `start` (length 1) of a hunk header
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+fn parse_mode(mode: &str) -> GitResult<u32> {
+    u32::from_str_radix(mode.trim(), 8)
+        .map_err(|_| GitError::InvalidInput(format!("Invalid file mode in patch: {mode}")))
+}
+
+/// The path of a `---`/`+++` line without its `a/`/`b/` prefix; `None` for
+/// `/dev/null`
+fn marker_path(text: &str) -> Option<String> {
+    // `diff -u` appends a tab and a timestamp
+    let text = text.split('\t').next().unwrap_or(text);
+    let path = unquote(text);
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(strip_prefix(&path).to_string())
+}
+
+/// Old and new path of a `diff --git a/old b/new` header
+fn git_header_paths(paths: &str) -> (String, String) {
+    if let Some(quoted) = paths.strip_prefix('"') {
+        let end = quoted.find('"').map_or(paths.len(), |index| index + 2);
+        let (old, new) = paths.split_at(end.min(paths.len()));
+        return (
+            strip_prefix(&unquote(old)).to_string(),
+            strip_prefix(&unquote(new.trim_start())).to_string(),
+        );
+    }
+    match paths.rsplit_once(" b/") {
+        Some((old, new)) => (strip_prefix(old).to_string(), new.to_string()),
+        None => (paths.to_string(), paths.to_string()),
+    }
+}
+
+fn strip_prefix(path: &str) -> &str {
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+}
+
+/// Undo git's C-style quoting of paths with special characters
+fn unquote(path: &str) -> String {
+    let Some(inner) = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+    else {
+        return path.to_string();
+    };
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut input = inner.bytes().peekable();
+    while let Some(byte) = input.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match input.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(digit @ b'0'..=b'7') => {
+                let mut value = u32::from(digit - b'0');
+                for _ in 0..2 {
+                    if let Some(&next @ b'0'..=b'7') = input.peek() {
+                        value = value * 8 + u32::from(next - b'0');
+                        input.next();
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,348 @@
+//! Editing `.gitattributes` files.
+//!
+//! Line endings, diff drivers, linguist overrides and Git LFS tracking are all
+//! configured through `.gitattributes`. These helpers merge attributes into
+//! the line of a pattern instead of appending duplicates: setting `eol=lf` on
+//! a pattern that has `eol=crlf` replaces it, setting what is already there
+//! changes nothing, and comments, blank lines and the order of lines are kept.
+
+use std::path::{Path, PathBuf};
+
+use super::ignore::{dir_file, edit_file, newline, read};
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Attributes `git lfs track` writes for a pattern
+pub const LFS_ATTRIBUTES: [&str; 4] = ["filter=lfs", "diff=lfs", "merge=lfs", "-text"];
+
+/// A pattern line of a `.gitattributes` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeRule {
+    /// 1-based line number
+    pub line: usize,
+    /// The pattern as written; `[attr]name` for macro definitions
+    pub pattern: String,
+    /// Attributes in file order, e.g. `text`, `-diff`, `!eol`, `eol=lf`
+    pub attributes: Vec<String>,
+}
+
+/// Result of [`set_attributes`], [`remove_attributes`], [`lfs_track`] or [`lfs_untrack`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributesEdit {
+    /// The `.gitattributes` that was edited, relative to the working tree
+    pub file: PathBuf,
+    /// Patterns whose line was added, changed or removed
+    pub changed: Vec<String>,
+}
+
+/// Set `attributes` on each of `patterns` in the `.gitattributes` in `dir`
+/// (relative to the working tree; empty for the root), creating it if needed.
+///
+/// An attribute replaces one of the same name on the pattern's line, so
+/// `eol=lf` replaces `eol=crlf` and `-diff` replaces `diff`. Patterns without
+/// a line get one appended.
+pub async fn set_attributes<P, A, S, T>(
+    repo: RepoHandle,
+    dir: impl AsRef<Path>,
+    patterns: P,
+    attributes: A,
+) -> GitResult<AttributesEdit>
+where
+    P: IntoIterator<Item = S>,
+    S: Into<String>,
+    A: IntoIterator<Item = T>,
+    T: Into<String>,
+{
+    let patterns = validate_patterns(patterns)?;
+    let attributes = validate_attributes(attributes)?;
+    let (file, changed) = edit_file(repo.clone(), dir.as_ref(), ".gitattributes", move |text| {
+        let newline = newline(text);
+        let mut lines: Vec<String> = text.split_inclusive('\n').map(str::to_string).collect();
+        let mut changed: Vec<String> = Vec::new();
+        for pattern in patterns {
+            let rule = lines.iter().enumerate().rev().find_map(|(index, line)| {
+                split_rule(line)
+                    .filter(|(p, _)| *p == pattern)
+                    .map(|(_, existing)| (index, existing))
+            });
+            match rule {
+                Some((index, mut existing)) => {
+                    if merge(&mut existing, &attributes) {
+                        lines[index] = render(&lines[index], &pattern, &existing);
+                        changed.push(pattern);
+                    }
+                }
+                None => {
+                    if let Some(last) = lines.last_mut()
+                        && !last.ends_with('\n')
+                    {
+                        last.push_str(newline);
+                    }
+                    lines.push(format!("{pattern} {}{newline}", attributes.join(" ")));
+                    changed.push(pattern);
+                }
+            }
+        }
+        if changed.is_empty() {
+            (None, changed)
+        } else {
+            (Some(lines.concat()), changed)
+        }
+    })
+    .await?;
+    Ok(AttributesEdit { file, changed })
+}
+
+/// Remove the attributes called `names` from the lines of `patterns` in the
+/// `.gitattributes` in `dir`.
+///
+/// A name removes the attribute in any state: `diff` removes `diff`, `-diff`,
+/// `!diff` and `diff=lfs`. Lines left without attributes are deleted. A
+/// missing file is treated as empty.
+pub async fn remove_attributes<P, N, S, T>(
+    repo: RepoHandle,
+    dir: impl AsRef<Path>,
+    patterns: P,
+    names: N,
+) -> GitResult<AttributesEdit>
+where
+    P: IntoIterator<Item = S>,
+    S: Into<String>,
+    N: IntoIterator<Item = T>,
+    T: Into<String>,
+{
+    let patterns = validate_patterns(patterns)?;
+    let names: Vec<String> = validate_attributes(names)?
+        .iter()
+        .map(|attribute| attribute_name(attribute).to_string())
+        .collect();
+    remove_matching(repo.clone(), dir.as_ref(), patterns, move |attribute| {
+        names.iter().any(|name| name == attribute_name(attribute))
+    })
+    .await
+}
+
+/// Track `patterns` with Git LFS in the root `.gitattributes`, like
+/// `git lfs track`.
+///
+/// Patterns already tracked are left out of [`AttributesEdit::changed`].
+pub async fn lfs_track<P, S>(repo: RepoHandle, patterns: P) -> GitResult<AttributesEdit>
+where
+    P: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    set_attributes(repo, "", patterns, LFS_ATTRIBUTES).await
+}
+
+/// Stop tracking `patterns` with Git LFS, like `git lfs untrack`.
+///
+/// Only the [`LFS_ATTRIBUTES`] are removed; other attributes of the pattern
+/// stay.
+pub async fn lfs_untrack<P, S>(repo: RepoHandle, patterns: P) -> GitResult<AttributesEdit>
+where
+    P: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let patterns = validate_patterns(patterns)?;
+    remove_matching(repo.clone(), Path::new(""), patterns, |attribute| {
+        LFS_ATTRIBUTES.contains(&attribute)
+    })
+    .await
+}
+
+/// Pattern lines of the `.gitattributes` in `dir`, in file order; empty if
+/// there is none.
+pub async fn list_attributes(
+    repo: RepoHandle,
+    dir: impl AsRef<Path>,
+) -> GitResult<Vec<AttributeRule>> {
+    let path = dir_file(&repo, dir.as_ref(), ".gitattributes")?;
+    tokio::task::spawn_blocking(move || {
+        let text = read(&path)?;
+        Ok(text
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                split_rule(line).map(|(pattern, attributes)| AttributeRule {
+                    line: index + 1,
+                    pattern: pattern.to_string(),
+                    attributes,
+                })
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Drop the attributes `remove` matches from the lines of `patterns`
+async fn remove_matching<F>(
+    repo: RepoHandle,
+    dir: &Path,
+    patterns: Vec<String>,
+    remove: F,
+) -> GitResult<AttributesEdit>
+where
+    F: Fn(&str) -> bool + Send + 'static,
+{
+    let (file, changed) = edit_file(repo, dir, ".gitattributes", move |text| {
+        let mut changed: Vec<String> = Vec::new();
+        let mut edited = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            let Some((pattern, attributes)) = split_rule(line) else {
+                edited.push_str(line);
+                continue;
+            };
+            if !patterns.iter().any(|p| p == pattern) {
+                edited.push_str(line);
+                continue;
+            }
+            let kept: Vec<String> = attributes
+                .iter()
+                .filter(|attribute| !remove(attribute.as_str()))
+                .cloned()
+                .collect();
+            if kept.len() == attributes.len() {
+                edited.push_str(line);
+                continue;
+            }
+            if !changed.iter().any(|c| c == pattern) {
+                changed.push(pattern.to_string());
+            }
+            if !kept.is_empty() {
+                edited.push_str(&render(line, pattern, &kept));
+            }
+        }
+        if changed.is_empty() {
+            (None, changed)
+        } else {
+            (Some(edited), changed)
+        }
+    })
+    .await?;
+    Ok(AttributesEdit { file, changed })
+}
+
+/// Split a line into its pattern and attributes; `None` for blank lines and comments
+fn split_rule(line: &str) -> Option<(&str, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    // A quoted pattern may contain whitespace; it ends at the closing quote
+    let end = if line.starts_with('"') {
+        let mut escaped = false;
+        line.char_indices()
+            .skip(1)
+            .find(|&(_, c)| {
+                let closing = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                closing
+            })
+            .map_or(line.len(), |(index, _)| index + 1)
+    } else {
+        line.find(char::is_whitespace).unwrap_or(line.len())
+    };
+    let (pattern, rest) = line.split_at(end);
+    Some((
+        pattern,
+        rest.split_whitespace().map(str::to_string).collect(),
+    ))
+}
+
+/// `line` with its attributes replaced by `attributes`, keeping its line ending
+fn render(line: &str, pattern: &str, attributes: &[String]) -> String {
+    let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+    format!("{pattern} {}{ending}", attributes.join(" "))
+}
+
+/// Merge `attributes` into `existing`; whether anything changed
+fn merge(existing: &mut Vec<String>, attributes: &[String]) -> bool {
+    let mut changed = false;
+    for attribute in attributes {
+        let name = attribute_name(attribute);
+        match existing.iter().rposition(|a| attribute_name(a) == name) {
+            Some(index) if existing[index] == *attribute => {}
+            Some(index) => {
+                existing[index] = attribute.clone();
+                changed = true;
+            }
+            None => {
+                existing.push(attribute.clone());
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// The name of an attribute: `eol` for `eol=lf`, `diff` for `-diff` or `!diff`
+fn attribute_name(attribute: &str) -> &str {
+    let attribute = attribute.strip_prefix(['-', '!']).unwrap_or(attribute);
+    attribute
+        .split_once('=')
+        .map_or(attribute, |(name, _)| name)
+}
+
+/// Trim patterns and reject those that can't start an attribute line
+fn validate_patterns<I, S>(patterns: I) -> GitResult<Vec<String>>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut validated: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.into().trim().to_string();
+        if pattern.is_empty() || pattern.contains(char::is_whitespace) {
+            return Err(GitError::InvalidInput(format!(
+                "Invalid attribute pattern: {pattern:?}"
+            )));
+        }
+        if pattern.starts_with('#') || pattern.starts_with('!') {
+            return Err(GitError::InvalidInput(format!(
+                "'{pattern}' is not allowed as an attribute pattern"
+            )));
+        }
+        if !validated.contains(&pattern) {
+            validated.push(pattern);
+        }
+    }
+    if validated.is_empty() {
+        return Err(GitError::InvalidInput("No patterns given".to_string()));
+    }
+    Ok(validated)
+}
+
+/// Reject attributes that aren't `name`, `-name`, `!name` or `name=value`
+fn validate_attributes<I, S>(attributes: I) -> GitResult<Vec<String>>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let attributes: Vec<String> = attributes
+        .into_iter()
+        .map(|attribute| attribute.into().trim().to_string())
+        .collect();
+    if attributes.is_empty() {
+        return Err(GitError::InvalidInput("No attributes given".to_string()));
+    }
+    for attribute in &attributes {
+        let name = attribute_name(attribute);
+        let valid_name = !name.is_empty()
+            && !name.starts_with('-')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        let prefixed = attribute.starts_with(['-', '!']);
+        let value = attribute.split_once('=').map(|(_, value)| value);
+        let valid_value = match value {
+            Some(value) => !prefixed && !value.is_empty() && !value.contains(char::is_whitespace),
+            None => true,
+        };
+        if !valid_name || !valid_value {
+            return Err(GitError::InvalidInput(format!(
+                "Invalid attribute: {attribute:?}"
+            )));
+        }
+    }
+    Ok(attributes)
+}
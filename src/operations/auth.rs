@@ -3,15 +3,34 @@
 //! Single source of truth for:
 //! - Reading user's git configuration (SSH, credentials) via git binary
 //! - Configuring gix clone operations with proper auth
+//! - URL rewrite rules (`insteadOf` / `pushInsteadOf`), from config or registered at runtime
+//! - SSH host key verification policy for gix transports and the git CLI
+//! - Which git executable to run and the environment it gets
 //! - Running authenticated git CLI commands (push, ls-remote, delete)
 //! - Generating helpful error messages for auth failures
+//!
+//! With the `pure-gix` feature, configuration is read natively from the global
+//! git config files and git CLI commands return [`GitError::Unsupported`].
 
+#[cfg(not(feature = "pure-gix"))]
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::process::{Output, Stdio};
-use std::sync::OnceLock;
+use std::process::Output;
+#[cfg(not(feature = "pure-gix"))]
+use std::process::Stdio;
+use std::sync::{Arc, OnceLock, RwLock};
+#[cfg(not(feature = "pure-gix"))]
+use std::sync::{Mutex, PoisonError};
+#[cfg(not(feature = "pure-gix"))]
 use std::time::Duration;
 
+#[cfg(not(feature = "pure-gix"))]
+use gix::bstr::{BStr, BString, ByteSlice};
+use gix::remote::Direction;
+
+#[cfg(not(feature = "pure-gix"))]
 use tokio::io::AsyncReadExt;
+#[cfg(not(feature = "pure-gix"))]
 use tokio::process::Command as TokioCommand;
 
 use crate::{GitError, GitResult};
@@ -32,10 +51,13 @@ pub struct GitConfig {
     pub ssh_variant: Option<String>,
     /// Credential helper (credential.helper)
     pub credential_helper: Option<String>,
+    /// URL rewrite rules (url.<base>.insteadOf / url.<base>.pushInsteadOf)
+    pub url_rewrites: Vec<UrlRewrite>,
 }
 
 impl GitConfig {
     /// Read git configuration from the system using git binary
+    #[cfg(not(feature = "pure-gix"))]
     fn read() -> Self {
         let mut config = GitConfig::default();
 
@@ -47,10 +69,34 @@ impl GitConfig {
         config.ssh_command = git_config_get("core.sshCommand");
         config.ssh_variant = git_config_get("ssh.variant");
         config.credential_helper = git_config_get("credential.helper");
+        config.url_rewrites = git_config_url_rewrites();
 
         config
     }
 
+    /// Read git configuration natively from the installation, system and global config files
+    #[cfg(feature = "pure-gix")]
+    fn read() -> Self {
+        use gix::bstr::ByteSlice;
+
+        let Ok(file) = gix::config::File::from_globals() else {
+            return GitConfig::default();
+        };
+
+        let get = |key: &str| {
+            file.string(key)
+                .map(|v| v.to_str_lossy().trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        GitConfig {
+            ssh_command: get("core.sshCommand"),
+            ssh_variant: get("ssh.variant"),
+            credential_helper: get("credential.helper"),
+            url_rewrites: url_rewrites_in(&file),
+        }
+    }
+
     /// Convert to gix config override format: ["key=value", ...]
     ///
     /// These overrides are passed to `PrepareFetch::with_in_memory_config_overrides()`
@@ -67,6 +113,7 @@ impl GitConfig {
         if let Some(ref v) = self.credential_helper {
             overrides.push(format!("credential.helper={v}"));
         }
+        overrides.extend(self.url_rewrites.iter().map(UrlRewrite::to_gix_override));
 
         overrides
     }
@@ -78,8 +125,17 @@ pub fn get_config() -> &'static GitConfig {
 }
 
 /// Check if git binary is available
+///
+/// Always `false` with the `pure-gix` feature, which never runs git.
+#[cfg(feature = "pure-gix")]
+pub fn git_available() -> bool {
+    false
+}
+
+/// Check if git binary is available
+#[cfg(not(feature = "pure-gix"))]
 pub fn git_available() -> bool {
-    std::process::Command::new("git")
+    git_command()
         .arg("--version")
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -89,8 +145,9 @@ pub fn git_available() -> bool {
 }
 
 /// Read a single git config value using git binary
+#[cfg(not(feature = "pure-gix"))]
 fn git_config_get(key: &str) -> Option<String> {
-    std::process::Command::new("git")
+    git_command()
         .args(["config", "--get", key])
         .output()
         .ok()
@@ -100,6 +157,422 @@ fn git_config_get(key: &str) -> Option<String> {
         .filter(|s| !s.is_empty())
 }
 
+/// Read all `url.<base>.insteadOf` and `url.<base>.pushInsteadOf` entries using git binary
+#[cfg(not(feature = "pure-gix"))]
+fn git_config_url_rewrites() -> Vec<UrlRewrite> {
+    let Some(stdout) = git_command()
+        .args(["config", "--get-regexp", r"^url\..*\.(insteadof|pushinsteadof)$"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+    else {
+        return Vec::new();
+    };
+
+    stdout
+        .lines()
+        .filter_map(|line| {
+            // Format: "url.<base>.<insteadof|pushinsteadof> <prefix>"
+            let (key, value) = line.split_once(' ')?;
+            let (base, variable) = key.strip_prefix("url.")?.rsplit_once('.')?;
+            let rule = UrlRewrite::new(base, value.trim());
+            match variable {
+                "insteadof" => Some(rule),
+                "pushinsteadof" => Some(rule.push_only(true)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// URL Rewrite Rules (insteadOf)
+// ============================================================================
+
+/// Programmatically registered URL rewrite rules, combined with those from git config
+static URL_REWRITES: RwLock<Vec<UrlRewrite>> = RwLock::new(Vec::new());
+
+/// A URL rewrite rule, equivalent to `url.<base>.insteadOf` / `url.<base>.pushInsteadOf`
+///
+/// URLs starting with `instead_of` have that prefix replaced by `base`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlRewrite {
+    /// Replacement prefix (the `<base>` of `url.<base>.insteadOf`)
+    pub base: String,
+    /// Prefix matched against the original URL
+    pub instead_of: String,
+    /// Only apply when pushing (`pushInsteadOf`)
+    pub push_only: bool,
+}
+
+impl UrlRewrite {
+    /// Create a rule that rewrites URLs starting with `instead_of` to start with `base`
+    pub fn new<B: Into<String>, I: Into<String>>(base: B, instead_of: I) -> Self {
+        Self {
+            base: base.into(),
+            instead_of: instead_of.into(),
+            push_only: false,
+        }
+    }
+
+    /// Restrict the rule to push URLs (`pushInsteadOf`)
+    #[must_use]
+    pub fn push_only(mut self, yes: bool) -> Self {
+        self.push_only = yes;
+        self
+    }
+
+    /// Config key/value pair for this rule, e.g. `url.<base>.insteadOf=<prefix>`
+    fn to_gix_override(&self) -> String {
+        let variable = if self.push_only {
+            "pushInsteadOf"
+        } else {
+            "insteadOf"
+        };
+        format!("url.{}.{variable}={}", self.base, self.instead_of)
+    }
+}
+
+/// Register a rewrite rule applied to every clone, fetch, pull and push
+///
+/// Registered rules compete with `insteadOf` rules from git config: only one
+/// rule is applied to a URL, the one with the longest matching prefix, and a
+/// registered rule wins over a config rule with the same prefix. This lets a
+/// server transparently redirect e.g. `https://github.com/` to an internal
+/// mirror without touching user configuration.
+pub fn add_url_rewrite(rule: UrlRewrite) {
+    if let Ok(mut rules) = URL_REWRITES.write()
+        && !rules.contains(&rule)
+    {
+        rules.push(rule);
+    }
+}
+
+/// Remove all programmatically registered rewrite rules
+pub fn clear_url_rewrites() {
+    if let Ok(mut rules) = URL_REWRITES.write() {
+        rules.clear();
+    }
+}
+
+/// Programmatically registered rewrite rules
+pub fn registered_url_rewrites() -> Vec<UrlRewrite> {
+    URL_REWRITES
+        .read()
+        .map(|rules| rules.clone())
+        .unwrap_or_default()
+}
+
+/// Rewrite `url` using registered rules and rules from git config
+///
+/// Returns `None` if no rule matches.
+pub fn rewrite_url(url: &str, direction: Direction) -> Option<String> {
+    with_registered_rules(get_config().url_rewrites.clone(), url, direction)
+}
+
+/// Apply the single best of `config_rules` and the registered rules to `url`
+///
+/// Registered rules come last so they win on equal prefixes.
+fn with_registered_rules(
+    mut config_rules: Vec<UrlRewrite>,
+    url: &str,
+    direction: Direction,
+) -> Option<String> {
+    config_rules.extend(registered_url_rewrites());
+    apply_url_rewrites(&config_rules, url, direction)
+}
+
+/// `url.<base>.insteadOf` and `url.<base>.pushInsteadOf` rules in `file`
+fn url_rewrites_in(file: &gix::config::File<'_>) -> Vec<UrlRewrite> {
+    use gix::bstr::ByteSlice;
+
+    let mut url_rewrites = Vec::new();
+    for section in file.sections_by_name("url").into_iter().flatten() {
+        let Some(base) = section.header().subsection_name() else {
+            continue;
+        };
+        let base = base.to_str_lossy();
+        for prefix in section.values("insteadOf") {
+            url_rewrites.push(UrlRewrite::new(base.as_ref(), prefix.to_str_lossy()));
+        }
+        for prefix in section.values("pushInsteadOf") {
+            url_rewrites.push(UrlRewrite::new(base.as_ref(), prefix.to_str_lossy()).push_only(true));
+        }
+    }
+    url_rewrites
+}
+
+/// Point `remote`'s fetch and push URLs at their rewrites
+///
+/// `remote` must have been loaded without rewrites (e.g. with
+/// `try_find_remote_without_url_rewrite`). Exactly one rule is applied per
+/// URL: the longest prefix among `repo`'s config rules and the registered
+/// rules, so a registered rule never rewrites a URL config already rewrote.
+pub(crate) fn rewrite_remote_urls<'repo>(
+    repo: &gix::Repository,
+    mut remote: gix::Remote<'repo>,
+) -> GitResult<gix::Remote<'repo>> {
+    let config_rules = url_rewrites_in(repo.config_snapshot().plumbing());
+    let rewrite = |direction| {
+        let url = remote.url(direction)?.to_bstring().to_string();
+        with_registered_rules(config_rules.clone(), &url, direction)
+    };
+    let (fetch, push) = (rewrite(Direction::Fetch), rewrite(Direction::Push));
+
+    if let Some(url) = fetch {
+        remote = remote
+            .with_url_without_url_rewrite(url.as_str())
+            .map_err(|e| GitError::InvalidInput(format!("Invalid rewritten URL '{url}': {e}")))?;
+    }
+    if let Some(url) = push {
+        remote = remote
+            .with_push_url_without_url_rewrite(url.as_str())
+            .map_err(|e| GitError::InvalidInput(format!("Invalid rewritten URL '{url}': {e}")))?;
+    }
+    Ok(remote)
+}
+
+/// URL `remote` of `repo` connects to in `direction`, after rewrites
+///
+/// `remote` is a remote name or a URL. Like git, at most one rewrite rule
+/// applies; see [`rewrite_remote_urls`].
+pub(crate) fn remote_url(repo: &gix::Repository, remote: &str, direction: Direction) -> Option<String> {
+    use gix::bstr::ByteSlice;
+
+    match repo.try_find_remote_without_url_rewrite(remote.as_bytes().as_bstr()) {
+        Some(Ok(found)) => {
+            let found = rewrite_remote_urls(repo, found).ok()?;
+            Some(found.url(direction)?.to_bstring().to_string())
+        }
+        _ => {
+            let config_rules = url_rewrites_in(repo.config_snapshot().plumbing());
+            Some(with_registered_rules(config_rules, remote, direction).unwrap_or_else(|| remote.to_string()))
+        }
+    }
+}
+
+/// Apply `rules` to `url` with git's semantics
+///
+/// The longest matching prefix wins. When pushing, `pushInsteadOf` rules are
+/// preferred and plain `insteadOf` rules are used as fallback.
+pub fn apply_url_rewrites(rules: &[UrlRewrite], url: &str, direction: Direction) -> Option<String> {
+    let best_match = |push_only: bool| {
+        rules
+            .iter()
+            .filter(|rule| rule.push_only == push_only && url.starts_with(&rule.instead_of))
+            .max_by_key(|rule| rule.instead_of.len())
+    };
+
+    let rule = match direction {
+        Direction::Push => best_match(true).or_else(|| best_match(false)),
+        Direction::Fetch => best_match(false),
+    }?;
+
+    Some(format!("{}{}", rule.base, &url[rule.instead_of.len()..]))
+}
+
+// ============================================================================
+// SSH Host Key Policy
+// ============================================================================
+
+/// Server-wide SSH options, applied to every network operation
+static SSH_OPTIONS: RwLock<SshOptions> = RwLock::new(SshOptions {
+    host_key_policy: HostKeyPolicy::Default,
+    known_hosts: None,
+});
+
+/// How SSH should verify remote host keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HostKeyPolicy {
+    /// Leave host key checking to the user's ssh configuration
+    #[default]
+    Default,
+    /// Reject unknown and changed host keys (`StrictHostKeyChecking=yes`)
+    Strict,
+    /// Record unknown host keys, reject changed ones (`StrictHostKeyChecking=accept-new`)
+    AcceptNew,
+    /// Skip host key verification entirely (`StrictHostKeyChecking=no`)
+    Insecure,
+}
+
+impl HostKeyPolicy {
+    /// Value for ssh's `StrictHostKeyChecking` option, `None` for [`HostKeyPolicy::Default`]
+    fn strict_host_key_checking(self) -> Option<&'static str> {
+        match self {
+            HostKeyPolicy::Default => None,
+            HostKeyPolicy::Strict => Some("yes"),
+            HostKeyPolicy::AcceptNew => Some("accept-new"),
+            HostKeyPolicy::Insecure => Some("no"),
+        }
+    }
+}
+
+/// SSH transport options
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SshOptions {
+    /// Host key verification policy
+    pub host_key_policy: HostKeyPolicy,
+    /// Custom known_hosts file (`UserKnownHostsFile`)
+    pub known_hosts: Option<PathBuf>,
+}
+
+impl SshOptions {
+    /// Create options that leave ssh's configuration untouched
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the host key verification policy
+    #[must_use]
+    pub fn host_key_policy(mut self, policy: HostKeyPolicy) -> Self {
+        self.host_key_policy = policy;
+        self
+    }
+
+    /// Use a custom known_hosts file
+    #[must_use]
+    pub fn known_hosts<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.known_hosts = Some(path.into());
+        self
+    }
+
+    /// Build the ssh command implementing these options on top of `base`
+    ///
+    /// `base` is the user's `core.sshCommand` (defaults to `ssh`). Returns `None`
+    /// when these options don't change anything.
+    pub fn ssh_command(&self, base: Option<&str>) -> Option<String> {
+        let mut options = Vec::new();
+
+        if let Some(value) = self.host_key_policy.strict_host_key_checking() {
+            options.push(format!("-o StrictHostKeyChecking={value}"));
+        }
+        if let Some(ref path) = self.known_hosts {
+            // ssh splits option values on whitespace unless double-quoted, and
+            // git runs the command through `sh`
+            let path = path.display().to_string().replace('\\', "\\\\").replace('"', "\\\"");
+            let option = format!("UserKnownHostsFile=\"{path}\"");
+            options.push(format!("-o {}", shell_quote(&option)));
+        }
+
+        if options.is_empty() {
+            return None;
+        }
+
+        Some(format!("{} {}", base.unwrap_or("ssh"), options.join(" ")))
+    }
+}
+
+/// Quote `arg` as a single `sh` word
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Set the SSH options used by all subsequent clone, fetch, pull and push operations
+pub fn set_ssh_options(opts: SshOptions) {
+    if let Ok(mut current) = SSH_OPTIONS.write() {
+        *current = opts;
+    }
+}
+
+/// Currently configured server-wide SSH options
+pub fn ssh_options() -> SshOptions {
+    SSH_OPTIONS
+        .read()
+        .map(|opts| opts.clone())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Git Executable
+// ============================================================================
+
+/// Server-wide git executable and environment, applied to every git CLI command
+static GIT_CLI_OPTIONS: RwLock<GitCliOptions> = RwLock::new(GitCliOptions {
+    binary: None,
+    env: Vec::new(),
+});
+
+/// Which git executable to run and what environment to give it
+///
+/// Commands never inherit changes made to the server's own environment after
+/// startup; variables like `GIT_CONFIG_GLOBAL` or `GIT_SSH_COMMAND` belong
+/// here, or in [`GitCommandOpts::env`] for a single command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitCliOptions {
+    /// Path of the git executable (default: `git` from `PATH`)
+    pub binary: Option<PathBuf>,
+    /// Environment variables set for every git command
+    pub env: Vec<(String, String)>,
+}
+
+impl GitCliOptions {
+    /// Create options that run `git` from `PATH` with the inherited environment
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run this git executable
+    #[must_use]
+    pub fn binary<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.binary = Some(path.into());
+        self
+    }
+
+    /// Set an environment variable for every git command
+    #[must_use]
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Set the git executable and environment used by all subsequent git CLI commands
+///
+/// Call this before the first operation: the git configuration read for
+/// [`get_config`] is cached with whatever options were in effect then.
+pub fn set_git_cli_options(opts: GitCliOptions) {
+    if let Ok(mut current) = GIT_CLI_OPTIONS.write() {
+        *current = opts;
+    }
+}
+
+/// Currently configured server-wide git executable and environment
+pub fn git_cli_options() -> GitCliOptions {
+    GIT_CLI_OPTIONS
+        .read()
+        .map(|opts| opts.clone())
+        .unwrap_or_default()
+}
+
+/// A blocking git command using the configured executable and environment
+#[cfg(not(feature = "pure-gix"))]
+pub(crate) fn git_command() -> std::process::Command {
+    let opts = git_cli_options();
+    let mut cmd = std::process::Command::new(opts.binary.as_deref().unwrap_or("git".as_ref()));
+    cmd.envs(opts.env);
+    cmd
+}
+
+/// Effective ssh command for `opts`, layered on top of the user's `core.sshCommand`
+///
+/// For clones, which have no repository config yet; existing repositories
+/// layer on [`repo_ssh_command`] instead.
+fn effective_ssh_command(opts: &SshOptions) -> Option<String> {
+    opts.ssh_command(get_config().ssh_command.as_deref())
+}
+
+/// `core.sshCommand` as `repo` sees it, repository-local settings such as a
+/// deploy key (`ssh -i ...`) included
+fn repo_ssh_command(repo: &gix::Repository) -> Option<String> {
+    use gix::bstr::ByteSlice;
+
+    repo.config_snapshot()
+        .string("core.sshCommand")
+        .map(|v| v.to_str_lossy().trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
 // ============================================================================
 // gix Configuration (clone)
 // ============================================================================
@@ -111,8 +584,25 @@ fn git_config_get(key: &str) -> Option<String> {
 /// Why this is needed: `gix::prepare_clone()` creates a fresh repo that has
 /// NO access to the user's global git config. This function reads the user's
 /// config via the git binary and injects it as in-memory overrides.
+///
+/// Registered URL rewrite rules are injected as `insteadOf` overrides, so gix
+/// applies them when it creates the `origin` remote. Credentials are served
+/// from the [credential cache](super::credentials).
 pub fn configure_clone(prepare: gix::clone::PrepareFetch) -> gix::clone::PrepareFetch {
-    let overrides = get_config().to_gix_overrides();
+    let prepare = prepare.configure_connection(|connection| {
+        super::credentials::install(connection).map_err(Into::into)
+    });
+
+    // gix applies the longest matching prefix and keeps the first of equally
+    // long ones, so registered rules go first to win ties with config rules
+    let mut overrides: Vec<String> = registered_url_rewrites()
+        .iter()
+        .map(UrlRewrite::to_gix_override)
+        .collect();
+    overrides.extend(get_config().to_gix_overrides());
+    if let Some(cmd) = effective_ssh_command(&ssh_options()) {
+        overrides.push(format!("core.sshCommand={cmd}"));
+    }
     if overrides.is_empty() {
         prepare
     } else {
@@ -120,10 +610,77 @@ pub fn configure_clone(prepare: gix::clone::PrepareFetch) -> gix::clone::Prepare
     }
 }
 
+/// Configure an opened repository's in-memory config with the server-wide SSH options
+///
+/// Used before connecting remotes of existing repositories (fetch, pull).
+/// The options are appended to the repository's own `core.sshCommand`.
+/// Nothing is written to the repository's config file.
+pub fn configure_repo(repo: &mut gix::Repository) -> GitResult<()> {
+    let Some(cmd) = ssh_options().ssh_command(repo_ssh_command(repo).as_deref()) else {
+        return Ok(());
+    };
+
+    let mut config = repo.config_snapshot_mut();
+    config
+        .set_value(&gix::config::tree::Core::SSH_COMMAND, cmd.as_str())
+        .map_err(GitError::gix)?;
+    config.commit().map_err(GitError::gix)?;
+
+    Ok(())
+}
+
 // ============================================================================
 // Git CLI Wrapper (push, ls-remote, delete)
 // ============================================================================
 
+/// Bytes of each output stream kept by default
+const DEFAULT_MAX_OUTPUT: usize = 16 * 1024 * 1024;
+
+/// Longest line handed to a [`LineCallback`]; the rest of the line is dropped
+#[cfg(not(feature = "pure-gix"))]
+const MAX_LINE: usize = 4096;
+
+/// Lines of output quoted in a timeout error
+#[cfg(not(feature = "pure-gix"))]
+const TIMEOUT_TAIL_LINES: usize = 20;
+
+/// Which output stream of a git command a line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Callback receiving each line a git command prints, as it prints it
+///
+/// Progress output redrawn with `\r` arrives as one line per redraw. Called
+/// from a background task; keep it cheap (e.g. send to a channel).
+#[derive(Clone)]
+pub struct LineCallback(Arc<LineFn>);
+
+type LineFn = dyn Fn(OutputStream, &str) + Send + Sync;
+
+impl LineCallback {
+    /// Wrap a closure as a line callback
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(OutputStream, &str) + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    /// Deliver a line
+    pub fn line(&self, stream: OutputStream, line: &str) {
+        (self.0)(stream, line);
+    }
+}
+
+impl std::fmt::Debug for LineCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LineCallback")
+    }
+}
+
 /// Options for running an authenticated git command
 #[derive(Debug, Clone)]
 pub struct GitCommandOpts {
@@ -131,6 +688,19 @@ pub struct GitCommandOpts {
     pub work_dir: PathBuf,
     /// Timeout in seconds (default: 300)
     pub timeout_secs: u64,
+    /// SSH options for this command (defaults to the server-wide options)
+    pub ssh: Option<SshOptions>,
+    /// Git executable for this command (defaults to the server-wide one)
+    pub git_binary: Option<PathBuf>,
+    /// Extra environment variables for this command, set after the
+    /// server-wide ones
+    pub env: Vec<(String, String)>,
+    /// Called with each line of output as it arrives
+    pub on_line: Option<LineCallback>,
+    /// Bytes of stdout and of stderr kept in the result (default: 16 MiB);
+    /// the command fails if it prints more, after passing every line to
+    /// `on_line`
+    pub max_output_bytes: usize,
 }
 
 impl GitCommandOpts {
@@ -139,6 +709,11 @@ impl GitCommandOpts {
         Self {
             work_dir,
             timeout_secs: 300,
+            ssh: None,
+            git_binary: None,
+            env: Vec::new(),
+            on_line: None,
+            max_output_bytes: DEFAULT_MAX_OUTPUT,
         }
     }
 
@@ -147,6 +722,36 @@ impl GitCommandOpts {
         self.timeout_secs = secs;
         self
     }
+
+    /// Override the server-wide SSH options for this command
+    pub fn with_ssh_options(mut self, ssh: SshOptions) -> Self {
+        self.ssh = Some(ssh);
+        self
+    }
+
+    /// Run this git executable instead of the server-wide one
+    pub fn with_git_binary<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.git_binary = Some(path.into());
+        self
+    }
+
+    /// Set an environment variable for this command
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Receive each line of output while the command runs
+    pub fn with_line_callback(mut self, on_line: LineCallback) -> Self {
+        self.on_line = Some(on_line);
+        self
+    }
+
+    /// Keep at most `bytes` of stdout and of stderr, failing beyond that
+    pub fn with_max_output(mut self, bytes: usize) -> Self {
+        self.max_output_bytes = bytes;
+        self
+    }
 }
 
 /// Run an authenticated git command with proper environment setup
@@ -157,15 +762,72 @@ impl GitCommandOpts {
 /// Handles:
 /// - Setting GIT_TERMINAL_PROMPT=0 to prevent hanging on credential prompts
 /// - Setting LC_ALL=C for consistent output parsing
-/// - Timeout handling with proper child process cleanup
+/// - Passing registered URL rewrite rules in a system config file that
+///   includes the real one, so git reads them first and they win ties with
+///   rules from config files (see [`add_url_rewrite`])
+/// - Setting GIT_SSH_COMMAND when an SSH host key policy is configured, on top
+///   of the GIT_SSH_COMMAND from the environment, or else the
+///   `core.sshCommand` of the repository at `work_dir`
+/// - Running the configured git executable (per command, then server-wide,
+///   then `git` from `PATH`)
+/// - Adding the server-wide and per-command environment variables; the
+///   prompt and locale settings above always apply
+/// - Streaming stdout and stderr while the command runs, to
+///   [`GitCommandOpts::on_line`] and into buffers capped at
+///   [`GitCommandOpts::max_output_bytes`]; more output is an error rather
+///   than a silently shortened result
+/// - Timeout handling with proper child process cleanup; the error quotes the
+///   last lines printed before the timeout
 /// - Auth error detection and helpful messaging
+#[cfg(not(feature = "pure-gix"))]
 pub async fn run_git_command(args: &[&str], opts: GitCommandOpts) -> GitResult<Output> {
     let timeout_duration = Duration::from_secs(opts.timeout_secs);
 
-    let mut cmd = TokioCommand::new("git");
+    let cli = git_cli_options();
+    let binary = opts.git_binary.clone().or(cli.binary);
+    let mut cmd = TokioCommand::new(binary.as_deref().unwrap_or("git".as_ref()));
     cmd.current_dir(&opts.work_dir);
     cmd.args(args);
 
+    // Value of `name` git will see: per-command, then server-wide, then inherited
+    let env_var = |name: &str| {
+        let last = |env: &[(String, String)]| {
+            env.iter()
+                .rev()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+        };
+        last(&opts.env)
+            .or_else(|| last(&cli.env))
+            .or_else(|| std::env::var(name).ok())
+    };
+
+    // A GIT_SSH_COMMAND given to git replaces core.sshCommand; the policy below extends it
+    let env_ssh_command = env_var("GIT_SSH_COMMAND").filter(|value| !value.trim().is_empty());
+
+    // Config-based rewrites are honored by git itself; registered ones are
+    // read from a system config file listing them first
+    let rewrite_config = {
+        let executable = binary.clone().unwrap_or_else(|| "git".into());
+        let mut probe = TokioCommand::new(&executable);
+        probe
+            .current_dir(&opts.work_dir)
+            .envs(cli.env.iter().cloned())
+            .envs(opts.env.iter().cloned());
+        let no_system = env_var("GIT_CONFIG_NOSYSTEM").is_some_and(|value| {
+            !matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "" | "0" | "false" | "no" | "off"
+            )
+        });
+        let system_env = env_var("GIT_CONFIG_SYSTEM");
+        RewriteConfig::write(probe, executable, system_env, &opts.work_dir, no_system).await?
+    };
+
+    // Server-wide, then per-command environment, so the settings below can't be replaced
+    cmd.envs(cli.env);
+    cmd.envs(opts.env.iter().cloned());
+
     // Prevent credential prompts from hanging in automation
     cmd.env("GIT_TERMINAL_PROMPT", "0");
 
@@ -173,36 +835,91 @@ pub async fn run_git_command(args: &[&str], opts: GitCommandOpts) -> GitResult<O
     cmd.env("LC_ALL", "C");
     cmd.env("LANG", "C");
 
-    // Capture output
+    if let Some(config) = &rewrite_config {
+        cmd.env("GIT_CONFIG_SYSTEM", &*config.path);
+        cmd.env_remove("GIT_CONFIG_NOSYSTEM");
+    }
+
+    // Host key policy (per-command options win over server-wide ones), added
+    // to the command git would otherwise run
+    let ssh = opts.ssh.clone().unwrap_or_else(ssh_options);
+    if ssh != SshOptions::default() {
+        let base = env_ssh_command.or_else(|| {
+            gix::discover(&opts.work_dir)
+                .ok()
+                .and_then(|repo| repo_ssh_command(&repo))
+                .or_else(|| get_config().ssh_command.clone())
+        });
+        if let Some(ssh_command) = ssh.ssh_command(base.as_deref()) {
+            cmd.env("GIT_SSH_COMMAND", ssh_command);
+        }
+    }
+
+    // Capture output; nothing is ever read from stdin
+    cmd.stdin(Stdio::null());
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
     // Spawn child process
     let mut child = cmd.spawn().map_err(GitError::Io)?;
 
+    // Read both streams while the command runs, so a full pipe can't stall it
+    let stdout = Arc::new(Mutex::new(Captured::default()));
+    let stderr = Arc::new(Mutex::new(Captured::default()));
+    let mut readers = Vec::with_capacity(2);
+    if let Some(out) = child.stdout.take() {
+        readers.push(tokio::spawn(drain(
+            out,
+            OutputStream::Stdout,
+            Arc::clone(&stdout),
+            opts.max_output_bytes,
+            opts.on_line.clone(),
+        )));
+    }
+    if let Some(err) = child.stderr.take() {
+        readers.push(tokio::spawn(drain(
+            err,
+            OutputStream::Stderr,
+            Arc::clone(&stderr),
+            opts.max_output_bytes,
+            opts.on_line.clone(),
+        )));
+    }
+
     // Wait with timeout
     let status = tokio::select! {
         result = child.wait() => result.map_err(GitError::Io)?,
         () = tokio::time::sleep(timeout_duration) => {
             let _ = child.kill().await;
+            // Helpers like ssh may still hold the pipes open
+            for reader in &readers {
+                reader.abort();
+            }
             return Err(GitError::InvalidInput(format!(
-                "Git operation timed out after {} seconds", opts.timeout_secs
+                "Git operation timed out after {} seconds{}",
+                opts.timeout_secs,
+                output_tail(&Captured::take(&stderr).bytes, &Captured::take(&stdout).bytes)
             )));
         }
     };
 
-    // Read output
-    let mut stdout = Vec::new();
-    let mut stderr = Vec::new();
-
-    if let Some(mut out) = child.stdout.take() {
-        let _ = out.read_to_end(&mut stdout).await;
-    }
-    if let Some(mut err) = child.stderr.take() {
-        let _ = err.read_to_end(&mut stderr).await;
+    // Collect the rest of the output, unless a leftover helper keeps a pipe open
+    for mut reader in readers {
+        if tokio::time::timeout(Duration::from_secs(5), &mut reader)
+            .await
+            .is_err()
+        {
+            reader.abort();
+        }
     }
 
-    let output = Output { status, stdout, stderr };
+    let (stdout, stderr) = (Captured::take(&stdout), Captured::take(&stderr));
+    let truncated = stdout.truncated || stderr.truncated;
+    let output = Output {
+        status,
+        stdout: stdout.bytes,
+        stderr: stderr.bytes,
+    };
 
     // Check for auth errors and provide helpful message
     if !output.status.success() {
@@ -216,14 +933,239 @@ pub async fn run_git_command(args: &[&str], opts: GitCommandOpts) -> GitResult<O
             return Err(GitError::InvalidInput(auth_error_message(&url)));
         }
     }
+    if truncated {
+        return Err(GitError::InvalidInput(format!(
+            "Git printed more than the {} bytes of output kept",
+            opts.max_output_bytes
+        )));
+    }
 
     Ok(output)
 }
 
+/// System config file of each git executable and `GIT_CONFIG_SYSTEM` value,
+/// as printed by `git config --system --show-origin`
+#[cfg(not(feature = "pure-gix"))]
+type SystemConfigs = HashMap<(PathBuf, Option<String>), Option<BString>>;
+
+/// System config files found so far, so git is asked once per executable
+#[cfg(not(feature = "pure-gix"))]
+static SYSTEM_CONFIGS: OnceLock<Mutex<SystemConfigs>> = OnceLock::new();
+
+/// System config file giving a git command the registered URL rewrite rules,
+/// removed when dropped
+///
+/// Of equally long `insteadOf` prefixes git applies the one it read first,
+/// so rules passed with `-c`, read last, would lose ties with config rules.
+/// The system config is read first; this file lists the registered rules and
+/// then includes the real one. It is created with a random name, only if it
+/// doesn't exist yet, and readable only by the current user.
+#[cfg(not(feature = "pure-gix"))]
+struct RewriteConfig {
+    path: tempfile::TempPath,
+}
+
+#[cfg(not(feature = "pure-gix"))]
+impl RewriteConfig {
+    /// Write the file if rules are registered
+    ///
+    /// `probe` runs `binary` with the command's directory and environment, to
+    /// find the system config to include unless `no_system` is set;
+    /// `system_env` is the `GIT_CONFIG_SYSTEM` the command sees.
+    async fn write(
+        probe: TokioCommand,
+        binary: PathBuf,
+        system_env: Option<String>,
+        work_dir: &std::path::Path,
+        no_system: bool,
+    ) -> GitResult<Option<Self>> {
+        use gix::config::parse::section::ValueName;
+
+        let rules = registered_url_rewrites();
+        if rules.is_empty() {
+            return Ok(None);
+        }
+
+        let mut file = gix::config::File::default();
+        for rule in &rules {
+            let variable = if rule.push_only {
+                "pushInsteadOf"
+            } else {
+                "insteadOf"
+            };
+            let base: &BStr = rule.base.as_str().into();
+            file.new_section("url", Some(base.into()))
+                .map_err(GitError::gix)?
+                .push(
+                    ValueName::try_from(variable).map_err(GitError::gix)?,
+                    Some(rule.instead_of.as_str().into()),
+                );
+        }
+
+        if !no_system
+            && let Some(system) = Self::system_config(probe, binary, system_env).await?
+        {
+            let system = work_dir.join(gix::path::from_bstr(system.as_bstr()));
+            file.new_section("include", None)
+                .map_err(GitError::gix)?
+                .push(
+                    ValueName::try_from("path").map_err(GitError::gix)?,
+                    Some(gix::path::into_bstr(system).as_ref()),
+                );
+        }
+
+        let contents = file.to_bstring();
+        let path = tokio::task::spawn_blocking(move || -> std::io::Result<_> {
+            let mut file = tempfile::Builder::new()
+                .prefix("git-url-rewrites-")
+                .suffix(".config")
+                .tempfile()?;
+            std::io::Write::write_all(&mut file, &contents)?;
+            Ok(file.into_temp_path())
+        })
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+        Ok(Some(Self { path }))
+    }
+
+    /// Path of the system config file `binary` reads, as printed by `probe`,
+    /// remembered per executable and `GIT_CONFIG_SYSTEM` value
+    async fn system_config(
+        mut probe: TokioCommand,
+        binary: PathBuf,
+        system_env: Option<String>,
+    ) -> GitResult<Option<BString>> {
+        let configs = SYSTEM_CONFIGS.get_or_init(Default::default);
+        let key = (binary, system_env);
+        if let Some(system) = configs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+        {
+            return Ok(system.clone());
+        }
+
+        // `-z` prints the origin unquoted: `file:<path>\0<key>\n<value>\0`
+        let listed = probe
+            .args(["config", "--system", "--show-origin", "--list", "-z"])
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(GitError::Io)?;
+        let system = listed
+            .stdout
+            .split(|&byte| byte == 0)
+            .next()
+            .and_then(|origin| origin.strip_prefix(b"file:"))
+            .filter(|path| listed.status.success() && !path.is_empty())
+            .map(BString::from);
+        configs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, system.clone());
+        Ok(system)
+    }
+}
+
+/// Output of one stream of a git command, capped in size
+#[cfg(not(feature = "pure-gix"))]
+#[derive(Default)]
+struct Captured {
+    bytes: Vec<u8>,
+    /// Output was dropped because `bytes` was full
+    truncated: bool,
+}
+
+#[cfg(not(feature = "pure-gix"))]
+impl Captured {
+    /// Take what was captured so far
+    fn take(shared: &Mutex<Self>) -> Self {
+        std::mem::take(&mut *shared.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// Read `reader` to its end, keeping up to `max` bytes in `sink` and passing
+/// each line to `on_line`
+#[cfg(not(feature = "pure-gix"))]
+async fn drain<R>(
+    mut reader: R,
+    stream: OutputStream,
+    sink: Arc<Mutex<Captured>>,
+    max: usize,
+    on_line: Option<LineCallback>,
+) where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut chunk = [0u8; 8192];
+    let mut line = Vec::new();
+    loop {
+        let n = match reader.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        {
+            let mut captured = sink.lock().unwrap_or_else(PoisonError::into_inner);
+            let room = max.saturating_sub(captured.bytes.len());
+            captured.bytes.extend_from_slice(&chunk[..n.min(room)]);
+            captured.truncated |= n > room;
+        }
+        let Some(on_line) = &on_line else {
+            continue;
+        };
+        for &byte in &chunk[..n] {
+            if byte == b'\n' || byte == b'\r' {
+                if !line.is_empty() {
+                    on_line.line(stream, &String::from_utf8_lossy(&line));
+                    line.clear();
+                }
+            } else if line.len() < MAX_LINE {
+                line.push(byte);
+            }
+        }
+    }
+    if let Some(on_line) = &on_line
+        && !line.is_empty()
+    {
+        on_line.line(stream, &String::from_utf8_lossy(&line));
+    }
+}
+
+/// The last lines of output, for a timeout error
+#[cfg(not(feature = "pure-gix"))]
+fn output_tail(stderr: &[u8], stdout: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let stdout = String::from_utf8_lossy(stdout);
+    let lines: Vec<&str> = stderr
+        .split(['\n', '\r'])
+        .chain(stdout.split(['\n', '\r']))
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    let tail = &lines[lines.len().saturating_sub(TIMEOUT_TAIL_LINES)..];
+    format!("; output so far:\n{}", tail.join("\n"))
+}
+
+/// Run an authenticated git command
+///
+/// The `pure-gix` feature compiles out every git CLI invocation, so this
+/// always fails. Push and remote ref checks are unavailable in that build.
+#[cfg(feature = "pure-gix")]
+pub async fn run_git_command(_args: &[&str], _opts: GitCommandOpts) -> GitResult<Output> {
+    Err(GitError::Unsupported(
+        "git CLI operations (push, ls-remote) are unavailable with the pure-gix feature",
+    ))
+}
+
 /// Check if an error message indicates an authentication failure
-fn is_auth_error(stderr: &str) -> bool {
+pub(crate) fn is_auth_error(stderr: &str) -> bool {
     let s = stderr.to_lowercase();
     s.contains("authentication")
+        || s.contains("401 unauthorized")
+        || s.contains("status 401")
+        || s.contains("error: 401")
         || s.contains("permission denied")
         || s.contains("could not read username")
         || s.contains("could not read password")
@@ -257,7 +1199,8 @@ Setup SSH authentication:
 4. Test connection:
    ssh -T git@github.com
 
-CI/CD: export GIT_SSH_COMMAND="ssh -o StrictHostKeyChecking=no"
+CI/CD: configure a host key policy instead of disabling verification:
+   auth::set_ssh_options(SshOptions::new().host_key_policy(HostKeyPolicy::AcceptNew))
 "#
         )
     } else {
@@ -0,0 +1,124 @@
+//! Automatic stashing around operations that need a clean worktree
+//!
+//! Mirrors git's `rebase.autoStash`: local changes to tracked files are
+//! stashed before the operation and reapplied afterwards, whether or not the
+//! operation succeeded. Untracked files are left in place. If reapplying
+//! conflicts, the stash entry is kept and [`GitError::AutostashConflict`]
+//! reports the conflicting paths.
+
+use std::future::Future;
+
+use crate::{GitError, GitResult, RepoHandle, StashInfo, StashOpts};
+
+/// Message recorded on autostash entries
+const AUTOSTASH_MESSAGE: &str = "autostash";
+
+/// Run `op` with local changes stashed away when `enabled`
+///
+/// Errors from `op` take precedence over a failed reapplication; the stash
+/// entry is kept in that case too.
+pub(crate) async fn with_autostash<T, F, Fut>(
+    repo: RepoHandle,
+    enabled: bool,
+    op: F,
+) -> GitResult<T>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = GitResult<T>>,
+{
+    if !enabled {
+        return op().await;
+    }
+
+    let Some(stash) = save(repo.clone()).await? else {
+        return op().await;
+    };
+
+    let result = op().await;
+    let reapplied = reapply(repo, stash).await;
+    let value = result?;
+    reapplied?;
+    Ok(value)
+}
+
+/// Stash tracked changes, `None` when there are none
+async fn save(repo: RepoHandle) -> GitResult<Option<StashInfo>> {
+    let repo_clone = repo.clone_inner();
+    let dirty = tokio::task::spawn_blocking(move || repo_clone.is_dirty().map_err(GitError::gix))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    if !dirty {
+        return Ok(None);
+    }
+
+    let opts = StashOpts {
+        message: Some(AUTOSTASH_MESSAGE.to_string()),
+        include_untracked: false,
+    };
+    crate::stash_save(repo, opts).await.map(Some)
+}
+
+/// Pop the autostash entry, reporting conflicts
+#[cfg(feature = "pure-gix")]
+async fn reapply(_repo: RepoHandle, _stash: StashInfo) -> GitResult<()> {
+    Err(GitError::Unsupported(
+        "stash requires the git CLI and is unavailable with the pure-gix feature",
+    ))
+}
+
+/// Pop the autostash entry, reporting conflicts
+#[cfg(not(feature = "pure-gix"))]
+async fn reapply(repo: RepoHandle, stash: StashInfo) -> GitResult<()> {
+    let repo_clone = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || {
+        let work_dir = repo_clone
+            .workdir()
+            .ok_or_else(|| GitError::InvalidInput("Repository has no working directory".to_string()))?;
+        let git = |args: &[&str]| {
+            crate::operations::auth::git_command()
+                .args(args)
+                .current_dir(work_dir)
+                .output()
+                .map_err(|e| GitError::InvalidInput(format!("Failed to run git stash: {e}")))
+        };
+
+        // The operation may have pushed entries of its own; find ours by commit
+        let list = git(&["stash", "list", "--format=%H"])?;
+        let position = String::from_utf8_lossy(&list.stdout)
+            .lines()
+            .position(|hash| hash.trim() == stash.commit_hash)
+            .ok_or_else(|| {
+                GitError::InvalidInput(format!(
+                    "Autostash entry {} is no longer in the stash list",
+                    stash.commit_hash
+                ))
+            })?;
+        let name = format!("stash@{{{position}}}");
+
+        let output = git(&["stash", "pop", &name])?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        // NUL-separated, so unusual paths come back unquoted
+        let unmerged = git(&["diff", "--name-only", "-z", "--diff-filter=U"])?;
+        let paths: Vec<String> = unmerged
+            .stdout
+            .split(|b| *b == 0)
+            .filter(|path| !path.is_empty())
+            .map(|path| String::from_utf8_lossy(path).into_owned())
+            .collect();
+        if paths.is_empty() {
+            return Err(GitError::InvalidInput(format!(
+                "Failed to reapply autostash (changes kept in {name}): {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Err(GitError::AutostashConflict { stash: name, paths })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
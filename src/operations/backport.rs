@@ -0,0 +1,293 @@
+//! Backports across release branches.
+//!
+//! [`backport`] cherry-picks the same commits onto several release branches
+//! at once. Each target gets a new branch `backport/<target>/<id>`, named
+//! after the first commit, so the release branches themselves are never
+//! moved and the result can be reviewed and merged like any other branch. The
+//! picks are made on objects only: no worktree is checked out or touched.
+
+use gix::bstr::ByteSlice;
+use gix::merge::blob::builtin_driver::text::Labels;
+use gix::merge::tree::TreatAsUnresolved;
+use gix::refs::transaction::PreviousValue;
+
+use super::compare::resolve_commit;
+use super::conflicts::{ConflictedFile, conflicted_files};
+use super::push::PushOpts;
+use super::update_branch::{committer, tree_of};
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Options for [`backport`] with builder pattern.
+#[derive(Debug, Clone)]
+pub struct BackportOpts {
+    /// Push the created branches to this remote.
+    pub push: Option<String>,
+    /// Append `(cherry picked from commit <id>)` to each message, like `git cherry-pick -x`.
+    pub record_origin: bool,
+}
+
+impl Default for BackportOpts {
+    fn default() -> Self {
+        Self {
+            push: None,
+            record_origin: true,
+        }
+    }
+}
+
+impl BackportOpts {
+    /// Record the origin of each pick and push nothing.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push the created branches to `remote`.
+    #[inline]
+    #[must_use]
+    pub fn push(mut self, remote: impl Into<String>) -> Self {
+        self.push = Some(remote.into());
+        self
+    }
+
+    /// Whether to append `(cherry picked from commit <id>)` to messages.
+    #[inline]
+    #[must_use]
+    pub fn record_origin(mut self, yes: bool) -> Self {
+        self.record_origin = yes;
+        self
+    }
+}
+
+/// What happened on one target branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackportOutcome {
+    /// The backport branch was created.
+    Created,
+    /// Every commit was already on the target; no branch was created.
+    AlreadyApplied,
+    /// A commit conflicted; no branch was created.
+    Conflicts,
+    /// The backport branch exists from an earlier run and was left alone.
+    BranchExists,
+}
+
+/// Result of backporting onto one target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackportResult {
+    /// The target as given.
+    pub target: String,
+    /// Name of the backport branch (without `refs/heads/`).
+    pub branch: String,
+    pub outcome: BackportOutcome,
+    /// Commit the target pointed at.
+    pub base_id: CommitId,
+    /// Tip of the backport branch, if it exists.
+    pub tip: Option<CommitId>,
+    /// Original and new ID of each picked commit, in order.
+    pub picked: Vec<(CommitId, CommitId)>,
+    /// Commits whose changes were already on the target.
+    pub skipped: Vec<CommitId>,
+    /// Conflicted files of the commit that stopped the backport.
+    pub conflicts: Vec<ConflictedFile>,
+    /// The commit that conflicted.
+    pub conflicting_commit: Option<CommitId>,
+    /// Whether the branch was pushed.
+    pub pushed: bool,
+    /// Why pushing the branch failed.
+    pub push_error: Option<String>,
+}
+
+/// Cherry-pick `commits`, in the order given, onto each of `targets`.
+///
+/// Targets are branches or any revision; for a remote-tracking branch like
+/// `origin/release-1.x` the backport branch is named after `release-1.x`.
+/// A conflict on one target doesn't stop the others: every target gets its
+/// own [`BackportResult`]. Fails up front if a commit can't be resolved or is
+/// a merge commit, which has no single change to pick.
+pub async fn backport(
+    repo: RepoHandle,
+    commits: &[&str],
+    targets: &[&str],
+    opts: BackportOpts,
+) -> GitResult<Vec<BackportResult>> {
+    if commits.is_empty() {
+        return Err(GitError::InvalidInput("No commits to backport".to_string()));
+    }
+
+    let repo_inner = repo.clone_inner();
+    let commits: Vec<String> = commits.iter().map(ToString::to_string).collect();
+    let targets: Vec<String> = targets.iter().map(ToString::to_string).collect();
+    let record_origin = opts.record_origin;
+    let mut results = tokio::task::spawn_blocking(move || {
+        let picks = commits
+            .iter()
+            .map(|rev| {
+                let id = resolve_commit(&repo_inner, rev)?;
+                let commit = repo_inner
+                    .find_commit(id)
+                    .map_err(GitError::gix)?
+                    .decode()
+                    .map_err(GitError::gix)?
+                    .into_owned();
+                if commit.parents.len() != 1 {
+                    return Err(GitError::InvalidInput(format!(
+                        "Cannot backport {rev}: only commits with a single parent can be picked"
+                    )));
+                }
+                Ok((id, commit))
+            })
+            .collect::<GitResult<Vec<_>>>()?;
+        let committer = committer(&repo_inner)?;
+        let label = picks[0].0.to_hex_with_len(7).to_string();
+
+        targets
+            .iter()
+            .map(|target| {
+                backport_one(
+                    &repo_inner,
+                    target,
+                    &label,
+                    &picks,
+                    &committer,
+                    record_origin,
+                )
+            })
+            .collect::<GitResult<Vec<_>>>()
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    if let Some(remote) = opts.push {
+        for result in &mut results {
+            if result.outcome != BackportOutcome::Created {
+                continue;
+            }
+            let refname = format!("refs/heads/{}", result.branch);
+            let push_opts = PushOpts {
+                remote: remote.clone(),
+                refspecs: vec![format!("{refname}:{refname}")],
+                ..PushOpts::default()
+            };
+            match super::push::push(repo.clone(), push_opts).await {
+                Ok(_) => result.pushed = true,
+                Err(e) => result.push_error = Some(e.to_string()),
+            }
+        }
+    }
+    Ok(results)
+}
+
+fn backport_one(
+    repo: &gix::Repository,
+    target: &str,
+    label: &str,
+    picks: &[(CommitId, gix::objs::Commit)],
+    committer: &gix::actor::Signature,
+    record_origin: bool,
+) -> GitResult<BackportResult> {
+    let base_id = resolve_commit(repo, target)?;
+    let local = format!("refs/heads/{target}");
+    let name = match super::branch::parse_remote_branch(target) {
+        Some((_, branch))
+            if repo
+                .try_find_reference(local.as_str())
+                .map_err(GitError::gix)?
+                .is_none() =>
+        {
+            branch
+        }
+        _ => target.strip_prefix("refs/heads/").unwrap_or(target),
+    };
+    let branch = format!("backport/{name}/{label}");
+    let branch_ref = format!("refs/heads/{branch}");
+
+    let mut result = BackportResult {
+        target: target.to_string(),
+        branch,
+        outcome: BackportOutcome::BranchExists,
+        base_id,
+        tip: None,
+        picked: Vec::new(),
+        skipped: Vec::new(),
+        conflicts: Vec::new(),
+        conflicting_commit: None,
+        pushed: false,
+        push_error: None,
+    };
+    if let Some(existing) = repo
+        .try_find_reference(branch_ref.as_str())
+        .map_err(GitError::gix)?
+    {
+        result.tip = Some(
+            existing
+                .into_fully_peeled_id()
+                .map_err(GitError::gix)?
+                .detach(),
+        );
+        return Ok(result);
+    }
+
+    let mut tip = base_id;
+    let mut tree = tree_of(repo, base_id)?;
+    for (id, original) in picks {
+        let parent_tree = tree_of(repo, original.parents[0])?;
+        let short = id.to_hex_with_len(7).to_string();
+        let ancestor = format!("parent of {short}");
+        let labels = Labels {
+            ancestor: Some(ancestor.as_str().into()),
+            current: Some(target.into()),
+            other: Some(short.as_str().into()),
+        };
+        let options = repo.tree_merge_options().map_err(GitError::gix)?;
+        let mut outcome = repo
+            .merge_trees(parent_tree, tree, original.tree, labels, options)
+            .map_err(GitError::gix)?;
+        if outcome.has_unresolved_conflicts(TreatAsUnresolved::default()) {
+            result.outcome = BackportOutcome::Conflicts;
+            result.conflicts = conflicted_files(&outcome);
+            result.conflicting_commit = Some(*id);
+            result.picked.clear();
+            return Ok(result);
+        }
+        let picked_tree = outcome.tree.write().map_err(GitError::gix)?.detach();
+        if picked_tree == tree {
+            result.skipped.push(*id);
+            continue;
+        }
+
+        let mut picked = original.clone();
+        picked.tree = picked_tree;
+        picked.parents = vec![tip].into();
+        picked.committer = committer.clone();
+        picked
+            .extra_headers
+            .retain(|(name, _)| name != "gpgsig" && name != "gpgsig-sha256");
+        if record_origin {
+            picked.message = format!(
+                "{}\n\n(cherry picked from commit {id})\n",
+                original.message.to_str_lossy().trim_end()
+            )
+            .into();
+        }
+        tip = repo.write_object(&picked).map_err(GitError::gix)?.detach();
+        tree = picked_tree;
+        result.picked.push((*id, tip));
+    }
+
+    if result.picked.is_empty() {
+        result.outcome = BackportOutcome::AlreadyApplied;
+        return Ok(result);
+    }
+    repo.reference(
+        branch_ref.as_str(),
+        tip,
+        PreviousValue::MustNotExist,
+        format!("backport: {} commit(s) onto {target}", result.picked.len()),
+    )
+    .map_err(GitError::gix)?;
+    result.outcome = BackportOutcome::Created;
+    result.tip = Some(tip);
+    Ok(result)
+}
@@ -0,0 +1,181 @@
+//! Per-line authorship of a file
+//!
+//! [`blame`] tells for each line of a file at a revision which commit last
+//! changed it, who authored that commit and when, following the line through
+//! renames.
+
+use std::collections::HashMap;
+
+use gix::bstr::{BStr, ByteSlice};
+
+use super::encoding::commit_summary;
+use crate::{CommitId, GitError, GitResult, RepoHandle, Signature};
+
+/// Options for [`blame`]
+#[derive(Debug, Clone)]
+pub struct BlameOpts {
+    /// File to blame, relative to the repository root
+    pub path: String,
+    /// Revision to blame at (default: `HEAD`)
+    pub rev: Option<String>,
+    /// First and last line to report, 1-based and inclusive (default: all)
+    pub lines: Option<(u32, u32)>,
+}
+
+impl BlameOpts {
+    /// Blame every line of `path` at `HEAD`
+    #[inline]
+    pub fn new<S: Into<String>>(path: S) -> Self {
+        Self {
+            path: path.into(),
+            rev: None,
+            lines: None,
+        }
+    }
+
+    /// Blame the file as of `rev`
+    #[inline]
+    #[must_use]
+    pub fn rev<S: Into<String>>(mut self, rev: S) -> Self {
+        self.rev = Some(rev.into());
+        self
+    }
+
+    /// Only report lines `start..=end`, 1-based
+    #[inline]
+    #[must_use]
+    pub fn lines(mut self, start: u32, end: u32) -> Self {
+        self.lines = Some((start, end));
+        self
+    }
+}
+
+/// Authorship of one line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    /// Line number in the blamed file, 1-based
+    pub line: u32,
+    /// Commit that last changed the line
+    pub commit: CommitId,
+    /// Author of that commit; its time is when the line was written
+    pub author: Signature,
+    /// Subject of that commit
+    pub summary: String,
+    /// Line number in the file as that commit left it, 1-based
+    pub original_line: u32,
+    /// Path in that commit, if the file was renamed since
+    pub original_path: Option<String>,
+    /// The line's text without its line ending
+    pub content: String,
+}
+
+/// Result of [`blame`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameResult {
+    /// The blamed file
+    pub path: String,
+    /// Commit the file was blamed at
+    pub commit: CommitId,
+    /// Lines in the file
+    pub total_lines: u32,
+    /// The requested lines, in order
+    pub lines: Vec<BlameLine>,
+}
+
+/// Find the commit, author and time that last touched each line of a file
+///
+/// Fails if the file doesn't exist at the revision, or if the line range is
+/// empty or starts past the end of the file; a range ending past the end is
+/// cut short.
+pub async fn blame(repo: RepoHandle, opts: BlameOpts) -> GitResult<BlameResult> {
+    let repo = repo.clone_for_traversal();
+
+    tokio::task::spawn_blocking(move || {
+        let path = opts.path.trim_start_matches("./").to_string();
+        let rev = opts.rev.as_deref().unwrap_or("HEAD");
+        let commit = repo
+            .rev_parse_single(rev)
+            .map_err(|e| GitError::InvalidInput(format!("Cannot resolve '{rev}': {e}")))?
+            .object()
+            .map_err(GitError::gix)?
+            .peel_to_commit()
+            .map_err(GitError::gix)?;
+        let entry = commit
+            .tree()
+            .map_err(GitError::gix)?
+            .peel_to_entry_by_path(&path)
+            .map_err(GitError::gix)?
+            .filter(|entry| entry.mode().is_blob())
+            .ok_or_else(|| GitError::InvalidInput(format!("No file '{path}' at {rev}")))?;
+        let blob = entry.object().map_err(GitError::gix)?.detach();
+        let contents: Vec<&BStr> = blob.data.lines().map(ByteSlice::as_bstr).collect();
+        let total_lines = u32::try_from(contents.len()).unwrap_or(u32::MAX);
+
+        let (start, end) = match opts.lines {
+            Some((start, end)) if start == 0 || start > end || start > total_lines => {
+                return Err(GitError::InvalidInput(format!(
+                    "Invalid line range {start}..={end} for '{path}' with {total_lines} lines"
+                )));
+            }
+            Some((start, end)) => (start, end.min(total_lines)),
+            None => (1, total_lines),
+        };
+
+        let outcome = repo
+            .blame_file(
+                path.as_bytes().as_bstr(),
+                commit.id,
+                gix::repository::blame_file::Options::default(),
+            )
+            .map_err(GitError::gix)?;
+
+        let mut commits: HashMap<gix::ObjectId, (Signature, String)> = HashMap::new();
+        let mut lines = Vec::new();
+        for entry in &outcome.entries {
+            let first = entry.start_in_blamed_file + 1;
+            let last = entry.start_in_blamed_file + entry.len.get();
+            if last < start || first > end {
+                continue;
+            }
+            let (author, summary) = match commits.get(&entry.commit_id) {
+                Some(found) => found.clone(),
+                None => {
+                    let source = repo.find_commit(entry.commit_id).map_err(GitError::gix)?;
+                    let author = Signature::try_from(source.author().map_err(GitError::gix)?)?;
+                    let found = (author, commit_summary(&source));
+                    commits.insert(entry.commit_id, found.clone());
+                    found
+                }
+            };
+            let original_path = entry
+                .source_file_name
+                .as_ref()
+                .map(|name| name.to_str_lossy().into_owned());
+            for line in first.max(start)..=last.min(end) {
+                let offset = line - first;
+                lines.push(BlameLine {
+                    line,
+                    commit: entry.commit_id,
+                    author: author.clone(),
+                    summary: summary.clone(),
+                    original_line: entry.start_in_source_file + offset + 1,
+                    original_path: original_path.clone(),
+                    content: contents
+                        .get(line as usize - 1)
+                        .map(|text| text.to_str_lossy().into_owned())
+                        .unwrap_or_default(),
+                });
+            }
+        }
+        lines.sort_by_key(|line| line.line);
+
+        Ok(BlameResult {
+            path,
+            commit: commit.id,
+            total_lines,
+            lines,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
@@ -0,0 +1,330 @@
+//! Stale branch cleanup.
+//!
+//! Finds local branches that are done with: merged into the default branch,
+//! or tracking a remote branch that no longer exists, and untouched for a
+//! while. Runs as a dry run unless deletion is asked for.
+
+use chrono::{DateTime, Duration, Utc};
+use gix::bstr::ByteSlice;
+use gix::refs::transaction::PreviousValue;
+
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Which branches [`cleanup_branches`] removes.
+#[derive(Debug, Clone)]
+pub struct CleanupPolicy {
+    /// Only branches whose last commit is older than this many days
+    pub older_than_days: u32,
+    /// Branches fully merged into the default branch
+    pub merged: bool,
+    /// Branches whose upstream was deleted on the remote (as last fetched)
+    pub upstream_gone: bool,
+    /// Branch merged branches are checked against (default: the remote's
+    /// HEAD, asked of the remote if not recorded locally, then `main`, then
+    /// `master`)
+    pub default_branch: Option<String>,
+    /// Delete the branches; otherwise only report them
+    pub delete: bool,
+    /// Also delete the upstream branch of merged branches on the remote
+    pub delete_remote: bool,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        Self {
+            older_than_days: 30,
+            merged: true,
+            upstream_gone: true,
+            default_branch: None,
+            delete: false,
+            delete_remote: false,
+        }
+    }
+}
+
+impl CleanupPolicy {
+    /// Report merged and orphaned branches older than 30 days, deleting nothing.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn older_than_days(mut self, days: u32) -> Self {
+        self.older_than_days = days;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn merged(mut self, yes: bool) -> Self {
+        self.merged = yes;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn upstream_gone(mut self, yes: bool) -> Self {
+        self.upstream_gone = yes;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn default_branch(mut self, name: impl Into<String>) -> Self {
+        self.default_branch = Some(name.into());
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn delete(mut self, yes: bool) -> Self {
+        self.delete = yes;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn delete_remote(mut self, yes: bool) -> Self {
+        self.delete_remote = yes;
+        self
+    }
+}
+
+/// Why a branch is up for cleanup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupReason {
+    /// Fully merged into the default branch
+    Merged,
+    /// Its upstream no longer exists on the remote
+    UpstreamGone,
+}
+
+impl CleanupReason {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Merged => "merged",
+            Self::UpstreamGone => "upstream-gone",
+        }
+    }
+}
+
+impl std::fmt::Display for CleanupReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A branch matched by the policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleBranch {
+    pub name: String,
+    pub tip: CommitId,
+    /// Time of the branch's last commit
+    pub last_commit: DateTime<Utc>,
+    pub reason: CleanupReason,
+    /// Remote-tracking branch it follows, e.g. `origin/feature`
+    pub upstream: Option<String>,
+    /// Whether the local branch was deleted
+    pub deleted: bool,
+    /// Whether the remote branch was deleted
+    pub remote_deleted: bool,
+    /// Why deleting the local or remote branch failed
+    pub error: Option<String>,
+}
+
+/// Result of [`cleanup_branches`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// Branch merged branches were checked against
+    pub default_branch: Option<String>,
+    /// Matching branches, by name
+    pub branches: Vec<StaleBranch>,
+    /// Whether nothing was deleted because the policy didn't ask for it
+    pub dry_run: bool,
+}
+
+/// The local branch of a candidate and where its upstream lives
+struct Candidate {
+    branch: StaleBranch,
+    /// Remote name and branch on the remote
+    remote_branch: Option<(String, String)>,
+}
+
+/// Find, and with [`CleanupPolicy::delete`] delete, local branches that are
+/// merged into the default branch or whose upstream is gone.
+///
+/// The default branch, the current branch and branches checked out in other
+/// worktrees are never matched. Deletion failures are recorded on the branch
+/// instead of stopping the cleanup; remote branches are deleted with the git
+/// CLI, so [`CleanupPolicy::delete_remote`] fails per branch under `pure-gix`.
+pub async fn cleanup_branches(
+    repo: RepoHandle,
+    policy: CleanupPolicy,
+) -> GitResult<CleanupReport> {
+    let repo_inner = repo.clone_inner();
+    let find_policy = policy.clone();
+    let (default_branch, candidates) =
+        tokio::task::spawn_blocking(move || find_candidates(&repo_inner, &find_policy))
+            .await
+            .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    let mut branches = Vec::with_capacity(candidates.len());
+    for Candidate {
+        mut branch,
+        remote_branch,
+    } in candidates
+    {
+        if policy.delete {
+            let repo_inner = repo.clone_inner();
+            let (name, tip) = (branch.name.clone(), branch.tip);
+            let deleted =
+                tokio::task::spawn_blocking(move || delete_local(&repo_inner, &name, tip))
+                    .await
+                    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?;
+            match deleted {
+                Ok(()) => branch.deleted = true,
+                Err(e) => branch.error = Some(e.to_string()),
+            }
+        }
+        if policy.delete
+            && policy.delete_remote
+            && branch.deleted
+            && branch.reason == CleanupReason::Merged
+            && let Some((remote, remote_name)) = &remote_branch
+        {
+            match crate::delete_remote_branch(repo.clone(), remote, remote_name).await {
+                Ok(()) => branch.remote_deleted = true,
+                Err(e) => branch.error = Some(e.to_string()),
+            }
+        }
+        branches.push(branch);
+    }
+
+    Ok(CleanupReport {
+        default_branch,
+        branches,
+        dry_run: !policy.delete,
+    })
+}
+
+fn find_candidates(
+    repo: &gix::Repository,
+    policy: &CleanupPolicy,
+) -> GitResult<(Option<String>, Vec<Candidate>)> {
+    if policy.default_branch.is_none() {
+        // Records the remote's HEAD for the lookup below if it's missing
+        let remote = repo
+            .remote_default_name(gix::remote::Direction::Fetch)
+            .map_or_else(
+                || "origin".to_string(),
+                |name| name.to_str_lossy().into_owned(),
+            );
+        crate::operations::remote_head::ensure_remote_head(repo, &remote);
+    }
+    let default = crate::operations::lint::default_branch(repo, policy.default_branch.as_deref());
+    let default_tip = match &default {
+        Some(name) => Some(crate::operations::compare::resolve_commit(repo, name)?),
+        None => None,
+    };
+    let current = repo
+        .head_name()
+        .map_err(GitError::gix)?
+        .map(|name| name.shorten().to_str_lossy().into_owned());
+    let older_than = Utc::now() - Duration::days(i64::from(policy.older_than_days));
+
+    let platform = repo.references().map_err(GitError::gix)?;
+    let references = platform.local_branches().map_err(GitError::gix)?;
+    let mut candidates = Vec::new();
+    for reference in references {
+        let mut reference = reference.map_err(GitError::gix)?;
+        let name = reference.name().shorten().to_str_lossy().into_owned();
+        if Some(&name) == default.as_ref() || Some(&name) == current.as_ref() {
+            continue;
+        }
+        let Ok(tip) = reference.peel_to_commit() else {
+            continue;
+        };
+        let seconds = tip.time().map_err(GitError::gix)?.seconds;
+        if seconds >= older_than.timestamp() {
+            continue;
+        }
+        let last_commit = DateTime::from_timestamp(seconds, 0).ok_or_else(|| {
+            GitError::InvalidInput(format!("Invalid timestamp {seconds} for branch {name}"))
+        })?;
+
+        // Configured upstream, and whether it still exists
+        let tracking = repo
+            .branch_remote_tracking_ref_name(reference.name(), gix::remote::Direction::Fetch)
+            .and_then(Result::ok)
+            .map(std::borrow::Cow::into_owned);
+        let upstream_exists = match &tracking {
+            Some(tracking) => repo
+                .try_find_reference(tracking.as_bstr())
+                .map_err(GitError::gix)?
+                .is_some(),
+            None => false,
+        };
+        let remote_branch = repo
+            .branch_remote_name(name.as_bytes().as_bstr(), gix::remote::Direction::Fetch)
+            .map(|remote| remote.as_bstr().to_str_lossy().into_owned())
+            .zip(
+                repo.branch_remote_ref_name(reference.name(), gix::remote::Direction::Fetch)
+                    .and_then(Result::ok)
+                    .map(|merge| merge.shorten().to_str_lossy().into_owned()),
+            );
+
+        let merged = policy.merged
+            && default_tip.is_some_and(|default_tip| {
+                tip.id == default_tip
+                    || repo
+                        .merge_base(tip.id, default_tip)
+                        .is_ok_and(|base| base == tip.id)
+            });
+        let reason = if merged {
+            CleanupReason::Merged
+        } else if policy.upstream_gone && tracking.is_some() && !upstream_exists {
+            CleanupReason::UpstreamGone
+        } else {
+            continue;
+        };
+        if crate::operations::worktree::check_branch_not_in_use(repo, &name).is_err() {
+            continue;
+        }
+
+        candidates.push(Candidate {
+            branch: StaleBranch {
+                name,
+                tip: tip.id,
+                last_commit,
+                reason,
+                upstream: tracking.map(|tracking| tracking.shorten().to_str_lossy().into_owned()),
+                deleted: false,
+                remote_deleted: false,
+                error: None,
+            },
+            remote_branch: remote_branch.filter(|_| upstream_exists),
+        });
+    }
+    candidates.sort_by(|a, b| a.branch.name.cmp(&b.branch.name));
+    Ok((default, candidates))
+}
+
+/// Delete `refs/heads/<name>` if it still points at `tip`
+fn delete_local(repo: &gix::Repository, name: &str, tip: CommitId) -> GitResult<()> {
+    use gix::refs::transaction::{Change, RefEdit, RefLog};
+
+    let edit = RefEdit {
+        change: Change::Delete {
+            expected: PreviousValue::MustExistAndMatch(gix::refs::Target::Object(tip)),
+            log: RefLog::AndReference,
+        },
+        name: gix::refs::FullName::try_from(format!("refs/heads/{name}")).map_err(GitError::gix)?,
+        deref: false,
+    };
+    repo.edit_reference(edit).map_err(GitError::gix)?;
+    Ok(())
+}
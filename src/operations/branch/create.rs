@@ -72,7 +72,7 @@ pub fn branch(repo: RepoHandle, opts: BranchOpts) -> AsyncTask<GitResult<()>> {
 
         // Create or update the branch reference
         repo.reference(branch_ref.as_str(), target_oid, constraint, reflog_message)
-            .map_err(|e| GitError::Gix(e.into()))?;
+            .map_err(GitError::gix)?;
 
         // Handle tracking configuration
         if track {
@@ -57,7 +57,7 @@ pub fn delete_branch(repo: RepoHandle, name: String, _force: bool) -> AsyncTask<
             .map_err(|_| GitError::BranchNotFound(name.clone()))?;
 
         // Delete the reference (creates reflog entry automatically)
-        branch.delete().map_err(|e| GitError::Gix(e.into()))?;
+        branch.delete().map_err(GitError::gix)?;
 
         Ok(())
     })
@@ -2,7 +2,6 @@
 //!
 //! This module contains internal utility functions used across branch operations.
 
-use std::borrow::Cow;
 use std::sync::atomic::AtomicBool;
 
 use gix::bstr::ByteSlice;
@@ -95,7 +94,7 @@ pub(super) fn is_valid_branch_name(name: &str) -> bool {
 ///
 /// Returns borrowed string slices to avoid allocation.
 #[inline]
-pub(super) fn parse_remote_branch(spec: &str) -> Option<(&str, &str)> {
+pub(crate) fn parse_remote_branch(spec: &str) -> Option<(&str, &str)> {
     // Handle full remote ref: refs/remotes/<remote>/<branch>
     if let Some(stripped) = spec.strip_prefix(REFS_REMOTES_PREFIX) {
         return stripped
@@ -116,36 +115,45 @@ pub(super) fn parse_remote_branch(spec: &str) -> Option<(&str, &str)> {
 
 /// Setup tracking configuration for a branch.
 ///
-/// Writes branch.<name>.remote and branch.<name>.merge to repository config.
+/// Writes branch.<name>.remote and branch.<name>.merge to the repository's
+/// config file, and to `repo`'s view of it.
 #[inline]
-pub(super) fn setup_tracking(
+pub(crate) fn setup_tracking(
     repo: &mut gix::Repository,
     branch_name: &str,
     remote_name: &str,
     remote_branch: &str,
 ) -> GitResult<()> {
-    use gix::config::parse::section::ValueName;
-
-    // Get mutable config snapshot
-    let mut config = repo.config_snapshot_mut();
-
-    // Create branch section with branch name as subsection
-    let mut section = config
-        .new_section("branch", Some(Cow::Owned(branch_name.to_string().into())))
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
-
-    // Set branch.<name>.remote = <remote_name>
-    let remote_key = ValueName::try_from("remote").map_err(|e| GitError::Gix(Box::new(e)))?;
-    section.push(remote_key, Some(remote_name.as_bytes().as_bstr()));
-
-    // Set branch.<name>.merge = refs/heads/<remote_branch>
-    let merge_key = ValueName::try_from("merge").map_err(|e| GitError::Gix(Box::new(e)))?;
     let merge_ref = format!("{REFS_HEADS_PREFIX}{remote_branch}");
-    section.push(merge_key, Some(merge_ref.as_bytes().as_bstr()));
+    let subsection = branch_name.as_bytes().as_bstr();
+    let set = |config: &mut gix::config::File<'static>| -> GitResult<()> {
+        config
+            .set_raw_value_by("branch", Some(subsection), "remote", remote_name)
+            .map_err(GitError::gix)?;
+        config
+            .set_raw_value_by("branch", Some(subsection), "merge", merge_ref.as_str())
+            .map_err(GitError::gix)?;
+        Ok(())
+    };
+
+    // Changes to the snapshot stay in memory, so the file is written too
+    let config_path = repo.common_dir().join("config");
+    let mut file =
+        gix::config::File::from_path_no_includes(config_path.clone(), gix::config::Source::Local)
+            .map_err(GitError::gix)?;
+    set(&mut file)?;
+    let mut lock = gix::lock::File::acquire_to_update_resource(
+        &config_path,
+        gix::lock::acquire::Fail::Immediately,
+        None,
+    )
+    .map_err(GitError::gix)?;
+    file.write_to(&mut lock)?;
+    lock.commit().map_err(|e| GitError::gix(e.error))?;
 
-    // Commit configuration changes to persist to .git/config
-    drop(section);
-    config.commit().map_err(|e| GitError::Gix(Box::new(e)))?;
+    let mut config = repo.config_snapshot_mut();
+    set(&mut config)?;
+    config.commit().map_err(GitError::gix)?;
 
     Ok(())
 }
@@ -175,21 +183,19 @@ pub(super) fn checkout_branch(
     // Get target commit and its tree
     let commit = repo
         .find_object(target_id)
-        .map_err(|e| GitError::Gix(e.into()))?
+        .map_err(GitError::gix)?
         .try_into_commit()
         .map_err(|_| GitError::InvalidInput("Target does not point to a commit".into()))?;
 
-    let tree_id = commit.tree_id().map_err(|e| GitError::Gix(e.into()))?;
+    let tree_id = commit.tree_id().map_err(GitError::gix)?;
 
     // Create index from target tree
-    let mut index = repo
-        .index_from_tree(&tree_id)
-        .map_err(|e| GitError::Gix(e.into()))?;
+    let mut index = repo.index_from_tree(&tree_id).map_err(GitError::gix)?;
 
     // Configure checkout options
     let mut checkout_opts = repo
         .checkout_options(gix::worktree::stack::state::attributes::Source::WorktreeThenIdMapping)
-        .map_err(|e| GitError::Gix(e.into()))?;
+        .map_err(GitError::gix)?;
     checkout_opts.overwrite_existing = force;
     checkout_opts.destination_is_initially_empty = false;
 
@@ -197,16 +203,13 @@ pub(super) fn checkout_branch(
     let outcome = gix::worktree::state::checkout(
         &mut index,
         worktree.base(),
-        repo.objects
-            .clone()
-            .into_arc()
-            .map_err(|e| GitError::Gix(e.into()))?,
+        repo.objects.clone().into_arc().map_err(GitError::gix)?,
         &gix::progress::Discard,
         &gix::progress::Discard,
         &AtomicBool::new(false),
         checkout_opts,
     )
-    .map_err(|e| GitError::Gix(e.into()))?;
+    .map_err(GitError::gix)?;
 
     // Handle checkout errors
     if !outcome.errors.is_empty() {
@@ -227,14 +230,13 @@ pub(super) fn checkout_branch(
     // Write updated index to disk with proper locking and checksum
     index
         .write(gix::index::write::Options::default())
-        .map_err(|e| GitError::Gix(e.into()))?;
+        .map_err(GitError::gix)?;
 
     // Update HEAD to point symbolically to the branch
-    let head_name: FullName = "HEAD".try_into().map_err(|e| GitError::Gix(Box::new(e)))?;
+    let from = crate::operations::checkout::head_label(repo);
+    let head_name: FullName = "HEAD".try_into().map_err(GitError::gix)?;
 
-    let branch_full_name: FullName = branch_ref
-        .try_into()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let branch_full_name: FullName = branch_ref.try_into().map_err(GitError::gix)?;
 
     let branch_name = branch_ref
         .strip_prefix(REFS_HEADS_PREFIX)
@@ -245,7 +247,7 @@ pub(super) fn checkout_branch(
             log: LogChange {
                 mode: RefLog::AndReference,
                 force_create_reflog: false,
-                message: format!("checkout: moving from HEAD to {branch_name}").into(),
+                message: format!("checkout: moving from {from} to {branch_name}").into(),
             },
             expected: PreviousValue::Any,
             new: Target::Symbolic(branch_full_name),
@@ -253,7 +255,7 @@ pub(super) fn checkout_branch(
         name: head_name,
         deref: false,
     })
-    .map_err(|e| GitError::Gix(e.into()))?;
+    .map_err(GitError::gix)?;
 
     Ok(())
 }
@@ -3,6 +3,7 @@
 //! This module provides the `BranchOpts` builder pattern and branch operation
 //! implementation for the `GitGix` service.
 
+mod cleanup;
 mod create;
 mod delete;
 mod helpers;
@@ -11,10 +12,14 @@ mod rename;
 mod types;
 
 // Re-export public types
+pub use cleanup::{CleanupPolicy, CleanupReason, CleanupReport, StaleBranch};
 pub use types::BranchOpts;
 
 // Re-export public functions
+pub use cleanup::cleanup_branches;
 pub use create::branch;
 pub use delete::delete_branch;
 pub use list::list_branches;
 pub use rename::rename_branch;
+
+pub(crate) use helpers::{parse_remote_branch, setup_tracking};
@@ -64,9 +64,7 @@ pub fn rename_branch(
             .map_err(|_| GitError::BranchNotFound(old_name.clone()))?;
 
         // IMPORTANT: into_fully_peeled_id() CONSUMES the reference!
-        let target_oid = old_branch
-            .into_fully_peeled_id()
-            .map_err(|e| GitError::Gix(e.into()))?;
+        let target_oid = old_branch.into_fully_peeled_id().map_err(GitError::gix)?;
 
         // Check if renaming current branch
         let is_current_branch = if let Ok(head) = repo.head() {
@@ -93,23 +91,18 @@ pub fn rename_branch(
             PreviousValue::Any,
             format!("branch: renamed {old_name} to {new_name}"),
         )
-        .map_err(|e| GitError::Gix(e.into()))?;
+        .map_err(GitError::gix)?;
 
         // Find old reference again (consumed by into_fully_peeled_id)
-        let old_branch = repo
-            .find_reference(&old_ref)
-            .map_err(|e| GitError::Gix(e.into()))?;
+        let old_branch = repo.find_reference(&old_ref).map_err(GitError::gix)?;
 
         // Delete old reference
-        old_branch.delete().map_err(|e| GitError::Gix(e.into()))?;
+        old_branch.delete().map_err(GitError::gix)?;
 
         // Update HEAD if renaming current branch
         if is_current_branch {
-            let head_name: FullName = "HEAD".try_into().map_err(|e| GitError::Gix(Box::new(e)))?;
-            let new_full: FullName = new_ref
-                .as_str()
-                .try_into()
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
+            let head_name: FullName = "HEAD".try_into().map_err(GitError::gix)?;
+            let new_full: FullName = new_ref.as_str().try_into().map_err(GitError::gix)?;
 
             repo.edit_reference(RefEdit {
                 change: Change::Update {
@@ -124,7 +117,7 @@ pub fn rename_branch(
                 name: head_name,
                 deref: false,
             })
-            .map_err(|e| GitError::Gix(e.into()))?;
+            .map_err(GitError::gix)?;
         }
 
         Ok(())
@@ -0,0 +1,89 @@
+//! Object and pack caches shared across clones of a [`RepoHandle`](crate::RepoHandle)
+//!
+//! gix gives every clone of a repository its own, empty caches. Operations run
+//! on a fresh clone, so by default nothing decoded by one operation is reused
+//! by the next. Caches installed here wrap a single shared store, so every
+//! clone of the handle (and thus every operation run on it) sees the same
+//! warm cache.
+
+use std::sync::{Arc, Mutex};
+
+use gix::odb::pack::cache::{DecodeEntry, Object};
+
+/// Object cache size used by history, log and diff when none is configured
+pub const DEFAULT_TRAVERSAL_OBJECT_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// A cache whose contents are shared by all clones
+struct Shared<T>(Arc<Mutex<T>>);
+
+impl Object for Shared<gix::odb::pack::cache::object::MemoryCappedHashmap> {
+    fn put(&mut self, id: gix::ObjectId, kind: gix::object::Kind, data: &[u8]) {
+        if let Ok(mut cache) = self.0.lock() {
+            cache.put(id, kind, data);
+        }
+    }
+
+    fn get(&mut self, id: &gix::ObjectId, out: &mut Vec<u8>) -> Option<gix::object::Kind> {
+        self.0.lock().ok()?.get(id, out)
+    }
+}
+
+impl DecodeEntry for Shared<gix::odb::pack::cache::lru::MemoryCappedHashmap> {
+    fn put(
+        &mut self,
+        pack_id: u32,
+        offset: u64,
+        data: &[u8],
+        kind: gix::object::Kind,
+        compressed_size: usize,
+    ) {
+        if let Ok(mut cache) = self.0.lock() {
+            cache.put(pack_id, offset, data, kind, compressed_size);
+        }
+    }
+
+    fn get(
+        &mut self,
+        pack_id: u32,
+        offset: u64,
+        out: &mut Vec<u8>,
+    ) -> Option<(gix::object::Kind, usize)> {
+        self.0.lock().ok()?.get(pack_id, offset, out)
+    }
+}
+
+/// Install a shared cache of decoded objects, capped at `bytes` (0 disables it)
+pub(crate) fn set_object_cache(repo: &mut gix::Repository, bytes: usize) {
+    if bytes == 0 {
+        repo.objects.unset_object_cache();
+        return;
+    }
+
+    let cache = Arc::new(Mutex::new(
+        gix::odb::pack::cache::object::MemoryCappedHashmap::new(bytes),
+    ));
+    repo.objects
+        .set_object_cache(move || Box::new(Shared(Arc::clone(&cache))));
+}
+
+/// Install a shared cache of decoded pack entries, capped at `bytes` (0 disables it)
+pub(crate) fn set_pack_cache(repo: &mut gix::Repository, bytes: usize) {
+    if bytes == 0 {
+        repo.objects.unset_pack_cache();
+        return;
+    }
+
+    let cache = Arc::new(Mutex::new(
+        gix::odb::pack::cache::lru::MemoryCappedHashmap::new(bytes),
+    ));
+    repo.objects
+        .set_pack_cache(move || Box::new(Shared(Arc::clone(&cache))));
+}
+
+/// Give a repository used for history traversal an object cache, unless one is configured
+///
+/// Walking history and diffing trees decodes the same trees over and over;
+/// without a cache every one of them is inflated again.
+pub(crate) fn ensure_traversal_cache(repo: &mut gix::Repository) {
+    repo.object_cache_size_if_unset(DEFAULT_TRAVERSAL_OBJECT_CACHE_BYTES);
+}
@@ -0,0 +1,139 @@
+//! Environment capability report
+//!
+//! Lets orchestrators check up front what this environment can do: whether
+//! the git CLI fallback (used by push, stash and config reading) is present
+//! and allowed (never with the `pure-gix` feature), which transports work,
+//! whether Git LFS is installed, and which optional features were compiled in.
+
+use std::process::{Command, Stdio};
+
+use crate::{GitError, GitResult};
+
+/// gix version this crate is built against (keep in sync with Cargo.toml)
+pub const GIX_VERSION: &str = "0.75";
+
+/// gix features enabled in Cargo.toml
+const GIX_FEATURES: &[&str] = &[
+    "blob-diff",
+    "blocking-network-client",
+    "blocking-http-transport-reqwest-rust-tls",
+    "max-performance",
+    "credentials",
+    "dirwalk",
+    "revision",
+    "index",
+    "worktree-mutation",
+    "merge",
+    "excludes",
+    "status",
+    "blame",
+];
+
+/// What this environment supports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitCapabilities {
+    /// Version of this crate
+    pub crate_version: String,
+    /// gix version compiled in
+    pub gix_version: String,
+    /// `git --version` output, if the git CLI is installed
+    pub git_cli_version: Option<String>,
+    /// `git lfs version` output, if Git LFS is installed
+    pub git_lfs_version: Option<String>,
+    /// Whether an `ssh` client is installed (needed for ssh:// and scp-like URLs)
+    pub ssh_available: bool,
+    /// URL schemes that can be used for clone/fetch/push
+    pub transports: Vec<String>,
+    /// Cargo features of this crate that are enabled
+    pub features: Vec<String>,
+    /// gix features compiled in
+    pub gix_features: Vec<String>,
+}
+
+impl GitCapabilities {
+    /// Whether operations that shell out to git (push, stash) can run
+    pub fn git_cli_available(&self) -> bool {
+        self.git_cli_version.is_some()
+    }
+}
+
+/// Probe the environment for git capabilities
+pub async fn capabilities() -> GitResult<GitCapabilities> {
+    tokio::task::spawn_blocking(capabilities_sync)
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))
+}
+
+fn capabilities_sync() -> GitCapabilities {
+    // pure-gix builds never run git, even if it is installed
+    #[cfg(feature = "pure-gix")]
+    let (git_cli_version, git_lfs_version) = (None, None);
+    // The executable and environment configured with `set_git_cli_options`
+    #[cfg(not(feature = "pure-gix"))]
+    let (git_cli_version, git_lfs_version) = {
+        use super::auth::git_command;
+
+        let git_cli_version = command_version(git_command().arg("--version"));
+        let git_lfs_version = git_cli_version
+            .as_ref()
+            .and_then(|_| command_version(git_command().args(["lfs", "version"])));
+        (git_cli_version, git_lfs_version)
+    };
+    let ssh_available = command_status("ssh", &["-V"]);
+
+    // Local and HTTP(S) transports are native; git:// uses the blocking network client
+    let mut transports = vec![
+        "file".to_string(),
+        "git".to_string(),
+        "http".to_string(),
+        "https".to_string(),
+    ];
+    if ssh_available {
+        transports.push("ssh".to_string());
+    }
+
+    GitCapabilities {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        gix_version: GIX_VERSION.to_string(),
+        git_cli_version,
+        git_lfs_version,
+        ssh_available,
+        transports,
+        features: enabled_features(),
+        gix_features: GIX_FEATURES.iter().map(|f| (*f).to_string()).collect(),
+    }
+}
+
+/// Cargo features of this crate that are enabled
+fn enabled_features() -> Vec<String> {
+    let mut features = Vec::new();
+    if cfg!(feature = "pure-gix") {
+        features.push("pure-gix".to_string());
+    }
+    features
+}
+
+/// First line of a command's stdout, if it ran successfully
+#[cfg(not(feature = "pure-gix"))]
+fn command_version(command: &mut Command) -> Option<String> {
+    command
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .and_then(|s| s.lines().next().map(|l| l.trim().to_string()))
+        .filter(|s| !s.is_empty())
+}
+
+/// Whether a command can be spawned and exits successfully
+fn command_status(program: &str, args: &[&str]) -> bool {
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
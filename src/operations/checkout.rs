@@ -9,6 +9,7 @@ use gix::bstr::ByteSlice;
 use gix::refs::Target;
 use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
 
+use super::progress::{ProgressCallback, with_checkout_progress};
 use crate::{GitError, GitResult, RepoHandle};
 
 /// Options for `checkout` operation with builder pattern.
@@ -17,16 +18,24 @@ pub struct CheckoutOpts {
     pub reference: String,
     pub force: bool,
     pub paths: Option<Vec<std::path::PathBuf>>,
+    /// Progress updates while writing the worktree (full checkout only)
+    pub progress: Option<ProgressCallback>,
+    /// Stash local changes first and reapply them afterwards (full checkout only)
+    pub autostash: bool,
 }
 
 impl CheckoutOpts {
     /// Create new checkout options for the given reference.
     #[inline]
+    ///
+    /// `"-"` (or `"@{-1}"`) checks out the previously checked out branch.
     pub fn new<S: Into<String>>(reference: S) -> Self {
         Self {
             reference: reference.into(),
             force: false,
             paths: None,
+            progress: None,
+            autostash: false,
         }
     }
 
@@ -48,6 +57,22 @@ impl CheckoutOpts {
         self.paths = Some(paths.into_iter().map(Into::into).collect());
         self
     }
+
+    /// Stash local changes before switching and reapply them afterwards.
+    #[inline]
+    #[must_use]
+    pub fn autostash(mut self, yes: bool) -> Self {
+        self.autostash = yes;
+        self
+    }
+
+    /// Report progress (files processed / total) while the worktree is written.
+    #[inline]
+    #[must_use]
+    pub fn progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
 }
 
 /// Checkout specific files from a reference (file restoration mode).
@@ -79,9 +104,7 @@ fn checkout_files(
     // Step 2: Get commit and tree
     let commit = repo
         .find_object(object_id)
-        .map_err(|e| {
-            GitError::InvalidInput(format!("Failed to find object for '{reference}': {e}"))
-        })?
+        .map_err(GitError::gix)?
         .try_into_commit()
         .map_err(|_| {
             GitError::InvalidInput(format!(
@@ -89,9 +112,7 @@ fn checkout_files(
             ))
         })?;
 
-    let tree = commit
-        .tree()
-        .map_err(|e| GitError::Gix(format!("Failed to get tree from commit: {e}").into()))?;
+    let tree = commit.tree().map_err(GitError::gix)?;
 
     // Step 3: Get worktree path
     let worktree = repo.worktree().ok_or_else(|| {
@@ -102,14 +123,14 @@ fn checkout_files(
     let worktree_path = worktree.base();
 
     // Step 4: Open index for updates
-    let mut index = repo.open_index().map_err(|e| GitError::Gix(e.into()))?;
+    let mut index = repo.open_index().map_err(GitError::gix)?;
 
     // Step 5: Process each file path
     for path in paths {
         // Lookup entry in tree
         let entry = tree
             .lookup_entry_by_path(&path)
-            .map_err(|e| GitError::Gix(format!("Failed to lookup path in tree: {e}").into()))?
+            .map_err(GitError::gix)?
             .ok_or_else(|| {
                 GitError::InvalidInput(format!("Path not found in tree: {}", path.display()))
             })?;
@@ -123,9 +144,7 @@ fn checkout_files(
         }
 
         // Get blob object
-        let object = entry
-            .object()
-            .map_err(|e| GitError::Gix(format!("Failed to read object: {e}").into()))?;
+        let object = entry.object().map_err(GitError::gix)?;
 
         let blob_data = &object.data;
 
@@ -145,13 +164,10 @@ fn checkout_files(
             })?;
 
             if current_content != *blob_data {
-                return Err(GitError::Gix(
-                    format!(
-                        "File '{}' has local changes. Use force=true to overwrite.",
-                        path.display()
-                    )
-                    .into(),
-                ));
+                return Err(GitError::InvalidInput(format!(
+                    "File '{}' has local changes. Use force=true to overwrite.",
+                    path.display()
+                )));
             }
         }
 
@@ -200,9 +216,7 @@ fn checkout_files(
 
     // Step 7: Write index to disk with proper locking and checksum
     use gix::index::write::Options;
-    index
-        .write(Options::default())
-        .map_err(|e| GitError::Gix(format!("Failed to write index: {e}").into()))?;
+    index.write(Options::default()).map_err(GitError::gix)?;
 
     Ok(())
 }
@@ -222,16 +236,45 @@ fn checkout_files(
 /// - Remote branches (e.g., "origin/main") → Detached HEAD
 /// - Tags (e.g., "v1.0", "refs/tags/v1.0") → Detached HEAD
 /// - Commit SHAs (e.g., "abc123") → Detached HEAD
+///
+/// Retries once after clearing stale lock files when lock recovery is
+/// enabled (see [`super::maintenance::set_lock_recovery`]). With
+/// `autostash`, local changes are carried over to the new checkout.
 pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
+    let autostash = opts.autostash && opts.paths.is_none();
+    super::autostash::with_autostash(repo.clone(), autostash, || {
+        super::maintenance::with_lock_recovery(repo, |repo| checkout_once(repo, opts.clone()))
+    })
+    .await
+}
+
+/// Single checkout attempt
+async fn checkout_once(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
-        let CheckoutOpts { reference, force, paths } = opts;
+        let CheckoutOpts {
+            reference,
+            force,
+            paths,
+            progress,
+            ..
+        } = opts;
+
+        // "-" switches back to the previous branch, like `git checkout -`
+        let reference = if reference == "-" || reference == "@{-1}" {
+            previous_checkout(&repo_clone)?.ok_or_else(|| {
+                GitError::InvalidInput("No previous branch to switch back to".to_string())
+            })?
+        } else {
+            reference
+        };
 
         // Branch on operation type: file checkout vs full checkout
         if let Some(file_paths) = paths {
             return checkout_files(&repo_clone, &reference, file_paths, force);
         }
+        let from = head_label(&repo_clone);
 
         // Step 1: Resolve reference to object ID (full checkout path)
         let parsed = repo_clone
@@ -251,9 +294,7 @@ pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
         // Step 2: Get commit object
         let commit = repo_clone
             .find_object(object_id)
-            .map_err(|e| {
-                GitError::InvalidInput(format!("Failed to find object for '{reference}': {e}"))
-            })?
+            .map_err(GitError::gix)?
             .try_into_commit()
             .map_err(|_| {
                 GitError::InvalidInput(format!(
@@ -262,19 +303,13 @@ pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
             })?;
 
         // Step 3: Extract tree ID from commit
-        let tree_id = commit.tree_id().map_err(|e| {
-            GitError::InvalidInput(format!(
-                "Failed to get tree from commit {object_id}: {e}"
-            ))
-        })?;
+        let tree_id = commit.tree_id().map_err(GitError::gix)?;
 
         // Step 3.5: Get current index to track which files to remove
         let old_index = repo_clone.open_index().ok();
 
         // Step 4: Create index from tree
-        let mut index = repo_clone.index_from_tree(&tree_id).map_err(|e| {
-            GitError::Gix(format!("Failed to create index from tree {tree_id}: {e}").into())
-        })?;
+        let mut index = repo_clone.index_from_tree(&tree_id).map_err(GitError::gix)?;
 
         // Step 5: Get worktree path (fail if bare repository)
         let worktree = repo_clone.worktree().ok_or_else(|| {
@@ -289,54 +324,29 @@ pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
             .checkout_options(
                 gix::worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
             )
-            .map_err(|e| {
-                GitError::Gix(format!("Failed to create checkout options: {e}").into())
-            })?;
+            .map_err(GitError::gix)?;
         checkout_opts.overwrite_existing = force;
         checkout_opts.destination_is_initially_empty = false;
 
         // Step 7: Perform the actual file checkout
-        let outcome = gix::worktree::state::checkout(
-            &mut index,
-            worktree_path.clone(),
-            repo_clone.objects.clone().into_arc().map_err(|e| {
-                GitError::Gix(format!("Failed to access object database: {e}").into())
-            })?,
-            &gix::progress::Discard,
-            &gix::progress::Discard,
-            &AtomicBool::new(false),
-            checkout_opts,
-        )
-        .map_err(|e| GitError::Gix(format!("Checkout operation failed: {e}").into()))?;
+        let objects = repo_clone.objects.clone().into_arc().map_err(GitError::gix)?;
+        let files_total = index.entries().len();
+        let outcome = with_checkout_progress(progress.as_ref(), files_total, |files, bytes| {
+            gix::worktree::state::checkout(
+                &mut index,
+                worktree_path.clone(),
+                objects,
+                files,
+                bytes,
+                &AtomicBool::new(false),
+                checkout_opts,
+            )
+            .map_err(GitError::gix)
+        })?;
 
         // Step 8: Handle errors and collisions
         if !outcome.errors.is_empty() {
-            // Collect error details for helpful error message  
-            let error_details: Vec<String> = outcome
-                .errors
-                .iter()
-                .take(10) // Show first 10 to avoid overwhelming output
-                .map(|err| {
-                    let path_str = std::str::from_utf8(err.path.as_ref()).map_or_else(|_| format!("{:?}", err.path), std::string::ToString::to_string);
-                    format!("{}: {}", path_str, err.error)
-                })
-                .collect();
-
-            let error_summary = if outcome.errors.len() > 10 {
-                format!(
-                    "Checkout failed with {} error(s). First 10:\n{}\nWorking directory may be in a partial state.",
-                    outcome.errors.len(),
-                    error_details.join("\n")
-                )
-            } else {
-                format!(
-                    "Checkout failed with {} error(s):\n{}\nWorking directory may be in a partial state.",
-                    outcome.errors.len(),
-                    error_details.join("\n")
-                )
-            };
-
-            return Err(GitError::Gix(error_summary.into()));
+            return Err(checkout_failed(outcome.errors));
         }
 
         if !outcome.collisions.is_empty() && !force {
@@ -356,14 +366,11 @@ pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
                 collision_paths.join(", ")
             };
 
-            return Err(GitError::Gix(
-                format!(
-                    "Checkout blocked: {} file collision(s) detected. Files: {}. Use force=true to overwrite.",
-                    outcome.collisions.len(),
-                    collision_summary
-                )
-                .into(),
-            ));
+            return Err(GitError::InvalidInput(format!(
+                "Checkout blocked: {} file collision(s) detected. Files: {}. Use force=true to overwrite.",
+                outcome.collisions.len(),
+                collision_summary
+            )));
         }
 
         // Step 8.5: Remove files that existed in old index but not in new index
@@ -400,25 +407,21 @@ pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
 
         // Step 9: Write updated index to disk with proper locking and checksum
         use gix::index::write::Options;
-        index.write(Options::default()).map_err(|e| {
-            GitError::Gix(
-                format!("Failed to write index: {e}").into(),
-            )
-        })?;
+        index.write(Options::default()).map_err(GitError::gix)?;
 
         // Step 10: Update HEAD (symbolic for local branches, direct otherwise)
         // Determine if this is a local branch that should use symbolic HEAD
         let is_local_branch = if reference.starts_with("refs/heads/") {
             // Already fully qualified - check if it exists
             repo_clone.try_find_reference(reference.as_bytes().as_bstr())
-                .map_err(|e| GitError::Gix(format!("Failed to check reference: {e}").into()))?
+                .map_err(GitError::gix)?
                 .is_some()
         } else if !reference.starts_with("refs/") && !reference.contains('/') {
             // Short name without path separator - might be local branch
             // Check refs/heads/{reference}
             let full_ref_name = format!("refs/heads/{reference}");
             repo_clone.try_find_reference(full_ref_name.as_bytes().as_bstr())
-                .map_err(|e| GitError::Gix(format!("Failed to check reference: {e}").into()))?
+                .map_err(GitError::gix)?
                 .is_some()
         } else {
             // Other patterns (origin/main, tags/v1.0, commit SHA) → detached HEAD
@@ -441,12 +444,14 @@ pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
                     ))
                 })?;
 
+            let previous_id = repo_clone.head_id().ok().map(gix::Id::detach);
+            let message = format!("checkout: moving from {from} to {reference}");
             repo_clone.edit_reference(RefEdit {
                 change: Change::Update {
                     log: LogChange {
                         mode: RefLog::AndReference,
                         force_create_reflog: false,
-                        message: format!("checkout: moving from HEAD to {reference}").into(),
+                        message: message.as_str().into(),
                     },
                     expected: PreviousValue::Any,
                     new: Target::Symbolic(sym_target),
@@ -456,24 +461,18 @@ pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
                 })?,
                 deref: false,
             })
-            .map_err(|e| {
-                GitError::Gix(
-                    format!("Failed to update HEAD to branch '{full_ref_name}': {e}").into(),
-                )
-            })?;
+            .map_err(GitError::gix)?;
+            // gix doesn't log symbolic target changes, but `@{-1}` reads them
+            log_head_update(&repo_clone, previous_id, object_id.detach(), &message)?;
         } else {
             // Direct HEAD update: HEAD → commit (detached HEAD)
             repo_clone.reference(
                 "HEAD",
                 object_id,
                 PreviousValue::Any,
-                format!("checkout: moving from HEAD to {reference}"),
+                format!("checkout: moving from {from} to {reference}"),
             )
-            .map_err(|e| {
-                GitError::Gix(
-                    format!("Failed to update HEAD to commit {object_id}: {e}").into(),
-                )
-            })?;
+            .map_err(GitError::gix)?;
         }
 
         Ok(())
@@ -481,3 +480,106 @@ pub async fn checkout(repo: RepoHandle, opts: CheckoutOpts) -> GitResult<()> {
     .await
     .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
 }
+
+/// Branch (or commit, if `HEAD` was detached) checked out before the
+/// current one, as `@{-1}` resolves it.
+///
+/// Read from the checkout entries of the `HEAD` reflog; `None` if nothing
+/// else was checked out yet.
+pub async fn previous_branch(repo: RepoHandle) -> GitResult<Option<String>> {
+    let repo_clone = repo.clone_inner();
+    tokio::task::spawn_blocking(move || previous_checkout(&repo_clone))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Source of the latest `checkout: moving from <from> to <to>` reflog entry
+pub(crate) fn previous_checkout(repo: &gix::Repository) -> GitResult<Option<String>> {
+    let head = repo.head().map_err(GitError::gix)?;
+    let mut log = head.log_iter();
+    let Some(lines) = log.rev().map_err(GitError::Io)? else {
+        return Ok(None);
+    };
+    for line in lines {
+        let line = line.map_err(GitError::gix)?;
+        if let Some(from_to) = line.message.strip_prefix(b"checkout: moving from ")
+            && let Some(pos) = from_to.find(" to ")
+        {
+            return Ok(Some(from_to[..pos].to_str_lossy().into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// [`GitError::CheckoutFailed`] for the files a checkout could not write,
+/// with the error for the first of them
+pub(crate) fn checkout_failed(
+    errors: Vec<gix::worktree::state::checkout::ErrorRecord>,
+) -> GitError {
+    let count = errors.len();
+    let Some(first) = errors.into_iter().next() else {
+        return GitError::InvalidInput("Checkout failed without an error".to_string());
+    };
+    GitError::CheckoutFailed {
+        path: first.path.to_str_lossy().into_owned(),
+        count,
+        source: Box::new(GitError::gix(first.error)),
+    }
+}
+
+/// Append a `HEAD` reflog entry moving from `previous` to `new`, like gix
+/// writes for direct updates: only with a committer identity and unless
+/// `core.logAllRefUpdates` is off.
+fn log_head_update(
+    repo: &gix::Repository,
+    previous: Option<gix::ObjectId>,
+    new: gix::ObjectId,
+    message: &str,
+) -> GitResult<()> {
+    use std::io::Write;
+
+    if repo
+        .config_snapshot()
+        .boolean("core.logAllRefUpdates")
+        .is_some_and(|enabled| !enabled)
+    {
+        return Ok(());
+    }
+    let Some(committer) = repo.committer() else {
+        return Ok(());
+    };
+    let mut signature = committer
+        .map_err(GitError::gix)?
+        .to_owned()
+        .map_err(GitError::gix)?;
+    signature.time = gix::date::Time::now_local_or_utc();
+
+    let line = gix::refs::log::Line {
+        previous_oid: previous.unwrap_or_else(|| gix::ObjectId::null(repo.object_hash())),
+        new_oid: new,
+        signature,
+        message: message.into(),
+    };
+    let path = repo.git_dir().join("logs").join("HEAD");
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    line.write_to(&mut file)?;
+    file.flush()?;
+    Ok(())
+}
+
+/// What `HEAD` points at, for reflog messages: the short branch name, or the
+/// commit id when detached
+pub(crate) fn head_label(repo: &gix::Repository) -> String {
+    match repo.head_name() {
+        Ok(Some(name)) => name.shorten().to_str_lossy().into_owned(),
+        _ => repo
+            .head_id()
+            .map_or_else(|_| "HEAD".to_string(), |id| id.to_string()),
+    }
+}
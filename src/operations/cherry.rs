@@ -0,0 +1,90 @@
+//! Cherry detection (`git cherry`): which commits of a branch are upstream.
+//!
+//! A commit counts as upstream when a commit with the same patch id exists
+//! on the upstream side, so commits that were cherry-picked or rebased into
+//! upstream are recognized even though their ids differ.
+
+use std::collections::HashSet;
+
+use super::compare::resolve_commit;
+use super::patch_id::commit_patch_id;
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// A commit of `head` checked against `upstream`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CherryCommit {
+    pub id: CommitId,
+    pub summary: String,
+    /// An equivalent change exists in upstream (`-` in `git cherry`).
+    pub in_upstream: bool,
+}
+
+/// Commits in `head` that are not in `upstream`, oldest first, each marked
+/// with whether an equivalent change was applied upstream.
+///
+/// Merge commits are skipped, as `git cherry` does.
+pub async fn cherry(repo: RepoHandle, upstream: &str, head: &str) -> GitResult<Vec<CherryCommit>> {
+    let repo_inner = repo.clone_for_traversal();
+    let upstream = upstream.to_string();
+    let head = head.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let upstream_id = resolve_commit(&repo_inner, &upstream)?;
+        let head_id = resolve_commit(&repo_inner, &head)?;
+
+        // Patch ids of the changes upstream made since the branches diverged
+        let mut upstream_patches = HashSet::new();
+        for id in side_commits(&repo_inner, upstream_id, head_id)? {
+            if let Some(patch_id) = commit_patch_id(&repo_inner, id)? {
+                upstream_patches.insert(patch_id);
+            }
+        }
+
+        let mut commits = Vec::new();
+        for id in side_commits(&repo_inner, head_id, upstream_id)? {
+            if is_merge(&repo_inner, id)? {
+                continue;
+            }
+            // Empty commits have no patch id and never match
+            let in_upstream = commit_patch_id(&repo_inner, id)?
+                .is_some_and(|patch_id| upstream_patches.contains(&patch_id));
+            commits.push(cherry_commit(&repo_inner, id, in_upstream)?);
+        }
+        commits.reverse();
+        Ok(commits)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Commits reachable from `tip` but not from `other`, newest first
+fn side_commits(
+    repo: &gix::Repository,
+    tip: CommitId,
+    other: CommitId,
+) -> GitResult<Vec<CommitId>> {
+    repo.rev_walk([tip])
+        .with_hidden([other])
+        .all()
+        .map_err(GitError::gix)?
+        .map(|info| info.map(|info| info.id).map_err(GitError::gix))
+        .collect()
+}
+
+fn is_merge(repo: &gix::Repository, id: CommitId) -> GitResult<bool> {
+    let commit = repo.find_commit(id).map_err(GitError::gix)?;
+    Ok(commit.parent_ids().count() > 1)
+}
+
+fn cherry_commit(
+    repo: &gix::Repository,
+    id: CommitId,
+    in_upstream: bool,
+) -> GitResult<CherryCommit> {
+    let commit = repo.find_commit(id).map_err(GitError::gix)?;
+    Ok(CherryCommit {
+        id,
+        summary: super::encoding::commit_summary(&commit),
+        in_upstream,
+    })
+}
@@ -0,0 +1,232 @@
+//! Cherry-picking a commit onto `HEAD`.
+//!
+//! [`cherry_pick`] applies the change a commit made relative to its parent
+//! to `HEAD`, as `git cherry-pick` does. The pick is a three-way merge of
+//! trees done by gix; the new commit keeps the original author and message.
+//! Conflicts leave the repository untouched and are reported as
+//! [`GitError::MergeConflict`] with the conflicting paths, rather than as
+//! conflict markers in the working tree.
+
+use gix::bstr::ByteSlice;
+use gix::merge::blob::builtin_driver::text::Labels;
+use gix::merge::tree::TreatAsUnresolved;
+use gix::refs::Target;
+use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+use super::compare::resolve_commit;
+use super::merge::{sync_worktree_to_tree, unresolved_conflict_paths};
+use super::update_branch::{committer, tree_of};
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Options for [`cherry_pick`] with builder pattern.
+#[derive(Debug, Clone)]
+pub struct CherryPickOpts {
+    /// Commit to pick (any revision).
+    pub commit: String,
+    /// Stage the change in the index and working tree without committing.
+    pub no_commit: bool,
+    /// For a merge commit, the 1-based parent the change is taken relative to.
+    pub mainline: Option<u32>,
+    /// Append `(cherry picked from commit <id>)` to the message, like `git cherry-pick -x`.
+    pub record_origin: bool,
+}
+
+impl CherryPickOpts {
+    /// Pick `commit` and commit it.
+    #[inline]
+    #[must_use]
+    pub fn new(commit: impl Into<String>) -> Self {
+        Self {
+            commit: commit.into(),
+            no_commit: false,
+            mainline: None,
+            record_origin: false,
+        }
+    }
+
+    /// Stage the change without committing it.
+    #[inline]
+    #[must_use]
+    pub fn no_commit(mut self, yes: bool) -> Self {
+        self.no_commit = yes;
+        self
+    }
+
+    /// Pick a merge commit relative to its `parent`th parent (1-based).
+    #[inline]
+    #[must_use]
+    pub fn mainline(mut self, parent: u32) -> Self {
+        self.mainline = Some(parent);
+        self
+    }
+
+    /// Whether to append `(cherry picked from commit <id>)` to the message.
+    #[inline]
+    #[must_use]
+    pub fn record_origin(mut self, yes: bool) -> Self {
+        self.record_origin = yes;
+        self
+    }
+}
+
+/// Result of [`cherry_pick`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CherryPickResult {
+    /// The picked commit.
+    pub source: CommitId,
+    /// The new commit on `HEAD`, `None` with [`CherryPickOpts::no_commit`].
+    pub commit_id: Option<CommitId>,
+    /// `HEAD` before the pick.
+    pub previous_head: CommitId,
+    /// Tree with the change applied, committed or staged.
+    pub tree: gix::ObjectId,
+}
+
+/// Apply the change `opts.commit` introduced on top of `HEAD`.
+///
+/// Merge commits need [`CherryPickOpts::mainline`] to say which parent the
+/// change is relative to; other commits refuse it. The working tree must be
+/// clean. Fails with [`GitError::MergeConflict`], listing the conflicting
+/// paths, if the change doesn't apply, and with [`GitError::InvalidInput`]
+/// if it is already in `HEAD`; nothing is modified in either case. On
+/// success the index and working tree match the new tree.
+pub async fn cherry_pick(repo: RepoHandle, opts: CherryPickOpts) -> GitResult<CherryPickResult> {
+    let repo_inner = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || {
+        let previous_head = repo_inner
+            .head_id()
+            .map_err(|_| GitError::InvalidInput("Cannot cherry-pick without a commit".to_string()))?
+            .detach();
+        if !repo_inner.is_bare() && super::fast_status::is_dirty_fast(&repo_inner)? {
+            return Err(GitError::InvalidInput(
+                "Cannot cherry-pick with local changes".to_string(),
+            ));
+        }
+
+        let source = resolve_commit(&repo_inner, &opts.commit)?;
+        let original = repo_inner
+            .find_commit(source)
+            .map_err(GitError::gix)?
+            .decode()
+            .map_err(GitError::gix)?
+            .into_owned();
+        let short = source.to_hex_with_len(7).to_string();
+        let base_tree = match (original.parents.len(), opts.mainline) {
+            (0, None) => gix::ObjectId::empty_tree(repo_inner.object_hash()),
+            (1, None) => tree_of(&repo_inner, original.parents[0])?,
+            (0 | 1, Some(_)) => {
+                return Err(GitError::InvalidInput(format!(
+                    "Mainline was given but {short} is not a merge"
+                )));
+            }
+            (parents, None) => {
+                return Err(GitError::InvalidInput(format!(
+                    "{short} is a merge of {parents} parents; give the mainline parent"
+                )));
+            }
+            (parents, Some(mainline)) => {
+                let parent = usize::try_from(mainline)
+                    .ok()
+                    .and_then(|mainline| mainline.checked_sub(1))
+                    .and_then(|index| original.parents.get(index))
+                    .ok_or_else(|| {
+                        GitError::InvalidInput(format!(
+                            "Mainline {mainline} is out of range; {short} has {parents} parents"
+                        ))
+                    })?;
+                tree_of(&repo_inner, *parent)?
+            }
+        };
+        let head_tree = tree_of(&repo_inner, previous_head)?;
+
+        let ancestor = format!("parent of {short}");
+        let labels = Labels {
+            ancestor: Some(ancestor.as_str().into()),
+            current: Some("HEAD".into()),
+            other: Some(short.as_str().into()),
+        };
+        let options = repo_inner.tree_merge_options().map_err(GitError::gix)?;
+        let mut outcome = repo_inner
+            .merge_trees(base_tree, head_tree, original.tree, labels, options)
+            .map_err(GitError::gix)?;
+        if outcome.has_unresolved_conflicts(TreatAsUnresolved::default()) {
+            let paths = unresolved_conflict_paths(&outcome);
+            return Err(GitError::MergeConflict {
+                message: format!("Cherry-picking {short} conflicts in: {}", paths.join(", ")),
+                paths,
+            });
+        }
+        let tree = outcome.tree.write().map_err(GitError::gix)?.detach();
+        if tree == head_tree {
+            return Err(GitError::InvalidInput(format!(
+                "The change of {short} is already in HEAD"
+            )));
+        }
+
+        let picked = if opts.no_commit {
+            None
+        } else {
+            let mut picked = original.clone();
+            picked.tree = tree;
+            picked.parents = vec![previous_head].into();
+            picked.committer = committer(&repo_inner)?;
+            picked
+                .extra_headers
+                .retain(|(name, _)| name != "gpgsig" && name != "gpgsig-sha256");
+            if opts.record_origin {
+                picked.message = format!(
+                    "{}\n\n(cherry picked from commit {source})\n",
+                    original.message.to_str_lossy().trim_end()
+                )
+                .into();
+            }
+            Some(picked)
+        };
+
+        // Update the worktree first, so a refused checkout leaves HEAD alone
+        if let Some(workdir) = repo_inner.workdir() {
+            sync_worktree_to_tree(&repo_inner, workdir, tree, "Cherry-pick")?;
+        }
+        let commit_id = picked
+            .map(|picked| commit_to_head(&repo_inner, &picked, previous_head))
+            .transpose()?;
+
+        Ok(CherryPickResult {
+            source,
+            commit_id,
+            previous_head,
+            tree,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Write `commit` and move `HEAD`, or the branch it points to, from `head`
+/// onto it
+fn commit_to_head(
+    repo: &gix::Repository,
+    commit: &gix::objs::Commit,
+    head: CommitId,
+) -> GitResult<CommitId> {
+    let id = repo.write_object(commit).map_err(GitError::gix)?.detach();
+    let subject = commit.message.lines().next().unwrap_or_default();
+    let edit = RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: format!("cherry-pick: {}", subject.to_str_lossy()).into(),
+            },
+            expected: PreviousValue::MustExistAndMatch(Target::Object(head)),
+            new: Target::Object(id),
+        },
+        name: "HEAD".try_into().map_err(GitError::gix)?,
+        deref: true,
+    };
+    let mut time_buf = gix::date::parse::TimeBuf::default();
+    repo.edit_references_as(Some(edit), Some(commit.committer.to_ref(&mut time_buf)))
+        .map_err(GitError::gix)?;
+    Ok(id)
+}
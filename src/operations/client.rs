@@ -27,7 +27,10 @@ use std::path::Path;
 use crate::runtime::{AsyncStream, AsyncTask};
 use crate::{CommitId, CommitInfo, GitResult, MergeOutcome, RepoHandle};
 
-use super::{AddOpts, BranchOpts, CheckoutOpts, CloneOpts, CommitOpts, FetchOpts, LogOpts, MergeOpts};
+use super::{
+    AddOpts, AddResult, BranchOpts, CheckoutOpts, CloneOpts, CommitOpts, FetchOpts, LogOpts,
+    MergeOpts,
+};
 
 /// Git client for local repository operations.
 ///
@@ -102,7 +105,7 @@ impl GitClient {
     // ========================================================================
 
     /// Stage files for commit
-    pub fn add(&self, opts: AddOpts) -> AsyncTask<GitResult<()>> {
+    pub fn add(&self, opts: AddOpts) -> AsyncTask<GitResult<AddResult>> {
         crate::git::add::add(self.repo.clone(), opts)
     }
 
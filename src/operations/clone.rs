@@ -4,15 +4,19 @@
 //! implementation for the `GitGix` service.
 
 use std::num::NonZeroU32;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
 
 use gix::progress::Discard;
 use gix::remote;
 
-use super::auth;
+use super::clone_preflight::{ClonePreflight, preflight};
+use super::fetch_verify::{FetchVerification, verify_received};
+use super::remote_head::{head_branch, record_remote_head};
+use super::{auth, credentials};
 use crate::runtime::AsyncTask;
-use crate::{GitError, GitResult, RepoHandle};
+use crate::{CommitId, GitError, GitResult, RepoHandle};
 
 /// Shared cancellation token for operations that don't need interruption.
 static NEVER_INTERRUPT: AtomicBool = AtomicBool::new(false);
@@ -25,6 +29,13 @@ pub struct CloneOpts {
     pub shallow: Option<u32>,
     pub branch: Option<String>,
     pub bare: bool,
+    /// Check the received data before checking out, see [`super::fetch_verify`].
+    pub verify: bool,
+    /// Allow the destination to be inside another repository.
+    pub nested: bool,
+    /// Bytes the clone is expected to need, for the free space check of
+    /// [`super::clone_preflight`].
+    pub expected_size: Option<u64>,
 }
 
 impl CloneOpts {
@@ -37,6 +48,9 @@ impl CloneOpts {
             shallow: None,
             branch: None,
             bare: false,
+            verify: false,
+            nested: false,
+            expected_size: None,
         }
     }
 
@@ -62,38 +76,93 @@ impl CloneOpts {
         self.bare = yes;
         self
     }
+
+    /// Verify the received pack and objects, removing the clone again if any
+    /// are missing or corrupt.
+    #[inline]
+    #[must_use]
+    pub fn verify(mut self, yes: bool) -> Self {
+        self.verify = yes;
+        self
+    }
+
+    /// Allow cloning into a directory inside another repository's worktree,
+    /// e.g. for a vendored checkout that the outer repository ignores.
+    #[inline]
+    #[must_use]
+    pub fn nested(mut self, yes: bool) -> Self {
+        self.nested = yes;
+        self
+    }
+
+    /// Set the bytes the clone is expected to need, which remotes don't
+    /// advertise, so the free space is checked before fetching.
+    #[inline]
+    #[must_use]
+    pub fn expected_size(mut self, bytes: u64) -> Self {
+        self.expected_size = Some(bytes);
+        self
+    }
+}
+
+/// Result of a clone operation.
+#[derive(Debug, Clone)]
+pub struct CloneResult {
+    pub repo: RepoHandle,
+    /// Commit checked out (or pointed to by `HEAD` in a bare clone), `None` for an empty remote.
+    pub head: Option<CommitId>,
+    /// Branch `HEAD` points to, without `refs/heads/`.
+    pub default_branch: Option<String>,
+    /// Remote references mapped by the fetch.
+    pub refs_fetched: usize,
+    /// Objects in the received pack.
+    pub objects_received: u32,
+    /// Whether the `HEAD` tree has a `.gitmodules` file.
+    pub has_submodules: bool,
+    /// Time taken by the fetch and checkout.
+    pub duration: Duration,
+    /// Bytes used by the destination directory, including the working tree.
+    pub size_on_disk: u64,
+    /// What verification found, if it was asked for.
+    pub verification: Option<FetchVerification>,
+    /// What the checks before the clone found.
+    pub preflight: ClonePreflight,
 }
 
 /// Execute clone operation with the given options.
+///
+/// The destination is checked first, see [`super::clone_preflight`]; if it
+/// fails the checks, [`GitError::PreflightFailed`] is returned before
+/// connecting. With a registered [token source](credentials::TokenSource), a
+/// clone rejected for authentication is retried once with a freshly minted
+/// token.
 #[must_use]
-pub fn clone_repo(opts: CloneOpts) -> AsyncTask<GitResult<RepoHandle>> {
-    AsyncTask::spawn(move || {
+pub fn clone_repo(opts: CloneOpts) -> AsyncTask<GitResult<CloneResult>> {
+    AsyncTask::spawn_async(async move {
+        let url = auth::rewrite_url(&opts.url, remote::Direction::Fetch)
+            .unwrap_or_else(|| opts.url.clone());
+        credentials::with_token(Some(url), |_| clone_once(opts.clone())).await
+    })
+}
+
+/// Single clone attempt
+async fn clone_once(opts: CloneOpts) -> GitResult<CloneResult> {
+    tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        let preflight = preflight(&opts);
+        if !preflight.is_ok() {
+            return Err(GitError::PreflightFailed(Box::new(preflight)));
+        }
         let CloneOpts {
             url,
             destination,
             shallow,
             branch,
             bare,
+            verify,
+            ..
         } = opts;
 
-        // Validate parent directory exists (cheap syscall before expensive operations)
-        if let Some(parent) = destination.parent()
-            && !parent.exists()
-        {
-            return Err(GitError::InvalidInput(format!(
-                "Parent directory does not exist: {}",
-                parent.display()
-            )));
-        }
-
-        // Check if destination already exists
-        if destination.exists() {
-            return Err(GitError::InvalidInput(format!(
-                "Destination path already exists: {}",
-                destination.display()
-            )));
-        }
-
         // Parse URL
         let parsed_url = gix::url::parse(url.as_str().into())
             .map_err(|e| GitError::InvalidInput(format!("Invalid URL '{url}': {e}")))?;
@@ -114,28 +183,29 @@ pub fn clone_repo(opts: CloneOpts) -> AsyncTask<GitResult<RepoHandle>> {
         if let Some(branch_name) = branch.as_deref() {
             prepare = prepare
                 .with_ref_name(Some(branch_name))
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
+                .map_err(GitError::gix)?;
         }
 
         // Execute fetch with appropriate method based on bare flag
-        let repo = if bare {
+        let (repo, outcome, verification) = if bare {
             // Bare clone: fetch only, no working tree
-            let (repo, _outcome) = prepare
-                .fetch_only(Discard, &NEVER_INTERRUPT)
-                .map_err(|e| {
-                    let err_str = e.to_string();
-                    if err_str.to_lowercase().contains("authentication")
-                        || err_str.contains("Permission denied")
-                    {
-                        GitError::InvalidInput(auth::auth_error_message(&url))
-                    } else {
-                        GitError::Gix(Box::new(e))
-                    }
-                })?;
-            repo
+            let (repo, outcome) = prepare.fetch_only(Discard, &NEVER_INTERRUPT).map_err(|e| {
+                let err_str = e.to_string();
+                if err_str.to_lowercase().contains("authentication")
+                    || err_str.contains("Permission denied")
+                {
+                    GitError::InvalidInput(auth::auth_error_message(&url))
+                } else {
+                    GitError::gix(e)
+                }
+            })?;
+            let verification = verify_clone(verify, &repo, &outcome).inspect_err(|_| {
+                let _ = std::fs::remove_dir_all(&destination);
+            })?;
+            (repo, outcome, verification)
         } else {
             // Full clone: fetch and checkout working tree
-            let (mut prepare_checkout, _outcome) = prepare
+            let (mut prepare_checkout, outcome) = prepare
                 .fetch_then_checkout(Discard, &NEVER_INTERRUPT)
                 .map_err(|e| {
                     let err_str = e.to_string();
@@ -144,16 +214,86 @@ pub fn clone_repo(opts: CloneOpts) -> AsyncTask<GitResult<RepoHandle>> {
                     {
                         GitError::InvalidInput(auth::auth_error_message(&url))
                     } else {
-                        GitError::Gix(Box::new(e))
+                        GitError::gix(e)
                     }
                 })?;
 
+            // Dropping the unfinished checkout on failure removes the clone
+            let verification = verify_clone(verify, prepare_checkout.repo(), &outcome)?;
+
             let (repo, _outcome) = prepare_checkout
                 .main_worktree(Discard, &NEVER_INTERRUPT)
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-            repo
+                .map_err(GitError::gix)?;
+            (repo, outcome, verification)
+        };
+        let duration = started.elapsed();
+
+        let objects_received = match &outcome.status {
+            remote::fetch::Status::Change {
+                write_pack_bundle, ..
+            } => write_pack_bundle.index.num_objects,
+            remote::fetch::Status::NoPackReceived { .. } => 0,
         };
+        let head = repo.head_id().ok().map(gix::Id::detach);
+        // Record the remote's default branch as `origin/HEAD`, like git does
+        if let Some(remote_head) = head_branch(&outcome.ref_map.remote_refs) {
+            record_remote_head(&repo, "origin", &remote_head)?;
+        }
+        let default_branch = repo
+            .head_name()
+            .ok()
+            .flatten()
+            .map(|name| name.shorten().to_string());
+        let has_submodules = repo
+            .head_tree()
+            .ok()
+            .is_some_and(|tree| tree.find_entry(".gitmodules").is_some());
 
-        Ok(RepoHandle::new(repo))
+        Ok(CloneResult {
+            head,
+            default_branch,
+            refs_fetched: outcome.ref_map.mappings.len(),
+            objects_received,
+            has_submodules,
+            duration,
+            size_on_disk: dir_size(&destination),
+            verification,
+            preflight,
+            repo: RepoHandle::new(repo),
+        })
     })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Verify a fresh clone if asked to, failing with the report on damage
+fn verify_clone(
+    verify: bool,
+    repo: &gix::Repository,
+    outcome: &remote::fetch::Outcome,
+) -> GitResult<Option<FetchVerification>> {
+    if !verify {
+        return Ok(None);
+    }
+    let report = verify_received(repo, outcome);
+    if report.is_ok() {
+        Ok(Some(report))
+    } else {
+        Err(GitError::VerificationFailed(Box::new(report)))
+    }
+}
+
+/// Total size of the files below `dir`, not following symlinks
+pub(crate) fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map_or(0, |meta| meta.len()),
+            _ => 0,
+        })
+        .sum()
 }
@@ -0,0 +1,233 @@
+//! Checks run before a clone touches the network.
+//!
+//! A clone that fails half way, on a read-only parent or a full disk, wastes
+//! the transfer and leaves a partial directory behind. [`clone_preflight`]
+//! checks the destination first: its parent exists and is writable, it
+//! doesn't exist itself, it isn't inside another repository unless
+//! [`CloneOpts::nested`](super::clone::CloneOpts::nested) allows it, and the
+//! clone fits in the free space. [`clone_repo`](super::clone::clone_repo) runs
+//! the same checks and fails with
+//! [`GitError::PreflightFailed`](crate::GitError::PreflightFailed) before
+//! connecting.
+//!
+//! Remotes don't advertise the size of the pack they would send. The size is
+//! estimated for local remotes from their object database and `HEAD` tree;
+//! for others it is [`CloneOpts::expected_size`](super::clone::CloneOpts::expected_size)
+//! if the caller knows it, e.g. from a forge API, and the space check is
+//! skipped otherwise.
+
+use std::path::{Path, PathBuf};
+
+use gix::bstr::ByteSlice;
+
+use crate::runtime::AsyncTask;
+use crate::{CloneOpts, GitResult};
+
+/// What the checks before a clone found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClonePreflight {
+    /// The clone's destination.
+    pub destination: PathBuf,
+    /// Estimated bytes the clone needs, if known.
+    pub estimated_size: Option<u64>,
+    /// Free bytes on the destination's file system, if it could be read.
+    pub available_space: Option<u64>,
+    /// Root of the repository the destination is inside, if any.
+    pub enclosing_repo: Option<PathBuf>,
+    /// Problems that stop the clone; empty if it can go ahead.
+    pub issues: Vec<PreflightIssue>,
+}
+
+impl ClonePreflight {
+    /// Whether the clone can go ahead.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl std::fmt::Display for ClonePreflight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.issues.is_empty() {
+            return write!(f, "{} is ready for a clone", self.destination.display());
+        }
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A problem that stops a clone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreflightIssue {
+    /// The URL can't be parsed.
+    InvalidUrl(String),
+    /// The destination's parent directory doesn't exist.
+    ParentMissing(PathBuf),
+    /// Files can't be created in the destination's parent directory.
+    ParentNotWritable {
+        /// The parent directory.
+        path: PathBuf,
+        /// Why creating a file failed.
+        reason: String,
+    },
+    /// The destination already exists.
+    DestinationExists(PathBuf),
+    /// The destination is inside the repository rooted at this path.
+    InsideRepository(PathBuf),
+    /// The clone needs more space than is free.
+    InsufficientSpace {
+        /// Estimated bytes the clone needs.
+        needed: u64,
+        /// Free bytes on the destination's file system.
+        available: u64,
+    },
+}
+
+impl std::fmt::Display for PreflightIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidUrl(reason) => write!(f, "invalid URL: {reason}"),
+            Self::ParentMissing(path) => {
+                write!(f, "parent directory does not exist: {}", path.display())
+            }
+            Self::ParentNotWritable { path, reason } => {
+                write!(
+                    f,
+                    "parent directory {} is not writable: {reason}",
+                    path.display()
+                )
+            }
+            Self::DestinationExists(path) => {
+                write!(f, "destination path already exists: {}", path.display())
+            }
+            Self::InsideRepository(root) => write!(
+                f,
+                "destination is inside the repository at {}; allow nested clones to proceed",
+                root.display()
+            ),
+            Self::InsufficientSpace { needed, available } => write!(
+                f,
+                "clone needs about {} MiB but only {} MiB are free",
+                needed.div_ceil(1024 * 1024),
+                available / (1024 * 1024)
+            ),
+        }
+    }
+}
+
+/// Check whether a clone with `opts` can go ahead, without contacting the
+/// remote or creating anything.
+#[must_use]
+pub fn clone_preflight(opts: CloneOpts) -> AsyncTask<GitResult<ClonePreflight>> {
+    AsyncTask::spawn(move || Ok(preflight(&opts)))
+}
+
+/// Run the checks for `opts`
+pub(crate) fn preflight(opts: &CloneOpts) -> ClonePreflight {
+    let destination = opts.destination.clone();
+    let mut report = ClonePreflight {
+        destination: destination.clone(),
+        estimated_size: opts.expected_size,
+        available_space: None,
+        enclosing_repo: None,
+        issues: Vec::new(),
+    };
+
+    let url = match gix::url::parse(opts.url.as_str().into()) {
+        Ok(url) => Some(url),
+        Err(e) => {
+            report
+                .issues
+                .push(PreflightIssue::InvalidUrl(e.to_string()));
+            None
+        }
+    };
+
+    if destination.exists() {
+        report
+            .issues
+            .push(PreflightIssue::DestinationExists(destination.clone()));
+    }
+    let parent = match destination.parent() {
+        Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+        Some(parent) => parent,
+        None => return report,
+    };
+    if !parent.is_dir() {
+        report
+            .issues
+            .push(PreflightIssue::ParentMissing(parent.to_path_buf()));
+        return report;
+    }
+    if let Err(e) = probe_writable(parent) {
+        report.issues.push(PreflightIssue::ParentNotWritable {
+            path: parent.to_path_buf(),
+            reason: e.to_string(),
+        });
+    }
+
+    report.enclosing_repo = gix::discover(parent).ok().map(|repo| {
+        repo.workdir()
+            .unwrap_or_else(|| repo.git_dir())
+            .to_path_buf()
+    });
+    if let Some(root) = &report.enclosing_repo
+        && !opts.nested
+    {
+        report
+            .issues
+            .push(PreflightIssue::InsideRepository(root.clone()));
+    }
+
+    if report.estimated_size.is_none() {
+        report.estimated_size = url.and_then(|url| estimate_local(&url, opts.bare));
+    }
+    report.available_space = fs4::available_space(parent).ok();
+    if let (Some(needed), Some(available)) = (report.estimated_size, report.available_space)
+        && needed > available
+    {
+        report
+            .issues
+            .push(PreflightIssue::InsufficientSpace { needed, available });
+    }
+    report
+}
+
+/// Create and remove a file in `dir`, as permission bits don't tell whether
+/// that works (ACLs, read-only mounts)
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".clone-preflight-{}", std::process::id()));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)?;
+    std::fs::remove_file(&probe)
+}
+
+/// Size of a clone of a local remote: its object database, plus the blobs of
+/// its `HEAD` tree for a checkout
+fn estimate_local(url: &gix::Url, bare: bool) -> Option<u64> {
+    if url.scheme != gix::url::Scheme::File {
+        return None;
+    }
+    let repo = gix::open(gix::path::from_bstr(url.path.as_bstr()).into_owned()).ok()?;
+    let mut size = super::clone::dir_size(&repo.common_dir().join("objects"));
+    if !bare && let Ok(tree) = repo.head_tree() {
+        let mut recorder = gix::traverse::tree::Recorder::default();
+        if tree.traverse().breadthfirst(&mut recorder).is_ok() {
+            size += recorder
+                .records
+                .iter()
+                .filter(|entry| entry.mode.is_blob())
+                .filter_map(|entry| repo.find_header(entry.oid).ok())
+                .map(|header| header.size())
+                .sum::<u64>();
+        }
+    }
+    Some(size)
+}
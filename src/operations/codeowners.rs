@@ -0,0 +1,225 @@
+//! CODEOWNERS parsing and matching
+//!
+//! Follows GitHub's rules: the file is looked up in `.github/`, the repository
+//! root and `docs/`, in that order, and the last rule matching a path wins.
+//! Patterns use gitignore syntax, except that negation (`!`) and character
+//! ranges (`[ ]`) are not supported; lines using them, or listing malformed
+//! owners, are skipped and reported as issues.
+
+use gix::bstr::{BStr, ByteSlice};
+
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Locations searched for a CODEOWNERS file, in GitHub's precedence order
+pub const CODEOWNERS_LOCATIONS: &[&str] =
+    &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// A parsed CODEOWNERS rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeownersRule {
+    /// 1-based line number in the CODEOWNERS file
+    pub line: usize,
+    /// Pattern as written
+    pub pattern: String,
+    /// Owners (`@user`, `@org/team` or email); empty means explicitly unowned
+    pub owners: Vec<String>,
+    glob: gix::glob::Pattern,
+}
+
+/// A CODEOWNERS line that was skipped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeownersIssue {
+    /// 1-based line number in the CODEOWNERS file
+    pub line: usize,
+    /// Why the line was skipped
+    pub message: String,
+}
+
+/// A parsed CODEOWNERS file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Codeowners {
+    /// Repository-relative location the file was read from
+    pub source: String,
+    /// Valid rules, in file order
+    pub rules: Vec<CodeownersRule>,
+    /// Lines that were skipped
+    pub issues: Vec<CodeownersIssue>,
+}
+
+impl Codeowners {
+    /// Parse CODEOWNERS `content` read from `source`
+    #[must_use]
+    pub fn parse(source: impl Into<String>, content: &str) -> Self {
+        let mut rules = Vec::new();
+        let mut issues = Vec::new();
+
+        for (index, raw) in content.lines().enumerate() {
+            let line = index + 1;
+            match parse_line(line, raw) {
+                Ok(Some(rule)) => rules.push(rule),
+                Ok(None) => {}
+                Err(message) => issues.push(CodeownersIssue { line, message }),
+            }
+        }
+
+        Self {
+            source: source.into(),
+            rules,
+            issues,
+        }
+    }
+
+    /// The rule deciding the owners of `path` (the last matching one)
+    #[must_use]
+    pub fn rule_for(&self, path: &str) -> Option<&CodeownersRule> {
+        let path = path.trim_start_matches("./").trim_matches('/');
+        self.rules.iter().rev().find(|rule| rule.matches(path))
+    }
+
+    /// Owners of a repository-relative `path` (empty when unowned)
+    #[must_use]
+    pub fn owners_for(&self, path: &str) -> &[String] {
+        self.rule_for(path).map_or(&[], |rule| &rule.owners)
+    }
+}
+
+impl CodeownersRule {
+    /// Whether this rule applies to `path`
+    ///
+    /// A rule matching a directory owns everything below it, except that a
+    /// trailing `/*` only covers the directory's direct children.
+    #[must_use]
+    pub fn matches(&self, path: &str) -> bool {
+        path_matches(&self.glob, &self.pattern, path)
+    }
+}
+
+/// Whether `glob` (written as `pattern`) covers `path` or a directory above it
+pub(crate) fn path_matches(glob: &gix::glob::Pattern, pattern: &str, path: &str) -> bool {
+    let path = path.as_bytes().as_bstr();
+    if glob_matches(glob, path, false) {
+        return true;
+    }
+    if pattern.ends_with("/*") {
+        return false;
+    }
+
+    let mut end = path.len();
+    while let Some(pos) = path[..end].rfind_byte(b'/') {
+        if glob_matches(glob, path[..pos].as_bstr(), true) {
+            return true;
+        }
+        end = pos;
+    }
+    false
+}
+
+fn glob_matches(glob: &gix::glob::Pattern, path: &BStr, is_dir: bool) -> bool {
+    glob.matches_repo_relative_path(
+        path,
+        path.rfind_byte(b'/').map(|pos| pos + 1),
+        Some(is_dir),
+        gix::glob::pattern::Case::Sensitive,
+        gix::glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+    )
+}
+
+/// Parse one line; `Ok(None)` for blank lines and comments
+fn parse_line(line: usize, raw: &str) -> Result<Option<CodeownersRule>, String> {
+    let text = raw.trim();
+    if text.is_empty() || text.starts_with('#') {
+        return Ok(None);
+    }
+
+    let mut fields = text.split_whitespace();
+    let Some(pattern) = fields.next() else {
+        return Ok(None);
+    };
+    if pattern.starts_with('!') {
+        return Err(format!("negated pattern '{pattern}' is not supported"));
+    }
+    if pattern.contains('[') {
+        return Err(format!("character range in '{pattern}' is not supported"));
+    }
+    let glob = gix::glob::Pattern::from_bytes(pattern.as_bytes())
+        .ok_or_else(|| format!("invalid pattern '{pattern}'"))?;
+
+    let mut owners = Vec::new();
+    for owner in fields {
+        // Inline comment
+        if owner.starts_with('#') {
+            break;
+        }
+        if !is_valid_owner(owner) {
+            return Err(format!("invalid owner '{owner}'"));
+        }
+        owners.push(owner.to_string());
+    }
+
+    Ok(Some(CodeownersRule {
+        line,
+        pattern: pattern.to_string(),
+        owners,
+        glob,
+    }))
+}
+
+/// `@user`, `@org/team` or an email address
+fn is_valid_owner(owner: &str) -> bool {
+    let name_ok = |name: &str| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+
+    if let Some(handle) = owner.strip_prefix('@') {
+        return match handle.split_once('/') {
+            Some((org, team)) => name_ok(org) && name_ok(team),
+            None => name_ok(handle),
+        };
+    }
+    matches!(owner.split_once('@'), Some((local, domain))
+        if !local.is_empty() && domain.contains('.') && !domain.contains('@'))
+}
+
+/// Read the CODEOWNERS file of a repository
+///
+/// Reads from the worktree, or from the HEAD tree in bare repositories.
+/// Returns `None` when no CODEOWNERS file exists.
+pub async fn read_codeowners(repo: RepoHandle) -> GitResult<Option<Codeowners>> {
+    let repo_inner = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || load(&repo_inner))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Synchronous [`read_codeowners`], for use inside other blocking operations
+pub(crate) fn load(repo: &gix::Repository) -> GitResult<Option<Codeowners>> {
+    if let Some(workdir) = repo.workdir() {
+        for location in CODEOWNERS_LOCATIONS {
+            match std::fs::read_to_string(workdir.join(location)) {
+                Ok(content) => return Ok(Some(Codeowners::parse(*location, &content))),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(GitError::Io(e)),
+            }
+        }
+        return Ok(None);
+    }
+
+    let Ok(head_commit) = repo.head_commit() else {
+        // Unborn HEAD: nothing committed yet
+        return Ok(None);
+    };
+    let tree = head_commit.tree().map_err(GitError::gix)?;
+    for location in CODEOWNERS_LOCATIONS {
+        let Some(entry) = tree.lookup_entry_by_path(location).map_err(GitError::gix)? else {
+            continue;
+        };
+        let blob = entry.object().map_err(GitError::gix)?;
+        let content = String::from_utf8_lossy(&blob.data);
+        return Ok(Some(Codeowners::parse(*location, &content)));
+    }
+    Ok(None)
+}
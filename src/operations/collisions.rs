@@ -0,0 +1,174 @@
+//! Paths that can't be checked out everywhere.
+//!
+//! Linux allows any byte but `/` and NUL in a file name and tells `README.md`
+//! from `readme.md`; macOS and Windows do neither. [`detect_case_collisions`]
+//! finds the paths of a commit that would collide or fail to check out on
+//! those systems, so they can be fixed before they are pushed.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use gix::bstr::ByteSlice;
+
+use super::compare::resolve_commit;
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Characters Windows rejects in file names (`:` is also rejected by macOS)
+const INVALID_CHARACTERS: &[char] = &['<', '>', ':', '"', '\\', '|', '?', '*'];
+
+/// Device names Windows reserves, with or without an extension
+const RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Why a path can't be checked out on some systems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathIssueKind {
+    /// Another path differs only by case (macOS, Windows).
+    CaseCollision,
+    /// Another path differs only by Unicode normalization (macOS).
+    UnicodeCollision,
+    /// A component contains a character Windows rejects, or a control character.
+    InvalidCharacter,
+    /// A component is a reserved device name such as `CON` or `nul.txt` (Windows).
+    ReservedName,
+    /// A component ends with a dot or space, which Windows drops (Windows).
+    TrailingDotOrSpace,
+}
+
+impl PathIssueKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CaseCollision => "case-collision",
+            Self::UnicodeCollision => "unicode-collision",
+            Self::InvalidCharacter => "invalid-character",
+            Self::ReservedName => "reserved-name",
+            Self::TrailingDotOrSpace => "trailing-dot-or-space",
+        }
+    }
+
+    /// Whether the issue is two paths colliding, rather than one bad path.
+    #[must_use]
+    pub fn is_collision(self) -> bool {
+        matches!(self, Self::CaseCollision | Self::UnicodeCollision)
+    }
+}
+
+impl std::fmt::Display for PathIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A path that can't be checked out on some systems.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathIssue {
+    /// Repository-relative path, `/`-separated; a directory for collisions
+    /// between directories
+    pub path: String,
+    pub kind: PathIssueKind,
+    /// The other paths of a collision, empty otherwise
+    pub conflicts_with: Vec<String>,
+}
+
+/// Find the paths of `rev`'s tree that collide or are invalid on macOS or
+/// Windows.
+///
+/// Paths differing only by case or Unicode normalization are reported once,
+/// on the first of them in path order; collisions between directories are
+/// reported on the directory. Paths are checked component by component for
+/// characters and names Windows rejects.
+pub async fn detect_case_collisions(repo: RepoHandle, rev: &str) -> GitResult<Vec<PathIssue>> {
+    let repo = repo.clone_for_traversal();
+    let rev = rev.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let commit = resolve_commit(&repo, &rev)?;
+        let tree = repo
+            .find_commit(commit)
+            .map_err(GitError::gix)?
+            .tree()
+            .map_err(GitError::gix)?;
+        let entries = tree
+            .traverse()
+            .breadthfirst
+            .files()
+            .map_err(GitError::gix)?;
+        let paths: Vec<String> = entries
+            .into_iter()
+            .filter(|entry| !entry.mode.is_tree())
+            .map(|entry| entry.filepath.to_str_lossy().into_owned())
+            .collect();
+        Ok(path_issues(paths.iter().map(String::as_str)))
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Collisions, then invalid paths, among `paths` and their directories
+pub(crate) fn path_issues<'a>(paths: impl IntoIterator<Item = &'a str>) -> Vec<PathIssue> {
+    let mut all: BTreeSet<&str> = BTreeSet::new();
+    for path in paths {
+        all.insert(path);
+        for (index, _) in path.match_indices('/') {
+            all.insert(&path[..index]);
+        }
+    }
+
+    let mut folded: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for &path in &all {
+        let key = gix_utils::str::precompose(Cow::Borrowed(path)).to_lowercase();
+        folded.entry(key).or_default().push(path);
+    }
+    let mut issues: Vec<PathIssue> = folded
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|group| {
+            let lowered = group[0].to_lowercase();
+            let kind = if group.iter().all(|path| path.to_lowercase() == lowered) {
+                PathIssueKind::CaseCollision
+            } else {
+                PathIssueKind::UnicodeCollision
+            };
+            PathIssue {
+                path: group[0].to_string(),
+                kind,
+                conflicts_with: group[1..].iter().map(|path| path.to_string()).collect(),
+            }
+        })
+        .collect();
+    issues.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // Each bad component is reported once, on the shortest path containing it
+    for &path in &all {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        if let Some(kind) = component_issue(name) {
+            issues.push(PathIssue {
+                path: path.to_string(),
+                kind,
+                conflicts_with: Vec::new(),
+            });
+        }
+    }
+    issues
+}
+
+/// What makes the file name `name` invalid on Windows, if anything
+fn component_issue(name: &str) -> Option<PathIssueKind> {
+    if name
+        .chars()
+        .any(|c| c.is_control() || INVALID_CHARACTERS.contains(&c))
+    {
+        return Some(PathIssueKind::InvalidCharacter);
+    }
+    if name.ends_with(['.', ' ']) {
+        return Some(PathIssueKind::TrailingDotOrSpace);
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end().to_lowercase();
+    if RESERVED_NAMES.contains(&stem.as_str()) {
+        return Some(PathIssueKind::ReservedName);
+    }
+    None
+}
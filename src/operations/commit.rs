@@ -3,15 +3,23 @@
 //! This module provides the `CommitOpts` builder pattern and commit operation
 //! implementation for the `GitGix` service.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 
+use super::index::open_index;
 use crate::{CommitId, GitError, GitResult, RepoHandle};
 
-/// Result of a commit operation containing ID and file count
+/// Result of a commit operation
 #[derive(Debug, Clone)]
 pub struct CommitResult {
     pub id: CommitId,
     pub file_count: usize,
+    /// Root tree of the new commit.
+    pub tree: gix::ObjectId,
+    pub parents: Vec<CommitId>,
+    /// Branch moved to the new commit, e.g. `main`; `None` on a detached `HEAD`.
+    pub branch: Option<String>,
+    /// Whether the commit replaced `HEAD` instead of extending it.
+    pub amended: bool,
 }
 
 /// Git signature (author/committer) information.
@@ -19,49 +27,99 @@ pub struct CommitResult {
 pub struct Signature {
     pub name: String,
     pub email: String,
+    /// Time of the signature in UTC
     pub time: DateTime<Utc>,
+    /// UTC offset the time was recorded in
+    pub offset: FixedOffset,
+    /// Time exactly as stored in the object, e.g. `1700000000 +0200`; `None`
+    /// for signatures not read from an object
+    pub raw_time: Option<String>,
 }
 
 impl Signature {
     /// Create a new signature with current time.
     pub fn new<N: Into<String>, E: Into<String>>(name: N, email: E) -> Self {
-        Self {
-            name: name.into(),
-            email: email.into(),
-            time: Utc::now(),
-        }
+        Self::with_time(name, email, Utc::now())
     }
 
-    /// Create a signature with a specific time.
+    /// Create a signature with a specific time, recorded in UTC.
     pub fn with_time<N: Into<String>, E: Into<String>>(
         name: N,
         email: E,
         time: DateTime<Utc>,
+    ) -> Self {
+        Self::with_date(name, email, time.fixed_offset())
+    }
+
+    /// Create a signature with a specific time, recorded in its own offset.
+    pub fn with_date<N: Into<String>, E: Into<String>>(
+        name: N,
+        email: E,
+        date: DateTime<FixedOffset>,
     ) -> Self {
         Self {
             name: name.into(),
             email: email.into(),
-            time,
+            time: date.to_utc(),
+            offset: *date.offset(),
+            raw_time: None,
         }
     }
-}
 
-impl From<gix::actor::Signature> for Signature {
-    fn from(sig: gix::actor::Signature) -> Self {
-        use chrono::TimeZone;
-        // Convert gix::date::Time to DateTime<Utc>
-        let timestamp = sig.time.seconds;
-        let time = Utc
-            .timestamp_opt(timestamp, 0)
-            .single()
-            .unwrap_or_else(Utc::now);
+    /// Time of the signature in the offset it was recorded in.
+    #[must_use]
+    pub fn local_time(&self) -> DateTime<FixedOffset> {
+        self.time.with_timezone(&self.offset)
+    }
 
-        Self {
-            name: sig.name.to_string(),
-            email: sig.email.to_string(),
-            time,
+    /// Time of the signature as git stores it.
+    #[must_use]
+    pub fn git_time(&self) -> gix::date::Time {
+        date_to_git_time(&self.local_time())
+    }
+
+    /// The signature in gix's form, for writing objects
+    pub(crate) fn to_gix(&self) -> gix::actor::Signature {
+        gix::actor::Signature {
+            name: self.name.as_str().into(),
+            email: self.email.as_str().into(),
+            time: self.git_time(),
         }
     }
+
+    fn from_git_time(name: String, email: String, time: gix::date::Time) -> GitResult<Self> {
+        let date = git_time_to_date(time).ok_or_else(|| {
+            GitError::Parse(format!("Invalid timestamp {time} in signature of {email}"))
+        })?;
+        Ok(Self::with_date(name, email, date))
+    }
+}
+
+impl TryFrom<gix::actor::Signature> for Signature {
+    type Error = GitError;
+
+    /// Fails if the signature's time is out of range, instead of making one up.
+    fn try_from(sig: gix::actor::Signature) -> GitResult<Self> {
+        Self::from_git_time(sig.name.to_string(), sig.email.to_string(), sig.time)
+    }
+}
+
+impl TryFrom<gix::actor::SignatureRef<'_>> for Signature {
+    type Error = GitError;
+
+    /// Like the owned conversion, but also keeps the time as stored in
+    /// [`Signature::raw_time`].
+    fn try_from(sig: gix::actor::SignatureRef<'_>) -> GitResult<Self> {
+        let time = sig.time().map_err(|e| {
+            GitError::Parse(format!(
+                "Invalid time '{}' in signature of {}: {e}",
+                sig.time, sig.email
+            ))
+        })?;
+        let mut signature = Self::from_git_time(sig.name.to_string(), sig.email.to_string(), time)?;
+        signature.raw_time = Some(sig.time.to_string());
+        Ok(signature)
+    }
 }
 
 /// Options for `commit` operation with builder pattern.
@@ -72,6 +130,13 @@ pub struct CommitOpts {
     pub all: bool,
     pub author: Option<Signature>,
     pub committer: Option<Signature>,
+    /// Author date, overriding the author signature's time (like `GIT_AUTHOR_DATE`)
+    pub author_date: Option<DateTime<FixedOffset>>,
+    /// Committer date, overriding the committer signature's time (like `GIT_COMMITTER_DATE`)
+    pub commit_date: Option<DateTime<FixedOffset>>,
+    /// Encoding to write the message in, e.g. `ISO-8859-1` (default:
+    /// `i18n.commitEncoding`, else UTF-8)
+    pub encoding: Option<String>,
 }
 
 impl CommitOpts {
@@ -84,6 +149,9 @@ impl CommitOpts {
             all: false,
             author: None,
             committer: None,
+            author_date: None,
+            commit_date: None,
+            encoding: None,
         }
     }
 
@@ -114,11 +182,63 @@ impl CommitOpts {
         self.committer = Some(sig);
         self
     }
+
+    /// Backdate the author date, keeping its UTC offset.
+    #[must_use]
+    pub fn author_date(mut self, date: DateTime<FixedOffset>) -> Self {
+        self.author_date = Some(date);
+        self
+    }
+
+    /// Backdate the committer date, keeping its UTC offset.
+    #[must_use]
+    pub fn commit_date(mut self, date: DateTime<FixedOffset>) -> Self {
+        self.commit_date = Some(date);
+        self
+    }
+
+    /// Write the message in this encoding and name it in the commit's
+    /// `encoding` header.
+    #[must_use]
+    pub fn encoding<S: Into<String>>(mut self, encoding: S) -> Self {
+        self.encoding = Some(encoding.into());
+        self
+    }
+}
+
+/// Parse a date in any format git accepts for `GIT_AUTHOR_DATE`: RFC 2822,
+/// ISO 8601, `<unix seconds> <offset>` or relative dates such as `2 days ago`.
+pub fn parse_date(input: &str) -> GitResult<DateTime<FixedOffset>> {
+    let time = gix::date::parse(input.trim(), Some(std::time::SystemTime::now()))
+        .map_err(|e| GitError::Parse(format!("Invalid date '{input}': {e}")))?;
+    git_time_to_date(time)
+        .ok_or_else(|| GitError::Parse(format!("Date out of range: '{input}'")))
+}
+
+/// Convert a git timestamp, keeping its offset
+pub(crate) fn git_time_to_date(time: gix::date::Time) -> Option<DateTime<FixedOffset>> {
+    let offset = FixedOffset::east_opt(time.offset)?;
+    DateTime::from_timestamp(time.seconds, 0).map(|utc| utc.with_timezone(&offset))
+}
+
+/// Convert a date to a git timestamp, keeping its offset
+fn date_to_git_time(date: &DateTime<FixedOffset>) -> gix::date::Time {
+    gix::date::Time::new(date.timestamp(), date.offset().local_minus_utc())
 }
 
 /// Execute commit operation with the given options.
+///
+/// Retries once after clearing stale lock files when lock recovery is
+/// enabled (see [`super::maintenance::set_lock_recovery`]).
 pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResult> {
+    super::maintenance::with_lock_recovery(repo, |repo| commit_once(repo, opts.clone())).await
+}
+
+/// Single commit attempt
+async fn commit_once(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResult> {
     let repo_clone = repo.clone_inner();
+    let index_file = repo.index_file().map(std::path::Path::to_path_buf);
+    let sparse_index = repo.sparse_index();
 
     tokio::task::spawn_blocking(move || {
         let CommitOpts {
@@ -127,6 +247,9 @@ pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResul
             all,
             author,
             committer,
+            author_date,
+            commit_date,
+            encoding,
         } = opts;
 
         if message.trim().is_empty() {
@@ -134,11 +257,19 @@ pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResul
                 "Commit message cannot be empty".to_string(),
             ));
         }
+        let encoding = encoding.or_else(|| {
+            repo_clone
+                .config_snapshot()
+                .string("i18n.commitEncoding")
+                .map(|label| label.to_string())
+        });
+        let (encoding, encoded_message) = match encoding {
+            Some(label) => super::encoding::encode_message(&label, &message)?,
+            None => (None, message.as_str().into()),
+        };
 
         // Get current index
-        let index = repo_clone
-            .open_index()
-            .map_err(|e| GitError::Gix(e.into()))?;
+        let index = open_index(&repo_clone, index_file.as_deref(), sparse_index)?;
 
         // Handle --all option: stage all modified tracked files
         let index = if all {
@@ -181,7 +312,7 @@ pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResul
                 // Write blob and get ID
                 let blob_id = repo_clone
                     .write_blob(&contents)
-                    .map_err(|e| GitError::Gix(e.into()))?
+                    .map_err(GitError::gix)?
                     .detach();
 
                 // Only update if content changed
@@ -223,15 +354,11 @@ pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResul
 
                 // Write index with proper locking and checksum
                 use gix::index::write::Options;
-                index
-                    .write(Options::default())
-                    .map_err(|e| GitError::Gix(e.into()))?;
+                index.write(Options::default()).map_err(GitError::gix)?;
             }
 
             // Re-open index for tree building
-            repo_clone
-                .open_index()
-                .map_err(|e| GitError::Gix(e.into()))?
+            open_index(&repo_clone, index_file.as_deref(), sparse_index)?
         } else {
             index
         };
@@ -246,10 +373,17 @@ pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResul
             repo_clone.object_hash(),
         );
 
-        // Add each index entry with path components to build hierarchy
-        for entry in index.entries() {
+        // Add each index entry with path components to build hierarchy;
+        // intent-to-add entries stay out of the commit, as in git
+        for entry in index.entries().iter().filter(|entry| {
+            !entry
+                .flags
+                .contains(gix::index::entry::Flags::INTENT_TO_ADD)
+        }) {
             if let Some(tree_mode) = entry.mode.to_tree_entry_mode() {
+                // Collapsed directories of a sparse index end in `/`
                 let path = entry.path(&index);
+                let path = path.strip_suffix(b"/").unwrap_or(path);
                 // Split path into components for hierarchical tree building
                 let components: Vec<&gix::bstr::BStr> = path
                     .split(|&b| b == b'/')
@@ -261,58 +395,53 @@ pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResul
 
                 editor
                     .upsert(components, kind, entry.id)
-                    .map_err(|e| GitError::Gix(Box::new(e)))?;
+                    .map_err(GitError::gix)?;
             }
         }
 
         // Write all tree objects and get root tree ID
-        let tree_id = editor
-            .write(|tree| {
-                repo_clone
-                    .write_object(tree)
-                    .map(gix::Id::detach)
-                    .map_err(|e| GitError::Gix(Box::new(e)))
-            })
-            .map_err(|e| match e {
-                GitError::Gix(inner) => GitError::Gix(inner),
-                _ => GitError::Gix(Box::new(e)),
-            })?;
+        let tree_id = editor.write(|tree| {
+            repo_clone
+                .write_object(tree)
+                .map(gix::Id::detach)
+                .map_err(GitError::gix)
+        })?;
 
         // Get current HEAD commit ID
         let head_commit_id = repo_clone.head_id().ok();
 
         // Get or create author signature
-        let author_sig = if let Some(author) = author {
-            gix::actor::Signature {
-                name: author.name.as_str().into(),
-                email: author.email.as_str().into(),
-                time: gix::date::Time::new(author.time.timestamp(), 0),
-            }
+        let mut author_sig = if let Some(author) = author {
+            author.to_gix()
         } else {
             // Use config default - convert SignatureRef to owned Signature
             let sig_ref = repo_clone
                 .author()
                 .ok_or_else(|| GitError::InvalidInput("No author configured".to_string()))?
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-            sig_ref.to_owned().map_err(|e| GitError::Gix(Box::new(e)))?
+                .map_err(GitError::gix)?;
+            sig_ref.to_owned().map_err(GitError::gix)?
         };
 
         // Get or create committer signature
-        let committer_sig = if let Some(committer) = committer {
-            gix::actor::Signature {
-                name: committer.name.as_str().into(),
-                email: committer.email.as_str().into(),
-                time: gix::date::Time::new(committer.time.timestamp(), 0),
-            }
+        let mut committer_sig = if let Some(committer) = committer {
+            committer.to_gix()
         } else {
             // Use config default or author
             match repo_clone.committer() {
-                Some(Ok(sig_ref)) => sig_ref.to_owned().map_err(|e| GitError::Gix(Box::new(e)))?,
-                Some(Err(e)) => return Err(GitError::Gix(Box::new(e))),
+                Some(Ok(sig_ref)) => sig_ref.to_owned().map_err(GitError::gix)?,
+                Some(Err(e)) => return Err(GitError::gix(e)),
                 None => author_sig.clone(),
             }
         };
 
+        // Explicit dates win over the signatures' times
+        if let Some(date) = &author_date {
+            author_sig.time = date_to_git_time(date);
+        }
+        if let Some(date) = &commit_date {
+            committer_sig.time = date_to_git_time(date);
+        }
+
         // Determine parents based on amend flag
         let parents = if amend {
             // For amend: use HEAD's parents (replace HEAD)
@@ -320,7 +449,7 @@ pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResul
                 // Get HEAD commit object using pattern from checkout.rs
                 let head_commit = repo_clone
                     .find_object(head_id)
-                    .map_err(|e| GitError::Gix(e.into()))?
+                    .map_err(GitError::gix)?
                     .try_into_commit()
                     .map_err(|_| {
                         GitError::InvalidInput("HEAD does not point to a commit".to_string())
@@ -345,27 +474,78 @@ pub async fn commit(repo: RepoHandle, opts: CommitOpts) -> GitResult<CommitResul
                 .collect::<Vec<_>>()
         };
 
-        // Create time buffers for signature conversion
-        use gix::date::parse::TimeBuf;
-        let mut committer_time_buf = TimeBuf::default();
-        let mut author_time_buf = TimeBuf::default();
-
-        let commit_id = repo_clone
-            .commit_as(
-                committer_sig.to_ref(&mut committer_time_buf),
-                author_sig.to_ref(&mut author_time_buf),
-                "HEAD",
-                &message,
-                tree_id,
-                parents,
-            )
-            .map_err(|e| GitError::Gix(e.into()))?;
+        // Branch that `HEAD` points to, which the commit moves
+        let branch = repo_clone
+            .head_name()
+            .map_err(GitError::gix)?
+            .map(|name| name.shorten().to_string());
+
+        let commit = gix::objs::Commit {
+            tree: tree_id,
+            parents: parents.iter().copied().collect(),
+            author: author_sig,
+            committer: committer_sig,
+            encoding,
+            message: encoded_message,
+            extra_headers: Vec::new(),
+        };
+        // An amended commit replaces HEAD rather than following it
+        let head = head_commit_id.map(gix::Id::detach);
+        let commit_id = write_commit_to_head(&repo_clone, &commit, &message, head, amend)?;
 
         Ok(CommitResult {
-            id: commit_id.detach(),
+            id: commit_id,
             file_count,
+            tree: tree_id,
+            parents,
+            branch,
+            amended: amend,
         })
     })
     .await
     .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
 }
+
+/// Write `commit` and move `HEAD`, or the branch it points to, onto it the
+/// way `gix::Repository::commit_as` does, which can't replace HEAD when
+/// amending.
+///
+/// `head` is the commit `HEAD` must still point at; `message` is the UTF-8
+/// message for the reflog.
+fn write_commit_to_head(
+    repo: &gix::Repository,
+    commit: &gix::objs::Commit,
+    message: &str,
+    head: Option<CommitId>,
+    amend: bool,
+) -> GitResult<CommitId> {
+    use gix::refs::Target;
+    use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+    let id = repo.write_object(commit).map_err(GitError::gix)?.detach();
+    let edit = RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: if amend {
+                    format!("commit (amend): {}", message.lines().next().unwrap_or_default())
+                        .into()
+                } else {
+                    gix::reference::log::message("commit", message.into(), commit.parents.len())
+                },
+            },
+            expected: match head {
+                Some(head) => PreviousValue::MustExistAndMatch(Target::Object(head)),
+                None => PreviousValue::MustNotExist,
+            },
+            new: Target::Object(id),
+        },
+        name: "HEAD".try_into().map_err(GitError::gix)?,
+        deref: true,
+    };
+    let mut time_buf = gix::date::parse::TimeBuf::default();
+    repo.edit_references_as(Some(edit), Some(commit.committer.to_ref(&mut time_buf)))
+        .map_err(GitError::gix)?;
+    Ok(id)
+}
@@ -0,0 +1,191 @@
+//! Branch comparison: commits, diffstat and mergeability in one call.
+//!
+//! [`compare_branches`] answers the questions behind a pull request summary:
+//! which commits `head` adds on top of `base` (and which it lacks), what the
+//! change looks like since the branches diverged, and whether merging `head`
+//! into `base` would conflict.
+
+use std::collections::HashSet;
+
+use gix::bstr::ByteSlice;
+
+use super::diff::{DiffStats, diff_with_line_counts};
+use super::encoding::commit_summary;
+use super::log::{commit_author, commit_date};
+use crate::{CommitId, CommitInfo, GitError, GitResult, RepoHandle};
+
+/// Predicted outcome of merging `head` into `base`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mergeability {
+    /// `base` already contains every commit of `head`.
+    UpToDate,
+    /// `base` can be fast-forwarded to `head`.
+    FastForward,
+    /// A merge commit would be needed and merges without conflicts.
+    Clean,
+    /// Merging would conflict in these paths.
+    Conflicts(Vec<String>),
+    /// The branches share no history.
+    Unrelated,
+}
+
+/// Result of comparing two branches.
+#[derive(Debug, Clone)]
+pub struct BranchComparison {
+    pub base_id: CommitId,
+    pub head_id: CommitId,
+    /// Best common ancestor, `None` for unrelated histories.
+    pub merge_base: Option<CommitId>,
+    /// Commits in `head` but not in `base`, newest first.
+    pub ahead: Vec<CommitInfo>,
+    /// Commits in `base` but not in `head`, newest first.
+    pub behind: Vec<CommitInfo>,
+    /// Changes `head` introduces since the merge base (the pull request diff),
+    /// with line counts.
+    pub diffstat: DiffStats,
+    pub mergeability: Mergeability,
+}
+
+/// Compare `head` against `base`.
+///
+/// Both arguments are revisions (branch names, tags, commit hashes). The
+/// diffstat is taken between the merge base and `head`, as a pull request
+/// would show it; for unrelated histories it is taken between the two tips.
+pub async fn compare_branches(
+    repo: RepoHandle,
+    base: &str,
+    head: &str,
+) -> GitResult<BranchComparison> {
+    let repo_inner = repo.clone_for_traversal();
+    let base = base.to_string();
+    let head = head.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let base_id = resolve_commit(&repo_inner, &base)?;
+        let head_id = resolve_commit(&repo_inner, &head)?;
+
+        let merge_base = if base_id == head_id {
+            Some(base_id)
+        } else {
+            repo_inner
+                .merge_base(base_id, head_id)
+                .ok()
+                .map(gix::Id::detach)
+        };
+
+        let ahead = commits_between(&repo_inner, head_id, base_id)?;
+        let behind = commits_between(&repo_inner, base_id, head_id)?;
+        let diffstat =
+            diff_with_line_counts(&repo_inner, Some(merge_base.unwrap_or(base_id)), head_id)?;
+
+        let mergeability = match merge_base {
+            None => Mergeability::Unrelated,
+            Some(_) if ahead.is_empty() => Mergeability::UpToDate,
+            Some(_) if behind.is_empty() => Mergeability::FastForward,
+            Some(_) => predict_merge(&repo_inner, base_id, head_id, &head)?,
+        };
+
+        Ok(BranchComparison {
+            base_id,
+            head_id,
+            merge_base,
+            ahead,
+            behind,
+            diffstat,
+            mergeability,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Resolve `rev` to the commit it names, peeling tags
+pub(crate) fn resolve_commit(repo: &gix::Repository, rev: &str) -> GitResult<CommitId> {
+    let id = repo
+        .rev_parse_single(rev.as_bytes().as_bstr())
+        .map_err(GitError::gix)?
+        .object()
+        .map_err(GitError::gix)?
+        .peel_to_commit()
+        .map_err(GitError::gix)?
+        .id;
+    Ok(id)
+}
+
+/// Commits reachable from `tip` but not from `hidden`, newest first
+pub(crate) fn commits_between(
+    repo: &gix::Repository,
+    tip: CommitId,
+    hidden: CommitId,
+) -> GitResult<Vec<CommitInfo>> {
+    let walk = repo
+        .rev_walk([tip])
+        .with_hidden([hidden])
+        .all()
+        .map_err(GitError::gix)?;
+
+    let boundary = super::shallow::shallow_boundary(repo)?;
+    let mut commits = Vec::new();
+    for info in walk {
+        let info = info.map_err(GitError::gix)?;
+        commits.push(commit_info(repo, info.id, &boundary)?);
+    }
+    Ok(commits)
+}
+
+/// Log entry for commit `id`, flagged if it is on the shallow `boundary`
+pub(crate) fn commit_info(
+    repo: &gix::Repository,
+    id: CommitId,
+    boundary: &HashSet<CommitId>,
+) -> GitResult<CommitInfo> {
+    let commit = repo.find_commit(id).map_err(GitError::gix)?;
+    let mut warnings = Vec::new();
+    let date = commit_date(&commit, &mut warnings);
+
+    Ok(CommitInfo {
+        id,
+        parents: commit.parent_ids().map(gix::Id::detach).collect(),
+        author: commit_author(&commit, date, &mut warnings),
+        summary: commit_summary(&commit),
+        time: date.to_utc(),
+        offset: *date.offset(),
+        shallow_boundary: boundary.contains(&id),
+        decorations: Vec::new(),
+        warnings,
+    })
+}
+
+/// Merge the trees in memory and report conflicting paths
+fn predict_merge(
+    repo: &gix::Repository,
+    base_id: CommitId,
+    head_id: CommitId,
+    head_name: &str,
+) -> GitResult<Mergeability> {
+    use gix::merge::blob::builtin_driver::text::Labels;
+    use gix::merge::tree::TreatAsUnresolved;
+
+    let options: gix::merge::commit::Options =
+        repo.tree_merge_options().map_err(GitError::gix)?.into();
+    let labels = Labels {
+        ancestor: None,
+        current: Some("HEAD".into()),
+        other: Some(head_name.into()),
+    };
+
+    let outcome = repo
+        .merge_commits(base_id, head_id, labels, options)
+        .map_err(GitError::gix)?;
+
+    if !outcome
+        .tree_merge
+        .has_unresolved_conflicts(TreatAsUnresolved::default())
+    {
+        return Ok(Mergeability::Clean);
+    }
+
+    Ok(Mergeability::Conflicts(super::merge::unresolved_conflict_paths(
+        &outcome.tree_merge,
+    )))
+}
@@ -0,0 +1,131 @@
+//! Conflict detail for merges that cannot complete on their own.
+//!
+//! Merges are performed in memory first; when the result has unresolved
+//! conflicts, [`merge_conflicts`] lists the affected files and how each side
+//! changed them, so callers can go straight to resolving them.
+
+use gix::bstr::ByteSlice;
+use gix::diff::tree_with_rewrites::Change;
+
+use crate::{GitError, GitResult, RepoHandle};
+
+/// How both sides of a merge changed a conflicted file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Both sides edited the same lines.
+    Content,
+    /// Both sides added the file with different content.
+    AddAdd,
+    /// One side deleted the file, the other modified it.
+    DeleteModify,
+    /// One side renamed the file, the other deleted it.
+    RenameDelete,
+    /// Both sides renamed the file to different paths.
+    RenameRename,
+}
+
+impl ConflictKind {
+    /// Short name as git prints it, e.g. `delete/modify`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Content => "content",
+            Self::AddAdd => "add/add",
+            Self::DeleteModify => "delete/modify",
+            Self::RenameDelete => "rename/delete",
+            Self::RenameRename => "rename/rename",
+        }
+    }
+}
+
+impl std::fmt::Display for ConflictKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A file a merge could not resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictedFile {
+    /// Path on our side.
+    pub path: String,
+    /// Path on their side, when they renamed it elsewhere.
+    pub their_path: Option<String>,
+    pub kind: ConflictKind,
+}
+
+/// List the files that merging `theirs` into `HEAD` would leave conflicted.
+///
+/// Nothing is written; an empty list means the merge would succeed. Returns
+/// an empty list as well when `theirs` is already contained in `HEAD`.
+pub async fn merge_conflicts(repo: RepoHandle, theirs: &str) -> GitResult<Vec<ConflictedFile>> {
+    use gix::merge::blob::builtin_driver::text::Labels;
+
+    let repo_inner = repo.clone_for_traversal();
+    let theirs = theirs.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let ours = repo_inner.head_id().map_err(GitError::gix)?.detach();
+        let their_id = repo_inner
+            .rev_parse_single(theirs.as_bytes().as_bstr())
+            .map_err(|e| GitError::InvalidInput(format!("Invalid merge target '{theirs}': {e}")))?
+            .detach();
+
+        let options: gix::merge::commit::Options = repo_inner
+            .tree_merge_options()
+            .map_err(GitError::gix)?
+            .into();
+        let labels = Labels {
+            ancestor: None,
+            current: Some("HEAD".into()),
+            other: Some(theirs.as_str().into()),
+        };
+        let outcome = repo_inner
+            .merge_commits(ours, their_id, labels, options)
+            .map_err(GitError::gix)?;
+        Ok(conflicted_files(&outcome.tree_merge))
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Unresolved conflicts of a tree merge, one entry per path, sorted by path
+pub(crate) fn conflicted_files(outcome: &gix::merge::tree::Outcome<'_>) -> Vec<ConflictedFile> {
+    let how = gix::merge::tree::TreatAsUnresolved::default();
+    let mut files: Vec<ConflictedFile> = Vec::new();
+    for conflict in outcome.conflicts.iter().filter(|c| c.is_unresolved(how)) {
+        let path = conflict.ours.location().to_str_lossy().into_owned();
+        if files.iter().any(|file| file.path == path) {
+            continue;
+        }
+        let their_location = conflict.theirs.location();
+        let their_path = (their_location != conflict.ours.location())
+            .then(|| their_location.to_str_lossy().into_owned());
+        files.push(ConflictedFile {
+            path,
+            their_path,
+            kind: conflict_kind(&conflict.ours, &conflict.theirs),
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Classify a conflict by what each side did to the file
+fn conflict_kind(ours: &Change, theirs: &Change) -> ConflictKind {
+    let deleted = |change: &Change| matches!(change, Change::Deletion { .. });
+    let renamed = |change: &Change| matches!(change, Change::Rewrite { .. });
+    let added = |change: &Change| matches!(change, Change::Addition { .. });
+
+    if renamed(ours) && renamed(theirs) {
+        ConflictKind::RenameRename
+    } else if (renamed(ours) && deleted(theirs)) || (deleted(ours) && renamed(theirs)) {
+        ConflictKind::RenameDelete
+    } else if deleted(ours) || deleted(theirs) {
+        ConflictKind::DeleteModify
+    } else if added(ours) && added(theirs) {
+        ConflictKind::AddAdd
+    } else {
+        ConflictKind::Content
+    }
+}
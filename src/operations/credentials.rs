@@ -0,0 +1,604 @@
+//! In-memory credential cache for network operations
+//!
+//! Credentials obtained from git credential helpers (or askpass) are cached per
+//! host with a TTL, so repeated clone/fetch/pull calls against the same host
+//! don't re-invoke helpers every time. Credentials rejected by the remote are
+//! invalidated automatically.
+//!
+//! The cache can optionally be persisted to disk, encrypted with a caller
+//! supplied 256-bit key (ChaCha20-Poly1305).
+//!
+//! Embedders can register a [`TokenSource`] (e.g. GitHub App installation token
+//! minting). Clone, fetch, pull and push consult it before connecting whenever
+//! no valid token is cached, and tokens are re-minted shortly before they
+//! expire or after the remote rejects them; the rejected operation is retried
+//! once with the fresh token.
+//!
+//! Note: git CLI operations other than push use git's own credential helpers
+//! and don't go through this cache.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use gix::credentials::helper::{Action, NextAction};
+use gix::credentials::protocol::{Context, Outcome};
+use serde::{Deserialize, Serialize};
+
+use crate::{GitError, GitResult};
+
+/// Default time-to-live for cached credentials (15 minutes, like `git credential-cache`)
+pub const DEFAULT_CREDENTIAL_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Tokens are re-minted this many seconds before their reported expiry
+const TOKEN_EXPIRY_MARGIN_SECS: u64 = 60;
+
+/// Size of the ChaCha20-Poly1305 nonce prefixed to the persisted cache file
+const NONCE_LEN: usize = 12;
+
+/// Global credential cache shared by all operations
+static CREDENTIAL_CACHE: LazyLock<Mutex<CredentialCache>> =
+    LazyLock::new(|| Mutex::new(CredentialCache::new(DEFAULT_CREDENTIAL_TTL)));
+
+/// Embedder-supplied token source, if any
+static TOKEN_SOURCE: RwLock<Option<Arc<dyn TokenSource>>> = RwLock::new(None);
+
+/// A cached username/password pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedCredential {
+    /// Username
+    pub username: String,
+    /// Password or token
+    pub password: String,
+    /// Expiry as seconds since the Unix epoch
+    pub expires_at: u64,
+}
+
+impl CachedCredential {
+    fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Encrypted on-disk persistence settings
+#[derive(Clone)]
+struct Persistence {
+    path: PathBuf,
+    key: [u8; 32],
+}
+
+/// Credential cache keyed by `<protocol>://<host[:port]>`
+pub struct CredentialCache {
+    entries: HashMap<String, CachedCredential>,
+    ttl: Duration,
+    persistence: Option<Persistence>,
+}
+
+impl CredentialCache {
+    /// Create an empty, memory-only cache
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl,
+            persistence: None,
+        }
+    }
+
+    /// Time-to-live applied to newly stored credentials
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Look up a non-expired credential for `host_key`
+    pub fn get(&mut self, host_key: &str) -> Option<CachedCredential> {
+        let now = unix_now();
+        match self.entries.get(host_key) {
+            Some(entry) if !entry.is_expired(now) => Some(entry.clone()),
+            Some(_) => {
+                self.entries.remove(host_key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store a credential for `host_key`, expiring after the cache TTL
+    pub fn insert(&mut self, host_key: &str, username: String, password: String) {
+        self.insert_until(host_key, username, password, None);
+    }
+
+    /// Store a credential that also expires at `expires_at` (Unix seconds), whichever comes first
+    pub fn insert_until(
+        &mut self,
+        host_key: &str,
+        username: String,
+        password: String,
+        expires_at: Option<u64>,
+    ) {
+        let ttl_expiry = unix_now().saturating_add(self.ttl.as_secs());
+        let expires_at = expires_at.map_or(ttl_expiry, |at| at.min(ttl_expiry));
+        self.entries.insert(
+            host_key.to_string(),
+            CachedCredential {
+                username,
+                password,
+                expires_at,
+            },
+        );
+    }
+
+    /// Remove the credential for `host_key`, returning whether one was cached
+    pub fn invalidate(&mut self, host_key: &str) -> bool {
+        self.entries.remove(host_key).is_some()
+    }
+
+    /// Remove all cached credentials
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Hosts with a non-expired cached credential
+    pub fn hosts(&self) -> Vec<String> {
+        let now = unix_now();
+        let mut hosts: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .map(|(host, _)| host.clone())
+            .collect();
+        hosts.sort();
+        hosts
+    }
+
+    /// Write the cache to its persistence file, if persistence is enabled
+    fn persist(&self) -> GitResult<()> {
+        let Some(ref persistence) = self.persistence else {
+            return Ok(());
+        };
+
+        let now = unix_now();
+        let live: HashMap<&String, &CachedCredential> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .collect();
+        let plaintext = serde_json::to_vec(&live)
+            .map_err(|e| GitError::InvalidInput(format!("Failed to serialize credential cache: {e}")))?;
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&persistence.key));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|_| GitError::InvalidInput("Failed to encrypt credential cache".to_string()))?;
+
+        let mut data = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+
+        write_private(&persistence.path, &data)
+    }
+
+    /// Load entries from an encrypted persistence file, replacing in-memory entries
+    fn load(&mut self, path: &Path, key: &[u8; 32]) -> GitResult<()> {
+        let data = std::fs::read(path)?;
+        if data.len() < NONCE_LEN {
+            return Err(GitError::InvalidInput(format!(
+                "Credential cache file '{}' is truncated",
+                path.display()
+            )));
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                GitError::InvalidInput(format!(
+                    "Failed to decrypt credential cache '{}' (wrong key or corrupted file)",
+                    path.display()
+                ))
+            })?;
+
+        let entries: HashMap<String, CachedCredential> = serde_json::from_slice(&plaintext)
+            .map_err(|e| GitError::InvalidInput(format!("Invalid credential cache file: {e}")))?;
+
+        let now = unix_now();
+        self.entries = entries
+            .into_iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .collect();
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Global cache API
+// ============================================================================
+
+/// Set the TTL used for credentials cached from now on
+pub fn set_credential_ttl(ttl: Duration) {
+    if let Ok(mut cache) = CREDENTIAL_CACHE.lock() {
+        cache.ttl = ttl;
+    }
+}
+
+/// Invalidate the cached credential for a URL or `<protocol>://<host>` key
+///
+/// Returns `true` if a credential was removed.
+pub fn invalidate_credentials(url: &str) -> bool {
+    let Some(key) = host_key_for_url(url) else {
+        return false;
+    };
+    let Ok(mut cache) = CREDENTIAL_CACHE.lock() else {
+        return false;
+    };
+    let removed = cache.invalidate(&key);
+    if removed {
+        let _ = cache.persist();
+    }
+    removed
+}
+
+/// Remove all cached credentials (and the persisted copy, if enabled)
+pub fn clear_credentials() {
+    if let Ok(mut cache) = CREDENTIAL_CACHE.lock() {
+        cache.clear();
+        let _ = cache.persist();
+    }
+}
+
+/// Hosts that currently have a cached credential
+pub fn cached_hosts() -> Vec<String> {
+    CREDENTIAL_CACHE
+        .lock()
+        .map(|cache| cache.hosts())
+        .unwrap_or_default()
+}
+
+/// Enable encrypted on-disk persistence of the credential cache
+///
+/// Existing entries at `path` are loaded (expired ones are dropped). The file
+/// is rewritten whenever credentials are stored or invalidated. `key` must be a
+/// 256-bit secret supplied by the embedder, e.g. from the OS keychain.
+pub fn enable_persistence<P: Into<PathBuf>>(path: P, key: [u8; 32]) -> GitResult<()> {
+    let path = path.into();
+    let mut cache = CREDENTIAL_CACHE
+        .lock()
+        .map_err(|_| GitError::InvalidInput("Credential cache lock poisoned".to_string()))?;
+
+    if path.exists() {
+        cache.load(&path, &key)?;
+    }
+    cache.persistence = Some(Persistence { path, key });
+    cache.persist()
+}
+
+/// Disable on-disk persistence; the persisted file is left untouched
+pub fn disable_persistence() {
+    if let Ok(mut cache) = CREDENTIAL_CACHE.lock() {
+        cache.persistence = None;
+    }
+}
+
+// ============================================================================
+// Token sources
+// ============================================================================
+
+/// Short-lived access token minted by a [`TokenSource`]
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    /// Username to authenticate with (e.g. `x-access-token` for GitHub Apps)
+    pub username: String,
+    /// The token, used as password
+    pub token: String,
+    /// Expiry as seconds since the Unix epoch, if known
+    pub expires_at: Option<u64>,
+}
+
+/// Future returned by [`TokenSource::token`]
+pub type TokenFuture<'a> = Pin<Box<dyn Future<Output = GitResult<Option<AccessToken>>> + Send + 'a>>;
+
+/// Async source of short-lived tokens supplied by the embedder
+///
+/// Called before connecting to a remote when no valid token is cached,
+/// including after the remote rejected the previous one (HTTP 401).
+/// Returning `Ok(None)` defers to the configured credential helpers.
+pub trait TokenSource: Send + Sync {
+    /// Mint a token for `host` (`<protocol>://<host[:port]>`, e.g. `https://github.com`)
+    fn token<'a>(&'a self, host: &'a str) -> TokenFuture<'a>;
+}
+
+/// Register the token source used by clone, fetch, pull and push
+///
+/// Returns the source it replaces, if any.
+pub fn set_token_source(source: Arc<dyn TokenSource>) -> Option<Arc<dyn TokenSource>> {
+    TOKEN_SOURCE
+        .write()
+        .ok()
+        .and_then(|mut current| current.replace(source))
+}
+
+/// Remove the registered token source
+pub fn clear_token_source() {
+    if let Ok(mut current) = TOKEN_SOURCE.write() {
+        *current = None;
+    }
+}
+
+/// Whether a token source is registered
+pub fn has_token_source() -> bool {
+    TOKEN_SOURCE.read().map(|s| s.is_some()).unwrap_or(false)
+}
+
+/// Cached credential for `url`'s host, minting a token from the registered
+/// source when none is cached
+///
+/// Tokens are minted here, before connecting, because gix asks for
+/// credentials from inside the blocking transport where the source's future
+/// can't be awaited.
+async fn prime_token(url: &str) -> GitResult<Option<CachedCredential>> {
+    // Only HTTP transports ask for credentials
+    let Some(key) = host_key_for_url(url).filter(|key| key.starts_with("http")) else {
+        return Ok(None);
+    };
+    if let Some(hit) = CREDENTIAL_CACHE.lock().ok().and_then(|mut c| c.get(&key)) {
+        return Ok(Some(hit));
+    }
+    let Some(source) = TOKEN_SOURCE.read().ok().and_then(|s| s.clone()) else {
+        return Ok(None);
+    };
+    let Some(token) = source
+        .token(&key)
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Token source failed for {key}: {e}")))?
+    else {
+        return Ok(None);
+    };
+
+    let mut cache = CREDENTIAL_CACHE
+        .lock()
+        .map_err(|_| GitError::InvalidInput("Credential cache lock poisoned".to_string()))?;
+    let expires_at = token
+        .expires_at
+        .map(|at| at.saturating_sub(TOKEN_EXPIRY_MARGIN_SECS));
+    cache.insert_until(&key, token.username, token.token, expires_at);
+    let _ = cache.persist();
+    Ok(cache.get(&key))
+}
+
+/// Run a network operation against `url` with a token from the registered
+/// source cached, retrying once with a fresh token when the remote rejects it
+///
+/// `op` gets the credential, for operations that can't read the cache
+/// themselves like the git CLI. Without a token source or `url`, `op` runs
+/// once with `None`.
+pub(crate) async fn with_token<T, F, Fut>(url: Option<String>, mut op: F) -> GitResult<T>
+where
+    F: FnMut(Option<CachedCredential>) -> Fut,
+    Fut: Future<Output = GitResult<T>>,
+{
+    let Some(url) = url.filter(|_| has_token_source()) else {
+        return op(None).await;
+    };
+
+    let credential = prime_token(&url).await?;
+    let retry = credential.is_some();
+    match op(credential).await {
+        Err(e) if retry && super::auth::is_auth_error(&e.to_string()) => {
+            // The rejected credential must not be served again
+            invalidate_credentials(&url);
+            op(prime_token(&url).await?).await
+        }
+        result => result,
+    }
+}
+
+/// Credential for `url` from the repository's configured helpers, served
+/// through [`cached`] like the credentials of clone and fetch
+///
+/// Used by push, where the git CLI can't call back into the cache. Helpers
+/// are only asked when a [`TokenSource`] is registered or the cache is
+/// persisted, and never prompt. Returns `None` for non-HTTP URLs or when no
+/// credential is found, leaving authentication to git itself.
+#[cfg(not(feature = "pure-gix"))]
+pub(crate) fn helper_credential(repo: &gix::Repository, url: &str) -> Option<CachedCredential> {
+    use gix::bstr::ByteSlice;
+
+    let key = host_key_for_url(url).filter(|key| key.starts_with("http"))?;
+    let persisted = {
+        let mut cache = CREDENTIAL_CACHE.lock().ok()?;
+        if let Some(hit) = cache.get(&key) {
+            return Some(hit);
+        }
+        cache.persistence.is_some()
+    };
+    if !persisted && !has_token_source() {
+        return None;
+    }
+
+    let parsed = gix::url::parse(url.as_bytes().as_bstr()).ok()?;
+    let (mut cascade, _, mut prompt_opts) =
+        repo.config_snapshot().credential_helpers(parsed).ok()?;
+    // Nobody is there to answer a prompt
+    prompt_opts.mode = gix::prompt::Mode::Disable;
+    prompt_opts.askpass = None;
+    let outcome = cascade.invoke(Action::get_for_url(url), prompt_opts).ok()??;
+    let gix::sec::identity::Account {
+        username, password, ..
+    } = outcome.identity;
+    // Cached as `cached` would; fall back to the outcome if that failed
+    CREDENTIAL_CACHE
+        .lock()
+        .ok()
+        .and_then(|mut cache| {
+            cache.insert(&key, username.clone(), password.clone());
+            let _ = cache.persist();
+            cache.get(&key)
+        })
+        .or(Some(CachedCredential {
+            username,
+            password,
+            expires_at: unix_now(),
+        }))
+}
+
+/// `git` arguments that answer credential requests from the environment set
+/// by [`cli_credential_env`] instead of the configured helpers
+#[cfg(not(feature = "pure-gix"))]
+pub(crate) const CLI_CREDENTIAL_ARGS: [&str; 4] = [
+    "-c",
+    "credential.helper=",
+    "-c",
+    "credential.helper=!f() { test \"$1\" = get && echo \"username=$KODEGEN_GIT_USERNAME\" \
+     && echo \"password=$KODEGEN_GIT_PASSWORD\"; }; f",
+];
+
+/// Environment for [`CLI_CREDENTIAL_ARGS`]; passed this way the token never
+/// shows up in the command line
+#[cfg(not(feature = "pure-gix"))]
+pub(crate) fn cli_credential_env(credential: &CachedCredential) -> [(String, String); 2] {
+    [
+        ("KODEGEN_GIT_USERNAME".to_string(), credential.username.clone()),
+        ("KODEGEN_GIT_PASSWORD".to_string(), credential.password.clone()),
+    ]
+}
+
+// ============================================================================
+// gix integration
+// ============================================================================
+
+/// Wrap a gix credential function (usually `Connection::configured_credentials()`)
+/// with the global credential cache.
+///
+/// - `Get`: served from the cache when possible (tokens from the
+///   [`TokenSource`] are cached before connecting), otherwise delegated; the
+///   result is cached
+/// - `Store`: delegated, so helpers can persist approved credentials
+/// - `Erase`: invalidates the cached entry (the remote rejected it), then delegated
+#[expect(
+    clippy::result_large_err,
+    reason = "gix fixes the error type of credential functions"
+)]
+pub fn cached(mut fallback: gix::remote::AuthenticateFn<'static>) -> gix::remote::AuthenticateFn<'static> {
+    Box::new(move |action: Action| match action {
+        Action::Get(ctx) => {
+            let key = host_key_for_context(&ctx);
+
+            if let Some(ref key) = key
+                && let Some(hit) = CREDENTIAL_CACHE.lock().ok().and_then(|mut c| c.get(key))
+            {
+                return Ok(Some(identity_outcome(ctx, hit.username, hit.password)));
+            }
+
+            let outcome = fallback(Action::Get(ctx))?;
+            if let (Some(key), Some(outcome)) = (key, outcome.as_ref())
+                && let Ok(mut cache) = CREDENTIAL_CACHE.lock()
+            {
+                cache.insert(
+                    &key,
+                    outcome.identity.username.clone(),
+                    outcome.identity.password.clone(),
+                );
+                let _ = cache.persist();
+            }
+            Ok(outcome)
+        }
+        Action::Erase(payload) => {
+            if let Some(key) = Context::from_bytes(payload.as_slice())
+                .ok()
+                .and_then(|ctx| host_key_for_context(&ctx))
+                && let Ok(mut cache) = CREDENTIAL_CACHE.lock()
+                && cache.invalidate(&key)
+            {
+                let _ = cache.persist();
+            }
+            fallback(Action::Erase(payload))
+        }
+        store @ Action::Store(_) => fallback(store),
+    })
+}
+
+/// Build a complete identity outcome for a `Get` request
+fn identity_outcome(ctx: Context, username: String, password: String) -> Outcome {
+    let next = Context {
+        username: Some(username.clone()),
+        password: Some(password.clone()),
+        ..ctx
+    };
+    Outcome {
+        identity: gix::sec::identity::Account {
+            username,
+            password,
+            oauth_refresh_token: None,
+        },
+        next: NextAction::from(next),
+    }
+}
+
+/// Install the caching credential function on a connection
+pub fn install<T>(connection: &mut gix::remote::Connection<'_, '_, T>) -> GitResult<()>
+where
+    T: gix::protocol::transport::client::blocking_io::Transport,
+{
+    let Some(url) = connection.remote().url(gix::remote::Direction::Fetch).cloned() else {
+        return Ok(());
+    };
+    let fallback = connection
+        .configured_credentials(url)
+        .map_err(GitError::gix)?;
+    connection.set_credentials(cached(fallback));
+    Ok(())
+}
+
+/// Cache key for a credential context: `<protocol>://<host[:port]>`
+fn host_key_for_context(ctx: &Context) -> Option<String> {
+    if let (Some(protocol), Some(host)) = (&ctx.protocol, &ctx.host) {
+        return Some(format!("{protocol}://{host}"));
+    }
+
+    let mut ctx = ctx.clone();
+    ctx.destructure_url_in_place(false).ok()?;
+    Some(format!("{}://{}", ctx.protocol?, ctx.host?))
+}
+
+/// Cache key for a URL, e.g. `https://github.com/owner/repo.git` -> `https://github.com`
+pub fn host_key_for_url(url: &str) -> Option<String> {
+    let ctx = Context {
+        url: Some(url.into()),
+        ..Default::default()
+    };
+    host_key_for_context(&ctx)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Write a file readable only by the current user
+///
+/// The data goes to a fresh temporary file (created with mode 0600 on Unix)
+/// that then replaces `path`, so an existing file's wider permissions are
+/// never reused.
+fn write_private(path: &Path, data: &[u8]) -> GitResult<()> {
+    use std::io::Write;
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(parent)?;
+
+    let mut file = tempfile::NamedTempFile::new_in(parent)?;
+    file.write_all(data)?;
+    file.persist(path).map_err(|e| GitError::Io(e.error))?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! Ref decorations: the branches and tags pointing at commits.
+//!
+//! Mirrors what `git log --decorate` prints next to a commit, so callers can
+//! see where branch heads and releases sit within history.
+
+use std::collections::HashMap;
+
+use gix::bstr::ByteSlice;
+use gix::refs::Category;
+
+use crate::{CommitId, GitError, GitResult};
+
+/// Kind of ref pointing at a commit, in the order decorations are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DecorationKind {
+    /// `HEAD` itself, on a branch or detached.
+    Head,
+    LocalBranch,
+    RemoteBranch,
+    Tag,
+}
+
+/// A ref pointing at a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoration {
+    /// Short name, e.g. `main`, `origin/main` or `v1.0`.
+    pub name: String,
+    pub kind: DecorationKind,
+}
+
+impl std::fmt::Display for Decoration {
+    /// As `git log --decorate` prints it, e.g. `main` or `tag: v1.0`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            DecorationKind::Tag => write!(f, "tag: {}", self.name),
+            _ => f.write_str(&self.name),
+        }
+    }
+}
+
+/// Decorations of every commit that `HEAD`, a branch or a tag points at.
+///
+/// Annotated tags are peeled to their commit. Symbolic refs such as
+/// `origin/HEAD` are skipped as they would repeat their target. Each list
+/// starts with `HEAD`, followed by local branches, remote branches and tags,
+/// each sorted by name.
+pub(crate) fn decorations(
+    repo: &gix::Repository,
+) -> GitResult<HashMap<CommitId, Vec<Decoration>>> {
+    let mut by_commit: HashMap<CommitId, Vec<Decoration>> = HashMap::new();
+
+    if let Ok(head) = repo.head_id() {
+        by_commit.entry(head.detach()).or_default().push(Decoration {
+            name: "HEAD".to_string(),
+            kind: DecorationKind::Head,
+        });
+    }
+
+    let platform = repo.references().map_err(GitError::gix)?;
+    for reference in platform.all().map_err(GitError::gix)? {
+        let mut reference = reference.map_err(GitError::gix)?;
+        if reference.target().try_id().is_none() {
+            continue;
+        }
+        let kind = match reference.name().category() {
+            Some(Category::LocalBranch) => DecorationKind::LocalBranch,
+            Some(Category::RemoteBranch) => DecorationKind::RemoteBranch,
+            Some(Category::Tag) => DecorationKind::Tag,
+            _ => continue,
+        };
+        let name = reference.name().shorten().to_str_lossy().into_owned();
+        // Tags may point at trees or blobs, which have nothing to decorate
+        let Ok(commit) = reference.peel_to_commit() else {
+            continue;
+        };
+        by_commit
+            .entry(commit.id)
+            .or_default()
+            .push(Decoration { name, kind });
+    }
+
+    for decorations in by_commit.values_mut() {
+        decorations.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+    }
+    Ok(by_commit)
+}
@@ -5,7 +5,14 @@
 
 use std::path::PathBuf;
 
-use crate::{GitError, GitResult, RepoHandle};
+use similar::{ChangeTag, TextDiff};
+
+use super::patch_id::is_binary;
+use super::repo_path::RepoPath;
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Lines of context around changes in hunks
+const CONTEXT_LINES: usize = 3;
 
 /// Type of change for a file
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,13 +23,106 @@ pub enum ChangeType {
     Renamed,
 }
 
+/// A file mode change, such as a file becoming executable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeChange {
+    /// Old mode, e.g. `0o100644`
+    pub from: u32,
+    /// New mode, e.g. `0o100755`
+    pub to: u32,
+}
+
+impl std::fmt::Display for ModeChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:06o} → {:06o}", self.from, self.to)
+    }
+}
+
+/// Where a line of a hunk comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOrigin {
+    /// Unchanged line around changes
+    Context,
+    Addition,
+    Deletion,
+}
+
+impl LineOrigin {
+    /// The marker starting the line in a unified diff: ` `, `+` or `-`
+    #[must_use]
+    pub fn marker(self) -> char {
+        match self {
+            Self::Context => ' ',
+            Self::Addition => '+',
+            Self::Deletion => '-',
+        }
+    }
+}
+
+/// A line of a hunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub origin: LineOrigin,
+    /// The line with its newline; the last line of a file may have none
+    pub content: String,
+}
+
+/// A region of changed lines with their context, as in a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// First old line (1-based; the line before the hunk if `old_lines` is 0)
+    pub old_start: usize,
+    pub old_lines: usize,
+    /// First new line (1-based; the line before the hunk if `new_lines` is 0)
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+impl DiffHunk {
+    /// The `@@ -old_start,old_lines +new_start,new_lines @@` header
+    #[must_use]
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_lines, self.new_start, self.new_lines
+        )
+    }
+
+    /// The hunk in unified diff form, with `\ No newline at end of file`
+    /// after lines missing one
+    #[must_use]
+    pub fn to_patch(&self) -> String {
+        let mut patch = self.header();
+        patch.push('\n');
+        for line in &self.lines {
+            patch.push(line.origin.marker());
+            patch.push_str(&line.content);
+            if !line.content.ends_with('\n') {
+                patch.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        patch
+    }
+}
+
 /// Statistics for a single file in a diff
 #[derive(Debug, Clone)]
 pub struct FileDiffStats {
-    pub path: String,
+    /// Path in the repository, exact even if it isn't UTF-8
+    pub path: RepoPath,
     pub change_type: ChangeType,
     pub additions: usize,
     pub deletions: usize,
+    /// Old and new mode if the mode changed; with no additions or deletions
+    /// only the mode changed
+    pub mode_change: Option<ModeChange>,
+    /// Whether the file is a symbolic link on either side; a changed target
+    /// is reported without additions or deletions
+    pub is_symlink: bool,
+    /// Changed lines with context, if requested with [`DiffOpts::hunks`];
+    /// empty for binary files, symlinks and mode-only changes
+    pub hunks: Vec<DiffHunk>,
 }
 
 /// Overall diff statistics
@@ -44,7 +144,7 @@ impl DiffStats {
         }
     }
 
-    fn add_file(&mut self, file: FileDiffStats) {
+    pub(crate) fn add_file(&mut self, file: FileDiffStats) {
         self.total_files_changed += 1;
         self.total_additions += file.additions;
         self.total_deletions += file.deletions;
@@ -67,6 +167,8 @@ pub struct DiffOpts {
     pub to: Option<String>,
     /// Include only files matching this pattern (glob)
     pub filter_path: Option<String>,
+    /// Compute the hunks and exact line counts of each file
+    pub hunks: bool,
 }
 
 impl DiffOpts {
@@ -75,6 +177,7 @@ impl DiffOpts {
             from: from.into(),
             to: None,
             filter_path: None,
+            hunks: false,
         }
     }
 
@@ -87,11 +190,18 @@ impl DiffOpts {
         self.filter_path = Some(path.into());
         self
     }
+
+    /// Compute the hunks of each file, see [`FileDiffStats::hunks`]
+    #[must_use]
+    pub fn hunks(mut self, yes: bool) -> Self {
+        self.hunks = yes;
+        self
+    }
 }
 
 /// Execute diff operation and collect statistics
 pub async fn diff(repo: RepoHandle, opts: DiffOpts) -> GitResult<DiffStats> {
-    let repo_clone = repo.clone_inner();
+    let repo_clone = repo.clone_for_traversal();
 
     tokio::task::spawn_blocking(move || {
         use gix::bstr::ByteSlice;
@@ -101,27 +211,19 @@ pub async fn diff(repo: RepoHandle, opts: DiffOpts) -> GitResult<DiffStats> {
         // Parse 'from' revision
         let from_spec = repo_clone
             .rev_parse_single(opts.from.as_bytes().as_bstr())
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
-        let from_object = repo_clone
-            .find_object(from_spec)
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
-        let from_commit = from_object
-            .try_into_commit()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
-        let from_tree = from_commit.tree().map_err(|e| GitError::Gix(Box::new(e)))?;
+            .map_err(GitError::gix)?;
+        let from_object = repo_clone.find_object(from_spec).map_err(GitError::gix)?;
+        let from_commit = from_object.try_into_commit().map_err(GitError::gix)?;
+        let from_tree = from_commit.tree().map_err(GitError::gix)?;
 
         // Parse 'to' revision or use working directory
         let to_tree = if let Some(to_ref) = opts.to {
             let to_spec = repo_clone
                 .rev_parse_single(to_ref.as_bytes().as_bstr())
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-            let to_object = repo_clone
-                .find_object(to_spec)
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-            let to_commit = to_object
-                .try_into_commit()
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-            Some(to_commit.tree().map_err(|e| GitError::Gix(Box::new(e)))?)
+                .map_err(GitError::gix)?;
+            let to_object = repo_clone.find_object(to_spec).map_err(GitError::gix)?;
+            let to_commit = to_object.try_into_commit().map_err(GitError::gix)?;
+            Some(to_commit.tree().map_err(GitError::gix)?)
         } else {
             // Compare to working directory (use HEAD for now as a simplification)
             // In a full implementation, we'd compare against the index
@@ -129,22 +231,20 @@ pub async fn diff(repo: RepoHandle, opts: DiffOpts) -> GitResult<DiffStats> {
         };
 
         // Perform the diff
-        let mut diff_platform = from_tree
-            .changes()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        let mut diff_platform = from_tree.changes().map_err(GitError::gix)?;
 
         if let Some(to_tree_ref) = to_tree {
             // Diff between two commits
             diff_platform
                 .for_each_to_obtain_tree(&to_tree_ref, |change| {
-                    use gix::object::tree::diff::{Action, Change};
+                    use gix::object::tree::diff::Action;
 
-                    let (location, change_type) = match change {
-                        Change::Addition { location, .. } => (location, ChangeType::Added),
-                        Change::Deletion { location, .. } => (location, ChangeType::Deleted),
-                        Change::Modification { location, .. } => (location, ChangeType::Modified),
-                        Change::Rewrite { location, .. } => (location, ChangeType::Renamed),
-                    };
+                    // Directories are walked into, only their files count
+                    if change.entry_mode().is_tree() {
+                        return Ok::<Action, std::convert::Infallible>(Action::Continue);
+                    }
+                    let location = change.location();
+                    let details = change_details(&change);
 
                     // Apply path filter if specified
                     if let Some(ref filter) = opts.filter_path {
@@ -154,48 +254,34 @@ pub async fn diff(repo: RepoHandle, opts: DiffOpts) -> GitResult<DiffStats> {
                         }
                     }
 
-                    // For now, use placeholder values for additions/deletions
-                    // A full implementation would analyze blob diffs
-                    let (additions, deletions) = match change_type {
-                        ChangeType::Added => (1, 0),
-                        ChangeType::Deleted => (0, 1),
-                        ChangeType::Modified => (1, 1),
-                        ChangeType::Renamed => (0, 0),
-                    };
-
-                    let path_str = location.to_string();
-                    stats.add_file(FileDiffStats {
-                        path: path_str,
-                        change_type,
-                        additions,
-                        deletions,
-                    });
+                    stats.add_file(file_stats(
+                        &repo_clone,
+                        location.into(),
+                        details,
+                        opts.hunks,
+                    ));
 
                     Ok::<Action, std::convert::Infallible>(Action::Continue)
                 })
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
+                .map_err(GitError::gix)?;
         } else {
             // Comparing to working directory - use HEAD tree as target
             // This is a simplification; a full implementation would use the index
-            let head_id = repo_clone.head_id().map_err(|e| GitError::Gix(Box::new(e)))?;
-            let head_object = repo_clone
-                .find_object(head_id)
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-            let head_commit = head_object
-                .try_into_commit()
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-            let head_tree = head_commit.tree().map_err(|e| GitError::Gix(Box::new(e)))?;
+            let head_id = repo_clone.head_id().map_err(GitError::gix)?;
+            let head_object = repo_clone.find_object(head_id).map_err(GitError::gix)?;
+            let head_commit = head_object.try_into_commit().map_err(GitError::gix)?;
+            let head_tree = head_commit.tree().map_err(GitError::gix)?;
 
             diff_platform
                 .for_each_to_obtain_tree(&head_tree, |change| {
-                    use gix::object::tree::diff::{Action, Change};
+                    use gix::object::tree::diff::Action;
 
-                    let (location, change_type) = match change {
-                        Change::Addition { location, .. } => (location, ChangeType::Added),
-                        Change::Deletion { location, .. } => (location, ChangeType::Deleted),
-                        Change::Modification { location, .. } => (location, ChangeType::Modified),
-                        Change::Rewrite { location, .. } => (location, ChangeType::Renamed),
-                    };
+                    // Directories are walked into, only their files count
+                    if change.entry_mode().is_tree() {
+                        return Ok::<Action, std::convert::Infallible>(Action::Continue);
+                    }
+                    let location = change.location();
+                    let details = change_details(&change);
 
                     // Apply path filter if specified
                     if let Some(ref filter) = opts.filter_path {
@@ -205,31 +291,264 @@ pub async fn diff(repo: RepoHandle, opts: DiffOpts) -> GitResult<DiffStats> {
                         }
                     }
 
-                    // For now, use placeholder values for additions/deletions
-                    let (additions, deletions) = match change_type {
-                        ChangeType::Added => (1, 0),
-                        ChangeType::Deleted => (0, 1),
-                        ChangeType::Modified => (1, 1),
-                        ChangeType::Renamed => (0, 0),
-                    };
-
-                    let path_str = location.to_string();
-                    stats.add_file(FileDiffStats {
-                        path: path_str,
-                        change_type,
-                        additions,
-                        deletions,
-                    });
+                    stats.add_file(file_stats(
+                        &repo_clone,
+                        location.into(),
+                        details,
+                        opts.hunks,
+                    ));
 
                     Ok::<Action, std::convert::Infallible>(Action::Continue)
                 })
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
+                .map_err(GitError::gix)?;
         }
 
         Ok(stats)
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
+}
+
+/// Diff `from..to` with per-file line counts; `from: None` diffs against the
+/// empty tree, as for a root commit
+pub(crate) fn diff_with_line_counts(
+    repo: &gix::Repository,
+    from: Option<CommitId>,
+    to: CommitId,
+) -> GitResult<DiffStats> {
+    use gix::object::tree::diff::Action;
+
+    let tree_of = |id: CommitId| -> GitResult<gix::Tree<'_>> {
+        repo.find_commit(id)
+            .map_err(GitError::gix)?
+            .tree()
+            .map_err(GitError::gix)
+    };
+    let from_tree = match from {
+        Some(from) => tree_of(from)?,
+        None => repo.empty_tree(),
+    };
+    let to_tree = tree_of(to)?;
+
+    let mut resource_cache = repo
+        .diff_resource_cache_for_tree_diff()
+        .map_err(GitError::gix)?;
+    let mut stats = DiffStats::new();
+
+    from_tree
+        .changes()
+        .map_err(GitError::gix)?
+        .for_each_to_obtain_tree(&to_tree, |change| {
+            let mode = change.entry_mode();
+            if !mode.is_blob() && !mode.is_link() {
+                return Ok::<_, std::convert::Infallible>(Action::Continue);
+            }
+
+            let details = change_details(&change);
+            // Binary files count as changed without line counts
+            let (additions, deletions) = if !details.content_changed || details.is_symlink {
+                (0, 0)
+            } else {
+                let counts = change
+                    .diff(&mut resource_cache)
+                    .ok()
+                    .and_then(|mut platform| platform.line_counts().ok())
+                    .flatten()
+                    .map_or((0, 0), |counts| {
+                        (counts.insertions as usize, counts.removals as usize)
+                    });
+                resource_cache.clear_resource_cache_keep_allocation();
+                counts
+            };
+
+            stats.add_file(FileDiffStats {
+                path: change.location().into(),
+                change_type: details.change_type,
+                additions,
+                deletions,
+                mode_change: details.mode_change,
+                is_symlink: details.is_symlink,
+                hunks: Vec::new(),
+            });
+            Ok(Action::Continue)
+        })
+        .map_err(GitError::gix)?;
+
+    Ok(stats)
+}
+
+/// What a tree change did to a file, apart from its lines
+struct ChangeDetails {
+    change_type: ChangeType,
+    mode_change: Option<ModeChange>,
+    is_symlink: bool,
+    /// Whether the content (or link target) changed, not just the mode
+    content_changed: bool,
+    old_id: Option<gix::ObjectId>,
+    new_id: Option<gix::ObjectId>,
+}
+
+fn change_details(change: &gix::object::tree::diff::Change<'_, '_, '_>) -> ChangeDetails {
+    use gix::object::tree::diff::Change;
+
+    let (change_type, old, new) = match change {
+        Change::Addition { entry_mode, id, .. } => {
+            (ChangeType::Added, None, Some((*entry_mode, id.detach())))
+        }
+        Change::Deletion { entry_mode, id, .. } => {
+            (ChangeType::Deleted, Some((*entry_mode, id.detach())), None)
+        }
+        Change::Modification {
+            previous_entry_mode,
+            previous_id,
+            entry_mode,
+            id,
+            ..
+        } => (
+            ChangeType::Modified,
+            Some((*previous_entry_mode, previous_id.detach())),
+            Some((*entry_mode, id.detach())),
+        ),
+        Change::Rewrite {
+            source_entry_mode,
+            source_id,
+            entry_mode,
+            id,
+            ..
+        } => (
+            ChangeType::Renamed,
+            Some((*source_entry_mode, source_id.detach())),
+            Some((*entry_mode, id.detach())),
+        ),
+    };
+    let (old_mode, new_mode) = (old.map(|(mode, _)| mode), new.map(|(mode, _)| mode));
+    let (old_id, new_id) = (old.map(|(_, id)| id), new.map(|(_, id)| id));
+    ChangeDetails {
+        change_type,
+        mode_change: mode_change(old_mode, new_mode),
+        is_symlink: is_symlink(old_mode, new_mode),
+        content_changed: old_id != new_id,
+        old_id,
+        new_id,
+    }
+}
+
+/// Stats of one file of [`diff`], with hunks and exact line counts if `hunks`
+fn file_stats(
+    repo: &gix::Repository,
+    path: RepoPath,
+    details: ChangeDetails,
+    hunks: bool,
+) -> FileDiffStats {
+    let hunks = if hunks && details.content_changed && !details.is_symlink {
+        let blob = |id: Option<gix::ObjectId>| match id {
+            Some(id) => repo.find_blob(id).ok().map(|blob| blob.detach().data),
+            None => Some(Vec::new()),
+        };
+        match (blob(details.old_id), blob(details.new_id)) {
+            (Some(old), Some(new)) => Some(diff_hunks(&old, &new)),
+            _ => Some(Vec::new()),
+        }
+    } else {
+        None
+    };
+
+    let (additions, deletions) = match &hunks {
+        Some(hunks) => line_counts(hunks),
+        None if !details.content_changed || details.is_symlink => (0, 0),
+        // Without hunks, use placeholder values for additions/deletions
+        None => match details.change_type {
+            ChangeType::Added => (1, 0),
+            ChangeType::Deleted => (0, 1),
+            ChangeType::Modified => (1, 1),
+            ChangeType::Renamed => (0, 0),
+        },
+    };
+
+    FileDiffStats {
+        path,
+        change_type: details.change_type,
+        additions,
+        deletions,
+        mode_change: details.mode_change,
+        is_symlink: details.is_symlink,
+        hunks: hunks.unwrap_or_default(),
+    }
+}
+
+/// Hunks turning `old` into `new`, with three lines of context; empty if
+/// either is binary
+pub(crate) fn diff_hunks(old: &[u8], new: &[u8]) -> Vec<DiffHunk> {
+    if is_binary(old) || is_binary(new) {
+        return Vec::new();
+    }
+    let old_text = String::from_utf8_lossy(old);
+    let new_text = String::from_utf8_lossy(new);
+    let diff = TextDiff::from_lines(old_text.as_ref(), new_text.as_ref());
+
+    let mut hunks = Vec::new();
+    for hunk in diff.unified_diff().context_radius(CONTEXT_LINES).iter_hunks() {
+        let (Some(first), Some(last)) = (hunk.ops().first(), hunk.ops().last()) else {
+            continue;
+        };
+        let (old_start, new_start) = (first.old_range().start, first.new_range().start);
+        let old_lines = last.old_range().end - old_start;
+        let new_lines = last.new_range().end - new_start;
+        let lines = hunk
+            .iter_changes()
+            .map(|change| DiffLine {
+                origin: match change.tag() {
+                    ChangeTag::Equal => LineOrigin::Context,
+                    ChangeTag::Insert => LineOrigin::Addition,
+                    ChangeTag::Delete => LineOrigin::Deletion,
+                },
+                content: change.value().to_string(),
+            })
+            .collect();
+        hunks.push(DiffHunk {
+            // Empty ranges start at the line before them
+            old_start: if old_lines == 0 { old_start } else { old_start + 1 },
+            old_lines,
+            new_start: if new_lines == 0 { new_start } else { new_start + 1 },
+            new_lines,
+            lines,
+        });
+    }
+    hunks
+}
+
+/// Added and deleted lines of `hunks`
+pub(crate) fn line_counts(hunks: &[DiffHunk]) -> (usize, usize) {
+    hunks
+        .iter()
+        .flat_map(|hunk| &hunk.lines)
+        .fold((0, 0), |(additions, deletions), line| match line.origin {
+            LineOrigin::Addition => (additions + 1, deletions),
+            LineOrigin::Deletion => (additions, deletions + 1),
+            LineOrigin::Context => (additions, deletions),
+        })
+}
+
+/// The mode change between the sides of a file, if both exist and differ
+pub(crate) fn mode_change(
+    old: Option<gix::objs::tree::EntryMode>,
+    new: Option<gix::objs::tree::EntryMode>,
+) -> Option<ModeChange> {
+    match (old, new) {
+        (Some(old), Some(new)) if old != new => Some(ModeChange {
+            from: u32::from(old.value()),
+            to: u32::from(new.value()),
+        }),
+        _ => None,
+    }
+}
+
+/// Whether either side of a file is a symbolic link
+pub(crate) fn is_symlink(
+    old: Option<gix::objs::tree::EntryMode>,
+    new: Option<gix::objs::tree::EntryMode>,
+) -> bool {
+    old.is_some_and(|mode| mode.is_link()) || new.is_some_and(|mode| mode.is_link())
 }
 
 /// Check if a change location matches the filter path.
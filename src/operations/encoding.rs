@@ -0,0 +1,64 @@
+//! Commit message encodings.
+//!
+//! Git stores messages as bytes and names their encoding in the commit's
+//! `encoding` header when it isn't UTF-8, as older tools and
+//! `i18n.commitEncoding` produce for ISO-8859 or Shift_JIS. Messages are
+//! decoded from that encoding instead of being read as lossy UTF-8, and
+//! [`CommitOpts::encoding`](super::commit::CommitOpts::encoding) writes them
+//! in one.
+
+use encoding_rs::Encoding;
+use gix::bstr::{BStr, BString, ByteSlice};
+
+use crate::{GitError, GitResult};
+
+/// Decode `message` from the encoding named by a commit's `encoding` header.
+///
+/// No header, UTF-8 and labels that name no known encoding are read as
+/// UTF-8, replacing invalid sequences, like `git log` does.
+pub(crate) fn decode_message(encoding: Option<&BStr>, message: &BStr) -> String {
+    match encoding.and_then(|label| Encoding::for_label(label.trim())) {
+        Some(encoding) if encoding != encoding_rs::UTF_8 => {
+            encoding.decode_without_bom_handling(message).0.into_owned()
+        }
+        _ => message.to_str_lossy().into_owned(),
+    }
+}
+
+/// Full message of `commit`, decoded as its `encoding` header says
+pub(crate) fn commit_message(commit: &gix::Commit<'_>) -> GitResult<String> {
+    let decoded = commit.decode().map_err(GitError::gix)?;
+    Ok(decode_message(decoded.encoding, decoded.message))
+}
+
+/// Subject of `commit` as `git log --oneline` shows it, empty if the message
+/// can't be read
+pub(crate) fn commit_summary(commit: &gix::Commit<'_>) -> String {
+    commit_message(commit)
+        .map(|message| {
+            gix::objs::commit::MessageRef::from_bytes(message.as_bytes())
+                .summary()
+                .to_str_lossy()
+                .into_owned()
+        })
+        .unwrap_or_default()
+}
+
+/// Encode `message` as `label`, for a commit with that `encoding` header.
+///
+/// Returns `None` for the header if `label` names UTF-8, which git leaves
+/// implicit. Fails if the encoding is unknown or can't represent the message.
+pub(crate) fn encode_message(label: &str, message: &str) -> GitResult<(Option<BString>, BString)> {
+    let encoding = Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| GitError::InvalidInput(format!("Unknown commit encoding '{label}'")))?;
+    if encoding == encoding_rs::UTF_8 {
+        return Ok((None, message.into()));
+    }
+    let (bytes, _, unmappable) = encoding.encode(message);
+    if unmappable {
+        return Err(GitError::InvalidInput(format!(
+            "Commit message can't be represented in {label}"
+        )));
+    }
+    Ok((Some(label.trim().into()), bytes.into_owned().into()))
+}
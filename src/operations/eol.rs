@@ -0,0 +1,301 @@
+//! Line ending normalization.
+//!
+//! Files git treats as text are stored with LF line endings and converted on
+//! checkout as `core.autocrlf` or the `eol` attribute asks. Files committed
+//! before those settings were in place keep their CRLF line endings in the
+//! index, so every checkout shows them as modified. [`eol_audit`] lists them
+//! and [`renormalize`] stages them again with normalized line endings, like
+//! `git add --renormalize .`.
+
+use gix::attrs::StateRef;
+use gix::bstr::ByteSlice;
+
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Line endings found in a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    /// Both LF and CRLF
+    Mixed,
+}
+
+impl LineEnding {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "lf",
+            Self::Crlf => "crlf",
+            Self::Mixed => "mixed",
+        }
+    }
+
+    /// Line endings of `data`; `None` if it has no CRLF or LF at all
+    #[must_use]
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        let crlf = data.find(b"\r\n").is_some();
+        let lf = data
+            .iter()
+            .enumerate()
+            .any(|(index, &byte)| byte == b'\n' && (index == 0 || data[index - 1] != b'\r'));
+        match (crlf, lf) {
+            (true, true) => Some(Self::Mixed),
+            (true, false) => Some(Self::Crlf),
+            (false, true) => Some(Self::Lf),
+            (false, false) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A tracked file whose staged line endings don't match its settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EolIssue {
+    /// Repository-relative path, `/`-separated
+    pub path: String,
+    /// Line endings of the staged content
+    pub found: LineEnding,
+    /// The setting that makes the file text, e.g. `text`, `eol=crlf` or
+    /// `core.autocrlf=true`
+    pub setting: String,
+}
+
+/// Whether a file is normalized on `git add`, and why
+enum Conversion {
+    /// Always, because of the named setting
+    Text(String),
+    /// Unless its content looks binary, because of the named setting
+    Auto(String),
+    None,
+}
+
+impl Conversion {
+    /// The setting normalizing `data`, if it is normalized
+    fn applies_to(&self, data: &[u8]) -> Option<&str> {
+        match self {
+            Self::Text(setting) => Some(setting),
+            Self::Auto(setting) if !is_binary(data) => Some(setting),
+            Self::Auto(_) | Self::None => None,
+        }
+    }
+}
+
+/// List the tracked files whose staged content has CRLF line endings although
+/// `.gitattributes` or `core.autocrlf` make git store them with LF.
+///
+/// Files with a `filter` attribute (such as Git LFS) are skipped, since their
+/// staged content is the filter's output.
+pub async fn eol_audit(repo: RepoHandle) -> GitResult<Vec<EolIssue>> {
+    let repo = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || {
+        let index = repo.index_or_empty().map_err(GitError::gix)?;
+        let mut conversions = Conversions::new(&repo, &index)?;
+        let mut issues = Vec::new();
+        for entry in index.entries() {
+            if !is_regular_file(entry) {
+                continue;
+            }
+            let path = entry.path(&index);
+            let conversion = conversions.at(path)?;
+            if matches!(conversion, Conversion::None) {
+                continue;
+            }
+            let data = repo
+                .find_blob(entry.id)
+                .map_err(GitError::gix)?
+                .detach()
+                .data;
+            let Some(found @ (LineEnding::Crlf | LineEnding::Mixed)) = LineEnding::detect(&data)
+            else {
+                continue;
+            };
+            if let Some(setting) = conversion.applies_to(&data) {
+                issues.push(EolIssue {
+                    path: path.to_str_lossy().into_owned(),
+                    found,
+                    setting: setting.to_string(),
+                });
+            }
+        }
+        Ok(issues)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Stage every tracked text file again with LF line endings, like
+/// `git add --renormalize .`; returns the paths whose staged content changed.
+///
+/// As with git, the working tree content is staged, so text files with other
+/// unstaged changes have those staged too. Files git doesn't treat as text,
+/// symlinks, submodules and files with a `filter` attribute are left alone.
+pub async fn renormalize(repo: RepoHandle) -> GitResult<Vec<String>> {
+    let repo = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || {
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| {
+                GitError::InvalidInput("Cannot renormalize a bare repository".to_string())
+            })?
+            .to_path_buf();
+        let mut index = repo.open_index().map_err(GitError::gix)?;
+
+        let mut updates = Vec::new();
+        {
+            let mut conversions = Conversions::new(&repo, &index)?;
+            for (position, entry) in index.entries().iter().enumerate() {
+                if !is_regular_file(entry) {
+                    continue;
+                }
+                let path = entry.path(&index);
+                let conversion = conversions.at(path)?;
+                if matches!(conversion, Conversion::None) {
+                    continue;
+                }
+                let full_path = workdir.join(gix::path::from_bstr(path));
+                // Deleted in the working tree but still staged
+                let Ok(data) = std::fs::read(&full_path) else {
+                    continue;
+                };
+                if conversion.applies_to(&data).is_none() {
+                    continue;
+                }
+                let normalized = data.replace(b"\r\n", b"\n");
+                let id = repo
+                    .write_blob(&normalized)
+                    .map_err(GitError::gix)?
+                    .detach();
+                if id == entry.id {
+                    continue;
+                }
+                let metadata = gix::index::fs::Metadata::from_path_no_follow(&full_path)?;
+                let stat = gix::index::entry::Stat::from_fs(&metadata).map_err(|e| {
+                    GitError::InvalidInput(format!(
+                        "Failed to create stat for {}: {e}",
+                        full_path.display()
+                    ))
+                })?;
+                updates.push((position, id, stat, path.to_str_lossy().into_owned()));
+            }
+        }
+
+        if updates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let entries = index.entries_mut();
+        let mut changed = Vec::with_capacity(updates.len());
+        for (position, id, stat, path) in updates {
+            entries[position].id = id;
+            entries[position].stat = stat;
+            changed.push(path);
+        }
+        index
+            .write(gix::index::write::Options::default())
+            .map_err(GitError::gix)?;
+        Ok(changed)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Evaluates the line ending attributes of index paths
+struct Conversions<'repo> {
+    attributes: gix::AttributeStack<'repo>,
+    outcome: gix::attrs::search::Outcome,
+    /// `core.autocrlf`, if `true` or `input`
+    autocrlf: Option<&'static str>,
+}
+
+impl<'repo> Conversions<'repo> {
+    fn new(repo: &'repo gix::Repository, index: &gix::index::State) -> GitResult<Self> {
+        let attributes = repo
+            .attributes_only(
+                index,
+                gix::worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )
+            .map_err(GitError::gix)?;
+        let outcome = attributes.selected_attribute_matches(["text", "crlf", "eol", "filter"]);
+        let config = repo.config_snapshot();
+        let autocrlf = match config.string("core.autocrlf") {
+            Some(value) if value.eq_ignore_ascii_case(b"input") => Some("input"),
+            _ => config
+                .boolean("core.autocrlf")
+                .unwrap_or(false)
+                .then_some("true"),
+        };
+        Ok(Self {
+            attributes,
+            outcome,
+            autocrlf,
+        })
+    }
+
+    /// How the file at `path` is converted, following git's precedence:
+    /// `text` over the legacy `crlf`, then `eol`, then `core.autocrlf`
+    fn at(&mut self, path: &gix::bstr::BStr) -> GitResult<Conversion> {
+        self.attributes
+            .at_entry(path, None)?
+            .matching_attributes(&mut self.outcome);
+
+        let mut text = None;
+        let mut crlf = None;
+        let mut eol = None;
+        for matched in self.outcome.iter_selected() {
+            let state = matched.assignment.state;
+            if matches!(state, StateRef::Unspecified) {
+                continue;
+            }
+            match matched.assignment.name.as_str() {
+                // Filtered content can't be normalized without running the filter
+                "filter" => return Ok(Conversion::None),
+                "text" => text = Some(state),
+                "crlf" => crlf = Some(state),
+                "eol" => eol = Some(state),
+                _ => {}
+            }
+        }
+
+        let conversion = match (text, crlf) {
+            (Some(StateRef::Set), _) => Conversion::Text("text".to_string()),
+            (Some(StateRef::Value(value)), _) if value.as_bstr() == "auto" => {
+                Conversion::Auto("text=auto".to_string())
+            }
+            (Some(_), _) => Conversion::None,
+            (None, Some(StateRef::Set)) => Conversion::Text("crlf".to_string()),
+            (None, Some(StateRef::Value(value))) if value.as_bstr() == "input" => {
+                Conversion::Text("crlf=input".to_string())
+            }
+            (None, Some(_)) => Conversion::None,
+            (None, None) => match (eol, self.autocrlf) {
+                (Some(StateRef::Value(value)), _) => {
+                    Conversion::Text(format!("eol={}", value.as_bstr()))
+                }
+                (_, Some(autocrlf)) => Conversion::Auto(format!("core.autocrlf={autocrlf}")),
+                _ => Conversion::None,
+            },
+        };
+        Ok(conversion)
+    }
+}
+
+/// Whether git's `text=auto` would treat `data` as binary: it has a NUL byte
+/// or a CR that doesn't start a CRLF
+fn is_binary(data: &[u8]) -> bool {
+    data.iter()
+        .enumerate()
+        .any(|(index, &byte)| byte == 0 || (byte == b'\r' && data.get(index + 1) != Some(&b'\n')))
+}
+
+/// Whether `entry` is a staged regular file, not a symlink, submodule or conflict
+fn is_regular_file(entry: &gix::index::Entry) -> bool {
+    use gix::index::entry::Mode;
+    (entry.mode == Mode::FILE || entry.mode == Mode::FILE_EXECUTABLE) && entry.stage_raw() == 0
+}
@@ -0,0 +1,204 @@
+//! Export of a revision's files into a plain directory.
+//!
+//! Build pipelines want the files of one commit without a second clone or a
+//! worktree: [`export_tree`] writes them, with `.gitattributes` conversions
+//! applied as in a checkout, into any directory. Nothing else is written
+//! there: no `.git`, no index, no refs.
+
+use std::path::{Path, PathBuf};
+
+use gix::bstr::{BStr, ByteSlice};
+
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// What [`export_tree`] does about files already in the destination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportOverwrite {
+    /// Fail before writing anything if an exported file already exists.
+    #[default]
+    Refuse,
+    /// Replace exported files that exist; leave all other files alone.
+    Overwrite,
+    /// Empty the destination first, so it holds exactly the exported files.
+    Clean,
+}
+
+/// Result of [`export_tree`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportResult {
+    /// Commit exported, `None` when the revision names a tree.
+    pub commit: Option<CommitId>,
+    /// Tree exported.
+    pub tree: gix::ObjectId,
+    /// Destination directory.
+    pub dest: PathBuf,
+    /// Files written, including symlinks.
+    pub files: usize,
+    /// Submodules in the selection, which are left out.
+    pub skipped_submodules: Vec<String>,
+}
+
+/// Write the files of `rev`, or just those under `paths`, into `dest`.
+///
+/// `rev` is any commit, tag or tree. `paths` are files or directories relative
+/// to the repository root; empty exports everything, and a path matching
+/// nothing is an error. `dest` is created if missing and may neither be nor
+/// contain the repository's own worktree or git directory. Executable bits
+/// and symlinks are kept and `.gitattributes` of the exported tree apply;
+/// submodules are left out.
+pub async fn export_tree(
+    repo: RepoHandle,
+    rev: &str,
+    dest: impl AsRef<Path>,
+    paths: &[&str],
+    overwrite: ExportOverwrite,
+) -> GitResult<ExportResult> {
+    let repo_inner = repo.clone_inner();
+    let rev = rev.to_string();
+    let dest = dest.as_ref().to_path_buf();
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| path.trim_start_matches("./").trim_matches('/').to_string())
+        .collect();
+    tokio::task::spawn_blocking(move || export_blocking(&repo_inner, &rev, dest, &paths, overwrite))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+fn export_blocking(
+    repo: &gix::Repository,
+    rev: &str,
+    dest: PathBuf,
+    paths: &[String],
+    overwrite: ExportOverwrite,
+) -> GitResult<ExportResult> {
+    let object = repo
+        .rev_parse_single(rev.as_bytes().as_bstr())
+        .map_err(|e| GitError::InvalidInput(format!("Invalid revision '{rev}': {e}")))?
+        .object()
+        .map_err(GitError::gix)?
+        .peel_tags_to_end()
+        .map_err(GitError::gix)?;
+    let commit = (object.kind == gix::object::Kind::Commit).then_some(object.id);
+    let tree = object.peel_to_tree().map_err(GitError::gix)?.id;
+
+    std::fs::create_dir_all(&dest)?;
+    let dest = dest.canonicalize()?;
+    // Cleaning a directory that contains the repository would delete it
+    let overlaps_git_dir = [repo.git_dir(), repo.common_dir()]
+        .into_iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .any(|dir| dest.starts_with(&dir) || dir.starts_with(&dest));
+    let contains_workdir = repo
+        .workdir()
+        .and_then(|workdir| workdir.canonicalize().ok())
+        .is_some_and(|workdir| workdir.starts_with(&dest));
+    if overlaps_git_dir || contains_workdir {
+        return Err(GitError::InvalidInput(format!(
+            "Cannot export into the repository itself: {}",
+            dest.display()
+        )));
+    }
+
+    // Select the entries to write
+    let mut index = repo.index_from_tree(&tree).map_err(GitError::gix)?;
+    let mut matched = vec![false; paths.len()];
+    let mut skipped_submodules = Vec::new();
+    index.remove_entries(|_, path, entry| {
+        let selected = paths.is_empty() || select(paths, path, &mut matched);
+        if selected && entry.mode == gix::index::entry::Mode::COMMIT {
+            skipped_submodules.push(path.to_str_lossy().into_owned());
+            return true;
+        }
+        !selected
+    });
+    if let Some(missing) = paths
+        .iter()
+        .zip(&matched)
+        .find_map(|(path, matched)| (!matched).then_some(path))
+    {
+        return Err(GitError::InvalidInput(format!(
+            "Path '{missing}' does not exist in {rev}"
+        )));
+    }
+
+    match overwrite {
+        ExportOverwrite::Refuse => {
+            let existing = index
+                .entries()
+                .iter()
+                .map(|entry| entry.path(&index))
+                .find(|path| {
+                    dest.join(gix::path::from_bstr(*path))
+                        .symlink_metadata()
+                        .is_ok()
+                });
+            if let Some(path) = existing {
+                return Err(GitError::InvalidInput(format!(
+                    "'{path}' already exists in {}",
+                    dest.display()
+                )));
+            }
+        }
+        ExportOverwrite::Overwrite => {}
+        ExportOverwrite::Clean => {
+            for entry in std::fs::read_dir(&dest)? {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    std::fs::remove_dir_all(entry.path())?;
+                } else {
+                    std::fs::remove_file(entry.path())?;
+                }
+            }
+        }
+    }
+
+    let mut checkout_opts = repo
+        .checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)
+        .map_err(GitError::gix)?;
+    checkout_opts.overwrite_existing = overwrite == ExportOverwrite::Overwrite;
+    checkout_opts.destination_is_initially_empty = overwrite != ExportOverwrite::Overwrite;
+
+    let outcome = gix::worktree::state::checkout(
+        &mut index,
+        &dest,
+        repo.objects.clone().into_arc().map_err(GitError::gix)?,
+        &gix::progress::Discard,
+        &gix::progress::Discard,
+        &std::sync::atomic::AtomicBool::new(false),
+        checkout_opts,
+    )
+    .map_err(GitError::gix)?;
+    if !outcome.errors.is_empty() || !outcome.collisions.is_empty() {
+        return Err(GitError::InvalidInput(format!(
+            "Export of {rev} encountered {} error(s) and {} collision(s)",
+            outcome.errors.len(),
+            outcome.collisions.len()
+        )));
+    }
+
+    Ok(ExportResult {
+        commit,
+        tree,
+        dest,
+        files: index.entries().len(),
+        skipped_submodules,
+    })
+}
+
+/// Whether `path` is one of `paths` or below one, marking the ones it matches
+fn select(paths: &[String], path: &BStr, matched: &mut [bool]) -> bool {
+    let mut selected = false;
+    let path: &[u8] = path.as_ref();
+    for (wanted, matched) in paths.iter().zip(matched.iter_mut()) {
+        let wanted = wanted.as_bytes();
+        let hit = wanted.is_empty()
+            || path == wanted
+            || (path.starts_with(wanted) && path.get(wanted.len()) == Some(&b'/'));
+        if hit {
+            *matched = true;
+            selected = true;
+        }
+    }
+    selected
+}
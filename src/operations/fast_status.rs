@@ -0,0 +1,440 @@
+//! Fast dirty-state checks for agent loops that poll status
+//!
+//! A full dirty check compares HEAD's tree with the index and stats every
+//! tracked file. When `fast_status` is requested this module avoids repeating
+//! that work:
+//!
+//! - The HEAD-vs-index comparison is cached per repository and reused while
+//!   neither HEAD nor the index file changes.
+//! - If `core.fsmonitor` names a hook (e.g. `fsmonitor-watchman`), it is
+//!   queried with the token from the previous check and only the files it
+//!   reports are re-examined. A hook answer of `/` (everything may have
+//!   changed) or a failing hook falls back to the full scan.
+//! - Untracked files are cached per repository like git's untracked cache:
+//!   the list is reused while the index, the modification time of every
+//!   directory that was walked (which changes when entries are added, removed
+//!   or renamed in it) and the ignore files stay the same, so a repeated
+//!   check stats those directories instead of reading and matching all of
+//!   them. Results from directories changed within the last two seconds
+//!   aren't kept, as a later change could leave their time unchanged.
+//!
+//! The hook comes from repository config, so it only runs for repositories
+//! gix fully trusts (owned by the current user or listed in
+//! `safe.directory`), and only on Unix, where it is run through `sh`.
+//! `core.fsmonitor=true` (the builtin daemon) can't be queried without the
+//! git CLI and also falls back to the full scan.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::process::{Command, Stdio};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime};
+
+use gix::bstr::{BString, ByteSlice};
+
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Last known status per git dir
+static STATUS_CACHE: LazyLock<Mutex<HashMap<PathBuf, CachedStatus>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+struct CachedStatus {
+    /// Index file modification time and size when this was computed
+    index_stamp: Option<(SystemTime, u64)>,
+    /// HEAD commit when this was computed
+    head: Option<gix::ObjectId>,
+    /// Index differs from HEAD's tree
+    staged_dirty: bool,
+    /// Worktree differs from the index (`None` if not computed)
+    worktree_dirty: Option<bool>,
+    /// fsmonitor token to pass on the next query
+    fsmonitor_token: Option<String>,
+}
+
+/// Untracked files per git dir
+static UNTRACKED_CACHE: LazyLock<Mutex<HashMap<PathBuf, CachedUntracked>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Changes younger than this may not have moved a modification time yet
+const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone)]
+struct CachedUntracked {
+    /// Modification time (`None` if missing) of the index, the walked
+    /// directories, their `.gitignore` files and the repository-wide exclude
+    /// files when this was computed
+    stamps: Vec<(PathBuf, Option<SystemTime>)>,
+    /// Untracked files that aren't ignored
+    files: Vec<BString>,
+}
+
+/// Files reported by an fsmonitor hook
+enum FsmonitorChanges {
+    /// The hook can't tell; everything must be rescanned
+    All,
+    /// Only these worktree-relative paths may have changed
+    Paths(Vec<BString>),
+}
+
+/// Check if the working directory is clean, reusing previous results where possible
+///
+/// Same result as [`is_clean`](super::status::is_clean), but repeated calls on
+/// an unchanged repository are much cheaper. See the module docs for details.
+pub async fn is_clean_fast(repo: RepoHandle) -> GitResult<bool> {
+    let repo_clone = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || is_dirty_fast(&repo_clone).map(|dirty| !dirty))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Forget cached status and untracked files for all repositories
+pub fn clear_status_cache() {
+    if let Ok(mut cache) = STATUS_CACHE.lock() {
+        cache.clear();
+    }
+    if let Ok(mut cache) = UNTRACKED_CACHE.lock() {
+        cache.clear();
+    }
+}
+
+/// Dirty check with caching and fsmonitor support
+///
+/// Untracked files don't count, so this alone can't guard a checkout:
+/// commands that write the working tree also go through
+/// [`sync_worktree_to_tree`](super::merge::sync_worktree_to_tree), which
+/// refuses to overwrite untracked files with files of the new tree.
+pub(crate) fn is_dirty_fast(repo: &gix::Repository) -> GitResult<bool> {
+    let key = repo.git_dir().to_path_buf();
+    let index_stamp = file_stamp(&repo.index_path());
+    let head = repo.head_id().ok().map(gix::Id::detach);
+
+    let cached = STATUS_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&key).cloned())
+        .filter(|c| c.index_stamp == index_stamp && c.head == head);
+
+    // Staged changes only depend on HEAD and the index
+    let staged_dirty = match cached {
+        Some(ref c) => c.staged_dirty,
+        None => staged_changes(repo)?,
+    };
+
+    let mut entry = CachedStatus {
+        index_stamp,
+        head,
+        staged_dirty,
+        worktree_dirty: None,
+        fsmonitor_token: None,
+    };
+
+    if staged_dirty {
+        store(key, entry);
+        return Ok(true);
+    }
+
+    let previous_token = cached.as_ref().and_then(|c| c.fsmonitor_token.clone());
+    let query = fsmonitor_query(repo, previous_token.as_deref());
+
+    let worktree_dirty = match (cached.and_then(|c| c.worktree_dirty), &query) {
+        // Nothing touched since the last check
+        (Some(dirty), Some((_, FsmonitorChanges::Paths(paths)))) if paths.is_empty() => dirty,
+        // Was clean: only the reported files can have made it dirty
+        (Some(false), Some((_, FsmonitorChanges::Paths(paths)))) => {
+            worktree_changes(repo, literal_patterns(paths))?
+        }
+        _ => worktree_changes(repo, Vec::new())?,
+    };
+
+    entry.worktree_dirty = Some(worktree_dirty);
+    entry.fsmonitor_token = query.map(|(token, _)| token);
+    store(key, entry);
+
+    Ok(worktree_dirty)
+}
+
+fn store(key: PathBuf, entry: CachedStatus) {
+    if let Ok(mut cache) = STATUS_CACHE.lock() {
+        cache.insert(key, entry);
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Whether the index differs from HEAD's tree
+pub(crate) fn staged_changes(repo: &gix::Repository) -> GitResult<bool> {
+    let head_tree_id = repo.head_tree_id_or_empty().map_err(GitError::gix)?;
+    let index = repo.index_or_empty().map_err(GitError::gix)?;
+    if index.is_sparse() {
+        // Compare collapsed directories by tree id instead of expanding them
+        return super::sparse::staged_changes(repo, head_tree_id.detach(), &index);
+    }
+
+    let mut dirty = false;
+    repo.tree_index_status(
+        &head_tree_id,
+        &index,
+        None,
+        gix::status::tree_index::TrackRenames::Disabled,
+        |_, _, _| {
+            dirty = true;
+            Ok::<_, Infallible>(gix::diff::index::Action::Cancel)
+        },
+    )
+    .map_err(GitError::gix)?;
+
+    Ok(dirty)
+}
+
+/// Whether tracked files in the worktree differ from the index (limited to `patterns` if any)
+pub(crate) fn worktree_changes(repo: &gix::Repository, patterns: Vec<BString>) -> GitResult<bool> {
+    let mut changes = repo
+        .status(gix::progress::Discard)
+        .map_err(GitError::gix)?
+        .index_worktree_rewrites(None)
+        .index_worktree_submodules(gix::status::Submodule::AsConfigured { check_dirty: true })
+        .index_worktree_options_mut(|opts| {
+            opts.dirwalk_options = None;
+        })
+        .into_index_worktree_iter(patterns)
+        .map_err(GitError::gix)?;
+
+    // A file that can't be checked must not pass for clean
+    match changes.next() {
+        Some(change) => change.map(|_| true).map_err(GitError::gix),
+        None => Ok(false),
+    }
+}
+
+/// Whether the working tree has untracked files that aren't ignored
+pub(crate) fn has_untracked(repo: &gix::Repository) -> GitResult<bool> {
+    untracked_files(repo).map(|files| !files.is_empty())
+}
+
+/// Untracked files of the working tree that aren't ignored, one path each
+///
+/// Served from the untracked cache while it is valid (see the module docs).
+pub(crate) fn untracked_files(repo: &gix::Repository) -> GitResult<Vec<BString>> {
+    let key = repo.git_dir().to_path_buf();
+    let cached = UNTRACKED_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&key).cloned());
+    if let Some(cached) = cached
+        && cached
+            .stamps
+            .iter()
+            .all(|(path, stamp)| modified(path) == *stamp)
+    {
+        return Ok(cached.files);
+    }
+
+    let scan_start = SystemTime::now();
+    let stamps = untracked_stamps(repo)?;
+    let files = walk_untracked(repo)?;
+    let racy = stamps
+        .iter()
+        .filter_map(|(_, stamp)| *stamp)
+        .any(|stamp| stamp + RACY_WINDOW > scan_start);
+    if let Ok(mut cache) = UNTRACKED_CACHE.lock() {
+        if racy {
+            cache.remove(&key);
+        } else {
+            cache.insert(
+                key,
+                CachedUntracked {
+                    stamps,
+                    files: files.clone(),
+                },
+            );
+        }
+    }
+    Ok(files)
+}
+
+/// List untracked files that aren't ignored by walking the working tree
+fn walk_untracked(repo: &gix::Repository) -> GitResult<Vec<BString>> {
+    use gix::dir::entry::Status;
+    use gix::status::index_worktree::Item;
+
+    let iter = repo
+        .status(gix::progress::Discard)
+        .map_err(GitError::gix)?
+        .untracked_files(gix::status::UntrackedFiles::Files)
+        .index_worktree_rewrites(None)
+        .into_index_worktree_iter(Vec::<BString>::new())
+        .map_err(GitError::gix)?;
+    let mut paths = Vec::new();
+    for item in iter {
+        if let Item::DirectoryContents { entry, .. } = item.map_err(GitError::gix)?
+            && entry.status == Status::Untracked
+        {
+            paths.push(entry.rela_path);
+        }
+    }
+    Ok(paths)
+}
+
+/// What the untracked files of `repo` depend on: the index, the repository
+/// config and exclude files, and every directory that isn't ignored (nested
+/// repositories and `.git` excluded) with its `.gitignore`
+fn untracked_stamps(repo: &gix::Repository) -> GitResult<Vec<(PathBuf, Option<SystemTime>)>> {
+    let mut files = vec![
+        repo.index_path(),
+        repo.common_dir().join("config"),
+        repo.git_dir().join("info").join("exclude"),
+    ];
+    let user_excludes = repo
+        .config_snapshot()
+        .trusted_path("core.excludesFile")
+        .and_then(Result::ok)
+        .map(std::borrow::Cow::into_owned)
+        .or_else(|| gix::path::env::xdg_config("ignore", &mut |name| std::env::var_os(name)));
+    files.extend(user_excludes);
+    let mut stamps: Vec<_> = files
+        .into_iter()
+        .map(|path| {
+            let stamp = modified(&path);
+            (path, stamp)
+        })
+        .collect();
+
+    let Some(workdir) = repo.workdir() else {
+        return Ok(stamps);
+    };
+    let index = repo.index_or_empty().map_err(GitError::gix)?;
+    let mut excludes = repo
+        .excludes(
+            &index,
+            None,
+            gix::worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+        )
+        .map_err(GitError::gix)?;
+
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(relative) = dirs.pop() {
+        let dir = workdir.join(&relative);
+        let gitignore = dir.join(".gitignore");
+        stamps.push((dir.clone(), modified(&dir)));
+        stamps.push((gitignore.clone(), modified(&gitignore)));
+
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() || entry.file_name() == ".git" {
+                continue;
+            }
+            let path = entry.path();
+            if path == repo.git_dir() || path.join(".git").exists() {
+                continue;
+            }
+            let relative = relative.join(entry.file_name());
+            if excludes
+                .at_path(&relative, Some(gix::index::entry::Mode::DIR))?
+                .is_excluded()
+            {
+                continue;
+            }
+            dirs.push(relative);
+        }
+    }
+    Ok(stamps)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Pathspecs matching exactly the given paths
+fn literal_patterns(paths: &[BString]) -> Vec<BString> {
+    paths
+        .iter()
+        .map(|p| {
+            let mut pattern = BString::from(":(literal)");
+            pattern.extend_from_slice(p);
+            pattern
+        })
+        .collect()
+}
+
+/// Query the configured fsmonitor hook (protocol version 2)
+///
+/// Returns the new token and the changed paths, or `None` if no hook is
+/// configured, the repository isn't fully trusted or the hook failed.
+#[cfg(unix)]
+fn fsmonitor_query(
+    repo: &gix::Repository,
+    token: Option<&str>,
+) -> Option<(String, FsmonitorChanges)> {
+    // The hook is a command from repository config; never run it for a
+    // repository owned by someone else
+    if repo.git_dir_trust() != gix::sec::Trust::Full {
+        return None;
+    }
+
+    let config = repo.config_snapshot();
+    let hook = config.string("core.fsmonitor")?.to_str_lossy().trim().to_string();
+
+    // Booleans select the builtin daemon (or disable fsmonitor)
+    if hook.is_empty() || config.boolean("core.fsmonitor").is_some() {
+        return None;
+    }
+    if config
+        .integer("core.fsmonitorHookVersion")
+        .is_some_and(|version| version != 2)
+    {
+        return None;
+    }
+
+    let workdir = repo.workdir()?;
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{hook} \"$@\""))
+        .arg(&hook)
+        .arg("2")
+        .arg(token.unwrap_or(""))
+        .current_dir(workdir)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+
+    parse_fsmonitor_output(&output.stdout)
+}
+
+/// fsmonitor hooks are shell commands, only run on Unix
+#[cfg(not(unix))]
+fn fsmonitor_query(
+    _repo: &gix::Repository,
+    _token: Option<&str>,
+) -> Option<(String, FsmonitorChanges)> {
+    None
+}
+
+#[cfg(unix)]
+/// Parse `<token>\0<path>\0<path>\0...` as written by v2 fsmonitor hooks
+fn parse_fsmonitor_output(stdout: &[u8]) -> Option<(String, FsmonitorChanges)> {
+    let mut fields = stdout.split(|b| *b == 0);
+    let token = fields.next()?.to_str().ok()?.to_string();
+    if token.is_empty() {
+        return None;
+    }
+
+    let mut paths = Vec::new();
+    for field in fields.filter(|f| !f.is_empty()) {
+        if field == b"/" {
+            return Some((token, FsmonitorChanges::All));
+        }
+        // Directories are reported with a trailing slash
+        paths.push(BString::from(field.strip_suffix(b"/").unwrap_or(field)));
+    }
+
+    Some((token, FsmonitorChanges::Paths(paths)))
+}
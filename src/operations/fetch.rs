@@ -10,8 +10,11 @@ use gix::bstr::ByteSlice;
 use gix::progress::Discard;
 use gix::remote::ref_map;
 
-use super::auth;
-use crate::{GitError, GitResult, RepoHandle};
+use super::fetch_verify::{FetchVerification, Quarantine, verify_received, withheld_refs};
+use super::shallow::Deepen;
+use super::warnings::{OperationWarning, WarningKind};
+use super::{auth, credentials};
+use crate::{CommitId, GitError, GitResult, RepoHandle};
 
 /// Options for `fetch` operation with builder pattern.
 #[derive(Debug, Clone)]
@@ -19,6 +22,9 @@ pub struct FetchOpts {
     pub remote: String,
     pub refspecs: Vec<String>,
     pub prune: bool,
+    pub deepen: Option<Deepen>,
+    /// Check the received data before keeping it, see [`super::fetch_verify`].
+    pub verify: bool,
 }
 
 impl FetchOpts {
@@ -29,6 +35,8 @@ impl FetchOpts {
             remote: remote.into(),
             refspecs: Vec::new(),
             prune: false,
+            deepen: None,
+            verify: false,
         }
     }
 
@@ -44,6 +52,21 @@ impl FetchOpts {
         self.prune = yes;
         self
     }
+
+    /// Extend the history of a shallow repository while fetching.
+    #[must_use]
+    pub fn deepen(mut self, deepen: Deepen) -> Self {
+        self.deepen = Some(deepen);
+        self
+    }
+
+    /// Verify the received pack and objects, keeping neither them nor any
+    /// ref updates if some are missing or corrupt.
+    #[must_use]
+    pub fn verify(mut self, yes: bool) -> Self {
+        self.verify = yes;
+        self
+    }
 }
 
 impl Default for FetchOpts {
@@ -52,32 +75,107 @@ impl Default for FetchOpts {
             remote: "origin".to_string(),
             refspecs: Vec::new(),
             prune: false,
+            deepen: None,
+            verify: false,
         }
     }
 }
 
+/// How a fetch changed a local ref.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefUpdateKind {
+    /// The ref did not exist before.
+    New,
+    FastForward,
+    /// The ref was moved to a commit that does not contain its old one.
+    Forced,
+    /// The update was refused, with the reason.
+    Rejected(String),
+}
+
+/// A local ref changed (or refused to change) by a fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefUpdate {
+    /// Full name of the local ref, e.g. `refs/remotes/origin/main`.
+    pub name: String,
+    pub old: Option<CommitId>,
+    pub new: Option<CommitId>,
+    pub kind: RefUpdateKind,
+}
+
+/// Result of a fetch operation.
+#[derive(Debug, Clone, Default)]
+pub struct FetchResult {
+    /// Refs that were created or moved, tags other than new ones included.
+    pub updated: Vec<RefUpdate>,
+    /// Tags created by the fetch.
+    pub new_tags: Vec<String>,
+    /// Remote-tracking refs deleted by pruning.
+    pub pruned: Vec<String>,
+    /// Objects in the received pack.
+    pub objects_received: u32,
+    /// Size of the received pack.
+    pub bytes_received: u64,
+    /// What verification found, if it was asked for.
+    pub verification: Option<FetchVerification>,
+    /// Problems worked around, such as a stale ref pruning couldn't delete.
+    pub warnings: Vec<OperationWarning>,
+}
+
+impl FetchResult {
+    /// Whether the fetch left all local refs as they were.
+    #[must_use]
+    pub fn is_up_to_date(&self) -> bool {
+        self.updated.is_empty() && self.new_tags.is_empty() && self.pruned.is_empty()
+    }
+}
+
 /// Execute fetch operation with the given options.
-pub async fn fetch(repo: RepoHandle, opts: FetchOpts) -> GitResult<()> {
-    let repo_clone = repo.clone_inner();
+///
+/// With a registered [token source](credentials::TokenSource), a fetch rejected
+/// for authentication is retried once with a freshly minted token.
+pub async fn fetch(repo: RepoHandle, opts: FetchOpts) -> GitResult<FetchResult> {
+    let url = auth::remote_url(repo.raw(), &opts.remote, gix::remote::Direction::Fetch);
+    let repo = repo.clone_inner();
+    credentials::with_token(url, move |_| fetch_once(repo.clone(), opts.clone())).await
+}
 
+/// Single fetch attempt
+async fn fetch_once(mut repo_clone: gix::Repository, opts: FetchOpts) -> GitResult<FetchResult> {
     tokio::task::spawn_blocking(move || {
         let FetchOpts {
             remote,
             refspecs,
             prune,
+            deepen,
+            verify,
         } = opts;
 
+        // Apply SSH host key policy to this clone's in-memory config
+        auth::configure_repo(&mut repo_clone)?;
+
         // Store remote name for pruning
         let remote_name = remote.clone();
 
+        // Fetch into a quarantine first when the data is to be verified
+        let quarantine = if verify {
+            Some(Quarantine::enter(&mut repo_clone)?)
+        } else {
+            None
+        };
+
         // Find the remote
         let remote_bstr = remote.as_bytes().as_bstr();
         let remote_ref = repo_clone
-            .find_remote(remote_bstr)
-            .map_err(|e| GitError::InvalidInput(format!("Remote '{remote}' not found: {e}")))?;
+            .try_find_remote_without_url_rewrite(remote_bstr)
+            .ok_or_else(|| GitError::RemoteNotFound(remote.clone()))?
+            .map_err(GitError::gix)?;
+
+        // One rewrite from config and registered `insteadOf` rules combined
+        let remote_ref = auth::rewrite_remote_urls(&repo_clone, remote_ref)?;
 
         // Connect to the remote
-        let connection = remote_ref
+        let mut connection = remote_ref
             .connect(gix::remote::Direction::Fetch)
             .map_err(|e| {
                 let err_str = e.to_string();
@@ -90,10 +188,13 @@ pub async fn fetch(repo: RepoHandle, opts: FetchOpts) -> GitResult<()> {
                         .unwrap_or_else(|| remote.clone());
                     GitError::InvalidInput(auth::auth_error_message(&url))
                 } else {
-                    GitError::Gix(e.into())
+                    GitError::gix(e)
                 }
             })?;
 
+        // Serve credentials from the cache instead of re-invoking helpers
+        credentials::install(&mut connection)?;
+
         // Parse custom refspecs if provided
         let parsed_refspecs = if refspecs.is_empty() {
             Vec::new()
@@ -118,32 +219,136 @@ pub async fn fetch(repo: RepoHandle, opts: FetchOpts) -> GitResult<()> {
         };
 
         // Prepare fetch operation
-        let fetch_prep = connection
+        let mut fetch_prep = connection
             .prepare_fetch(Discard, ref_map_options)
-            .map_err(|e| GitError::Gix(e.into()))?;
+            .map_err(GitError::gix)?;
+        if let Some(deepen) = deepen {
+            fetch_prep = fetch_prep.with_shallow(deepen.to_shallow());
+        }
 
         // Execute the fetch
         let outcome = fetch_prep
             .receive(Discard, &AtomicBool::new(false))
-            .map_err(|e| GitError::Gix(e.into()))?;
+            .map_err(GitError::gix)?;
+
+        let mut result = fetch_result(&repo_clone, &outcome);
+
+        // Keep the fetch only if it brought in no missing or damaged objects
+        if let Some(quarantine) = quarantine {
+            let mut report = verify_received(&repo_clone, &outcome);
+            if !report.is_ok() {
+                report.withheld_refs = withheld_refs(&result);
+                return Err(GitError::VerificationFailed(Box::new(report)));
+            }
+            quarantine.release(&mut repo_clone, &outcome)?;
+            result.verification = Some(report);
+        }
 
         // Implement pruning if enabled
         if prune {
-            prune_stale_refs(&repo_clone, &remote_name, &outcome.ref_map)?;
+            result.pruned = prune_stale_refs(
+                &repo_clone,
+                &remote_name,
+                &outcome.ref_map,
+                &mut result.warnings,
+            )?;
         }
 
-        Ok(())
+        Ok(result)
     })
     .await
     .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
 }
 
-/// Helper function to prune stale remote-tracking refs
+/// Collect the ref updates and pack statistics of a fetch
+fn fetch_result(repo: &gix::Repository, outcome: &gix::remote::fetch::Outcome) -> FetchResult {
+    use gix::refs::transaction::{Change, PreviousValue};
+    use gix::remote::fetch::refs::update::Mode;
+    use gix::remote::fetch::Status;
+
+    let mut result = FetchResult::default();
+    let update_refs = match &outcome.status {
+        Status::Change {
+            write_pack_bundle,
+            update_refs,
+            ..
+        } => {
+            result.objects_received = write_pack_bundle.index.num_objects;
+            if let Some(path) = &write_pack_bundle.data_path {
+                match std::fs::metadata(path) {
+                    Ok(meta) => result.bytes_received = meta.len(),
+                    Err(e) => result.warnings.push(
+                        OperationWarning::new(
+                            WarningKind::PackSizeUnknown,
+                            format!("pack size could not be read ({e}); reporting 0 bytes"),
+                        )
+                        .subject(path.display().to_string()),
+                    ),
+                }
+            }
+            update_refs
+        }
+        Status::NoPackReceived { update_refs, .. } => update_refs,
+    };
+
+    let ref_map = &outcome.ref_map;
+    let updates = update_refs.iter_mapping_updates(
+        &ref_map.mappings,
+        &ref_map.refspecs,
+        &ref_map.extra_refspecs,
+    );
+    for (update, mapping, _, edit) in updates {
+        let kind = match &update.mode {
+            Mode::NoChangeNeeded | Mode::ImplicitTagNotSentByRemote => continue,
+            Mode::New => RefUpdateKind::New,
+            Mode::FastForward => RefUpdateKind::FastForward,
+            Mode::Forced => RefUpdateKind::Forced,
+            rejected => RefUpdateKind::Rejected(rejected.to_string()),
+        };
+        let Some(name) = edit
+            .map(|edit| edit.name.as_bstr().to_str_lossy().into_owned())
+            .or_else(|| mapping.local.as_ref().map(|local| local.to_str_lossy().into_owned()))
+        else {
+            continue;
+        };
+
+        let (old, new) = match edit.map(|edit| &edit.change) {
+            Some(Change::Update { expected, new, .. }) => {
+                let old = match expected {
+                    PreviousValue::MustExistAndMatch(target)
+                    | PreviousValue::ExistingMustMatch(target) => {
+                        target.try_id().map(ToOwned::to_owned)
+                    }
+                    _ => None,
+                };
+                (old, new.try_id().map(ToOwned::to_owned))
+            }
+            _ => (
+                repo.find_reference(name.as_str())
+                    .ok()
+                    .and_then(|mut reference| reference.peel_to_id().ok())
+                    .map(gix::Id::detach),
+                mapping.remote.as_id().map(ToOwned::to_owned),
+            ),
+        };
+
+        if kind == RefUpdateKind::New && name.starts_with("refs/tags/") {
+            result.new_tags.push(name);
+        } else {
+            result.updated.push(RefUpdate { name, old, new, kind });
+        }
+    }
+    result
+}
+
+/// Helper function to prune stale remote-tracking refs, returning the names
+/// of those deleted; refs that can't be deleted are kept with a warning
 fn prune_stale_refs(
     repo: &gix::Repository,
     remote_name: &str,
     ref_map: &gix::remote::fetch::RefMap,
-) -> GitResult<()> {
+    warnings: &mut Vec<OperationWarning>,
+) -> GitResult<Vec<String>> {
     use gix::protocol::handshake::Ref;
 
     // Build set of branch names that exist on the remote
@@ -172,14 +377,24 @@ fn prune_stale_refs(
     let mut refs_to_delete = Vec::new();
 
     // Iterate through local remote-tracking refs
-    let all_refs = repo.references().map_err(|e| GitError::Gix(e.into()))?;
+    let all_refs = repo.references().map_err(GitError::gix)?;
 
-    for reference_result in all_refs.all().map_err(|e| GitError::Gix(e.into()))? {
-        let reference = reference_result.map_err(GitError::Gix)?;
+    for reference_result in all_refs.all().map_err(GitError::gix)? {
+        let reference = reference_result.map_err(GitError::gix)?;
         let ref_name = reference.name().as_bstr().to_str_lossy();
 
         // Check if this is a remote-tracking ref for our remote
         if let Some(branch_name) = ref_name.strip_prefix(&prefix) {
+            // Symbolic refs such as origin/HEAD stay while their target does
+            if let gix::refs::TargetRef::Symbolic(target) = reference.target() {
+                let target = target.as_bstr().to_str_lossy();
+                if target
+                    .strip_prefix(&prefix)
+                    .is_some_and(|target| remote_branches.contains(target))
+                {
+                    continue;
+                }
+            }
             // If the branch doesn't exist on the remote, mark it for deletion
             if !remote_branches.contains(branch_name) {
                 refs_to_delete.push(reference);
@@ -188,9 +403,20 @@ fn prune_stale_refs(
     }
 
     // Delete stale refs
+    let mut pruned = Vec::with_capacity(refs_to_delete.len());
     for reference in refs_to_delete {
-        reference.delete().map_err(|e| GitError::Gix(e.into()))?;
+        let name = reference.name().as_bstr().to_str_lossy().into_owned();
+        match reference.delete() {
+            Ok(()) => pruned.push(name),
+            Err(e) => warnings.push(
+                OperationWarning::new(
+                    WarningKind::PruneFailed,
+                    format!("stale ref could not be deleted ({e}); keeping it"),
+                )
+                .subject(name),
+            ),
+        }
     }
 
-    Ok(())
+    Ok(pruned)
 }
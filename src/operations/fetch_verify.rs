@@ -0,0 +1,363 @@
+//! Verification of fetched data.
+//!
+//! A proxy that truncates or mangles a transfer can leave refs pointing at
+//! objects that are missing or unreadable, and the repository only breaks
+//! later. With [`FetchOpts::verify`](super::fetch::FetchOpts::verify) or
+//! [`CloneOpts::verify`](super::clone::CloneOpts::verify) the received pack is
+//! checked against its checksums and every object it brings in, from the
+//! fetched refs down to objects already present, is read back before the
+//! fetch counts as done. A verified fetch goes to a quarantine first, so
+//! a failed one leaves the repository untouched.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use gix::progress::Discard;
+
+use super::fetch::FetchResult;
+use crate::{GitError, GitResult};
+
+/// What verifying a fetch found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchVerification {
+    /// Whether a pack was received and checked.
+    pub pack_checked: bool,
+    /// Objects of the received pack that were read back.
+    pub objects_checked: usize,
+    /// Objects the fetched refs need that are in neither the pack nor the
+    /// repository.
+    pub missing: Vec<gix::ObjectId>,
+    /// Objects that could not be read or decoded, with the reason.
+    pub corrupt: Vec<(gix::ObjectId, String)>,
+    /// Pack or index files that don't match their checksum.
+    pub pack_errors: Vec<String>,
+    /// Refs the fetch would have created or moved, left as they were.
+    pub withheld_refs: Vec<String>,
+}
+
+impl FetchVerification {
+    /// Whether nothing is missing or corrupt.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.corrupt.is_empty() && self.pack_errors.is_empty()
+    }
+}
+
+impl std::fmt::Display for FetchVerification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} missing object(s), {} corrupt object(s), {} damaged pack file(s)",
+            self.missing.len(),
+            self.corrupt.len(),
+            self.pack_errors.len()
+        )?;
+        if let Some(id) = self.missing.first() {
+            write!(f, "; first missing: {id}")?;
+        }
+        if let Some((id, reason)) = self.corrupt.first() {
+            write!(f, "; first corrupt: {id} ({reason})")?;
+        }
+        if let Some(error) = self.pack_errors.first() {
+            write!(f, "; {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Pack files written by a fetch
+pub(crate) struct ReceivedPack {
+    data: Option<PathBuf>,
+    index: Option<PathBuf>,
+    keep: Option<PathBuf>,
+}
+
+impl ReceivedPack {
+    pub(crate) fn of(outcome: &gix::remote::fetch::Outcome) -> Option<Self> {
+        match &outcome.status {
+            gix::remote::fetch::Status::Change {
+                write_pack_bundle, ..
+            } => Some(Self {
+                data: write_pack_bundle.data_path.clone(),
+                index: write_pack_bundle.index_path.clone(),
+                keep: write_pack_bundle.keep_path.clone(),
+            }),
+            gix::remote::fetch::Status::NoPackReceived { .. } => None,
+        }
+    }
+
+    /// Move the pack into `dir`, the data ahead of the index that makes it
+    /// visible
+    fn move_to(&self, dir: &Path) -> GitResult<()> {
+        for path in [&self.data, &self.index, &self.keep].into_iter().flatten() {
+            if let Some(name) = path.file_name() {
+                std::fs::rename(path, dir.join(name))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check the received pack and everything the fetched refs point at.
+///
+/// Objects in the pack are read and followed; objects outside it only have
+/// to exist, as the repository had them before. Parents of shallow commits
+/// are not expected to exist.
+pub(crate) fn verify_received(
+    repo: &gix::Repository,
+    outcome: &gix::remote::fetch::Outcome,
+) -> FetchVerification {
+    let mut report = FetchVerification::default();
+    let hash = repo.object_hash();
+    let interrupt = AtomicBool::new(false);
+
+    let mut index = None;
+    if let Some(pack) = ReceivedPack::of(outcome) {
+        report.pack_checked = true;
+        if let Some(path) = &pack.data {
+            let checked = gix::odb::pack::data::File::at(path, hash)
+                .map_err(|e| e.to_string())
+                .and_then(|file| {
+                    file.verify_checksum(&mut Discard, &interrupt)
+                        .map_err(|e| e.to_string())
+                });
+            if let Err(e) = checked {
+                report.pack_errors.push(format!("{}: {e}", path.display()));
+            }
+        }
+        if let Some(path) = &pack.index {
+            match gix::odb::pack::index::File::at(path, hash) {
+                Ok(file) => {
+                    if let Err(e) = file.verify_checksum(&mut Discard, &interrupt) {
+                        report.pack_errors.push(format!("{}: {e}", path.display()));
+                    }
+                    index = Some(file);
+                }
+                Err(e) => report.pack_errors.push(format!("{}: {e}", path.display())),
+            }
+        }
+    }
+
+    let shallow: HashSet<gix::ObjectId> = repo
+        .shallow_commits()
+        .ok()
+        .flatten()
+        .map(|commits| commits.iter().copied().collect())
+        .unwrap_or_default();
+    let mut stack: Vec<gix::ObjectId> = outcome
+        .ref_map
+        .mappings
+        .iter()
+        .filter_map(|mapping| mapping.remote.as_id().map(ToOwned::to_owned))
+        .collect();
+    let mut seen = HashSet::new();
+    while let Some(id) = stack.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let in_pack = index.as_ref().is_some_and(|index| index.lookup(id).is_some());
+        if !in_pack {
+            if !repo.has_object(id) {
+                report.missing.push(id);
+            }
+            continue;
+        }
+
+        report.objects_checked += 1;
+        let object = match repo.try_find_object(id) {
+            Ok(Some(object)) => object,
+            Ok(None) => {
+                report.missing.push(id);
+                continue;
+            }
+            Err(e) => {
+                report.corrupt.push((id, e.to_string()));
+                continue;
+            }
+        };
+        let followed = match object.kind {
+            gix::object::Kind::Commit => object.into_commit().decode().map(|commit| {
+                stack.push(commit.tree());
+                if !shallow.contains(&id) {
+                    stack.extend(commit.parents());
+                }
+            }),
+            gix::object::Kind::Tree => object.into_tree().decode().map(|tree| {
+                stack.extend(
+                    tree.entries
+                        .iter()
+                        .filter(|entry| !entry.mode.is_commit())
+                        .map(|entry| entry.oid.to_owned()),
+                );
+            }),
+            gix::object::Kind::Tag => object
+                .into_tag()
+                .decode()
+                .map(|tag| stack.push(tag.target())),
+            gix::object::Kind::Blob => Ok(()),
+        };
+        if let Err(e) = followed {
+            report.corrupt.push((id, e.to_string()));
+        }
+    }
+    report.missing.sort();
+    report.corrupt.sort();
+    report
+}
+
+/// A private object directory and copy of the refs a verified fetch goes to.
+///
+/// The pack lands in the quarantine and ref updates are worked out against
+/// the copied refs, so nothing reaches the repository until
+/// [`Quarantine::release`] moves the pack over and applies the ref edits,
+/// expecting every ref to still be where the fetch found it. Dropping the
+/// quarantine discards it and puts the shallow file back.
+pub(crate) struct Quarantine {
+    root: PathBuf,
+    objects: gix::OdbHandle,
+    refs: gix::RefStore,
+    shallow_file: PathBuf,
+    shallow: Option<Vec<u8>>,
+    released: bool,
+}
+
+impl Quarantine {
+    /// Point `repo` at a new quarantine, remembering its real objects and refs
+    pub(crate) fn enter(repo: &mut gix::Repository) -> GitResult<Self> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let objects_dir = repo.objects.store_ref().path().to_owned();
+        let root = objects_dir.join(format!(
+            "tmp_objdir-fetch-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let shallow_file = repo.shallow_file();
+        let shallow = match std::fs::read(&shallow_file) {
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(GitError::Io(e)),
+        };
+        std::fs::create_dir(&root)?;
+        let quarantine = Self {
+            root,
+            objects: repo.objects.clone(),
+            refs: repo.refs.clone(),
+            shallow_file,
+            shallow,
+            released: false,
+        };
+
+        let info = quarantine.root.join("objects").join("info");
+        std::fs::create_dir_all(&info)?;
+        std::fs::create_dir(quarantine.root.join("objects").join("pack"))?;
+        let mut alternates = gix::path::into_bstr(&objects_dir).into_owned();
+        alternates.push(b'\n');
+        std::fs::write(info.join("alternates"), alternates)?;
+
+        let common_dir = repo.common_dir().to_owned();
+        match std::fs::copy(
+            common_dir.join("packed-refs"),
+            quarantine.root.join("packed-refs"),
+        ) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(GitError::Io(e)),
+            _ => {}
+        }
+        copy_refs(&common_dir.join("refs"), &quarantine.root.join("refs"))?;
+        std::fs::copy(repo.git_dir().join("HEAD"), quarantine.root.join("HEAD"))?;
+
+        let hash = repo.object_hash();
+        let odb = gix::odb::at_opts(
+            quarantine.root.join("objects"),
+            Vec::new(),
+            gix::odb::store::init::Options {
+                object_hash: hash,
+                ..Default::default()
+            },
+        )?;
+        let mut refs = gix::RefStore::at(
+            quarantine.root.clone(),
+            gix::refs::store::init::Options {
+                write_reflog: gix::refs::store::WriteReflog::Disable,
+                object_hash: hash,
+                precompose_unicode: quarantine.refs.precompose_unicode,
+                prohibit_windows_device_names: quarantine.refs.prohibit_windows_device_names,
+            },
+        );
+        refs.namespace.clone_from(&quarantine.refs.namespace);
+        repo.objects = gix::odb::memory::Proxy::new(odb, hash);
+        repo.refs = refs;
+        Ok(quarantine)
+    }
+
+    /// Point `repo` back at its real objects and refs, move the received
+    /// pack there and apply the ref edits of the fetch.
+    pub(crate) fn release(
+        mut self,
+        repo: &mut gix::Repository,
+        outcome: &gix::remote::fetch::Outcome,
+    ) -> GitResult<()> {
+        repo.objects = self.objects.clone();
+        repo.refs = self.refs.clone();
+
+        let update_refs = match &outcome.status {
+            gix::remote::fetch::Status::Change { update_refs, .. }
+            | gix::remote::fetch::Status::NoPackReceived { update_refs, .. } => update_refs,
+        };
+        if let Some(pack) = ReceivedPack::of(outcome) {
+            pack.move_to(&repo.objects.store_ref().path().join("pack"))?;
+        }
+        if !update_refs.edits.is_empty() {
+            repo.edit_references(update_refs.edits.clone())
+                .map_err(GitError::gix)?;
+        }
+        self.released = true;
+        Ok(())
+    }
+}
+
+impl Drop for Quarantine {
+    fn drop(&mut self) {
+        if !self.released {
+            let _ = match &self.shallow {
+                Some(bytes) => std::fs::write(&self.shallow_file, bytes),
+                None => std::fs::remove_file(&self.shallow_file),
+            };
+        }
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Refs a fetch would have created or moved
+pub(crate) fn withheld_refs(result: &FetchResult) -> Vec<String> {
+    use super::fetch::RefUpdateKind;
+
+    result
+        .updated
+        .iter()
+        .filter(|update| !matches!(update.kind, RefUpdateKind::Rejected(_)))
+        .map(|update| update.name.clone())
+        .chain(result.new_tags.iter().cloned())
+        .collect()
+}
+
+/// Copy loose refs, leaving out lock files of edits in progress
+fn copy_refs(from: &Path, to: &Path) -> GitResult<()> {
+    std::fs::create_dir_all(to)?;
+    let entries = match std::fs::read_dir(from) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(GitError::Io(e)),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            copy_refs(&path, &to.join(entry.file_name()))?;
+        } else if path.extension().is_none_or(|ext| ext != "lock") {
+            std::fs::copy(&path, to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
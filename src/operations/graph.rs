@@ -0,0 +1,101 @@
+//! Commit graph rendering for log output.
+//!
+//! [`render_graph`] draws the parent/child topology of a list of commits the
+//! way `git log --graph --oneline` does, one lane per line of history.
+
+use crate::{CommitId, CommitInfo};
+
+/// Render `commits` as an ASCII graph, one or more lines per commit.
+///
+/// Commits must be ordered children first, as [`crate::LogOpts::graph`]
+/// walks them. Each commit line holds the lanes, the short id and the summary;
+/// extra lines show branches joining (`/`) and merges splitting (`\`).
+#[must_use]
+pub fn render_graph(commits: &[CommitInfo]) -> Vec<String> {
+    let mut lanes: Vec<Option<CommitId>> = Vec::new();
+    let mut lines = Vec::new();
+
+    for commit in commits {
+        let expected = |lane: &Option<CommitId>| *lane == Some(commit.id);
+        let column = match lanes.iter().position(expected) {
+            Some(column) => column,
+            // A branch tip nothing led to yet
+            None => {
+                let column = free_lane(&mut lanes, 0);
+                lanes[column] = Some(commit.id);
+                column
+            }
+        };
+
+        // Further lanes waiting for this commit are branches forked from it
+        let joined: Vec<usize> = (column + 1..lanes.len())
+            .filter(|&lane| expected(&lanes[lane]))
+            .collect();
+        if !joined.is_empty() {
+            lines.push(edge_row(&lanes, &joined, '/'));
+            for &lane in &joined {
+                lanes[lane] = None;
+            }
+        }
+
+        let mut row = lane_row(&lanes);
+        row[2 * column] = '*';
+        lines.push(format!(
+            "{} {} {}",
+            row.iter().collect::<String>().trim_end(),
+            commit.id.to_hex_with_len(7),
+            commit.summary
+        ));
+
+        lanes[column] = commit.parents.first().copied();
+        let mut split = Vec::new();
+        for parent in commit.parents.iter().skip(1) {
+            if lanes.contains(&Some(*parent)) {
+                continue;
+            }
+            let lane = free_lane(&mut lanes, column + 1);
+            lanes[lane] = Some(*parent);
+            split.push(lane);
+        }
+        if !split.is_empty() {
+            lines.push(edge_row(&lanes, &split, '\\'));
+        }
+
+        while lanes.last() == Some(&None) {
+            lanes.pop();
+        }
+    }
+    lines
+}
+
+/// First unused lane at or after `from`, adding one if all are taken
+fn free_lane(lanes: &mut Vec<Option<CommitId>>, from: usize) -> usize {
+    match (from..lanes.len()).find(|&lane| lanes[lane].is_none()) {
+        Some(lane) => lane,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+/// `|` for every active lane, spaced one column apart
+fn lane_row(lanes: &[Option<CommitId>]) -> Vec<char> {
+    let mut row = vec![' '; (2 * lanes.len()).saturating_sub(1)];
+    for (lane, id) in lanes.iter().enumerate() {
+        if id.is_some() {
+            row[2 * lane] = '|';
+        }
+    }
+    row
+}
+
+/// Active lanes with `edge` leading into each of `edges` from its left
+fn edge_row(lanes: &[Option<CommitId>], edges: &[usize], edge: char) -> String {
+    let mut row = lane_row(lanes);
+    for &lane in edges {
+        row[2 * lane] = ' ';
+        row[2 * lane - 1] = edge;
+    }
+    row.iter().collect::<String>().trim_end().to_string()
+}
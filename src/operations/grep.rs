@@ -0,0 +1,211 @@
+//! Content search across tracked files, like `git grep`.
+//!
+//! Without a revision the working tree copies of the files in the index are
+//! searched, so uncommitted edits are found but untracked and ignored files
+//! are not. With a revision, the files of that commit's tree are searched.
+
+use std::path::PathBuf;
+
+use gix::bstr::ByteSlice;
+use regex::{Regex, RegexBuilder};
+
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Bytes inspected for a NUL when deciding whether a file is binary
+const BINARY_PROBE: usize = 8000;
+
+/// Options for [`grep`]
+#[derive(Debug, Clone)]
+pub struct GrepOpts {
+    /// Regex matched against each line
+    pub pattern: String,
+    pub ignore_case: bool,
+    /// Search this revision's tree instead of the working tree
+    pub rev: Option<String>,
+    /// Only search files under this directory (or this file)
+    pub path: Option<PathBuf>,
+    /// Stop after this many matching lines
+    pub max_matches: usize,
+}
+
+impl GrepOpts {
+    /// Search for `pattern` in the working tree, up to 100 matches.
+    #[inline]
+    #[must_use]
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            ignore_case: false,
+            rev: None,
+            path: None,
+            max_matches: 100,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn ignore_case(mut self, yes: bool) -> Self {
+        self.ignore_case = yes;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn rev(mut self, rev: impl Into<String>) -> Self {
+        self.rev = Some(rev.into());
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn max_matches(mut self, count: usize) -> Self {
+        self.max_matches = count;
+        self
+    }
+}
+
+/// A matching line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepMatch {
+    /// Repository-relative path, `/`-separated
+    pub path: String,
+    /// 1-based line number
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Result of [`grep`]
+#[derive(Debug, Clone, Default)]
+pub struct GrepResult {
+    pub matches: Vec<GrepMatch>,
+    /// Text files searched (binary files are skipped)
+    pub files_searched: usize,
+    /// Whether the search stopped at `max_matches`
+    pub truncated: bool,
+}
+
+/// Search tracked files for lines matching a regex
+pub async fn grep(repo: RepoHandle, opts: GrepOpts) -> GitResult<GrepResult> {
+    let repo = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || grep_sync(&repo, &opts))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+fn grep_sync(repo: &gix::Repository, opts: &GrepOpts) -> GitResult<GrepResult> {
+    let regex = RegexBuilder::new(&opts.pattern)
+        .case_insensitive(opts.ignore_case)
+        .build()
+        .map_err(|e| GitError::InvalidInput(format!("Invalid pattern '{}': {e}", opts.pattern)))?;
+    let prefix = opts
+        .path
+        .as_ref()
+        .map(|path| path.to_string_lossy().replace('\\', "/").trim_matches('/').to_string())
+        .filter(|prefix| !prefix.is_empty());
+    let in_scope = |path: &str| match &prefix {
+        Some(prefix) => path
+            .strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/')),
+        None => true,
+    };
+
+    let mut result = GrepResult::default();
+    match (&opts.rev, repo.workdir()) {
+        (None, Some(workdir)) => {
+            let index = repo.index_or_empty().map_err(GitError::gix)?;
+            for entry in index.entries() {
+                if entry.mode.is_submodule() || entry.stage_raw() != 0 {
+                    continue;
+                }
+                let path = entry.path(&index).to_str_lossy();
+                if !in_scope(&path) {
+                    continue;
+                }
+                let relative = gix::path::from_bstr(entry.path(&index));
+                // Deleted in the working tree but still staged
+                let Ok(data) = std::fs::read(workdir.join(relative)) else {
+                    continue;
+                };
+                if search_file(&path, &data, &regex, opts.max_matches, &mut result) {
+                    break;
+                }
+            }
+        }
+        // Bare repositories have no working tree and search HEAD instead
+        (rev, _) => {
+            let rev = rev.as_deref().unwrap_or("HEAD");
+            let tree = repo
+                .rev_parse_single(rev)
+                .map_err(|e| GitError::InvalidInput(format!("Invalid revision '{rev}': {e}")))?
+                .object()
+                .map_err(GitError::gix)?
+                .peel_to_tree()
+                .map_err(GitError::gix)?;
+            let entries = tree
+                .traverse()
+                .breadthfirst
+                .files()
+                .map_err(GitError::gix)?;
+            let mut entries: Vec<_> = entries
+                .into_iter()
+                .filter(|entry| !entry.mode.is_tree() && !entry.mode.is_commit())
+                .collect();
+            // Breadth-first order lists shallow files first; report in path order like git
+            entries.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+            for entry in entries {
+                let path = entry.filepath.to_str_lossy();
+                if !in_scope(&path) {
+                    continue;
+                }
+                let data = repo
+                    .find_blob(entry.oid)
+                    .map_err(GitError::gix)?
+                    .detach()
+                    .data;
+                if search_file(&path, &data, &regex, opts.max_matches, &mut result) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Add the lines of `data` matching `regex`; returns `true` once `max` is reached
+fn search_file(
+    path: &str,
+    data: &[u8],
+    regex: &Regex,
+    max: usize,
+    result: &mut GrepResult,
+) -> bool {
+    if data[..data.len().min(BINARY_PROBE)].contains(&0) {
+        return false;
+    }
+    result.files_searched += 1;
+
+    for (index, line) in data.lines().enumerate() {
+        let line = line.to_str_lossy();
+        if !regex.is_match(&line) {
+            continue;
+        }
+        if result.matches.len() >= max {
+            result.truncated = true;
+            return true;
+        }
+        result.matches.push(GrepMatch {
+            path: path.to_string(),
+            line_number: index + 1,
+            line: line.into_owned(),
+        });
+    }
+    false
+}
@@ -7,6 +7,8 @@ use std::path::PathBuf;
 
 use regex::Regex;
 
+use super::repo_path::RepoPath;
+use super::warnings::OperationWarning;
 use crate::{GitError, GitResult, RepoHandle};
 
 /// Options for history operation
@@ -62,6 +64,8 @@ pub struct HistoryCommit {
     pub additions: u32,
     pub deletions: u32,
     pub diff: String,      // Unified diff with context
+    /// Problems reading the commit, such as a time that fell back to the epoch
+    pub warnings: Vec<OperationWarning>,
 }
 
 /// History result (two modes)
@@ -69,13 +73,16 @@ pub struct HistoryCommit {
 pub enum HistoryResult {
     /// Per-commit diffs
     Commits {
-        file: String,
+        file: RepoPath,
         total_examined: usize,
         commits: Vec<HistoryCommit>,
+        /// The walk ended at the boundary of a shallow clone, so older
+        /// changes to the file may be missing.
+        reached_shallow_boundary: bool,
     },
     /// Cumulative diff between two revisions
     Range {
-        file: String,
+        file: RepoPath,
         since: String,
         until: String,
         additions: u32,
@@ -86,7 +93,7 @@ pub enum HistoryResult {
 
 /// Execute history query
 pub async fn history(repo: RepoHandle, opts: HistoryOpts) -> GitResult<HistoryResult> {
-    let repo_inner = repo.clone_inner();
+    let repo_inner = repo.clone_for_traversal();
 
     tokio::task::spawn_blocking(move || history_sync(&repo_inner, opts))
         .await
@@ -94,8 +101,6 @@ pub async fn history(repo: RepoHandle, opts: HistoryOpts) -> GitResult<HistoryRe
 }
 
 fn history_sync(repo: &gix::Repository, opts: HistoryOpts) -> GitResult<HistoryResult> {
-    use gix::bstr::ByteSlice;
-
     // Normalize file path
     let workdir = repo.workdir().ok_or_else(|| {
         GitError::InvalidInput("Cannot query history in bare repository".to_string())
@@ -118,25 +123,23 @@ fn history_sync(repo: &gix::Repository, opts: HistoryOpts) -> GitResult<HistoryR
     // Resolve start revision
     let since_id = if let Some(ref rev) = opts.since {
         repo.rev_parse_single(rev.as_str())
-            .map_err(|e| GitError::Gix(Box::new(e)))?
+            .map_err(GitError::gix)?
             .detach()
     } else {
-        repo.head_id()
-            .map_err(|e| GitError::Gix(Box::new(e)))?
-            .detach()
+        repo.head_id().map_err(GitError::gix)?.detach()
     };
 
     // RANGE MODE: cumulative diff between two revisions
     if let Some(ref until_rev) = opts.until {
         let until_id = repo
             .rev_parse_single(until_rev.as_str())
-            .map_err(|e| GitError::Gix(Box::new(e)))?
+            .map_err(GitError::gix)?
             .detach();
 
         let (additions, deletions, diff) = compute_file_diff(repo, since_id, until_id, &file_path)?;
 
         return Ok(HistoryResult::Range {
-            file: file_path.to_string_lossy().to_string(),
+            file: RepoPath::from(gix::path::into_bstr(&file_path).as_ref()),
             since: opts.since.unwrap_or_else(|| "HEAD".to_string()),
             until: until_rev.clone(),
             additions,
@@ -148,32 +151,37 @@ fn history_sync(repo: &gix::Repository, opts: HistoryOpts) -> GitResult<HistoryR
     // COMMITS MODE: per-commit diffs
     let mut commits = Vec::new();
     let mut total_examined = 0;
+    let boundary = crate::operations::shallow::shallow_boundary(repo)?;
+    let mut reached_shallow_boundary = false;
 
-    let rev_walk = repo
-        .rev_walk([since_id])
-        .all()
-        .map_err(|e| GitError::Gix(e.into()))?;
+    let rev_walk = repo.rev_walk([since_id]).all().map_err(GitError::gix)?;
 
     for commit_result in rev_walk {
         if commits.len() >= opts.limit {
             break;
         }
 
-        let info = commit_result.map_err(|e| GitError::Gix(e.into()))?;
+        let info = commit_result.map_err(GitError::gix)?;
         let commit = repo
             .find_object(info.id)
-            .map_err(|e| GitError::Gix(e.into()))?
+            .map_err(GitError::gix)?
             .into_commit();
 
         total_examined += 1;
+        reached_shallow_boundary |= boundary.contains(&info.id);
 
         // REUSE commit_touches_path from log.rs
         if !crate::operations::log::commit_touches_path(repo, &commit, &file_path)? {
             continue;
         }
 
-        // Compute diff against parent
-        let parent_id = commit.parent_ids().next().map(|p| p.detach());
+        // Compute diff against parent; shallow boundary commits have none,
+        // even where the parent object happens to be present
+        let parent_id = commit
+            .parent_ids()
+            .next()
+            .map(|p| p.detach())
+            .filter(|p| !boundary.contains(&info.id) && repo.has_object(p));
 
         let (additions, deletions, diff) = if let Some(pid) = parent_id {
             compute_file_diff(repo, pid, info.id, &file_path)?
@@ -194,34 +202,25 @@ fn history_sync(repo: &gix::Repository, opts: HistoryOpts) -> GitResult<HistoryR
         }
 
         // Get commit metadata
-        let time = commit.time().map_err(|e| GitError::Gix(Box::new(e)))?;
-        let commit_time = {
-            use chrono::TimeZone;
-            chrono::Utc
-                .timestamp_opt(time.seconds, 0)
-                .single()
-                .ok_or_else(|| {
-                    GitError::InvalidInput(format!("Invalid timestamp {}", time.seconds))
-                })?
-        };
+        let mut warnings = Vec::new();
+        let commit_time = super::log::commit_date(&commit, &mut warnings).to_utc();
 
         commits.push(HistoryCommit {
             id: info.id.to_string()[..7].to_string(), // Short hash
-            summary: commit
-                .message()
-                .map(|msg| msg.summary().as_bstr().to_string())
-                .unwrap_or_default(),
+            summary: super::encoding::commit_summary(&commit),
             time: commit_time,
             additions,
             deletions,
             diff,
+            warnings,
         });
     }
 
     Ok(HistoryResult::Commits {
-        file: file_path.to_string_lossy().to_string(),
+        file: RepoPath::from(gix::path::into_bstr(&file_path).as_ref()),
         total_examined,
         commits,
+        reached_shallow_boundary,
     })
 }
 
@@ -256,22 +255,22 @@ fn get_file_at_commit(
 ) -> GitResult<String> {
     let commit = repo
         .find_object(commit_id)
-        .map_err(|e| GitError::Gix(e.into()))?
+        .map_err(GitError::gix)?
         .try_into_commit()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+        .map_err(GitError::gix)?;
 
-    let tree = commit.tree().map_err(|e| GitError::Gix(Box::new(e)))?;
+    let tree = commit.tree().map_err(GitError::gix)?;
 
     match tree
         .lookup_entry_by_path(file_path)
-        .map_err(|e| GitError::Gix(Box::new(e)))?
+        .map_err(GitError::gix)?
     {
         Some(entry) => {
             let blob = repo
                 .find_object(entry.oid())
-                .map_err(|e| GitError::Gix(e.into()))?
+                .map_err(GitError::gix)?
                 .try_into_blob()
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
+                .map_err(GitError::gix)?;
             Ok(String::from_utf8_lossy(blob.data.as_slice()).to_string())
         }
         None => Ok(String::new()),
@@ -0,0 +1,256 @@
+//! Editing `.gitignore` files.
+//!
+//! Agents routinely need to ignore build output or stop ignoring a file they
+//! want to commit. These helpers edit the `.gitignore` of the repository root
+//! or of any directory below it line by line: comments, blank lines and the
+//! order of existing patterns are kept, and adding a pattern that is already
+//! present (or removing one that isn't) changes nothing.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::{GitError, GitResult, RepoHandle};
+
+/// A pattern line of a `.gitignore` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnorePattern {
+    /// 1-based line number
+    pub line: usize,
+    /// The pattern as written, without trailing whitespace
+    pub pattern: String,
+    /// `!pattern`: re-includes what an earlier pattern ignored
+    pub negated: bool,
+}
+
+/// Result of [`add_patterns`] or [`remove_patterns`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreEdit {
+    /// The `.gitignore` that was edited, relative to the working tree
+    pub file: PathBuf,
+    /// Patterns added or removed; patterns already in the requested state are left out
+    pub changed: Vec<String>,
+}
+
+/// Append `patterns` to the `.gitignore` in `dir` (relative to the working
+/// tree; empty for the root), creating it if needed.
+///
+/// Patterns already present are skipped. With a `comment`, the new patterns
+/// are preceded by `# comment`, unless none are added.
+pub async fn add_patterns<I, S>(
+    repo: RepoHandle,
+    dir: impl AsRef<Path>,
+    patterns: I,
+    comment: Option<&str>,
+) -> GitResult<IgnoreEdit>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let patterns = validate(patterns)?;
+    let comment = comment.map(|comment| comment.trim().to_string());
+    let (file, changed) = edit_file(repo.clone(), dir.as_ref(), ".gitignore", move |text| {
+        let existing: Vec<String> = pattern_lines(text).map(|(_, line)| line).collect();
+        let mut added: Vec<String> = Vec::new();
+        for pattern in patterns {
+            if !existing.contains(&pattern) && !added.contains(&pattern) {
+                added.push(pattern);
+            }
+        }
+        if added.is_empty() {
+            return (None, Vec::new());
+        }
+
+        let newline = newline(text);
+        let mut edited = text.to_string();
+        if !edited.is_empty() && !edited.ends_with('\n') {
+            edited.push_str(newline);
+        }
+        if let Some(comment) = comment.filter(|comment| !comment.is_empty()) {
+            // Keep the new block apart from whatever came before
+            if !edited.is_empty() && !edited.ends_with(&format!("{newline}{newline}")) {
+                edited.push_str(newline);
+            }
+            edited.push_str(&format!("# {comment}{newline}"));
+        }
+        for pattern in &added {
+            edited.push_str(pattern);
+            edited.push_str(newline);
+        }
+        (Some(edited), added)
+    })
+    .await?;
+    Ok(IgnoreEdit { file, changed })
+}
+
+/// Remove `patterns` from the `.gitignore` in `dir`.
+///
+/// Only exact pattern lines are removed; comments and other patterns stay.
+/// A missing file is treated as empty.
+pub async fn remove_patterns<I, S>(
+    repo: RepoHandle,
+    dir: impl AsRef<Path>,
+    patterns: I,
+) -> GitResult<IgnoreEdit>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let patterns = validate(patterns)?;
+    let (file, changed) = edit_file(repo.clone(), dir.as_ref(), ".gitignore", move |text| {
+        let mut removed: Vec<String> = Vec::new();
+        let mut edited = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            let pattern = line.trim_end();
+            if patterns.iter().any(|p| p == pattern) {
+                if !removed.iter().any(|r| r == pattern) {
+                    removed.push(pattern.to_string());
+                }
+                continue;
+            }
+            edited.push_str(line);
+        }
+        if removed.is_empty() {
+            (None, removed)
+        } else {
+            (Some(edited), removed)
+        }
+    })
+    .await?;
+    Ok(IgnoreEdit { file, changed })
+}
+
+/// Patterns of the `.gitignore` in `dir`, in file order; empty if there is none.
+pub async fn list_patterns(
+    repo: RepoHandle,
+    dir: impl AsRef<Path>,
+) -> GitResult<Vec<IgnorePattern>> {
+    let path = dir_file(&repo, dir.as_ref(), ".gitignore")?;
+    tokio::task::spawn_blocking(move || {
+        let text = read(&path)?;
+        Ok(pattern_lines(&text)
+            .map(|(line, pattern)| IgnorePattern {
+                line,
+                negated: pattern.starts_with('!'),
+                pattern,
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Read the file `name` in `dir`, let `change` rewrite it and write it back
+/// if it returned new contents.
+///
+/// Returns the file relative to the working tree and what `change` reported
+/// as changed.
+pub(crate) async fn edit_file<F>(
+    repo: RepoHandle,
+    dir: &Path,
+    name: &str,
+    change: F,
+) -> GitResult<(PathBuf, Vec<String>)>
+where
+    F: FnOnce(&str) -> (Option<String>, Vec<String>) + Send + 'static,
+{
+    let path = dir_file(&repo, dir, name)?;
+    let file = relative_file(dir, name);
+    tokio::task::spawn_blocking(move || {
+        let text = read(&path)?;
+        let (edited, changed) = change(&text);
+        if let Some(edited) = edited {
+            std::fs::write(&path, edited)?;
+        }
+        Ok((file, changed))
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Absolute path of the file `name` in `dir`, which must be an existing
+/// directory inside the working tree
+pub(crate) fn dir_file(repo: &RepoHandle, dir: &Path, name: &str) -> GitResult<PathBuf> {
+    let workdir = repo
+        .raw()
+        .workdir()
+        .ok_or_else(|| GitError::InvalidInput("Repository has no working directory".to_string()))?;
+    if dir
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(GitError::InvalidInput(format!(
+            "Directory must be relative to the working tree: {}",
+            dir.display()
+        )));
+    }
+    if dir.components().any(|c| c.as_os_str() == ".git") {
+        return Err(GitError::InvalidInput(format!(
+            "Directory is inside .git: {}",
+            dir.display()
+        )));
+    }
+
+    let full = workdir.join(dir);
+    if !full.is_dir() {
+        return Err(GitError::InvalidInput(format!(
+            "Directory does not exist: {}",
+            dir.display()
+        )));
+    }
+    Ok(full.join(name))
+}
+
+/// `dir/name`, relative to the working tree
+fn relative_file(dir: &Path, name: &str) -> PathBuf {
+    dir.components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect::<PathBuf>()
+        .join(name)
+}
+
+/// Contents of `path`, empty if it doesn't exist
+pub(crate) fn read(path: &Path) -> GitResult<String> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Patterns with their 1-based line numbers, skipping blank lines and comments
+fn pattern_lines(text: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+    text.lines().enumerate().filter_map(|(index, line)| {
+        let line = line.trim_end();
+        (!line.is_empty() && !line.starts_with('#')).then(|| (index + 1, line.to_string()))
+    })
+}
+
+/// The file's line ending, `\n` unless it already uses `\r\n`
+pub(crate) fn newline(text: &str) -> &'static str {
+    if text.contains("\r\n") { "\r\n" } else { "\n" }
+}
+
+/// Trim patterns and reject those that aren't a single pattern line
+fn validate<I, S>(patterns: I) -> GitResult<Vec<String>>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let patterns: Vec<String> = patterns
+        .into_iter()
+        .map(|pattern| pattern.into().trim_end().to_string())
+        .collect();
+    if patterns.is_empty() {
+        return Err(GitError::InvalidInput("No patterns given".to_string()));
+    }
+    for pattern in &patterns {
+        if pattern.is_empty() || pattern.contains(['\n', '\r']) {
+            return Err(GitError::InvalidInput(format!("Invalid ignore pattern: {pattern:?}")));
+        }
+        if pattern.starts_with('#') {
+            return Err(GitError::InvalidInput(format!(
+                "'{pattern}' would be a comment; escape it as '\\{pattern}'"
+            )));
+        }
+    }
+    Ok(patterns)
+}
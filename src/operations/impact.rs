@@ -0,0 +1,168 @@
+//! Impact analysis: which build or test targets a range of commits touches.
+//!
+//! Targets are described by [`ImpactRule`]s mapping path globs to target
+//! names, e.g. `src/operations/**` to `crate:kodegen-tools-git`. Globs use
+//! the gitignore syntax of CODEOWNERS patterns: a pattern without a slash
+//! matches at any depth, and a pattern matching a directory covers
+//! everything below it. Every matching rule contributes its target, so one
+//! path can impact several targets.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::codeowners::path_matches;
+use super::patch_id::{FileChange, file_changes};
+use super::rewrite::parse_range;
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Maps paths matching a glob to a target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImpactRule {
+    /// Glob as written
+    pub pattern: String,
+    /// Target name, e.g. a crate, CI job or test suite
+    pub target: String,
+    glob: gix::glob::Pattern,
+}
+
+impl ImpactRule {
+    /// Rule mapping paths matching `pattern` to `target`
+    pub fn new(pattern: impl Into<String>, target: impl Into<String>) -> GitResult<Self> {
+        let pattern = pattern.into();
+        let target = target.into();
+        if target.is_empty() {
+            return Err(GitError::InvalidInput(format!(
+                "Impact rule '{pattern}' has no target"
+            )));
+        }
+        if pattern.starts_with('!') {
+            return Err(GitError::InvalidInput(format!(
+                "Negated pattern '{pattern}' is not supported in impact rules"
+            )));
+        }
+        let glob = gix::glob::Pattern::from_bytes(pattern.as_bytes())
+            .ok_or_else(|| GitError::InvalidInput(format!("Invalid pattern '{pattern}'")))?;
+        Ok(Self {
+            pattern,
+            target,
+            glob,
+        })
+    }
+
+    /// Whether this rule applies to the repository-relative `path`
+    #[must_use]
+    pub fn matches(&self, path: &str) -> bool {
+        path_matches(&self.glob, &self.pattern, path)
+    }
+}
+
+/// A target touched by a range, with the paths that touched it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactedTarget {
+    pub target: String,
+    /// Changed paths mapped to this target, sorted
+    pub paths: Vec<String>,
+}
+
+/// Result of [`impacted_paths`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactReport {
+    /// Every path changed in the range, sorted
+    pub changed_paths: Vec<String>,
+    /// Impacted targets, sorted by name
+    pub targets: Vec<ImpactedTarget>,
+    /// Changed paths no rule matched, sorted
+    pub unmatched_paths: Vec<String>,
+}
+
+impl ImpactReport {
+    /// Names of the impacted targets
+    #[must_use]
+    pub fn target_names(&self) -> Vec<&str> {
+        self.targets.iter().map(|t| t.target.as_str()).collect()
+    }
+}
+
+/// Map the paths changed in `range` to targets using `rules`.
+///
+/// `range` is `<base>..<tip>` for the commits reachable from `tip` but not
+/// from `base`, or a single revision for the change of that one commit. A
+/// path counts as changed if any commit in the range touched it, even if a
+/// later commit reverted it; merge commits are compared to their first
+/// parent.
+pub async fn impacted_paths(
+    repo: RepoHandle,
+    range: &str,
+    rules: &[ImpactRule],
+) -> GitResult<ImpactReport> {
+    let repo_inner = repo.clone_for_traversal();
+    let range = range.to_string();
+    let rules = rules.to_vec();
+
+    tokio::task::spawn_blocking(move || {
+        let changed = changed_paths(&repo_inner, &range)?;
+
+        let mut targets: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        let mut unmatched_paths = Vec::new();
+        for path in &changed {
+            let mut matched = false;
+            for rule in rules.iter().filter(|rule| rule.matches(path)) {
+                matched = true;
+                let paths = targets.entry(rule.target.as_str()).or_default();
+                // Several rules may map the same path to one target
+                if paths.last() != Some(path) {
+                    paths.push(path.clone());
+                }
+            }
+            if !matched {
+                unmatched_paths.push(path.clone());
+            }
+        }
+
+        Ok(ImpactReport {
+            targets: targets
+                .into_iter()
+                .map(|(target, paths)| ImpactedTarget {
+                    target: target.to_string(),
+                    paths,
+                })
+                .collect(),
+            changed_paths: changed.into_iter().collect(),
+            unmatched_paths,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Paths touched by any commit of `range`
+fn changed_paths(repo: &gix::Repository, range: &str) -> GitResult<BTreeSet<String>> {
+    let (base, tip) = parse_range(repo, range)?;
+    let commits: Vec<CommitId> = match base {
+        Some(base) => repo
+            .rev_walk([tip])
+            .with_hidden([base])
+            .all()
+            .map_err(GitError::gix)?
+            .map(|info| info.map(|info| info.id).map_err(GitError::gix))
+            .collect::<GitResult<_>>()?,
+        None => vec![tip],
+    };
+
+    let mut paths = BTreeSet::new();
+    for id in commits {
+        let commit = repo.find_commit(id).map_err(GitError::gix)?;
+        let tree = commit.tree().map_err(GitError::gix)?;
+        let parent_tree = match commit.parent_ids().next() {
+            Some(parent) => repo
+                .find_commit(parent)
+                .map_err(GitError::gix)?
+                .tree()
+                .map_err(GitError::gix)?,
+            None => repo.empty_tree(),
+        };
+        for FileChange { path, .. } in file_changes(repo, &parent_tree, &tree)? {
+            paths.insert(path.to_string());
+        }
+    }
+    Ok(paths)
+}
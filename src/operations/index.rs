@@ -0,0 +1,136 @@
+//! Temporary index files.
+//!
+//! A worktree has a single staging area, so agents staging in the same
+//! worktree at the same time add to and commit each other's changes. Like
+//! `GIT_INDEX_FILE`, a [`TempIndex`] gives each its own: `add`,
+//! `apply_to_index` and `commit` through [`TempIndex::repo`] stage into a
+//! private index file, while the working tree, refs and objects stay shared.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Distinguishes temporary indexes created by this process
+static NEXT_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+/// What a temporary index starts with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexSeed {
+    /// Nothing staged, not even `HEAD`'s files
+    Empty,
+    /// The files of `HEAD`, so only new changes are staged
+    Head,
+    /// A copy of the repository's index, including what is staged there
+    Current,
+}
+
+/// A private index file, removed when dropped.
+#[derive(Debug)]
+pub struct TempIndex {
+    repo: RepoHandle,
+    path: PathBuf,
+}
+
+impl TempIndex {
+    /// Handle staging to and committing from this index.
+    ///
+    /// Clones of it must not be used after the `TempIndex` is dropped.
+    #[must_use]
+    pub fn repo(&self) -> &RepoHandle {
+        &self.repo
+    }
+
+    /// Path of the index file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempIndex {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Create a private index file in the git dir, starting from `seed`.
+pub async fn temporary_index(repo: RepoHandle, seed: IndexSeed) -> GitResult<TempIndex> {
+    let repo_inner = repo.clone_inner();
+    let path = repo_inner.git_dir().join(format!(
+        "index.tmp.{}.{}",
+        std::process::id(),
+        NEXT_INDEX.fetch_add(1, Ordering::Relaxed)
+    ));
+    let index_path = path.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut index = match seed {
+            IndexSeed::Empty => empty_index(&repo_inner, index_path),
+            IndexSeed::Head => match repo_inner.head_tree_id() {
+                Ok(tree) => {
+                    let (state, _) = repo_inner
+                        .index_from_tree(&tree)
+                        .map_err(GitError::gix)?
+                        .into_parts();
+                    gix::index::File::from_state(state, index_path)
+                }
+                // Unborn HEAD
+                Err(_) => empty_index(&repo_inner, index_path),
+            },
+            IndexSeed::Current => {
+                let current = repo_inner.index_path();
+                if current.exists() {
+                    std::fs::copy(&current, &index_path)?;
+                    return Ok(());
+                }
+                empty_index(&repo_inner, index_path)
+            }
+        };
+        index
+            .write(gix::index::write::Options::default())
+            .map_err(GitError::gix)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    Ok(TempIndex {
+        repo: repo.clone().with_index_file(&path),
+        path,
+    })
+}
+
+/// Open the index `index_file` (see [`RepoHandle::with_index_file`]), or the
+/// repository's if `None`
+///
+/// A sparse index is expanded unless `sparse_index` is set (see
+/// [`RepoHandle::with_sparse_index`]).
+pub(crate) fn open_index(
+    repo: &gix::Repository,
+    index_file: Option<&Path>,
+    sparse_index: bool,
+) -> GitResult<gix::index::File> {
+    let mut index = read_index(repo, index_file)?;
+    if !sparse_index && index.is_sparse() {
+        super::sparse::expand(repo, &mut index)?;
+    }
+    Ok(index)
+}
+
+fn read_index(repo: &gix::Repository, index_file: Option<&Path>) -> GitResult<gix::index::File> {
+    match index_file {
+        None => repo.open_index().map_err(GitError::gix),
+        Some(path) if path.exists() => gix::index::File::at(
+            path,
+            repo.object_hash(),
+            false,
+            gix::index::decode::Options::default(),
+        )
+        .map_err(GitError::gix),
+        Some(path) => Ok(empty_index(repo, path.to_path_buf())),
+    }
+}
+
+fn empty_index(repo: &gix::Repository, path: PathBuf) -> gix::index::File {
+    gix::index::File::from_state(gix::index::State::new(repo.object_hash()), path)
+}
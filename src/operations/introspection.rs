@@ -3,10 +3,12 @@
 //! This module provides functions for inspecting Git repository internals
 //! such as commit metadata, repository paths, and URL parsing.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
 
+use super::decorate::{Decoration, decorations};
+use super::diff::{DiffStats, diff_with_line_counts};
 use crate::{CommitId, GitError, GitResult, RepoHandle, Signature};
 
 /// Detailed commit information including parents and short hash.
@@ -14,11 +16,28 @@ use crate::{CommitId, GitError, GitResult, RepoHandle, Signature};
 pub struct DetailedCommitInfo {
     pub id: CommitId,
     pub short_id: String,
+    /// Title (first paragraph) of the message.
     pub message: String,
+    /// Message after the title, trailers included; empty for one-line messages.
+    pub body: String,
+    /// Trailers from the last paragraph of the message, e.g. `Signed-off-by`.
+    pub trailers: Vec<Trailer>,
     pub author: Signature,
     pub committer: Signature,
     pub timestamp: DateTime<Utc>,
     pub parent_ids: Vec<CommitId>,
+    /// Changes against the first parent (the empty tree for root commits),
+    /// with line counts.
+    pub diffstat: DiffStats,
+    /// Branches, tags and `HEAD` pointing at the commit.
+    pub decorations: Vec<Decoration>,
+}
+
+/// A `Token: value` trailer of a commit message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+    pub token: String,
+    pub value: String,
 }
 
 /// Repository paths.
@@ -28,20 +47,104 @@ pub struct RepoPaths {
     pub work_dir: Option<PathBuf>,
 }
 
+/// An entry of `FETCH_HEAD`, one per ref the last fetch brought in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchHeadEntry {
+    pub id: CommitId,
+    /// Full name of the fetched ref, e.g. `refs/heads/main`; `None` when
+    /// the remote's `HEAD` was fetched.
+    pub ref_name: Option<String>,
+    /// URL (or remote name) fetched from.
+    pub url: String,
+    /// Whether `git pull` merges it; `false` for `not-for-merge` entries.
+    pub for_merge: bool,
+}
+
+/// A merge that was started but not yet committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeState {
+    /// Commits being merged into `HEAD` (`MERGE_HEAD`).
+    pub heads: Vec<CommitId>,
+    /// Prepared commit message (`MERGE_MSG`).
+    pub message: Option<String>,
+}
+
 /// Parsed Git URL information.
 #[derive(Debug, Clone)]
 pub struct GitUrl {
+    /// The URL in canonical form: scheme and host lowercased, scp-like and
+    /// local path forms kept as written.
+    pub url: String,
     pub scheme: String,
+    /// User name, e.g. `git` of `git@github.com:owner/repo.git`.
+    pub user: Option<String>,
     pub host: String,
+    /// Port if given explicitly.
+    pub port: Option<u16>,
+    /// What kind of forge the host is.
+    pub host_kind: HostKind,
     pub path: String,
+    /// Non-empty segments of `path`, e.g. `group`, `subgroup`, `repo.git`.
+    pub segments: Vec<String>,
+    /// Namespace of the repository: the user or organization, or the full
+    /// group path like `group/subgroup` on GitLab.
     pub owner: Option<String>,
+    /// Repository name, without `.git`.
     pub repo: Option<String>,
 }
 
+/// Forge hosting a repository, which decides how its URL path is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HostKind {
+    /// github.com: `owner/repo`.
+    GitHub,
+    /// gitlab.com or a host named `gitlab.*`: `group/subgroup/.../repo`.
+    GitLab,
+    /// bitbucket.org: `workspace/repo`.
+    Bitbucket,
+    /// Azure DevOps: `org/project/_git/repo`, or `v3/org/project/repo` over SSH.
+    Azure,
+    /// Any other host, or a local path.
+    Other,
+}
+
+impl HostKind {
+    /// Kind of the forge at `host`
+    #[must_use]
+    pub fn of(host: &str) -> Self {
+        let host = host.to_ascii_lowercase();
+        let is = |domain: &str| host == domain || host.ends_with(&format!(".{domain}"));
+        if is("github.com") {
+            Self::GitHub
+        } else if is("gitlab.com") || host.starts_with("gitlab.") {
+            Self::GitLab
+        } else if is("bitbucket.org") {
+            Self::Bitbucket
+        } else if is("dev.azure.com") || is("visualstudio.com") {
+            Self::Azure
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Lowercase name, e.g. `github`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::GitHub => "github",
+            Self::GitLab => "gitlab",
+            Self::Bitbucket => "bitbucket",
+            Self::Azure => "azure",
+            Self::Other => "other",
+        }
+    }
+}
+
 /// Get detailed information about a commit by ID.
 ///
 /// Returns comprehensive commit metadata including author, committer, timestamp,
-/// message, and parent commit IDs.
+/// message with body and trailers, parent commit IDs, the diffstat against the
+/// first parent and the refs pointing at the commit.
 ///
 /// # Example
 ///
@@ -51,12 +154,12 @@ pub struct GitUrl {
 /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 /// let repo = open_repo("/path/to/repo").await?;
 /// let head_id = repo.raw().head_id().ok().expect("No HEAD");
-/// let info = get_commit_details(&repo, &head_id.to_string()).await?;
+/// let info = get_commit_details(repo, &head_id.to_string()).await?;
 /// println!("Author: {} <{}>", info.author.name, info.author.email);
 /// # Ok(())
 /// # }
 /// ```
-pub async fn get_commit_details(repo: &RepoHandle, commit_id: &str) -> GitResult<DetailedCommitInfo> {
+pub async fn get_commit_details(repo: RepoHandle, commit_id: &str) -> GitResult<DetailedCommitInfo> {
     let repo_clone = repo.clone_inner();
     let commit_id_str = commit_id.to_string();
 
@@ -66,15 +169,13 @@ pub async fn get_commit_details(repo: &RepoHandle, commit_id: &str) -> GitResult
         // Parse commit ID
         let oid = repo_clone
             .rev_parse_single(commit_id_str.as_bytes().as_bstr())
-            .map_err(|e| GitError::Gix(Box::new(e)))?
+            .map_err(GitError::gix)?
             .object()
-            .map_err(|e| GitError::Gix(Box::new(e)))?
+            .map_err(GitError::gix)?
             .id;
 
         // Find commit object
-        let commit = repo_clone
-            .find_commit(oid)
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        let commit = repo_clone.find_commit(oid).map_err(GitError::gix)?;
 
         // Extract metadata
         let id = commit.id().detach();
@@ -84,65 +185,58 @@ pub async fn get_commit_details(repo: &RepoHandle, commit_id: &str) -> GitResult
             .map(|prefix| prefix.to_string())
             .unwrap_or_else(|_| id.to_string());
 
-        let message = commit
-            .message()
-            .map(|m| m.title.to_string())
-            .unwrap_or_else(|_| "No commit message".to_string());
-
-        // Extract author
-        let author_ref = commit.author().map_err(|e| GitError::Gix(Box::new(e)))?;
-        let author_time = parse_git_time(author_ref.time)?;
-        let author = Signature {
-            name: author_ref.name.to_string(),
-            email: author_ref.email.to_string(),
-            time: author_time,
+        let text = super::encoding::commit_message(&commit);
+        let (message, body, trailers) = match &text {
+            Ok(text) => {
+                let message = gix::objs::commit::MessageRef::from_bytes(text.as_bytes());
+                let trailers = message
+                    .body()
+                    .map(|body| {
+                        body.trailers()
+                            .map(|trailer| Trailer {
+                                token: trailer.token.to_string(),
+                                value: trailer.value.to_string(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let body = message
+                    .body
+                    .map(|body| body.trim_end().to_str_lossy().into_owned())
+                    .unwrap_or_default();
+                (message.title.to_string(), body, trailers)
+            }
+            Err(_) => ("No commit message".to_string(), String::new(), Vec::new()),
         };
 
-        // Extract committer
-        let committer_ref = commit.committer().map_err(|e| GitError::Gix(Box::new(e)))?;
-        let committer_time = parse_git_time(committer_ref.time)?;
-        let committer = Signature {
-            name: committer_ref.name.to_string(),
-            email: committer_ref.email.to_string(),
-            time: committer_time,
-        };
+        // Extract author and committer, keeping their times as stored
+        let author = Signature::try_from(commit.author().map_err(GitError::gix)?)?;
+        let committer = Signature::try_from(commit.committer().map_err(GitError::gix)?)?;
 
-        let timestamp = author_time;
-        let parent_ids = commit.parent_ids().map(|id| id.detach()).collect();
+        let timestamp = author.time;
+        let parent_ids: Vec<CommitId> = commit.parent_ids().map(|id| id.detach()).collect();
+
+        let diffstat = diff_with_line_counts(&repo_clone, parent_ids.first().copied(), id)?;
+        let decorations = decorations(&repo_clone)?.remove(&id).unwrap_or_default();
 
         Ok(DetailedCommitInfo {
             id,
             short_id,
             message,
+            body,
+            trailers,
             author,
             committer,
             timestamp,
             parent_ids,
+            diffstat,
+            decorations,
         })
     })
     .await
     .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
 }
 
-/// Parse Git time format from string representation.
-///
-/// Returns an error instead of silently falling back to current time.
-fn parse_git_time(time_str: &str) -> GitResult<DateTime<Utc>> {
-    // Git time format: "<seconds> <timezone>"
-    let seconds = time_str
-        .split_whitespace()
-        .next()
-        .and_then(|s| s.parse::<i64>().ok())
-        .ok_or_else(|| GitError::InvalidInput(
-            format!("Failed to parse Git timestamp: {}", time_str)
-        ))?;
-
-    DateTime::from_timestamp(seconds, 0)
-        .ok_or_else(|| GitError::InvalidInput(
-            format!("Invalid timestamp value: {}", seconds)
-        ))
-}
-
 /// Get repository directory paths.
 ///
 /// Returns the Git directory (.git) and optional working directory.
@@ -154,13 +248,13 @@ fn parse_git_time(time_str: &str) -> GitResult<DateTime<Utc>> {
 ///
 /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 /// let repo = open_repo("/path/to/repo").await?;
-/// let paths = get_repo_paths(&repo).await?;
+/// let paths = get_repo_paths(repo).await?;
 /// println!("Git dir: {:?}", paths.git_dir);
 /// println!("Work dir: {:?}", paths.work_dir);
 /// # Ok(())
 /// # }
 /// ```
-pub async fn get_repo_paths(repo: &RepoHandle) -> GitResult<RepoPaths> {
+pub async fn get_repo_paths(repo: RepoHandle) -> GitResult<RepoPaths> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
@@ -173,6 +267,373 @@ pub async fn get_repo_paths(repo: &RepoHandle) -> GitResult<RepoPaths> {
     .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
 }
 
+/// Check whether a file (or directory) exists at a revision.
+///
+/// `path` is relative to the repository root; absolute paths inside the
+/// working directory are accepted too.
+///
+/// # Example
+///
+/// ```rust
+/// use kodegen_tools_git::{open_repo, file_exists_at};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let repo = open_repo("/path/to/repo").await??;
+/// if file_exists_at(repo, "v1.0.0", "src/lib.rs").await? {
+///     println!("src/lib.rs existed in v1.0.0");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn file_exists_at(
+    repo: RepoHandle,
+    rev: &str,
+    path: impl AsRef<Path>,
+) -> GitResult<bool> {
+    let repo_clone = repo.clone_inner();
+    let rev = rev.to_string();
+    let path = path.as_ref().to_path_buf();
+
+    tokio::task::spawn_blocking(move || Ok(entry_at(&repo_clone, &rev, &path)?.is_some()))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Check whether a file (or directory) differs between two revisions.
+///
+/// Compares object ids and modes, so no diff is computed. A path that exists
+/// in only one of the revisions counts as changed; a path missing from both
+/// does not.
+///
+/// # Example
+///
+/// ```rust
+/// use kodegen_tools_git::{open_repo, file_changed_between};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let repo = open_repo("/path/to/repo").await??;
+/// let changed = file_changed_between(repo, "main", "HEAD", "Cargo.toml").await?;
+/// println!("Cargo.toml changed: {changed}");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn file_changed_between(
+    repo: RepoHandle,
+    rev_a: &str,
+    rev_b: &str,
+    path: impl AsRef<Path>,
+) -> GitResult<bool> {
+    let repo_clone = repo.clone_inner();
+    let rev_a = rev_a.to_string();
+    let rev_b = rev_b.to_string();
+    let path = path.as_ref().to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        let before = entry_at(&repo_clone, &rev_a, &path)?;
+        let after = entry_at(&repo_clone, &rev_b, &path)?;
+        Ok(before != after)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Look up the object id and mode of `path` in the tree of `rev`.
+fn entry_at(
+    repo: &gix::Repository,
+    rev: &str,
+    path: &Path,
+) -> GitResult<Option<(gix::ObjectId, gix::objs::tree::EntryMode)>> {
+    use gix::bstr::ByteSlice;
+
+    let path = match repo.workdir() {
+        Some(workdir) if path.is_absolute() => path.strip_prefix(workdir).map_err(|_| {
+            GitError::InvalidInput(format!("Path {} is not within repository", path.display()))
+        })?,
+        _ => path,
+    };
+
+    let tree = repo
+        .rev_parse_single(rev.as_bytes().as_bstr())
+        .map_err(GitError::gix)?
+        .object()
+        .map_err(GitError::gix)?
+        .peel_to_tree()
+        .map_err(GitError::gix)?;
+
+    let entry = tree.lookup_entry_by_path(path).map_err(GitError::gix)?;
+
+    Ok(entry.map(|e| (e.object_id(), e.mode())))
+}
+
+/// Read the entries of `FETCH_HEAD`, as written by the last fetch.
+///
+/// Returns an empty list if nothing was fetched yet.
+pub async fn fetch_head(repo: RepoHandle) -> GitResult<Vec<FetchHeadEntry>> {
+    let git_dir = repo.raw().path().to_path_buf();
+
+    tokio::task::spawn_blocking(move || read_fetch_head(&git_dir))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// The merge in progress, from `MERGE_HEAD` and `MERGE_MSG`.
+///
+/// Returns `None` unless a merge stopped before committing, for example on
+/// conflicts or with `--no-commit`.
+pub async fn merge_state(repo: RepoHandle) -> GitResult<Option<MergeState>> {
+    let git_dir = repo.raw().path().to_path_buf();
+
+    tokio::task::spawn_blocking(move || read_merge_state(&git_dir))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Check whether an object is in the repository's object database.
+///
+/// Looks at loose objects, packs and alternates; nothing is decoded.
+pub async fn object_exists(repo: RepoHandle, oid: gix::ObjectId) -> GitResult<bool> {
+    let repo_clone = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || Ok(repo_clone.has_object(oid)))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Check whether an object can be reached from the given refs.
+///
+/// `from_refs` are ref names or revisions; empty means every ref and `HEAD`.
+/// Commits are found by walking history only, trees and blobs by also walking
+/// the trees of every reachable commit. An object missing from the repository
+/// is never reachable.
+///
+/// # Example
+///
+/// ```rust
+/// use kodegen_tools_git::{open_repo, is_reachable};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let repo = open_repo("/path/to/repo").await??;
+/// let id = gix::ObjectId::from_hex(b"0123456789abcdef0123456789abcdef01234567")?;
+/// if !is_reachable(repo, id, &["main"]).await? {
+///     println!("{id} is not on main");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn is_reachable(
+    repo: RepoHandle,
+    oid: gix::ObjectId,
+    from_refs: &[&str],
+) -> GitResult<bool> {
+    let repo_clone = repo.clone_inner();
+    let from_refs: Vec<String> = from_refs.iter().map(ToString::to_string).collect();
+
+    tokio::task::spawn_blocking(move || {
+        use gix::bstr::ByteSlice;
+
+        let Some(header) = repo_clone.try_find_header(oid).map_err(GitError::gix)? else {
+            return Ok(false);
+        };
+        let tips = if from_refs.is_empty() {
+            ref_tips(&repo_clone)?
+        } else {
+            from_refs
+                .iter()
+                .map(|name| {
+                    repo_clone
+                        .rev_parse_single(name.as_bytes().as_bstr())
+                        .map(gix::Id::detach)
+                        .map_err(|e| GitError::InvalidInput(format!("Invalid ref '{name}': {e}")))
+                })
+                .collect::<GitResult<_>>()?
+        };
+        let trees = matches!(
+            header.kind(),
+            gix::object::Kind::Tree | gix::object::Kind::Blob
+        );
+        let mut found = false;
+        walk_reachable(&repo_clone, tips, trees, |id| {
+            found = id == oid;
+            !found
+        })?;
+        Ok(found)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Count the objects reachable from every ref and `HEAD`.
+///
+/// Commits, trees, blobs and annotated tags are counted once each. Objects
+/// kept alive only by reflogs or the index are not, so the difference to the
+/// total object count is an upper bound of what garbage collection can prune.
+///
+/// With reachability bitmaps written by
+/// [`write_midx`](super::midx::write_midx) the count is read from them
+/// instead of walking every tree.
+pub async fn reachable_count(repo: RepoHandle) -> GitResult<usize> {
+    let repo_clone = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || {
+        if let Some(&[count]) =
+            super::midx::bitmap_count(&repo_clone, &["--objects", "--all"]).as_deref()
+        {
+            return Ok(count);
+        }
+        let tips = ref_tips(&repo_clone)?;
+        let mut count = 0;
+        walk_reachable(&repo_clone, tips, true, |_| {
+            count += 1;
+            true
+        })?;
+        Ok(count)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Objects every ref and `HEAD` point at directly, annotated tags unpeeled
+fn ref_tips(repo: &gix::Repository) -> GitResult<Vec<gix::ObjectId>> {
+    let platform = repo.references().map_err(GitError::gix)?;
+    let mut tips: Vec<gix::ObjectId> = platform
+        .all()
+        .map_err(GitError::gix)?
+        .filter_map(Result::ok)
+        .filter_map(|reference| reference.target().try_id().map(ToOwned::to_owned))
+        .collect();
+    tips.extend(repo.head_id().ok().map(gix::Id::detach));
+    Ok(tips)
+}
+
+/// Visit each object reachable from `tips` once, through tags, parents and,
+/// with `trees`, commit trees, until `visit` returns `false`.
+///
+/// Objects missing from the repository, like the parents of a shallow
+/// boundary, end the walk along their path. Submodule commits are not
+/// followed.
+fn walk_reachable(
+    repo: &gix::Repository,
+    tips: Vec<gix::ObjectId>,
+    trees: bool,
+    mut visit: impl FnMut(gix::ObjectId) -> bool,
+) -> GitResult<()> {
+    let mut seen = std::collections::HashSet::new();
+    // Blobs are known from their tree entry and never read
+    let mut stack: Vec<(gix::ObjectId, bool)> = tips.into_iter().map(|id| (id, false)).collect();
+    while let Some((id, is_blob)) = stack.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        if is_blob {
+            if repo.has_object(id) && !visit(id) {
+                return Ok(());
+            }
+            continue;
+        }
+        let Some(object) = repo.try_find_object(id).map_err(GitError::gix)? else {
+            continue;
+        };
+        if !visit(id) {
+            return Ok(());
+        }
+        match object.kind {
+            gix::object::Kind::Commit => {
+                let commit = object.into_commit();
+                let commit = commit.decode().map_err(GitError::gix)?;
+                stack.extend(commit.parents().map(|parent| (parent, false)));
+                if trees {
+                    stack.push((commit.tree(), false));
+                }
+            }
+            gix::object::Kind::Tree => {
+                let tree = object.into_tree();
+                let tree = tree.decode().map_err(GitError::gix)?;
+                for entry in &tree.entries {
+                    if entry.mode.is_commit() {
+                        continue;
+                    }
+                    stack.push((entry.oid.to_owned(), entry.mode.is_blob_or_symlink()));
+                }
+            }
+            gix::object::Kind::Tag => {
+                let tag = object.into_tag();
+                let target = tag.target_id().map_err(GitError::gix)?;
+                stack.push((target.detach(), false));
+            }
+            gix::object::Kind::Blob => {}
+        }
+    }
+    Ok(())
+}
+
+/// Entries of `FETCH_HEAD` in `git_dir`; lines that cannot be parsed are skipped
+pub(crate) fn read_fetch_head(git_dir: &Path) -> GitResult<Vec<FetchHeadEntry>> {
+    let Some(contents) = read_optional(&git_dir.join("FETCH_HEAD"))? else {
+        return Ok(Vec::new());
+    };
+    Ok(contents.lines().filter_map(parse_fetch_head_line).collect())
+}
+
+fn read_merge_state(git_dir: &Path) -> GitResult<Option<MergeState>> {
+    let Some(merge_head) = read_optional(&git_dir.join("MERGE_HEAD"))? else {
+        return Ok(None);
+    };
+    let heads = merge_head
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            CommitId::from_hex(line.trim().as_bytes())
+                .map_err(|e| GitError::Parse(format!("Invalid MERGE_HEAD entry '{line}': {e}")))
+        })
+        .collect::<GitResult<_>>()?;
+    let message = read_optional(&git_dir.join("MERGE_MSG"))?;
+
+    Ok(Some(MergeState { heads, message }))
+}
+
+/// Contents of `path`, or `None` if it does not exist
+fn read_optional(path: &Path) -> GitResult<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(GitError::Io(e)),
+    }
+}
+
+/// Parse `<id> TAB [not-for-merge] TAB <description> of <url>`
+fn parse_fetch_head_line(line: &str) -> Option<FetchHeadEntry> {
+    let mut fields = line.splitn(3, '\t');
+    let id = CommitId::from_hex(fields.next()?.as_bytes()).ok()?;
+    let for_merge = fields.next()? != "not-for-merge";
+    let description = fields.next()?;
+
+    // Fetching a remote's HEAD records only the URL
+    let (fetched, url) = description.rsplit_once(" of ").unwrap_or(("", description));
+    Some(FetchHeadEntry {
+        id,
+        ref_name: fetched_ref(fetched),
+        url: url.trim().to_string(),
+        for_merge,
+    })
+}
+
+/// Full ref name of a `FETCH_HEAD` description such as `branch 'main'`
+fn fetched_ref(fetched: &str) -> Option<String> {
+    let quoted = |kind: &str| -> Option<&str> {
+        fetched.strip_prefix(kind)?.strip_prefix('\'')?.strip_suffix('\'')
+    };
+    if let Some(name) = quoted("branch ") {
+        Some(format!("refs/heads/{name}"))
+    } else if let Some(name) = quoted("tag ") {
+        Some(format!("refs/tags/{name}"))
+    } else if let Some(name) = quoted("remote-tracking branch ") {
+        Some(format!("refs/remotes/{name}"))
+    } else {
+        // Any other ref is recorded by its full name
+        quoted("").map(ToString::to_string)
+    }
+}
+
 /// Parse a Git URL into its components.
 ///
 /// Supports multiple URL formats:
@@ -180,8 +641,12 @@ pub async fn get_repo_paths(repo: &RepoHandle) -> GitResult<RepoPaths> {
 /// - SSH protocol: `ssh://git@github.com/owner/repo.git`
 /// - HTTPS: `https://github.com/owner/repo.git`
 /// - HTTP: `http://github.com/owner/repo.git`
+/// - Local paths and `file://` URLs
 ///
-/// For GitHub URLs, extracts owner and repo names.
+/// URLs with whitespace or control characters, network URLs without a host
+/// and URLs without a path are rejected. User and port are kept, and owner
+/// and repo are read from the path as the [`HostKind`] lays it out, so
+/// GitLab's nested groups end up in the owner.
 ///
 /// # Example
 ///
@@ -189,62 +654,126 @@ pub async fn get_repo_paths(repo: &RepoHandle) -> GitResult<RepoPaths> {
 /// use kodegen_tools_git::parse_git_url;
 ///
 /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-/// let url = parse_git_url("git@github.com:owner/repo.git").await?;
+/// let url = parse_git_url("git@GitHub.com:owner/repo.git").await?;
+/// assert_eq!(url.url, "git@github.com:owner/repo.git");
 /// assert_eq!(url.owner, Some("owner".to_string()));
 /// assert_eq!(url.repo, Some("repo".to_string()));
+///
+/// let url = parse_git_url("ssh://git@gitlab.com:2222/group/subgroup/repo.git").await?;
+/// assert_eq!(url.port, Some(2222));
+/// assert_eq!(url.owner, Some("group/subgroup".to_string()));
 /// # Ok(())
 /// # }
 /// ```
 pub async fn parse_git_url(url: &str) -> GitResult<GitUrl> {
     let url = url.to_string();
 
-    tokio::task::spawn_blocking(move || {
-        use gix::bstr::{BStr, ByteSlice};
-
-        // Parse URL using gix_url
-        let parsed = gix_url::parse(BStr::new(url.as_bytes()))
-            .map_err(|e| GitError::Parse(format!("Failed to parse Git URL: {e}")))?;
-
-        let scheme = parsed.scheme.as_str().to_string();
-        let host = parsed
-            .host()
-            .map(|h| h.to_string())
-            .unwrap_or_default();
-        let path = parsed.path.to_str_lossy().to_string();
-
-        // Try to extract owner/repo from path
-        let (owner, repo) = extract_owner_repo(&path);
-
-        Ok(GitUrl {
-            scheme,
-            host,
-            path,
-            owner,
-            repo,
-        })
-    })
-    .await
-    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+    tokio::task::spawn_blocking(move || parse_url(&url))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
 }
 
-/// Extract owner and repo from a Git path.
-fn extract_owner_repo(path: &str) -> (Option<String>, Option<String>) {
-    // Remove leading slash
-    let path = path.trim_start_matches('/');
+/// Parse and normalize `url`, see [`parse_git_url`]
+pub(crate) fn parse_url(url: &str) -> GitResult<GitUrl> {
+    use gix::bstr::{BStr, ByteSlice};
 
-    // Remove .git suffix
-    let path = path.trim_end_matches(".git");
+    let invalid = |reason: &str| GitError::Parse(format!("Invalid Git URL '{url}': {reason}"));
+    if url.is_empty() {
+        return Err(invalid("empty"));
+    }
+    if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(invalid("contains whitespace or control characters"));
+    }
 
-    // Split by '/'
-    let parts: Vec<&str> = path.split('/').collect();
+    // Schemes are case-insensitive, but gix only knows them in lowercase
+    let lowered;
+    let url_str = match url.split_once("://") {
+        Some((scheme, rest))
+            if scheme.chars().any(|c| c.is_ascii_uppercase())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) =>
+        {
+            lowered = format!("{}://{rest}", scheme.to_ascii_lowercase());
+            lowered.as_str()
+        }
+        _ => url,
+    };
+
+    // Parse URL using gix_url
+    let parsed = gix_url::parse(BStr::new(url_str.as_bytes()))
+        .map_err(|e| GitError::Parse(format!("Failed to parse Git URL: {e}")))?;
+    let is_local = parsed.scheme == gix_url::Scheme::File;
+    if !is_local && parsed.host().is_none_or(str::is_empty) {
+        return Err(invalid("missing host"));
+    }
+    if parsed.path.trim_start_with(|c| c == '/').is_empty() {
+        return Err(invalid("missing repository path"));
+    }
 
-    if parts.len() >= 2 {
-        let owner = parts[0].to_string();
-        let repo = parts[1].to_string();
-        (Some(owner), Some(repo))
+    // Host names are case-insensitive; paths are not
+    let parsed = gix_url::Url::from_parts(
+        parsed.scheme.clone(),
+        parsed.user().map(ToOwned::to_owned),
+        parsed.password().map(ToOwned::to_owned),
+        parsed.host().map(str::to_ascii_lowercase),
+        parsed.port,
+        parsed.path.clone(),
+        parsed.serialize_alternative_form,
+    )
+    .map_err(|e| invalid(&e.to_string()))?;
+
+    let scheme = parsed.scheme.as_str().to_string();
+    let host = parsed.host().map(ToString::to_string).unwrap_or_default();
+    let path = parsed.path.to_str_lossy().to_string();
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(ToString::to_string)
+        .collect();
+    let host_kind = if is_local {
+        HostKind::Other
     } else {
-        (None, None)
-    }
+        HostKind::of(&host)
+    };
+
+    // Try to extract owner/repo from path
+    let (owner, repo) = extract_owner_repo(host_kind, &segments);
+
+    Ok(GitUrl {
+        url: parsed.to_bstring().to_str_lossy().into_owned(),
+        scheme,
+        user: parsed.user().map(ToString::to_string),
+        host,
+        port: parsed.port,
+        host_kind,
+        path,
+        segments,
+        owner,
+        repo,
+    })
+}
+
+/// Extract owner and repo from the segments of a Git path.
+///
+/// GitHub and Bitbucket have exactly `owner/repo`; later segments, as in
+/// browser URLs, are ignored. Azure DevOps puts `_git` between project and
+/// repo, or prefixes SSH paths with `v3`. Elsewhere, as in GitLab's nested
+/// groups, everything before the last segment is the owner.
+fn extract_owner_repo(kind: HostKind, segments: &[String]) -> (Option<String>, Option<String>) {
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    let (owner, repo) = match (kind, segments.as_slice()) {
+        (HostKind::GitHub | HostKind::Bitbucket, [owner, repo, ..]) => (owner.to_string(), *repo),
+        (HostKind::Azure, ["v3", org, project, repo, ..] | [org, project, "_git", repo, ..]) => {
+            (format!("{org}/{project}"), *repo)
+        }
+        (HostKind::Azure, [project, "_git", repo, ..]) => (project.to_string(), *repo),
+        (_, [owner @ .., repo]) if !owner.is_empty() => (owner.join("/"), *repo),
+        _ => return (None, None),
+    };
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    (Some(owner), Some(repo.to_string()))
 }
 
 #[cfg(test)]
@@ -252,17 +781,132 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_extract_owner_repo() {
-        let (owner, repo) = extract_owner_repo("/owner/repo.git");
-        assert_eq!(owner, Some("owner".to_string()));
-        assert_eq!(repo, Some("repo".to_string()));
+    fn test_parse_fetch_head_line() {
+        let id = "1".repeat(40);
+        let entry = parse_fetch_head_line(&format!(
+            "{id}\t\tbranch 'main' of https://example.com/repo"
+        ))
+        .unwrap();
+        assert_eq!(entry.ref_name.as_deref(), Some("refs/heads/main"));
+        assert_eq!(entry.url, "https://example.com/repo");
+        assert!(entry.for_merge);
+
+        let entry = parse_fetch_head_line(&format!(
+            "{id}\tnot-for-merge\t'refs/pull/1/head' of github.com:owner/repo"
+        ))
+        .unwrap();
+        assert_eq!(entry.ref_name.as_deref(), Some("refs/pull/1/head"));
+        assert!(!entry.for_merge);
+
+        let entry = parse_fetch_head_line(&format!("{id}\t\t../upstream")).unwrap();
+        assert_eq!(entry.ref_name, None);
+        assert_eq!(entry.url, "../upstream");
+
+        assert!(parse_fetch_head_line("not a fetch head line").is_none());
+    }
 
-        let (owner, repo) = extract_owner_repo("owner/repo");
-        assert_eq!(owner, Some("owner".to_string()));
-        assert_eq!(repo, Some("repo".to_string()));
+    #[test]
+    fn test_parse_url() {
+        let url = parse_url("git@GitHub.com:owner/repo.git").unwrap();
+        assert_eq!(url.url, "git@github.com:owner/repo.git");
+        assert_eq!(
+            (url.scheme.as_str(), url.host.as_str()),
+            ("ssh", "github.com")
+        );
+        assert_eq!(url.owner.as_deref(), Some("owner"));
+
+        let url = parse_url("HTTPS://Example.com/Owner/Repo.git").unwrap();
+        assert_eq!(url.url, "https://example.com/Owner/Repo.git");
+
+        let url = parse_url("../upstream").unwrap();
+        assert_eq!(
+            (url.scheme.as_str(), url.url.as_str()),
+            ("file", "../upstream")
+        );
+
+        for invalid in [
+            "",
+            "https://",
+            "https:///repo",
+            "git@host:",
+            "not a url",
+            "https://host/a\nb",
+        ] {
+            assert!(parse_url(invalid).is_err(), "{invalid:?} was accepted");
+        }
+    }
 
-        let (owner, repo) = extract_owner_repo("invalid");
-        assert_eq!(owner, None);
-        assert_eq!(repo, None);
+    #[test]
+    fn test_extract_owner_repo() {
+        let extract = |kind, path: &str| {
+            let segments: Vec<String> = path
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(ToString::to_string)
+                .collect();
+            let (owner, repo) = extract_owner_repo(kind, &segments);
+            (owner.unwrap_or_default(), repo.unwrap_or_default())
+        };
+        let pair = |owner: &str, repo: &str| (owner.to_string(), repo.to_string());
+
+        assert_eq!(
+            extract(HostKind::Other, "/owner/repo.git"),
+            pair("owner", "repo")
+        );
+        assert_eq!(
+            extract(HostKind::Other, "owner/repo"),
+            pair("owner", "repo")
+        );
+        assert_eq!(extract(HostKind::Other, "invalid"), pair("", ""));
+        assert_eq!(extract(HostKind::Other, ""), pair("", ""));
+
+        // Only the first two segments count on GitHub
+        assert_eq!(
+            extract(HostKind::GitHub, "/owner/repo/tree/main"),
+            pair("owner", "repo")
+        );
+        assert_eq!(
+            extract(HostKind::GitLab, "/group/subgroup/deeper/repo.git"),
+            pair("group/subgroup/deeper", "repo")
+        );
+        assert_eq!(
+            extract(HostKind::Azure, "/org/project/_git/repo"),
+            pair("org/project", "repo")
+        );
+        assert_eq!(
+            extract(HostKind::Azure, "v3/org/project/repo"),
+            pair("org/project", "repo")
+        );
+        // The repository name may itself end in .git only once
+        assert_eq!(extract(HostKind::Other, "a/b.git.git"), pair("a", "b.git"));
+    }
+
+    #[test]
+    fn test_parse_url_components() {
+        let url = parse_url("ssh://deploy@gitlab.example.com:2222/group/sub/repo.git").unwrap();
+        assert_eq!(url.user.as_deref(), Some("deploy"));
+        assert_eq!(url.port, Some(2222));
+        assert_eq!(url.host_kind, HostKind::GitLab);
+        assert_eq!(url.segments, ["group", "sub", "repo.git"]);
+        assert_eq!(url.owner.as_deref(), Some("group/sub"));
+        assert_eq!(url.repo.as_deref(), Some("repo"));
+
+        let url = parse_url("https://user@dev.azure.com/org/project/_git/repo").unwrap();
+        assert_eq!(url.host_kind, HostKind::Azure);
+        assert_eq!(url.user.as_deref(), Some("user"));
+        assert_eq!(url.port, None);
+        assert_eq!(url.owner.as_deref(), Some("org/project"));
+
+        let url = parse_url("git@ssh.dev.azure.com:v3/org/project/repo").unwrap();
+        assert_eq!(url.host_kind, HostKind::Azure);
+        assert_eq!(url.owner.as_deref(), Some("org/project"));
+
+        let url = parse_url("https://bitbucket.org/team/repo.git").unwrap();
+        assert_eq!(url.host_kind, HostKind::Bitbucket);
+        assert_eq!(url.user, None);
+
+        let url = parse_url("/srv/git/repo.git").unwrap();
+        assert_eq!(url.host_kind, HostKind::Other);
+        assert_eq!(url.host, "");
     }
 }
@@ -0,0 +1,603 @@
+//! Repository hygiene checks.
+//!
+//! [`check`] runs a configurable set of checks over a repository and returns
+//! every problem found as a [`LintFinding`]: missing LICENSE or README files,
+//! oversized tracked files, commits without a `Signed-off-by` trailer, branches
+//! far behind or long untouched, broken symlinks, and paths that collide or
+//! are invalid on macOS and Windows. Nothing is modified.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{Duration, Utc};
+use gix::bstr::ByteSlice;
+
+use super::collisions::path_issues;
+use crate::{GitError, GitResult, RepoHandle};
+
+/// A hygiene check run by [`check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LintCheck {
+    /// No LICENSE, LICENCE or COPYING file at the root.
+    MissingLicense,
+    /// No README file at the root.
+    MissingReadme,
+    /// A tracked file is larger than [`LintRules::max_file_size`].
+    LargeFile,
+    /// A recent commit has no `Signed-off-by` trailer.
+    MissingSignoff,
+    /// A branch lacks more than [`LintRules::max_behind`] commits of the default branch.
+    BranchBehind,
+    /// A branch has had no commit for [`LintRules::stale_days`].
+    StaleBranch,
+    /// A tracked symlink points at nothing.
+    BrokenSymlink,
+    /// Tracked paths differ only by case or Unicode normalization.
+    CaseConflict,
+    /// A tracked path has a name Windows rejects.
+    NonPortablePath,
+}
+
+impl LintCheck {
+    /// Every check, in the order they run.
+    pub const ALL: [Self; 9] = [
+        Self::MissingLicense,
+        Self::MissingReadme,
+        Self::LargeFile,
+        Self::MissingSignoff,
+        Self::BranchBehind,
+        Self::StaleBranch,
+        Self::BrokenSymlink,
+        Self::CaseConflict,
+        Self::NonPortablePath,
+    ];
+
+    /// Name of the check, e.g. `large-file`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingLicense => "missing-license",
+            Self::MissingReadme => "missing-readme",
+            Self::LargeFile => "large-file",
+            Self::MissingSignoff => "missing-signoff",
+            Self::BranchBehind => "branch-behind",
+            Self::StaleBranch => "stale-branch",
+            Self::BrokenSymlink => "broken-symlink",
+            Self::CaseConflict => "case-conflict",
+            Self::NonPortablePath => "non-portable-path",
+        }
+    }
+
+    /// The check called `name`, as returned by [`LintCheck::as_str`].
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|check| check.as_str() == name)
+    }
+}
+
+impl std::fmt::Display for LintCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How serious a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    /// Worth knowing, often a matter of project policy.
+    Info,
+    /// Likely to cause trouble for contributors or tooling.
+    Warning,
+    /// Breaks checkouts for some users.
+    Error,
+}
+
+impl LintSeverity {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl std::fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which checks [`check`] runs, and their thresholds.
+#[derive(Debug, Clone)]
+pub struct LintRules {
+    pub checks: Vec<LintCheck>,
+    /// Tracked files above this many bytes are reported
+    pub max_file_size: u64,
+    /// Most recent commits on HEAD checked for a sign-off
+    pub signoff_commits: usize,
+    /// Branches missing more default-branch commits than this are reported
+    pub max_behind: usize,
+    /// Branches whose last commit is older than this many days are reported
+    pub stale_days: u32,
+    /// Branch the others are compared with (default: the remote's HEAD,
+    /// then `main`, then `master`)
+    pub default_branch: Option<String>,
+}
+
+impl Default for LintRules {
+    fn default() -> Self {
+        Self {
+            checks: LintCheck::ALL.to_vec(),
+            max_file_size: 5 * 1024 * 1024,
+            signoff_commits: 20,
+            max_behind: 100,
+            stale_days: 90,
+            default_branch: None,
+        }
+    }
+}
+
+impl LintRules {
+    /// Every check with default thresholds: 5 MiB files, 20 commits checked
+    /// for a sign-off, 100 commits behind, 90 days without a commit.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run only `checks`
+    #[inline]
+    #[must_use]
+    pub fn only(mut self, checks: impl IntoIterator<Item = LintCheck>) -> Self {
+        self.checks = checks.into_iter().collect();
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn signoff_commits(mut self, count: usize) -> Self {
+        self.signoff_commits = count;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn max_behind(mut self, count: usize) -> Self {
+        self.max_behind = count;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn stale_days(mut self, days: u32) -> Self {
+        self.stale_days = days;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn default_branch(mut self, name: impl Into<String>) -> Self {
+        self.default_branch = Some(name.into());
+        self
+    }
+}
+
+/// A problem found by a check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub check: LintCheck,
+    pub severity: LintSeverity,
+    /// What the finding is about: a path, branch or commit; empty for the
+    /// repository as a whole
+    pub subject: String,
+    pub message: String,
+}
+
+/// Result of [`check`].
+#[derive(Debug, Clone, Default)]
+pub struct LintReport {
+    /// Checks that ran
+    pub checks: Vec<LintCheck>,
+    /// Findings in check order
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    /// Whether no check found anything.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Findings of at least `severity`.
+    pub fn at_least(&self, severity: LintSeverity) -> impl Iterator<Item = &LintFinding> {
+        self.findings
+            .iter()
+            .filter(move |finding| finding.severity >= severity)
+    }
+}
+
+/// A tracked file: from the index with a working tree, from HEAD otherwise
+struct TrackedFile {
+    path: String,
+    id: gix::ObjectId,
+    symlink: bool,
+}
+
+/// Run the hygiene checks of `rules` over the repository.
+///
+/// Files are those in the index, or in HEAD for bare repositories; branch
+/// checks look at local branches. Checks that don't apply (e.g. branch checks
+/// without a default branch) report nothing.
+pub async fn check(repo: RepoHandle, rules: LintRules) -> GitResult<LintReport> {
+    let repo = repo.clone_for_traversal();
+
+    tokio::task::spawn_blocking(move || {
+        let mut checks = rules.checks.clone();
+        checks.sort();
+        checks.dedup();
+
+        let files = tracked_files(&repo)?;
+        let mut findings = Vec::new();
+        for check in &checks {
+            match check {
+                LintCheck::MissingLicense => {
+                    if !has_root_file(&files, &["license", "licence", "copying", "unlicense"]) {
+                        findings.push(repo_finding(
+                            *check,
+                            LintSeverity::Warning,
+                            "No LICENSE file at the repository root",
+                        ));
+                    }
+                }
+                LintCheck::MissingReadme => {
+                    if !has_root_file(&files, &["readme"]) {
+                        findings.push(repo_finding(
+                            *check,
+                            LintSeverity::Warning,
+                            "No README file at the repository root",
+                        ));
+                    }
+                }
+                LintCheck::LargeFile => {
+                    large_files(&repo, &files, rules.max_file_size, &mut findings)?
+                }
+                LintCheck::MissingSignoff => {
+                    missing_signoffs(&repo, rules.signoff_commits, &mut findings)?;
+                }
+                LintCheck::BranchBehind | LintCheck::StaleBranch => {
+                    // Both run from one pass over the branches, on the first of the two
+                    if checks.contains(&LintCheck::BranchBehind) && *check == LintCheck::StaleBranch
+                    {
+                        continue;
+                    }
+                    branch_checks(&repo, &rules, &checks, &mut findings)?;
+                }
+                LintCheck::BrokenSymlink => broken_symlinks(&repo, &files, &mut findings)?,
+                LintCheck::CaseConflict | LintCheck::NonPortablePath => {
+                    // Both come from one pass over the paths, like the branch checks
+                    if checks.contains(&LintCheck::CaseConflict)
+                        && *check == LintCheck::NonPortablePath
+                    {
+                        continue;
+                    }
+                    path_checks(&files, &checks, &mut findings);
+                }
+            }
+        }
+        Ok(LintReport { checks, findings })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+fn repo_finding(check: LintCheck, severity: LintSeverity, message: &str) -> LintFinding {
+    LintFinding {
+        check,
+        severity,
+        subject: String::new(),
+        message: message.to_string(),
+    }
+}
+
+fn tracked_files(repo: &gix::Repository) -> GitResult<Vec<TrackedFile>> {
+    if repo.workdir().is_some() {
+        let index = repo.index_or_empty().map_err(GitError::gix)?;
+        return Ok(index
+            .entries()
+            .iter()
+            .filter(|entry| !entry.mode.is_submodule() && entry.stage_raw() == 0)
+            .map(|entry| TrackedFile {
+                path: entry.path(&index).to_str_lossy().into_owned(),
+                id: entry.id,
+                symlink: entry.mode == gix::index::entry::Mode::SYMLINK,
+            })
+            .collect());
+    }
+
+    let Ok(commit) = repo.head_commit() else {
+        // Unborn HEAD: nothing committed yet
+        return Ok(Vec::new());
+    };
+    let tree = commit.tree().map_err(GitError::gix)?;
+    let entries = tree
+        .traverse()
+        .breadthfirst
+        .files()
+        .map_err(GitError::gix)?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| !entry.mode.is_tree() && !entry.mode.is_commit())
+        .map(|entry| TrackedFile {
+            path: entry.filepath.to_str_lossy().into_owned(),
+            id: entry.oid,
+            symlink: entry.mode.is_link(),
+        })
+        .collect())
+}
+
+/// Whether a root file's name, lowercased, starts with one of `prefixes`
+fn has_root_file(files: &[TrackedFile], prefixes: &[&str]) -> bool {
+    files.iter().any(|file| {
+        let name = file.path.to_lowercase();
+        !name.contains('/') && prefixes.iter().any(|prefix| name.starts_with(prefix))
+    })
+}
+
+fn large_files(
+    repo: &gix::Repository,
+    files: &[TrackedFile],
+    max: u64,
+    findings: &mut Vec<LintFinding>,
+) -> GitResult<()> {
+    for file in files {
+        // Staged but not yet written objects have nothing to measure
+        let Ok(header) = repo.find_header(file.id) else {
+            continue;
+        };
+        let size = header.size();
+        if size > max {
+            findings.push(LintFinding {
+                check: LintCheck::LargeFile,
+                severity: LintSeverity::Warning,
+                subject: file.path.clone(),
+                message: format!("{size} bytes, above the {max} byte limit"),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn missing_signoffs(
+    repo: &gix::Repository,
+    count: usize,
+    findings: &mut Vec<LintFinding>,
+) -> GitResult<()> {
+    let Ok(head) = repo.head_id() else {
+        return Ok(());
+    };
+    let walk = repo
+        .rev_walk([head.detach()])
+        .all()
+        .map_err(GitError::gix)?;
+    for info in walk.take(count) {
+        let info = info.map_err(GitError::gix)?;
+        let commit = repo.find_commit(info.id).map_err(GitError::gix)?;
+        // Merge commits are made by tooling and rarely signed off
+        if commit.parent_ids().count() > 1 {
+            continue;
+        }
+        let message = commit.message().map_err(GitError::gix)?;
+        // Trailers are the last paragraph, which may be the whole body; gix
+        // only finds them after a paragraph of text
+        let signed = message.body.is_some_and(|body| {
+            let last = body.trim_end().rsplit_str("\n\n").next().unwrap_or_default();
+            last.lines().any(|line| {
+                line.get(..14)
+                    .is_some_and(|token| token.eq_ignore_ascii_case(b"Signed-off-by:"))
+            })
+        });
+        if !signed {
+            findings.push(LintFinding {
+                check: LintCheck::MissingSignoff,
+                severity: LintSeverity::Info,
+                subject: info.id.to_string(),
+                message: format!(
+                    "'{}' has no Signed-off-by trailer",
+                    message.title.to_str_lossy().trim()
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The branch others are compared with: `configured`, the branch the
+/// remote's HEAD points at, `main` or `master`
+pub(crate) fn default_branch(repo: &gix::Repository, configured: Option<&str>) -> Option<String> {
+    if let Some(name) = configured {
+        return Some(name.to_string());
+    }
+    let remote = repo
+        .remote_default_name(gix::remote::Direction::Fetch)
+        .map_or_else(
+            || "origin".to_string(),
+            |name| name.to_str_lossy().into_owned(),
+        );
+    let remote_head = repo
+        .find_reference(format!("refs/remotes/{remote}/HEAD").as_str())
+        .ok()
+        .and_then(|reference| {
+            reference
+                .target()
+                .try_name()
+                .map(|name| name.shorten().to_string())
+        })
+        .and_then(|name| name.split_once('/').map(|(_, branch)| branch.to_string()));
+    remote_head
+        .into_iter()
+        .chain(["main".to_string(), "master".to_string()])
+        .find(|name| {
+            repo.find_reference(format!("refs/heads/{name}").as_str())
+                .is_ok()
+        })
+}
+
+/// [`LintCheck::BranchBehind`] and [`LintCheck::StaleBranch`] over local branches
+fn branch_checks(
+    repo: &gix::Repository,
+    rules: &LintRules,
+    checks: &[LintCheck],
+    findings: &mut Vec<LintFinding>,
+) -> GitResult<()> {
+    let default = default_branch(repo, rules.default_branch.as_deref());
+    let default_tip = match &default {
+        Some(name) => Some(super::compare::resolve_commit(repo, name)?),
+        None => None,
+    };
+    let stale_before = Utc::now() - Duration::days(i64::from(rules.stale_days));
+
+    let platform = repo.references().map_err(GitError::gix)?;
+    let references = platform.local_branches().map_err(GitError::gix)?;
+    let mut branches = Vec::new();
+    for reference in references {
+        let mut reference = reference.map_err(GitError::gix)?;
+        let name = reference.name().shorten().to_string();
+        if Some(&name) == default.as_ref() {
+            continue;
+        }
+        if let Ok(tip) = reference.peel_to_commit() {
+            let seconds = tip.time().map_err(GitError::gix)?.seconds;
+            branches.push((name, tip.id, seconds));
+        }
+    }
+    branches.sort();
+
+    for (name, tip, seconds) in branches {
+        if checks.contains(&LintCheck::BranchBehind)
+            && let (Some(default), Some(default_tip)) = (&default, default_tip)
+        {
+            let behind = repo
+                .rev_walk([default_tip])
+                .with_hidden([tip])
+                .all()
+                .map_err(GitError::gix)?
+                .count();
+            if behind > rules.max_behind {
+                findings.push(LintFinding {
+                    check: LintCheck::BranchBehind,
+                    severity: LintSeverity::Warning,
+                    subject: name.clone(),
+                    message: format!("{behind} commits behind {default}"),
+                });
+            }
+        }
+        if checks.contains(&LintCheck::StaleBranch) && seconds < stale_before.timestamp() {
+            let days = (Utc::now().timestamp() - seconds) / 86_400;
+            findings.push(LintFinding {
+                check: LintCheck::StaleBranch,
+                severity: LintSeverity::Info,
+                subject: name,
+                message: format!("No commit for {days} days"),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn broken_symlinks(
+    repo: &gix::Repository,
+    files: &[TrackedFile],
+    findings: &mut Vec<LintFinding>,
+) -> GitResult<()> {
+    let tracked: BTreeSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+    for file in files.iter().filter(|file| file.symlink) {
+        let broken = match repo.workdir() {
+            // A link missing from the working tree is a deletion, not a broken link
+            Some(workdir) => {
+                let link = workdir.join(&file.path);
+                link.symlink_metadata().is_ok() && std::fs::metadata(&link).is_err()
+            }
+            None => {
+                let target = repo
+                    .find_blob(file.id)
+                    .map_err(GitError::gix)?
+                    .detach()
+                    .data;
+                let target = target.to_str_lossy();
+                resolve_in_repo(&file.path, &target).is_some_and(|resolved| {
+                    let prefix = format!("{resolved}/");
+                    !tracked.contains(resolved.as_str())
+                        && !tracked.iter().any(|path| path.starts_with(&prefix))
+                })
+            }
+        };
+        if broken {
+            findings.push(LintFinding {
+                check: LintCheck::BrokenSymlink,
+                severity: LintSeverity::Warning,
+                subject: file.path.clone(),
+                message: "Symlink target does not exist".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Repository-relative path a relative symlink at `link` resolves to; `None`
+/// for absolute targets and targets outside the repository, which can't be
+/// checked without a working tree
+fn resolve_in_repo(link: &str, target: &str) -> Option<String> {
+    let mut resolved: PathBuf = Path::new(link)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    for component in Path::new(target).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(resolved.to_string_lossy().replace('\\', "/"))
+}
+
+/// [`LintCheck::CaseConflict`] and [`LintCheck::NonPortablePath`] findings
+fn path_checks(files: &[TrackedFile], checks: &[LintCheck], findings: &mut Vec<LintFinding>) {
+    for issue in path_issues(files.iter().map(|file| file.path.as_str())) {
+        let check = if issue.kind.is_collision() {
+            LintCheck::CaseConflict
+        } else {
+            LintCheck::NonPortablePath
+        };
+        if !checks.contains(&check) {
+            continue;
+        }
+        let message = if issue.conflicts_with.is_empty() {
+            format!("Invalid on Windows: {}", issue.kind)
+        } else {
+            format!("Collides with {} ({})", issue.conflicts_with.join(", "), issue.kind)
+        };
+        findings.push(LintFinding {
+            check,
+            severity: LintSeverity::Error,
+            subject: issue.path,
+            message,
+        });
+    }
+}
@@ -3,21 +3,35 @@
 //! This module provides the `LogOpts` builder pattern and log operation
 //! implementation for the `GitGix` service.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use tokio::sync::mpsc;
 
+use super::commit::git_time_to_date;
+use super::encoding::commit_summary;
+use super::warnings::{OperationWarning, WarningKind};
 use crate::runtime::AsyncStream;
 use crate::{CommitInfo, GitError, GitResult, RepoHandle, Signature};
 
+/// Commits the walk may run ahead of the consumer
+const LOG_BUFFER: usize = 32;
+
 /// Options for `log` operation with builder pattern.
 #[derive(Debug, Clone)]
 pub struct LogOpts {
     pub max_count: Option<usize>,
+    /// Matching commits to leave out before the first one returned.
+    pub skip: usize,
     pub since: Option<DateTime<Utc>>,
     pub until: Option<DateTime<Utc>>,
     pub path: Option<PathBuf>,
+    /// Fill in [`CommitInfo::decorations`] with the refs pointing at each commit.
+    pub decorate: bool,
+    /// Walk newest first by commit time so children come before their
+    /// parents, as [`super::graph::render_graph`] needs.
+    pub graph: bool,
 }
 
 impl LogOpts {
@@ -27,9 +41,12 @@ impl LogOpts {
     pub fn new() -> Self {
         Self {
             max_count: None,
+            skip: 0,
             since: None,
             until: None,
             path: None,
+            decorate: false,
+            graph: false,
         }
     }
 
@@ -41,6 +58,14 @@ impl LogOpts {
         self
     }
 
+    /// Skip the first `count` matching commits, for paging.
+    #[inline]
+    #[must_use]
+    pub fn skip(mut self, count: usize) -> Self {
+        self.skip = count;
+        self
+    }
+
     /// Set start date filter (only commits after this date).
     #[inline]
     #[must_use]
@@ -63,6 +88,22 @@ impl LogOpts {
         self.path = Some(path.into());
         self
     }
+
+    /// List the branches and tags pointing at each commit, like `git log --decorate`.
+    #[inline]
+    #[must_use]
+    pub fn decorate(mut self, decorate: bool) -> Self {
+        self.decorate = decorate;
+        self
+    }
+
+    /// Order commits for drawing the commit graph, like `git log --graph`.
+    #[inline]
+    #[must_use]
+    pub fn graph(mut self, graph: bool) -> Self {
+        self.graph = graph;
+        self
+    }
 }
 
 impl Default for LogOpts {
@@ -72,23 +113,30 @@ impl Default for LogOpts {
 }
 
 /// Execute log operation with the given options, returning a stream of commits.
+///
+/// Commits are produced as the stream is consumed, a few ahead at most. The
+/// walk ends after `max_count` commits or as soon as the stream is dropped,
+/// so callers that only need the first page can stop reading early.
 pub fn log(
     repo: RepoHandle,
     opts: LogOpts,
     client_pwd: Option<&std::path::Path>,
 ) -> AsyncStream<GitResult<CommitInfo>> {
-    let (tx, rx) = mpsc::unbounded_channel();
-    let repo = repo.clone_inner();
-    
+    let (tx, rx) = mpsc::channel(LOG_BUFFER);
+    let repo = repo.clone_for_traversal();
+
     // Convert borrowed path to owned for 'static lifetime requirement
     let client_pwd_owned = client_pwd.map(|p| p.to_path_buf());
 
     tokio::task::spawn_blocking(move || {
         let LogOpts {
             max_count,
+            skip,
             since,
             until,
             path,
+            decorate,
+            graph,
         } = opts;
 
         // Normalize path if provided
@@ -96,7 +144,7 @@ pub fn log(
             Some(match normalize_path(&repo, p, client_pwd_owned.as_deref()) {
                 Ok(normalized) => normalized,
                 Err(e) => {
-                    let _ = tx.send(Err(e));
+                    let _ = tx.blocking_send(Err(e));
                     return;
                 }
             })
@@ -104,58 +152,65 @@ pub fn log(
             None
         };
 
+        // Commits beyond which a shallow clone has no history
+        let boundary = match super::shallow::shallow_boundary(&repo) {
+            Ok(boundary) => boundary,
+            Err(e) => {
+                let _ = tx.blocking_send(Err(e));
+                return;
+            }
+        };
+
+        // Refs by commit, resolved once up front
+        let mut decorations = if decorate {
+            match super::decorate::decorations(&repo) {
+                Ok(decorations) => decorations,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(e));
+                    return;
+                }
+            }
+        } else {
+            HashMap::new()
+        };
+
         // Create revision walker
         let head_id = match repo.head_id() {
             Ok(id) => id,
             Err(e) => {
-                let _ = tx.send(Err(GitError::Gix(Box::new(e))));
+                let _ = tx.blocking_send(Err(GitError::gix(e)));
                 return;
             }
         };
-        let rev_walk = match repo.rev_walk([head_id.detach()]).all() {
+        let mut platform = repo.rev_walk([head_id.detach()]);
+        if graph {
+            use gix::revision::walk::Sorting;
+            use gix::traverse::commit::simple::CommitTimeOrder;
+            platform = platform.sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst));
+        }
+        let rev_walk = match platform.all() {
             Ok(walker) => walker,
             Err(e) => {
-                let _ = tx.send(Err(GitError::Gix(e.into())));
+                let _ = tx.blocking_send(Err(GitError::gix(e)));
                 return;
             }
         };
 
+        if max_count == Some(0) {
+            return;
+        }
+        let mut skipped = 0;
         let mut count = 0;
 
         // Stream commits one at a time
         for commit_result in rev_walk {
-            // Check max_count limit
-            if let Some(max) = max_count
-                && count >= max
-            {
-                break;
-            }
-
             match commit_result {
                 Ok(info) => {
                     match repo.find_object(info.id).map(gix::Object::into_commit) {
                         Ok(commit) => {
-                            // Get commit time with proper error handling
-                            let time = match commit.time() {
-                                Ok(t) => t,
-                                Err(e) => {
-                                    let _ = tx.send(Err(GitError::Gix(Box::new(e))));
-                                    continue;
-                                }
-                            };
-
-                            let commit_time = {
-                                use chrono::TimeZone;
-                                if let Some(t) = Utc.timestamp_opt(time.seconds, 0).single() {
-                                    t
-                                } else {
-                                    let _ = tx.send(Err(GitError::InvalidInput(format!(
-                                        "Invalid timestamp {} for commit {}",
-                                        time.seconds, info.id
-                                    ))));
-                                    continue;
-                                }
-                            };
+                            let mut warnings = Vec::new();
+                            let commit_date = commit_date(&commit, &mut warnings);
+                            let commit_time = commit_date.to_utc();
 
                             // Apply time filters (cheapest checks first)
                             if let Some(since_time) = since
@@ -179,59 +234,102 @@ pub fn log(
                                         }
                                     }
                                     Err(e) => {
-                                        let _ = tx.send(Err(e));
+                                        let _ = tx.blocking_send(Err(e));
                                         return;
                                     }
                                 }
                             }
 
-                            // Get author information only after all filters pass
-                            let author_sig = match commit.author() {
-                                Ok(sig) => sig,
-                                Err(e) => {
-                                    let _ = tx.send(Err(GitError::Gix(Box::new(e))));
-                                    continue;
-                                }
-                            };
+                            // Skip only commits that pass the filters
+                            if skipped < skip {
+                                skipped += 1;
+                                continue;
+                            }
 
-                            let author_owned = match author_sig.to_owned() {
-                                Ok(sig) => sig,
-                                Err(e) => {
-                                    let _ = tx.send(Err(GitError::Gix(Box::new(e))));
-                                    continue;
-                                }
-                            };
+                            // Get author information only after all filters pass
+                            let author = commit_author(&commit, commit_date, &mut warnings);
 
-                            use gix::bstr::ByteSlice;
                             let commit_info = CommitInfo {
                                 id: info.id,
-                                author: Signature::from(author_owned),
-                                summary: commit
-                                    .message()
-                                    .map(|msg| msg.summary().as_bstr().to_string())
-                                    .unwrap_or_default(),
+                                parents: info.parent_ids.iter().copied().collect(),
+                                author,
+                                summary: commit_summary(&commit),
                                 time: commit_time,
+                                offset: *commit_date.offset(),
+                                shallow_boundary: boundary.contains(&info.id),
+                                decorations: decorations.remove(&info.id).unwrap_or_default(),
+                                warnings,
                             };
 
-                            // Send to stream - if receiver dropped, stop
-                            if tx.send(Ok(commit_info)).is_err() {
+                            // Waits while the consumer is behind; stops once the
+                            // stream is dropped
+                            if tx.blocking_send(Ok(commit_info)).is_err() {
                                 break;
                             }
                             count += 1;
+                            if max_count.is_some_and(|max| count >= max) {
+                                break;
+                            }
                         }
                         Err(e) => {
-                            let _ = tx.send(Err(GitError::Gix(e.into())));
+                            let _ = tx.blocking_send(Err(GitError::gix(e)));
                         }
                     }
                 }
                 Err(e) => {
-                    let _ = tx.send(Err(GitError::Gix(e.into())));
+                    let _ = tx.blocking_send(Err(GitError::gix(e)));
                 }
             }
         }
     });
 
-    AsyncStream::new(rx)
+    AsyncStream::bounded(rx)
+}
+
+/// Time of `commit`, or the Unix epoch with a warning if it can't be read
+pub(crate) fn commit_date(
+    commit: &gix::Commit<'_>,
+    warnings: &mut Vec<OperationWarning>,
+) -> DateTime<FixedOffset> {
+    let reason = match commit.time() {
+        Ok(time) => match git_time_to_date(time) {
+            Some(date) => return date,
+            None => format!("commit time {time} is out of range"),
+        },
+        Err(e) => format!("commit time could not be read ({e})"),
+    };
+    warnings.push(
+        OperationWarning::new(
+            WarningKind::MissingTimestamp,
+            format!("{reason}; using the Unix epoch"),
+        )
+        .subject(commit.id.to_string()),
+    );
+    DateTime::<Utc>::default().fixed_offset()
+}
+
+/// Author of `commit`, or a nameless signature at `date` with a warning if
+/// it can't be parsed
+pub(crate) fn commit_author(
+    commit: &gix::Commit<'_>,
+    date: DateTime<FixedOffset>,
+    warnings: &mut Vec<OperationWarning>,
+) -> Signature {
+    let error = match commit.author() {
+        Ok(author) => match Signature::try_from(author) {
+            Ok(author) => return author,
+            Err(e) => e,
+        },
+        Err(e) => GitError::gix(e),
+    };
+    warnings.push(
+        OperationWarning::new(
+            WarningKind::UnparsableSignature,
+            format!("author could not be parsed ({error}); leaving it empty"),
+        )
+        .subject(commit.id.to_string()),
+    );
+    Signature::with_date("", "", date)
 }
 
 /// Normalize path to repo-relative format
@@ -339,7 +437,8 @@ fn change_matches_path(change_location: &gix::bstr::BStr, filter_path: &std::pat
 /// For root commits (no parents), checks if the path exists in the commit's tree.
 /// For regular commits, diffs against each parent to detect changes.
 /// For merge commits with multiple parents, returns true if the path was modified
-/// relative to *any* parent.
+/// relative to *any* parent. Commits on the boundary of a shallow clone, whose
+/// parents are missing, are treated like root commits.
 ///
 /// Uses zero-allocation iteration over parent IDs and early-exit optimization
 /// to minimize overhead in the path filtering hot path.
@@ -350,17 +449,17 @@ pub fn commit_touches_path(
     filter_path: &std::path::Path,
 ) -> GitResult<bool> {
     // Get commit's tree
-    let commit_tree = commit.tree().map_err(|e| GitError::Gix(Box::new(e)))?;
+    let commit_tree = commit.tree().map_err(GitError::gix)?;
 
     // Peek at parent count without allocation
     let mut parent_iter = commit.parent_ids();
     let first_parent = match parent_iter.next() {
-        Some(p) => p,
-        None => {
-            // Root commit (no parents) - check if path exists in tree
+        Some(p) if repo.has_object(p) => p,
+        _ => {
+            // Root commit (no parents available) - check if path exists in tree
             return Ok(commit_tree
                 .lookup_entry_by_path(filter_path)
-                .map_err(|e| GitError::Gix(Box::new(e)))?
+                .map_err(GitError::gix)?
                 .is_some());
         }
     };
@@ -368,13 +467,9 @@ pub fn commit_touches_path(
     // Compare with first parent
     let parent_obj = repo
         .find_object(first_parent.detach())
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
-    let parent_commit = parent_obj
-        .try_into_commit()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
-    let parent_tree = parent_commit
-        .tree()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+        .map_err(GitError::gix)?;
+    let parent_commit = parent_obj.try_into_commit().map_err(GitError::gix)?;
+    let parent_tree = parent_commit.tree().map_err(GitError::gix)?;
 
     if check_tree_diff_touches_path(&commit_tree, &parent_tree, filter_path)? {
         return Ok(true);
@@ -384,13 +479,9 @@ pub fn commit_touches_path(
     for parent_id in parent_iter {
         let parent_obj = repo
             .find_object(parent_id.detach())
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
-        let parent_commit = parent_obj
-            .try_into_commit()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
-        let parent_tree = parent_commit
-            .tree()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+            .map_err(GitError::gix)?;
+        let parent_commit = parent_obj.try_into_commit().map_err(GitError::gix)?;
+        let parent_tree = parent_commit.tree().map_err(GitError::gix)?;
 
         if check_tree_diff_touches_path(&commit_tree, &parent_tree, filter_path)? {
             return Ok(true);
@@ -409,11 +500,9 @@ fn check_tree_diff_touches_path(
     filter_path: &std::path::Path,
 ) -> GitResult<bool> {
     let mut touched = false;
-    let mut diff_platform = commit_tree
-        .changes()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let mut diff_platform = commit_tree.changes().map_err(GitError::gix)?;
 
-    diff_platform
+    let outcome = diff_platform
         .for_each_to_obtain_tree(parent_tree, |change| {
             use gix::object::tree::diff::{Action, Change};
 
@@ -430,8 +519,12 @@ fn check_tree_diff_touches_path(
             } else {
                 Ok::<Action, std::convert::Infallible>(Action::Continue)
             }
-        })
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+        });
 
-    Ok(touched)
+    match outcome {
+        Ok(_) => Ok(touched),
+        // Stopping at the first touching change surfaces as a cancellation
+        Err(_) if touched => Ok(true),
+        Err(e) => Err(GitError::gix(e)),
+    }
 }
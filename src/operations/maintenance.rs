@@ -0,0 +1,263 @@
+//! Repository maintenance: stale lock file detection and cleanup
+//!
+//! A crashed git process leaves `index.lock`, `HEAD.lock` or ref lock files
+//! behind, and every later operation touching that resource fails until the
+//! lock is removed. [`clear_stale_locks`] removes lock files that are older
+//! than a given age and not held open by a running process.
+//!
+//! Automatic recovery can be enabled with [`set_lock_recovery`]: `add`,
+//! `commit` and `checkout` then clear stale locks and retry once when they
+//! fail on a lock.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Lock files git creates directly in a git dir
+const DIR_LOCKS: &[&str] = &[
+    "index.lock",
+    "HEAD.lock",
+    "ORIG_HEAD.lock",
+    "FETCH_HEAD.lock",
+    "MERGE_HEAD.lock",
+    "config.lock",
+    "packed-refs.lock",
+    "shallow.lock",
+];
+
+/// Maximum lock age for automatic recovery (`None` disables it)
+static LOCK_RECOVERY: RwLock<Option<Duration>> = RwLock::new(None);
+
+/// A lock file found in the repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockFile {
+    /// Absolute path of the lock file
+    pub path: PathBuf,
+    /// Time since the lock file was last modified
+    pub age: Option<Duration>,
+    /// PID of a running process holding the lock file open, if any
+    pub owner_pid: Option<u32>,
+}
+
+impl LockFile {
+    /// Whether this lock can safely be removed given `max_age`
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.owner_pid.is_none() && self.age.is_some_and(|age| age >= max_age)
+    }
+}
+
+/// Result of [`clear_stale_locks`]
+#[derive(Debug, Clone, Default)]
+pub struct LockCleanupReport {
+    /// Lock files that were removed
+    pub removed: Vec<LockFile>,
+    /// Lock files left in place (too recent or held by a running process)
+    pub kept: Vec<LockFile>,
+}
+
+/// Enable or disable automatic stale lock recovery
+///
+/// With `Some(max_age)`, operations that fail on a lock clear locks older than
+/// `max_age` (and not held by a running process) and retry once.
+pub fn set_lock_recovery(max_age: Option<Duration>) {
+    if let Ok(mut guard) = LOCK_RECOVERY.write() {
+        *guard = max_age;
+    }
+}
+
+/// Current automatic lock recovery setting
+pub fn lock_recovery() -> Option<Duration> {
+    LOCK_RECOVERY.read().map(|guard| *guard).unwrap_or(None)
+}
+
+/// List all lock files in the repository without touching them
+pub async fn find_locks(repo: RepoHandle) -> GitResult<Vec<LockFile>> {
+    let repo_inner = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || find_locks_sync(&repo_inner))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Remove lock files older than `max_age` that no running process holds open
+pub async fn clear_stale_locks(repo: RepoHandle, max_age: Duration) -> GitResult<LockCleanupReport> {
+    let repo_inner = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || {
+        let mut report = LockCleanupReport::default();
+
+        for lock in find_locks_sync(&repo_inner)? {
+            if !lock.is_stale(max_age) {
+                report.kept.push(lock);
+                continue;
+            }
+
+            match std::fs::remove_file(&lock.path) {
+                Ok(()) => report.removed.push(lock),
+                // Released by its owner in the meantime
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(GitError::Io(std::io::Error::new(
+                        e.kind(),
+                        format!("Failed to remove lock file {}: {e}", lock.path.display()),
+                    )));
+                }
+            }
+        }
+
+        Ok(report)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Check if an error message indicates a held lock file
+pub(crate) fn is_lock_error(error_msg: &str) -> bool {
+    error_msg.contains(".lock")
+        && (error_msg.contains("could not be obtained")
+            || error_msg.contains("File exists")
+            || error_msg.contains("Unable to create"))
+}
+
+/// Run `op`, clearing stale locks and retrying once if it fails on a lock
+///
+/// Only active when enabled via [`set_lock_recovery`].
+pub(crate) async fn with_lock_recovery<T, F, Fut>(repo: RepoHandle, op: F) -> GitResult<T>
+where
+    F: Fn(RepoHandle) -> Fut,
+    Fut: Future<Output = GitResult<T>>,
+{
+    match op(repo.clone()).await {
+        Err(e) if is_lock_error(&e.to_string()) => {
+            let Some(max_age) = lock_recovery() else {
+                return Err(e);
+            };
+            let report = clear_stale_locks(repo.clone(), max_age).await?;
+            if report.removed.is_empty() {
+                return Err(e);
+            }
+            op(repo).await
+        }
+        result => result,
+    }
+}
+
+fn find_locks_sync(repo: &gix::Repository) -> GitResult<Vec<LockFile>> {
+    let mut paths = Vec::new();
+
+    let git_dir = repo.git_dir();
+    let common_dir = repo.common_dir();
+
+    collect_dir_locks(git_dir, &mut paths);
+    if common_dir != git_dir {
+        collect_dir_locks(common_dir, &mut paths);
+    }
+
+    // Per-worktree index/HEAD locks
+    if let Ok(entries) = std::fs::read_dir(common_dir.join("worktrees")) {
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            if dir != git_dir {
+                collect_dir_locks(&dir, &mut paths);
+            }
+        }
+    }
+
+    // Loose ref locks
+    collect_ref_locks(&common_dir.join("refs"), &mut paths)?;
+
+    let now = SystemTime::now();
+    let open_files = open_files_by_pid();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let age = std::fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|mtime| now.duration_since(mtime).ok());
+            let owner_pid = lock_owner(&path, &open_files);
+            LockFile {
+                path,
+                age,
+                owner_pid,
+            }
+        })
+        .collect())
+}
+
+fn collect_dir_locks(dir: &Path, paths: &mut Vec<PathBuf>) {
+    for name in DIR_LOCKS {
+        let path = dir.join(name);
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+}
+
+fn collect_ref_locks(dir: &Path, paths: &mut Vec<PathBuf>) -> GitResult<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(GitError::Io(e)),
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_ref_locks(&path, paths)?;
+        } else if path.extension().is_some_and(|ext| ext == "lock") {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Open file descriptors of all visible processes, as (pid, target path)
+#[cfg(target_os = "linux")]
+fn open_files_by_pid() -> Vec<(u32, PathBuf)> {
+    let Ok(procs) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    let mut open_files = Vec::new();
+    for proc_entry in procs.flatten() {
+        let Some(pid) = proc_entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        // Processes of other users aren't readable; they can't be checked
+        let Ok(fds) = std::fs::read_dir(proc_entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Ok(target) = std::fs::read_link(fd.path()) {
+                open_files.push((pid, target));
+            }
+        }
+    }
+
+    open_files
+}
+
+/// Open file inspection isn't available on this platform; rely on age only
+#[cfg(not(target_os = "linux"))]
+fn open_files_by_pid() -> Vec<(u32, PathBuf)> {
+    Vec::new()
+}
+
+fn lock_owner(path: &Path, open_files: &[(u32, PathBuf)]) -> Option<u32> {
+    let canonical = path.canonicalize().ok()?;
+    open_files
+        .iter()
+        .find(|(_, target)| *target == canonical)
+        .map(|(pid, _)| *pid)
+}
@@ -31,6 +31,8 @@ pub struct MergeOpts {
     pub no_ff: bool,
     pub squash: bool,
     pub commit: bool,
+    /// Stash local changes first and reapply them afterwards.
+    pub autostash: bool,
 }
 
 impl MergeOpts {
@@ -42,6 +44,7 @@ impl MergeOpts {
             no_ff: false,
             squash: false,
             commit: true,
+            autostash: false,
         }
     }
 
@@ -68,10 +71,24 @@ impl MergeOpts {
         self.commit = yes;
         self
     }
+
+    /// Stash local changes before merging and reapply them afterwards.
+    #[inline]
+    #[must_use]
+    pub fn autostash(mut self, yes: bool) -> Self {
+        self.autostash = yes;
+        self
+    }
 }
 
 /// Execute merge operation with the given options.
 pub async fn merge(repo: RepoHandle, opts: MergeOpts) -> GitResult<MergeOutcome> {
+    let autostash = opts.autostash;
+    super::autostash::with_autostash(repo.clone(), autostash, || merge_once(repo, opts)).await
+}
+
+/// Merge without autostash handling
+async fn merge_once(repo: RepoHandle, opts: MergeOpts) -> GitResult<MergeOutcome> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
@@ -80,6 +97,7 @@ pub async fn merge(repo: RepoHandle, opts: MergeOpts) -> GitResult<MergeOutcome>
             no_ff,
             squash,
             commit,
+            ..
         } = opts;
 
         // Resolve the target reference
@@ -93,12 +111,9 @@ pub async fn merge(repo: RepoHandle, opts: MergeOpts) -> GitResult<MergeOutcome>
             .ok_or_else(|| GitError::InvalidInput(format!("Ambiguous merge target: {theirs}")))?;
 
         // Get current HEAD commit
-        let mut head_ref = repo_clone.head().map_err(|e| GitError::Gix(e.into()))?;
+        let mut head_ref = repo_clone.head().map_err(GitError::gix)?;
 
-        let our_commit_id = head_ref
-            .peel_to_commit()
-            .map_err(|e| GitError::Gix(e.into()))?
-            .id;
+        let our_commit_id = head_ref.peel_to_commit().map_err(GitError::gix)?.id;
 
         // Check if already up to date
         if our_commit_id == their_commit_id {
@@ -110,7 +125,7 @@ pub async fn merge(repo: RepoHandle, opts: MergeOpts) -> GitResult<MergeOutcome>
         // Use merge_base to determine commit relationships
         let merge_base = repo_clone
             .merge_base(our_commit_id, their_commit_id_detached)
-            .map_err(|e| GitError::Gix(e.into()))?;
+            .map_err(GitError::gix)?;
 
         // Case 1: Their commit is the merge base (they're already in our history)
         if merge_base.detach() == their_commit_id_detached {
@@ -172,70 +187,146 @@ fn fast_forward_merge(repo: &gix::Repository, target_commit: CommitId) -> GitRes
     // Step 2: Get target commit and its tree
     let commit = repo
         .find_object(target_commit)
-        .map_err(|e| GitError::Gix(e.into()))?
+        .map_err(GitError::gix)?
         .try_into_commit()
         .map_err(|_| GitError::InvalidInput("Target does not point to a commit".to_string()))?;
 
-    let tree_id = commit.tree_id().map_err(|e| GitError::Gix(e.into()))?;
+    let tree_id = commit.tree_id().map_err(GitError::gix)?;
+
+    // Steps 3-8: Replace index and working tree with the target tree
+    sync_worktree_to_tree(repo, workdir, tree_id.detach(), "Fast-forward")?;
+
+    // Step 9: Update HEAD reference
+    repo.reference(
+        "HEAD",
+        target_commit,
+        gix::refs::transaction::PreviousValue::Any,
+        "merge: Fast-forward",
+    )
+    .map_err(GitError::gix)?;
+
+    Ok(())
+}
+
+/// Make the index and working tree match `tree_id`.
+///
+/// Expects a clean working tree: files tracked before but absent from the
+/// new tree are deleted, everything else is checked out from the tree.
+/// Fails without writing anything if an untracked file is in the way of a
+/// file of the tree. `context` prefixes error messages.
+pub(crate) fn sync_worktree_to_tree(
+    repo: &gix::Repository,
+    workdir: &std::path::Path,
+    tree_id: gix::ObjectId,
+    context: &str,
+) -> GitResult<()> {
+    let previous = repo.index_or_empty().map_err(GitError::gix)?;
 
     // Step 3: Create index from target tree
-    let mut index = repo
-        .index_from_tree(&tree_id)
-        .map_err(|e| GitError::Gix(e.into()))?;
+    let mut index = repo.index_from_tree(&tree_id).map_err(GitError::gix)?;
+
+    // Checkout truncates whatever is in the way, so check untracked files first
+    let blocking = untracked_collisions(repo, &index)?;
+    if !blocking.is_empty() {
+        return Err(GitError::InvalidInput(format!(
+            "{context} would overwrite untracked files: {}",
+            blocking.join(", ")
+        )));
+    }
 
     // Step 4: Get checkout options
     let checkout_opts = repo
         .checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)
-        .map_err(|e| GitError::Gix(e.into()))?;
+        .map_err(GitError::gix)?;
 
     // Step 5: Checkout tree to working directory
     let outcome = gix::worktree::state::checkout(
         &mut index,
         workdir,
-        repo.objects
-            .clone()
-            .into_arc()
-            .map_err(|e| GitError::Gix(e.into()))?,
+        repo.objects.clone().into_arc().map_err(GitError::gix)?,
         &gix::progress::Discard,
         &gix::progress::Discard,
         &std::sync::atomic::AtomicBool::new(false),
         checkout_opts,
     )
-    .map_err(|e| GitError::Gix(e.into()))?;
+    .map_err(GitError::gix)?;
 
     // Step 6: Handle checkout errors
     if !outcome.errors.is_empty() {
         return Err(GitError::InvalidInput(format!(
-            "Fast-forward checkout encountered {} error(s)",
+            "{context} checkout encountered {} error(s)",
             outcome.errors.len()
         )));
     }
 
-    // Step 7: Handle collisions (should not occur in fast-forward, but check anyway)
+    // Step 7: Handle collisions
     if !outcome.collisions.is_empty() {
         return Err(GitError::InvalidInput(format!(
-            "Fast-forward checkout encountered {} collision(s)",
+            "{context} checkout encountered {} collision(s)",
             outcome.collisions.len()
         )));
     }
 
-    // Step 8: Write index to disk
-    index
-        .write(Default::default())
-        .map_err(|e| GitError::Gix(e.into()))?;
+    // Step 7b: Remove files the target tree no longer tracks
+    for entry in previous.entries() {
+        let path = entry.path(&previous);
+        if index.entry_by_path(path).is_none() {
+            let file = workdir.join(gix::path::from_bstr(path));
+            match std::fs::remove_file(&file) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(GitError::Io(e)),
+            }
+        }
+    }
 
-    // Step 9: Update HEAD reference
-    repo.reference(
-        "HEAD",
-        target_commit,
-        gix::refs::transaction::PreviousValue::Any,
-        "merge: Fast-forward",
-    )
-    .map_err(|e| GitError::Gix(e.into()))?;
+    // Step 8: Write index to disk
+    index.write(Default::default()).map_err(GitError::gix)?;
 
     Ok(())
 }
 
+/// Untracked files that checking out `index` would overwrite: files at a path
+/// of the index, inside a directory the index has a file at, or where the
+/// index has a directory
+fn untracked_collisions(
+    repo: &gix::Repository,
+    index: &gix::index::State,
+) -> GitResult<Vec<String>> {
+    let untracked = super::fast_status::untracked_files(repo)?;
+    Ok(untracked
+        .into_iter()
+        .filter(|path| {
+            let mut dir = path.clone();
+            dir.push(b'/');
+            index.entry_by_path(path.as_ref()).is_some()
+                || index
+                    .prefixed_entries(dir.as_ref())
+                    .is_some_and(|e| !e.is_empty())
+                || path
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| **b == b'/')
+                    .any(|(i, _)| index.entry_by_path(path[..i].as_ref()).is_some())
+        })
+        .map(|path| path.to_string())
+        .collect())
+}
+
+/// Sorted, de-duplicated paths of the unresolved conflicts in a tree merge
+pub(crate) fn unresolved_conflict_paths(outcome: &gix::merge::tree::Outcome<'_>) -> Vec<String> {
+    let how = gix::merge::tree::TreatAsUnresolved::default();
+    let mut paths: Vec<String> = outcome
+        .conflicts
+        .iter()
+        .filter(|conflict| conflict.is_unresolved(how))
+        .map(|conflict| conflict.ours.location().to_string())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
 /// Create a merge commit combining two parent commits.
 ///
 /// This function performs the actual merge operation, combining the trees from
@@ -273,9 +364,7 @@ fn create_merge_commit(
     config: MergeCommitConfig,
 ) -> GitResult<CommitId> {
     // Step 1: Get merge options from repository config
-    let tree_merge_opts = repo
-        .tree_merge_options()
-        .map_err(|e| GitError::Gix(e.into()))?;
+    let tree_merge_opts = repo.tree_merge_options().map_err(GitError::gix)?;
 
     let commit_merge_opts: gix::merge::commit::Options = tree_merge_opts.into();
 
@@ -290,7 +379,7 @@ fn create_merge_commit(
     // Step 3: Perform the merge using gix
     let mut merge_outcome = repo
         .merge_commits(our_commit, their_commit, labels, commit_merge_opts)
-        .map_err(|e| GitError::Gix(e.into()))?;
+        .map_err(GitError::gix)?;
 
     // Step 4: Check for unresolved conflicts
     use gix::merge::tree::TreatAsUnresolved;
@@ -298,9 +387,14 @@ fn create_merge_commit(
         .tree_merge
         .has_unresolved_conflicts(TreatAsUnresolved::default())
     {
-        return Err(GitError::MergeConflict(
-            "Merge has conflicts that must be resolved manually".to_string(),
-        ));
+        let paths = unresolved_conflict_paths(&merge_outcome.tree_merge);
+        return Err(GitError::MergeConflict {
+            message: format!(
+                "Merge has conflicts that must be resolved manually in: {}",
+                paths.join(", ")
+            ),
+            paths,
+        });
     }
 
     // Step 5: Write the merged tree to ODB
@@ -308,7 +402,7 @@ fn create_merge_commit(
         .tree_merge
         .tree
         .write()
-        .map_err(|e| GitError::Gix(e.into()))?;
+        .map_err(GitError::gix)?;
 
     // Step 6: Handle commit option
     if !config.commit {
@@ -318,12 +412,12 @@ fn create_merge_commit(
         // 6a. Create index from merged tree using gix's public API
         let mut index = repo
             .index_from_tree(&merged_tree_id)
-            .map_err(|e| GitError::Gix(e.into()))?;
+            .map_err(GitError::gix)?;
 
         // 6b. Get checkout options
         let opts = repo
             .checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)
-            .map_err(|e| GitError::Gix(e.into()))?;
+            .map_err(GitError::gix)?;
 
         // 6c. Checkout merged tree to worktree
         let workdir = repo
@@ -333,16 +427,13 @@ fn create_merge_commit(
         let outcome = gix::worktree::state::checkout(
             &mut index,
             workdir,
-            repo.objects
-                .clone()
-                .into_arc()
-                .map_err(|e| GitError::Gix(e.into()))?,
+            repo.objects.clone().into_arc().map_err(GitError::gix)?,
             &gix::progress::Discard,
             &gix::progress::Discard,
             &std::sync::atomic::AtomicBool::new(false),
             opts,
         )
-        .map_err(|e| GitError::Gix(e.into()))?;
+        .map_err(GitError::gix)?;
 
         // 6d. Handle checkout errors
         if !outcome.errors.is_empty() {
@@ -362,24 +453,21 @@ fn create_merge_commit(
 
         // 6f. Write MERGE_HEAD to mark merge in progress
         let merge_head_path = repo.path().join("MERGE_HEAD");
-        std::fs::write(&merge_head_path, format!("{their_commit}\n"))
-            .map_err(|e| GitError::Gix(e.into()))?;
+        std::fs::write(&merge_head_path, format!("{their_commit}\n")).map_err(GitError::gix)?;
 
         // 6g. Write MERGE_MSG with the merge message
         let merge_msg_path = repo.path().join("MERGE_MSG");
         let message = format!("Merge '{their_name}'\n");
-        std::fs::write(&merge_msg_path, message).map_err(|e| GitError::Gix(e.into()))?;
+        std::fs::write(&merge_msg_path, message).map_err(GitError::gix)?;
 
         // 6h. Write MERGE_MODE if this is a forced merge commit (--no-ff on fast-forwardable)
         if config.no_ff && config.could_fast_forward {
             let merge_mode_path = repo.path().join("MERGE_MODE");
-            std::fs::write(&merge_mode_path, "no-ff\n").map_err(|e| GitError::Gix(e.into()))?;
+            std::fs::write(&merge_mode_path, "no-ff\n").map_err(GitError::gix)?;
         }
 
         // 6i. Write index to disk
-        index
-            .write(Default::default())
-            .map_err(|e| GitError::Gix(e.into()))?;
+        index.write(Default::default()).map_err(GitError::gix)?;
 
         // Return our_commit since HEAD doesn't move (merge prepared but not committed)
         return Ok(our_commit);
@@ -395,7 +483,7 @@ fn create_merge_commit(
 
     let merge_commit_id = repo
         .commit("HEAD", &message, merged_tree_id, parents)
-        .map_err(|e| GitError::Gix(e.into()))?;
+        .map_err(GitError::gix)?;
 
     Ok(merge_commit_id.detach())
 }
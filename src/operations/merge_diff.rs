@@ -0,0 +1,354 @@
+//! What a merge commit changed.
+//!
+//! Diffing a merge is ambiguous: against which parent? [`merge_diff`] answers
+//! two questions. The first-parent diff is what the merge brought into the
+//! branch it was made on. The combined diff (`git show --cc`) covers only
+//! files whose merged content matches none of the parents, i.e. where
+//! conflicts were resolved or changes were made while merging, and within
+//! those only the regions that differ from every parent.
+
+use gix::bstr::{BStr, ByteSlice};
+use similar::{DiffOp, TextDiff};
+
+use super::compare::resolve_commit;
+use super::diff::{
+    ChangeType, DiffStats, FileDiffStats, diff_hunks, is_symlink, mode_change,
+};
+use super::patch_id::{FileChange, Side, content, file_changes, is_binary};
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Lines of context around changes
+const CONTEXT_LINES: usize = 3;
+
+/// Changes introduced by a merge commit.
+#[derive(Debug, Clone)]
+pub struct MergeDiff {
+    pub merge: CommitId,
+    pub parents: Vec<CommitId>,
+    /// Files changed relative to the first parent.
+    pub stats: DiffStats,
+    /// Unified diff against the first parent.
+    pub patch: String,
+    /// Combined diffs of files that differ from every parent; only filled
+    /// when requested.
+    pub combined: Vec<CombinedFileDiff>,
+}
+
+/// Combined diff of one file against all parents of a merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CombinedFileDiff {
+    pub path: String,
+    /// `--cc` style diff with one marker column per parent.
+    pub diff: String,
+}
+
+/// Diff `merge_commit` against its first parent and, with `combined`, compute
+/// `--cc` diffs for the files the merge itself changed.
+///
+/// Fails for commits with fewer than two parents.
+pub async fn merge_diff(
+    repo: RepoHandle,
+    merge_commit: &str,
+    combined: bool,
+) -> GitResult<MergeDiff> {
+    let repo_inner = repo.clone_inner();
+    let merge_commit = merge_commit.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let merge = resolve_commit(&repo_inner, &merge_commit)?;
+        let commit = repo_inner.find_commit(merge).map_err(GitError::gix)?;
+        let parents: Vec<CommitId> = commit.parent_ids().map(gix::Id::detach).collect();
+        if parents.len() < 2 {
+            return Err(GitError::InvalidInput(format!(
+                "{merge_commit} is not a merge commit"
+            )));
+        }
+
+        let tree = commit.tree().map_err(GitError::gix)?;
+        let parent_trees = parents
+            .iter()
+            .map(|id| {
+                repo_inner
+                    .find_commit(*id)
+                    .map_err(GitError::gix)?
+                    .tree()
+                    .map_err(GitError::gix)
+            })
+            .collect::<GitResult<Vec<_>>>()?;
+
+        let mut stats = DiffStats::new();
+        let mut patch = String::new();
+        let mut combined_diffs = Vec::new();
+        for FileChange { path, old, new } in file_changes(&repo_inner, &parent_trees[0], &tree)? {
+            let old_content = side_content(&repo_inner, old)?;
+            let new_content = side_content(&repo_inner, new)?;
+            let (additions, deletions) = write_file_patch(
+                &mut patch,
+                path.as_bstr(),
+                (old, &old_content),
+                (new, &new_content),
+            );
+            stats.add_file(FileDiffStats {
+                path: path.as_bstr().into(),
+                change_type: match (old, new) {
+                    (None, _) => ChangeType::Added,
+                    (_, None) => ChangeType::Deleted,
+                    _ => ChangeType::Modified,
+                },
+                additions,
+                deletions,
+                mode_change: mode_change(old.map(|side| side.mode), new.map(|side| side.mode)),
+                is_symlink: is_symlink(old.map(|side| side.mode), new.map(|side| side.mode)),
+                hunks: diff_hunks(&old_content, &new_content),
+            });
+
+            if !combined {
+                continue;
+            }
+            // Files taken unchanged from some parent are not interesting
+            let mut parent_contents = vec![old_content];
+            let mut from_a_parent = old.map(|side| side.id) == new.map(|side| side.id);
+            for parent_tree in &parent_trees[1..] {
+                let side = entry_at(parent_tree, path.as_bstr())?;
+                from_a_parent |= side.map(|side| side.id) == new.map(|side| side.id);
+                parent_contents.push(side_content(&repo_inner, side)?);
+            }
+            if from_a_parent {
+                continue;
+            }
+            if let Some(diff) = combined_file_diff(path.as_bstr(), &parent_contents, &new_content)
+            {
+                combined_diffs.push(CombinedFileDiff {
+                    path: path.to_string(),
+                    diff,
+                });
+            }
+        }
+
+        Ok(MergeDiff {
+            merge,
+            parents,
+            stats,
+            patch,
+            combined: combined_diffs,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+fn side_content(repo: &gix::Repository, side: Option<Side>) -> GitResult<Vec<u8>> {
+    side.map(|side| content(repo, side))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// The non-tree entry at `path` in `tree`
+fn entry_at(tree: &gix::Tree<'_>, path: &BStr) -> GitResult<Option<Side>> {
+    let entry = tree
+        .lookup_entry(path.split(|b| *b == b'/'))
+        .map_err(GitError::gix)?;
+    Ok(entry
+        .filter(|entry| !entry.mode().is_tree())
+        .map(|entry| Side {
+            mode: entry.mode(),
+            id: entry.object_id(),
+        }))
+}
+
+/// Append the unified diff of one file to `out`, returning its line counts
+fn write_file_patch(
+    out: &mut String,
+    path: &BStr,
+    (old_side, old): (Option<Side>, &[u8]),
+    (new_side, new): (Option<Side>, &[u8]),
+) -> (usize, usize) {
+    out.push_str(&format!("diff --git a/{path} b/{path}\n"));
+    if is_binary(old) || is_binary(new) {
+        out.push_str(&format!("Binary files a/{path} and b/{path} differ\n"));
+        return (0, 0);
+    }
+
+    let old_text = String::from_utf8_lossy(old);
+    let new_text = String::from_utf8_lossy(new);
+    let diff = TextDiff::from_lines(old_text.as_ref(), new_text.as_ref());
+    let mut additions = 0;
+    let mut deletions = 0;
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            similar::ChangeTag::Insert => additions += 1,
+            similar::ChangeTag::Delete => deletions += 1,
+            similar::ChangeTag::Equal => {}
+        }
+    }
+
+    let old_label = old_side.map_or_else(|| "/dev/null".to_string(), |_| format!("a/{path}"));
+    let new_label = new_side.map_or_else(|| "/dev/null".to_string(), |_| format!("b/{path}"));
+    out.push_str(
+        &diff
+            .unified_diff()
+            .context_radius(CONTEXT_LINES)
+            .header(&old_label, &new_label)
+            .to_string(),
+    );
+    (additions, deletions)
+}
+
+/// How the merge result relates to one parent's version of a file
+struct ParentView<'a> {
+    /// Result lines that are not in this parent
+    added: Vec<bool>,
+    /// Parent lines removed just before each result line (one slot past the end)
+    removed: Vec<Vec<&'a str>>,
+    /// Parent lines preceding each slot of `removed`
+    before: Vec<usize>,
+}
+
+impl<'a> ParentView<'a> {
+    fn new(parent: &'a str, result: &str) -> Self {
+        let diff = TextDiff::from_lines(parent, result);
+        let parent_lines: Vec<&str> = parent.split_inclusive('\n').collect();
+        let result_len = result.split_inclusive('\n').count();
+
+        let mut added = vec![false; result_len];
+        let mut removed = vec![Vec::new(); result_len + 1];
+        let mut before = vec![None; result_len + 1];
+        let mut parent_pos = 0;
+        for op in diff.ops() {
+            let (old_len, new_index, new_len, changed) = match *op {
+                DiffOp::Equal {
+                    new_index, len, ..
+                } => (len, new_index, len, false),
+                DiffOp::Delete {
+                    old_len, new_index, ..
+                } => (old_len, new_index, 0, true),
+                DiffOp::Insert {
+                    new_index, new_len, ..
+                } => (0, new_index, new_len, true),
+                DiffOp::Replace {
+                    old_len,
+                    new_index,
+                    new_len,
+                    ..
+                } => (old_len, new_index, new_len, true),
+            };
+            before[new_index].get_or_insert(parent_pos);
+            if changed {
+                removed[new_index].extend(&parent_lines[parent_pos..parent_pos + old_len]);
+                parent_pos += old_len;
+                added[new_index..new_index + new_len].fill(true);
+            }
+            let lines = &mut before[new_index..new_index + new_len];
+            for (offset, line) in lines.iter_mut().enumerate() {
+                line.get_or_insert(if changed { parent_pos } else { parent_pos + offset });
+            }
+            if !changed {
+                parent_pos += old_len;
+            }
+        }
+        before[result_len].get_or_insert(parent_pos);
+
+        Self {
+            added,
+            removed,
+            before: before.into_iter().map(Option::unwrap_or_default).collect(),
+        }
+    }
+}
+
+/// `--cc` diff of `result` against all `parents`, `None` if every change
+/// matches some parent
+fn combined_file_diff(path: &BStr, parents: &[Vec<u8>], result: &[u8]) -> Option<String> {
+    let mut out = format!("diff --cc {path}\n");
+    if is_binary(result) || parents.iter().any(|parent| is_binary(parent)) {
+        out.push_str("Binary files differ\n");
+        return Some(out);
+    }
+
+    let parent_texts: Vec<_> = parents.iter().map(|p| String::from_utf8_lossy(p)).collect();
+    let result_text = String::from_utf8_lossy(result);
+    let result_lines: Vec<&str> = result_text.split_inclusive('\n').collect();
+    let views: Vec<ParentView<'_>> = parent_texts
+        .iter()
+        .map(|parent| ParentView::new(parent, &result_text))
+        .collect();
+
+    // Slots and lines that differ from every parent
+    let slots = result_lines.len() + 1;
+    let interesting: Vec<usize> = (0..slots)
+        .filter(|&i| {
+            let new_everywhere =
+                i < result_lines.len() && views.iter().all(|view| view.added[i]);
+            let removed_everywhere = views.iter().all(|view| !view.removed[i].is_empty());
+            new_everywhere || removed_everywhere
+        })
+        .collect();
+    if interesting.is_empty() {
+        return None;
+    }
+
+    // Group into hunks of result lines [start, end) with context
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &interesting {
+        let start = i.saturating_sub(CONTEXT_LINES);
+        let end = (i + 1 + CONTEXT_LINES).min(result_lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let marker = "@".repeat(parents.len() + 1);
+    for (start, end) in hunks {
+        // Removals after the last line belong to the hunk that reaches the end
+        let last_slot = if end == result_lines.len() { end } else { end - 1 };
+        let mut header = marker.clone();
+        for view in &views {
+            let from = view.before[start];
+            let to = if last_slot == end {
+                view.before[end] + view.removed[end].len()
+            } else {
+                view.before[end]
+            };
+            header.push_str(&format!(" -{}", range(from, to - from)));
+        }
+        header.push_str(&format!(" +{} {marker}\n", range(start, end - start)));
+        out.push_str(&header);
+
+        for slot in start..=last_slot {
+            for (p, view) in views.iter().enumerate() {
+                for line in &view.removed[slot] {
+                    let columns: String = (0..views.len())
+                        .map(|q| if q == p { '-' } else { ' ' })
+                        .collect();
+                    push_line(&mut out, &columns, line);
+                }
+            }
+            if slot < end {
+                let columns: String = views
+                    .iter()
+                    .map(|view| if view.added[slot] { '+' } else { ' ' })
+                    .collect();
+                push_line(&mut out, &columns, result_lines[slot]);
+            }
+        }
+    }
+    Some(out)
+}
+
+/// `start,len` in hunk header form (1-based, or the preceding line if empty)
+fn range(start: usize, len: usize) -> String {
+    if len == 0 {
+        format!("{start},0")
+    } else {
+        format!("{},{len}", start + 1)
+    }
+}
+
+fn push_line(out: &mut String, columns: &str, line: &str) {
+    out.push_str(columns);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push('\n');
+    }
+}
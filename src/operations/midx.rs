@@ -0,0 +1,255 @@
+//! Multi-pack index and reachability bitmaps.
+//!
+//! A repository fetched into many times has many packs, and every object
+//! lookup probes each pack index in turn. A multi-pack index (MIDX) answers
+//! from one table, and gix uses it as soon as it exists. Reachability bitmaps
+//! go further: for selected commits they record every object the commit
+//! reaches, so counting reachable objects or commits no longer walks history.
+//!
+//! [`write_midx`] writes the MIDX natively; with [`MidxOpts::bitmap`] and
+//! [`MidxOpts::commit_graph`] it also writes a bitmap and a commit-graph
+//! through the git CLI, since gix can't write either. Once present,
+//! [`reachable_count`](super::introspection::reachable_count) and the
+//! ahead/behind counts of status and
+//! [`upstream_status`](super::upstream::upstream_status) are answered from
+//! the bitmap by git, and git lists the refs of
+//! [`branches_containing`](super::ancestry::branches_containing) that reach
+//! the commit in one call; its walk otherwise prunes with the commit-graph's
+//! generation numbers. Without the git CLI (the `pure-gix` feature) all of these fall
+//! back to walking.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Options for [`write_midx`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MidxOpts {
+    /// Also write a reachability bitmap for the MIDX (git CLI).
+    pub bitmap: bool,
+    /// Also write a commit-graph of all reachable commits (git CLI).
+    pub commit_graph: bool,
+}
+
+impl MidxOpts {
+    /// Write the multi-pack index only.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write a reachability bitmap alongside the MIDX.
+    #[inline]
+    #[must_use]
+    pub fn bitmap(mut self, yes: bool) -> Self {
+        self.bitmap = yes;
+        self
+    }
+
+    /// Write a commit-graph of all reachable commits.
+    #[inline]
+    #[must_use]
+    pub fn commit_graph(mut self, yes: bool) -> Self {
+        self.commit_graph = yes;
+        self
+    }
+}
+
+/// What [`write_midx`] wrote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MidxResult {
+    /// The multi-pack index file.
+    pub path: PathBuf,
+    /// Packs it covers.
+    pub packs: usize,
+    /// Objects it indexes.
+    pub objects: u32,
+    /// The bitmap file, if one was written.
+    pub bitmap: Option<PathBuf>,
+    /// Whether a commit-graph was written.
+    pub commit_graph: bool,
+}
+
+/// Write a multi-pack index over all packs of `repo`, replacing any existing
+/// one, and optionally a bitmap and commit-graph.
+///
+/// Fails if the repository has no packs, as a MIDX over loose objects would
+/// be empty; repack first. Bitmap and commit-graph need the git CLI and fail
+/// with the `pure-gix` feature.
+pub async fn write_midx(repo: RepoHandle, opts: MidxOpts) -> GitResult<MidxResult> {
+    let repo_clone = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || {
+        let pack_dir = pack_dir(&repo_clone);
+        let mut index_paths: Vec<PathBuf> = std::fs::read_dir(&pack_dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "idx"))
+            .collect();
+        index_paths.sort();
+        if index_paths.is_empty() {
+            return Err(GitError::InvalidInput(
+                "Repository has no packs to index; repack first".to_string(),
+            ));
+        }
+        let packs = index_paths.len();
+
+        let path = pack_dir.join("multi-pack-index");
+        let mut lock = gix::lock::File::acquire_to_update_resource(
+            &path,
+            gix::lock::acquire::Fail::Immediately,
+            None,
+        )
+        .map_err(GitError::gix)?;
+        gix::odb::pack::multi_index::File::write_from_index_paths(
+            index_paths,
+            &mut lock,
+            &mut gix::progress::Discard,
+            &AtomicBool::new(false),
+            gix::odb::pack::multi_index::write::Options {
+                object_hash: repo_clone.object_hash(),
+            },
+        )
+        .map_err(GitError::gix)?;
+        lock.commit().map_err(|e| GitError::gix(e.error))?;
+
+        let bitmap = if opts.bitmap {
+            // Rewrites the MIDX with the reverse index the bitmap needs
+            run_git(&repo_clone, &["multi-pack-index", "write", "--bitmap"])?;
+            find_bitmap(&pack_dir)
+        } else {
+            None
+        };
+        if opts.commit_graph {
+            run_git(&repo_clone, &["commit-graph", "write", "--reachable"])?;
+        }
+
+        let objects = gix::odb::pack::multi_index::File::at(&path)
+            .map_err(GitError::gix)?
+            .num_objects();
+        Ok(MidxResult {
+            path,
+            packs,
+            objects,
+            bitmap,
+            commit_graph: opts.commit_graph,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Whether any pack or the MIDX of `repo` has a reachability bitmap
+#[cfg(not(feature = "pure-gix"))]
+pub(crate) fn has_bitmaps(repo: &gix::Repository) -> bool {
+    find_bitmap(&pack_dir(repo)).is_some()
+}
+
+/// Count with `git rev-list --count --use-bitmap-index <args>` if `repo` has
+/// bitmaps; `None` if it has none, git is unavailable or the count fails,
+/// so callers walk instead
+#[cfg(not(feature = "pure-gix"))]
+pub(crate) fn bitmap_count(repo: &gix::Repository, args: &[&str]) -> Option<Vec<usize>> {
+    if !has_bitmaps(repo) {
+        return None;
+    }
+    let output = super::auth::git_command()
+        .arg("--git-dir")
+        .arg(repo.git_dir())
+        .args(["rev-list", "--count", "--use-bitmap-index"])
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8(output.stdout)
+        .ok()?
+        .split_whitespace()
+        .map(|count| count.parse().ok())
+        .collect()
+}
+
+/// Count from bitmaps; never available without the git CLI
+#[cfg(feature = "pure-gix")]
+pub(crate) fn bitmap_count(_repo: &gix::Repository, _args: &[&str]) -> Option<Vec<usize>> {
+    None
+}
+
+/// Full names of the refs under `prefix` whose history contains `target`,
+/// from one `git for-each-ref --contains`; `None` as for [`bitmap_count`]
+#[cfg(not(feature = "pure-gix"))]
+pub(crate) fn bitmap_reaching(
+    repo: &gix::Repository,
+    target: CommitId,
+    prefix: &str,
+) -> Option<HashSet<String>> {
+    if !has_bitmaps(repo) {
+        return None;
+    }
+    let output = super::auth::git_command()
+        .arg("--git-dir")
+        .arg(repo.git_dir())
+        .args(["for-each-ref", "--format=%(refname)", "--contains"])
+        .arg(target.to_string())
+        .arg(prefix)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(
+        String::from_utf8(output.stdout)
+            .ok()?
+            .lines()
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Refs reaching a commit from bitmaps; never available without the git CLI
+#[cfg(feature = "pure-gix")]
+pub(crate) fn bitmap_reaching(
+    _repo: &gix::Repository,
+    _target: CommitId,
+    _prefix: &str,
+) -> Option<HashSet<String>> {
+    None
+}
+
+fn pack_dir(repo: &gix::Repository) -> PathBuf {
+    repo.common_dir().join("objects").join("pack")
+}
+
+/// The first `.bitmap` file in `pack_dir`
+fn find_bitmap(pack_dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(pack_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "bitmap"))
+}
+
+#[cfg(not(feature = "pure-gix"))]
+fn run_git(repo: &gix::Repository, args: &[&str]) -> GitResult<()> {
+    let output = super::auth::git_command()
+        .arg("--git-dir")
+        .arg(repo.git_dir())
+        .args(args)
+        .output()
+        .map_err(|e| GitError::InvalidInput(format!("Failed to run git {}: {e}", args[0])))?;
+    if !output.status.success() {
+        return Err(GitError::InvalidInput(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "pure-gix")]
+fn run_git(_repo: &gix::Repository, _args: &[&str]) -> GitResult<()> {
+    Err(GitError::Unsupported(
+        "bitmaps and commit-graphs are written by the git CLI, which is unavailable with the pure-gix feature",
+    ))
+}
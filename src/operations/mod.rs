@@ -3,57 +3,193 @@
 //! Provides local Git repository operations using the gix (Gitoxide) library.
 
 pub mod add;
+pub mod ancestry;
+pub mod apply;
+pub mod attributes;
 pub mod auth;
+pub mod autostash;
+pub mod backport;
+pub mod blame;
 pub mod branch;
+pub mod cache;
+pub mod capabilities;
 pub mod checkout;
+pub mod cherry;
+pub mod cherry_pick;
 pub mod clone;
+pub mod clone_preflight;
+pub mod codeowners;
+pub mod collisions;
 pub mod commit;
+pub mod compare;
+pub mod conflicts;
+pub mod credentials;
+pub mod decorate;
 pub mod diff;
+pub mod encoding;
+pub mod eol;
+pub mod export;
+pub mod fast_status;
 pub mod fetch;
+pub mod fetch_verify;
+pub mod graph;
+pub mod grep;
 pub mod history;
+pub mod ignore;
+pub mod impact;
+pub mod index;
 pub mod introspection;
+pub mod lint;
 pub mod log;
+pub mod maintenance;
 pub mod merge;
+pub mod merge_diff;
+pub mod midx;
+pub mod nested;
 pub mod open;
+pub mod ownership;
+pub mod patch_id;
+pub mod progress;
 pub mod pull;
 pub mod push;
+pub mod rebase;
+pub mod registry;
+pub mod release;
 pub mod remote;
+pub mod remote_head;
+pub mod replace;
+pub mod repo_path;
 pub mod reset;
+pub mod review;
+pub mod rewrite;
+pub mod scaffold;
+pub mod search;
+pub mod shallow;
+pub mod snapshot;
+pub mod sparse;
+pub mod squash;
 pub mod stash;
 pub mod status;
+pub mod subtree;
+pub mod sync;
 pub mod tag;
+pub mod update_branch;
+pub mod upstream;
+pub mod warnings;
+pub mod workspace;
 pub mod worktree;
 
 // Re-export operation functions
-pub use add::{AddOpts, add};
-pub use branch::{BranchOpts, branch, delete_branch, list_branches, rename_branch};
-pub use checkout::{CheckoutOpts, checkout};
-pub use clone::{CloneOpts, clone_repo};
-pub use commit::{CommitOpts, CommitResult, Signature, commit};
-pub use diff::{ChangeType, DiffOpts, DiffStats, FileDiffStats, diff};
-pub use fetch::{FetchOpts, fetch};
+pub use add::{AddOpts, AddResult, add};
+pub use ancestry::{ancestry_path, branches_containing, children_of, tags_containing};
+pub use apply::{ApplyOpts, ApplyResult, FilePatch, apply_to_index, parse_patch};
+pub use attributes::{
+    AttributeRule, AttributesEdit, LFS_ATTRIBUTES, lfs_track, lfs_untrack, list_attributes,
+    remove_attributes, set_attributes,
+};
+pub use backport::{BackportOpts, BackportOutcome, BackportResult, backport};
+pub use blame::{BlameLine, BlameOpts, BlameResult, blame};
+pub use branch::{
+    BranchOpts, CleanupPolicy, CleanupReason, CleanupReport, StaleBranch, branch, cleanup_branches,
+    delete_branch, list_branches, rename_branch,
+};
+pub use capabilities::{GitCapabilities, capabilities};
+pub use checkout::{CheckoutOpts, checkout, previous_branch};
+pub use cherry::{CherryCommit, cherry};
+pub use cherry_pick::{CherryPickOpts, CherryPickResult, cherry_pick};
+pub use clone::{CloneOpts, CloneResult, clone_repo};
+pub use clone_preflight::{ClonePreflight, PreflightIssue, clone_preflight};
+pub use codeowners::{Codeowners, CodeownersIssue, CodeownersRule, read_codeowners};
+pub use collisions::{PathIssue, PathIssueKind, detect_case_collisions};
+pub use commit::{CommitOpts, CommitResult, Signature, commit, parse_date};
+pub use compare::{BranchComparison, Mergeability, compare_branches};
+pub use conflicts::{ConflictKind, ConflictedFile, merge_conflicts};
+pub use decorate::{Decoration, DecorationKind};
+pub use diff::{
+    ChangeType, DiffHunk, DiffLine, DiffOpts, DiffStats, FileDiffStats, LineOrigin, ModeChange, diff,
+};
+pub use eol::{EolIssue, LineEnding, eol_audit, renormalize};
+pub use export::{ExportOverwrite, ExportResult, export_tree};
+pub use fast_status::{clear_status_cache, is_clean_fast};
+pub use fetch::{FetchOpts, FetchResult, RefUpdate, RefUpdateKind, fetch};
+pub use fetch_verify::FetchVerification;
+pub use graph::render_graph;
+pub use grep::{GrepMatch, GrepOpts, GrepResult, grep};
 pub use history::{HistoryCommit, HistoryOpts, HistoryResult, history};
-pub use introspection::{DetailedCommitInfo, GitUrl, RepoPaths, get_commit_details, get_repo_paths, parse_git_url};
+pub use ignore::{IgnoreEdit, IgnorePattern, add_patterns, list_patterns, remove_patterns};
+pub use impact::{ImpactReport, ImpactRule, ImpactedTarget, impacted_paths};
+pub use index::{IndexSeed, TempIndex, temporary_index};
+pub use introspection::{
+    DetailedCommitInfo, FetchHeadEntry, GitUrl, HostKind, MergeState, RepoPaths, Trailer,
+    fetch_head, file_changed_between, file_exists_at, get_commit_details, get_repo_paths,
+    is_reachable, merge_state, object_exists, parse_git_url, reachable_count,
+};
+pub use lint::{LintCheck, LintFinding, LintReport, LintRules, LintSeverity};
 pub use log::{LogOpts, log};
+pub use maintenance::{
+    LockCleanupReport, LockFile, clear_stale_locks, find_locks, set_lock_recovery,
+};
 pub use merge::{MergeOpts, MergeOutcome, merge};
+pub use merge_diff::{CombinedFileDiff, MergeDiff, merge_diff};
+pub use midx::{MidxOpts, MidxResult, write_midx};
+pub use nested::{NestedRepo, NestedRepoKind, NestedScanOpts, find_nested_repos};
 pub use open::{
-    RepositoryInfo, discover_repo, init_bare_repo, init_repo, is_repository, open_repo,
-    probe_repository,
+    Discovery, DiscoveryBoundary, GitEnvironment, InitOpts, OpenOpts, RepositoryInfo,
+    discover_repo, discover_repo_report, discover_repo_with, init_bare_repo, init_repo,
+    init_repo_with, is_repository, open_repo, open_repo_with, probe_repository,
+    probe_repository_with,
 };
+pub use ownership::{OwnerCandidate, PathOwners, suggest_owners};
+pub use patch_id::patch_id;
+pub use progress::{CheckoutProgress, ProgressCallback};
 pub use pull::{PullOpts, PullResult, pull};
 pub use push::{
     PushOpts, PushResult, check_remote_branch_exists, check_remote_tag_exists,
-    delete_remote_branch, delete_remote_tag, push, push_current_branch, push_tags,
+    clear_remote_refs_cache, delete_remote_branch, delete_remote_tag, push, push_current_branch,
+    push_tags, remote_refs_exist, remote_refs_exist_cached,
+};
+pub use rebase::{RebaseOpts, RebaseResult, rebase};
+pub use registry::{
+    RepoAlias, lookup_repo, register_repo, registered_repos, resolve_repo_path, unregister_repo,
 };
 pub use remote::{RemoteAddOpts, add_remote, remove_remote};
+pub use remote_head::remote_default_branch;
+pub use release::{ReleaseOpts, ReleaseResult};
+pub use replace::{ReplaceRef, create_replace, delete_replace, graft, list_replace};
+pub use repo_path::RepoPath;
 pub use reset::{ResetMode, ResetOpts, reset, reset_hard, reset_mixed, reset_soft};
+pub use review::{ReviewCommit, ReviewPacket, RiskFlag, RiskKind};
+pub use rewrite::{
+    AuthorMapping, IdentityRule, RewriteResult, RewrittenRef, remove_paths, rewrite_authors,
+};
+pub use scaffold::{ScaffoldOpts, ScaffoldResult, scaffold};
+pub use search::{CommitMatch, CommitQuery, CommitSearchResult};
+pub use shallow::{Deepen, deepen};
+pub use snapshot::{
+    SnapshotInfo, SnapshotRestoreResult, drop_snapshot, list_snapshots, restore_snapshot,
+    snapshot_worktree,
+};
+pub use squash::{SquashMergeResult, squash_merge};
 pub use stash::{StashInfo, StashOpts, stash_pop, stash_save};
 pub use status::{
     BranchInfo, RemoteInfo, current_branch, head_commit, is_clean, is_detached, list_remotes,
     remote_exists,
 };
+pub use subtree::{SubtreeMergeResult, SubtreeSplit, subtree_add, subtree_merge, subtree_split};
+pub use sync::{SyncAction, SyncOpts, SyncResult, sync_repo};
 pub use tag::{TagInfo, TagOpts, create_tag, delete_tag, list_tags, tag_exists};
+pub use update_branch::{
+    UpdateBranchOpts, UpdateBranchResult, UpdateOutcome, UpdateStrategy, update_branch,
+};
+pub use upstream::{UpstreamStatus, ensure_up_to_date, upstream_status};
+pub use warnings::{OperationWarning, WarningKind};
+pub use workspace::{
+    WorkspaceCommit, WorkspaceCommitOutcome, WorkspaceCommitPolicy, WorkspaceCommitResult,
+    WorkspaceRepoStatus, workspace_commit, workspace_status,
+};
 pub use worktree::{
-    WorktreeAddOpts, WorktreeInfo, WorktreeLockOpts, WorktreeRemoveOpts, list_worktrees,
-    worktree_add, worktree_lock, worktree_prune, worktree_remove, worktree_unlock,
+    PruneReason, PrunedWorktree, SkippedWorktree, WorktreeAddOpts, WorktreeInfo, WorktreeLockOpts,
+    WorktreePruneOpts, WorktreePruneReport, WorktreeRemoveOpts, WorktreeStatus, list_worktrees,
+    open_worktree, worktree_add, worktree_lock, worktree_prune, worktree_prune_with,
+    worktree_remove, worktree_statuses, worktree_unlock,
 };
@@ -0,0 +1,285 @@
+//! Repositories nested below a directory.
+//!
+//! A checkout often holds more repositories than its own: submodules,
+//! vendored checkouts, linked worktrees, and now and then a `.git` directory
+//! committed by mistake. [`find_nested_repos`] walks down from a directory
+//! and classifies every repository it finds against the repository that
+//! contains it, so an indexer can tell which directories belong to which
+//! history. It complements [`discover_repo`](super::open::discover_repo),
+//! which searches upward.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use gix::bstr::{BString, ByteSlice};
+use walkdir::WalkDir;
+
+use crate::{GitError, GitResult};
+
+/// Options for [`find_nested_repos`] with builder pattern.
+#[derive(Debug, Clone, Default)]
+pub struct NestedScanOpts {
+    /// Directory levels to descend below the start; `None` for no limit.
+    pub max_depth: Option<usize>,
+}
+
+impl NestedScanOpts {
+    /// Scan the whole tree below the start.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Descend at most `levels` directories below the start.
+    #[inline]
+    #[must_use]
+    pub fn max_depth(mut self, levels: usize) -> Self {
+        self.max_depth = Some(levels);
+        self
+    }
+}
+
+/// What a nested repository is to the repository containing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NestedRepoKind {
+    /// A submodule: a gitlink with a `.gitmodules` entry.
+    Submodule,
+    /// A gitlink without a `.gitmodules` entry, as `git add` makes of a
+    /// repository inside another; clones don't get its content.
+    EmbeddedGitlink,
+    /// A linked worktree of some repository.
+    Worktree,
+    /// A repository the containing one doesn't track, e.g. a vendored
+    /// checkout, or any repository when nothing contains it.
+    Independent,
+    /// A bare repository.
+    Bare,
+    /// A repository whose `.git` directory has files tracked by the
+    /// containing repository, usually committed by mistake.
+    CommittedGitDir,
+    /// A `.git` file pointing at a git dir that doesn't exist.
+    Broken,
+}
+
+impl NestedRepoKind {
+    /// Short name, e.g. `embedded_gitlink`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Submodule => "submodule",
+            Self::EmbeddedGitlink => "embedded_gitlink",
+            Self::Worktree => "worktree",
+            Self::Independent => "independent",
+            Self::Bare => "bare",
+            Self::CommittedGitDir => "committed_git_dir",
+            Self::Broken => "broken",
+        }
+    }
+}
+
+/// A repository found by [`find_nested_repos`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedRepo {
+    /// Directory of the repository: its worktree, or the bare repository.
+    pub path: PathBuf,
+    /// `path` relative to the directory scanned.
+    pub relative_path: PathBuf,
+    pub kind: NestedRepoKind,
+    /// Where its repository files are; `None` for [`NestedRepoKind::Broken`].
+    pub git_dir: Option<PathBuf>,
+    /// Worktree of the nearest repository containing it, if any.
+    pub container: Option<PathBuf>,
+}
+
+/// Find the repositories below `path`, not counting one at `path` itself.
+///
+/// Each is classified against the nearest repository containing it, which
+/// is either another one found or the repository `path` is in. Bare
+/// repositories and `.git` directories aren't searched further; the
+/// worktrees of nested repositories are, so nested submodules are found
+/// too. Symlinks aren't followed. Results are in path order.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kodegen_tools_git::{NestedRepoKind, NestedScanOpts, find_nested_repos};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// for nested in find_nested_repos("/path/to/workspace", NestedScanOpts::new().max_depth(4)).await? {
+///     if nested.kind == NestedRepoKind::CommittedGitDir {
+///         println!("{} has a committed .git directory", nested.path.display());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn find_nested_repos(
+    path: impl AsRef<Path>,
+    opts: NestedScanOpts,
+) -> GitResult<Vec<NestedRepo>> {
+    let path = path.as_ref().to_path_buf();
+
+    tokio::task::spawn_blocking(move || scan(&path, &opts))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// What classifying a nested repository needs of the repository containing it
+struct Container {
+    workdir: PathBuf,
+    /// Paths of gitlinks in the index
+    gitlinks: HashSet<BString>,
+    /// Paths of submodules in `.gitmodules`
+    submodules: HashSet<BString>,
+    /// Paths of tracked files inside a `.git` directory
+    committed_git_dirs: HashSet<BString>,
+}
+
+impl Container {
+    fn load(repo: &gix::Repository) -> GitResult<Option<Self>> {
+        let Some(workdir) = repo.workdir() else {
+            return Ok(None);
+        };
+        let index = repo.index_or_empty().map_err(GitError::gix)?;
+        let mut gitlinks = HashSet::new();
+        let mut committed_git_dirs = HashSet::new();
+        for entry in index.entries() {
+            let path = entry.path(&index);
+            if entry.mode == gix::index::entry::Mode::COMMIT {
+                gitlinks.insert(path.to_owned());
+            }
+            // `a/b/.git/HEAD` is a file of the git dir of `a/b`
+            if let Some(at) = path.find(b"/.git/") {
+                committed_git_dirs.insert(path[..at].to_owned());
+            }
+        }
+        let submodules = match repo.submodules() {
+            Ok(Some(submodules)) => submodules
+                .filter_map(|submodule| submodule.path().ok().map(|path| path.into_owned()))
+                .collect(),
+            _ => HashSet::new(),
+        };
+        Ok(Some(Self {
+            workdir: workdir.canonicalize()?,
+            gitlinks,
+            submodules,
+            committed_git_dirs,
+        }))
+    }
+
+    /// Kind of the non-bare repository at `dir`, which this one contains
+    fn classify(&self, dir: &Path) -> NestedRepoKind {
+        let Ok(relative) = dir.strip_prefix(&self.workdir) else {
+            return NestedRepoKind::Independent;
+        };
+        let relative = gix::path::to_unix_separators_on_windows(gix::path::into_bstr(relative));
+        if self.committed_git_dirs.contains(&*relative) {
+            NestedRepoKind::CommittedGitDir
+        } else if self.gitlinks.contains(&*relative) {
+            if self.submodules.contains(&*relative) {
+                NestedRepoKind::Submodule
+            } else {
+                NestedRepoKind::EmbeddedGitlink
+            }
+        } else {
+            NestedRepoKind::Independent
+        }
+    }
+}
+
+fn scan(path: &Path, opts: &NestedScanOpts) -> GitResult<Vec<NestedRepo>> {
+    let root = path
+        .canonicalize()
+        .map_err(|e| GitError::InvalidInput(format!("Cannot scan {}: {e}", path.display())))?;
+
+    // Repositories containing the one being looked at, innermost last
+    let mut containers: Vec<Container> = Vec::new();
+    if let Ok(outer) = gix::discover(&root)
+        && let Ok(Some(container)) = Container::load(&outer)
+    {
+        containers.push(container);
+    }
+
+    let mut found = Vec::new();
+    let mut walk = WalkDir::new(&root).min_depth(1).sort_by_file_name();
+    if let Some(levels) = opts.max_depth {
+        walk = walk.max_depth(levels);
+    }
+    let mut entries = walk.into_iter();
+    while let Some(entry) = entries.next() {
+        let Ok(entry) = entry else {
+            // Unreadable directories are skipped, as git status does
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let dir = entry.path();
+        if entry.file_name() == ".git" {
+            entries.skip_current_dir();
+            continue;
+        }
+        while containers
+            .last()
+            .is_some_and(|container| !dir.starts_with(&container.workdir))
+        {
+            containers.pop();
+        }
+        let container = containers.last();
+
+        let dotgit = dir.join(".git");
+        let (kind, git_dir) = if dotgit.is_dir() {
+            let kind = container.map_or(NestedRepoKind::Independent, |c| c.classify(dir));
+            (kind, Some(dotgit))
+        } else if dotgit.is_file() {
+            match super::open::gitfile_target(&dotgit) {
+                Some(target) if target.is_dir() => {
+                    let is_worktree = target
+                        .parent()
+                        .and_then(Path::file_name)
+                        .is_some_and(|name| name == "worktrees");
+                    let kind = if is_worktree {
+                        NestedRepoKind::Worktree
+                    } else {
+                        container.map_or(NestedRepoKind::Independent, |c| c.classify(dir))
+                    };
+                    (kind, Some(target))
+                }
+                _ => (NestedRepoKind::Broken, None),
+            }
+        } else if is_bare_repo(dir) {
+            entries.skip_current_dir();
+            (NestedRepoKind::Bare, Some(dir.to_path_buf()))
+        } else {
+            continue;
+        };
+
+        let container = container.map(|c| c.workdir.clone());
+
+        // Repositories inside this one are classified against it
+        if git_dir.is_some()
+            && kind != NestedRepoKind::Bare
+            && let Ok(repo) = gix::open(dir)
+            && let Ok(Some(nested)) = Container::load(&repo)
+        {
+            containers.push(nested);
+        }
+        found.push(NestedRepo {
+            path: dir.to_path_buf(),
+            relative_path: dir.strip_prefix(&root).unwrap_or(dir).to_path_buf(),
+            kind,
+            git_dir,
+            container,
+        });
+    }
+    Ok(found)
+}
+
+/// Whether `dir` is a bare repository, checking the layout before opening it
+fn is_bare_repo(dir: &Path) -> bool {
+    dir.join("HEAD").is_file()
+        && dir.join("objects").is_dir()
+        && dir.join("refs").is_dir()
+        && gix::open(dir).is_ok_and(|repo| repo.is_bare())
+}
@@ -2,35 +2,185 @@
 //!
 //! This module provides functions for opening existing repositories and
 //! discovering repositories from subdirectories for the `GitGix` service.
+//!
+//! A worktree's `.git` may be a file holding `gitdir: <path>` instead of a
+//! directory, as in submodules, linked worktrees and repositories made with
+//! `git init --separate-git-dir`. Everything here accepts both; code that
+//! needs the repository's files should use `git_dir()` or `common_dir()`
+//! rather than joining `.git` to the worktree.
 
 use std::path::{Path, PathBuf};
 
+use gix::bstr::ByteSlice;
+
 use crate::runtime::AsyncTask;
 use crate::{GitError, GitResult, RepoHandle};
 
-/// Open an existing repository at the given path.
-pub fn open_repo<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepoHandle>> {
-    let path = path.as_ref().to_path_buf();
+/// Repository selection from git's environment variables.
+///
+/// git lets a caller choose the repository with `GIT_DIR`, its worktree with
+/// `GIT_WORK_TREE`, and where discovery stops with `GIT_CEILING_DIRECTORIES`.
+/// Hooks and editors started by git, and CI jobs, run with these set; the
+/// opening functions only honor them when asked to, see [`OpenOpts`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitEnvironment {
+    /// `GIT_DIR`: the repository to use instead of looking at the path.
+    pub git_dir: Option<PathBuf>,
+    /// `GIT_WORK_TREE`: the worktree to use instead of the configured one.
+    pub work_tree: Option<PathBuf>,
+    /// `GIT_CEILING_DIRECTORIES`: directories above which discovery doesn't
+    /// search. Only absolute entries count, as for git.
+    pub ceiling_directories: Vec<PathBuf>,
+}
 
-    AsyncTask::spawn(move || {
-        // Check if path exists first
-        if !path.exists() {
-            return Err(GitError::InvalidInput(format!(
-                "Path does not exist: {}",
-                path.display()
-            )));
+impl GitEnvironment {
+    /// Read the variables of the current process.
+    ///
+    /// Relative `GIT_DIR` and `GIT_WORK_TREE` are taken from the current
+    /// directory, as git does.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let path_var = |name: &str| {
+            std::env::var_os(name)
+                .filter(|value| !value.is_empty())
+                .map(|value| std::path::absolute(&value).unwrap_or_else(|_| value.into()))
+        };
+        Self {
+            git_dir: path_var("GIT_DIR"),
+            work_tree: path_var("GIT_WORK_TREE"),
+            ceiling_directories: std::env::var_os("GIT_CEILING_DIRECTORIES")
+                .map(|dirs| {
+                    std::env::split_paths(&dirs)
+                        .filter(|dir| dir.is_absolute())
+                        .collect()
+                })
+                .unwrap_or_default(),
         }
+    }
 
-        let repo = gix::open(&path).map_err(|e| {
-            GitError::InvalidInput(format!(
-                "Failed to open Git repository at {}: {}",
-                path.display(),
-                e
-            ))
-        })?;
+    /// Whether none of the variables is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.git_dir.is_none() && self.work_tree.is_none() && self.ceiling_directories.is_empty()
+    }
 
-        Ok(RepoHandle::new(repo))
-    })
+    /// Point `repo` at `GIT_WORK_TREE`, if set
+    fn apply(&self, repo: &mut gix::Repository) -> GitResult<()> {
+        // gix only honors `core.worktree` from the repository's own config
+        if let Some(work_tree) = &self.work_tree {
+            repo.set_workdir(work_tree.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// Options for [`open_repo_with`], [`discover_repo_with`] and
+/// [`probe_repository_with`] with builder pattern.
+#[derive(Debug, Clone, Default)]
+pub struct OpenOpts {
+    /// Environment to honor; `None` ignores git's environment variables.
+    pub environment: Option<GitEnvironment>,
+    /// Directories above which discovery doesn't search, in addition to
+    /// `GIT_CEILING_DIRECTORIES` of the environment.
+    pub ceiling_dirs: Vec<PathBuf>,
+    /// Parent directories discovery may search above the start; `Some(0)`
+    /// only looks at the start itself.
+    pub max_depth: Option<usize>,
+}
+
+impl OpenOpts {
+    /// Options that ignore the environment, like [`open_repo`].
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Honor `GIT_DIR`, `GIT_WORK_TREE` and `GIT_CEILING_DIRECTORIES` as
+    /// set in this process now.
+    #[inline]
+    #[must_use]
+    pub fn honor_env(mut self, yes: bool) -> Self {
+        self.environment = yes.then(GitEnvironment::from_env);
+        self
+    }
+
+    /// Honor the given variables instead of the process's.
+    #[inline]
+    #[must_use]
+    pub fn environment(mut self, environment: GitEnvironment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Don't let discovery search above `dir`.
+    #[inline]
+    #[must_use]
+    pub fn ceiling_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.ceiling_dirs.push(dir.into());
+        self
+    }
+
+    /// Let discovery search at most `levels` parent directories.
+    #[inline]
+    #[must_use]
+    pub fn max_depth(mut self, levels: usize) -> Self {
+        self.max_depth = Some(levels);
+        self
+    }
+}
+
+/// A limit that bounds how far [`discover_repo_report`] searches upward.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveryBoundary {
+    /// A ceiling directory, from [`OpenOpts::ceiling_dirs`] or
+    /// `GIT_CEILING_DIRECTORIES`.
+    Ceiling(PathBuf),
+    /// [`OpenOpts::max_depth`] parent directories.
+    MaxDepth(usize),
+}
+
+impl std::fmt::Display for DiscoveryBoundary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ceiling(dir) => write!(f, "ceiling directory {}", dir.display()),
+            Self::MaxDepth(levels) => write!(f, "maximum depth of {levels}"),
+        }
+    }
+}
+
+/// A repository found by [`discover_repo_report`], and how.
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    /// The repository.
+    pub repo: RepoHandle,
+    /// Directory the search started from, canonicalized.
+    pub start: PathBuf,
+    /// Directory the repository was found in: its worktree, or its git dir
+    /// if it is bare.
+    pub root: PathBuf,
+    /// Parent directories walked up from `start` to `root`; 0 when `start`
+    /// is `root` or `GIT_DIR` chose the repository.
+    pub levels_up: usize,
+    /// The nearest limit above `start`, which would have stopped the search
+    /// had `root` not been a repository; `None` if it could go on to the
+    /// filesystem root.
+    pub boundary: Option<DiscoveryBoundary>,
+}
+
+/// Open an existing repository at the given path.
+pub fn open_repo<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepoHandle>> {
+    open_repo_with(path, OpenOpts::new())
+}
+
+/// Open an existing repository at the given path with the given options.
+///
+/// With an environment, `GIT_DIR` names the repository and `path` is only
+/// used without it; `GIT_WORK_TREE` replaces the repository's worktree.
+pub fn open_repo_with<P: AsRef<Path>>(path: P, opts: OpenOpts) -> AsyncTask<GitResult<RepoHandle>> {
+    let path = path.as_ref().to_path_buf();
+
+    AsyncTask::spawn(move || open_blocking(&path, &opts, "open").map(RepoHandle::new))
 }
 
 /// Discover a repository by searching upward from the given path.
@@ -38,49 +188,251 @@ pub fn open_repo<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepoHandle>> {
 /// This function will search from the given path upward through parent
 /// directories until it finds a Git repository or reaches the filesystem root.
 pub fn discover_repo<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepoHandle>> {
-    let path = path.as_ref().to_path_buf();
+    discover_repo_with(path, OpenOpts::new())
+}
 
-    AsyncTask::spawn(move || {
-        let repo = gix::discover(&path).map_err(|e| {
-            GitError::InvalidInput(format!(
-                "No Git repository found at {} or any parent directory: {}",
-                path.display(),
-                e
-            ))
-        })?;
+/// Discover a repository from the given path with the given options.
+///
+/// With an environment, `GIT_DIR` is used instead of searching, the search
+/// doesn't go above `GIT_CEILING_DIRECTORIES`, and `GIT_WORK_TREE` replaces
+/// the worktree of the repository found. [`OpenOpts::ceiling_dirs`] and
+/// [`OpenOpts::max_depth`] bound the search further.
+pub fn discover_repo_with<P: AsRef<Path>>(
+    path: P,
+    opts: OpenOpts,
+) -> AsyncTask<GitResult<RepoHandle>> {
+    let path = path.as_ref().to_path_buf();
 
-        Ok(RepoHandle::new(repo))
-    })
+    AsyncTask::spawn(move || discover_blocking(&path, &opts).map(|found| found.repo))
 }
 
-/// Initialize a new repository at the given path.
-pub fn init_repo<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepoHandle>> {
+/// Discover a repository like [`discover_repo_with`], reporting how far the
+/// search went and what bounded it.
+///
+/// An unbounded search finds any repository above the start, which may be
+/// an unrelated one, e.g. a home directory kept in git. Check
+/// [`Discovery::levels_up`] or bound the search to avoid working on it. If
+/// nothing is found, the error names the boundary that stopped the search.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kodegen_tools_git::{OpenOpts, discover_repo_report};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let found = discover_repo_report("/path/to/repo/src", OpenOpts::new().max_depth(3)).await??;
+/// println!("{} is {} levels up", found.root.display(), found.levels_up);
+/// # Ok(())
+/// # }
+/// ```
+pub fn discover_repo_report<P: AsRef<Path>>(
+    path: P,
+    opts: OpenOpts,
+) -> AsyncTask<GitResult<Discovery>> {
     let path = path.as_ref().to_path_buf();
 
-    AsyncTask::spawn(move || {
-        if !path.exists() {
-            std::fs::create_dir_all(&path).map_err(GitError::Io)?;
-        }
+    AsyncTask::spawn(move || discover_blocking(&path, &opts))
+}
 
-        // Check if already a repository
-        if gix::open(&path).is_ok() {
-            return Err(GitError::InvalidInput(format!(
-                "Path is already a Git repository: {}",
-                path.display()
-            )));
-        }
+/// Search upward from `path` within the bounds of `opts`
+fn discover_blocking(path: &Path, opts: &OpenOpts) -> GitResult<Discovery> {
+    let not_found = |e: &dyn std::fmt::Display| {
+        GitError::InvalidInput(format!(
+            "No Git repository found at {} or any parent directory: {e}",
+            path.display()
+        ))
+    };
 
-        let repo = gix::init(&path).map_err(GitError::from)?;
+    // GIT_DIR chooses the repository, there is nothing to search
+    if opts
+        .environment
+        .as_ref()
+        .is_some_and(|environment| environment.git_dir.is_some())
+    {
+        let repo = open_blocking(path, opts, "open")?;
+        return Ok(Discovery {
+            start: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+            root: root_of(&repo),
+            repo: RepoHandle::new(repo),
+            levels_up: 0,
+            boundary: None,
+        });
+    }
 
-        Ok(RepoHandle::new(repo))
+    let start = path.canonicalize().map_err(|e| not_found(&e))?;
+    let nearest = nearest_boundary(&start, opts);
+    let upwards = gix::discover::upwards::Options {
+        ceiling_dirs: nearest.iter().map(|(dir, _)| dir.clone()).collect(),
+        match_ceiling_dir_or_error: false,
+        ..Default::default()
+    };
+    let stopped = |(dir, boundary): &(PathBuf, DiscoveryBoundary)| {
+        GitError::InvalidInput(format!(
+            "No Git repository found from {} up to {}; stopped by the {boundary}",
+            path.display(),
+            dir.display()
+        ))
+    };
+    let trust_map = gix::sec::trust::Mapping::<gix::open::Options>::default();
+    let mut repo = gix::ThreadSafeRepository::discover_opts(&start, upwards, trust_map)
+        .map_err(|e| nearest.as_ref().map_or_else(|| not_found(&e), stopped))?
+        .to_thread_local();
+    if let Some(environment) = &opts.environment {
+        environment.apply(&mut repo)?;
+    }
+
+    let root = root_of(&repo);
+    let levels_up = root
+        .canonicalize()
+        .ok()
+        .and_then(|root| start.ancestors().position(|dir| dir == root))
+        .unwrap_or(0);
+    // gix ignores a ceiling at the start itself, so `max_depth(0)` is checked here
+    if let Some(limit) = &nearest
+        && start
+            .ancestors()
+            .nth(levels_up)
+            .is_some_and(|root| !root.starts_with(&limit.0))
+    {
+        return Err(stopped(limit));
+    }
+    Ok(Discovery {
+        repo: RepoHandle::new(repo),
+        start,
+        root,
+        levels_up,
+        boundary: nearest.map(|(_, boundary)| boundary),
     })
 }
 
+/// Directory the nearest limit of `opts` keeps discovery from `start` out
+/// of, with that limit
+fn nearest_boundary(start: &Path, opts: &OpenOpts) -> Option<(PathBuf, DiscoveryBoundary)> {
+    let environment_ceilings = opts
+        .environment
+        .iter()
+        .flat_map(|environment| &environment.ceiling_directories);
+    let ceilings = opts
+        .ceiling_dirs
+        .iter()
+        .chain(environment_ceilings)
+        .filter_map(|dir| {
+            let dir = dir.canonicalize().ok()?;
+            let level = start.ancestors().position(|parent| parent == dir)?;
+            Some((level, dir.clone(), DiscoveryBoundary::Ceiling(dir)))
+        });
+    // Like gix, the directory returned is the last one searched
+    let depth = opts.max_depth.and_then(|levels| {
+        let dir = start.ancestors().nth(levels)?;
+        Some((
+            levels,
+            dir.to_path_buf(),
+            DiscoveryBoundary::MaxDepth(levels),
+        ))
+    });
+    ceilings
+        .chain(depth)
+        .min_by_key(|(level, ..)| *level)
+        .map(|(_, dir, boundary)| (dir, boundary))
+}
+
+/// Worktree of `repo`, or its git dir if it is bare
+fn root_of(repo: &gix::Repository) -> PathBuf {
+    repo.workdir()
+        .unwrap_or_else(|| repo.git_dir())
+        .to_path_buf()
+}
+
+/// Open `path`, or the repository the environment of `opts` names
+fn open_blocking(path: &Path, opts: &OpenOpts, verb: &str) -> GitResult<gix::Repository> {
+    let path = opts
+        .environment
+        .as_ref()
+        .and_then(|environment| environment.git_dir.as_deref())
+        .unwrap_or(path);
+
+    // Check if path exists first
+    if !path.exists() {
+        return Err(GitError::InvalidInput(format!(
+            "Path does not exist: {}",
+            path.display()
+        )));
+    }
+
+    check_gitfile(path)?;
+    let mut repo = gix::open(path).map_err(|e| {
+        GitError::InvalidInput(format!(
+            "Failed to {verb} Git repository at {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    if let Some(environment) = &opts.environment {
+        environment.apply(&mut repo)?;
+    }
+    Ok(repo)
+}
+
+/// Options for [`init_repo_with`] with builder pattern.
+#[derive(Debug, Clone, Default)]
+pub struct InitOpts {
+    /// Create a bare repository without a working tree.
+    pub bare: bool,
+    /// Keep the repository in this directory and point the worktree's `.git`
+    /// file at it, like `git init --separate-git-dir`.
+    pub separate_git_dir: Option<PathBuf>,
+}
+
+impl InitOpts {
+    /// Options for a normal repository with its `.git` directory in the worktree.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set bare repository flag.
+    #[inline]
+    #[must_use]
+    pub fn bare(mut self, yes: bool) -> Self {
+        self.bare = yes;
+        self
+    }
+
+    /// Keep the repository in `dir` instead of `.git` in the worktree.
+    #[inline]
+    #[must_use]
+    pub fn separate_git_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.separate_git_dir = Some(dir.into());
+        self
+    }
+}
+
+/// Initialize a new repository at the given path.
+pub fn init_repo<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepoHandle>> {
+    init_repo_with(path, InitOpts::new())
+}
+
 /// Initialize a bare repository at the given path.
 pub fn init_bare_repo<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepoHandle>> {
+    init_repo_with(path, InitOpts::new().bare(true))
+}
+
+/// Initialize a repository at the given path with the given options.
+///
+/// With [`InitOpts::separate_git_dir`], the repository is created in that
+/// directory, which must not be a repository yet, and `path` gets a `.git`
+/// file pointing at it. A bare repository has no worktree to point from, so
+/// the two options can't be combined.
+pub fn init_repo_with<P: AsRef<Path>>(path: P, opts: InitOpts) -> AsyncTask<GitResult<RepoHandle>> {
     let path = path.as_ref().to_path_buf();
 
     AsyncTask::spawn(move || {
+        if opts.bare && opts.separate_git_dir.is_some() {
+            return Err(GitError::InvalidInput(
+                "A bare repository can't have a separate git dir".to_string(),
+            ));
+        }
         if !path.exists() {
             std::fs::create_dir_all(&path).map_err(GitError::Io)?;
         }
@@ -93,12 +445,96 @@ pub fn init_bare_repo<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepoHandle
             )));
         }
 
-        let repo = gix::init_bare(&path).map_err(GitError::from)?;
+        let repo = match &opts.separate_git_dir {
+            Some(git_dir) => init_separate(&path, git_dir)?,
+            None if opts.bare => gix::init_bare(&path).map_err(GitError::from)?,
+            None => gix::init(&path).map_err(GitError::from)?,
+        };
 
         Ok(RepoHandle::new(repo))
     })
 }
 
+/// Create the repository in `git_dir` and link the worktree `path` to it
+fn init_separate(path: &Path, git_dir: &Path) -> GitResult<gix::Repository> {
+    if gix::open(git_dir).is_ok() {
+        return Err(GitError::InvalidInput(format!(
+            "Git dir is already a Git repository: {}",
+            git_dir.display()
+        )));
+    }
+    std::fs::create_dir_all(git_dir)?;
+    let git_dir = git_dir.canonicalize()?;
+    let repo = gix::init_bare(&git_dir).map_err(GitError::from)?;
+
+    // The repository has a worktree, just not around it. Recording where
+    // lets handles opened on the git dir itself, such as the main repository
+    // of a linked worktree, find it instead of guessing the git dir's parent.
+    let path = path.canonicalize()?;
+    let config_path = repo.common_dir().join("config");
+    let mut config =
+        gix::config::File::from_path_no_includes(config_path.clone(), gix::config::Source::Local)
+            .map_err(GitError::gix)?;
+    config
+        .set_raw_value_by("core", None, "bare", "false")
+        .map_err(GitError::gix)?;
+    config
+        .set_raw_value_by(
+            "core",
+            None,
+            "worktree",
+            gix::path::into_bstr(path.as_path()).as_ref(),
+        )
+        .map_err(GitError::gix)?;
+    let mut lock = gix::lock::File::acquire_to_update_resource(
+        &config_path,
+        gix::lock::acquire::Fail::Immediately,
+        None,
+    )
+    .map_err(GitError::gix)?;
+    config.write_to(&mut lock)?;
+    lock.commit().map_err(|e| GitError::gix(e.error))?;
+
+    std::fs::write(
+        path.join(".git"),
+        format!("gitdir: {}\n", git_dir.display()),
+    )?;
+    gix::open(&path).map_err(GitError::gix)
+}
+
+/// Git dir a `.git` file points at, relative paths taken from its directory
+///
+/// `None` if `dotgit` isn't a file or doesn't start with `gitdir: `.
+pub(crate) fn gitfile_target(dotgit: &Path) -> Option<PathBuf> {
+    if !dotgit.is_file() {
+        return None;
+    }
+    let content = std::fs::read(dotgit).ok()?;
+    let target = content.strip_prefix(b"gitdir: ")?.trim_end();
+    let target = gix::path::from_bstr(target.as_bstr()).into_owned();
+    Some(match dotgit.parent() {
+        Some(parent) if target.is_relative() => parent.join(target),
+        _ => target,
+    })
+}
+
+/// Fail with a useful message if `path` has a `.git` file pointing nowhere
+///
+/// gix only says the path isn't a repository, which hides that the worktree
+/// is fine and its repository was moved or deleted, e.g. a submodule whose
+/// superproject lost `.git/modules/<name>`.
+fn check_gitfile(path: &Path) -> GitResult<()> {
+    let dotgit = path.join(".git");
+    match gitfile_target(&dotgit) {
+        Some(target) if !target.is_dir() => Err(GitError::InvalidInput(format!(
+            "{} points at {}, which does not exist",
+            dotgit.display(),
+            target.display()
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Check if a path contains a valid Git repository.
 pub fn is_repository<P: AsRef<Path>>(path: P) -> AsyncTask<bool> {
     let path = path.as_ref().to_path_buf();
@@ -108,22 +544,34 @@ pub fn is_repository<P: AsRef<Path>>(path: P) -> AsyncTask<bool> {
 
 /// Get repository information without opening the full repository.
 pub fn probe_repository<P: AsRef<Path>>(path: P) -> AsyncTask<GitResult<RepositoryInfo>> {
+    probe_repository_with(path, OpenOpts::new())
+}
+
+/// Get repository information, opening the repository with the given options.
+///
+/// [`RepositoryInfo::environment`] reports the variables that were honored.
+pub fn probe_repository_with<P: AsRef<Path>>(
+    path: P,
+    opts: OpenOpts,
+) -> AsyncTask<GitResult<RepositoryInfo>> {
     let path = path.as_ref().to_path_buf();
 
     AsyncTask::spawn(move || {
-        let repo = gix::open(&path).map_err(|e| {
-            GitError::InvalidInput(format!(
-                "Failed to probe Git repository at {}: {}",
-                path.display(),
-                e
-            ))
-        })?;
+        let repo = open_blocking(&path, &opts, "probe")?;
 
+        let gitfile = repo
+            .workdir()
+            .map(|workdir| workdir.join(".git"))
+            .filter(|dotgit| dotgit.is_file());
         Ok(RepositoryInfo {
             path,
             is_bare: repo.is_bare(),
             git_dir: repo.git_dir().to_path_buf(),
+            common_dir: repo.common_dir().to_path_buf(),
             work_dir: repo.workdir().map(std::path::Path::to_path_buf),
+            gitfile,
+            is_shallow: repo.is_shallow(),
+            environment: opts.environment.unwrap_or_default(),
         })
     })
 }
@@ -135,8 +583,17 @@ pub struct RepositoryInfo {
     pub path: PathBuf,
     /// Whether this is a bare repository.
     pub is_bare: bool,
-    /// Path to the .git directory.
+    /// Path to the .git directory, wherever it lives.
     pub git_dir: PathBuf,
+    /// Directory shared by all worktrees; differs from `git_dir` in a linked worktree.
+    pub common_dir: PathBuf,
     /// Path to the working directory (None for bare repositories).
     pub work_dir: Option<PathBuf>,
+    /// The worktree's `.git` file, if `.git` is a file pointing at `git_dir`
+    /// (submodules, linked worktrees, separate git dirs).
+    pub gitfile: Option<PathBuf>,
+    /// Whether this is a shallow clone with truncated history.
+    pub is_shallow: bool,
+    /// Environment variables honored when opening; empty if none were.
+    pub environment: GitEnvironment,
 }
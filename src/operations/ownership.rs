@@ -0,0 +1,199 @@
+//! Reviewer suggestions from blame and recent history
+//!
+//! [`suggest_owners`] ranks the people most familiar with each path: authors
+//! of the lines that survive at HEAD (blame) and authors of the most recent
+//! commits touching the path. Candidates also listed in CODEOWNERS are flagged
+//! so automation can prefer them.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use gix::bstr::ByteSlice;
+
+use super::codeowners::Codeowners;
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Weight of the blame share in the score (the rest is recent activity)
+const BLAME_WEIGHT: f64 = 0.6;
+
+/// A likely owner of a path
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerCandidate {
+    /// Author name (from the most recent commit seen)
+    pub name: String,
+    /// Author email, used to identify the person
+    pub email: String,
+    /// Lines at HEAD last changed by this author
+    pub blame_lines: usize,
+    /// Commits by this author among the recent commits touching the path
+    pub recent_commits: usize,
+    /// Combined score in `0.0..=1.0`
+    pub score: f64,
+    /// Whether CODEOWNERS lists this author (by email) for the path
+    pub in_codeowners: bool,
+}
+
+/// Ranked owner candidates for one path
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathOwners {
+    /// Repository-relative path
+    pub path: String,
+    /// Candidates, best first
+    pub candidates: Vec<OwnerCandidate>,
+    /// Owners CODEOWNERS assigns to the path (empty without a CODEOWNERS file)
+    pub codeowners: Vec<String>,
+}
+
+#[derive(Default)]
+struct Tally {
+    name: String,
+    blame_lines: usize,
+    recent_commits: usize,
+}
+
+/// Rank likely owners/reviewers for each path
+///
+/// `window` is the number of most recent commits touching a path that count
+/// as recent activity. Directories get history-based candidates only, since
+/// blame needs a file. Paths are relative to the repository root.
+pub async fn suggest_owners(
+    repo: RepoHandle,
+    paths: Vec<String>,
+    window: usize,
+) -> GitResult<Vec<PathOwners>> {
+    let repo_inner = repo.clone_for_traversal();
+
+    tokio::task::spawn_blocking(move || {
+        let head_id = repo_inner.head_id().map_err(GitError::gix)?.detach();
+        owners_at(&repo_inner, head_id, &paths, window)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// [`suggest_owners`] with blame and history taken at commit `at`
+pub(crate) fn owners_at(
+    repo: &gix::Repository,
+    at: gix::ObjectId,
+    paths: &[String],
+    window: usize,
+) -> GitResult<Vec<PathOwners>> {
+    let codeowners = super::codeowners::load(repo)?;
+    let mut authors = HashMap::new();
+
+    paths
+        .iter()
+        .map(|path| {
+            let path = path.trim_start_matches("./").trim_end_matches('/');
+            owners_for_path(repo, at, path, window, codeowners.as_ref(), &mut authors)
+        })
+        .collect()
+}
+
+fn owners_for_path(
+    repo: &gix::Repository,
+    head_id: gix::ObjectId,
+    path: &str,
+    window: usize,
+    codeowners: Option<&Codeowners>,
+    authors: &mut HashMap<gix::ObjectId, (String, String)>,
+) -> GitResult<PathOwners> {
+    let mut tallies: HashMap<String, Tally> = HashMap::new();
+
+    // Blame fails for directories, binary files and paths missing at HEAD
+    if let Ok(outcome) =
+        repo.blame_file(path.as_bytes().as_bstr(), head_id, gix::repository::blame_file::Options::default())
+    {
+        for entry in &outcome.entries {
+            let (name, email) = commit_author(repo, entry.commit_id, authors)?;
+            let tally = tallies.entry(email).or_default();
+            tally.name = name;
+            tally.blame_lines += entry.len.get() as usize;
+        }
+    }
+
+    if window > 0 {
+        let walk = repo.rev_walk([head_id]).all().map_err(GitError::gix)?;
+
+        let mut seen = 0;
+        for info in walk {
+            let info = info.map_err(GitError::gix)?;
+            let commit = repo.find_commit(info.id).map_err(GitError::gix)?;
+            if !super::log::commit_touches_path(repo, &commit, Path::new(path))? {
+                continue;
+            }
+
+            let (name, email) = commit_author(repo, info.id, authors)?;
+            let tally = tallies.entry(email).or_default();
+            if tally.name.is_empty() {
+                tally.name = name;
+            }
+            tally.recent_commits += 1;
+
+            seen += 1;
+            if seen >= window {
+                break;
+            }
+        }
+    }
+
+    let path_codeowners = codeowners
+        .map(|codeowners| codeowners.owners_for(path).to_vec())
+        .unwrap_or_default();
+    let total_lines: usize = tallies.values().map(|t| t.blame_lines).sum();
+    let total_commits: usize = tallies.values().map(|t| t.recent_commits).sum();
+    let share = |part: usize, total: usize| {
+        if total == 0 { 0.0 } else { part as f64 / total as f64 }
+    };
+    // Without blame data recent activity is all there is, and vice versa
+    let blame_weight = match (total_lines, total_commits) {
+        (0, _) => 0.0,
+        (_, 0) => 1.0,
+        _ => BLAME_WEIGHT,
+    };
+
+    let mut candidates: Vec<OwnerCandidate> = tallies
+        .into_iter()
+        .map(|(email, tally)| OwnerCandidate {
+            score: blame_weight * share(tally.blame_lines, total_lines)
+                + (1.0 - blame_weight) * share(tally.recent_commits, total_commits),
+            in_codeowners: path_codeowners
+                .iter()
+                .any(|owner| owner.eq_ignore_ascii_case(&email)),
+            name: tally.name,
+            email,
+            blame_lines: tally.blame_lines,
+            recent_commits: tally.recent_commits,
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.in_codeowners
+            .cmp(&a.in_codeowners)
+            .then(b.score.total_cmp(&a.score))
+            .then_with(|| a.email.cmp(&b.email))
+    });
+
+    Ok(PathOwners {
+        path: path.to_string(),
+        candidates,
+        codeowners: path_codeowners,
+    })
+}
+
+/// Author (name, email) of a commit, cached across paths
+fn commit_author(
+    repo: &gix::Repository,
+    id: gix::ObjectId,
+    cache: &mut HashMap<gix::ObjectId, (String, String)>,
+) -> GitResult<(String, String)> {
+    if let Some(author) = cache.get(&id) {
+        return Ok(author.clone());
+    }
+
+    let commit = repo.find_commit(id).map_err(GitError::gix)?;
+    let author = commit.author().map_err(GitError::gix)?;
+    let entry = (author.name.to_string(), author.email.to_string().to_lowercase());
+    cache.insert(id, entry.clone());
+    Ok(entry)
+}
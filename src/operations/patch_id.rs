@@ -0,0 +1,339 @@
+//! Patch ids: a hash of the change a commit introduces.
+//!
+//! Two commits that make the same change have the same patch id even if they
+//! sit on different parents, which is how cherry-picked and rebased commits
+//! are recognized. Like git's stable patch ids, every changed file is hashed
+//! on its own and the per-file hashes are summed, so the result does not
+//! depend on file order. Whitespace, line numbers and blob ids of text files
+//! do not contribute.
+//!
+//! For text changes the ids equal `git show <commit> | git patch-id --stable`.
+//! Binary files are hashed by their full blob ids, as git does internally for
+//! `git cherry` and rebase, rather than the abbreviated ids of `git show`.
+
+use gix::bstr::{BStr, BString, ByteSlice};
+use gix::objs::tree::{EntryKind, EntryMode};
+
+use super::compare::resolve_commit;
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Lines of context around changes, as in `git diff`
+const CONTEXT_LINES: usize = 3;
+
+/// Bytes git inspects when deciding whether content is binary
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// One side of a changed path
+#[derive(Clone, Copy)]
+pub(crate) struct Side {
+    pub(crate) mode: EntryMode,
+    pub(crate) id: gix::ObjectId,
+}
+
+/// A changed file: its path and the sides before and after the change
+pub(crate) struct FileChange {
+    pub(crate) path: BString,
+    pub(crate) old: Option<Side>,
+    pub(crate) new: Option<Side>,
+}
+
+/// Stable patch id of `commit` (a revision or object id).
+///
+/// Commits making the same change get the same id wherever they were
+/// applied. `None` for merge commits and for commits that change nothing.
+pub async fn patch_id(repo: RepoHandle, commit: &str) -> GitResult<Option<gix::ObjectId>> {
+    let repo_inner = repo.clone_inner();
+    let commit = commit.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let id = resolve_commit(&repo_inner, &commit)?;
+        commit_patch_id(&repo_inner, id)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Patch id of the change `commit` makes relative to its parent.
+///
+/// `None` for merge commits, which have no single change, and for commits
+/// that change nothing.
+pub(crate) fn commit_patch_id(
+    repo: &gix::Repository,
+    commit: CommitId,
+) -> GitResult<Option<gix::ObjectId>> {
+    let commit = repo.find_commit(commit).map_err(GitError::gix)?;
+    let mut parents = commit.parent_ids();
+    let parent = parents.next();
+    if parents.next().is_some() {
+        return Ok(None);
+    }
+
+    let new_tree = commit.tree().map_err(GitError::gix)?;
+    let old_tree = match parent {
+        Some(parent) => repo
+            .find_commit(parent)
+            .map_err(GitError::gix)?
+            .tree()
+            .map_err(GitError::gix)?,
+        None => repo.empty_tree(),
+    };
+    tree_patch_id(repo, &old_tree, &new_tree)
+}
+
+/// Patch id of the change from `old` to `new`, `None` if they are equal
+pub(crate) fn tree_patch_id(
+    repo: &gix::Repository,
+    old: &gix::Tree<'_>,
+    new: &gix::Tree<'_>,
+) -> GitResult<Option<gix::ObjectId>> {
+    let changes = file_changes(repo, old, new)?;
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    // git patch-id reads `git show` as a stream and hashes each file up to
+    // the next file that has hunks; files without hunks (mode changes, empty
+    // files) are hashed together with the file after them
+    let mut sum = vec![0u8; repo.object_hash().len_in_bytes()];
+    let mut hasher = PatchHasher::new(repo);
+    let mut last = None;
+    for FileChange { path, old, new } in changes {
+        if last == Some(FileDiff::Text) {
+            hasher.flush_into(&mut sum)?;
+        }
+        let after_binary = last == Some(FileDiff::Binary);
+        let diff = hash_file(repo, &mut hasher, path.as_bstr(), old, new, after_binary)?;
+        if diff == FileDiff::Binary {
+            hasher.flush_into(&mut sum)?;
+        }
+        last = Some(diff);
+    }
+    hasher.flush_into(&mut sum)?;
+    Ok(Some(gix::ObjectId::from_bytes_or_panic(&sum)))
+}
+
+/// Changed files between two trees, without rename detection
+///
+/// A path whose type changes (e.g. file to symlink) is a deletion and an
+/// addition, as git shows it.
+pub(crate) fn file_changes(
+    repo: &gix::Repository,
+    old: &gix::Tree<'_>,
+    new: &gix::Tree<'_>,
+) -> GitResult<Vec<FileChange>> {
+    use gix::object::tree::diff::ChangeDetached;
+
+    let changes = repo
+        .diff_tree_to_tree(old, new, gix::diff::Options::default().with_rewrites(None))
+        .map_err(GitError::gix)?;
+
+    let mut files = Vec::new();
+    for change in changes {
+        match change {
+            ChangeDetached::Addition {
+                location,
+                entry_mode,
+                id,
+                ..
+            } if !entry_mode.is_tree() => {
+                files.push(FileChange {
+                    path: location,
+                    old: None,
+                    new: Some(Side { mode: entry_mode, id }),
+                });
+            }
+            ChangeDetached::Deletion {
+                location,
+                entry_mode,
+                id,
+                ..
+            } if !entry_mode.is_tree() => {
+                files.push(FileChange {
+                    path: location,
+                    old: Some(Side { mode: entry_mode, id }),
+                    new: None,
+                });
+            }
+            ChangeDetached::Modification {
+                location,
+                previous_entry_mode,
+                previous_id,
+                entry_mode,
+                id,
+            } => {
+                let old = Side {
+                    mode: previous_entry_mode,
+                    id: previous_id,
+                };
+                let new = Side {
+                    mode: entry_mode,
+                    id,
+                };
+                let change = |old, new| FileChange {
+                    path: location.clone(),
+                    old,
+                    new,
+                };
+                match (old.mode.is_tree(), new.mode.is_tree()) {
+                    (true, true) => {}
+                    (true, false) => files.push(change(None, Some(new))),
+                    (false, true) => files.push(change(Some(old), None)),
+                    (false, false) if content_kind(old.mode) != content_kind(new.mode) => {
+                        files.push(change(Some(old), None));
+                        files.push(change(None, Some(new)));
+                    }
+                    (false, false) => files.push(change(Some(old), Some(new))),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(files)
+}
+
+/// What a file's diff consisted of, which decides where git splits hashes
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FileDiff {
+    /// Only header lines, such as for a mode change or an empty file
+    Headers,
+    /// `Binary files differ`
+    Binary,
+    /// Header and hunks
+    Text,
+}
+
+/// Feeds the lines `git diff` would print for one file into `hasher`
+///
+/// After a binary file git skips the next `diff --git` line, so
+/// `after_binary` leaves it out too.
+fn hash_file(
+    repo: &gix::Repository,
+    hasher: &mut PatchHasher,
+    path: &BStr,
+    old: Option<Side>,
+    new: Option<Side>,
+    after_binary: bool,
+) -> GitResult<FileDiff> {
+    if !after_binary {
+        hasher.line(format!("diff --git a/{path} b/{path}").as_bytes());
+    }
+    match (old, new) {
+        (None, Some(new)) => {
+            hasher.line(format!("new file mode {}", octal(new.mode)).as_bytes());
+        }
+        (Some(old), None) => {
+            hasher.line(format!("deleted file mode {}", octal(old.mode)).as_bytes());
+        }
+        (Some(old), Some(new)) if old.mode != new.mode => {
+            hasher.line(format!("old mode {}", octal(old.mode)).as_bytes());
+            hasher.line(format!("new mode {}", octal(new.mode)).as_bytes());
+        }
+        _ => {}
+    }
+
+    let old_content = old.map(|side| content(repo, side)).transpose()?.unwrap_or_default();
+    let new_content = new.map(|side| content(repo, side)).transpose()?.unwrap_or_default();
+
+    // Mode-only changes and empty files have no content lines at all
+    if old_content == new_content {
+        return Ok(FileDiff::Headers);
+    }
+
+    if is_binary(&old_content) || is_binary(&new_content) {
+        let null = gix::ObjectId::null(repo.object_hash());
+        hasher.line(old.map_or(null, |side| side.id).to_hex().to_string().as_bytes());
+        hasher.line(new.map_or(null, |side| side.id).to_hex().to_string().as_bytes());
+        return Ok(FileDiff::Binary);
+    }
+
+    let old_label = old.map_or_else(|| "/dev/null".to_string(), |_| format!("a/{path}"));
+    let new_label = new.map_or_else(|| "/dev/null".to_string(), |_| format!("b/{path}"));
+    hasher.line(format!("--- {old_label}").as_bytes());
+    hasher.line(format!("+++ {new_label}").as_bytes());
+
+    let old_text = String::from_utf8_lossy(&old_content);
+    let new_text = String::from_utf8_lossy(&new_content);
+    let diff = similar::TextDiff::configure()
+        .algorithm(similar::Algorithm::Myers)
+        .diff_lines(old_text.as_ref(), new_text.as_ref());
+    for group in diff.grouped_ops(CONTEXT_LINES) {
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let prefix = match change.tag() {
+                    similar::ChangeTag::Delete => "-",
+                    similar::ChangeTag::Insert => "+",
+                    similar::ChangeTag::Equal => " ",
+                };
+                hasher.line(format!("{prefix}{}", change.value()).as_bytes());
+            }
+        }
+    }
+    Ok(FileDiff::Text)
+}
+
+/// Hashes diff lines with all whitespace removed
+struct PatchHasher {
+    kind: gix::hash::Kind,
+    hasher: gix::hash::Hasher,
+}
+
+impl PatchHasher {
+    fn new(repo: &gix::Repository) -> Self {
+        let kind = repo.object_hash();
+        Self {
+            kind,
+            hasher: gix::hash::hasher(kind),
+        }
+    }
+
+    fn line(&mut self, line: &[u8]) {
+        // Whitespace as C's isspace() sees it, including vertical tab
+        let kept: Vec<u8> = line
+            .iter()
+            .copied()
+            .filter(|b| !b.is_ascii_whitespace() && *b != 0x0b)
+            .collect();
+        self.hasher.update(&kept);
+    }
+
+    /// Adds the hash so far to `sum` and starts a new one
+    fn flush_into(&mut self, sum: &mut [u8]) -> GitResult<()> {
+        let hasher = std::mem::replace(&mut self.hasher, gix::hash::hasher(self.kind));
+        let hash = hasher.try_finalize().map_err(GitError::gix)?;
+        // Sum with carry, so the order of files does not matter
+        let mut carry = 0u16;
+        for (total, byte) in sum.iter_mut().zip(hash.as_bytes()) {
+            carry += u16::from(*total) + u16::from(*byte);
+            *total = carry as u8;
+            carry >>= 8;
+        }
+        Ok(())
+    }
+}
+
+/// Content git diffs for an entry; submodules show the commit they point at
+pub(crate) fn content(repo: &gix::Repository, side: Side) -> GitResult<Vec<u8>> {
+    if side.mode.is_commit() {
+        return Ok(format!("Subproject commit {}\n", side.id).into_bytes());
+    }
+    let blob = repo.find_blob(side.id).map_err(GitError::gix)?;
+    Ok(blob.detach().data)
+}
+
+/// Whether git treats `content` as binary: a NUL byte near the start
+pub(crate) fn is_binary(content: &[u8]) -> bool {
+    content[..content.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+/// Kind of content, where a change of kind is a type change rather than a
+/// mode change
+fn content_kind(mode: EntryMode) -> EntryKind {
+    match mode.kind() {
+        EntryKind::BlobExecutable => EntryKind::Blob,
+        kind => kind,
+    }
+}
+
+fn octal(mode: EntryMode) -> String {
+    format!("{:06o}", mode.value())
+}
@@ -0,0 +1,128 @@
+//! Progress reporting for long-running worktree operations
+//!
+//! Checkout and hard reset of very large worktrees can take minutes. Both
+//! accept an optional [`ProgressCallback`] that receives periodic updates of
+//! how many index entries have been written so far.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How often progress is reported while an operation is running
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Snapshot of checkout progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckoutProgress {
+    /// Index entries processed so far
+    pub files_processed: usize,
+    /// Total index entries to process
+    pub files_total: usize,
+    /// Bytes written to the worktree so far
+    pub bytes_written: usize,
+}
+
+/// Callback receiving [`CheckoutProgress`] updates
+///
+/// Called from a background thread; keep it cheap (e.g. send to a channel).
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(CheckoutProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Wrap a closure as a progress callback
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(CheckoutProgress) + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    /// Deliver a progress update
+    pub fn report(&self, progress: CheckoutProgress) {
+        (self.0)(progress);
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+/// Counter handed to gix; worker threads increment the shared step directly
+#[derive(Default)]
+struct Counter(gix::progress::StepShared);
+
+impl Counter {
+    fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl gix::progress::Count for Counter {
+    fn set(&self, step: gix::progress::Step) {
+        self.0.store(step, Ordering::Relaxed);
+    }
+
+    fn step(&self) -> gix::progress::Step {
+        self.get()
+    }
+
+    fn inc_by(&self, step: gix::progress::Step) {
+        self.0.fetch_add(step, Ordering::Relaxed);
+    }
+
+    fn counter(&self) -> gix::progress::StepShared {
+        Arc::clone(&self.0)
+    }
+}
+
+/// Run a gix checkout with file and byte counters, reporting to `callback`
+///
+/// `run` receives the file and byte counters to pass to
+/// `gix::worktree::state::checkout`. Without a callback the counters are
+/// still provided but nothing is reported. A final update is always sent
+/// when `run` returns.
+pub(crate) fn with_checkout_progress<T>(
+    callback: Option<&ProgressCallback>,
+    files_total: usize,
+    run: impl FnOnce(&dyn gix::progress::Count, &dyn gix::progress::Count) -> T,
+) -> T {
+    let files = Counter::default();
+    let bytes = Counter::default();
+
+    let Some(callback) = callback else {
+        return run(&files, &bytes);
+    };
+
+    let snapshot = || CheckoutProgress {
+        files_processed: files.get().min(files_total),
+        files_total,
+        bytes_written: bytes.get(),
+    };
+
+    let done = AtomicBool::new(false);
+    let result = std::thread::scope(|scope| {
+        let reporter = scope.spawn(|| {
+            let mut last = None;
+            while !done.load(Ordering::Acquire) {
+                let current = snapshot();
+                if last != Some(current) {
+                    callback.report(current);
+                    last = Some(current);
+                }
+                std::thread::park_timeout(REPORT_INTERVAL);
+            }
+        });
+
+        let result = run(&files, &bytes);
+        done.store(true, Ordering::Release);
+        reporter.thread().unpark();
+        result
+    });
+
+    callback.report(snapshot());
+    result
+}
+
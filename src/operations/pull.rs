@@ -1,6 +1,6 @@
 //! Git pull operations (fetch + merge)
 
-use crate::{GitResult, RepoHandle, FetchOpts, MergeOpts, MergeOutcome};
+use crate::{ConflictedFile, GitError, GitResult, RepoHandle, FetchOpts, MergeOpts, MergeOutcome};
 
 /// Options for pull operation
 #[derive(Debug, Clone)]
@@ -13,26 +13,38 @@ pub struct PullOpts {
     pub fast_forward: bool,
     /// Automatically create merge commit
     pub auto_commit: bool,
+    /// Stash local changes first and reapply them after merging
+    pub autostash: bool,
 }
 
 /// Result of pull operation
 #[derive(Debug, Clone)]
 pub struct PullResult {
-    /// Merge outcome after fetch
-    pub merge_outcome: MergeOutcome,
+    /// Merge outcome after fetch, `None` if the merge stopped on conflicts
+    pub merge_outcome: Option<MergeOutcome>,
+    /// Files the merge could not resolve; HEAD and the working tree are left untouched
+    pub conflicts: Vec<ConflictedFile>,
 }
 
 /// Pull from remote (fetch + merge)
 ///
 /// Note: The branch parameter should be the local branch name, not the remote tracking branch.
 /// This function will construct the remote tracking branch name (e.g., "origin/main").
+///
+/// With `autostash`, local changes are stashed before fetching and reapplied
+/// after the merge.
+///
+/// A conflicting merge is not an error: the result lists the conflicted
+/// files in [`PullResult::conflicts`] and nothing is merged.
 pub async fn pull(repo: RepoHandle, opts: PullOpts) -> GitResult<PullResult> {
+    let autostash = opts.autostash;
+    super::autostash::with_autostash(repo.clone(), autostash, || pull_once(repo, opts)).await
+}
+
+/// Pull without autostash handling
+async fn pull_once(repo: RepoHandle, opts: PullOpts) -> GitResult<PullResult> {
     // Step 1: Fetch from remote
-    let fetch_opts = FetchOpts {
-        remote: opts.remote.clone(),
-        refspecs: vec![],
-        prune: false,
-    };
+    let fetch_opts = FetchOpts::from_remote(opts.remote.clone());
     crate::fetch(repo.clone(), fetch_opts).await?;
 
     // Step 2: Construct the remote tracking branch name
@@ -44,7 +56,21 @@ pub async fn pull(repo: RepoHandle, opts: PullOpts) -> GitResult<PullResult> {
         .no_ff(!opts.fast_forward)
         .commit(opts.auto_commit);
 
-    let merge_outcome = crate::merge(repo, merge_opts).await?;
-
-    Ok(PullResult { merge_outcome })
+    match crate::merge(repo.clone(), merge_opts).await {
+        Ok(merge_outcome) => Ok(PullResult {
+            merge_outcome: Some(merge_outcome),
+            conflicts: Vec::new(),
+        }),
+        Err(GitError::MergeConflict { message, paths }) => {
+            let conflicts = crate::merge_conflicts(repo.clone(), &remote_branch).await?;
+            if conflicts.is_empty() {
+                return Err(GitError::MergeConflict { message, paths });
+            }
+            Ok(PullResult {
+                merge_outcome: None,
+                conflicts,
+            })
+        }
+        Err(e) => Err(e),
+    }
 }
@@ -1,5 +1,10 @@
 //! Remote existence check operations for tags and branches
 
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
 use crate::operations::auth::{self, GitCommandOpts};
 use crate::{GitError, GitResult, RepoHandle};
 
@@ -27,14 +32,14 @@ use crate::{GitError, GitResult, RepoHandle};
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// if check_remote_branch_exists(&repo, "origin", "main").await? {
+/// if check_remote_branch_exists(repo, "origin", "main").await? {
 ///     println!("Branch exists on remote");
 /// }
 /// # Ok(())
 /// # }
 /// ```
 pub async fn check_remote_branch_exists(
-    repo: &RepoHandle,
+    repo: RepoHandle,
     remote: &str,
     branch_name: &str,
 ) -> GitResult<bool> {
@@ -94,14 +99,14 @@ pub async fn check_remote_branch_exists(
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// if check_remote_tag_exists(&repo, "origin", "v1.2.3").await? {
+/// if check_remote_tag_exists(repo, "origin", "v1.2.3").await? {
 ///     println!("Tag exists on remote");
 /// }
 /// # Ok(())
 /// # }
 /// ```
 pub async fn check_remote_tag_exists(
-    repo: &RepoHandle,
+    repo: RepoHandle,
     remote: &str,
     tag_name: &str,
 ) -> GitResult<bool> {
@@ -134,3 +139,181 @@ pub async fn check_remote_tag_exists(
 
     Ok(!String::from_utf8_lossy(&output.stdout).trim().is_empty())
 }
+
+/// Branch and tag listings per (working directory, remote), with when they were taken
+static REMOTE_REFS_CACHE: LazyLock<Mutex<HashMap<(PathBuf, String), CachedRefs>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+struct CachedRefs {
+    listed_at: Instant,
+    refs: Arc<HashSet<String>>,
+}
+
+/// Check which of `refs` exist on a remote, with a single `git ls-remote`
+///
+/// Each entry is either a full ref name (`refs/heads/main`, `refs/tags/v1.2.3`)
+/// or a short name, which exists if a branch or a tag of that name does. Only
+/// branches and tags are listed, so other full refs are always reported
+/// missing.
+///
+/// # Returns
+///
+/// Whether each ref exists, keyed by the name as given.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kodegen_tools_git::{open_repo, remote_refs_exist};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let repo = open_repo("/path/to/repo").await??;
+/// let found = remote_refs_exist(repo, "origin", ["v1.0.0", "v1.1.0", "main"]).await?;
+/// if !found["v1.1.0"] {
+///     println!("v1.1.0 has not been pushed");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn remote_refs_exist<I, S>(
+    repo: RepoHandle,
+    remote: &str,
+    refs: I,
+) -> GitResult<HashMap<String, bool>>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let work_dir = work_dir(&repo)?;
+    let listed = list_remote_refs(work_dir.clone(), remote).await?;
+    let listed = store_listing(work_dir, remote, listed);
+    Ok(lookup(&listed, refs))
+}
+
+/// Like [`remote_refs_exist`], reusing a listing of the remote taken less
+/// than `max_age` ago
+///
+/// Meant for scripts that check many refs in a row, such as a release job
+/// verifying dozens of tags. Refs pushed or deleted by someone else within
+/// `max_age` may be reported wrongly; call [`clear_remote_refs_cache`] after
+/// pushing to see your own changes.
+pub async fn remote_refs_exist_cached<I, S>(
+    repo: RepoHandle,
+    remote: &str,
+    refs: I,
+    max_age: Duration,
+) -> GitResult<HashMap<String, bool>>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let work_dir = work_dir(&repo)?;
+    let key = (work_dir.clone(), remote.to_string());
+    let cached = REMOTE_REFS_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&key).cloned())
+        .filter(|cached| cached.listed_at.elapsed() < max_age);
+
+    let listed = match cached {
+        Some(cached) => cached.refs,
+        None => {
+            let listed = list_remote_refs(work_dir.clone(), remote).await?;
+            store_listing(work_dir, remote, listed)
+        }
+    };
+    Ok(lookup(&listed, refs))
+}
+
+/// Forget cached remote ref listings for all repositories
+pub fn clear_remote_refs_cache() {
+    if let Ok(mut cache) = REMOTE_REFS_CACHE.lock() {
+        cache.clear();
+    }
+}
+
+fn work_dir(repo: &RepoHandle) -> GitResult<PathBuf> {
+    repo.raw()
+        .workdir()
+        .map(std::path::Path::to_path_buf)
+        .ok_or_else(|| GitError::InvalidInput("Repository has no working directory".to_string()))
+}
+
+/// Names of all branches and tags on `remote`
+async fn list_remote_refs(work_dir: PathBuf, remote: &str) -> GitResult<HashSet<String>> {
+    let output = auth::run_git_command(
+        &["ls-remote", "--heads", "--tags", "--refs", remote],
+        GitCommandOpts::new(work_dir).with_timeout(30),
+    )
+    .await?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::InvalidInput(format!("ls-remote failed: {stderr}")));
+    }
+
+    Ok(parse_ls_remote(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Remember `listed` as the current listing of `remote`
+fn store_listing(work_dir: PathBuf, remote: &str, listed: HashSet<String>) -> Arc<HashSet<String>> {
+    let refs = Arc::new(listed);
+    if let Ok(mut cache) = REMOTE_REFS_CACHE.lock() {
+        let entry = CachedRefs {
+            listed_at: Instant::now(),
+            refs: Arc::clone(&refs),
+        };
+        cache.insert((work_dir, remote.to_string()), entry);
+    }
+    refs
+}
+
+/// Ref names from `git ls-remote` output (`<oid>\t<ref>` per line)
+fn parse_ls_remote(stdout: &str) -> HashSet<String> {
+    stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(_, name)| name.trim().to_string())
+        .collect()
+}
+
+fn lookup<I, S>(listed: &HashSet<String>, refs: I) -> HashMap<String, bool>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    refs.into_iter()
+        .map(Into::into)
+        .map(|name| {
+            let exists = if name.starts_with("refs/") {
+                listed.contains(&name)
+            } else {
+                listed.contains(&format!("refs/heads/{name}"))
+                    || listed.contains(&format!("refs/tags/{name}"))
+            };
+            (name, exists)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_short_and_full_names() {
+        let listed = parse_ls_remote(
+            "1111111111111111111111111111111111111111\trefs/heads/main\n\
+             2222222222222222222222222222222222222222\trefs/tags/v1.0.0\n",
+        );
+        let found = lookup(
+            &listed,
+            ["main", "v1.0.0", "refs/tags/v1.0.0", "refs/heads/v1.0.0", "v2.0.0"],
+        );
+        assert!(found["main"]);
+        assert!(found["v1.0.0"]);
+        assert!(found["refs/tags/v1.0.0"]);
+        assert!(!found["refs/heads/v1.0.0"]);
+        assert!(!found["v2.0.0"]);
+    }
+}
@@ -1,9 +1,25 @@
 //! Core push operations
 
+#[cfg(not(feature = "pure-gix"))]
+use std::path::Path;
+
 use super::{PushOpts, PushResult};
+#[cfg(not(feature = "pure-gix"))]
 use crate::operations::auth::{self, GitCommandOpts};
+#[cfg(not(feature = "pure-gix"))]
+use crate::operations::credentials::{self, CachedCredential};
+#[cfg(not(feature = "pure-gix"))]
+use crate::operations::warnings::{OperationWarning, WarningKind};
 use crate::{GitError, GitResult, RepoHandle};
 
+/// Push to remote repository
+#[cfg(feature = "pure-gix")]
+pub async fn push(_repo: RepoHandle, _opts: PushOpts) -> GitResult<PushResult> {
+    Err(GitError::Unsupported(
+        "push requires the git CLI and is unavailable with the pure-gix feature",
+    ))
+}
+
 /// Push to remote repository
 ///
 /// Pushes commits and/or tags to the specified remote using native git CLI.
@@ -25,6 +41,12 @@ use crate::{GitError, GitResult, RepoHandle};
 /// - **SSH**: Ensure keys are loaded in ssh-agent: `ssh-add ~/.ssh/id_rsa`
 /// - **HTTPS**: Configure credential helper: `git config --global credential.helper store`
 ///
+/// With a registered [token source](crate::operations::credentials::TokenSource),
+/// HTTPS pushes authenticate with its token instead of the credential helpers,
+/// and a push rejected for authentication is retried once with a fresh token.
+/// Otherwise HTTPS credentials come from the configured helpers through the
+/// [credential cache](crate::operations::credentials), like clone and fetch.
+///
 /// # Arguments
 ///
 /// * `repo` - Repository handle
@@ -52,114 +74,178 @@ use crate::{GitError, GitResult, RepoHandle};
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// let result = push(&repo, PushOpts {
+/// let result = push(repo, PushOpts {
 ///     remote: "origin".to_string(),
 ///     refspecs: vec![],
 ///     force: false,
 ///     tags: false,
 ///     timeout_secs: None,
+///     check_paths: false,
 /// }).await?;
 /// println!("Pushed {} commits", result.commits_pushed);
 /// # Ok(())
 /// # }
 /// ```
-pub async fn push(repo: &RepoHandle, opts: PushOpts) -> GitResult<PushResult> {
+#[cfg(not(feature = "pure-gix"))]
+pub async fn push(repo: RepoHandle, opts: PushOpts) -> GitResult<PushResult> {
+    if opts.check_paths {
+        ensure_portable_paths(repo.clone(), &opts).await?;
+    }
+
     let work_dir = repo
         .raw()
         .workdir()
         .ok_or_else(|| GitError::InvalidInput("Repository has no working directory".to_string()))?
         .to_path_buf();
 
+    let url = auth::remote_url(repo.raw(), &opts.remote, gix::remote::Direction::Push);
+    let (work_dir, opts) = (&work_dir, &opts);
+    let raw = repo.clone_inner();
+    credentials::with_token(url.clone(), move |credential| {
+        let raw = raw.clone();
+        let url = url.clone();
+        async move {
+            if credential.is_some() {
+                return push_once(work_dir, opts, credential).await;
+            }
+
+            // No token: resolve helpers through the credential cache, like clone and fetch
+            let Some(url) = url else {
+                return push_once(work_dir, opts, None).await;
+            };
+            let lookup_url = url.clone();
+            let credential = tokio::task::spawn_blocking(move || {
+                credentials::helper_credential(&raw, &lookup_url)
+            })
+            .await
+            .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?;
+            let from_helper = credential.is_some();
+            let result = push_once(work_dir, opts, credential).await;
+            if let Err(e) = &result
+                && from_helper
+                && auth::is_auth_error(&e.to_string())
+            {
+                // The remote rejected the cached credential
+                credentials::invalidate_credentials(&url);
+            }
+            result
+        }
+    })
+    .await
+}
+
+/// Fail if a commit about to be pushed has paths that can't be checked out
+/// on macOS or Windows
+#[cfg(not(feature = "pure-gix"))]
+async fn ensure_portable_paths(repo: RepoHandle, opts: &PushOpts) -> GitResult<()> {
+    let mut sources: Vec<&str> = opts
+        .refspecs
+        .iter()
+        .filter_map(|spec| spec.trim_start_matches('+').split(':').next())
+        .filter(|source| !source.is_empty())
+        .collect();
+    // A plain push sends the current branch; `--tags` alone sends only tags
+    if opts.refspecs.is_empty() && !opts.tags {
+        sources.push("HEAD");
+    }
+
+    for source in sources {
+        let issues = crate::detect_case_collisions(repo.clone(), source).await?;
+        if issues.is_empty() {
+            continue;
+        }
+        let paths: Vec<String> = issues
+            .iter()
+            .map(|issue| format!("{} ({})", issue.path, issue.kind))
+            .collect();
+        return Err(GitError::InvalidInput(format!(
+            "Refusing to push {source}: paths can't be checked out on macOS or Windows: {}",
+            paths.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Single push attempt, answering credential requests with `credential`
+#[cfg(not(feature = "pure-gix"))]
+async fn push_once(
+    work_dir: &Path,
+    opts: &PushOpts,
+    credential: Option<CachedCredential>,
+) -> GitResult<PushResult> {
     let PushOpts {
         remote,
         refspecs,
         force,
         tags,
         timeout_secs,
+        check_paths: _,
     } = opts;
 
-    // Build args
-    let mut args: Vec<&str> = vec!["push"];
+    let mut args: Vec<&str> = Vec::new();
+    let mut command_opts =
+        GitCommandOpts::new(work_dir.to_path_buf()).with_timeout(timeout_secs.unwrap_or(300));
+    if let Some(credential) = &credential {
+        args.extend(credentials::CLI_CREDENTIAL_ARGS);
+        command_opts.env.extend(credentials::cli_credential_env(credential));
+    }
 
-    if force {
+    // Build args; porcelain output is stable across git versions and locales
+    args.push("push");
+    args.push("--porcelain");
+
+    if *force {
         args.push("--force");
     }
-    if tags {
+    if *tags {
         args.push("--tags");
     }
 
-    args.push(&remote);
+    args.push(remote);
 
-    // Need owned strings for refspecs to extend lifetime
-    let refspec_strs: Vec<String> = refspecs.clone();
-    for r in &refspec_strs {
+    for r in refspecs {
         args.push(r);
     }
 
-    let output = auth::run_git_command(
-        &args,
-        GitCommandOpts::new(work_dir).with_timeout(timeout_secs.unwrap_or(300)),
-    )
-    .await?;
+    let output = auth::run_git_command(&args, command_opts).await?;
+    let refs = parse_porcelain(&String::from_utf8_lossy(&output.stdout));
 
-    if !output.status.success() {
+    let rejected: Vec<String> = refs
+        .iter()
+        .filter(|r| r.flag == '!')
+        .map(|r| format!("{} {}", r.to, r.summary))
+        .collect();
+    if !output.status.success() || !rejected.is_empty() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(GitError::InvalidInput(format!("Push failed: {stderr}")));
+        let mut message = format!("Push failed: {}", stderr.trim());
+        if !rejected.is_empty() {
+            message.push_str(&format!("\nRejected: {}", rejected.join(", ")));
+        }
+        return Err(GitError::InvalidInput(message));
     }
 
-    // Parse output to estimate what was pushed
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let combined = format!("{stdout}\n{stderr}");
-
-    // Count successful ref updates (branches/tags pushed)
-    // Note: This counts refs, not individual commits, as accurate commit
-    // counting would require additional git commands (git rev-list)
-    let commits_pushed = combined
-        .lines()
-        .filter(|line| {
-            let trimmed = line.trim_start();
-
-            // Must contain the push arrow
-            if !trimmed.contains(" -> ") {
-                return false;
-            }
-
-            // Exclude errors and rejections
-            if trimmed.starts_with('!')
-                || trimmed.starts_with("error:")
-                || trimmed.contains("[rejected]")
-            {
-                return false;
-            }
-
-            // Match successful update patterns:
-            // "   abc123..def456  ref -> ref" (commit range)
-            // " * [new branch]    ref -> ref" (new branch)
-            // " + abc123...def456 ref -> ref" (forced update)
-            trimmed.starts_with(|c: char| c.is_ascii_hexdigit())
-                || trimmed.starts_with("* [new")
-                || trimmed.starts_with('+')
-        })
+    // Count ref updates (branches and tags pushed), not individual commits;
+    // refs already up to date on the remote don't count
+    let updated: Vec<&PorcelainRef> = refs.iter().filter(|r| r.flag != '=').collect();
+    let commits_pushed = updated.len();
+    let tags_pushed = updated
+        .iter()
+        .filter(|r| r.to.starts_with("refs/tags/"))
         .count();
 
-    // Conservative tag counting: indicate whether tags were pushed
-    // without attempting fragile output parsing
-    let tags_pushed = if tags && output.status.success() {
-        // --tags flag used and push succeeded
-        1 // At least some tags were pushed (conservative estimate)
-    } else if output.status.success() && refspecs.iter().any(|r| r.contains("refs/tags/")) {
-        // Specific tag refspecs provided and push succeeded
-        refspecs.iter().filter(|r| r.contains("refs/tags/")).count()
-    } else {
-        0
-    };
-
-    let mut warnings = Vec::new();
-    // Check the force flag directly instead of parsing output (locale-independent)
-    if force {
-        warnings.push("Force push executed".to_string());
-    }
+    // Forced updates are flagged with `+`; a plain --force that only
+    // fast-forwarded replaced nothing
+    let warnings = updated
+        .iter()
+        .filter(|r| r.flag == '+')
+        .map(|r| {
+            OperationWarning::new(
+                WarningKind::ForcePush,
+                "forced update replaced remote history",
+            )
+            .subject(&r.to)
+        })
+        .collect();
 
     Ok(PushResult {
         commits_pushed,
@@ -168,6 +254,40 @@ pub async fn push(repo: &RepoHandle, opts: PushOpts) -> GitResult<PushResult> {
     })
 }
 
+/// One ref line of `git push --porcelain` output
+#[cfg(not(feature = "pure-gix"))]
+#[derive(Debug, PartialEq, Eq)]
+struct PorcelainRef {
+    /// ` ` fast-forward, `+` forced, `-` deleted, `*` new, `=` up to date, `!` rejected
+    flag: char,
+    /// Full name of the remote ref
+    to: String,
+    /// Summary and reason, e.g. `[rejected] (fetch first)`
+    summary: String,
+}
+
+/// Parse the `<flag>\t<from>:<to>\t<summary>` lines of `git push --porcelain`,
+/// skipping the `To <url>` and `Done` lines
+#[cfg(not(feature = "pure-gix"))]
+fn parse_porcelain(stdout: &str) -> Vec<PorcelainRef> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let mut flag = fields.next()?.chars();
+            let (Some(flag), None) = (flag.next(), flag.next()) else {
+                return None;
+            };
+            let (_, to) = fields.next()?.rsplit_once(':')?;
+            Some(PorcelainRef {
+                flag,
+                to: to.to_string(),
+                summary: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
 /// Push current branch to remote
 ///
 /// Convenience function that pushes the current branch to the specified remote.
@@ -185,19 +305,20 @@ pub async fn push(repo: &RepoHandle, opts: PushOpts) -> GitResult<PushResult> {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// push_current_branch(&repo, "origin").await?;
+/// push_current_branch(repo, "origin").await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn push_current_branch(repo: &RepoHandle, remote: &str) -> GitResult<PushResult> {
+pub async fn push_current_branch(repo: RepoHandle, remote: &str) -> GitResult<PushResult> {
     push(
-        repo,
+        repo.clone(),
         PushOpts {
             remote: remote.to_string(),
             refspecs: Vec::new(),
             force: false,
             tags: false,
             timeout_secs: None,
+            check_paths: false,
         },
     )
     .await
@@ -220,20 +341,53 @@ pub async fn push_current_branch(repo: &RepoHandle, remote: &str) -> GitResult<P
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// push_tags(&repo, "origin").await?;
+/// push_tags(repo, "origin").await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn push_tags(repo: &RepoHandle, remote: &str) -> GitResult<PushResult> {
+pub async fn push_tags(repo: RepoHandle, remote: &str) -> GitResult<PushResult> {
     push(
-        repo,
+        repo.clone(),
         PushOpts {
             remote: remote.to_string(),
             refspecs: Vec::new(),
             force: false,
             tags: true,
             timeout_secs: None,
+            check_paths: false,
         },
     )
     .await
 }
+
+#[cfg(all(test, not(feature = "pure-gix")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_porcelain() {
+        let refs = parse_porcelain(
+            "To /tmp/remote.git\n\
+             \x20\trefs/heads/main:refs/heads/main\t1111111..2222222\n\
+             *\trefs/heads/feature:refs/heads/feature\t[new branch]\n\
+             +\trefs/heads/wip:refs/heads/wip\t3333333...4444444 (forced update)\n\
+             -\t:refs/heads/old\t[deleted]\n\
+             =\trefs/tags/v1.0.0:refs/tags/v1.0.0\t[up to date]\n\
+             *\trefs/tags/v1.1.0:refs/tags/v1.1.0\t[new tag]\n\
+             !\trefs/heads/dev:refs/heads/dev\t[rejected] (fetch first)\n\
+             Done\n",
+        );
+        let flags: String = refs.iter().map(|r| r.flag).collect();
+        assert_eq!(flags, " *+-=*!");
+        assert_eq!(refs[0].to, "refs/heads/main");
+        assert_eq!(refs[3].to, "refs/heads/old");
+        assert_eq!(
+            refs[6],
+            PorcelainRef {
+                flag: '!',
+                to: "refs/heads/dev".to_string(),
+                summary: "[rejected] (fetch first)".to_string(),
+            }
+        );
+    }
+}
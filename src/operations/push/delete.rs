@@ -21,11 +21,11 @@ use crate::{GitError, GitResult, RepoHandle};
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// delete_remote_tag(&repo, "origin", "v1.0.0").await?;
+/// delete_remote_tag(repo, "origin", "v1.0.0").await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn delete_remote_tag(repo: &RepoHandle, remote: &str, tag_name: &str) -> GitResult<()> {
+pub async fn delete_remote_tag(repo: RepoHandle, remote: &str, tag_name: &str) -> GitResult<()> {
     let work_dir = repo
         .raw()
         .workdir()
@@ -71,12 +71,12 @@ pub async fn delete_remote_tag(repo: &RepoHandle, remote: &str, tag_name: &str)
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// delete_remote_branch(&repo, "origin", "feature-branch").await?;
+/// delete_remote_branch(repo, "origin", "feature-branch").await?;
 /// # Ok(())
 /// # }
 /// ```
 pub async fn delete_remote_branch(
-    repo: &RepoHandle,
+    repo: RepoHandle,
     remote: &str,
     branch_name: &str,
 ) -> GitResult<()> {
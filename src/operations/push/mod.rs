@@ -3,7 +3,8 @@
 //! Provides functionality for pushing commits and tags to remote repositories.
 //! Uses native git CLI since gix doesn't yet support push operations.
 //!
-//! **Dependency**: Requires git to be installed and available in PATH.
+//! **Dependency**: Requires git to be installed and available in PATH. With the
+//! `pure-gix` feature, push operations return [`GitError::Unsupported`](crate::GitError).
 //!
 //! # Authentication
 //!
@@ -23,11 +24,12 @@
 //! # Start SSH agent and add key
 //! eval "$(ssh-agent -s)"
 //! ssh-add ~/.ssh/id_rsa
-//!
-//! # Or disable strict host checking for CI
-//! export GIT_SSH_COMMAND="ssh -o StrictHostKeyChecking=no"
 //! ```
 //!
+//! Host key verification is configured with
+//! [`auth::set_ssh_options`](crate::operations::auth::set_ssh_options) (strict,
+//! accept-new, or a custom known_hosts file) rather than by exporting `GIT_SSH_COMMAND`.
+//!
 //! ## HTTPS
 //!
 //! HTTPS authentication requires credential configuration:
@@ -48,10 +50,10 @@
 //!
 //! **Recommended practices:**
 //! ```rust
-//! use std::env;
+//! use kodegen_tools_git::operations::auth::{self, HostKeyPolicy, SshOptions};
 //!
-//! // For SSH in CI/CD environments
-//! env::set_var("GIT_SSH_COMMAND", "ssh -o StrictHostKeyChecking=no");
+//! // For SSH in CI/CD environments: trust new hosts, reject changed keys
+//! auth::set_ssh_options(SshOptions::new().host_key_policy(HostKeyPolicy::AcceptNew));
 //!
 //! // For HTTPS with credential helper
 //! // Run: git config --global credential.helper store
@@ -120,7 +122,10 @@ mod check;
 
 pub use core::{push, push_current_branch, push_tags};
 pub use delete::{delete_remote_tag, delete_remote_branch};
-pub use check::{check_remote_branch_exists, check_remote_tag_exists};
+pub use check::{
+    check_remote_branch_exists, check_remote_tag_exists, clear_remote_refs_cache, remote_refs_exist,
+    remote_refs_exist_cached,
+};
 
 /// Options for push operation
 #[derive(Debug, Clone)]
@@ -135,6 +140,9 @@ pub struct PushOpts {
     pub tags: bool,
     /// Timeout in seconds (default: 300)
     pub timeout_secs: Option<u64>,
+    /// Refuse to push commits with paths that collide or are invalid on
+    /// macOS or Windows (see [`detect_case_collisions`](crate::detect_case_collisions))
+    pub check_paths: bool,
 }
 
 impl Default for PushOpts {
@@ -145,6 +153,7 @@ impl Default for PushOpts {
             force: false,
             tags: false,
             timeout_secs: None,
+            check_paths: false,
         }
     }
 }
@@ -158,13 +167,10 @@ pub struct PushResult {
     /// For example, pushing a branch with 5 commits counts as 1 ref update.
     pub commits_pushed: usize,
 
-    /// Number of tags pushed (conservative estimate)
-    ///
-    /// **Note:** Returns 1 when `--tags` is used and push succeeds, or counts
-    /// the number of `refs/tags/*` refspecs provided. Does not parse git output
-    /// for exact count due to fragility. Sufficient for most telemetry use cases.
+    /// Number of tags pushed, as reported by `git push --porcelain`; tags
+    /// already on the remote don't count
     pub tags_pushed: usize,
 
     /// Any warnings or messages
-    pub warnings: Vec<String>,
+    pub warnings: Vec<crate::OperationWarning>,
 }
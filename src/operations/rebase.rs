@@ -0,0 +1,332 @@
+//! Non-interactive rebase of a branch onto a new base.
+//!
+//! [`rebase`] replays the commits of a branch that aren't in an upstream on
+//! top of another commit, like `git rebase --onto <onto> <upstream> <branch>`.
+//! Each commit is a three-way merge of trees done by gix, made on objects
+//! only, so the branch doesn't need to be checked out; when it is checked
+//! out in this worktree, the index and working tree follow along. A conflict
+//! leaves the branch as it was and is reported as
+//! [`GitError::MergeConflict`], as for [`cherry_pick`](super::cherry_pick).
+
+use std::collections::HashMap;
+
+use gix::merge::blob::builtin_driver::text::Labels;
+use gix::merge::tree::TreatAsUnresolved;
+use gix::refs::transaction::PreviousValue;
+
+use super::compare::resolve_commit;
+use super::conflicts::{ConflictedFile, conflicted_files};
+use super::update_branch::{committer, tree_of};
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Options for [`rebase`] with builder pattern.
+#[derive(Debug, Clone)]
+pub struct RebaseOpts {
+    /// Commit to replay onto (any revision).
+    pub onto: String,
+    /// Commits of the branch reachable from this revision aren't replayed;
+    /// `None` uses `onto`, as `git rebase <onto>` does.
+    pub upstream: Option<String>,
+    /// Local branch to rebase; `None` uses the branch checked out here.
+    pub branch: Option<String>,
+    /// Stash local changes while rebasing the branch checked out here.
+    pub autostash: bool,
+}
+
+impl RebaseOpts {
+    /// Rebase the checked-out branch onto `onto`.
+    #[inline]
+    #[must_use]
+    pub fn new(onto: impl Into<String>) -> Self {
+        Self {
+            onto: onto.into(),
+            upstream: None,
+            branch: None,
+            autostash: false,
+        }
+    }
+
+    /// Only replay the commits not reachable from `upstream`, like the
+    /// `<upstream>` of `git rebase --onto <onto> <upstream>`.
+    #[inline]
+    #[must_use]
+    pub fn upstream(mut self, upstream: impl Into<String>) -> Self {
+        self.upstream = Some(upstream.into());
+        self
+    }
+
+    /// Rebase `branch` instead of the branch checked out here.
+    #[inline]
+    #[must_use]
+    pub fn branch(mut self, branch: impl Into<String>) -> Self {
+        self.branch = Some(branch.into());
+        self
+    }
+
+    /// Stash local changes around the rebase, see [`MergeOpts::autostash`](crate::MergeOpts::autostash).
+    #[inline]
+    #[must_use]
+    pub fn autostash(mut self, yes: bool) -> Self {
+        self.autostash = yes;
+        self
+    }
+}
+
+/// Result of [`rebase`].
+#[derive(Debug, Clone)]
+pub struct RebaseResult {
+    /// Full name of the branch, e.g. `refs/heads/feature`.
+    pub branch_ref: String,
+    /// The commit the branch was replayed onto.
+    pub onto: CommitId,
+    /// Tip of the branch before the rebase.
+    pub old_tip: CommitId,
+    /// Tip after the rebase; `old_tip` if the branch was up to date.
+    pub new_tip: CommitId,
+    /// Commits rewritten, as (original, replayed), oldest first.
+    pub rewritten: Vec<(CommitId, CommitId)>,
+    /// Commits dropped because `onto` already has their changes; merge
+    /// commits are dropped too, as `git rebase` does.
+    pub dropped: Vec<CommitId>,
+    /// Whether the index and working tree were updated because the branch is
+    /// checked out here.
+    pub worktree_updated: bool,
+}
+
+/// Replay the commits of a branch missing from the upstream onto `opts.onto`.
+///
+/// Commits whose parent doesn't change are kept as they are, so a branch
+/// already on `onto` is left alone. Fails with [`GitError::MergeConflict`],
+/// listing the conflicting paths, if a commit doesn't apply; the branch is
+/// unchanged then. Fails as well for a branch checked out in another
+/// worktree, for a branch checked out here with local changes unless
+/// `autostash` is set, and for a branch sharing no history with the upstream.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kodegen_tools_git::{RebaseOpts, open_repo, rebase};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let repo = open_repo("/path/to/repo").await??;
+/// // git rebase --onto main release feature
+/// let result = rebase(
+///     repo,
+///     RebaseOpts::new("main").upstream("release").branch("feature"),
+/// )
+/// .await?;
+/// println!("rewrote {} commits", result.rewritten.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn rebase(repo: RepoHandle, opts: RebaseOpts) -> GitResult<RebaseResult> {
+    let head_branch = repo
+        .raw()
+        .head_name()
+        .map_err(GitError::gix)?
+        .map(|name| name.shorten().to_string());
+    let branch = match (&opts.branch, head_branch.as_ref()) {
+        (Some(branch), _) => branch.clone(),
+        (None, Some(head)) => head.clone(),
+        (None, None) => {
+            return Err(GitError::InvalidInput(
+                "HEAD is detached; name the branch to rebase".to_string(),
+            ));
+        }
+    };
+    let checked_out_here = head_branch.as_deref() == Some(branch.as_str());
+    let autostash = opts.autostash && checked_out_here;
+
+    let repo_inner = repo.clone_inner();
+    super::autostash::with_autostash(repo, autostash, || async move {
+        tokio::task::spawn_blocking(move || {
+            rebase_blocking(&repo_inner, &branch, &opts, checked_out_here)
+        })
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+    })
+    .await
+}
+
+fn rebase_blocking(
+    repo: &gix::Repository,
+    branch: &str,
+    opts: &RebaseOpts,
+    checked_out_here: bool,
+) -> GitResult<RebaseResult> {
+    let branch_ref = format!("refs/heads/{branch}");
+    let old_tip = repo
+        .find_reference(branch_ref.as_str())
+        .map_err(|_| GitError::BranchNotFound(branch.to_string()))?
+        .into_fully_peeled_id()
+        .map_err(GitError::gix)?
+        .detach();
+    let onto = resolve_commit(repo, &opts.onto)?;
+    let upstream_name = opts.upstream.as_deref().unwrap_or(&opts.onto);
+    let upstream = resolve_commit(repo, upstream_name)?;
+
+    // Moving a branch checked out elsewhere would leave that worktree stale
+    let workdir = if checked_out_here {
+        if super::fast_status::is_dirty_fast(repo)? {
+            return Err(GitError::InvalidInput(format!(
+                "Cannot rebase checked-out branch '{branch}' with local changes; use autostash"
+            )));
+        }
+        repo.workdir().map(std::path::Path::to_path_buf)
+    } else {
+        super::worktree::check_branch_not_in_use(repo, branch)?;
+        None
+    };
+    if old_tip != upstream {
+        repo.merge_base(old_tip, upstream).map_err(|_| {
+            GitError::InvalidInput(format!("'{branch}' and '{upstream_name}' share no history"))
+        })?;
+    }
+
+    let replayed = match replay(repo, old_tip, upstream, onto, &opts.onto)? {
+        Ok(replayed) => replayed,
+        Err(conflict) => {
+            let short = conflict.commit.to_hex_with_len(7).to_string();
+            let paths: Vec<String> = conflict
+                .conflicts
+                .into_iter()
+                .map(|file| file.path)
+                .collect();
+            return Err(GitError::MergeConflict {
+                message: format!(
+                    "Rebasing {short} onto {} conflicts in: {}",
+                    opts.onto,
+                    paths.join(", ")
+                ),
+                paths,
+            });
+        }
+    };
+
+    let mut result = RebaseResult {
+        branch_ref,
+        onto,
+        old_tip,
+        new_tip: replayed.tip,
+        rewritten: replayed.rewritten,
+        dropped: replayed.dropped,
+        worktree_updated: false,
+    };
+    if result.new_tip == old_tip {
+        return Ok(result);
+    }
+    // Update the worktree first, so a refused checkout leaves the branch alone
+    if let Some(workdir) = &workdir {
+        let tree = tree_of(repo, result.new_tip)?;
+        super::merge::sync_worktree_to_tree(repo, workdir, tree, "Rebase")?;
+        result.worktree_updated = true;
+    }
+    repo.reference(
+        result.branch_ref.as_str(),
+        result.new_tip,
+        PreviousValue::MustExistAndMatch(gix::refs::Target::Object(old_tip)),
+        format!("rebase (finish): {} onto {onto}", result.branch_ref),
+    )
+    .map_err(GitError::gix)?;
+    Ok(result)
+}
+
+/// Commits [`replay`] wrote, and the new tip
+pub(crate) struct Replayed {
+    pub(crate) tip: CommitId,
+    /// (original, replayed), oldest first
+    pub(crate) rewritten: Vec<(CommitId, CommitId)>,
+    pub(crate) dropped: Vec<CommitId>,
+}
+
+/// The commit [`replay`] stopped at, and its conflicts
+pub(crate) struct ReplayConflict {
+    pub(crate) commit: CommitId,
+    pub(crate) conflicts: Vec<ConflictedFile>,
+}
+
+/// Replay the commits of `tip` missing from `upstream` on top of `onto`,
+/// writing objects only; `label` names `onto` in conflict markers
+///
+/// Merge commits, and commits whose change `onto` already has, are dropped.
+/// Commits are kept as they are while their parent doesn't change.
+pub(crate) fn replay(
+    repo: &gix::Repository,
+    tip: CommitId,
+    upstream: CommitId,
+    onto: CommitId,
+    label: &str,
+) -> GitResult<Result<Replayed, ReplayConflict>> {
+    let mut commits: HashMap<CommitId, gix::objs::Commit> = HashMap::new();
+    let walk = repo
+        .rev_walk([tip])
+        .with_hidden([upstream])
+        .all()
+        .map_err(GitError::gix)?;
+    for info in walk {
+        let info = info.map_err(GitError::gix)?;
+        let commit = repo
+            .find_commit(info.id)
+            .map_err(GitError::gix)?
+            .decode()
+            .map_err(GitError::gix)?
+            .into_owned();
+        commits.insert(info.id, commit);
+    }
+
+    let committer = committer(repo)?;
+    let mut replayed = Replayed {
+        tip: onto,
+        rewritten: Vec::new(),
+        dropped: Vec::new(),
+    };
+    let mut new_tree = tree_of(repo, onto)?;
+    for id in super::rewrite::parents_first(&commits) {
+        let original = &commits[&id];
+        if original.parents.len() != 1 {
+            replayed.dropped.push(id);
+            continue;
+        }
+        if original.parents[0] == replayed.tip {
+            replayed.tip = id;
+            new_tree = original.tree;
+            continue;
+        }
+
+        let parent_tree = tree_of(repo, original.parents[0])?;
+        let short = id.to_hex_with_len(7).to_string();
+        let ancestor = format!("parent of {short}");
+        let labels = Labels {
+            ancestor: Some(ancestor.as_str().into()),
+            current: Some(label.into()),
+            other: Some(short.as_str().into()),
+        };
+        let options = repo.tree_merge_options().map_err(GitError::gix)?;
+        let mut outcome = repo
+            .merge_trees(parent_tree, new_tree, original.tree, labels, options)
+            .map_err(GitError::gix)?;
+        if outcome.has_unresolved_conflicts(TreatAsUnresolved::default()) {
+            return Ok(Err(ReplayConflict {
+                commit: id,
+                conflicts: conflicted_files(&outcome),
+            }));
+        }
+        let tree = outcome.tree.write().map_err(GitError::gix)?.detach();
+        if tree == new_tree {
+            replayed.dropped.push(id);
+            continue;
+        }
+
+        let mut commit = original.clone();
+        commit.tree = tree;
+        commit.parents = vec![replayed.tip].into();
+        commit.committer = committer.clone();
+        commit
+            .extra_headers
+            .retain(|(name, _)| name != "gpgsig" && name != "gpgsig-sha256");
+        replayed.tip = repo.write_object(&commit).map_err(GitError::gix)?.detach();
+        new_tree = tree;
+        replayed.rewritten.push((id, replayed.tip));
+    }
+    Ok(Ok(replayed))
+}
@@ -0,0 +1,205 @@
+//! Named repositories for servers juggling several checkouts
+//!
+//! An agent working across "backend" and "infra" shouldn't have to repeat
+//! absolute paths in every call. The registry maps such logical names to a
+//! repository path and, optionally, the URL it is cloned from. Tools resolve
+//! their `path` argument through [`resolve_repo_path`], so a registered name
+//! can be passed wherever a path is expected.
+//!
+//! The registry is process-wide: a server registers its repositories once
+//! (at startup or through the registry tool) and every tool sees them. It
+//! lives here rather than in `Managers`, which belongs to
+//! `kodegen_server_http`.
+//!
+//! Tools take no separate `repo` argument: the published tool schemas only
+//! have `path`, so a registered name is passed as the `path`. Names can't
+//! contain path separators, and a name that is also a directory is refused
+//! unless both are the same repository (see [`resolve_repo_path`]).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+
+use tokio::sync::Mutex;
+
+use super::clone::{CloneOpts, clone_repo};
+use crate::{GitError, GitResult};
+
+/// Registered repositories by name
+static REGISTRY: LazyLock<RwLock<HashMap<String, RepoAlias>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Held while cloning a registered repository, so concurrent first uses clone once
+static CLONE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A logical name for a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoAlias {
+    /// Name tools accept in place of a path, e.g. `backend`.
+    pub name: String,
+    /// Working directory (or git dir, for bare repositories).
+    pub path: PathBuf,
+    /// URL to clone from when `path` does not exist yet.
+    pub url: Option<String>,
+}
+
+impl RepoAlias {
+    /// Whether the repository has been cloned (or otherwise exists) at `path`.
+    #[must_use]
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+}
+
+/// Register `name` for the repository at `path`, replacing any previous entry.
+///
+/// With a `url`, the repository is cloned into `path` the first time it is
+/// used; without one, `path` must already hold a repository by then. Names
+/// may not be empty or contain path separators, so they never shadow a
+/// relative path such as `services/api`. A name that is also a directory in
+/// the current directory is refused by [`resolve_repo_path`] unless both are
+/// the same repository; pass `./backend` to mean the directory.
+pub fn register_repo(
+    name: impl Into<String>,
+    path: impl Into<PathBuf>,
+    url: Option<String>,
+) -> GitResult<RepoAlias> {
+    let name = name.into();
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(GitError::InvalidInput(format!(
+            "Invalid repository name '{name}': use a plain name such as 'backend'"
+        )));
+    }
+
+    let alias = RepoAlias {
+        name: name.clone(),
+        path: path.into(),
+        url,
+    };
+    REGISTRY
+        .write()
+        .map_err(|_| GitError::InvalidInput("Repository registry is poisoned".to_string()))?
+        .insert(name, alias.clone());
+    Ok(alias)
+}
+
+/// Remove `name` from the registry, returning its entry if it was registered.
+///
+/// The repository itself is left on disk.
+pub fn unregister_repo(name: &str) -> Option<RepoAlias> {
+    REGISTRY.write().ok()?.remove(name)
+}
+
+/// All registered repositories, sorted by name.
+#[must_use]
+pub fn registered_repos() -> Vec<RepoAlias> {
+    let mut repos: Vec<RepoAlias> = REGISTRY
+        .read()
+        .map(|registry| registry.values().cloned().collect())
+        .unwrap_or_default();
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+    repos
+}
+
+/// The registry entry for `name`, if any.
+#[must_use]
+pub fn lookup_repo(name: &str) -> Option<RepoAlias> {
+    REGISTRY.read().ok()?.get(name).cloned()
+}
+
+/// Resolve a tool's `path` argument to a filesystem path.
+///
+/// A registered name resolves to its repository path, cloning the repository
+/// first if it has a URL and does not exist yet. Anything else is returned
+/// unchanged. A registered name that is also a different directory relative
+/// to the current directory is ambiguous and fails.
+pub async fn resolve_repo_path(path_or_name: impl AsRef<Path>) -> GitResult<PathBuf> {
+    let path_or_name = path_or_name.as_ref();
+    let Some(alias) = path_or_name.to_str().and_then(lookup_repo) else {
+        return Ok(path_or_name.to_path_buf());
+    };
+
+    if path_or_name.is_dir() && !same_dir(path_or_name, &alias.path) {
+        return Err(GitError::InvalidInput(format!(
+            "'{}' is both a registered repository at {} and a directory here; \
+             pass './{}' for the directory or the registered path",
+            alias.name,
+            alias.path.display(),
+            alias.name
+        )));
+    }
+
+    if alias.exists() {
+        return Ok(alias.path);
+    }
+    let Some(url) = alias.url else {
+        return Err(GitError::InvalidInput(format!(
+            "Repository '{}' is registered at {}, which does not exist",
+            alias.name,
+            alias.path.display()
+        )));
+    };
+
+    let _guard = CLONE_LOCK.lock().await;
+    // Another caller may have cloned it while we waited
+    if !alias.path.exists() {
+        if let Some(parent) = alias.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        clone_repo(CloneOpts::new(url, &alias.path))
+            .await
+            .map_err(|_| GitError::ChannelClosed)??;
+    }
+    Ok(alias.path)
+}
+
+/// Whether `a` and `b` are the same existing directory
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_rejects_path_like_names() {
+        for name in ["", ".", "..", "a/b", "a\\b"] {
+            assert!(register_repo(name, "/tmp/repo", None).is_err(), "{name:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_registered_and_plain_paths() {
+        let dir = tempfile::TempDir::new().unwrap();
+        register_repo("registry-test-backend", dir.path(), None).unwrap();
+
+        let resolved = resolve_repo_path("registry-test-backend").await.unwrap();
+        assert_eq!(resolved, dir.path());
+        // Unregistered names and paths pass through untouched
+        let plain = resolve_repo_path("some/relative/path").await.unwrap();
+        assert_eq!(plain, Path::new("some/relative/path"));
+
+        assert!(unregister_repo("registry-test-backend").is_some());
+        assert!(lookup_repo("registry-test-backend").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_refuses_name_shadowing_a_directory() {
+        // Tests run from the crate root, which has a `src` directory
+        let dir = tempfile::TempDir::new().unwrap();
+        register_repo("src", dir.path(), None).unwrap();
+        assert!(resolve_repo_path("src").await.is_err());
+        assert_eq!(resolve_repo_path("./src").await.unwrap(), Path::new("./src"));
+
+        // Registering the directory itself is not ambiguous
+        register_repo("src", "src", None).unwrap();
+        assert_eq!(resolve_repo_path("src").await.unwrap(), Path::new("src"));
+        unregister_repo("src");
+    }
+}
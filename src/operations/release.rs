@@ -0,0 +1,404 @@
+//! Release tagging.
+//!
+//! [`cut`] puts the steps of tagging a release into one call: check that the
+//! working tree is clean, check that the tag isn't taken locally or on the
+//! remote, create an annotated tag, push it, and collect the commits since the
+//! previous release as a changelog. Running it again after a partial failure
+//! picks up where it stopped: a tag already at the release commit is reused and
+//! a tag the remote already has is not pushed again.
+
+use std::collections::HashMap;
+
+use gix::bstr::ByteSlice;
+
+use super::auth::{self, GitCommandOpts};
+use super::compare::{commit_info, resolve_commit};
+use super::push::PushOpts;
+use super::tag::{TagInfo, TagOpts, create_tag, tag_info};
+use crate::{CommitId, CommitInfo, GitError, GitResult, RepoHandle, Signature};
+
+/// Options for [`cut`]
+#[derive(Debug, Clone)]
+pub struct ReleaseOpts {
+    /// Commit to release (defaults to `HEAD`)
+    pub target: Option<String>,
+    /// Tag message (defaults to `Release <version>`)
+    pub message: Option<String>,
+    /// Sign the tag with the configured GPG, SSH or X.509 key (`git tag -s`)
+    pub sign: bool,
+    /// Remote checked for the tag and pushed to (defaults to `origin`)
+    pub remote: String,
+    /// Push the tag; when `false` the remote is not contacted at all
+    pub push: bool,
+    /// Tagger (defaults to `user.name` and `user.email`)
+    pub tagger: Option<Signature>,
+}
+
+impl Default for ReleaseOpts {
+    fn default() -> Self {
+        Self {
+            target: None,
+            message: None,
+            sign: false,
+            remote: "origin".to_string(),
+            push: true,
+            tagger: None,
+        }
+    }
+}
+
+impl ReleaseOpts {
+    /// Release `HEAD` and push the tag to `origin`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Release this revision instead of `HEAD`.
+    #[inline]
+    #[must_use]
+    pub fn target<S: Into<String>>(mut self, target: S) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Use this tag message.
+    #[inline]
+    #[must_use]
+    pub fn message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Sign the tag.
+    #[inline]
+    #[must_use]
+    pub fn sign(mut self, yes: bool) -> Self {
+        self.sign = yes;
+        self
+    }
+
+    /// Check and push to this remote.
+    #[inline]
+    #[must_use]
+    pub fn remote<S: Into<String>>(mut self, remote: S) -> Self {
+        self.remote = remote.into();
+        self
+    }
+
+    /// Whether to push the tag.
+    #[inline]
+    #[must_use]
+    pub fn push(mut self, yes: bool) -> Self {
+        self.push = yes;
+        self
+    }
+
+    /// Set the tagger.
+    #[inline]
+    #[must_use]
+    pub fn tagger(mut self, tagger: Signature) -> Self {
+        self.tagger = Some(tagger);
+        self
+    }
+}
+
+/// Result of [`cut`]
+#[derive(Debug, Clone)]
+pub struct ReleaseResult {
+    /// The release tag
+    pub tag: TagInfo,
+    /// Nearest tag on an ancestor of the release commit
+    pub previous_tag: Option<String>,
+    /// Commits since `previous_tag`, newest first, merges left out
+    pub commits: Vec<CommitInfo>,
+    /// Markdown list of `commits`, headed by the version
+    pub changelog: String,
+    /// Whether the tag was created, rather than found at the release commit
+    pub created: bool,
+    /// Whether the tag was pushed, rather than found on the remote or not pushed
+    pub pushed: bool,
+}
+
+/// Tag `version` as a release and push the tag.
+///
+/// Fails with [`GitError::InvalidInput`] if the working tree has uncommitted
+/// changes, or if a tag named `version` points at another commit locally or
+/// on the remote; nothing is created or pushed then. Signing and pushing use
+/// the git CLI and fail with [`GitError::Unsupported`] under `pure-gix`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kodegen_tools_git::{open_repo, operations::release::{self, ReleaseOpts}};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let repo = open_repo("/path/to/repo").await??;
+/// let release = release::cut(repo, "v1.2.0", ReleaseOpts::new()).await?;
+/// println!("{}", release.changelog);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn cut(repo: RepoHandle, version: &str, opts: ReleaseOpts) -> GitResult<ReleaseResult> {
+    let tag_ref = format!("refs/tags/{version}");
+    if version.is_empty() || gix::refs::FullName::try_from(tag_ref.as_str()).is_err() {
+        return Err(GitError::InvalidInput(format!(
+            "Invalid release version '{version}'"
+        )));
+    }
+
+    let repo_inner = repo.clone_inner();
+    let (version_owned, target_rev) = (version.to_string(), opts.target.clone());
+    let plan = tokio::task::spawn_blocking(move || {
+        plan(&repo_inner, &version_owned, target_rev.as_deref())
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    let mut on_remote = false;
+    if opts.push
+        && let Some(remote_target) = remote_tag_target(repo.clone(), &opts.remote, version).await?
+    {
+        if remote_target != plan.target {
+            return Err(GitError::InvalidInput(format!(
+                "Tag '{version}' already exists on '{}' at {remote_target}",
+                opts.remote
+            )));
+        }
+        on_remote = true;
+    }
+
+    let created = !plan.exists;
+    if created {
+        let message = opts
+            .message
+            .clone()
+            .unwrap_or_else(|| format!("Release {version}"));
+        if opts.sign {
+            signed_tag(repo.clone(), version, plan.target, &message, opts.tagger.as_ref()).await?;
+        } else {
+            let mut tag_opts = TagOpts::new(version)
+                .message(message)
+                .target(plan.target.to_string());
+            tag_opts.tagger = opts.tagger.clone();
+            create_tag(repo.clone(), tag_opts).await?;
+        }
+    }
+
+    let pushed = opts.push && !on_remote;
+    if pushed {
+        let push_opts = PushOpts {
+            remote: opts.remote.clone(),
+            refspecs: vec![format!("{tag_ref}:{tag_ref}")],
+            ..PushOpts::default()
+        };
+        super::push::push(repo.clone(), push_opts).await?;
+    }
+
+    let repo_inner = repo.clone_inner();
+    let tag = tokio::task::spawn_blocking(move || {
+        let mut reference = repo_inner
+            .find_reference(tag_ref.as_str())
+            .map_err(GitError::gix)?;
+        tag_info(&repo_inner, &mut reference)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    Ok(ReleaseResult {
+        tag,
+        previous_tag: plan.previous_tag,
+        commits: plan.commits,
+        changelog: plan.changelog,
+        created,
+        pushed,
+    })
+}
+
+/// What [`cut`] found out before changing anything
+struct Plan {
+    target: CommitId,
+    /// Whether the tag already exists at `target`
+    exists: bool,
+    previous_tag: Option<String>,
+    commits: Vec<CommitInfo>,
+    changelog: String,
+}
+
+fn plan(repo: &gix::Repository, version: &str, target: Option<&str>) -> GitResult<Plan> {
+    if super::fast_status::is_dirty_fast(repo)? {
+        return Err(GitError::InvalidInput(
+            "Working tree has uncommitted changes; commit or stash them before a release"
+                .to_string(),
+        ));
+    }
+    let target = resolve_commit(repo, target.unwrap_or("HEAD"))?;
+
+    let tags = tags_by_commit(repo)?;
+    let existing = tags
+        .iter()
+        .find(|(_, names)| names.iter().any(|name| name == version))
+        .map(|(id, _)| *id);
+    match existing {
+        Some(id) if id != target => {
+            return Err(GitError::InvalidInput(format!(
+                "Tag '{version}' already exists at {id}"
+            )));
+        }
+        Some(_) => {}
+        None => {
+            let tag_ref = format!("refs/tags/{version}");
+            // Tags of trees or blobs aren't in `tags`
+            if repo
+                .try_find_reference(tag_ref.as_str())
+                .map_err(GitError::gix)?
+                .is_some()
+            {
+                return Err(GitError::InvalidInput(format!(
+                    "Tag '{version}' already exists and does not point to a commit"
+                )));
+            }
+        }
+    }
+
+    // Walk back from the release to the nearest tagged ancestor
+    let boundary = super::shallow::shallow_boundary(repo)?;
+    let walk = repo.rev_walk([target]).all().map_err(GitError::gix)?;
+    let mut previous = None;
+    for info in walk {
+        let info = info.map_err(GitError::gix)?;
+        if info.id != target
+            && let Some(names) = tags.get(&info.id)
+        {
+            previous = Some((info.id, names[0].clone()));
+            break;
+        }
+    }
+    let mut walk = repo.rev_walk([target]);
+    if let Some((id, _)) = previous {
+        walk = walk.with_hidden([id]);
+    }
+    let mut commits = Vec::new();
+    for info in walk.all().map_err(GitError::gix)? {
+        let info = info.map_err(GitError::gix)?;
+        let commit = commit_info(repo, info.id, &boundary)?;
+        if commit.parents.len() <= 1 {
+            commits.push(commit);
+        }
+    }
+
+    let mut changelog = format!("## {version}\n\n");
+    for commit in &commits {
+        let id = commit.id.to_hex_with_len(7);
+        changelog.push_str(&format!("- {} ({id})\n", commit.summary));
+    }
+
+    Ok(Plan {
+        target,
+        exists: existing.is_some(),
+        previous_tag: previous.map(|(_, name)| name),
+        commits,
+        changelog,
+    })
+}
+
+/// Names of the tags on each commit, sorted
+fn tags_by_commit(repo: &gix::Repository) -> GitResult<HashMap<CommitId, Vec<String>>> {
+    let mut tags: HashMap<CommitId, Vec<String>> = HashMap::new();
+    let references = repo.references().map_err(GitError::gix)?;
+    for reference in references.tags().map_err(GitError::gix)? {
+        let mut reference = reference.map_err(GitError::gix)?;
+        // Tags of trees or blobs don't name a release
+        let Ok(commit) = reference.peel_to_commit() else {
+            continue;
+        };
+        tags.entry(commit.id)
+            .or_default()
+            .push(reference.name().shorten().to_str_lossy().into_owned());
+    }
+    for names in tags.values_mut() {
+        names.sort();
+    }
+    Ok(tags)
+}
+
+/// Commit the tag `version` points at on `remote`, if it is there
+async fn remote_tag_target(
+    repo: RepoHandle,
+    remote: &str,
+    version: &str,
+) -> GitResult<Option<CommitId>> {
+    let work_dir = workdir(&repo)?;
+    let refspec = format!("refs/tags/{version}");
+    // Annotated tags are listed twice, the second time peeled with `^{}`,
+    // which only matches a pattern naming it
+    let peeled = format!("{refspec}^{{}}");
+    let output = auth::run_git_command(
+        &["ls-remote", "--tags", remote, &refspec, &peeled],
+        GitCommandOpts::new(work_dir).with_timeout(30),
+    )
+    .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::InvalidInput(format!(
+            "ls-remote failed: {stderr}"
+        )));
+    }
+
+    let mut found = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((id, name)) = line.split_once('\t') else {
+            continue;
+        };
+        let id = CommitId::from_hex(id.as_bytes())
+            .map_err(|e| GitError::Parse(format!("Invalid object id from ls-remote: {e}")))?;
+        if name == peeled {
+            return Ok(Some(id));
+        }
+        if name == refspec {
+            found = Some(id);
+        }
+    }
+    Ok(found)
+}
+
+/// Create a signed annotated tag with `git tag -s`
+async fn signed_tag(
+    repo: RepoHandle,
+    version: &str,
+    target: CommitId,
+    message: &str,
+    tagger: Option<&Signature>,
+) -> GitResult<()> {
+    let mut opts = GitCommandOpts::new(workdir(&repo)?);
+    if let Some(tagger) = tagger {
+        // git takes the tagger from the committer identity
+        opts.env = vec![
+            ("GIT_COMMITTER_NAME".to_string(), tagger.name.clone()),
+            ("GIT_COMMITTER_EMAIL".to_string(), tagger.email.clone()),
+            (
+                "GIT_COMMITTER_DATE".to_string(),
+                tagger.git_time().to_string(),
+            ),
+        ];
+    }
+    let target = target.to_string();
+    let output =
+        auth::run_git_command(&["tag", "-s", "-m", message, version, &target], opts).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::InvalidInput(format!(
+            "Failed to sign tag '{version}': {}",
+            stderr.trim()
+        )));
+    }
+    Ok(())
+}
+
+fn workdir(repo: &RepoHandle) -> GitResult<std::path::PathBuf> {
+    Ok(repo
+        .raw()
+        .workdir()
+        .ok_or_else(|| GitError::InvalidInput("Repository has no working directory".to_string()))?
+        .to_path_buf())
+}
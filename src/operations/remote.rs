@@ -1,6 +1,7 @@
 //! Git remote operations
 
-use crate::{GitError, GitResult, RepoHandle};
+use super::introspection::parse_url;
+use crate::{GitError, GitResult, GitUrl, RepoHandle};
 use gix::bstr::ByteSlice;
 use gix::config::parse::section::ValueName;
 use std::borrow::Cow;
@@ -17,59 +18,76 @@ pub struct RemoteAddOpts {
 }
 
 /// Add a new remote to repository configuration
-pub async fn add_remote(repo: RepoHandle, opts: RemoteAddOpts) -> GitResult<()> {
-    let mut repo_clone = repo.clone_inner();
+///
+/// The URL is parsed and stored in canonical form, see
+/// [`parse_git_url`](super::introspection::parse_git_url). Local paths
+/// without `file://` must exist, relative to the worktree if relative, so a
+/// mistyped URL isn't taken for a path. Returns the parsed URL.
+///
+/// The remote is written to the repository's config file; handles opened
+/// before see it once reopened.
+pub async fn add_remote(repo: RepoHandle, opts: RemoteAddOpts) -> GitResult<GitUrl> {
+    let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
-        // Validate URL format
-        if !is_valid_git_url(&opts.url) {
-            return Err(GitError::InvalidInput(format!(
-                "Invalid Git URL format: {}",
-                opts.url
-            )));
-        }
-
-        // Check if remote exists
-        if !opts.force
-            && repo_clone
-                .find_remote(opts.name.as_bytes().as_bstr())
-                .is_ok()
-        {
+        let url = validate_url(&repo_clone, &opts.url)?;
+
+        // Add remote to the repository's own config file, which the
+        // in-memory snapshot of this handle doesn't write back
+        let config_path = repo_clone.common_dir().join("config");
+        let mut config = gix::config::File::from_path_no_includes(
+            config_path.clone(),
+            gix::config::Source::Local,
+        )
+        .map_err(GitError::gix)?;
+
+        // Check if remote exists, also if added after this handle was opened
+        let exists = repo_clone
+            .find_remote(opts.name.as_bytes().as_bstr())
+            .is_ok()
+            || config
+                .section("remote", Some(opts.name.as_bytes().as_bstr()))
+                .is_ok();
+        if !opts.force && exists {
             return Err(GitError::InvalidInput(format!(
                 "Remote '{}' already exists",
                 opts.name
             )));
         }
-
-        // Add remote via config
-        let mut config = repo_clone.config_snapshot_mut();
+        while config
+            .remove_section("remote", Some(opts.name.as_bytes().as_bstr()))
+            .is_some()
+        {}
 
         // Create remote section with remote name as subsection
         let mut section = config
             .new_section("remote", Some(Cow::Owned(opts.name.clone().into())))
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+            .map_err(GitError::gix)?;
 
         // Set remote.<name>.url = <url>
-        let url_key = ValueName::try_from("url").map_err(|e| GitError::Gix(Box::new(e)))?;
-        section.push(url_key, Some(opts.url.as_bytes().as_bstr()));
+        let url_key = ValueName::try_from("url").map_err(GitError::gix)?;
+        section.push(url_key, Some(url.url.as_bytes().as_bstr()));
 
         // Set remote.<name>.fetch = +refs/heads/*:refs/remotes/<name>/*
-        let fetch_key = ValueName::try_from("fetch").map_err(|e| GitError::Gix(Box::new(e)))?;
+        let fetch_key = ValueName::try_from("fetch").map_err(GitError::gix)?;
         let refspec = format!("+refs/heads/*:refs/remotes/{}/*", opts.name);
         section.push(fetch_key, Some(refspec.as_bytes().as_bstr()));
-
-        // Commit the config changes
         drop(section);
-        config
-            .commit()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
 
-        Ok(())
+        // Replace the file atomically
+        let mut lock = gix::lock::File::acquire_to_update_resource(
+            &config_path,
+            gix::lock::acquire::Fail::Immediately,
+            None,
+        )
+        .map_err(GitError::gix)?;
+        config.write_to(&mut lock)?;
+        lock.commit().map_err(|e| GitError::gix(e.error))?;
+
+        Ok(url)
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))??;
-
-    Ok(())
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
 }
 
 /// Remove a remote from repository configuration
@@ -103,24 +121,31 @@ pub async fn remove_remote(repo: RepoHandle, name: &str) -> GitResult<()> {
         }
 
         // Commit the config changes
-        config
-            .commit()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        config.commit().map_err(GitError::gix)?;
 
         Ok(())
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))??;
+    .map_err(GitError::gix)??;
 
     Ok(())
 }
 
-/// Validate Git URL format
-fn is_valid_git_url(url: &str) -> bool {
-    url.starts_with("https://")
-        || url.starts_with("http://")
-        || url.starts_with("git://")
-        || url.starts_with("ssh://")
-        || url.starts_with("file://")
-        || (url.contains('@') && url.contains(':')) // SSH format like git@github.com:user/repo.git
+/// Parse `url` for a remote of `repo`, rejecting local paths that don't
+/// exist unless written as `file://` URLs
+fn validate_url(repo: &gix::Repository, url: &str) -> GitResult<GitUrl> {
+    let parsed = parse_url(url)?;
+    let explicit = url
+        .get(..7)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("file://"));
+    if parsed.scheme == "file" && !explicit {
+        let path = std::path::Path::new(&parsed.path);
+        let base = repo.workdir().unwrap_or_else(|| repo.git_dir());
+        if !base.join(path).exists() {
+            return Err(GitError::InvalidInput(format!(
+                "Invalid Git URL '{url}': not a URL and no repository at that path"
+            )));
+        }
+    }
+    Ok(parsed)
 }
@@ -0,0 +1,184 @@
+//! The default branch of a remote.
+//!
+//! A remote's `HEAD` names its default branch. `git clone` records it as the
+//! symbolic ref `refs/remotes/<remote>/HEAD`, and the default-branch lookups
+//! of sync, lint and branch cleanup read it from there. Checkouts made by
+//! `git init` + `git remote add`, or by clones that never recorded it, don't
+//! have one; [`remote_default_branch`] asks the remote instead.
+
+use gix::bstr::ByteSlice;
+use gix::progress::Discard;
+use gix::protocol::handshake::Ref;
+use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix::remote::{Direction, ref_map};
+
+use super::auth::{self, GitCommandOpts};
+use super::credentials;
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Branch the `HEAD` of a remote points at, without `refs/heads/`
+///
+/// With a repository, `remote` is one of its remotes or a URL, and the
+/// branch is read from the transport handshake, honoring the repository's
+/// URL rewrites and credentials. Without one, `remote` must be a URL and
+/// `git ls-remote --symref` is used, which the `pure-gix` feature lacks.
+///
+/// `Ok(None)` if the remote doesn't say, e.g. because its `HEAD` is
+/// detached. The branch may not exist yet on an empty remote. Nothing is
+/// recorded locally; see [`sync_repo`](super::sync::sync_repo) and
+/// [`cleanup_branches`](super::branch::cleanup_branches) for callers that do.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kodegen_tools_git::{open_repo, remote_default_branch};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let repo = open_repo("/path/to/repo").await??;
+/// let branch = remote_default_branch(Some(&repo), "origin").await?;
+/// let upstream = remote_default_branch(None, "https://github.com/rust-lang/rust").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn remote_default_branch(
+    repo: Option<&RepoHandle>,
+    remote: &str,
+) -> GitResult<Option<String>> {
+    match repo {
+        Some(repo) => {
+            let repo = repo.clone_inner();
+            let remote = remote.to_string();
+            tokio::task::spawn_blocking(move || query(&repo, &remote))
+                .await
+                .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+        }
+        None => ls_remote(remote).await,
+    }
+}
+
+/// Ask `remote` of `repo` for its default branch over the transport
+pub(crate) fn query(repo: &gix::Repository, remote: &str) -> GitResult<Option<String>> {
+    let url = auth::remote_url(repo, remote, Direction::Fetch).ok_or_else(|| {
+        GitError::InvalidInput(format!("Remote '{remote}' not found or has no URL"))
+    })?;
+    let remote = repo
+        .remote_at_without_url_rewrite(url.as_str())
+        .map_err(|e| GitError::InvalidInput(format!("Invalid URL '{url}': {e}")))?;
+    let mut connection = remote
+        .connect(Direction::Fetch)
+        .map_err(|e| {
+            if auth::is_auth_error(&e.to_string()) {
+                GitError::InvalidInput(auth::auth_error_message(&url))
+            } else {
+                GitError::gix(e)
+            }
+        })?;
+    credentials::install(&mut connection)?;
+
+    // Ask only for HEAD, as a clone does to learn the default branch
+    let head = gix::refspec::parse("HEAD".into(), gix::refspec::parse::Operation::Fetch)
+        .map_err(GitError::gix)?
+        .to_owned();
+    let (ref_map, _handshake) = connection
+        .ref_map(
+            Discard,
+            ref_map::Options {
+                prefix_from_spec_as_filter_on_remote: true,
+                extra_refspecs: vec![head],
+                ..Default::default()
+            },
+        )
+        .map_err(GitError::gix)?;
+    Ok(head_branch(&ref_map.remote_refs))
+}
+
+/// Branch `HEAD` points at among refs a remote advertised
+pub(crate) fn head_branch(refs: &[Ref]) -> Option<String> {
+    refs.iter().find_map(|remote_ref| match remote_ref {
+        Ref::Symbolic {
+            full_ref_name,
+            target,
+            ..
+        }
+        | Ref::Unborn {
+            full_ref_name,
+            target,
+        } if full_ref_name == "HEAD" => target
+            .strip_prefix(b"refs/heads/")
+            .map(|branch| branch.to_str_lossy().into_owned()),
+        _ => None,
+    })
+}
+
+/// The default branch recorded for `remote`, or asked of it and recorded if
+/// there is none
+///
+/// Returns `None` if neither works; failing to reach the remote is not an
+/// error here, as callers fall back to other guesses.
+pub(crate) fn ensure_remote_head(repo: &gix::Repository, remote: &str) -> Option<String> {
+    let recorded = format!("refs/remotes/{remote}/HEAD");
+    if let Ok(Some(reference)) = repo.try_find_reference(recorded.as_str())
+        && let gix::refs::TargetRef::Symbolic(target) = reference.target()
+    {
+        let prefix = format!("refs/remotes/{remote}/");
+        return target
+            .as_bstr()
+            .to_str_lossy()
+            .strip_prefix(&prefix)
+            .map(ToString::to_string);
+    }
+    let branch = query(repo, remote).ok().flatten()?;
+    if let Err(e) = record_remote_head(repo, remote, &branch) {
+        log::debug!("Could not record {recorded}: {e}");
+    }
+    Some(branch)
+}
+
+/// Point `refs/remotes/<remote>/HEAD` at `refs/remotes/<remote>/<branch>`
+pub(crate) fn record_remote_head(
+    repo: &gix::Repository,
+    remote: &str,
+    branch: &str,
+) -> GitResult<()> {
+    let name = format!("refs/remotes/{remote}/HEAD");
+    let target = format!("refs/remotes/{remote}/{branch}");
+    repo.edit_reference(RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: format!("remote: {remote} HEAD is {branch}").into(),
+            },
+            expected: PreviousValue::Any,
+            new: gix::refs::Target::Symbolic(target.try_into().map_err(GitError::gix)?),
+        },
+        name: name.try_into().map_err(GitError::gix)?,
+        deref: false,
+    })
+    .map_err(GitError::gix)?;
+    Ok(())
+}
+
+/// `git ls-remote --symref <url> HEAD`, for URLs without a repository
+async fn ls_remote(url: &str) -> GitResult<Option<String>> {
+    let output = auth::run_git_command(
+        &["ls-remote", "--symref", url, "HEAD"],
+        GitCommandOpts::new(std::env::temp_dir()).with_timeout(30),
+    )
+    .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::InvalidInput(format!(
+            "ls-remote failed: {stderr}"
+        )));
+    }
+
+    // `ref: refs/heads/main<TAB>HEAD`
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("ref: "))
+        .filter_map(|line| line.split_once('\t'))
+        .find(|(_, name)| *name == "HEAD")
+        .and_then(|(target, _)| target.strip_prefix("refs/heads/"))
+        .map(ToString::to_string))
+}
@@ -0,0 +1,281 @@
+//! Replace refs (`git replace`): substitute one object for another.
+//!
+//! A ref `refs/replace/<original>` pointing at another object makes readers
+//! that honor replacements see that object wherever `<original>` is
+//! referenced. The classic use is grafting: giving a commit different
+//! parents, e.g. attaching the root of a shallow history to the full history
+//! it was cut from, without rewriting any commit.
+//!
+//! Honoring replacements is opt-in per handle, see
+//! [`RepoHandle::with_replace_refs`](crate::RepoHandle::with_replace_refs).
+//! The functions here always look at the objects as stored.
+
+use gix::bstr::ByteSlice;
+use gix::refs::transaction::PreviousValue;
+
+use super::compare::resolve_commit;
+use crate::{GitError, GitResult, RepoHandle};
+
+/// Namespace holding replace refs
+const REPLACE_PREFIX: &str = "refs/replace/";
+
+/// A replace ref.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplaceRef {
+    /// The object being replaced.
+    pub original: gix::ObjectId,
+    /// The object shown instead.
+    pub replacement: gix::ObjectId,
+    /// Kind of the replacement object.
+    pub kind: gix::object::Kind,
+}
+
+/// Replace `original` with `replacement` (both revisions or object ids).
+///
+/// The objects must be of the same kind. Fails if `original` is already
+/// replaced unless `force` is set.
+pub async fn create_replace(
+    repo: RepoHandle,
+    original: &str,
+    replacement: &str,
+    force: bool,
+) -> GitResult<ReplaceRef> {
+    let repo_inner = stored_objects(&repo);
+    let original = original.to_string();
+    let replacement = replacement.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let original_id = resolve(&repo_inner, &original)?;
+        let replacement_id = resolve(&repo_inner, &replacement)?;
+        if original_id == replacement_id {
+            return Err(GitError::InvalidInput(format!(
+                "Cannot replace {original_id} with itself"
+            )));
+        }
+
+        let original_kind = kind_of(&repo_inner, original_id)?;
+        let kind = kind_of(&repo_inner, replacement_id)?;
+        if original_kind != kind {
+            return Err(GitError::InvalidInput(format!(
+                "Cannot replace {original_kind} {original_id} with {kind} {replacement_id}"
+            )));
+        }
+
+        write_replace_ref(&repo_inner, original_id, replacement_id, force)?;
+        Ok(ReplaceRef {
+            original: original_id,
+            replacement: replacement_id,
+            kind,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Give `commit` a different set of `parents` (`git replace --graft`).
+///
+/// Writes a copy of the commit with the new parents and replaces the
+/// original with it. An empty `parents` list turns the commit into a root.
+/// Grafting the root of a shallow clone onto the commit it was cut from
+/// (fetched from a full clone) makes the complete history visible to
+/// handles that honor replacements.
+pub async fn graft(
+    repo: RepoHandle,
+    commit: &str,
+    parents: &[String],
+    force: bool,
+) -> GitResult<ReplaceRef> {
+    let repo_inner = stored_objects(&repo);
+    let commit = commit.to_string();
+    let parents = parents.to_vec();
+
+    tokio::task::spawn_blocking(move || {
+        let original_id = resolve_commit(&repo_inner, &commit)?;
+        let parent_ids = parents
+            .iter()
+            .map(|parent| resolve_commit(&repo_inner, parent))
+            .collect::<GitResult<Vec<_>>>()?;
+
+        let mut grafted = repo_inner
+            .find_commit(original_id)
+            .map_err(GitError::gix)?
+            .decode()
+            .map_err(GitError::gix)?
+            .into_owned();
+        grafted.parents = parent_ids.into_iter().collect();
+        // The signature covers the old parents
+        grafted
+            .extra_headers
+            .retain(|(name, _)| name != "gpgsig" && name != "gpgsig-sha256");
+
+        let replacement_id = repo_inner
+            .write_object(&grafted)
+            .map_err(GitError::gix)?
+            .detach();
+        if replacement_id == original_id {
+            return Err(GitError::InvalidInput(format!(
+                "{original_id} already has these parents"
+            )));
+        }
+
+        write_replace_ref(&repo_inner, original_id, replacement_id, force)?;
+        Ok(ReplaceRef {
+            original: original_id,
+            replacement: replacement_id,
+            kind: gix::object::Kind::Commit,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// List replace refs.
+pub async fn list_replace(repo: RepoHandle) -> GitResult<Vec<ReplaceRef>> {
+    let repo_inner = stored_objects(&repo);
+
+    tokio::task::spawn_blocking(move || {
+        replace_refs(&repo_inner)?
+            .into_iter()
+            .map(|(original, replacement)| {
+                Ok(ReplaceRef {
+                    original,
+                    replacement,
+                    kind: kind_of(&repo_inner, replacement)?,
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Remove the replacement of `original` (a revision or object id).
+pub async fn delete_replace(repo: RepoHandle, original: &str) -> GitResult<()> {
+    let repo_inner = stored_objects(&repo);
+    let original = original.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let name = format!("{REPLACE_PREFIX}{}", resolve(&repo_inner, &original)?);
+        repo_inner
+            .try_find_reference(name.as_str())
+            .map_err(GitError::gix)?
+            .ok_or(GitError::ReferenceNotFound(name))?
+            .delete()
+            .map_err(GitError::gix)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Make `repo` honor replace refs for object lookups, or ignore them
+///
+/// Replacements are read once when a repository is opened, so enabling them
+/// reopens it; caches installed on the handle before are not carried over.
+pub(crate) fn set_replace_refs(repo: &mut gix::Repository, enabled: bool) -> GitResult<()> {
+    if !enabled {
+        repo.objects.ignore_replacements = true;
+        return Ok(());
+    }
+
+    let expected = replace_refs(repo)?.len();
+    let loaded = |repo: &gix::Repository| repo.objects.store_ref().replacements().count();
+    if loaded(repo) >= expected {
+        repo.objects.ignore_replacements = false;
+        return Ok(());
+    }
+
+    // gix releases disagree on the polarity of core.useReplaceRefs; keep
+    // whichever setting actually loads the replacements
+    let path = repo.workdir().unwrap_or_else(|| repo.git_dir()).to_path_buf();
+    for value in ["true", "false"] {
+        let options = repo
+            .open_options()
+            .clone()
+            .config_overrides([format!("core.useReplaceRefs={value}")]);
+        let reopened = gix::open_opts(path.as_path(), options)?;
+        if loaded(&reopened) >= expected {
+            *repo = reopened;
+            return Ok(());
+        }
+    }
+
+    Err(GitError::Unsupported(
+        "replace refs could not be enabled for this repository",
+    ))
+}
+
+/// Clone of `repo` that sees objects as stored, ignoring replacements
+fn stored_objects(repo: &RepoHandle) -> gix::Repository {
+    let mut repo = repo.clone_inner();
+    repo.objects.ignore_replacements = true;
+    repo
+}
+
+/// All `(original, replacement)` pairs under `refs/replace/`
+fn replace_refs(repo: &gix::Repository) -> GitResult<Vec<(gix::ObjectId, gix::ObjectId)>> {
+    let platform = repo.references().map_err(GitError::gix)?;
+    let references = platform.prefixed(REPLACE_PREFIX).map_err(GitError::gix)?;
+
+    let mut pairs = Vec::new();
+    for reference in references {
+        let reference = reference.map_err(GitError::gix)?;
+        let Some(original) = reference
+            .name()
+            .as_bstr()
+            .strip_prefix(REPLACE_PREFIX.as_bytes())
+            .and_then(|hex| gix::ObjectId::from_hex(hex).ok())
+        else {
+            continue;
+        };
+        if let Some(replacement) = reference.target().try_id() {
+            pairs.push((original, replacement.to_owned()));
+        }
+    }
+    Ok(pairs)
+}
+
+fn write_replace_ref(
+    repo: &gix::Repository,
+    original: gix::ObjectId,
+    replacement: gix::ObjectId,
+    force: bool,
+) -> GitResult<()> {
+    let name = format!("{REPLACE_PREFIX}{original}");
+    if !force
+        && repo
+            .try_find_reference(name.as_str())
+            .map_err(GitError::gix)?
+            .is_some()
+    {
+        return Err(GitError::InvalidInput(format!(
+            "{original} is already replaced; use force to overwrite"
+        )));
+    }
+
+    let constraint = if force {
+        PreviousValue::Any
+    } else {
+        PreviousValue::MustNotExist
+    };
+    repo.reference(
+        name.as_str(),
+        replacement,
+        constraint,
+        format!("replace: {original} with {replacement}"),
+    )
+    .map_err(GitError::gix)?;
+    Ok(())
+}
+
+fn resolve(repo: &gix::Repository, rev: &str) -> GitResult<gix::ObjectId> {
+    let id = repo
+        .rev_parse_single(rev.as_bytes().as_bstr())
+        .map_err(|e| GitError::InvalidInput(format!("Invalid revision '{rev}': {e}")))?
+        .detach();
+    Ok(id)
+}
+
+fn kind_of(repo: &gix::Repository, id: gix::ObjectId) -> GitResult<gix::object::Kind> {
+    let kind = repo.find_header(id).map_err(GitError::gix)?.kind();
+    Ok(kind)
+}
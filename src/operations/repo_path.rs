@@ -0,0 +1,141 @@
+//! Repository paths as git stores them.
+//!
+//! Git paths are bytes. Most are UTF-8, but a repository created on a system
+//! with a legacy locale can hold names that aren't, and converting those to
+//! `String` mangles them for good. [`RepoPath`] keeps the bytes; text
+//! output degrades through [`RepoPath::escaped`], which is exact for UTF-8
+//! paths and unambiguous for the rest.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use gix::bstr::{BStr, BString, ByteSlice};
+
+/// A path relative to the repository root, `/`-separated, as stored in trees
+/// and the index.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RepoPath(BString);
+
+impl RepoPath {
+    /// The path's bytes.
+    #[inline]
+    #[must_use]
+    pub fn as_bstr(&self) -> &BStr {
+        self.0.as_bstr()
+    }
+
+    /// The path as text, `None` if it isn't UTF-8.
+    #[inline]
+    #[must_use]
+    pub fn to_str(&self) -> Option<&str> {
+        self.0.to_str().ok()
+    }
+
+    /// Whether the path is UTF-8, so that [`RepoPath::escaped`] is the path
+    /// itself.
+    #[inline]
+    #[must_use]
+    pub fn is_utf8(&self) -> bool {
+        self.to_str().is_some()
+    }
+
+    /// The path on the file system, relative to the worktree root; lossless
+    /// on Unix.
+    #[must_use]
+    pub fn to_path(&self) -> Cow<'_, Path> {
+        gix::path::from_bstr(self.as_bstr())
+    }
+
+    /// The path as text for display or JSON.
+    ///
+    /// UTF-8 paths are returned as they are. In other paths backslashes are
+    /// doubled and every byte that isn't part of valid UTF-8 is written as an
+    /// octal escape like `\351`, as git quotes names with `core.quotePath`
+    /// off, so distinct paths never look the same.
+    #[must_use]
+    pub fn escaped(&self) -> Cow<'_, str> {
+        if let Some(path) = self.to_str() {
+            return Cow::Borrowed(path);
+        }
+        let mut escaped = String::with_capacity(self.0.len() + 8);
+        for chunk in self.0.utf8_chunks() {
+            escaped.push_str(&chunk.valid().replace('\\', "\\\\"));
+            for byte in chunk.invalid() {
+                escaped.push_str(&format!("\\{byte:03o}"));
+            }
+        }
+        Cow::Owned(escaped)
+    }
+
+    /// The path's bytes, consuming it.
+    #[inline]
+    #[must_use]
+    pub fn into_bstring(self) -> BString {
+        self.0
+    }
+}
+
+impl std::fmt::Display for RepoPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.escaped())
+    }
+}
+
+impl From<BString> for RepoPath {
+    fn from(path: BString) -> Self {
+        Self(path)
+    }
+}
+
+impl From<&BStr> for RepoPath {
+    fn from(path: &BStr) -> Self {
+        Self(path.to_owned())
+    }
+}
+
+impl From<&str> for RepoPath {
+    fn from(path: &str) -> Self {
+        Self(path.into())
+    }
+}
+
+impl From<String> for RepoPath {
+    fn from(path: String) -> Self {
+        Self(path.into())
+    }
+}
+
+impl PartialEq<str> for RepoPath {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for RepoPath {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+impl PartialEq<String> for RepoPath {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escaped() {
+        let utf8 = RepoPath::from("dir/caf\u{e9}\\x.txt");
+        assert!(utf8.is_utf8());
+        assert_eq!(utf8.escaped(), "dir/caf\u{e9}\\x.txt");
+
+        let latin1 = RepoPath::from(BString::from(&b"dir/caf\xe9\\x.txt"[..]));
+        assert!(!latin1.is_utf8());
+        assert_eq!(latin1.escaped(), "dir/caf\\351\\\\x.txt");
+        assert_eq!(latin1.to_string(), latin1.escaped());
+    }
+}
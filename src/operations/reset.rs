@@ -2,6 +2,8 @@
 //!
 //! Provides functionality for resetting repository state to a specific commit.
 
+use super::checkout::checkout_failed;
+use super::progress::{ProgressCallback, with_checkout_progress};
 use crate::{GitError, GitResult, RepoHandle};
 use gix::bstr::ByteSlice;
 use std::sync::Arc;
@@ -28,6 +30,8 @@ pub struct ResetOpts {
     /// Optional cancellation token for graceful abort
     /// When set to true, operation will abort and return `GitError::Aborted`
     pub cancel_token: Option<Arc<AtomicBool>>,
+    /// Optional progress updates while the working directory is rewritten (hard reset)
+    pub progress: Option<ProgressCallback>,
 }
 
 /// Validate preconditions for reset operation
@@ -75,14 +79,16 @@ fn validate_reset_preconditions(
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// reset(&repo, ResetOpts {
+/// reset(repo, ResetOpts {
 ///     target: "HEAD~1".to_string(),
 ///     mode: ResetMode::Mixed,
+///     cancel_token: None,
+///     progress: None,
 /// }).await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn reset(repo: &RepoHandle, opts: ResetOpts) -> GitResult<()> {
+pub async fn reset(repo: RepoHandle, opts: ResetOpts) -> GitResult<()> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
@@ -104,7 +110,7 @@ pub async fn reset(repo: &RepoHandle, opts: ResetOpts) -> GitResult<()> {
         // Get target commit
         let target_commit = repo_clone
             .find_object(target_id)
-            .map_err(|e| GitError::Gix(Box::new(e)))?
+            .map_err(GitError::gix)?
             .try_into_commit()
             .map_err(|_| GitError::Parse("Target is not a commit".to_string()))?;
 
@@ -130,7 +136,7 @@ pub async fn reset(repo: &RepoHandle, opts: ResetOpts) -> GitResult<()> {
             reset_working_directory(
                 &repo_clone,
                 &target_commit,
-                None,
+                opts.progress.as_ref(),
                 opts.cancel_token.as_ref(),
             )
             .map_err(|e| {
@@ -187,7 +193,7 @@ pub async fn reset(repo: &RepoHandle, opts: ResetOpts) -> GitResult<()> {
         Ok(())
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
 
 /// Reset HEAD to a specific commit
@@ -196,7 +202,7 @@ fn reset_head(
     target_id: gix::hash::ObjectId,
     target_ref: &str,
 ) -> GitResult<()> {
-    let head = repo.head().map_err(|e| GitError::Gix(Box::new(e)))?;
+    let head = repo.head().map_err(GitError::gix)?;
 
     // Check if HEAD is symbolic (on a branch) to determine deref behavior
     // For symbolic refs (HEAD -> refs/heads/main), we update the branch reference
@@ -211,8 +217,7 @@ fn reset_head(
         // This uses edit_reference which handles reflog creation automatically
         use gix::bstr::ByteSlice;
         let head_name = head.name().as_bstr();
-        let ref_name = gix::refs::FullName::try_from(head_name.as_bstr())
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        let ref_name = gix::refs::FullName::try_from(head_name.as_bstr()).map_err(GitError::gix)?;
 
         use gix::refs::Target;
         use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
@@ -230,7 +235,7 @@ fn reset_head(
             name: ref_name,
             deref: true,
         })
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+        .map_err(GitError::gix)?;
     } else {
         // Detached HEAD: Update HEAD directly
         // Use high-level reference API which handles reflog automatically
@@ -242,7 +247,7 @@ fn reset_head(
             PreviousValue::Any,
             format!("reset: moving to {target_ref}"),
         )
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+        .map_err(GitError::gix)?;
     }
 
     Ok(())
@@ -251,23 +256,17 @@ fn reset_head(
 /// Reset index to match a specific commit
 fn reset_index(repo: &gix::Repository, target_commit: &gix::Commit) -> GitResult<()> {
     // Step 1: Get tree ID from target commit
-    let tree_id = target_commit
-        .tree_id()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let tree_id = target_commit.tree_id().map_err(GitError::gix)?;
 
     // Step 2: Create new index from target tree
     // This uses gix_index::State::from_tree internally
     // See: packages/git/tmp/gitoxide/gix-index/src/init.rs:48-64
-    let mut new_index = repo
-        .index_from_tree(&tree_id)
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let mut new_index = repo.index_from_tree(&tree_id).map_err(GitError::gix)?;
 
     // Step 3: Write new index to disk with proper locking and checksum
     // Note: index.write() handles flushing, syncing, and mtime updates automatically
     use gix::index::write::Options;
-    new_index
-        .write(Options::default())
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    new_index.write(Options::default()).map_err(GitError::gix)?;
 
     Ok(())
 }
@@ -276,20 +275,16 @@ fn reset_index(repo: &gix::Repository, target_commit: &gix::Commit) -> GitResult
 fn reset_working_directory(
     repo: &gix::Repository,
     target_commit: &gix::Commit,
-    progress: Option<&dyn gix::progress::Progress>,
+    progress: Option<&ProgressCallback>,
     cancel_token: Option<&Arc<AtomicBool>>,
 ) -> GitResult<()> {
     use std::sync::atomic::AtomicBool;
 
     // Step 1: Get tree ID from target commit
-    let tree_id = target_commit
-        .tree_id()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let tree_id = target_commit.tree_id().map_err(GitError::gix)?;
 
     // Step 2: Create index from target tree
-    let mut index = repo
-        .index_from_tree(&tree_id)
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let mut index = repo.index_from_tree(&tree_id).map_err(GitError::gix)?;
 
     // Step 3: Get worktree path (fail if bare repository)
     let worktree = repo.worktree().ok_or_else(|| {
@@ -300,19 +295,13 @@ fn reset_working_directory(
     // Step 4: Configure checkout options for force overwrite
     let mut checkout_opts = repo
         .checkout_options(gix::worktree::stack::state::attributes::Source::WorktreeThenIdMapping)
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+        .map_err(GitError::gix)?;
 
     // Force overwrite all files (this is --hard reset behavior)
     checkout_opts.overwrite_existing = true;
     checkout_opts.destination_is_initially_empty = false;
 
     // Step 5: Perform the actual file checkout
-    // Accept progress parameter or use Discard
-    let progress_ref: &dyn gix::progress::Progress = match progress {
-        Some(p) => p,
-        None => &gix::progress::Discard,
-    };
-
     // Use caller's token or create a default false one
     let default_token = AtomicBool::new(false);
     let cancel_ref: &AtomicBool = match cancel_token {
@@ -320,19 +309,20 @@ fn reset_working_directory(
         None => &default_token,
     };
 
-    let outcome = gix::worktree::state::checkout(
-        &mut index,
-        &worktree_path,
-        repo.objects
-            .clone()
-            .into_arc()
-            .map_err(|e| GitError::Gix(Box::new(e)))?,
-        progress_ref,
-        progress_ref,
-        cancel_ref,
-        checkout_opts,
-    )
-    .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let objects = repo.objects.clone().into_arc().map_err(GitError::gix)?;
+    let files_total = index.entries().len();
+    let outcome = with_checkout_progress(progress, files_total, |files, bytes| {
+        gix::worktree::state::checkout(
+            &mut index,
+            &worktree_path,
+            objects,
+            files,
+            bytes,
+            cancel_ref,
+            checkout_opts,
+        )
+        .map_err(GitError::gix)
+    })?;
 
     // Check if cancelled after checkout
     if let Some(token) = cancel_token
@@ -343,35 +333,7 @@ fn reset_working_directory(
 
     // Step 6: Check for errors
     if !outcome.errors.is_empty() {
-        // Collect error details for helpful error message
-        let error_details: Vec<String> = outcome
-            .errors
-            .iter()
-            .take(10) // Show first 10 to avoid overwhelming output
-            .map(|err| {
-                let path_str = std::str::from_utf8(err.path.as_ref()).map_or_else(
-                    |_| format!("{:?}", err.path),
-                    std::string::ToString::to_string,
-                );
-                format!("{}: {}", path_str, err.error)
-            })
-            .collect();
-
-        let error_summary = if outcome.errors.len() > 10 {
-            format!(
-                "Reset failed with {} error(s). First 10:\n{}",
-                outcome.errors.len(),
-                error_details.join("\n")
-            )
-        } else {
-            format!(
-                "Reset failed with {} error(s):\n{}",
-                outcome.errors.len(),
-                error_details.join("\n")
-            )
-        };
-
-        return Err(GitError::Gix(error_summary.into()));
+        return Err(checkout_failed(outcome.errors));
     }
 
     Ok(())
@@ -386,17 +348,18 @@ fn reset_working_directory(
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// reset_soft(&repo, "HEAD~1").await?;
+/// reset_soft(repo, "HEAD~1").await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn reset_soft(repo: &RepoHandle, target: &str) -> GitResult<()> {
+pub async fn reset_soft(repo: RepoHandle, target: &str) -> GitResult<()> {
     reset(
-        repo,
+        repo.clone(),
         ResetOpts {
             target: target.to_string(),
             mode: ResetMode::Soft,
             cancel_token: None,
+            progress: None,
         },
     )
     .await
@@ -411,17 +374,18 @@ pub async fn reset_soft(repo: &RepoHandle, target: &str) -> GitResult<()> {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// reset_mixed(&repo, "HEAD~1").await?;
+/// reset_mixed(repo, "HEAD~1").await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn reset_mixed(repo: &RepoHandle, target: &str) -> GitResult<()> {
+pub async fn reset_mixed(repo: RepoHandle, target: &str) -> GitResult<()> {
     reset(
-        repo,
+        repo.clone(),
         ResetOpts {
             target: target.to_string(),
             mode: ResetMode::Mixed,
             cancel_token: None,
+            progress: None,
         },
     )
     .await
@@ -436,17 +400,18 @@ pub async fn reset_mixed(repo: &RepoHandle, target: &str) -> GitResult<()> {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// reset_hard(&repo, "HEAD~1").await?;
+/// reset_hard(repo, "HEAD~1").await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn reset_hard(repo: &RepoHandle, target: &str) -> GitResult<()> {
+pub async fn reset_hard(repo: RepoHandle, target: &str) -> GitResult<()> {
     reset(
-        repo,
+        repo.clone(),
         ResetOpts {
             target: target.to_string(),
             mode: ResetMode::Hard,
             cancel_token: None,
+            progress: None,
         },
     )
     .await
@@ -0,0 +1,462 @@
+//! Pull request review packets.
+//!
+//! Reviewing a branch starts from the same few facts every time: its commits,
+//! the diff, the files it touches, who knows that code and what deserves a
+//! closer look. [`packet`] gathers them in one call, with the diff capped so
+//! the packet stays small enough to be read whole.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use gix::bstr::ByteSlice;
+
+use super::compare::{commits_between, resolve_commit};
+use super::diff::{ChangeType, DiffOpts, DiffStats, FileDiffStats};
+use super::ownership::{OwnerCandidate, PathOwners, owners_at};
+use crate::{CommitId, CommitInfo, GitError, GitResult, RepoHandle};
+
+/// Bytes of unified diff in a packet; files that don't fit are left out
+pub const MAX_PATCH_BYTES: usize = 256 * 1024;
+
+/// Size above which an added or changed file is flagged as large
+pub const LARGE_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Changed files whose owners are looked up, most changed lines first
+const OWNER_PATHS: usize = 20;
+
+/// Recent commits per path counted as activity when ranking reviewers
+const OWNER_WINDOW: usize = 50;
+
+/// Reviewers suggested per packet
+const MAX_REVIEWERS: usize = 5;
+
+/// Bytes at the start of a file searched for generated-code markers
+const GENERATED_SNIFF_LEN: usize = 1024;
+
+/// Dependency lockfiles, by file name
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "Gemfile.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "composer.lock",
+    "go.sum",
+    "flake.lock",
+    "mix.lock",
+    "pubspec.lock",
+    "Podfile.lock",
+    "packages.lock.json",
+];
+
+/// File name endings of common code generators' output
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.rs",
+    ".pb.cc",
+    ".pb.h",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".min.js",
+    ".min.css",
+    ".g.dart",
+    ".freezed.dart",
+    ".designer.cs",
+];
+
+/// Markers generators put in the header of their output, lowercased
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "do not edit",
+    "code generated by",
+    "auto-generated",
+    "autogenerated",
+];
+
+/// A commit of the reviewed branch
+#[derive(Debug, Clone)]
+pub struct ReviewCommit {
+    pub info: CommitInfo,
+    /// Full message, summary included
+    pub message: String,
+}
+
+/// What makes a change worth a closer look
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskKind {
+    /// A file above [`LARGE_FILE_BYTES`] is added or changed
+    LargeFile,
+    /// Generated code is changed, which is usually regenerated rather than
+    /// reviewed line by line
+    GeneratedCode,
+    /// Only dependency lockfiles change, so the diff hides what was upgraded
+    LockfileOnly,
+}
+
+impl RiskKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LargeFile => "large-file",
+            Self::GeneratedCode => "generated-code",
+            Self::LockfileOnly => "lockfile-only",
+        }
+    }
+}
+
+impl std::fmt::Display for RiskKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A risk found in the change, with the files it concerns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiskFlag {
+    pub kind: RiskKind,
+    pub paths: Vec<String>,
+    pub message: String,
+}
+
+/// Everything a reviewer needs to start on a branch
+#[derive(Debug, Clone)]
+pub struct ReviewPacket {
+    pub base_id: CommitId,
+    pub head_id: CommitId,
+    /// Best common ancestor, `None` for unrelated histories
+    pub merge_base: Option<CommitId>,
+    /// Commits in `head` but not in `base`, oldest first
+    pub commits: Vec<ReviewCommit>,
+    /// Changes since the merge base, with hunks
+    pub diffstat: DiffStats,
+    /// Unified diff of the changes, whole files only, at most
+    /// [`MAX_PATCH_BYTES`]
+    pub patch: String,
+    /// Changed files left out of `patch` for its size
+    pub omitted_files: Vec<String>,
+    /// The changed files as an indented tree, with their line counts
+    pub file_tree: String,
+    /// Likely reviewers for the changed files, best first; the branch's own
+    /// authors are left out
+    pub reviewers: Vec<OwnerCandidate>,
+    /// Owners CODEOWNERS assigns to the changed files
+    pub codeowners: Vec<String>,
+    pub risks: Vec<RiskFlag>,
+}
+
+/// Gather a review packet for merging `head` into `base`, as a pull request
+/// would show it.
+///
+/// Both arguments are revisions. The diff is taken from the merge base (the
+/// tip of `base` for unrelated histories) to `head`. Reviewers are ranked by
+/// blame and history at the merge base, for the files with the most changed
+/// lines.
+pub async fn packet(repo: RepoHandle, base: &str, head: &str) -> GitResult<ReviewPacket> {
+    let repo_inner = repo.clone_for_traversal();
+    let base = base.to_string();
+    let head = head.to_string();
+
+    let (base_id, head_id, merge_base, commits) = tokio::task::spawn_blocking(move || {
+        let base_id = resolve_commit(&repo_inner, &base)?;
+        let head_id = resolve_commit(&repo_inner, &head)?;
+        let merge_base = if base_id == head_id {
+            Some(base_id)
+        } else {
+            repo_inner
+                .merge_base(base_id, head_id)
+                .ok()
+                .map(gix::Id::detach)
+        };
+        let commits = commits_between(&repo_inner, head_id, base_id)?
+            .into_iter()
+            .rev()
+            .map(|info| review_commit(&repo_inner, info))
+            .collect::<GitResult<Vec<_>>>()?;
+        Ok::<_, GitError>((base_id, head_id, merge_base, commits))
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    let from = merge_base.unwrap_or(base_id);
+    let diffstat = super::diff::diff(
+        repo.clone(),
+        DiffOpts::new(from.to_string())
+            .to(head_id.to_string())
+            .hunks(true),
+    )
+    .await?;
+
+    let repo_inner = repo.clone_for_traversal();
+    let files = diffstat.files.clone();
+    let authors: HashSet<String> = commits
+        .iter()
+        .map(|commit| commit.info.author.email.to_lowercase())
+        .collect();
+    let (reviewers, codeowners, risks) = tokio::task::spawn_blocking(move || {
+        let mut owned: Vec<&FileDiffStats> = files
+            .iter()
+            .filter(|file| file.change_type != ChangeType::Added)
+            .collect();
+        owned.sort_by_key(|file| std::cmp::Reverse(file.additions + file.deletions));
+        let owner_paths: Vec<String> = owned
+            .iter()
+            .take(OWNER_PATHS)
+            .filter_map(|file| file.path.to_str().map(ToString::to_string))
+            .collect();
+        let reviewers = combine_owners(
+            owners_at(&repo_inner, from, &owner_paths, OWNER_WINDOW)?,
+            &authors,
+        );
+
+        let mut codeowners: Vec<String> = Vec::new();
+        if let Some(rules) = super::codeowners::load(&repo_inner)? {
+            for file in &files {
+                for owner in rules.owners_for(&file.path.escaped()) {
+                    if !codeowners.contains(owner) {
+                        codeowners.push(owner.clone());
+                    }
+                }
+            }
+        }
+
+        let risks = risk_flags(&repo_inner, head_id, &files)?;
+        Ok::<_, GitError>((reviewers, codeowners, risks))
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    let (patch, omitted_files) = render_patch(&diffstat.files, MAX_PATCH_BYTES);
+    let file_tree = render_file_tree(&diffstat.files);
+
+    Ok(ReviewPacket {
+        base_id,
+        head_id,
+        merge_base,
+        commits,
+        diffstat,
+        patch,
+        omitted_files,
+        file_tree,
+        reviewers,
+        codeowners,
+        risks,
+    })
+}
+
+fn review_commit(repo: &gix::Repository, info: CommitInfo) -> GitResult<ReviewCommit> {
+    let message = repo
+        .find_commit(info.id)
+        .map_err(GitError::gix)?
+        .message_raw_sloppy()
+        .to_str_lossy()
+        .trim_end()
+        .to_string();
+    Ok(ReviewCommit { info, message })
+}
+
+/// Candidates of all paths combined, without `authors` (lowercased emails),
+/// best first
+fn combine_owners(owners: Vec<PathOwners>, authors: &HashSet<String>) -> Vec<OwnerCandidate> {
+    let paths = owners.len().max(1) as f64;
+    let mut combined: HashMap<String, OwnerCandidate> = HashMap::new();
+    for candidate in owners.into_iter().flat_map(|path| path.candidates) {
+        if authors.contains(&candidate.email) {
+            continue;
+        }
+        match combined.entry(candidate.email.clone()) {
+            Entry::Occupied(mut entry) => {
+                let total = entry.get_mut();
+                total.blame_lines += candidate.blame_lines;
+                total.recent_commits += candidate.recent_commits;
+                total.score += candidate.score;
+                total.in_codeowners |= candidate.in_codeowners;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(candidate);
+            }
+        }
+    }
+
+    // Scores are averaged over all paths, so one path's owner ranks below
+    // someone owning several
+    let mut reviewers: Vec<OwnerCandidate> = combined
+        .into_values()
+        .map(|candidate| OwnerCandidate {
+            score: candidate.score / paths,
+            ..candidate
+        })
+        .collect();
+    reviewers.sort_by(|a, b| {
+        b.in_codeowners
+            .cmp(&a.in_codeowners)
+            .then(b.score.total_cmp(&a.score))
+            .then_with(|| a.email.cmp(&b.email))
+    });
+    reviewers.truncate(MAX_REVIEWERS);
+    reviewers
+}
+
+fn risk_flags(
+    repo: &gix::Repository,
+    head_id: CommitId,
+    files: &[FileDiffStats],
+) -> GitResult<Vec<RiskFlag>> {
+    let tree = repo
+        .find_commit(head_id)
+        .map_err(GitError::gix)?
+        .tree()
+        .map_err(GitError::gix)?;
+
+    let mut large = Vec::new();
+    let mut generated = Vec::new();
+    for file in files {
+        if file.change_type == ChangeType::Deleted || file.is_symlink {
+            continue;
+        }
+        let Some(entry) = tree
+            .lookup_entry_by_path(file.path.to_path())
+            .map_err(GitError::gix)?
+        else {
+            continue;
+        };
+        let size = repo
+            .find_header(entry.object_id())
+            .map_err(GitError::gix)?
+            .size();
+        if size > LARGE_FILE_BYTES {
+            large.push(file.path.to_string());
+        }
+
+        let generated_name = GENERATED_SUFFIXES
+            .iter()
+            .any(|suffix| file.path.as_bstr().ends_with_str(suffix));
+        let is_generated = generated_name || {
+            let blob = entry.object().map_err(GitError::gix)?;
+            let header = &blob.data[..blob.data.len().min(GENERATED_SNIFF_LEN)];
+            let header = header.to_str_lossy().to_lowercase();
+            GENERATED_MARKERS
+                .iter()
+                .any(|marker| header.contains(marker))
+        };
+        if is_generated {
+            generated.push(file.path.to_string());
+        }
+    }
+
+    let mut risks = Vec::new();
+    if !large.is_empty() {
+        risks.push(RiskFlag {
+            kind: RiskKind::LargeFile,
+            message: format!(
+                "{} file(s) larger than {} KiB",
+                large.len(),
+                LARGE_FILE_BYTES / 1024
+            ),
+            paths: large,
+        });
+    }
+    if !generated.is_empty() {
+        risks.push(RiskFlag {
+            kind: RiskKind::GeneratedCode,
+            message: format!("{} generated file(s) changed", generated.len()),
+            paths: generated,
+        });
+    }
+    if !files.is_empty() && files.iter().all(|file| is_lockfile(&file.path.escaped())) {
+        risks.push(RiskFlag {
+            kind: RiskKind::LockfileOnly,
+            message: "Only lockfiles change; check which dependencies moved".to_string(),
+            paths: files.iter().map(|file| file.path.to_string()).collect(),
+        });
+    }
+    Ok(risks)
+}
+
+fn is_lockfile(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    LOCKFILES.contains(&name)
+}
+
+/// The unified diff of `files` up to `limit` bytes, and the files left out
+fn render_patch(files: &[FileDiffStats], limit: usize) -> (String, Vec<String>) {
+    let mut patch = String::new();
+    let mut omitted = Vec::new();
+    for file in files {
+        let file_patch = file_patch(file);
+        if patch.len() + file_patch.len() > limit {
+            omitted.push(file.path.to_string());
+        } else {
+            patch.push_str(&file_patch);
+        }
+    }
+    (patch, omitted)
+}
+
+fn file_patch(file: &FileDiffStats) -> String {
+    let path = &file.path;
+    let mut patch = format!("diff --git a/{path} b/{path}\n");
+    if let Some(mode) = file.mode_change {
+        patch.push_str(&format!(
+            "old mode {:06o}\nnew mode {:06o}\n",
+            mode.from, mode.to
+        ));
+    }
+    if file.hunks.is_empty() {
+        return patch;
+    }
+    let (old, new) = match file.change_type {
+        ChangeType::Added => ("/dev/null".to_string(), format!("b/{path}")),
+        ChangeType::Deleted => (format!("a/{path}"), "/dev/null".to_string()),
+        ChangeType::Modified | ChangeType::Renamed => (format!("a/{path}"), format!("b/{path}")),
+    };
+    patch.push_str(&format!("--- {old}\n+++ {new}\n"));
+    for hunk in &file.hunks {
+        patch.push_str(&hunk.to_patch());
+    }
+    patch
+}
+
+/// `files` as an indented tree of their paths, each file with its line counts
+fn render_file_tree(files: &[FileDiffStats]) -> String {
+    let mut sorted: Vec<(String, &FileDiffStats)> = files
+        .iter()
+        .map(|file| (file.path.to_string(), file))
+        .collect();
+    sorted.sort_by(|(a, _), (b, _)| a.split('/').cmp(b.split('/')));
+
+    let mut tree = String::new();
+    let mut open: Vec<&str> = Vec::new();
+    for (path, file) in &sorted {
+        let mut components: Vec<&str> = path.split('/').collect();
+        let name = components.pop().unwrap_or_default();
+        let shared = open
+            .iter()
+            .zip(&components)
+            .take_while(|(a, b)| a == b)
+            .count();
+        open.truncate(shared);
+        for &dir in &components[shared..] {
+            tree.push_str(&format!("{}{dir}/\n", "  ".repeat(open.len())));
+            open.push(dir);
+        }
+
+        let change = match file.change_type {
+            ChangeType::Added => " (added)",
+            ChangeType::Deleted => " (deleted)",
+            ChangeType::Renamed => " (renamed)",
+            ChangeType::Modified => "",
+        };
+        tree.push_str(&format!(
+            "{}{name} +{} -{}{change}\n",
+            "  ".repeat(open.len()),
+            file.additions,
+            file.deletions
+        ));
+    }
+    tree
+}
@@ -0,0 +1,579 @@
+//! History rewriting: recreate a range of commits with edits applied.
+//!
+//! [`remove_paths`] drops files or directories from every commit in a range,
+//! the usual emergency fix for committed secrets or large binaries.
+//! [`rewrite_authors`] replaces author and committer identities, e.g. a
+//! misconfigured bot, according to an [`AuthorMapping`]. Commits are
+//! recreated parents-first, replacing only what the edit changes and keeping
+//! everything else; signatures on rewritten commits are dropped since they
+//! no longer verify. Local branches, lightweight tags and a detached `HEAD` that point
+//! at rewritten commits are moved in a single transaction (their reflogs keep
+//! the old ids). Annotated tags are left alone and reported.
+//!
+//! The rewritten commits only exist locally; publishing them needs a force
+//! push, and every clone still holds the old objects.
+
+use std::collections::{HashMap, HashSet};
+
+use gix::bstr::ByteSlice;
+use gix::refs::Target;
+use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+use super::update_branch::tree_of;
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// A reference moved by a rewrite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewrittenRef {
+    /// Full reference name (e.g. `refs/heads/main`, or `HEAD` when detached).
+    pub name: String,
+    pub old: CommitId,
+    pub new: CommitId,
+}
+
+/// Result of a history rewrite.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RewriteResult {
+    /// Old and new id of every commit that changed, parents before children.
+    /// A commit dropped for becoming empty maps to its rewritten parent.
+    pub commit_map: Vec<(CommitId, CommitId)>,
+    /// Commits dropped because the rewrite left them without changes.
+    pub dropped_commits: usize,
+    pub updated_refs: Vec<RewrittenRef>,
+    /// Annotated tags pointing at rewritten commits, left untouched.
+    pub skipped_tags: Vec<String>,
+}
+
+impl RewriteResult {
+    /// New id for `old`, if it was rewritten.
+    #[must_use]
+    pub fn new_id(&self, old: CommitId) -> Option<CommitId> {
+        self.commit_map
+            .iter()
+            .find(|(from, _)| *from == old)
+            .map(|(_, to)| *to)
+    }
+}
+
+/// One identity replacement, as a `.mailmap` line expresses it.
+///
+/// Matches signatures by email and, if set, name (both case-insensitive)
+/// and replaces whichever of name and email are given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityRule {
+    pub match_email: String,
+    pub match_name: Option<String>,
+    pub new_name: Option<String>,
+    pub new_email: Option<String>,
+}
+
+impl IdentityRule {
+    /// Match every signature with `email`.
+    #[inline]
+    pub fn for_email(email: impl Into<String>) -> Self {
+        Self {
+            match_email: email.into(),
+            match_name: None,
+            new_name: None,
+            new_email: None,
+        }
+    }
+
+    /// Match signatures with both `name` and `email`.
+    #[inline]
+    pub fn for_identity(name: impl Into<String>, email: impl Into<String>) -> Self {
+        Self {
+            match_name: Some(name.into()),
+            ..Self::for_email(email)
+        }
+    }
+
+    /// Replace the name.
+    #[inline]
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.new_name = Some(name.into());
+        self
+    }
+
+    /// Replace the email.
+    #[inline]
+    #[must_use]
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.new_email = Some(email.into());
+        self
+    }
+
+    fn matches(&self, name: &str, email: &str) -> bool {
+        self.match_email.eq_ignore_ascii_case(email)
+            && self
+                .match_name
+                .as_ref()
+                .is_none_or(|match_name| match_name.to_lowercase() == name.to_lowercase())
+    }
+}
+
+/// Identity replacements for [`rewrite_authors`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthorMapping {
+    rules: Vec<IdentityRule>,
+}
+
+impl AuthorMapping {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule; later rules win over earlier ones.
+    #[inline]
+    #[must_use]
+    pub fn rule(mut self, rule: IdentityRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Parse `.mailmap` syntax:
+    ///
+    /// ```text
+    /// Proper Name <commit@email>
+    /// <proper@email> <commit@email>
+    /// Proper Name <proper@email> <commit@email>
+    /// Proper Name <proper@email> Commit Name <commit@email>
+    /// ```
+    pub fn parse_mailmap(text: &str) -> GitResult<Self> {
+        let mut mapping = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            if let Some(rule) = parse_mailmap_line(line)
+                .map_err(|e| GitError::Parse(format!("mailmap line {}: {e}", index + 1)))?
+            {
+                mapping.rules.push(rule);
+            }
+        }
+        Ok(mapping)
+    }
+
+    #[inline]
+    pub fn rules(&self) -> &[IdentityRule] {
+        &self.rules
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Replacement name and email for an identity, `None` if no rule applies.
+    ///
+    /// Rules naming the identity take precedence over email-only rules.
+    pub fn resolve(&self, name: &str, email: &str) -> Option<(String, String)> {
+        let rule = self
+            .rules
+            .iter()
+            .rev()
+            .find(|rule| rule.match_name.is_some() && rule.matches(name, email))
+            .or_else(|| self.rules.iter().rev().find(|rule| rule.matches(name, email)))?;
+        Some((
+            rule.new_name.clone().unwrap_or_else(|| name.to_string()),
+            rule.new_email.clone().unwrap_or_else(|| email.to_string()),
+        ))
+    }
+
+    /// Apply the mapping to `signature`
+    fn apply(&self, signature: &mut gix::actor::Signature) {
+        let name = signature.name.to_str_lossy();
+        let email = signature.email.to_str_lossy();
+        let Some((new_name, new_email)) = self.resolve(&name, &email) else {
+            return;
+        };
+        // Keep the original bytes of a field that isn't remapped, so a
+        // non-UTF-8 name or email survives a change to the other one
+        let (name_changed, email_changed) = (new_name != name, new_email != email);
+        if name_changed {
+            signature.name = new_name.into();
+        }
+        if email_changed {
+            signature.email = new_email.into();
+        }
+    }
+}
+
+/// Parse one `.mailmap` line, `None` for blanks and comments
+fn parse_mailmap_line(line: &str) -> Result<Option<IdentityRule>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    // Up to two `Name <email>` pairs; anything after the last `>` is a comment
+    let mut pairs: Vec<(Option<String>, String)> = Vec::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('<') {
+        let close = rest[open..]
+            .find('>')
+            .map(|offset| open + offset)
+            .ok_or_else(|| "unterminated email".to_string())?;
+        let name = rest[..open].trim();
+        let name = (!name.is_empty()).then(|| name.to_string());
+        pairs.push((name, rest[open + 1..close].trim().to_string()));
+        rest = &rest[close + 1..];
+    }
+
+    match pairs.as_slice() {
+        [(Some(name), email)] => {
+            Ok(Some(IdentityRule::for_email(email.as_str()).name(name.as_str())))
+        }
+        [(None, _)] => Err("a single email needs a proper name".to_string()),
+        [(proper_name, proper_email), (commit_name, commit_email)] => {
+            let mut rule = match commit_name {
+                Some(commit_name) => {
+                    IdentityRule::for_identity(commit_name.as_str(), commit_email.as_str())
+                }
+                None => IdentityRule::for_email(commit_email.as_str()),
+            };
+            rule.new_name.clone_from(proper_name);
+            if !proper_email.is_empty() {
+                rule.new_email = Some(proper_email.clone());
+            }
+            Ok(Some(rule))
+        }
+        [] => Err("no email found".to_string()),
+        _ => Err("more than two emails".to_string()),
+    }
+}
+
+/// Remove `paths` (files or whole directories) from every commit in `range`.
+///
+/// `range` is either `<tip>` to rewrite all history reachable from it, or
+/// `<base>..<tip>` to keep everything reachable from `base` as is. Commits
+/// that only touched the removed paths are dropped. Paths that never existed
+/// are not an error; if nothing matches, no commit or ref changes.
+///
+/// When the checked-out branch is rewritten the working tree must be clean;
+/// the index and working tree are then updated to the new `HEAD`, deleting
+/// the removed files, before any ref moves. Fails with [`GitError::BranchInUse`] if an affected
+/// branch is checked out in another worktree; nothing is modified then.
+pub async fn remove_paths(
+    repo: RepoHandle,
+    paths: Vec<String>,
+    range: &str,
+) -> GitResult<RewriteResult> {
+    let repo_inner = repo.clone_for_traversal();
+    let range = range.to_string();
+
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| path.trim_matches('/').to_string())
+        .collect();
+    if paths.is_empty() || paths.iter().any(String::is_empty) {
+        return Err(GitError::InvalidInput(
+            "remove_paths needs at least one non-empty path".to_string(),
+        ));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        rewrite_range(&repo_inner, &range, true, "remove paths", |repo, commit| {
+            let mut editor = repo.edit_tree(commit.tree).map_err(GitError::gix)?;
+            for path in &paths {
+                editor.remove(path.as_str()).map_err(GitError::gix)?;
+            }
+            commit.tree = editor.write().map_err(GitError::gix)?.detach();
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Replace author and committer identities in every commit in `range`.
+///
+/// `range` is `<tip>` or `<base>..<tip>` as for [`remove_paths`]. Timestamps
+/// and messages are kept; commits whose identities all stay the same keep
+/// their ids unless an ancestor changed. The same worktree rules as for
+/// [`remove_paths`] apply.
+pub async fn rewrite_authors(
+    repo: RepoHandle,
+    mapping: AuthorMapping,
+    range: &str,
+) -> GitResult<RewriteResult> {
+    let repo_inner = repo.clone_for_traversal();
+    let range = range.to_string();
+
+    if mapping.is_empty() {
+        return Err(GitError::InvalidInput(
+            "rewrite_authors needs at least one identity rule".to_string(),
+        ));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        rewrite_range(&repo_inner, &range, false, "rewrite authors", |_, commit| {
+            mapping.apply(&mut commit.author);
+            mapping.apply(&mut commit.committer);
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Recreate every commit in `range` after passing it through `edit`, then move refs
+///
+/// `edit` sees each commit with its parents already remapped. With
+/// `prune_empty`, single-parent commits that had changes but end up with their
+/// parent's tree are dropped.
+pub(crate) fn rewrite_range<F>(
+    repo: &gix::Repository,
+    range: &str,
+    prune_empty: bool,
+    reason: &str,
+    mut edit: F,
+) -> GitResult<RewriteResult>
+where
+    F: FnMut(&gix::Repository, &mut gix::objs::Commit) -> GitResult<()>,
+{
+    let (base, tip) = parse_range(repo, range)?;
+
+    let mut commits: HashMap<CommitId, gix::objs::Commit> = HashMap::new();
+    let walk = repo
+        .rev_walk([tip])
+        .with_hidden(base)
+        .all()
+        .map_err(GitError::gix)?;
+    for info in walk {
+        let info = info.map_err(GitError::gix)?;
+        let commit = repo.find_commit(info.id).map_err(GitError::gix)?;
+        let decoded = commit.decode().map_err(GitError::gix)?.into_owned();
+        commits.insert(info.id, decoded);
+    }
+
+    let mut result = RewriteResult::default();
+    // Every processed commit maps to its replacement (possibly itself)
+    let mut mapping: HashMap<CommitId, CommitId> = HashMap::new();
+    let mut trees: HashMap<CommitId, gix::ObjectId> = HashMap::new();
+    for id in parents_first(&commits) {
+        let original = &commits[&id];
+        let mut rewritten = original.clone();
+        rewritten.parents.clear();
+        for parent in &original.parents {
+            let parent = mapping.get(parent).copied().unwrap_or(*parent);
+            // Dropped commits can collapse two parents into one
+            if !rewritten.parents.contains(&parent) {
+                rewritten.parents.push(parent);
+            }
+        }
+        edit(repo, &mut rewritten)?;
+
+        if prune_empty && original.parents.len() == 1 && rewritten.parents.len() == 1 {
+            let was_empty = original.tree == cached_tree(repo, &mut trees, original.parents[0])?;
+            let is_empty = rewritten.tree == cached_tree(repo, &mut trees, rewritten.parents[0])?;
+            if is_empty && !was_empty {
+                mapping.insert(id, rewritten.parents[0]);
+                result.commit_map.push((id, rewritten.parents[0]));
+                result.dropped_commits += 1;
+                continue;
+            }
+        }
+
+        if rewritten == *original {
+            mapping.insert(id, id);
+            continue;
+        }
+
+        rewritten
+            .extra_headers
+            .retain(|(name, _)| name != "gpgsig" && name != "gpgsig-sha256");
+        let new_id = repo
+            .write_object(&rewritten)
+            .map_err(GitError::gix)?
+            .detach();
+        trees.insert(new_id, rewritten.tree);
+        mapping.insert(id, new_id);
+        result.commit_map.push((id, new_id));
+    }
+
+    if result.commit_map.is_empty() {
+        return Ok(result);
+    }
+
+    let changed: HashMap<CommitId, CommitId> = result.commit_map.iter().copied().collect();
+    let (updates, skipped_tags) = collect_ref_updates(repo, &changed)?;
+    result.skipped_tags = skipped_tags;
+
+    // Refuse before touching anything if a worktree would be left behind
+    let head_ref = repo
+        .head_name()
+        .map_err(GitError::gix)?
+        .map(|name| name.as_bstr().to_string())
+        .unwrap_or_else(|| "HEAD".to_string());
+    let mut moves_head = false;
+    for update in &updates {
+        if update.name == head_ref {
+            moves_head = true;
+        } else if let Some(branch) = update.name.strip_prefix("refs/heads/") {
+            super::worktree::check_branch_not_in_use(repo, branch)?;
+        }
+    }
+    if moves_head && !repo.is_bare() && super::fast_status::is_dirty_fast(repo)? {
+        return Err(GitError::InvalidInput(
+            "Cannot rewrite the checked-out branch with local changes".to_string(),
+        ));
+    }
+
+    let edits = updates
+        .iter()
+        .map(|update| {
+            Ok(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("rewrite: {reason}").into(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Object(update.old)),
+                    new: Target::Object(update.new),
+                },
+                name: gix::refs::FullName::try_from(update.name.as_str()).map_err(GitError::gix)?,
+                deref: false,
+            })
+        })
+        .collect::<GitResult<Vec<_>>>()?;
+
+    // Update the worktree first, so a refused checkout leaves the refs alone
+    if moves_head
+        && let Some(workdir) = repo.workdir()
+        && let Some(update) = updates.iter().find(|update| update.name == head_ref)
+    {
+        let tree = cached_tree(repo, &mut trees, update.new)?;
+        super::merge::sync_worktree_to_tree(repo, workdir, tree, "Rewrite")?;
+    }
+    repo.edit_references(edits).map_err(GitError::gix)?;
+
+    result.updated_refs = updates;
+    Ok(result)
+}
+
+/// Resolve `<tip>` or `<base>..<tip>` to commit ids
+pub(crate) fn parse_range(
+    repo: &gix::Repository,
+    range: &str,
+) -> GitResult<(Option<CommitId>, CommitId)> {
+    let resolve = |rev: &str| -> GitResult<CommitId> {
+        let id = repo
+            .rev_parse_single(rev.as_bytes().as_bstr())
+            .map_err(|e| GitError::InvalidInput(format!("Invalid revision '{rev}': {e}")))?
+            .object()
+            .map_err(GitError::gix)?
+            .peel_to_commit()
+            .map_err(GitError::gix)?
+            .id;
+        Ok(id)
+    };
+
+    if range.contains("...") {
+        return Err(GitError::InvalidInput(format!(
+            "Symmetric difference '{range}' is not a rewritable range; use <base>..<tip>"
+        )));
+    }
+    match range.split_once("..") {
+        Some((base, tip)) => {
+            let base = if base.is_empty() { "HEAD" } else { base };
+            let tip = if tip.is_empty() { "HEAD" } else { tip };
+            Ok((Some(resolve(base)?), resolve(tip)?))
+        }
+        None => Ok((None, resolve(range)?)),
+    }
+}
+
+/// Tree of commit `id`, cached
+fn cached_tree(
+    repo: &gix::Repository,
+    trees: &mut HashMap<CommitId, gix::ObjectId>,
+    id: CommitId,
+) -> GitResult<gix::ObjectId> {
+    if let Some(tree) = trees.get(&id) {
+        return Ok(*tree);
+    }
+    let tree = tree_of(repo, id)?;
+    trees.insert(id, tree);
+    Ok(tree)
+}
+
+/// Order `commits` so that every commit comes after its parents in the set
+pub(crate) fn parents_first(commits: &HashMap<CommitId, gix::objs::Commit>) -> Vec<CommitId> {
+    let mut starts: Vec<CommitId> = commits.keys().copied().collect();
+    starts.sort();
+
+    let mut order = Vec::with_capacity(commits.len());
+    let mut visited = HashSet::new();
+    for start in starts {
+        let mut stack = vec![(start, false)];
+        while let Some((id, parents_done)) = stack.pop() {
+            if parents_done {
+                order.push(id);
+                continue;
+            }
+            if !visited.insert(id) {
+                continue;
+            }
+            stack.push((id, true));
+            for parent in &commits[&id].parents {
+                if commits.contains_key(parent) && !visited.contains(parent) {
+                    stack.push((*parent, false));
+                }
+            }
+        }
+    }
+    order
+}
+
+/// Branches, lightweight tags and a detached `HEAD` pointing at changed commits
+///
+/// Also returns annotated tags whose target changed.
+fn collect_ref_updates(
+    repo: &gix::Repository,
+    changed: &HashMap<CommitId, CommitId>,
+) -> GitResult<(Vec<RewrittenRef>, Vec<String>)> {
+    let mut updates = Vec::new();
+    let mut skipped_tags = Vec::new();
+
+    let platform = repo.references().map_err(GitError::gix)?;
+    let references = platform.all().map_err(GitError::gix)?;
+    for reference in references {
+        let mut reference = reference.map_err(GitError::gix)?;
+        let name = reference.name().as_bstr().to_string();
+        let is_tag = name.starts_with("refs/tags/");
+        if !is_tag && !name.starts_with("refs/heads/") {
+            continue;
+        }
+        let Some(target) = reference.target().try_id().map(ToOwned::to_owned) else {
+            continue;
+        };
+
+        if let Some(new) = changed.get(&target) {
+            updates.push(RewrittenRef {
+                name,
+                old: target,
+                new: *new,
+            });
+        } else if is_tag
+            && let Ok(peeled) = reference.peel_to_id()
+            && changed.contains_key(&peeled.detach())
+        {
+            skipped_tags.push(name);
+        }
+    }
+
+    let head = repo.head().map_err(GitError::gix)?;
+    if head.is_detached()
+        && let Some(id) = head.id().map(gix::Id::detach)
+        && let Some(new) = changed.get(&id)
+    {
+        updates.push(RewrittenRef {
+            name: "HEAD".to_string(),
+            old: id,
+            new: *new,
+        });
+    }
+
+    Ok((updates, skipped_tags))
+}
@@ -0,0 +1,416 @@
+//! Repository scaffolding: start a new repository from a template repository.
+//!
+//! [`scaffold`] takes the tree of a template (a local repository or a URL to
+//! clone), replaces `{{name}}` placeholders in file contents and paths with
+//! the given variables, and commits the result as the single initial commit
+//! of a fresh repository; the template's history is not carried over. It can
+//! then create further branches and publish them to a new remote.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use gix::bstr::ByteSlice;
+
+use super::patch_id::is_binary;
+use crate::{
+    CloneOpts, CommitId, CommitOpts, GitError, GitResult, PushOpts, RemoteAddOpts, RepoHandle,
+    Signature,
+};
+
+/// Options for [`scaffold`].
+#[derive(Debug, Clone)]
+pub struct ScaffoldOpts {
+    /// Template repository: a local path or a URL to clone
+    pub template: String,
+    /// Directory of the new repository; must not exist or be empty
+    pub destination: PathBuf,
+    /// Template branch, tag or commit (defaults to its `HEAD`)
+    pub reference: Option<String>,
+    /// Values for `{{name}}` placeholders
+    pub variables: BTreeMap<String, String>,
+    /// Message of the initial commit
+    pub message: String,
+    pub author: Option<Signature>,
+    /// Branches to create at the initial commit, besides the current one
+    pub branches: Vec<String>,
+    /// URL to add as `origin`
+    pub remote: Option<String>,
+    /// Push all branches to `origin`
+    pub push: bool,
+}
+
+impl ScaffoldOpts {
+    /// Scaffold `destination` from `template`.
+    #[inline]
+    pub fn new<T: Into<String>, P: Into<PathBuf>>(template: T, destination: P) -> Self {
+        Self {
+            template: template.into(),
+            destination: destination.into(),
+            reference: None,
+            variables: BTreeMap::new(),
+            message: "Initial commit".to_string(),
+            author: None,
+            branches: Vec::new(),
+            remote: None,
+            push: false,
+        }
+    }
+
+    /// Use this branch, tag or commit of the template.
+    #[inline]
+    pub fn reference<S: Into<String>>(mut self, reference: S) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    /// Replace `{{name}}` with `value`.
+    #[inline]
+    pub fn variable<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    /// Set the message of the initial commit.
+    #[inline]
+    pub fn message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Set the author of the initial commit.
+    #[inline]
+    #[must_use]
+    pub fn author(mut self, author: Signature) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Also create `branch` at the initial commit.
+    #[inline]
+    pub fn branch<S: Into<String>>(mut self, branch: S) -> Self {
+        self.branches.push(branch.into());
+        self
+    }
+
+    /// Add `url` as the `origin` remote.
+    #[inline]
+    pub fn remote<S: Into<String>>(mut self, url: S) -> Self {
+        self.remote = Some(url.into());
+        self
+    }
+
+    /// Push every branch to `origin` (requires a remote).
+    #[inline]
+    #[must_use]
+    pub fn push(mut self, yes: bool) -> Self {
+        self.push = yes;
+        self
+    }
+}
+
+/// Result of [`scaffold`].
+#[derive(Debug, Clone)]
+pub struct ScaffoldResult {
+    pub repo: RepoHandle,
+    /// The initial commit.
+    pub commit_id: CommitId,
+    /// Files written from the template.
+    pub files: usize,
+    /// Files whose content or path had placeholders replaced.
+    pub rewritten_files: usize,
+    /// Placeholders without a value, left as they were.
+    pub unresolved: Vec<String>,
+    /// All branches of the new repository, the checked-out one first.
+    pub branches: Vec<String>,
+    /// Whether the branches were pushed to `origin`.
+    pub pushed: bool,
+}
+
+/// Create a repository at `opts.destination` from a template.
+///
+/// Text files and paths have their `{{name}}` placeholders replaced (spaces
+/// inside the braces are allowed); binary files are copied as they are.
+/// Placeholders without a value are reported in
+/// [`ScaffoldResult::unresolved`] and kept. Submodules of the template are
+/// skipped.
+pub async fn scaffold(opts: ScaffoldOpts) -> GitResult<ScaffoldResult> {
+    if opts.push && opts.remote.is_none() {
+        return Err(GitError::InvalidInput(
+            "Pushing a scaffolded repository needs a remote".to_string(),
+        ));
+    }
+    check_destination(&opts.destination)?;
+
+    let (template, _clone_dir) = open_template(&opts).await?;
+    let repo = crate::init_repo(&opts.destination)
+        .await
+        .map_err(|_| GitError::ChannelClosed)??;
+
+    let template_inner = template.clone_inner();
+    let repo_inner = repo.clone_inner();
+    let reference = opts.reference.clone().unwrap_or_else(|| "HEAD".to_string());
+    let variables = opts.variables.clone();
+    let copied = tokio::task::spawn_blocking(move || {
+        copy_template(&template_inner, &repo_inner, &reference, &variables)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    let mut commit_opts = CommitOpts::message(opts.message.clone());
+    if let Some(author) = opts.author.clone() {
+        commit_opts = commit_opts.author(author);
+    }
+    let commit_id = crate::commit(repo.clone(), commit_opts).await?.id;
+
+    let mut branches = vec![crate::current_branch(repo.clone()).await?.name];
+    for name in &opts.branches {
+        if !branches.contains(name) {
+            branches.push(name.clone());
+        }
+    }
+    let repo_inner = repo.clone_inner();
+    let new_branches = branches[1..].to_vec();
+    tokio::task::spawn_blocking(move || create_branches(&repo_inner, commit_id, &new_branches))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    if let Some(url) = &opts.remote {
+        let remote = RemoteAddOpts {
+            name: "origin".to_string(),
+            url: url.clone(),
+            force: false,
+        };
+        crate::add_remote(repo.clone(), remote).await?;
+    }
+    if opts.push {
+        let push = PushOpts {
+            refspecs: branches
+                .iter()
+                .map(|name| format!("refs/heads/{name}:refs/heads/{name}"))
+                .collect(),
+            ..PushOpts::default()
+        };
+        crate::push(repo.clone(), push).await?;
+    }
+
+    Ok(ScaffoldResult {
+        repo,
+        commit_id,
+        files: copied.files,
+        rewritten_files: copied.rewritten_files,
+        unresolved: copied.unresolved.into_iter().collect(),
+        branches,
+        pushed: opts.push,
+    })
+}
+
+/// Create `names` at the initial commit `commit_id`
+///
+/// The reflog entries are written as the commit's committer, so this works
+/// without a configured identity when the author was given.
+fn create_branches(repo: &gix::Repository, commit_id: CommitId, names: &[String]) -> GitResult<()> {
+    use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+    let commit = repo
+        .find_commit(commit_id)
+        .map_err(GitError::gix)?
+        .decode()
+        .map_err(GitError::gix)?
+        .into_owned();
+    let edits = names
+        .iter()
+        .map(|name| {
+            Ok(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "branch: Created from HEAD".into(),
+                    },
+                    expected: PreviousValue::MustNotExist,
+                    new: gix::refs::Target::Object(commit_id),
+                },
+                name: format!("refs/heads/{name}")
+                    .try_into()
+                    .map_err(GitError::gix)?,
+                deref: false,
+            })
+        })
+        .collect::<GitResult<Vec<_>>>()?;
+    let mut time_buf = gix::date::parse::TimeBuf::default();
+    repo.edit_references_as(edits, Some(commit.committer.to_ref(&mut time_buf)))
+        .map_err(GitError::gix)?;
+    Ok(())
+}
+
+/// The destination may be missing or an empty directory
+fn check_destination(destination: &Path) -> GitResult<()> {
+    match std::fs::read_dir(destination) {
+        Ok(mut entries) => {
+            if entries.next().is_some() {
+                return Err(GitError::InvalidInput(format!(
+                    "Destination is not empty: {}",
+                    destination.display()
+                )));
+            }
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(GitError::Io(e)),
+    }
+}
+
+/// Removes a temporary clone when dropped
+struct TempClone(PathBuf);
+
+impl Drop for TempClone {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Open a local template, or clone a remote one into a temporary directory
+async fn open_template(opts: &ScaffoldOpts) -> GitResult<(RepoHandle, Option<TempClone>)> {
+    if Path::new(&opts.template).exists() {
+        let template = crate::open_repo(&opts.template)
+            .await
+            .map_err(|_| GitError::ChannelClosed)??;
+        return Ok((template, None));
+    }
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    let dir = std::env::temp_dir().join(format!(
+        "kodegen-scaffold-{}-{nanos}",
+        std::process::id()
+    ));
+    let guard = TempClone(dir.clone());
+    let mut clone = CloneOpts::new(opts.template.clone(), dir).bare(true).shallow(1);
+    if let Some(reference) = &opts.reference {
+        clone = clone.branch(reference.clone());
+    }
+    let template = crate::clone_repo(clone)
+        .await
+        .map_err(|_| GitError::ChannelClosed)??
+        .repo;
+    Ok((template, Some(guard)))
+}
+
+/// What [`copy_template`] wrote
+struct Copied {
+    files: usize,
+    rewritten_files: usize,
+    unresolved: BTreeSet<String>,
+}
+
+/// Write the rendered template tree into `repo` and check it out
+fn copy_template(
+    template: &gix::Repository,
+    repo: &gix::Repository,
+    reference: &str,
+    variables: &BTreeMap<String, String>,
+) -> GitResult<Copied> {
+    let tree = template
+        .rev_parse_single(reference.as_bytes().as_bstr())
+        .map_err(|e| {
+            GitError::InvalidInput(format!("Invalid template revision '{reference}': {e}"))
+        })?
+        .object()
+        .map_err(GitError::gix)?
+        .peel_to_tree()
+        .map_err(GitError::gix)?;
+    let entries = tree
+        .traverse()
+        .breadthfirst
+        .files()
+        .map_err(GitError::gix)?;
+
+    let mut copied = Copied {
+        files: 0,
+        rewritten_files: 0,
+        unresolved: BTreeSet::new(),
+    };
+    let mut editor = repo
+        .edit_tree(gix::ObjectId::empty_tree(repo.object_hash()))
+        .map_err(GitError::gix)?;
+    for entry in entries {
+        if entry.mode.is_tree() || entry.mode.is_commit() {
+            continue;
+        }
+
+        let path = entry.filepath.to_str_lossy();
+        let new_path = render(&path, variables, &mut copied.unresolved);
+        if new_path.split('/').any(str::is_empty) {
+            return Err(GitError::InvalidInput(format!(
+                "Template path '{path}' renders to the invalid path '{new_path}'"
+            )));
+        }
+
+        let data = template
+            .find_blob(entry.oid)
+            .map_err(GitError::gix)?
+            .detach()
+            .data;
+        let new_data = match std::str::from_utf8(&data) {
+            Ok(text) if !is_binary(&data) => {
+                render(text, variables, &mut copied.unresolved).into_bytes()
+            }
+            _ => data.clone(),
+        };
+
+        if new_path != path || new_data != data {
+            copied.rewritten_files += 1;
+        }
+        copied.files += 1;
+        let blob = repo.write_blob(&new_data).map_err(GitError::gix)?.detach();
+        editor
+            .upsert(new_path.as_str(), entry.mode.kind(), blob)
+            .map_err(GitError::gix)?;
+    }
+    let tree_id = editor.write().map_err(GitError::gix)?.detach();
+
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| GitError::InvalidInput("Cannot scaffold a bare repository".to_string()))?;
+    // Refuses to overwrite files put into the destination since it was checked
+    super::merge::sync_worktree_to_tree(repo, workdir, tree_id, "Scaffold")?;
+    Ok(copied)
+}
+
+/// Replace `{{name}}` placeholders in `text`, collecting names without a value
+fn render(
+    text: &str,
+    variables: &BTreeMap<String, String>,
+    unresolved: &mut BTreeSet<String>,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        let end = start + 2 + len + 2;
+        let is_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+
+        match variables.get(name) {
+            Some(value) if is_name => {
+                out.push_str(&rest[..start]);
+                out.push_str(value);
+            }
+            _ => {
+                if is_name {
+                    unresolved.insert(name.to_string());
+                }
+                out.push_str(&rest[..end]);
+            }
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
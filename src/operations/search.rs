@@ -0,0 +1,380 @@
+//! Commit search by message, author, date and content.
+//!
+//! The agent's primary "find the commit that did X" entry point. Criteria are
+//! combined (all given criteria must match) and results are ranked by how
+//! strongly each commit matched, newest first on ties.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, TimeZone, Utc};
+use gix::bstr::ByteSlice;
+use regex::Regex;
+
+use crate::{GitError, GitResult, RepoHandle, Signature};
+
+/// Default maximum number of commits examined per search
+const DEFAULT_MAX_SCAN: usize = 10_000;
+
+/// Search query for [`commits`]
+#[derive(Debug, Clone)]
+pub struct CommitQuery {
+    /// Regex matched against the full commit message
+    pub message: Option<Regex>,
+    /// Case-insensitive substring matched against author name or email
+    pub author: Option<String>,
+    /// Only commits at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only commits at or before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Pickaxe: string whose number of occurrences changed (like `git log -S`)
+    pub pickaxe: Option<String>,
+    /// Only commits touching this path
+    pub path: Option<PathBuf>,
+    /// Revision to start from (default: HEAD)
+    pub rev: Option<String>,
+    /// Maximum number of results
+    pub limit: usize,
+    /// Maximum number of commits to examine
+    pub max_scan: usize,
+}
+
+impl CommitQuery {
+    /// Create an empty query: every commit from HEAD matches.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            message: None,
+            author: None,
+            since: None,
+            until: None,
+            pickaxe: None,
+            path: None,
+            rev: None,
+            limit: 20,
+            max_scan: DEFAULT_MAX_SCAN,
+        }
+    }
+
+    /// Match commit messages against a regex (case-insensitive unless the pattern says otherwise)
+    pub fn message(mut self, pattern: &str) -> GitResult<Self> {
+        self.message = Some(
+            regex::RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| GitError::InvalidInput(format!("Invalid message regex: {e}")))?,
+        );
+        Ok(self)
+    }
+
+    /// Only commits whose author name or email contains `author`.
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Only commits at or after `since`.
+    #[must_use]
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only commits at or before `until`.
+    #[must_use]
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Only commits changing the number of occurrences of `needle`, like `git log -S`.
+    pub fn pickaxe(mut self, needle: impl Into<String>) -> Self {
+        self.pickaxe = Some(needle.into());
+        self
+    }
+
+    /// Only commits touching `path`.
+    pub fn path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Start from `rev` instead of HEAD.
+    pub fn rev(mut self, rev: impl Into<String>) -> Self {
+        self.rev = Some(rev.into());
+        self
+    }
+
+    /// Return at most `n` results.
+    #[must_use]
+    pub fn limit(mut self, n: usize) -> Self {
+        self.limit = n;
+        self
+    }
+
+    /// Examine at most `n` commits.
+    #[must_use]
+    pub fn max_scan(mut self, n: usize) -> Self {
+        self.max_scan = n;
+        self
+    }
+}
+
+impl Default for CommitQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A ranked search hit
+#[derive(Debug, Clone)]
+pub struct CommitMatch {
+    /// Full commit hash
+    pub id: String,
+    /// Abbreviated commit hash
+    pub short_id: String,
+    /// First line of the message
+    pub summary: String,
+    /// Commit author
+    pub author: Signature,
+    /// Committer time
+    pub time: DateTime<Utc>,
+    /// Relevance score (higher is better)
+    pub score: u32,
+    /// Message regex matched the summary line (not just the body)
+    pub summary_match: bool,
+    /// Files whose pickaxe occurrence count changed
+    pub pickaxe_paths: Vec<String>,
+}
+
+/// Result of a commit search
+#[derive(Debug, Clone)]
+pub struct CommitSearchResult {
+    /// Commits examined before stopping
+    pub total_examined: usize,
+    /// Whether the scan stopped at `max_scan` before reaching the root
+    pub truncated: bool,
+    /// Ranked matches (at most `limit`)
+    pub commits: Vec<CommitMatch>,
+}
+
+/// Search commit history
+pub async fn commits(repo: RepoHandle, query: CommitQuery) -> GitResult<CommitSearchResult> {
+    let repo_inner = repo.clone_for_traversal();
+
+    tokio::task::spawn_blocking(move || commits_sync(&repo_inner, &query))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+fn commits_sync(repo: &gix::Repository, query: &CommitQuery) -> GitResult<CommitSearchResult> {
+    if query.pickaxe.as_deref() == Some("") {
+        return Err(GitError::InvalidInput("Pickaxe string must not be empty".to_string()));
+    }
+
+    let start_id = match query.rev {
+        Some(ref rev) => repo
+            .rev_parse_single(rev.as_str())
+            .map_err(GitError::gix)?
+            .detach(),
+        None => repo.head_id().map_err(GitError::gix)?.detach(),
+    };
+
+    let author_needle = query.author.as_ref().map(|a| a.to_lowercase());
+
+    let rev_walk = repo.rev_walk([start_id]).all().map_err(GitError::gix)?;
+
+    let mut matches = Vec::new();
+    let mut total_examined = 0;
+    let mut truncated = false;
+
+    for commit_result in rev_walk {
+        if total_examined >= query.max_scan {
+            truncated = true;
+            break;
+        }
+
+        let info = commit_result.map_err(GitError::gix)?;
+        let commit = repo
+            .find_object(info.id)
+            .map_err(GitError::gix)?
+            .into_commit();
+
+        total_examined += 1;
+        let mut score = 0;
+
+        // Date range (cheapest checks first)
+        let time = commit.time().map_err(GitError::gix)?;
+        let commit_time = Utc
+            .timestamp_opt(time.seconds, 0)
+            .single()
+            .ok_or_else(|| GitError::InvalidInput(format!("Invalid timestamp {}", time.seconds)))?;
+
+        if query.since.is_some_and(|since| commit_time < since)
+            || query.until.is_some_and(|until| commit_time > until)
+        {
+            continue;
+        }
+
+        // Author
+        let author = commit
+            .author()
+            .map_err(GitError::gix)?
+            .to_owned()
+            .map_err(GitError::gix)?;
+
+        if let Some(ref needle) = author_needle {
+            let name = author.name.to_str_lossy().to_lowercase();
+            let email = author.email.to_str_lossy().to_lowercase();
+            if email == *needle || name == *needle {
+                score += 2;
+            } else if name.contains(needle.as_str()) || email.contains(needle.as_str()) {
+                score += 1;
+            } else {
+                continue;
+            }
+        }
+
+        // Message
+        let message = super::encoding::commit_message(&commit)?;
+        let summary = message.lines().next().unwrap_or_default().trim().to_string();
+
+        let mut summary_match = false;
+        if let Some(ref re) = query.message {
+            if re.is_match(&summary) {
+                summary_match = true;
+                score += 3;
+            } else if re.is_match(&message) {
+                score += 1;
+            } else {
+                continue;
+            }
+        }
+
+        // Path (tree diff)
+        if let Some(ref path) = query.path
+            && !crate::operations::log::commit_touches_path(repo, &commit, path)?
+        {
+            continue;
+        }
+
+        // Pickaxe (blob contents, most expensive)
+        let mut pickaxe_paths = Vec::new();
+        if let Some(ref needle) = query.pickaxe {
+            pickaxe_paths = pickaxe_changes(repo, &commit, needle.as_bytes())?;
+            if pickaxe_paths.is_empty() {
+                continue;
+            }
+            score += 2 + u32::try_from(pickaxe_paths.len().min(8)).unwrap_or(8);
+        }
+
+        matches.push(CommitMatch {
+            id: info.id.to_string(),
+            short_id: info.id.to_hex_with_len(7).to_string(),
+            summary,
+            author: Signature::try_from(author)?,
+            time: commit_time,
+            score,
+            summary_match,
+            pickaxe_paths,
+        });
+    }
+
+    // Rank: strongest match first, newest first on ties
+    matches.sort_by(|a, b| b.score.cmp(&a.score).then(b.time.cmp(&a.time)));
+    matches.truncate(query.limit);
+
+    Ok(CommitSearchResult {
+        total_examined,
+        truncated,
+        commits: matches,
+    })
+}
+
+/// Files in `commit` (against its first parent) where the occurrence count of `needle` changed
+fn pickaxe_changes(
+    repo: &gix::Repository,
+    commit: &gix::Commit<'_>,
+    needle: &[u8],
+) -> GitResult<Vec<String>> {
+    let tree = commit.tree().map_err(GitError::gix)?;
+    let parent_tree = match commit.parent_ids().next() {
+        Some(parent_id) => repo
+            .find_object(parent_id.detach())
+            .map_err(GitError::gix)?
+            .try_into_commit()
+            .map_err(GitError::gix)?
+            .tree()
+            .map_err(GitError::gix)?,
+        None => repo.empty_tree(),
+    };
+
+    // Collect changed blobs first; blob lookups can't happen inside the diff callback
+    let mut changed: Vec<(String, Option<gix::ObjectId>, Option<gix::ObjectId>)> = Vec::new();
+    let mut diff_platform = parent_tree.changes().map_err(GitError::gix)?;
+
+    diff_platform
+        .for_each_to_obtain_tree(&tree, |change| {
+            use gix::object::tree::diff::{Action, Change};
+
+            match change {
+                Change::Addition { location, entry_mode, id, .. } if entry_mode.is_blob() => {
+                    changed.push((location.to_string(), None, Some(id.detach())));
+                }
+                Change::Deletion { location, entry_mode, id, .. } if entry_mode.is_blob() => {
+                    changed.push((location.to_string(), Some(id.detach()), None));
+                }
+                Change::Modification {
+                    location,
+                    entry_mode,
+                    previous_id,
+                    id,
+                    ..
+                } if entry_mode.is_blob() => {
+                    changed.push((location.to_string(), Some(previous_id.detach()), Some(id.detach())));
+                }
+                Change::Rewrite {
+                    location,
+                    entry_mode,
+                    source_id,
+                    id,
+                    ..
+                } if entry_mode.is_blob() => {
+                    changed.push((location.to_string(), Some(source_id.detach()), Some(id.detach())));
+                }
+                _ => {}
+            }
+
+            Ok::<Action, std::convert::Infallible>(Action::Continue)
+        })
+        .map_err(GitError::gix)?;
+
+    let mut paths = Vec::new();
+    for (location, old_id, new_id) in changed {
+        let before = count_occurrences(repo, old_id, needle)?;
+        let after = count_occurrences(repo, new_id, needle)?;
+        if before != after {
+            paths.push(location);
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Number of occurrences of `needle` in a blob (0 for a missing side)
+fn count_occurrences(
+    repo: &gix::Repository,
+    id: Option<gix::ObjectId>,
+    needle: &[u8],
+) -> GitResult<usize> {
+    let Some(id) = id else {
+        return Ok(0);
+    };
+
+    let blob = repo
+        .find_object(id)
+        .map_err(GitError::gix)?
+        .try_into_blob()
+        .map_err(GitError::gix)?;
+
+    Ok(blob.data.find_iter(needle).count())
+}
@@ -0,0 +1,68 @@
+//! Shallow repositories: detecting the shallow boundary and deepening history.
+//!
+//! A shallow clone lacks the parents of its boundary commits, which are
+//! listed in `.git/shallow`. History walks end at those commits as if they
+//! were roots, so results that stop there are incomplete rather than the
+//! beginning of the project. [`deepen`] fetches more of the history.
+
+use std::collections::HashSet;
+
+use gix::remote::fetch::Shallow;
+
+use crate::{CommitId, FetchOpts, GitError, GitResult, RepoHandle};
+
+/// How much history to add to a shallow repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deepen {
+    /// Fetch this many more commits below the current boundary.
+    By(u32),
+    /// Fetch the complete history (`git fetch --unshallow`).
+    Unshallow,
+}
+
+impl Deepen {
+    pub(crate) fn to_shallow(self) -> Shallow {
+        match self {
+            Self::By(depth) => Shallow::Deepen(depth),
+            Self::Unshallow => Shallow::undo(),
+        }
+    }
+}
+
+/// Deepen a shallow repository by fetching from `remote`.
+///
+/// Returns the commits that still form the shallow boundary, sorted; an
+/// empty list means the history is now complete.
+pub async fn deepen(repo: RepoHandle, remote: &str, by: Deepen) -> GitResult<Vec<CommitId>> {
+    if by == Deepen::By(0) {
+        return Err(GitError::InvalidInput(
+            "Deepen depth must be greater than 0".to_string(),
+        ));
+    }
+    if !repo.raw().is_shallow() {
+        return Err(GitError::InvalidInput(
+            "Repository is not shallow; its history is already complete".to_string(),
+        ));
+    }
+
+    super::fetch(repo.clone(), FetchOpts::from_remote(remote).deepen(by)).await?;
+
+    let repo_inner = repo.clone_inner();
+    tokio::task::spawn_blocking(move || {
+        let mut boundary: Vec<CommitId> = shallow_boundary(&repo_inner)?.into_iter().collect();
+        boundary.sort();
+        Ok(boundary)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Commits whose parents are missing because `repo` is shallow
+///
+/// Empty for complete repositories.
+pub(crate) fn shallow_boundary(repo: &gix::Repository) -> GitResult<HashSet<CommitId>> {
+    let commits = repo.shallow_commits().map_err(GitError::gix)?;
+    Ok(commits
+        .map(|commits| commits.iter().copied().collect())
+        .unwrap_or_default())
+}
@@ -0,0 +1,438 @@
+//! Worktree snapshots: checkpoint and restore local changes outside the stash.
+//!
+//! [`snapshot_worktree`] records the index and the working tree (tracked
+//! modifications plus untracked, non-ignored files) as commits under
+//! `refs/snapshots/<id>`, laid out like a stash entry: the snapshot commit's
+//! tree is the working tree and its parents are `HEAD` and a commit holding
+//! the index. Taking a snapshot leaves the working tree untouched, and
+//! [`restore_snapshot`] puts both states back without moving `HEAD`.
+//! Neither touches `refs/stash`.
+//!
+//! File contents are recorded exactly as they are on disk; clean and smudge
+//! filters are not applied.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use gix::bstr::{BStr, BString, ByteSlice};
+use gix::object::tree::EntryKind;
+use gix::refs::transaction::PreviousValue;
+
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Namespace holding snapshot references
+const SNAPSHOT_PREFIX: &str = "refs/snapshots/";
+
+/// Message used when none is given
+const DEFAULT_MESSAGE: &str = "snapshot";
+
+/// A recorded worktree snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotInfo {
+    /// Identifier accepted by [`restore_snapshot`] and [`drop_snapshot`].
+    pub id: String,
+    /// The snapshot commit; its tree is the recorded working tree.
+    pub commit_id: CommitId,
+    /// `HEAD` when the snapshot was taken, `None` on an unborn branch.
+    pub head: Option<CommitId>,
+    /// Message given when taking the snapshot, `snapshot` by default.
+    pub message: String,
+    /// When the snapshot was taken (its commit time).
+    pub time: DateTime<Utc>,
+}
+
+/// Result of restoring a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotRestoreResult {
+    /// The snapshot that was restored.
+    pub snapshot: SnapshotInfo,
+    /// Files written to the working tree.
+    pub files_written: usize,
+    /// Files removed because the snapshot does not contain them.
+    pub files_removed: usize,
+    /// Whether `HEAD` still points where it did when the snapshot was taken.
+    pub head_matches: bool,
+}
+
+/// Record the index and working tree under `refs/snapshots/<id>`.
+///
+/// Nothing in the working tree, index or stash is modified. Fails when the
+/// index has unresolved conflicts.
+pub async fn snapshot_worktree(repo: RepoHandle, message: Option<&str>) -> GitResult<SnapshotInfo> {
+    let repo_inner = repo.clone_inner();
+    let message = message.unwrap_or(DEFAULT_MESSAGE).to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let workdir = repo_inner
+            .workdir()
+            .ok_or_else(|| GitError::InvalidInput("Cannot snapshot a bare repository".to_string()))?
+            .to_path_buf();
+        let head = repo_inner
+            .head()
+            .map_err(GitError::gix)?
+            .id()
+            .map(gix::Id::detach);
+
+        let index = repo_inner.index_or_empty().map_err(GitError::gix)?;
+        if index.entries().iter().any(|entry| entry.stage_raw() != 0) {
+            return Err(GitError::InvalidInput(
+                "Cannot snapshot with unresolved conflicts in the index".to_string(),
+            ));
+        }
+
+        let mut editor = repo_inner
+            .edit_tree(gix::ObjectId::empty_tree(repo_inner.object_hash()))
+            .map_err(GitError::gix)?;
+        for entry in index.entries() {
+            let Some(mode) = entry.mode.to_tree_entry_mode() else {
+                continue;
+            };
+            editor
+                .upsert(entry.path(&index), mode.kind(), entry.id)
+                .map_err(GitError::gix)?;
+        }
+        let index_tree = editor.write().map_err(GitError::gix)?.detach();
+
+        // The working tree is the index plus whatever differs on disk
+        let (modified, untracked) = local_changes(&repo_inner)?;
+        for path in modified.iter().chain(&untracked) {
+            match disk_blob(&repo_inner, &workdir, path.as_ref())? {
+                Some((kind, id)) => editor.upsert(path, kind, id),
+                None => editor.remove(path),
+            }
+            .map_err(GitError::gix)?;
+        }
+        let worktree_tree = editor.write().map_err(GitError::gix)?.detach();
+
+        let index_commit = write_commit(
+            &repo_inner,
+            index_tree,
+            head.into_iter().collect(),
+            &format!("index on {message}"),
+        )?;
+        let commit_id = write_commit(
+            &repo_inner,
+            worktree_tree,
+            head.into_iter().chain([index_commit]).collect(),
+            &message,
+        )?;
+
+        let id = commit_id.to_hex_with_len(12).to_string();
+        repo_inner
+            .reference(
+                format!("{SNAPSHOT_PREFIX}{id}").as_str(),
+                commit_id,
+                PreviousValue::MustNotExist,
+                format!("snapshot: {message}"),
+            )
+            .map_err(GitError::gix)?;
+
+        read_info(&repo_inner, id, commit_id)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Put the index and working tree back to the state recorded in snapshot `id`.
+///
+/// Tracked and untracked files the snapshot does not contain are removed;
+/// ignored files are left alone. `HEAD` is not moved, so if it changed since
+/// the snapshot was taken, the restored files show up as changes against the
+/// new `HEAD` (see [`SnapshotRestoreResult::head_matches`]). The snapshot is
+/// kept; remove it with [`drop_snapshot`].
+pub async fn restore_snapshot(repo: RepoHandle, id: &str) -> GitResult<SnapshotRestoreResult> {
+    let repo_inner = repo.clone_inner();
+    let id = id.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let workdir = repo_inner
+            .workdir()
+            .ok_or_else(|| {
+                GitError::InvalidInput("Cannot restore a snapshot in a bare repository".to_string())
+            })?
+            .to_path_buf();
+
+        let (id, commit_id) = find_snapshot(&repo_inner, &id)?;
+        let snapshot = read_info(&repo_inner, id, commit_id)?;
+        let commit = repo_inner.find_commit(commit_id).map_err(GitError::gix)?;
+        let worktree_tree = commit.tree_id().map_err(GitError::gix)?.detach();
+        let index_commit = commit.parent_ids().last().ok_or_else(|| {
+            GitError::InvalidInput(format!("Snapshot '{}' has no index commit", snapshot.id))
+        })?;
+        let index_tree = repo_inner
+            .find_commit(index_commit)
+            .map_err(GitError::gix)?
+            .tree_id()
+            .map_err(GitError::gix)?
+            .detach();
+
+        // Everything present now, to remove what the snapshot lacks afterwards
+        let current_index = repo_inner.index_or_empty().map_err(GitError::gix)?;
+        let (_, untracked) = local_changes(&repo_inner)?;
+
+        let mut worktree_index = repo_inner
+            .index_from_tree(&worktree_tree)
+            .map_err(GitError::gix)?;
+        let mut checkout_opts = repo_inner
+            .checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)
+            .map_err(GitError::gix)?;
+        checkout_opts.overwrite_existing = true;
+        checkout_opts.destination_is_initially_empty = false;
+
+        let outcome = gix::worktree::state::checkout(
+            &mut worktree_index,
+            &workdir,
+            repo_inner
+                .objects
+                .clone()
+                .into_arc()
+                .map_err(GitError::gix)?,
+            &gix::progress::Discard,
+            &gix::progress::Discard,
+            &std::sync::atomic::AtomicBool::new(false),
+            checkout_opts,
+        )
+        .map_err(GitError::gix)?;
+        if !outcome.errors.is_empty() || !outcome.collisions.is_empty() {
+            return Err(GitError::InvalidInput(format!(
+                "Restoring snapshot '{}' encountered {} error(s) and {} collision(s)",
+                snapshot.id,
+                outcome.errors.len(),
+                outcome.collisions.len()
+            )));
+        }
+
+        let mut files_removed = 0;
+        let current_paths = current_index
+            .entries()
+            .iter()
+            .map(|entry| entry.path(&current_index))
+            .chain(untracked.iter().map(|path| path.as_bstr()));
+        for path in current_paths {
+            if worktree_index.entry_by_path(path).is_some() {
+                continue;
+            }
+            match std::fs::remove_file(workdir.join(gix::path::from_bstr(path))) {
+                Ok(()) => files_removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(GitError::Io(e)),
+            }
+        }
+
+        repo_inner
+            .index_from_tree(&index_tree)
+            .map_err(GitError::gix)?
+            .write(Default::default())
+            .map_err(GitError::gix)?;
+
+        let head = repo_inner
+            .head()
+            .map_err(GitError::gix)?
+            .id()
+            .map(gix::Id::detach);
+
+        Ok(SnapshotRestoreResult {
+            files_written: worktree_index.entries().len(),
+            files_removed,
+            head_matches: head == snapshot.head,
+            snapshot,
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// List snapshots, newest first.
+pub async fn list_snapshots(repo: RepoHandle) -> GitResult<Vec<SnapshotInfo>> {
+    let repo_inner = repo.clone_inner();
+
+    tokio::task::spawn_blocking(move || {
+        let platform = repo_inner.references().map_err(GitError::gix)?;
+        let references = platform.prefixed(SNAPSHOT_PREFIX).map_err(GitError::gix)?;
+
+        let mut snapshots = Vec::new();
+        for reference in references {
+            let mut reference = reference.map_err(GitError::gix)?;
+            let Some(id) = reference
+                .name()
+                .as_bstr()
+                .strip_prefix(SNAPSHOT_PREFIX.as_bytes())
+                .map(|id| id.to_str_lossy().into_owned())
+            else {
+                continue;
+            };
+            let commit_id = reference.peel_to_id().map_err(GitError::gix)?.detach();
+            snapshots.push(read_info(&repo_inner, id, commit_id)?);
+        }
+
+        snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.time));
+        Ok(snapshots)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Delete snapshot `id`.
+pub async fn drop_snapshot(repo: RepoHandle, id: &str) -> GitResult<()> {
+    let repo_inner = repo.clone_inner();
+    let id = id.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let name = snapshot_ref(&id);
+        repo_inner
+            .try_find_reference(name.as_str())
+            .map_err(GitError::gix)?
+            .ok_or(GitError::ReferenceNotFound(name))?
+            .delete()
+            .map_err(GitError::gix)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Full reference name for `id`, which may already be one
+fn snapshot_ref(id: &str) -> String {
+    if id.starts_with(SNAPSHOT_PREFIX) {
+        id.to_string()
+    } else {
+        format!("{SNAPSHOT_PREFIX}{id}")
+    }
+}
+
+/// Resolve `id` to its short id and snapshot commit
+fn find_snapshot(repo: &gix::Repository, id: &str) -> GitResult<(String, CommitId)> {
+    let name = snapshot_ref(id);
+    let commit_id = repo
+        .try_find_reference(name.as_str())
+        .map_err(GitError::gix)?
+        .ok_or_else(|| GitError::ReferenceNotFound(name.clone()))?
+        .peel_to_id()
+        .map_err(GitError::gix)?
+        .detach();
+    let id = name.trim_start_matches(SNAPSHOT_PREFIX).to_string();
+    Ok((id, commit_id))
+}
+
+fn read_info(repo: &gix::Repository, id: String, commit_id: CommitId) -> GitResult<SnapshotInfo> {
+    let commit = repo.find_commit(commit_id).map_err(GitError::gix)?;
+    let parents: Vec<CommitId> = commit.parent_ids().map(gix::Id::detach).collect();
+    let seconds = commit.time().map_err(GitError::gix)?.seconds;
+    let time = DateTime::from_timestamp(seconds, 0).ok_or_else(|| {
+        GitError::InvalidInput(format!("Invalid timestamp {seconds} for snapshot {id}"))
+    })?;
+
+    Ok(SnapshotInfo {
+        commit_id,
+        // Snapshots taken on an unborn branch only have the index parent
+        head: (parents.len() == 2).then(|| parents[0]),
+        message: commit.message_raw_sloppy().to_str_lossy().trim_end().to_string(),
+        time,
+        id,
+    })
+}
+
+/// Tracked paths that differ on disk, and untracked non-ignored files
+fn local_changes(repo: &gix::Repository) -> GitResult<(Vec<BString>, Vec<BString>)> {
+    use gix::dir::entry::{Kind, Status};
+    use gix::status::index_worktree::Item;
+
+    let iter = repo
+        .status(gix::progress::Discard)
+        .map_err(GitError::gix)?
+        .untracked_files(gix::status::UntrackedFiles::Files)
+        .index_worktree_rewrites(None)
+        .into_index_worktree_iter(Vec::<BString>::new())
+        .map_err(GitError::gix)?;
+
+    let mut modified = Vec::new();
+    let mut untracked = Vec::new();
+    for item in iter {
+        match item.map_err(GitError::gix)? {
+            // Submodules are recorded at the commit the index points to
+            Item::Modification { entry, rela_path, .. }
+                if entry.mode != gix::index::entry::Mode::COMMIT =>
+            {
+                modified.push(rela_path);
+            }
+            Item::DirectoryContents { entry, .. }
+                if entry.status == Status::Untracked
+                    && matches!(entry.disk_kind, Some(Kind::File | Kind::Symlink)) =>
+            {
+                untracked.push(entry.rela_path);
+            }
+            _ => {}
+        }
+    }
+    Ok((modified, untracked))
+}
+
+/// Write the file at `rela_path` as a blob, `None` if it no longer exists
+fn disk_blob(
+    repo: &gix::Repository,
+    workdir: &Path,
+    rela_path: &BStr,
+) -> GitResult<Option<(EntryKind, gix::ObjectId)>> {
+    let path = workdir.join(gix::path::from_bstr(rela_path));
+    let metadata = match gix::index::fs::Metadata::from_path_no_follow(&path) {
+        Ok(metadata) => metadata,
+        Err(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::NotADirectory
+            ) =>
+        {
+            return Ok(None);
+        }
+        Err(e) => return Err(GitError::Io(e)),
+    };
+
+    let (kind, data) = if metadata.is_symlink() {
+        let target = std::fs::read_link(&path)?;
+        (EntryKind::Link, gix::path::into_bstr(target).to_vec())
+    } else if metadata.is_file() {
+        let kind = if metadata.is_executable() {
+            EntryKind::BlobExecutable
+        } else {
+            EntryKind::Blob
+        };
+        (kind, std::fs::read(&path)?)
+    } else {
+        return Ok(None);
+    };
+
+    let id = repo.write_blob(&data).map_err(GitError::gix)?.detach();
+    Ok(Some((kind, id)))
+}
+
+/// Write a commit object without updating any reference
+fn write_commit(
+    repo: &gix::Repository,
+    tree: gix::ObjectId,
+    parents: Vec<CommitId>,
+    message: &str,
+) -> GitResult<CommitId> {
+    let config = repo.config_snapshot();
+    let field = |key: &str, fallback: &str| -> BString {
+        config
+            .string(key)
+            .map_or_else(|| fallback.into(), std::borrow::Cow::into_owned)
+    };
+    let signature = gix::actor::Signature {
+        name: field("user.name", "kodegen"),
+        email: field("user.email", "kodegen@localhost"),
+        time: gix::date::Time::now_local_or_utc(),
+    };
+
+    let commit = gix::objs::Commit {
+        tree,
+        parents: parents.into_iter().collect(),
+        author: signature.clone(),
+        committer: signature,
+        encoding: None,
+        message: message.into(),
+        extra_headers: Vec::new(),
+    };
+    repo.write_object(&commit)
+        .map(gix::Id::detach)
+        .map_err(GitError::gix)
+}
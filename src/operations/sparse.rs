@@ -0,0 +1,134 @@
+//! Sparse index support.
+//!
+//! In a cone-mode sparse checkout with `index.sparse` set, git collapses every
+//! directory outside the cone into a single index entry naming its tree, so the
+//! index holds the populated paths rather than the whole repository. Status
+//! compares those entries with `HEAD` by tree id. Through a handle opened with
+//! [`RepoHandle::with_sparse_index`](crate::RepoHandle::with_sparse_index)
+//! `add`, `commit` and `apply_to_index` keep them collapsed too; without it a
+//! sparse index is expanded to one entry per file when read, as git does for
+//! commands that aren't sparse-aware, which is correct but costs time in the
+//! repository's size.
+
+use gix::bstr::{BStr, BString, ByteSlice};
+use gix::index::entry::{Flags, Mode, Stat};
+
+use crate::{GitError, GitResult};
+
+/// Whether `entry` is a directory collapsed by a sparse index
+#[inline]
+pub(crate) fn is_sparse_dir(entry: &gix::index::Entry) -> bool {
+    entry.mode == Mode::DIR
+}
+
+/// Whether `path` lies in a directory collapsed by the sparse index `index`
+pub(crate) fn in_sparse_dir(index: &gix::index::State, path: &BStr) -> bool {
+    index.is_sparse()
+        && path
+            .find_iter("/")
+            .map(|slash| &path[..slash + 1])
+            .any(|dir| index.entry_by_path(dir).is_some_and(is_sparse_dir))
+}
+
+/// Replace the collapsed directories of a sparse index by the files below
+/// them, marked skip-worktree as git marks them when expanding.
+pub(crate) fn expand(repo: &gix::Repository, index: &mut gix::index::File) -> GitResult<()> {
+    let state: &gix::index::State = index;
+    let dirs: Vec<(BString, gix::ObjectId)> = state
+        .entries()
+        .iter()
+        .filter(|entry| is_sparse_dir(entry))
+        .map(|entry| (entry.path(state).to_owned(), entry.id))
+        .collect();
+    if dirs.is_empty() {
+        return Ok(());
+    }
+
+    for (dir, tree) in dirs {
+        let files = repo.index_from_tree(&tree).map_err(GitError::gix)?;
+        for file in files.entries() {
+            let mut path = dir.clone();
+            if !path.ends_with(b"/") {
+                path.push(b'/');
+            }
+            path.extend_from_slice(file.path(&files));
+            index.dangerously_push_entry(
+                Stat::default(),
+                file.id,
+                Flags::SKIP_WORKTREE | Flags::EXTENDED,
+                file.mode,
+                path.as_bstr(),
+            );
+        }
+    }
+    index.remove_entries(|_, _, entry| is_sparse_dir(entry));
+    index.sort_entries();
+    Ok(())
+}
+
+/// Whether the sparse index `index` differs from the tree `head_tree`.
+///
+/// Walks the tree alongside the index and stops at collapsed directories,
+/// whose tree id is compared instead, so directories outside the cone are
+/// never read.
+pub(crate) fn staged_changes(
+    repo: &gix::Repository,
+    head_tree: gix::ObjectId,
+    index: &gix::index::State,
+) -> GitResult<bool> {
+    if index.entries().iter().any(|entry| entry.stage_raw() != 0) {
+        return Ok(true);
+    }
+    let mut matched = 0;
+    let differs = tree_differs(
+        repo,
+        head_tree,
+        &mut BString::default(),
+        index,
+        &mut matched,
+    )?;
+    // Index entries the tree has no counterpart for are additions
+    Ok(differs || matched != index.entries().len())
+}
+
+/// Whether an entry below `prefix` in `tree` differs from the index, counting
+/// the index entries it matched
+fn tree_differs(
+    repo: &gix::Repository,
+    tree: gix::ObjectId,
+    prefix: &mut BString,
+    index: &gix::index::State,
+    matched: &mut usize,
+) -> GitResult<bool> {
+    let tree = repo.find_tree(tree).map_err(GitError::gix)?;
+    let decoded = tree.decode().map_err(GitError::gix)?;
+    for entry in &decoded.entries {
+        let len = prefix.len();
+        prefix.extend_from_slice(entry.filename);
+        let differs = if entry.mode.is_tree() {
+            prefix.push(b'/');
+            match index.entry_by_path(prefix.as_bstr()) {
+                Some(dir) if is_sparse_dir(dir) => {
+                    *matched += 1;
+                    &*dir.id != entry.oid
+                }
+                _ => tree_differs(repo, entry.oid.to_owned(), prefix, index, matched)?,
+            }
+        } else {
+            match index.entry_by_path(prefix.as_bstr()) {
+                Some(file) => {
+                    *matched += 1;
+                    &*file.id != entry.oid
+                        || file.mode.to_tree_entry_mode().map(|mode| mode.kind())
+                            != Some(entry.mode.kind())
+                }
+                None => true,
+            }
+        };
+        prefix.truncate(len);
+        if differs {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
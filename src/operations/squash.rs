@@ -0,0 +1,182 @@
+//! Squash merge onto a base branch.
+//!
+//! [`squash_merge`] combines everything `head` adds on top of `base` into one
+//! commit whose only parent is the tip of `base`. The merge happens on
+//! objects only, so `base` does not need to be checked out; when it is
+//! checked out in this worktree, the index and working tree follow along.
+
+use gix::bstr::ByteSlice;
+
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Result of a squash merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SquashMergeResult {
+    /// The new commit on `base`.
+    pub commit_id: CommitId,
+    /// Full name of the updated branch (e.g. `refs/heads/main`).
+    pub base_ref: String,
+    /// Tip of `base` before the squash (the new commit's parent).
+    pub previous_base: CommitId,
+    /// Number of `head` commits folded into the new commit.
+    pub squashed_commits: usize,
+    /// Whether the index and working tree were updated because `base` is checked out here.
+    pub worktree_updated: bool,
+}
+
+/// Squash `head` onto the local branch `base` as a single commit.
+///
+/// Fails with [`GitError::MergeConflict`] if the changes conflict, when they
+/// add nothing to `base` (as after squashing `head` before), with
+/// [`GitError::BranchInUse`] if `base` is checked out in another worktree,
+/// and when `base` is checked out here but the working tree has local
+/// changes; in all these cases nothing is modified.
+pub async fn squash_merge(
+    repo: RepoHandle,
+    base: &str,
+    head: &str,
+    message: &str,
+) -> GitResult<SquashMergeResult> {
+    let repo_inner = repo.clone_inner();
+    let base = base.strip_prefix("refs/heads/").unwrap_or(base).to_string();
+    let head = head.to_string();
+    let message = message.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let base_ref = format!("refs/heads/{base}");
+        let previous_base = repo_inner
+            .try_find_reference(base_ref.as_str())
+            .map_err(GitError::gix)?
+            .ok_or_else(|| GitError::BranchNotFound(base.clone()))?
+            .peel_to_id()
+            .map_err(GitError::gix)?
+            .detach();
+        let head_id = repo_inner
+            .rev_parse_single(head.as_bytes().as_bstr())
+            .map_err(GitError::gix)?
+            .object()
+            .map_err(GitError::gix)?
+            .peel_to_commit()
+            .map_err(GitError::gix)?
+            .id;
+
+        let merge_base = repo_inner
+            .merge_base(previous_base, head_id)
+            .map_err(GitError::gix)?
+            .detach();
+        if merge_base == head_id {
+            return Err(GitError::InvalidInput(format!(
+                "'{head}' is already merged into '{base}'"
+            )));
+        }
+
+        let squashed_commits = repo_inner
+            .rev_walk([head_id])
+            .with_hidden([previous_base])
+            .all()
+            .map_err(GitError::gix)?
+            .count();
+
+        let tree_id = if merge_base == previous_base {
+            // Base has not moved: the result is exactly head's tree
+            repo_inner
+                .find_commit(head_id)
+                .map_err(GitError::gix)?
+                .tree_id()
+                .map_err(GitError::gix)?
+                .detach()
+        } else {
+            merged_tree(&repo_inner, previous_base, head_id, &head)?
+        };
+        // Squashing never leaves head merged, so catch repeats by their result
+        let base_tree = repo_inner
+            .find_commit(previous_base)
+            .map_err(GitError::gix)?
+            .tree_id()
+            .map_err(GitError::gix)?;
+        if tree_id == base_tree {
+            return Err(GitError::InvalidInput(format!(
+                "'{head}' adds nothing to '{base}'; was it squashed already?"
+            )));
+        }
+
+        // Moving a branch checked out elsewhere would leave that worktree stale
+        let checked_out_here = repo_inner
+            .head_name()
+            .map_err(GitError::gix)?
+            .is_some_and(|name| name.as_bstr() == base_ref.as_bytes().as_bstr());
+        let workdir = if checked_out_here {
+            if super::fast_status::is_dirty_fast(&repo_inner)? {
+                return Err(GitError::InvalidInput(format!(
+                    "Cannot squash onto checked-out branch '{base}' with local changes"
+                )));
+            }
+            repo_inner.workdir().map(std::path::Path::to_path_buf)
+        } else {
+            super::worktree::check_branch_not_in_use(&repo_inner, &base)?;
+            None
+        };
+
+        // Update the worktree first, so a refused checkout leaves the branch alone
+        if let Some(workdir) = &workdir {
+            super::merge::sync_worktree_to_tree(&repo_inner, workdir, tree_id, "Squash")?;
+        }
+
+        let commit_id = repo_inner
+            .commit(base_ref.as_str(), &message, tree_id, [previous_base])
+            .map_err(GitError::gix)?
+            .detach();
+
+        Ok(SquashMergeResult {
+            commit_id,
+            base_ref,
+            previous_base,
+            squashed_commits,
+            worktree_updated: workdir.is_some(),
+        })
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Merge `head` into `base` in memory and write the resulting tree
+fn merged_tree(
+    repo: &gix::Repository,
+    base: CommitId,
+    head: CommitId,
+    head_name: &str,
+) -> GitResult<gix::ObjectId> {
+    use gix::merge::blob::builtin_driver::text::Labels;
+    use gix::merge::tree::TreatAsUnresolved;
+
+    let options: gix::merge::commit::Options =
+        repo.tree_merge_options().map_err(GitError::gix)?.into();
+    let labels = Labels {
+        ancestor: None,
+        current: Some("HEAD".into()),
+        other: Some(head_name.into()),
+    };
+
+    let mut outcome = repo
+        .merge_commits(base, head, labels, options)
+        .map_err(GitError::gix)?;
+
+    if outcome
+        .tree_merge
+        .has_unresolved_conflicts(TreatAsUnresolved::default())
+    {
+        let paths = super::merge::unresolved_conflict_paths(&outcome.tree_merge);
+        return Err(GitError::MergeConflict {
+            message: format!("Squashing '{head_name}' conflicts in: {}", paths.join(", ")),
+            paths,
+        });
+    }
+
+    let tree_id = outcome
+        .tree_merge
+        .tree
+        .write()
+        .map_err(GitError::gix)?
+        .detach();
+    Ok(tree_id)
+}
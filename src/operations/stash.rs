@@ -1,6 +1,10 @@
 //! Git stash operations
+//!
+//! Stash uses the git CLI (gix has no stash API); with the `pure-gix` feature
+//! both operations return [`GitError::Unsupported`].
 
 use crate::{GitError, GitResult, RepoHandle};
+#[cfg(not(feature = "pure-gix"))]
 use gix::bstr::ByteSlice;
 
 /// Options for stash save
@@ -24,14 +28,21 @@ pub struct StashInfo {
 }
 
 /// Save working directory changes to stash
+#[cfg(feature = "pure-gix")]
+pub async fn stash_save(_repo: RepoHandle, _opts: StashOpts) -> GitResult<StashInfo> {
+    Err(GitError::Unsupported(
+        "stash requires the git CLI and is unavailable with the pure-gix feature",
+    ))
+}
+
+/// Save working directory changes to stash
+#[cfg(not(feature = "pure-gix"))]
 pub async fn stash_save(repo: RepoHandle, opts: StashOpts) -> GitResult<StashInfo> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
         // Check if there are changes to stash
-        let is_dirty = repo_clone
-            .is_dirty()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        let is_dirty = repo_clone.is_dirty().map_err(GitError::gix)?;
 
         if !is_dirty {
             return Err(GitError::InvalidInput(
@@ -40,7 +51,7 @@ pub async fn stash_save(repo: RepoHandle, opts: StashOpts) -> GitResult<StashInf
         }
 
         // Get current branch for context
-        let head = repo_clone.head().map_err(|e| GitError::Gix(Box::new(e)))?;
+        let head = repo_clone.head().map_err(GitError::gix)?;
         let branch_name = head
             .referent_name()
             .and_then(|name| {
@@ -65,7 +76,7 @@ pub async fn stash_save(repo: RepoHandle, opts: StashOpts) -> GitResult<StashInf
 
         // Create stash via git stash command
         // (Using command-line as gix doesn't have direct stash API)
-        let mut cmd = std::process::Command::new("git");
+        let mut cmd = crate::operations::auth::git_command();
         cmd.arg("stash")
             .arg("push")
             .arg("-m")
@@ -89,7 +100,7 @@ pub async fn stash_save(repo: RepoHandle, opts: StashOpts) -> GitResult<StashInf
         }
 
         // Get the stash commit hash
-        let list_output = std::process::Command::new("git")
+        let list_output = crate::operations::auth::git_command()
             .arg("stash")
             .arg("list")
             .arg("-1")
@@ -114,10 +125,19 @@ pub async fn stash_save(repo: RepoHandle, opts: StashOpts) -> GitResult<StashInf
         })
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
+}
+
+/// Apply and remove stash entry
+#[cfg(feature = "pure-gix")]
+pub async fn stash_pop(_repo: RepoHandle, _stash_name: Option<&str>) -> GitResult<()> {
+    Err(GitError::Unsupported(
+        "stash requires the git CLI and is unavailable with the pure-gix feature",
+    ))
 }
 
 /// Apply and remove stash entry
+#[cfg(not(feature = "pure-gix"))]
 pub async fn stash_pop(repo: RepoHandle, stash_name: Option<&str>) -> GitResult<()> {
     let repo_clone = repo.clone_inner();
     let stash_name = stash_name.unwrap_or("stash@{0}").to_string();
@@ -128,7 +148,7 @@ pub async fn stash_pop(repo: RepoHandle, stash_name: Option<&str>) -> GitResult<
             .workdir()
             .ok_or_else(|| GitError::InvalidInput("Repository has no working directory".to_string()))?;
 
-        let output = std::process::Command::new("git")
+        let output = crate::operations::auth::git_command()
             .arg("stash")
             .arg("pop")
             .arg(&stash_name)
@@ -147,5 +167,5 @@ pub async fn stash_pop(repo: RepoHandle, stash_name: Option<&str>) -> GitResult<
         Ok(())
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
@@ -2,8 +2,11 @@
 //!
 //! Provides functionality for checking repository state, branch information, and remote details.
 
+use super::introspection::{FetchHeadEntry, read_fetch_head};
+use super::warnings::{OperationWarning, WarningKind};
 use crate::{GitError, GitResult, RepoHandle};
-use gix::bstr::ByteSlice;
+use chrono::{DateTime, Utc};
+use gix::bstr::{BString, ByteSlice};
 
 /// Information about a Git branch
 #[derive(Debug, Clone)]
@@ -20,6 +23,8 @@ pub struct BranchInfo {
     pub ahead_count: Option<usize>,
     /// Number of commits behind upstream
     pub behind_count: Option<usize>,
+    /// Why the counts are missing although an upstream is configured
+    pub warnings: Vec<OperationWarning>,
 }
 
 /// Information about a Git remote
@@ -31,6 +36,17 @@ pub struct RemoteInfo {
     pub fetch_url: String,
     /// Push URL (may be different from fetch)
     pub push_url: String,
+    /// Fetch refspecs, e.g. `+refs/heads/*:refs/remotes/origin/*`
+    pub fetch_refspecs: Vec<String>,
+    /// Push refspecs (empty when pushing uses the defaults)
+    pub push_refspecs: Vec<String>,
+    /// Whether this is the remote the current branch fetches from, or the
+    /// repository's default remote if the branch has none configured
+    pub is_default: bool,
+    /// When `FETCH_HEAD` was last written by a fetch from this remote
+    pub last_fetched: Option<DateTime<Utc>>,
+    /// Parts of the remote's configuration that couldn't be read
+    pub warnings: Vec<OperationWarning>,
 }
 
 /// Check if the working directory is clean
@@ -48,25 +64,34 @@ pub struct RemoteInfo {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// if is_clean(&repo).await? {
+/// if is_clean(repo).await? {
 ///     println!("Working directory is clean");
 /// }
 /// # Ok(())
 /// # }
 /// ```
-pub async fn is_clean(repo: &RepoHandle) -> GitResult<bool> {
+pub async fn is_clean(repo: RepoHandle) -> GitResult<bool> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
+        // Collapsed directories of a sparse index are compared by tree id
+        if repo_clone
+            .index_or_empty()
+            .map_err(GitError::gix)?
+            .is_sparse()
+        {
+            let is_dirty = super::fast_status::staged_changes(&repo_clone)?
+                || super::fast_status::worktree_changes(&repo_clone, Vec::new())?;
+            return Ok(!is_dirty);
+        }
+
         // Use is_dirty() which is the proper API for checking if repo has changes
-        let is_dirty = repo_clone
-            .is_dirty()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        let is_dirty = repo_clone.is_dirty().map_err(GitError::gix)?;
 
         Ok(!is_dirty)
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
 
 /// Get information about the current branch
@@ -86,16 +111,16 @@ pub async fn is_clean(repo: &RepoHandle) -> GitResult<bool> {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// let branch = current_branch(&repo).await?;
+/// let branch = current_branch(repo).await?;
 /// println!("Current branch: {}", branch.name);
 /// # Ok(())
 /// # }
 /// ```
-pub async fn current_branch(repo: &RepoHandle) -> GitResult<BranchInfo> {
+pub async fn current_branch(repo: RepoHandle) -> GitResult<BranchInfo> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
-        let mut head = repo_clone.head().map_err(|e| GitError::Gix(Box::new(e)))?;
+        let mut head = repo_clone.head().map_err(GitError::gix)?;
 
         let branch_name = head
             .referent_name()
@@ -107,14 +132,14 @@ pub async fn current_branch(repo: &RepoHandle) -> GitResult<BranchInfo> {
             })
             .unwrap_or_else(|| "detached HEAD".to_string());
 
-        let commit = head
-            .peel_to_commit()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        let commit = head.peel_to_commit().map_err(GitError::gix)?;
 
         let commit_hash = commit.id().to_string();
 
         // Try to get upstream information
-        let (upstream, ahead_count, behind_count) = get_upstream_info(&repo_clone, &mut head)?;
+        let mut warnings = Vec::new();
+        let (upstream, ahead_count, behind_count) =
+            get_upstream_info(&repo_clone, &mut head, &mut warnings)?;
 
         Ok(BranchInfo {
             name: branch_name,
@@ -123,10 +148,11 @@ pub async fn current_branch(repo: &RepoHandle) -> GitResult<BranchInfo> {
             upstream,
             ahead_count,
             behind_count,
+            warnings,
         })
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
 
 /// Calculate ahead/behind commit counts between local and upstream branches
@@ -163,13 +189,13 @@ fn calculate_ahead_behind(
         match repo.try_find_reference(upstream_ref_path.as_bytes().as_bstr()) {
             Ok(Some(r)) => r,
             Ok(None) => return Ok((None, None)), // Upstream doesn't exist
-            Err(e) => return Err(GitError::Gix(Box::new(e))),
+            Err(e) => return Err(GitError::gix(e)),
         };
 
     // Get the upstream commit ID
     let upstream_commit_id = match upstream_reference.peel_to_id() {
         Ok(id) => id.detach(),
-        Err(e) => return Err(GitError::Gix(Box::new(e))),
+        Err(e) => return Err(GitError::gix(e)),
     };
 
     // If both commits are the same, return (0, 0)
@@ -177,6 +203,14 @@ fn calculate_ahead_behind(
         return Ok((Some(0), Some(0)));
     }
 
+    // Bitmaps answer both counts without walking either side
+    let range = format!("{local_commit_id}...{upstream_commit_id}");
+    if let Some(&[ahead, behind]) =
+        super::midx::bitmap_count(repo, &["--left-right", &range]).as_deref()
+    {
+        return Ok((Some(ahead), Some(behind)));
+    }
+
     // Find merge base (common ancestor)
     // Create a graph for merge base calculation
     let mut graph = repo.revision_graph(None);
@@ -187,7 +221,7 @@ fn calculate_ahead_behind(
             Err(e) => {
                 // If no merge base found (completely diverged histories),
                 // we can't calculate ahead/behind in a meaningful way
-                return Err(GitError::Gix(Box::new(e)));
+                return Err(GitError::gix(e));
             }
         };
 
@@ -224,27 +258,21 @@ fn count_commits_between(
     // Collect all commits reachable from 'from' (the merge base)
     let mut from_commits = std::collections::HashSet::new();
 
-    let from_walker = repo
-        .rev_walk([from])
-        .all()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let from_walker = repo.rev_walk([from]).all().map_err(GitError::gix)?;
 
     for commit_result in from_walker {
         match commit_result {
             Ok(info) => {
                 from_commits.insert(info.id);
             }
-            Err(e) => return Err(GitError::Gix(Box::new(e))),
+            Err(e) => return Err(GitError::gix(e)),
         }
     }
 
     // Count commits reachable from 'to' that are NOT in from_commits
     let mut count = 0;
 
-    let to_walker = repo
-        .rev_walk([to])
-        .all()
-        .map_err(|e| GitError::Gix(Box::new(e)))?;
+    let to_walker = repo.rev_walk([to]).all().map_err(GitError::gix)?;
 
     for commit_result in to_walker {
         match commit_result {
@@ -253,7 +281,7 @@ fn count_commits_between(
                     count += 1;
                 }
             }
-            Err(e) => return Err(GitError::Gix(Box::new(e))),
+            Err(e) => return Err(GitError::gix(e)),
         }
     }
 
@@ -261,9 +289,13 @@ fn count_commits_between(
 }
 
 /// Get upstream tracking information for a branch
-fn get_upstream_info(
+///
+/// Counts that can't be computed for a configured upstream are `None`, with
+/// the reason added to `warnings`.
+pub(crate) fn get_upstream_info(
     repo: &gix::Repository,
     head: &mut gix::Head,
+    warnings: &mut Vec<OperationWarning>,
 ) -> GitResult<(Option<String>, Option<usize>, Option<usize>)> {
     // Try to get upstream branch
     let upstream = if let Some(branch_ref) = head.referent_name() {
@@ -299,9 +331,16 @@ fn get_upstream_info(
         // Get the local commit ID from HEAD
         let local_commit_id = match head.peel_to_commit() {
             Ok(commit) => commit.id().detach(),
-            Err(_) => {
-                // If we can't get the commit (e.g., detached HEAD with invalid ref),
+            Err(e) => {
+                // If we can't get the commit (e.g., an unborn branch),
                 // return None for counts
+                warnings.push(
+                    OperationWarning::new(
+                        WarningKind::UpstreamUnavailable,
+                        format!("HEAD has no commit to compare ({e})"),
+                    )
+                    .subject(upstream_ref),
+                );
                 return Ok((upstream, None, None));
             }
         };
@@ -309,7 +348,29 @@ fn get_upstream_info(
         // Calculate ahead/behind counts
         // If calculation fails (e.g., no merge base), return None for counts
         // but still return the upstream ref name
-        calculate_ahead_behind(repo, local_commit_id, upstream_ref).unwrap_or_default()
+        match calculate_ahead_behind(repo, local_commit_id, upstream_ref) {
+            Ok((None, None)) => {
+                warnings.push(
+                    OperationWarning::new(
+                        WarningKind::UpstreamUnavailable,
+                        "upstream is configured but has not been fetched",
+                    )
+                    .subject(upstream_ref),
+                );
+                (None, None)
+            }
+            Ok(counts) => counts,
+            Err(e) => {
+                warnings.push(
+                    OperationWarning::new(
+                        WarningKind::UpstreamUnavailable,
+                        format!("ahead/behind counts could not be computed ({e})"),
+                    )
+                    .subject(upstream_ref),
+                );
+                (None, None)
+            }
+        }
     } else {
         // No upstream configured
         (None, None)
@@ -335,41 +396,124 @@ fn get_upstream_info(
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// let remotes = list_remotes(&repo).await?;
+/// let remotes = list_remotes(repo).await?;
 /// for remote in remotes {
 ///     println!("Remote: {} -> {}", remote.name, remote.fetch_url);
 /// }
 /// # Ok(())
 /// # }
 /// ```
-pub async fn list_remotes(repo: &RepoHandle) -> GitResult<Vec<RemoteInfo>> {
+pub async fn list_remotes(repo: RepoHandle) -> GitResult<Vec<RemoteInfo>> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
         let mut remotes = Vec::new();
+        let default_remote = default_remote(&repo_clone);
+        let fetch_head = last_fetch(&repo_clone);
 
         for remote_name in repo_clone.remote_names() {
-            if let Ok(remote) = repo_clone.find_remote(remote_name.as_ref()) {
-                let fetch_url = remote
-                    .url(gix::remote::Direction::Fetch)
-                    .map_or_else(|| "unknown".to_string(), std::string::ToString::to_string);
-
-                let push_url = remote
-                    .url(gix::remote::Direction::Push)
-                    .map_or_else(|| fetch_url.clone(), std::string::ToString::to_string);
-
-                remotes.push(RemoteInfo {
-                    name: remote_name.to_string(),
-                    fetch_url,
-                    push_url,
-                });
-            }
+            let name = remote_name.to_string();
+            let is_default = default_remote
+                .as_ref()
+                .is_some_and(|default| default == remote_name.as_ref());
+            let unreadable = |message: String| {
+                OperationWarning::new(WarningKind::UnreadableRemote, message).subject(&name)
+            };
+
+            // Still list a remote whose configuration is broken, as git does
+            let remote = match repo_clone.find_remote(remote_name.as_ref()) {
+                Ok(remote) => remote,
+                Err(e) => {
+                    remotes.push(RemoteInfo {
+                        warnings: vec![unreadable(format!(
+                            "configuration could not be read ({e}); URLs and refspecs left out"
+                        ))],
+                        name,
+                        fetch_url: "unknown".to_string(),
+                        push_url: "unknown".to_string(),
+                        fetch_refspecs: Vec::new(),
+                        push_refspecs: Vec::new(),
+                        is_default,
+                        last_fetched: None,
+                    });
+                    continue;
+                }
+            };
+
+            let mut warnings = Vec::new();
+            let fetch_url = match remote.url(gix::remote::Direction::Fetch) {
+                Some(url) => url.to_string(),
+                None => {
+                    warnings.push(unreadable("no fetch URL is configured".to_string()));
+                    "unknown".to_string()
+                }
+            };
+
+            let push_url = remote
+                .url(gix::remote::Direction::Push)
+                .map_or_else(|| fetch_url.clone(), std::string::ToString::to_string);
+
+            let refspecs = |direction| -> Vec<String> {
+                remote
+                    .refspecs(direction)
+                    .iter()
+                    .map(|spec| spec.to_ref().to_bstring().to_string())
+                    .collect()
+            };
+            let last_fetched = fetch_head.as_ref().and_then(|(entries, time)| {
+                fetched_from(entries, &name, &fetch_url).then_some(*time)
+            });
+
+            remotes.push(RemoteInfo {
+                is_default,
+                fetch_refspecs: refspecs(gix::remote::Direction::Fetch),
+                push_refspecs: refspecs(gix::remote::Direction::Push),
+                name,
+                fetch_url,
+                push_url,
+                last_fetched,
+                warnings,
+            });
         }
 
         Ok(remotes)
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
+}
+
+/// The remote the current branch fetches from (`branch.<name>.remote`),
+/// falling back to the only remote or `origin`
+fn default_remote(repo: &gix::Repository) -> Option<BString> {
+    let branch_remote = repo.head_name().ok().flatten().and_then(|head| {
+        repo.branch_remote_name(head.as_ref().shorten(), gix::remote::Direction::Fetch)
+            .map(|name| name.as_bstr().to_owned())
+    });
+    branch_remote.or_else(|| {
+        repo.remote_default_name(gix::remote::Direction::Fetch)
+            .map(std::borrow::Cow::into_owned)
+    })
+}
+
+/// Entries and modification time of `FETCH_HEAD`, if a fetch wrote one
+fn last_fetch(repo: &gix::Repository) -> Option<(Vec<FetchHeadEntry>, DateTime<Utc>)> {
+    let modified = std::fs::metadata(repo.git_dir().join("FETCH_HEAD"))
+        .and_then(|meta| meta.modified())
+        .ok()?;
+    let entries = read_fetch_head(repo.git_dir()).ok()?;
+    Some((entries, modified.into()))
+}
+
+/// Whether `FETCH_HEAD` records a fetch from the remote `name` at `url`.
+///
+/// Entries name the URL with any trailing `.git` dropped, or the remote
+/// name for some git versions.
+fn fetched_from(entries: &[FetchHeadEntry], name: &str, url: &str) -> bool {
+    let url = url.trim_end_matches('/');
+    let short_url = url.strip_suffix(".git").unwrap_or(url);
+    entries
+        .iter()
+        .any(|entry| entry.url == name || entry.url == url || entry.url == short_url)
 }
 
 /// Check if a remote exists
@@ -390,13 +534,13 @@ pub async fn list_remotes(repo: &RepoHandle) -> GitResult<Vec<RemoteInfo>> {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// if remote_exists(&repo, "origin").await? {
+/// if remote_exists(repo, "origin").await? {
 ///     println!("Origin remote exists");
 /// }
 /// # Ok(())
 /// # }
 /// ```
-pub async fn remote_exists(repo: &RepoHandle, remote_name: &str) -> GitResult<bool> {
+pub async fn remote_exists(repo: RepoHandle, remote_name: &str) -> GitResult<bool> {
     let repo_clone = repo.clone_inner();
     let remote_name = remote_name.to_string();
 
@@ -407,7 +551,7 @@ pub async fn remote_exists(repo: &RepoHandle, remote_name: &str) -> GitResult<bo
             .is_ok())
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
 
 /// Get the current HEAD commit hash
@@ -427,25 +571,23 @@ pub async fn remote_exists(repo: &RepoHandle, remote_name: &str) -> GitResult<bo
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// let commit_hash = head_commit(&repo).await?;
+/// let commit_hash = head_commit(repo).await?;
 /// println!("HEAD: {}", commit_hash);
 /// # Ok(())
 /// # }
 /// ```
-pub async fn head_commit(repo: &RepoHandle) -> GitResult<String> {
+pub async fn head_commit(repo: RepoHandle) -> GitResult<String> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
-        let mut head = repo_clone.head().map_err(|e| GitError::Gix(Box::new(e)))?;
+        let mut head = repo_clone.head().map_err(GitError::gix)?;
 
-        let commit = head
-            .peel_to_commit()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        let commit = head.peel_to_commit().map_err(GitError::gix)?;
 
         Ok(commit.id().to_string())
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
 
 /// Check if repository is in a detached HEAD state
@@ -465,20 +607,20 @@ pub async fn head_commit(repo: &RepoHandle) -> GitResult<String> {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// if is_detached(&repo).await? {
+/// if is_detached(repo).await? {
 ///     println!("Warning: Detached HEAD state");
 /// }
 /// # Ok(())
 /// # }
 /// ```
-pub async fn is_detached(repo: &RepoHandle) -> GitResult<bool> {
+pub async fn is_detached(repo: RepoHandle) -> GitResult<bool> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
-        let head = repo_clone.head().map_err(|e| GitError::Gix(Box::new(e)))?;
+        let head = repo_clone.head().map_err(GitError::gix)?;
 
         Ok(head.referent_name().is_none())
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
@@ -0,0 +1,371 @@
+//! Subtrees: vendor other repositories into a directory and split them out.
+//!
+//! [`subtree_add`] imports a commit (usually fetched from another
+//! repository) under a prefix, recording it as the second parent of a merge
+//! so its history stays reachable. [`subtree_merge`] brings later versions of
+//! that history in, merging them with local edits below the prefix.
+//! [`subtree_split`] goes the other way: it builds a synthetic history that
+//! contains only the prefix directory, e.g. to publish a component that lives
+//! in a monorepo. Splitting is deterministic, so splitting again after new
+//! commits extends the previous split history instead of replacing it.
+
+use std::collections::HashMap;
+
+use super::compare::resolve_commit;
+use super::rewrite::{parse_range, parents_first};
+use super::update_branch::tree_of;
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Result of [`subtree_split`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubtreeSplit {
+    /// Tip of the split history, `None` if the range never had the prefix.
+    pub tip: Option<CommitId>,
+    /// Every commit of the range that has the prefix, with the split commit
+    /// standing for it, parents before children.
+    pub commit_map: Vec<(CommitId, CommitId)>,
+}
+
+impl SubtreeSplit {
+    /// Split commit for the original commit `id`.
+    #[must_use]
+    pub fn split_id(&self, id: CommitId) -> Option<CommitId> {
+        self.commit_map
+            .iter()
+            .find(|(from, _)| *from == id)
+            .map(|(_, to)| *to)
+    }
+}
+
+/// Result of [`subtree_add`] and [`subtree_merge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtreeMergeResult {
+    /// The new merge commit on `HEAD`.
+    pub commit_id: CommitId,
+    /// `HEAD` before the merge (the first parent).
+    pub previous_head: CommitId,
+    /// The imported commit (the second parent).
+    pub source: CommitId,
+}
+
+/// Extract the history of `prefix` from `range` as a separate history whose
+/// root is the content of `prefix`.
+///
+/// `range` is `<tip>` or `<base>..<tip>` as in [`remove_paths`]; with a base,
+/// the split history starts at the first commit after it. Commits that
+/// leave the prefix as one of their parents had it are skipped. Second
+/// parents without the prefix are taken to be vendored history imported by
+/// [`subtree_add`] or [`subtree_merge`] and become parents in the split
+/// history as they are, so the split connects to the upstream commits
+/// rather than duplicating them.
+///
+/// Only objects are written; no reference changes.
+///
+/// [`remove_paths`]: super::rewrite::remove_paths
+pub async fn subtree_split(
+    repo: RepoHandle,
+    prefix: &str,
+    range: &str,
+) -> GitResult<SubtreeSplit> {
+    let repo_inner = repo.clone_for_traversal();
+    let prefix = normalize_prefix(prefix)?;
+    let range = range.to_string();
+
+    tokio::task::spawn_blocking(move || split(&repo_inner, &prefix, &range))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+fn split(repo: &gix::Repository, prefix: &str, range: &str) -> GitResult<SubtreeSplit> {
+    let (base, tip) = parse_range(repo, range)?;
+
+    let mut commits: HashMap<CommitId, gix::objs::Commit> = HashMap::new();
+    let walk = repo
+        .rev_walk([tip])
+        .with_hidden(base)
+        .all()
+        .map_err(GitError::gix)?;
+    for info in walk {
+        let info = info.map_err(GitError::gix)?;
+        let decoded = repo
+            .find_commit(info.id)
+            .map_err(GitError::gix)?
+            .decode()
+            .map_err(GitError::gix)?
+            .into_owned();
+        commits.insert(info.id, decoded);
+    }
+
+    let mut result = SubtreeSplit::default();
+    // Original commit to split commit, for commits that have the prefix
+    let mut mapping: HashMap<CommitId, CommitId> = HashMap::new();
+    // Trees of split commits
+    let mut split_trees: HashMap<CommitId, gix::ObjectId> = HashMap::new();
+    for id in parents_first(&commits) {
+        let original = &commits[&id];
+        let Some(subtree) = subtree_at(repo, original.tree, prefix)? else {
+            continue;
+        };
+
+        let mut parents: Vec<CommitId> = Vec::new();
+        for parent in original.parents.iter().filter_map(|p| mapping.get(p)) {
+            if !parents.contains(parent) {
+                parents.push(*parent);
+            }
+        }
+
+        let mut existing = parents
+            .iter()
+            .find(|parent| split_trees.get(*parent) == Some(&subtree))
+            .copied();
+        if existing.is_none() {
+            for parent in imported_parents(repo, original, &mapping, prefix)? {
+                let tree = tree_of(repo, parent)?;
+                if tree == subtree {
+                    existing = Some(parent);
+                    break;
+                }
+                split_trees.insert(parent, tree);
+                parents.push(parent);
+            }
+        }
+
+        let split_id = match existing {
+            Some(existing) => existing,
+            None => {
+                let commit = gix::objs::Commit {
+                    tree: subtree,
+                    parents: parents.into(),
+                    extra_headers: original
+                        .extra_headers
+                        .iter()
+                        .filter(|(name, _)| name != "gpgsig" && name != "gpgsig-sha256")
+                        .cloned()
+                        .collect(),
+                    ..original.clone()
+                };
+                repo.write_object(&commit).map_err(GitError::gix)?.detach()
+            }
+        };
+        split_trees.insert(split_id, subtree);
+        mapping.insert(id, split_id);
+        result.commit_map.push((id, split_id));
+    }
+
+    result.tip = mapping.get(&tip).copied();
+    Ok(result)
+}
+
+/// Second and later parents of `commit` that come from a vendored history
+///
+/// These are the commits [`subtree_add`] and [`subtree_merge`] record: they
+/// are outside the split and do not have the prefix themselves, because the
+/// prefix directory is their root.
+fn imported_parents(
+    repo: &gix::Repository,
+    commit: &gix::objs::Commit,
+    mapping: &HashMap<CommitId, CommitId>,
+    prefix: &str,
+) -> GitResult<Vec<CommitId>> {
+    let mut imported = Vec::new();
+    for parent in commit.parents.iter().skip(1) {
+        if mapping.contains_key(parent) || imported.contains(parent) {
+            continue;
+        }
+        if subtree_at(repo, tree_of(repo, *parent)?, prefix)?.is_none() {
+            imported.push(*parent);
+        }
+    }
+    Ok(imported)
+}
+
+/// Import `commit` under `prefix` as a merge into `HEAD`.
+///
+/// `commit` is a revision already present in this repository, e.g. a
+/// remote-tracking branch after fetching the vendored repository. `prefix`
+/// must not exist yet. Without a `message`, git subtree's
+/// `Add '<prefix>/' from commit '<id>'` is used. The working tree must be
+/// clean; it is updated to the new commit.
+pub async fn subtree_add(
+    repo: RepoHandle,
+    prefix: &str,
+    commit: &str,
+    message: Option<&str>,
+) -> GitResult<SubtreeMergeResult> {
+    let repo_inner = repo.clone_inner();
+    let prefix = normalize_prefix(prefix)?;
+    let commit = commit.to_string();
+    let message = message.map(str::to_string);
+
+    tokio::task::spawn_blocking(move || {
+        let (head, head_tree) = clean_head(&repo_inner, "add a subtree")?;
+        if subtree_at(&repo_inner, head_tree, &prefix)?.is_some() {
+            return Err(GitError::InvalidInput(format!(
+                "'{prefix}' already exists; use subtree merge to update it"
+            )));
+        }
+        let source = resolve_commit(&repo_inner, &commit)?;
+        let source_tree = tree_of(&repo_inner, source)?;
+
+        let message =
+            message.unwrap_or_else(|| format!("Add '{prefix}/' from commit '{source}'"));
+        commit_subtree(&repo_inner, head, head_tree, source, &prefix, source_tree, &message)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Merge `commit` into the subtree at `prefix` of `HEAD`.
+///
+/// `commit` must share history with an earlier [`subtree_add`] or
+/// [`subtree_merge`] of the same prefix: their merge base is the common
+/// ancestor of a three-way merge between the prefix directory and the tree
+/// of `commit`. Fails with [`GitError::MergeConflict`] if local edits below
+/// the prefix conflict; nothing is modified then. Without a `message`,
+/// `Merge commit '<id>' into '<prefix>/'` is used. The working tree must be
+/// clean; it is updated to the new commit.
+pub async fn subtree_merge(
+    repo: RepoHandle,
+    prefix: &str,
+    commit: &str,
+    message: Option<&str>,
+) -> GitResult<SubtreeMergeResult> {
+    use gix::merge::blob::builtin_driver::text::Labels;
+    use gix::merge::tree::TreatAsUnresolved;
+
+    let repo_inner = repo.clone_inner();
+    let prefix = normalize_prefix(prefix)?;
+    let commit = commit.to_string();
+    let message = message.map(str::to_string);
+
+    tokio::task::spawn_blocking(move || {
+        let (head, head_tree) = clean_head(&repo_inner, "merge a subtree")?;
+        let ours = subtree_at(&repo_inner, head_tree, &prefix)?.ok_or_else(|| {
+            GitError::InvalidInput(format!("'{prefix}' does not exist; use subtree add first"))
+        })?;
+        let source = resolve_commit(&repo_inner, &commit)?;
+        let theirs = tree_of(&repo_inner, source)?;
+
+        let merge_base = repo_inner
+            .merge_base(head, source)
+            .map_err(|_| {
+                GitError::InvalidInput(format!(
+                    "'{commit}' shares no history with '{prefix}'; use subtree add first"
+                ))
+            })?
+            .detach();
+        if merge_base == source {
+            return Err(GitError::InvalidInput(format!(
+                "'{commit}' is already merged into '{prefix}'"
+            )));
+        }
+        let ancestor = tree_of(&repo_inner, merge_base)?;
+
+        let labels = Labels {
+            ancestor: None,
+            current: Some("HEAD".into()),
+            other: Some(commit.as_str().into()),
+        };
+        let options = repo_inner.tree_merge_options().map_err(GitError::gix)?;
+        let mut outcome = repo_inner
+            .merge_trees(ancestor, ours, theirs, labels, options)
+            .map_err(GitError::gix)?;
+        if outcome.has_unresolved_conflicts(TreatAsUnresolved::default()) {
+            let paths: Vec<String> = super::merge::unresolved_conflict_paths(&outcome)
+                .iter()
+                .map(|path| format!("{prefix}/{path}"))
+                .collect();
+            return Err(GitError::MergeConflict {
+                message: format!(
+                    "Merging '{commit}' into '{prefix}' conflicts in: {}",
+                    paths.join(", ")
+                ),
+                paths,
+            });
+        }
+        let merged = outcome.tree.write().map_err(GitError::gix)?.detach();
+
+        let message =
+            message.unwrap_or_else(|| format!("Merge commit '{source}' into '{prefix}/'"));
+        commit_subtree(&repo_inner, head, head_tree, source, &prefix, merged, &message)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Repository-relative directory without surrounding slashes
+fn normalize_prefix(prefix: &str) -> GitResult<String> {
+    let prefix = prefix.trim_start_matches("./").trim_matches('/');
+    if prefix.is_empty()
+        || prefix
+            .split('/')
+            .any(|part| part.is_empty() || part == "." || part == ".." || part == ".git")
+    {
+        return Err(GitError::InvalidInput(format!(
+            "Invalid subtree prefix '{prefix}'"
+        )));
+    }
+    Ok(prefix.to_string())
+}
+
+/// Tree of `prefix` in `tree`, if it is a directory
+fn subtree_at(
+    repo: &gix::Repository,
+    tree: gix::ObjectId,
+    prefix: &str,
+) -> GitResult<Option<gix::ObjectId>> {
+    let entry = repo
+        .find_tree(tree)
+        .map_err(GitError::gix)?
+        .lookup_entry_by_path(prefix)
+        .map_err(GitError::gix)?;
+    Ok(entry
+        .filter(|entry| entry.mode().is_tree())
+        .map(|entry| entry.object_id()))
+}
+
+/// `HEAD` and its tree, refusing an unborn `HEAD` and local changes
+fn clean_head(repo: &gix::Repository, action: &str) -> GitResult<(CommitId, gix::ObjectId)> {
+    let head = repo
+        .head_id()
+        .map_err(|_| GitError::InvalidInput(format!("Cannot {action} without a commit")))?
+        .detach();
+    if !repo.is_bare() && super::fast_status::is_dirty_fast(repo)? {
+        return Err(GitError::InvalidInput(format!(
+            "Cannot {action} with local changes"
+        )));
+    }
+    Ok((head, tree_of(repo, head)?))
+}
+
+/// Commit `HEAD`'s tree with `prefix` set to `subtree` as a merge of `source`
+fn commit_subtree(
+    repo: &gix::Repository,
+    head: CommitId,
+    head_tree: gix::ObjectId,
+    source: CommitId,
+    prefix: &str,
+    subtree: gix::ObjectId,
+    message: &str,
+) -> GitResult<SubtreeMergeResult> {
+    let mut editor = repo.edit_tree(head_tree).map_err(GitError::gix)?;
+    editor
+        .upsert(prefix, gix::object::tree::EntryKind::Tree, subtree)
+        .map_err(GitError::gix)?;
+    let tree = editor.write().map_err(GitError::gix)?.detach();
+
+    // Update the worktree first, so a refused checkout leaves HEAD alone
+    if let Some(workdir) = repo.workdir() {
+        super::merge::sync_worktree_to_tree(repo, workdir, tree, "Subtree")?;
+    }
+    let commit_id = repo
+        .commit("HEAD", message, tree, [head, source])
+        .map_err(GitError::gix)?
+        .detach();
+
+    Ok(SubtreeMergeResult {
+        commit_id,
+        previous_head: head,
+        source,
+    })
+}
@@ -0,0 +1,423 @@
+//! Clone-or-update of a checkout.
+//!
+//! Deployment scripts keep a checkout of some repository at some ref: clone it
+//! the first time, afterwards fetch and move to whatever the ref now points
+//! at. [`sync_repo`] does either, refuses to touch a checkout of a different
+//! repository and reports what it did.
+
+use std::path::Path;
+
+use gix::bstr::ByteSlice;
+use gix::refs::transaction::PreviousValue;
+
+use super::checkout::{CheckoutOpts, checkout};
+use super::clone::{CloneOpts, clone_repo};
+use super::compare::resolve_commit;
+use super::fetch::{FetchOpts, fetch};
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// Options for [`sync_repo`] with builder pattern.
+#[derive(Debug, Clone)]
+pub struct SyncOpts {
+    /// Remote that must match the URL and is fetched from.
+    pub remote: String,
+    /// Discard local changes and local commits instead of failing on them.
+    pub force: bool,
+    /// Depth of the initial clone; later syncs fetch normally.
+    pub shallow: Option<u32>,
+}
+
+impl Default for SyncOpts {
+    fn default() -> Self {
+        Self {
+            remote: "origin".to_string(),
+            force: false,
+            shallow: None,
+        }
+    }
+}
+
+impl SyncOpts {
+    /// Sync from `origin`, keeping local changes safe.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn remote(mut self, remote: impl Into<String>) -> Self {
+        self.remote = remote.into();
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn force(mut self, yes: bool) -> Self {
+        self.force = yes;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn shallow(mut self, depth: u32) -> Self {
+        self.shallow = Some(depth);
+        self
+    }
+}
+
+/// What [`sync_repo`] did to the checkout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    /// The path didn't hold a repository yet and was cloned.
+    Cloned,
+    /// Already at the requested ref; nothing changed.
+    UpToDate,
+    /// The checked-out branch was fast-forwarded.
+    FastForwarded,
+    /// Another branch, tag or commit was checked out.
+    Switched,
+    /// Local changes or local commits were discarded to match the remote.
+    Reset,
+}
+
+impl SyncAction {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cloned => "cloned",
+            Self::UpToDate => "up-to-date",
+            Self::FastForwarded => "fast-forwarded",
+            Self::Switched => "switched",
+            Self::Reset => "reset",
+        }
+    }
+}
+
+impl std::fmt::Display for SyncAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of [`sync_repo`].
+#[derive(Debug, Clone)]
+pub struct SyncResult {
+    pub repo: RepoHandle,
+    pub action: SyncAction,
+    /// Commit checked out before the sync, `None` for a fresh clone.
+    pub previous: Option<CommitId>,
+    /// Commit checked out now, `None` for a clone of an empty remote.
+    pub head: Option<CommitId>,
+    /// Branch checked out now, `None` when `HEAD` is detached.
+    pub branch: Option<String>,
+}
+
+/// Where the sync moves `HEAD`
+enum Target {
+    /// A branch on the remote, checked out as the local branch of that name
+    Branch { name: String, tip: CommitId },
+    /// A tag or commit, checked out detached
+    Detached(CommitId),
+}
+
+/// The checkout as found before moving it
+struct Local {
+    head: Option<CommitId>,
+    /// Branch `HEAD` is on, without `refs/heads/`
+    branch: Option<String>,
+    /// Tip of the local branch of the target, if there is one
+    branch_tip: Option<CommitId>,
+    /// Whether the target tip contains `branch_tip`
+    fast_forward: bool,
+    dirty: bool,
+}
+
+/// Make `path` a checkout of `url` at `reference`.
+///
+/// If `path` doesn't exist or is an empty directory, `url` is cloned into it.
+/// Otherwise the repository there is opened, its remote is checked to point
+/// at the same repository as `url`, and it is fetched. Transport and a
+/// trailing `.git` are ignored in that check, so an SSH checkout of an HTTPS
+/// URL still matches.
+///
+/// `reference` is a branch on the remote, a tag or a commit; `None` keeps the
+/// current branch, or the remote's default branch when `HEAD` is detached;
+/// if the checkout never recorded it, the remote is asked, see
+/// [`remote_default_branch`](super::remote_head::remote_default_branch). A
+/// branch is checked out as the local branch of that name, fast-forwarded to
+/// the remote; tags and commits are checked out detached. Uncommitted changes
+/// and local commits make the sync fail unless [`SyncOpts::force`] is set, in
+/// which case they are discarded.
+pub async fn sync_repo(
+    url: &str,
+    path: impl AsRef<Path>,
+    reference: Option<&str>,
+    opts: SyncOpts,
+) -> GitResult<SyncResult> {
+    let path = path.as_ref();
+    let empty = match std::fs::read_dir(path) {
+        Ok(mut entries) => entries.next().is_none(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => true,
+        Err(e) => return Err(e.into()),
+    };
+
+    if empty {
+        let mut clone = CloneOpts::new(url, path);
+        if let Some(depth) = opts.shallow {
+            clone = clone.shallow(depth);
+        }
+        let cloned = clone_repo(clone)
+            .await
+            .map_err(|_| GitError::ChannelClosed)??;
+        let mut result = SyncResult {
+            repo: cloned.repo,
+            action: SyncAction::Cloned,
+            previous: None,
+            head: cloned.head,
+            branch: cloned.default_branch,
+        };
+        if reference.is_some() {
+            let moved = move_to(result.repo.clone(), &opts, reference).await?;
+            result.head = Some(moved.head);
+            result.branch = moved.branch;
+        }
+        return Ok(result);
+    }
+
+    let repo = crate::open_repo(path)
+        .await
+        .map_err(|_| GitError::ChannelClosed)?
+        .map_err(|_| {
+            GitError::InvalidInput(format!(
+                "{} exists and is not a git repository",
+                path.display()
+            ))
+        })?;
+    let repo_inner = repo.clone_inner();
+    let (remote, requested) = (opts.remote.clone(), url.to_string());
+    tokio::task::spawn_blocking(move || check_remote_url(&repo_inner, &remote, &requested))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    fetch(repo.clone(), FetchOpts::from_remote(opts.remote.clone())).await?;
+    let moved = move_to(repo.clone(), &opts, reference).await?;
+    Ok(SyncResult {
+        repo,
+        action: moved.action,
+        previous: moved.previous,
+        head: Some(moved.head),
+        branch: moved.branch,
+    })
+}
+
+/// Fail unless `remote` points at the same repository as `url`
+fn check_remote_url(repo: &gix::Repository, remote: &str, url: &str) -> GitResult<()> {
+    let configured = repo
+        .try_find_remote_without_url_rewrite(remote)
+        .ok_or_else(|| GitError::InvalidInput(format!("Remote '{remote}' not found")))?
+        .map_err(GitError::gix)?
+        .url(gix::remote::Direction::Fetch)
+        .cloned()
+        .ok_or_else(|| GitError::InvalidInput(format!("Remote '{remote}' has no URL")))?;
+    let requested = gix::url::parse(url.as_bytes().as_bstr()).map_err(GitError::gix)?;
+
+    if repository_key(&configured) != repository_key(&requested) {
+        return Err(GitError::InvalidInput(format!(
+            "Existing checkout is of {}, not {url}",
+            configured.to_bstring()
+        )));
+    }
+    Ok(())
+}
+
+/// Host and path of a URL, without transport, slashes or `.git`
+fn repository_key(url: &gix::Url) -> (Option<String>, String) {
+    let path = url.path.to_str_lossy();
+    let path = path.trim_matches('/');
+    let path = path
+        .strip_suffix(".git")
+        .unwrap_or(path)
+        .trim_end_matches('/');
+    (url.host().map(str::to_ascii_lowercase), path.to_string())
+}
+
+/// Outcome of [`move_to`]
+struct Moved {
+    action: SyncAction,
+    previous: Option<CommitId>,
+    head: CommitId,
+    branch: Option<String>,
+}
+
+/// Move the checkout to `reference`
+async fn move_to(repo: RepoHandle, opts: &SyncOpts, reference: Option<&str>) -> GitResult<Moved> {
+    let repo_inner = repo.clone_inner();
+    let remote = opts.remote.clone();
+    let reference = reference.map(ToString::to_string);
+    let (target, local) = tokio::task::spawn_blocking(move || {
+        let target = resolve_target(&repo_inner, &remote, reference.as_deref())?;
+        let local = inspect(&repo_inner, &target)?;
+        Ok::<_, GitError>((target, local))
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    if local.dirty && !opts.force {
+        return Err(GitError::InvalidInput(
+            "Working tree has uncommitted changes; sync with force to discard them".to_string(),
+        ));
+    }
+
+    let (action, head, branch) = match target {
+        Target::Branch { name, tip } => {
+            let on_branch = local.branch.as_deref() == Some(name.as_str());
+            if on_branch && local.head == Some(tip) && !local.dirty {
+                return Ok(Moved {
+                    action: SyncAction::UpToDate,
+                    previous: local.head,
+                    head: tip,
+                    branch: Some(name),
+                });
+            }
+            if !local.fast_forward && !opts.force {
+                return Err(GitError::InvalidInput(format!(
+                    "Branch '{name}' has commits not on {}/{name}; sync with force to discard them",
+                    opts.remote
+                )));
+            }
+            let action = if local.dirty || !local.fast_forward {
+                SyncAction::Reset
+            } else if on_branch {
+                SyncAction::FastForwarded
+            } else {
+                SyncAction::Switched
+            };
+
+            if on_branch {
+                super::reset::reset_hard(repo, &tip.to_string()).await?;
+            } else {
+                let mut repo_inner = repo.clone_inner();
+                let (branch, remote) = (name.clone(), opts.remote.clone());
+                let created = local.branch_tip.is_none();
+                tokio::task::spawn_blocking(move || {
+                    let refname = format!("refs/heads/{branch}");
+                    repo_inner
+                        .reference(
+                            refname.as_str(),
+                            tip,
+                            PreviousValue::Any,
+                            format!("sync: {remote}/{branch}"),
+                        )
+                        .map_err(GitError::gix)?;
+                    if created {
+                        super::branch::setup_tracking(&mut repo_inner, &branch, &remote, &branch)?;
+                    }
+                    Ok::<_, GitError>(())
+                })
+                .await
+                .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+                checkout(repo.clone(), CheckoutOpts::new(&name).force(opts.force)).await?;
+            }
+            (action, tip, Some(name))
+        }
+        Target::Detached(id) => {
+            if local.branch.is_none() && local.head == Some(id) && !local.dirty {
+                return Ok(Moved {
+                    action: SyncAction::UpToDate,
+                    previous: local.head,
+                    head: id,
+                    branch: None,
+                });
+            }
+            checkout(
+                repo.clone(),
+                CheckoutOpts::new(id.to_string()).force(opts.force),
+            )
+            .await?;
+            let action = if local.dirty {
+                SyncAction::Reset
+            } else {
+                SyncAction::Switched
+            };
+            (action, id, None)
+        }
+    };
+    Ok(Moved {
+        action,
+        previous: local.head,
+        head,
+        branch,
+    })
+}
+
+/// Resolve `reference` against the freshly fetched remote
+fn resolve_target(
+    repo: &gix::Repository,
+    remote: &str,
+    reference: Option<&str>,
+) -> GitResult<Target> {
+    let name = match reference {
+        Some(reference) => reference
+            .strip_prefix("refs/heads/")
+            .unwrap_or(reference)
+            .to_string(),
+        None => match repo.head_name().map_err(GitError::gix)? {
+            Some(head) => head.shorten().to_str_lossy().into_owned(),
+            None => super::remote_head::ensure_remote_head(repo, remote).ok_or_else(|| {
+                GitError::InvalidInput(format!(
+                    "HEAD is detached and {remote} has no default branch; pass a ref"
+                ))
+            })?,
+        },
+    };
+
+    let tracking = format!("refs/remotes/{remote}/{name}");
+    if let Some(reference) = repo
+        .try_find_reference(tracking.as_str())
+        .map_err(GitError::gix)?
+    {
+        let tip = reference
+            .into_fully_peeled_id()
+            .map_err(GitError::gix)?
+            .detach();
+        return Ok(Target::Branch { name, tip });
+    }
+    match reference {
+        Some(reference) => Ok(Target::Detached(resolve_commit(repo, reference)?)),
+        None => Err(GitError::BranchNotFound(format!("{remote}/{name}"))),
+    }
+}
+
+fn inspect(repo: &gix::Repository, target: &Target) -> GitResult<Local> {
+    let head = repo.head_id().ok().map(gix::Id::detach);
+    let branch = repo
+        .head_name()
+        .map_err(GitError::gix)?
+        .map(|name| name.shorten().to_str_lossy().into_owned());
+    let (branch_tip, fast_forward) = match target {
+        Target::Branch { name, tip } => {
+            let local = format!("refs/heads/{name}");
+            let branch_tip = repo
+                .try_find_reference(local.as_str())
+                .map_err(GitError::gix)?
+                .map(|reference| reference.into_fully_peeled_id().map(gix::Id::detach))
+                .transpose()
+                .map_err(GitError::gix)?;
+            let fast_forward = branch_tip.is_none_or(|local| {
+                local == *tip || repo.merge_base(local, *tip).is_ok_and(|base| base == local)
+            });
+            (branch_tip, fast_forward)
+        }
+        Target::Detached(_) => (None, true),
+    };
+    let dirty = head.is_some() && super::fast_status::is_dirty_fast(repo)?;
+    Ok(Local {
+        head,
+        branch,
+        branch_tip,
+        fast_forward,
+        dirty,
+    })
+}
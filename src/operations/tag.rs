@@ -2,12 +2,14 @@
 //!
 //! Provides functionality for creating, deleting, and listing Git tags.
 
-use crate::{GitError, GitResult, RepoHandle};
-use chrono::{DateTime, Utc};
+use super::commit::git_time_to_date;
+use super::warnings::{OperationWarning, WarningKind};
+use crate::{GitError, GitResult, RepoHandle, Signature};
+use chrono::{DateTime, FixedOffset};
 use gix::bstr::ByteSlice;
 
 /// Options for creating a tag
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TagOpts {
     /// Tag name
     pub name: String,
@@ -17,6 +19,51 @@ pub struct TagOpts {
     pub target: Option<String>,
     /// Force creation (overwrite if exists)
     pub force: bool,
+    /// Tagger of annotated tags (defaults to `user.name` and `user.email`)
+    pub tagger: Option<Signature>,
+}
+
+impl TagOpts {
+    /// Create options for a lightweight tag named `name` on `HEAD`.
+    #[inline]
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Create an annotated tag with this message.
+    #[inline]
+    #[must_use]
+    pub fn message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Tag this revision instead of `HEAD`.
+    #[inline]
+    #[must_use]
+    pub fn target<S: Into<String>>(mut self, target: S) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Replace an existing tag of the same name.
+    #[inline]
+    #[must_use]
+    pub fn force(mut self, yes: bool) -> Self {
+        self.force = yes;
+        self
+    }
+
+    /// Set the tagger of an annotated tag.
+    #[inline]
+    #[must_use]
+    pub fn tagger(mut self, tagger: Signature) -> Self {
+        self.tagger = Some(tagger);
+        self
+    }
 }
 
 /// Information about a Git tag
@@ -28,10 +75,15 @@ pub struct TagInfo {
     pub message: Option<String>,
     /// Target commit hash
     pub target_commit: String,
-    /// Tag timestamp
-    pub timestamp: DateTime<Utc>,
+    /// Tagging time of annotated tags, commit time of lightweight tags, with
+    /// the offset it was recorded in; `None` if neither can be read
+    pub timestamp: Option<DateTime<FixedOffset>>,
     /// Whether this is an annotated tag
     pub is_annotated: bool,
+    /// Tagger (if annotated)
+    pub tagger: Option<Signature>,
+    /// Problems reading the tag, such as a missing timestamp
+    pub warnings: Vec<OperationWarning>,
 }
 
 /// Create a Git tag
@@ -54,16 +106,11 @@ pub struct TagInfo {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// let tag_info = create_tag(&repo, TagOpts {
-///     name: "v1.0.0".to_string(),
-///     message: Some("Release v1.0.0".to_string()),
-///     target: None,
-///     force: false,
-/// }).await?;
+/// let tag_info = create_tag(repo, TagOpts::new("v1.0.0").message("Release v1.0.0")).await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn create_tag(repo: &RepoHandle, opts: TagOpts) -> GitResult<TagInfo> {
+pub async fn create_tag(repo: RepoHandle, opts: TagOpts) -> GitResult<TagInfo> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
@@ -77,9 +124,9 @@ pub async fn create_tag(repo: &RepoHandle, opts: TagOpts) -> GitResult<TagInfo>
                 .into()
         } else {
             // Default to HEAD
-            let mut head = repo_clone.head().map_err(|e| GitError::Gix(Box::new(e)))?;
+            let mut head = repo_clone.head().map_err(GitError::gix)?;
             head.try_peel_to_id()
-                .map_err(|e| GitError::Gix(Box::new(e)))?
+                .map_err(GitError::gix)?
                 .ok_or_else(|| {
                     GitError::InvalidInput("HEAD does not point to a commit".to_string())
                 })?
@@ -99,88 +146,84 @@ pub async fn create_tag(repo: &RepoHandle, opts: TagOpts) -> GitResult<TagInfo>
             )));
         }
 
-        // Create tag reference
-        let is_annotated = opts.message.is_some();
-
-        // For lightweight tags, use transaction
-        if is_annotated {
-            // For annotated tags, create tag object
-            let message = opts.message.as_deref().unwrap_or("");
-            let signature = get_signature(&repo_clone)?;
-
-            use gix::bstr::ByteSlice;
-            let time_str = signature.time.to_string();
-            let sig_ref = gix::actor::SignatureRef {
-                name: signature.name.as_bstr(),
-                email: signature.email.as_bstr(),
-                time: &time_str,
-            };
-
-            repo_clone
-                .tag(
-                    &opts.name,
-                    target,
-                    gix::objs::Kind::Commit,
-                    Some(sig_ref),
-                    message,
-                    if opts.force {
-                        gix::refs::transaction::PreviousValue::Any
-                    } else {
-                        gix::refs::transaction::PreviousValue::MustNotExist
-                    },
-                )
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
+        // Annotated tags get a tag object, lightweight tags only a reference
+        let previous = if opts.force {
+            gix::refs::transaction::PreviousValue::Any
         } else {
-            let ref_name = gix::refs::FullName::try_from(tag_ref_name.as_bytes().as_bstr())
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-
-            let edit = gix::refs::transaction::RefEdit {
-                change: gix::refs::transaction::Change::Update {
-                    log: gix::refs::transaction::LogChange::default(),
-                    expected: if opts.force {
-                        gix::refs::transaction::PreviousValue::Any
-                    } else {
-                        gix::refs::transaction::PreviousValue::MustNotExist
+            gix::refs::transaction::PreviousValue::MustNotExist
+        };
+        let tagger = match opts.message {
+            Some(ref message) => {
+                let tagger = match opts.tagger {
+                    Some(ref tagger) => tagger.to_gix(),
+                    None => get_signature(&repo_clone)?,
+                };
+                let time = tagger.time.to_string();
+                let sig_ref = gix::actor::SignatureRef {
+                    name: tagger.name.as_bstr(),
+                    email: tagger.email.as_bstr(),
+                    time: &time,
+                };
+
+                repo_clone
+                    .tag(
+                        &opts.name,
+                        target,
+                        gix::objs::Kind::Commit,
+                        Some(sig_ref),
+                        message,
+                        previous,
+                    )
+                    .map_err(GitError::gix)?;
+                Some(tagger)
+            }
+            None => {
+                let ref_name = gix::refs::FullName::try_from(tag_ref_name.as_bytes().as_bstr())
+                    .map_err(GitError::gix)?;
+
+                let edit = gix::refs::transaction::RefEdit {
+                    change: gix::refs::transaction::Change::Update {
+                        log: gix::refs::transaction::LogChange::default(),
+                        expected: previous,
+                        new: gix::refs::Target::Object(target),
                     },
-                    new: gix::refs::Target::Object(target),
-                },
-                name: ref_name,
-                deref: false,
-            };
-
-            repo_clone
-                .refs
-                .transaction()
-                .prepare(
-                    vec![edit],
-                    gix::lock::acquire::Fail::Immediately,
-                    gix::lock::acquire::Fail::Immediately,
-                )
-                .map_err(|e| GitError::Gix(Box::new(e)))?
-                .commit(None)
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-        }
-
-        // Get tag info
-        let commit = repo_clone
-            .find_object(target)
-            .map_err(|e| GitError::Gix(Box::new(e)))?
-            .try_into_commit()
-            .map_err(|_| GitError::Parse("Target is not a commit".to_string()))?;
+                    name: ref_name,
+                    deref: false,
+                };
+
+                repo_clone
+                    .refs
+                    .transaction()
+                    .prepare(
+                        vec![edit],
+                        gix::lock::acquire::Fail::Immediately,
+                        gix::lock::acquire::Fail::Immediately,
+                    )
+                    .map_err(GitError::gix)?
+                    .commit(None)
+                    .map_err(GitError::gix)?;
+                None
+            }
+        };
 
-        let commit_time = commit.time().map_err(|e| GitError::Gix(Box::new(e)))?;
-        let timestamp = DateTime::from_timestamp(commit_time.seconds, 0).unwrap_or_else(Utc::now);
+        let mut warnings = Vec::new();
+        let timestamp = match tagger {
+            Some(ref tagger) => git_time_to_date(tagger.time),
+            None => commit_time(&repo_clone, target, &opts.name, &mut warnings)?,
+        };
 
         Ok(TagInfo {
             name: opts.name,
             message: opts.message,
             target_commit: target.to_string(),
             timestamp,
-            is_annotated,
+            is_annotated: tagger.is_some(),
+            tagger: tagger.map(Signature::try_from).transpose()?,
+            warnings,
         })
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
 
 /// Delete a Git tag
@@ -199,11 +242,11 @@ pub async fn create_tag(repo: &RepoHandle, opts: TagOpts) -> GitResult<TagInfo>
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// delete_tag(&repo, "v1.0.0").await?;
+/// delete_tag(repo, "v1.0.0").await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn delete_tag(repo: &RepoHandle, tag_name: &str) -> GitResult<()> {
+pub async fn delete_tag(repo: RepoHandle, tag_name: &str) -> GitResult<()> {
     let repo_clone = repo.clone_inner();
     let tag_name = tag_name.to_string();
 
@@ -218,7 +261,7 @@ pub async fn delete_tag(repo: &RepoHandle, tag_name: &str) -> GitResult<()> {
 
         // Delete the tag using transaction
         let ref_name = gix::refs::FullName::try_from(tag_ref_name.as_bytes().as_bstr())
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+            .map_err(GitError::gix)?;
 
         let edit = gix::refs::transaction::RefEdit {
             change: gix::refs::transaction::Change::Delete {
@@ -237,14 +280,14 @@ pub async fn delete_tag(repo: &RepoHandle, tag_name: &str) -> GitResult<()> {
                 gix::lock::acquire::Fail::Immediately,
                 gix::lock::acquire::Fail::Immediately,
             )
-            .map_err(|e| GitError::Gix(Box::new(e)))?
+            .map_err(GitError::gix)?
             .commit(None)
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+            .map_err(GitError::gix)?;
 
         Ok(())
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
 
 /// Check if a tag exists
@@ -265,13 +308,13 @@ pub async fn delete_tag(repo: &RepoHandle, tag_name: &str) -> GitResult<()> {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// if tag_exists(&repo, "v1.0.0").await? {
+/// if tag_exists(repo, "v1.0.0").await? {
 ///     println!("Tag exists!");
 /// }
 /// # Ok(())
 /// # }
 /// ```
-pub async fn tag_exists(repo: &RepoHandle, tag_name: &str) -> GitResult<bool> {
+pub async fn tag_exists(repo: RepoHandle, tag_name: &str) -> GitResult<bool> {
     let repo_clone = repo.clone_inner();
     let tag_name = tag_name.to_string();
 
@@ -283,7 +326,7 @@ pub async fn tag_exists(repo: &RepoHandle, tag_name: &str) -> GitResult<bool> {
             .is_ok())
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
 }
 
 /// List all tags in the repository
@@ -303,98 +346,136 @@ pub async fn tag_exists(repo: &RepoHandle, tag_name: &str) -> GitResult<bool> {
 ///
 /// # async fn example() -> kodegen_git::GitResult<()> {
 /// let repo = open_repo("/path/to/repo")?;
-/// let tags = list_tags(&repo).await?;
+/// let tags = list_tags(repo).await?;
 /// for tag in tags {
 ///     println!("Tag: {} -> {}", tag.name, tag.target_commit);
 /// }
 /// # Ok(())
 /// # }
 /// ```
-pub async fn list_tags(repo: &RepoHandle) -> GitResult<Vec<TagInfo>> {
+pub async fn list_tags(repo: RepoHandle) -> GitResult<Vec<TagInfo>> {
     let repo_clone = repo.clone_inner();
 
     tokio::task::spawn_blocking(move || {
         let mut tags = Vec::new();
 
         // Iterate over all tag references
-        let refs_platform = repo_clone
-            .references()
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+        let refs_platform = repo_clone.references().map_err(GitError::gix)?;
         let tag_refs = refs_platform
             .prefixed("refs/tags/")
-            .map_err(|e| GitError::Gix(Box::new(e)))?;
+            .map_err(GitError::gix)?;
 
         for reference in tag_refs {
-            let mut reference = reference.map_err(GitError::Gix)?;
-
-            let name = reference.name().as_bstr();
-            if !name.starts_with(b"refs/tags/") {
-                continue;
-            }
-
-            let tag_name = name
-                .strip_prefix(b"refs/tags/")
-                .and_then(|n| std::str::from_utf8(n).ok())
-                .ok_or_else(|| GitError::Parse("Invalid tag name".to_string()))?
-                .to_string();
-
-            // Get target
-            let target_id = reference
-                .peel_to_id()
-                .map_err(|e| GitError::Gix(Box::new(e)))?;
-
-            // Try to get tag object for annotated tags
-            let (message, is_annotated, timestamp) = if let Ok(obj) =
-                repo_clone.find_object(target_id)
-            {
-                if let Ok(tag_obj) = obj.try_into_tag() {
-                    let tag_ref = tag_obj.decode().ok();
-                    let msg = tag_ref.as_ref().map(|t| t.message.to_string());
-                    let ts = if let Some(ref tag) = tag_ref {
-                        if let Some(tagger) = &tag.tagger {
-                            if let Ok(time) = tagger.time() {
-                                DateTime::from_timestamp(time.seconds, 0).unwrap_or_else(Utc::now)
-                            } else {
-                                Utc::now()
-                            }
-                        } else {
-                            Utc::now()
-                        }
-                    } else {
-                        Utc::now()
-                    };
-                    (msg, true, ts)
-                } else if let Ok(obj2) = repo_clone.find_object(target_id) {
-                    if let Ok(commit) = obj2.try_into_commit() {
-                        let ts = commit
-                            .time()
-                            .ok()
-                            .unwrap_or_else(gix::date::Time::now_local_or_utc);
-                        let ts = DateTime::from_timestamp(ts.seconds, 0).unwrap_or_else(Utc::now);
-                        (None, false, ts)
-                    } else {
-                        (None, false, Utc::now())
-                    }
-                } else {
-                    (None, false, Utc::now())
-                }
-            } else {
-                (None, false, Utc::now())
-            };
-
-            tags.push(TagInfo {
-                name: tag_name,
-                message,
-                target_commit: target_id.to_string(),
-                timestamp,
-                is_annotated,
-            });
+            let mut reference = reference.map_err(GitError::gix)?;
+            tags.push(tag_info(&repo_clone, &mut reference)?);
         }
 
+        // Newest first, tags without a timestamp last, then by name
+        tags.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| a.name.cmp(&b.name)));
         Ok(tags)
     })
     .await
-    .map_err(|e| GitError::Gix(Box::new(e)))?
+    .map_err(GitError::gix)?
+}
+
+/// Describe the tag `reference` points at
+pub(crate) fn tag_info(
+    repo: &gix::Repository,
+    reference: &mut gix::Reference<'_>,
+) -> GitResult<TagInfo> {
+    let name = reference.name().shorten().to_str_lossy().into_owned();
+
+    // The reference points at the tag object of annotated tags
+    let direct = reference.target().try_id().map(ToOwned::to_owned);
+    let target = reference.peel_to_id().map_err(GitError::gix)?.detach();
+
+    let tag = direct
+        .filter(|id| *id != target)
+        .and_then(|id| repo.find_object(id).ok())
+        .and_then(|object| object.try_into_tag().ok());
+    let mut warnings = Vec::new();
+    let Some(tag) = tag else {
+        return Ok(TagInfo {
+            timestamp: commit_time(repo, target, &name, &mut warnings)?,
+            name,
+            message: None,
+            target_commit: target.to_string(),
+            is_annotated: false,
+            tagger: None,
+            warnings,
+        });
+    };
+
+    let decoded = tag.decode().map_err(GitError::gix)?;
+    let tagger = match decoded.tagger.map(parse_tagger) {
+        Some(Ok(tagger)) => Some(tagger),
+        Some(Err(e)) => {
+            warnings.push(
+                OperationWarning::new(
+                    WarningKind::UnparsableSignature,
+                    format!("tagger could not be parsed ({e}); using the commit time"),
+                )
+                .subject(&name),
+            );
+            None
+        }
+        None => None,
+    };
+    let (tagger, timestamp) = match tagger {
+        Some((tagger, time)) => (Some(tagger), Some(time)),
+        None => (None, commit_time(repo, target, &name, &mut warnings)?),
+    };
+
+    Ok(TagInfo {
+        name,
+        message: Some(decoded.message.to_str_lossy().into_owned()),
+        target_commit: target.to_string(),
+        timestamp,
+        is_annotated: true,
+        tagger,
+        warnings,
+    })
+}
+
+/// Tagger of an annotated tag and the tagging time in the tagger's offset
+fn parse_tagger(
+    tagger: gix::actor::SignatureRef<'_>,
+) -> GitResult<(Signature, DateTime<FixedOffset>)> {
+    let tagger = tagger.trim().to_owned().map_err(GitError::gix)?;
+    let time = git_time_to_date(tagger.time)
+        .ok_or_else(|| GitError::Parse(format!("Invalid tag time {}", tagger.time)))?;
+    Ok((Signature::try_from(tagger)?, time))
+}
+
+/// Commit time of `id`, or `None` with a warning about tag `name` if it is
+/// not a commit or its time is out of range
+fn commit_time(
+    repo: &gix::Repository,
+    id: gix::ObjectId,
+    name: &str,
+    warnings: &mut Vec<OperationWarning>,
+) -> GitResult<Option<DateTime<FixedOffset>>> {
+    let object = repo.find_object(id).map_err(GitError::gix)?;
+    let kind = object.kind;
+    let time = match object.try_into_commit() {
+        Ok(commit) => Some(commit.time().map_err(GitError::gix)?),
+        Err(_) => None,
+    };
+    if let Some(date) = time.and_then(git_time_to_date) {
+        return Ok(Some(date));
+    }
+    let reason = match time {
+        Some(time) => format!("commit time {time} is out of range"),
+        None => format!("target is a {kind}, not a commit"),
+    };
+    warnings.push(
+        OperationWarning::new(
+            WarningKind::MissingTimestamp,
+            format!("{reason}; no timestamp"),
+        )
+        .subject(name),
+    );
+    Ok(None)
 }
 
 /// Helper function to get signature from repository config
@@ -0,0 +1,333 @@
+//! Bring a feature branch up to date with its base.
+//!
+//! [`update_branch`] does in one call what otherwise takes a fetch, a
+//! checkout, a merge or rebase and a look at what conflicted. The new
+//! commits are made on objects only, so the branch doesn't need to be
+//! checked out; when it is checked out in this worktree, the index and
+//! working tree follow along. A conflicting update leaves the branch as it
+//! was and reports the conflicted files instead of failing.
+
+use gix::bstr::ByteSlice;
+use gix::merge::blob::builtin_driver::text::Labels;
+use gix::merge::tree::TreatAsUnresolved;
+use gix::refs::transaction::PreviousValue;
+
+use super::compare::resolve_commit;
+use super::conflicts::{ConflictedFile, conflicted_files};
+use crate::{CommitId, FetchOpts, GitError, GitResult, RepoHandle};
+
+/// How [`update_branch`] brings in the base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateStrategy {
+    /// Merge the base into the branch with a merge commit.
+    Merge,
+    /// Replay the branch's commits on top of the base.
+    Rebase,
+}
+
+/// Options for [`update_branch`] with builder pattern.
+#[derive(Debug, Clone)]
+pub struct UpdateBranchOpts {
+    /// Local branch to update
+    pub branch: String,
+    /// Revision to bring in, usually a remote-tracking branch like `origin/main`
+    pub base: String,
+    pub strategy: UpdateStrategy,
+    /// Stash local changes while updating the branch checked out here
+    pub autostash: bool,
+    /// Fetch the remote first when `base` is a remote-tracking branch
+    pub fetch: bool,
+}
+
+impl UpdateBranchOpts {
+    /// Update `branch` from `base` by merging, fetching first.
+    pub fn new(branch: impl Into<String>, base: impl Into<String>) -> Self {
+        Self {
+            branch: branch.into(),
+            base: base.into(),
+            strategy: UpdateStrategy::Merge,
+            autostash: false,
+            fetch: true,
+        }
+    }
+
+    #[must_use]
+    pub fn strategy(mut self, strategy: UpdateStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Stash local changes around the update, see [`MergeOpts::autostash`](crate::MergeOpts::autostash).
+    #[must_use]
+    pub fn autostash(mut self, yes: bool) -> Self {
+        self.autostash = yes;
+        self
+    }
+
+    /// Fetch the remote of a remote-tracking `base` first (default: yes).
+    #[must_use]
+    pub fn fetch(mut self, yes: bool) -> Self {
+        self.fetch = yes;
+        self
+    }
+}
+
+/// What [`update_branch`] did to the branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    /// The branch already contains the base.
+    UpToDate,
+    /// The branch had no commits of its own and now points at the base.
+    FastForward,
+    /// A merge commit brought the base in.
+    Merged,
+    /// The branch's commits were replayed on top of the base.
+    Rebased,
+    /// The update conflicted; the branch was left unchanged.
+    Conflicts,
+}
+
+/// Result of [`update_branch`].
+#[derive(Debug, Clone)]
+pub struct UpdateBranchResult {
+    /// Full name of the branch, e.g. `refs/heads/feature`.
+    pub branch_ref: String,
+    pub outcome: UpdateOutcome,
+    /// Whether the base's remote was fetched first.
+    pub fetched: bool,
+    /// The base commit brought in.
+    pub base_id: CommitId,
+    pub old_tip: CommitId,
+    /// Tip after the update; `old_tip` if nothing changed.
+    pub new_tip: CommitId,
+    /// Commits replayed by a rebase, as (original, replayed), oldest first.
+    pub rebased: Vec<(CommitId, CommitId)>,
+    /// Commits a rebase dropped because the base already has their changes;
+    /// merge commits are dropped too, as `git rebase` does.
+    pub dropped: Vec<CommitId>,
+    /// Files that conflicted, for [`UpdateOutcome::Conflicts`].
+    pub conflicts: Vec<ConflictedFile>,
+    /// For a conflicting rebase, the commit that could not be replayed.
+    pub conflicting_commit: Option<CommitId>,
+    /// Whether the index and working tree were updated because the branch is
+    /// checked out here.
+    pub worktree_updated: bool,
+}
+
+/// Bring the local branch `opts.branch` up to date with `opts.base`.
+///
+/// When `base` is a branch of a configured remote (`origin/main`), that
+/// remote is fetched first. Conflicts are reported in
+/// [`UpdateBranchResult::conflicts`] with the branch untouched, so the caller
+/// can pick the other strategy or resolve them by hand. Fails for a branch
+/// checked out in another worktree, for a branch checked out here with local
+/// changes unless `autostash` is set, and for unrelated histories.
+pub async fn update_branch(
+    repo: RepoHandle,
+    opts: UpdateBranchOpts,
+) -> GitResult<UpdateBranchResult> {
+    let remote = super::branch::parse_remote_branch(&opts.base)
+        .map(|(remote, _)| remote.to_string())
+        .filter(|remote| repo.raw().find_remote(remote.as_bytes().as_bstr()).is_ok());
+    let fetched = match remote {
+        Some(remote) if opts.fetch => {
+            crate::fetch(repo.clone(), FetchOpts::from_remote(remote)).await?;
+            true
+        }
+        _ => false,
+    };
+
+    let branch_ref = format!("refs/heads/{}", opts.branch);
+    let checked_out_here = repo
+        .raw()
+        .head_name()
+        .map_err(GitError::gix)?
+        .is_some_and(|name| name.as_bstr() == branch_ref.as_bytes().as_bstr());
+    let autostash = opts.autostash && checked_out_here;
+
+    let repo_inner = repo.clone_inner();
+    super::autostash::with_autostash(repo, autostash, || async move {
+        tokio::task::spawn_blocking(move || update_once(&repo_inner, &opts, fetched))
+            .await
+            .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+    })
+    .await
+}
+
+fn update_once(
+    repo: &gix::Repository,
+    opts: &UpdateBranchOpts,
+    fetched: bool,
+) -> GitResult<UpdateBranchResult> {
+    let branch = &opts.branch;
+    let branch_ref = format!("refs/heads/{branch}");
+    let old_tip = repo
+        .find_reference(branch_ref.as_str())
+        .map_err(|_| GitError::BranchNotFound(branch.clone()))?
+        .into_fully_peeled_id()
+        .map_err(GitError::gix)?
+        .detach();
+    let base_id = resolve_commit(repo, &opts.base)?;
+
+    // Moving a branch checked out elsewhere would leave that worktree stale
+    let checked_out_here = repo
+        .head_name()
+        .map_err(GitError::gix)?
+        .is_some_and(|name| name.as_bstr() == branch_ref.as_bytes().as_bstr());
+    let workdir = if checked_out_here {
+        if super::fast_status::is_dirty_fast(repo)? {
+            return Err(GitError::InvalidInput(format!(
+                "Cannot update checked-out branch '{branch}' with local changes; use autostash"
+            )));
+        }
+        repo.workdir().map(std::path::Path::to_path_buf)
+    } else {
+        super::worktree::check_branch_not_in_use(repo, branch)?;
+        None
+    };
+
+    let mut result = UpdateBranchResult {
+        branch_ref: branch_ref.clone(),
+        outcome: UpdateOutcome::UpToDate,
+        fetched,
+        base_id,
+        old_tip,
+        new_tip: old_tip,
+        rebased: Vec::new(),
+        dropped: Vec::new(),
+        conflicts: Vec::new(),
+        conflicting_commit: None,
+        worktree_updated: false,
+    };
+
+    let merge_base = if old_tip == base_id {
+        base_id
+    } else {
+        repo.merge_base(old_tip, base_id)
+            .map_err(|_| {
+                GitError::InvalidInput(format!("'{branch}' and '{}' share no history", opts.base))
+            })?
+            .detach()
+    };
+
+    let new_tip = if merge_base == base_id {
+        return Ok(result);
+    } else if merge_base == old_tip {
+        result.outcome = UpdateOutcome::FastForward;
+        base_id
+    } else {
+        match opts.strategy {
+            UpdateStrategy::Merge => match merge(repo, old_tip, base_id, branch, &opts.base)? {
+                Ok(tip) => {
+                    result.outcome = UpdateOutcome::Merged;
+                    tip
+                }
+                Err(conflicts) => {
+                    result.outcome = UpdateOutcome::Conflicts;
+                    result.conflicts = conflicts;
+                    return Ok(result);
+                }
+            },
+            UpdateStrategy::Rebase => {
+                match super::rebase::replay(repo, old_tip, base_id, base_id, &opts.base)? {
+                    Ok(replayed) => {
+                        result.outcome = UpdateOutcome::Rebased;
+                        result.rebased = replayed.rewritten;
+                        result.dropped = replayed.dropped;
+                        replayed.tip
+                    }
+                    Err(conflict) => {
+                        result.outcome = UpdateOutcome::Conflicts;
+                        result.conflicts = conflict.conflicts;
+                        result.conflicting_commit = Some(conflict.commit);
+                        return Ok(result);
+                    }
+                }
+            }
+        }
+    };
+
+    let message = match result.outcome {
+        UpdateOutcome::FastForward => format!("update: Fast-forward to {}", opts.base),
+        UpdateOutcome::Rebased => format!("rebase (finish): {branch_ref} onto {base_id}"),
+        _ => format!("merge {}: Merge made by update_branch", opts.base),
+    };
+    // Update the worktree first, so a refused checkout leaves the branch alone
+    if let Some(workdir) = &workdir {
+        let tree = tree_of(repo, new_tip)?;
+        super::merge::sync_worktree_to_tree(repo, workdir, tree, "Update")?;
+        result.worktree_updated = true;
+    }
+    repo.reference(
+        branch_ref.as_str(),
+        new_tip,
+        PreviousValue::MustExistAndMatch(gix::refs::Target::Object(old_tip)),
+        message,
+    )
+    .map_err(GitError::gix)?;
+    result.new_tip = new_tip;
+    Ok(result)
+}
+
+/// Merge `base_id` into `tip` as a new merge commit, or the conflicted files
+fn merge(
+    repo: &gix::Repository,
+    tip: CommitId,
+    base_id: CommitId,
+    branch: &str,
+    base: &str,
+) -> GitResult<Result<CommitId, Vec<ConflictedFile>>> {
+    let options: gix::merge::commit::Options =
+        repo.tree_merge_options().map_err(GitError::gix)?.into();
+    let labels = Labels {
+        ancestor: None,
+        current: Some(branch.into()),
+        other: Some(base.into()),
+    };
+    let mut outcome = repo
+        .merge_commits(tip, base_id, labels, options)
+        .map_err(GitError::gix)?;
+    if outcome
+        .tree_merge
+        .has_unresolved_conflicts(TreatAsUnresolved::default())
+    {
+        return Ok(Err(conflicted_files(&outcome.tree_merge)));
+    }
+    let tree = outcome
+        .tree_merge
+        .tree
+        .write()
+        .map_err(GitError::gix)?
+        .detach();
+
+    let signature = committer(repo)?;
+    let commit = gix::objs::Commit {
+        tree,
+        parents: vec![tip, base_id].into(),
+        author: signature.clone(),
+        committer: signature,
+        encoding: None,
+        message: format!("Merge {base} into {branch}\n").into(),
+        extra_headers: Vec::new(),
+    };
+    let id = repo.write_object(&commit).map_err(GitError::gix)?.detach();
+    Ok(Ok(id))
+}
+
+pub(crate) fn tree_of(repo: &gix::Repository, id: CommitId) -> GitResult<gix::ObjectId> {
+    Ok(repo
+        .find_commit(id)
+        .map_err(GitError::gix)?
+        .tree_id()
+        .map_err(GitError::gix)?
+        .detach())
+}
+
+/// The configured committer, dated now
+pub(crate) fn committer(repo: &gix::Repository) -> GitResult<gix::actor::Signature> {
+    repo.committer()
+        .ok_or_else(|| GitError::InvalidInput("No committer configured".to_string()))?
+        .map_err(GitError::gix)?
+        .to_owned()
+        .map_err(GitError::gix)
+}
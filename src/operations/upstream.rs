@@ -0,0 +1,218 @@
+//! Divergence between local branches and their upstream.
+//!
+//! [`upstream_status`] counts the commits a branch is ahead of and behind its
+//! configured upstream (`branch.<name>.remote` and `branch.<name>.merge`).
+//! [`ensure_up_to_date`] fetches the upstream first and refuses with
+//! [`GitError::BehindUpstream`] when the branch is missing upstream commits,
+//! so work is not built on a stale branch.
+
+use gix::bstr::ByteSlice;
+
+use super::warnings::{OperationWarning, WarningKind};
+use crate::{CommitId, FetchOpts, GitError, GitResult, RepoHandle};
+
+/// Position of a local branch relative to its upstream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpstreamStatus {
+    /// Local branch name, e.g. `main`.
+    pub branch: String,
+    /// Remote-tracking branch, e.g. `origin/main`.
+    pub upstream: String,
+    pub remote: String,
+    /// Local commits not in the upstream.
+    pub ahead: usize,
+    /// Upstream commits not in the local branch.
+    pub behind: usize,
+    /// Whether the remote was fetched before counting; if not, the counts use
+    /// the remote-tracking branch as last fetched.
+    pub fetched: bool,
+    /// Why the counts may be stale, e.g. a failed fetch
+    pub warnings: Vec<OperationWarning>,
+}
+
+impl UpstreamStatus {
+    /// Whether the upstream has commits the branch lacks.
+    #[must_use]
+    pub fn is_behind(&self) -> bool {
+        self.behind > 0
+    }
+}
+
+/// Ahead/behind counts of `branch` (the current branch if `None`) against
+/// the remote-tracking branch as last fetched.
+///
+/// Returns `None` for a detached `HEAD`, a name that is not a local branch,
+/// a branch without upstream configuration, or an upstream that has not been
+/// fetched yet.
+pub async fn upstream_status(
+    repo: RepoHandle,
+    branch: Option<&str>,
+) -> GitResult<Option<UpstreamStatus>> {
+    let repo_inner = repo.clone_for_traversal();
+    let branch = branch.map(ToString::to_string);
+
+    tokio::task::spawn_blocking(move || status(&repo_inner, branch.as_deref()))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
+}
+
+/// Fail with [`GitError::BehindUpstream`] if `branch` (the current branch if
+/// `None`) is behind its upstream.
+///
+/// The upstream remote is fetched first. If that fails, for example while
+/// offline, the last fetched remote-tracking branch is used instead and
+/// [`UpstreamStatus::fetched`] is `false`. Branches without an upstream pass
+/// the check with `None`.
+pub async fn ensure_up_to_date(
+    repo: RepoHandle,
+    branch: Option<&str>,
+) -> GitResult<Option<UpstreamStatus>> {
+    let Some(cached) = upstream_status(repo.clone(), branch).await? else {
+        return Ok(None);
+    };
+
+    let fetched = crate::fetch(repo.clone(), FetchOpts::from_remote(cached.remote.clone())).await;
+    let status = match fetched {
+        Ok(_) => upstream_status(repo, Some(&cached.branch))
+            .await?
+            .map(|status| UpstreamStatus {
+                fetched: true,
+                ..status
+            })
+            .unwrap_or(cached),
+        Err(e) => {
+            let mut status = cached;
+            status.warnings.push(
+                OperationWarning::new(
+                    WarningKind::FetchFailed,
+                    format!("fetch failed, counts are as last fetched ({e})"),
+                )
+                .subject(status.remote.clone()),
+            );
+            status
+        }
+    };
+
+    if status.is_behind() {
+        return Err(GitError::BehindUpstream {
+            branch: status.branch,
+            upstream: status.upstream,
+            ahead: status.ahead,
+            behind: status.behind,
+        });
+    }
+    Ok(Some(status))
+}
+
+fn status(repo: &gix::Repository, branch: Option<&str>) -> GitResult<Option<UpstreamStatus>> {
+    let full_name = match branch {
+        Some(branch) => format!("refs/heads/{branch}"),
+        None => match repo.head_name().map_err(GitError::gix)? {
+            Some(name) => name.as_bstr().to_str_lossy().into_owned(),
+            None => return Ok(None),
+        },
+    };
+    let Some(mut local) = repo
+        .try_find_reference(full_name.as_str())
+        .map_err(GitError::gix)?
+    else {
+        return Ok(None);
+    };
+    let local_id = local.peel_to_id().map_err(GitError::gix)?.detach();
+
+    let Some(tracking) =
+        repo.branch_remote_tracking_ref_name(local.name(), gix::remote::Direction::Fetch)
+    else {
+        return Ok(None);
+    };
+    let tracking = tracking.map_err(GitError::gix)?.into_owned();
+    let Some(mut upstream) = repo
+        .try_find_reference(tracking.as_bstr())
+        .map_err(GitError::gix)?
+    else {
+        return Ok(None);
+    };
+    let upstream_id = upstream.peel_to_id().map_err(GitError::gix)?.detach();
+
+    let branch_name = local.name().shorten().to_str_lossy().into_owned();
+    let remote = repo
+        .branch_remote_name(branch_name.as_bytes().as_bstr(), gix::remote::Direction::Fetch)
+        .map(|name| name.as_bstr().to_str_lossy().into_owned())
+        .unwrap_or_default();
+
+    let range = format!("{local_id}...{upstream_id}");
+    let (ahead, behind) =
+        match super::midx::bitmap_count(repo, &["--left-right", &range]).as_deref() {
+            Some(&[ahead, behind]) => (ahead, behind),
+            _ => (
+                count_only_in(repo, local_id, upstream_id)?,
+                count_only_in(repo, upstream_id, local_id)?,
+            ),
+        };
+
+    Ok(Some(UpstreamStatus {
+        upstream: tracking.as_ref().shorten().to_str_lossy().into_owned(),
+        remote,
+        ahead,
+        behind,
+        fetched: false,
+        branch: branch_name,
+        warnings: Vec::new(),
+    }))
+}
+
+/// Warning for a branch (the current branch if `None`) whose configured
+/// upstream has not been fetched, which [`upstream_status`] reports as `None`
+pub(crate) fn unfetched_upstream(
+    repo: &gix::Repository,
+    branch: Option<&str>,
+) -> GitResult<Option<OperationWarning>> {
+    let full_name = match branch {
+        Some(branch) => format!("refs/heads/{branch}"),
+        None => match repo.head_name().map_err(GitError::gix)? {
+            Some(name) => name.as_bstr().to_str_lossy().into_owned(),
+            None => return Ok(None),
+        },
+    };
+    let Ok(name) = gix::refs::FullName::try_from(full_name) else {
+        return Ok(None);
+    };
+    let Some(tracking) =
+        repo.branch_remote_tracking_ref_name(name.as_ref(), gix::remote::Direction::Fetch)
+    else {
+        return Ok(None);
+    };
+    let tracking = tracking.map_err(GitError::gix)?.into_owned();
+    if repo
+        .try_find_reference(tracking.as_bstr())
+        .map_err(GitError::gix)?
+        .is_some()
+    {
+        return Ok(None);
+    }
+    Ok(Some(
+        OperationWarning::new(
+            WarningKind::UpstreamUnavailable,
+            "upstream is configured but has not been fetched",
+        )
+        .subject(tracking.as_ref().shorten().to_str_lossy()),
+    ))
+}
+
+/// Number of commits reachable from `tip` but not from `hidden`
+fn count_only_in(repo: &gix::Repository, tip: CommitId, hidden: CommitId) -> GitResult<usize> {
+    if tip == hidden {
+        return Ok(0);
+    }
+    let walk = repo
+        .rev_walk([tip])
+        .with_hidden([hidden])
+        .all()
+        .map_err(GitError::gix)?;
+    let mut count = 0;
+    for info in walk {
+        info.map_err(GitError::gix)?;
+        count += 1;
+    }
+    Ok(count)
+}
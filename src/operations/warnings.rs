@@ -0,0 +1,89 @@
+//! Recoverable problems reported alongside operation results.
+//!
+//! An operation that works around a problem, like a timestamp it can't read or
+//! a remote it can't reach, still succeeds, but records an
+//! [`OperationWarning`] in the `warnings` of its result so that degraded data
+//! doesn't pass for complete data.
+
+/// What kind of problem an [`OperationWarning`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A timestamp couldn't be read and was left out.
+    MissingTimestamp,
+    /// A signature couldn't be parsed and was left out.
+    UnparsableSignature,
+    /// The upstream is configured, but ahead/behind counts couldn't be computed.
+    UpstreamUnavailable,
+    /// The remote couldn't be fetched; its refs as last fetched were used.
+    FetchFailed,
+    /// A forced update replaced history on the remote.
+    ForcePush,
+    /// A stale remote-tracking ref couldn't be deleted and was kept.
+    PruneFailed,
+    /// The size of a received pack couldn't be read and was reported as 0.
+    PackSizeUnknown,
+    /// A remote's configuration couldn't be fully read; what's missing was
+    /// left out.
+    UnreadableRemote,
+    /// A worktree's `HEAD` couldn't be read; its commit and branch were left out.
+    UnreadableHead,
+}
+
+impl WarningKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingTimestamp => "missing-timestamp",
+            Self::UnparsableSignature => "unparsable-signature",
+            Self::UpstreamUnavailable => "upstream-unavailable",
+            Self::FetchFailed => "fetch-failed",
+            Self::ForcePush => "force-push",
+            Self::PruneFailed => "prune-failed",
+            Self::PackSizeUnknown => "pack-size-unknown",
+            Self::UnreadableRemote => "unreadable-remote",
+            Self::UnreadableHead => "unreadable-head",
+        }
+    }
+}
+
+impl std::fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A problem an operation worked around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationWarning {
+    pub kind: WarningKind,
+    /// What the warning is about: a ref, remote or path
+    pub subject: Option<String>,
+    pub message: String,
+}
+
+impl OperationWarning {
+    #[must_use]
+    pub fn new(kind: WarningKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            subject: None,
+            message: message.into(),
+        }
+    }
+
+    /// Name what the warning is about.
+    #[must_use]
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+}
+
+impl std::fmt::Display for OperationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.subject {
+            Some(subject) => write!(f, "{subject}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Coordinated operations across several repositories.
+//!
+//! A change that spans "backend" and "infra" should land as one commit per
+//! repository with the same message, so the pieces can be found together
+//! later. [`workspace_commit`] stages and commits every dirty repository with
+//! a shared message and trailers (optionally a shared `Change-Id`), and
+//! [`workspace_status`] reports which repositories have anything to commit.
+//!
+//! Repositories are given by registered name (see [`super::registry`]) or by
+//! path.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::fast_status::has_untracked;
+use crate::{AddOpts, CommitId, CommitOpts, GitError, GitResult, RepoHandle, Signature, Trailer};
+
+/// How [`workspace_commit`] stages changes and handles failures
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceCommitPolicy {
+    /// Stage untracked files too (like `git add .`); otherwise only changes
+    /// to tracked files are committed (like `git commit -a`).
+    pub include_untracked: bool,
+    /// Commit nothing unless every repository could be staged.
+    pub all_or_nothing: bool,
+    pub author: Option<Signature>,
+    /// Trailers appended to the message in every repository.
+    pub trailers: Vec<Trailer>,
+    /// Append a `Change-Id` trailer shared by all the commits.
+    pub change_id: bool,
+}
+
+impl WorkspaceCommitPolicy {
+    /// Commit tracked changes in every repository that has some.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn include_untracked(mut self, yes: bool) -> Self {
+        self.include_untracked = yes;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn all_or_nothing(mut self, yes: bool) -> Self {
+        self.all_or_nothing = yes;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn author(mut self, author: Signature) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Append `token: value` to every commit message.
+    #[inline]
+    #[must_use]
+    pub fn trailer(mut self, token: impl Into<String>, value: impl Into<String>) -> Self {
+        self.trailers.push(Trailer {
+            token: token.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn change_id(mut self, yes: bool) -> Self {
+        self.change_id = yes;
+        self
+    }
+}
+
+/// What happened in one repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceCommitOutcome {
+    Committed(CommitId),
+    /// Nothing to commit after staging.
+    Clean,
+    /// Left untouched because another repository failed under
+    /// [`WorkspaceCommitPolicy::all_or_nothing`].
+    Skipped,
+    Failed(String),
+}
+
+/// Result of [`workspace_commit`] for one repository
+#[derive(Debug, Clone)]
+pub struct WorkspaceCommit {
+    /// Name or path as given.
+    pub repo: String,
+    pub outcome: WorkspaceCommitOutcome,
+}
+
+/// Result of [`workspace_commit`]
+#[derive(Debug, Clone)]
+pub struct WorkspaceCommitResult {
+    /// The shared `Change-Id`, if one was requested.
+    pub change_id: Option<String>,
+    /// The full message every commit was made with.
+    pub message: String,
+    /// One entry per repository, in the order given.
+    pub repos: Vec<WorkspaceCommit>,
+}
+
+impl WorkspaceCommitResult {
+    /// Ids of the commits made, by repository.
+    pub fn commits(&self) -> impl Iterator<Item = (&str, CommitId)> {
+        self.repos.iter().filter_map(|repo| match repo.outcome {
+            WorkspaceCommitOutcome::Committed(id) => Some((repo.repo.as_str(), id)),
+            _ => None,
+        })
+    }
+
+    /// Whether any repository failed.
+    #[must_use]
+    pub fn has_failures(&self) -> bool {
+        self.repos
+            .iter()
+            .any(|repo| matches!(repo.outcome, WorkspaceCommitOutcome::Failed(_)))
+    }
+}
+
+/// Dirty state of one repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceRepoStatus {
+    /// Name or path as given.
+    pub repo: String,
+    /// Tracked files differ from `HEAD` (staged or not).
+    pub is_clean: bool,
+    /// Untracked, non-ignored files exist.
+    pub has_untracked: bool,
+}
+
+/// Report which repositories have changes to commit.
+pub async fn workspace_status<I, S>(repos: I) -> GitResult<Vec<WorkspaceRepoStatus>>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut statuses = Vec::new();
+    for name in repos.into_iter().map(Into::into) {
+        let repo = open(&name).await?;
+        let is_clean = crate::is_clean(repo.clone()).await?;
+        let inner = repo.clone_inner();
+        let has_untracked = tokio::task::spawn_blocking(move || has_untracked(&inner))
+            .await
+            .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+        statuses.push(WorkspaceRepoStatus {
+            repo: name,
+            is_clean,
+            has_untracked,
+        });
+    }
+    Ok(statuses)
+}
+
+/// Stage and commit changes in every repository with a shared message.
+///
+/// Repositories with nothing to commit are reported as
+/// [`WorkspaceCommitOutcome::Clean`]. All repositories are staged before any
+/// is committed, so with [`WorkspaceCommitPolicy::all_or_nothing`] a
+/// repository that cannot be opened or staged prevents every commit; what was
+/// staged stays staged. Otherwise failures are reported per repository and
+/// the rest are committed.
+pub async fn workspace_commit<I, S>(
+    repos: I,
+    message: &str,
+    policy: WorkspaceCommitPolicy,
+) -> GitResult<WorkspaceCommitResult>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    if message.trim().is_empty() {
+        return Err(GitError::InvalidInput("Commit message cannot be empty".to_string()));
+    }
+    let names: Vec<String> = repos.into_iter().map(Into::into).collect();
+    if names.is_empty() {
+        return Err(GitError::InvalidInput("No repositories given".to_string()));
+    }
+
+    let change_id = policy.change_id.then(|| change_id(message, &names));
+    let message = full_message(message, &policy.trailers, change_id.as_deref());
+
+    // Stage everything first so all-or-nothing can back out before committing
+    let mut staged = Vec::with_capacity(names.len());
+    for name in &names {
+        staged.push(stage(name, policy.include_untracked).await);
+    }
+    let abort = policy.all_or_nothing && staged.iter().any(Result::is_err);
+
+    let mut results = Vec::with_capacity(names.len());
+    for (name, staged) in names.into_iter().zip(staged) {
+        let outcome = match staged {
+            Err(e) => WorkspaceCommitOutcome::Failed(e.to_string()),
+            Ok(_) if abort => WorkspaceCommitOutcome::Skipped,
+            Ok(None) => WorkspaceCommitOutcome::Clean,
+            Ok(Some(repo)) => {
+                let mut opts = CommitOpts::message(message.as_str());
+                if let Some(author) = &policy.author {
+                    opts = opts.author(author.clone());
+                }
+                match crate::commit(repo, opts).await {
+                    Ok(commit) => WorkspaceCommitOutcome::Committed(commit.id),
+                    Err(e) => WorkspaceCommitOutcome::Failed(e.to_string()),
+                }
+            }
+        };
+        results.push(WorkspaceCommit {
+            repo: name,
+            outcome,
+        });
+    }
+
+    Ok(WorkspaceCommitResult {
+        change_id,
+        message,
+        repos: results,
+    })
+}
+
+/// Open a repository by registered name or path
+async fn open(name: &str) -> GitResult<RepoHandle> {
+    let path = super::registry::resolve_repo_path(name).await?;
+    crate::open_repo(path)
+        .await
+        .map_err(|_| GitError::ChannelClosed)?
+}
+
+/// Stage changes in `name`; the repository if anything is staged to commit
+async fn stage(name: &str, include_untracked: bool) -> GitResult<Option<RepoHandle>> {
+    let repo = open(name).await?;
+    let inner = repo.clone_inner();
+    let untracked = include_untracked
+        && tokio::task::spawn_blocking(move || has_untracked(&inner))
+            .await
+            .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    if untracked || !crate::is_clean(repo.clone()).await? {
+        let opts = AddOpts::new(["."])
+            .all(include_untracked)
+            .update_only(!include_untracked);
+        crate::add(repo.clone(), opts).await?;
+    }
+
+    let inner = repo.clone_inner();
+    let has_staged =
+        tokio::task::spawn_blocking(move || super::fast_status::staged_changes(&inner))
+            .await
+            .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+    Ok(has_staged.then_some(repo))
+}
+
+/// `message` followed by the trailers, in a paragraph of their own
+fn full_message(message: &str, trailers: &[Trailer], change_id: Option<&str>) -> String {
+    let mut message = message.trim_end().to_string();
+    let mut lines: Vec<String> = trailers
+        .iter()
+        .map(|trailer| format!("{}: {}", trailer.token, trailer.value))
+        .collect();
+    if let Some(change_id) = change_id {
+        lines.push(format!("Change-Id: {change_id}"));
+    }
+    if !lines.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(&lines.join("\n"));
+    }
+    message.push('\n');
+    message
+}
+
+/// A Gerrit-style `Change-Id` (`I` and 40 hex digits), unique to this call
+fn change_id(message: &str, repos: &[String]) -> String {
+    let mut hasher = gix::hash::hasher(gix::hash::Kind::Sha1);
+    hasher.update(message.as_bytes());
+    for repo in repos {
+        hasher.update(repo.as_bytes());
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    hasher.update(&now.to_le_bytes());
+    hasher.update(&std::process::id().to_le_bytes());
+    match hasher.try_finalize() {
+        Ok(id) => format!("I{id}"),
+        // Collision detection only fails on crafted SHA-1 attacks, never on this input
+        Err(_) => format!("I{now:040x}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_message_appends_trailers() {
+        let trailers = [Trailer {
+            token: "Refs".to_string(),
+            value: "#42".to_string(),
+        }];
+        assert_eq!(
+            full_message("Bump API\n", &trailers, Some("Iabc")),
+            "Bump API\n\nRefs: #42\nChange-Id: Iabc\n"
+        );
+        assert_eq!(full_message("Bump API", &[], None), "Bump API\n");
+    }
+
+    #[test]
+    fn test_change_id_format() {
+        let id = change_id("message", &["backend".to_string()]);
+        assert_eq!(id.len(), 41);
+        assert!(id.starts_with('I'));
+        assert!(id[1..].bytes().all(|b| b.is_ascii_hexdigit()));
+    }
+}
@@ -6,15 +6,23 @@ use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 
 use gix::bstr::ByteSlice;
+use gix::refs::transaction::PreviousValue;
 
+use crate::operations::checkout::checkout_failed;
+use crate::operations::branch::{parse_remote_branch, setup_tracking};
 use crate::runtime::AsyncTask;
-use crate::{GitError, GitResult, RepoHandle};
+use crate::{FetchOpts, GitError, GitResult, RepoHandle};
 
 use super::helpers::{check_branch_not_in_use, cleanup_failed_worktree, extract_branch_name};
 use super::types::WorktreeAddOpts;
 
 /// Create a new linked worktree.
 ///
+/// A remote branch such as `origin/feature` is fetched if it hasn't been yet
+/// and checked out on a local branch `feature` tracking it, which is created
+/// unless it already exists with that upstream. With `detach`, the fetched
+/// commit is checked out on a detached `HEAD` instead.
+///
 /// # Examples
 ///
 /// ```ignore
@@ -23,8 +31,118 @@ use super::types::WorktreeAddOpts;
 /// let path = worktree_add(repo, opts).await?;
 /// ```
 pub fn worktree_add(repo: RepoHandle, opts: WorktreeAddOpts) -> AsyncTask<GitResult<PathBuf>> {
-    let repo = repo.clone_inner();
-    AsyncTask::spawn(move || worktree_add_impl(repo, opts))
+    AsyncTask::spawn_async(async move {
+        let remote_branch = opts
+            .committish
+            .as_deref()
+            .and_then(|committish| remote_branch_of(repo.raw(), committish));
+        let Some((remote, branch)) = remote_branch else {
+            let repo = repo.clone_inner();
+            return run_blocking(move || worktree_add_impl(repo, opts)).await;
+        };
+
+        if opts.path.exists() && !opts.force {
+            return Err(GitError::WorktreeAlreadyExists(opts.path.clone()));
+        }
+
+        let tracking_ref = format!("refs/remotes/{remote}/{branch}");
+        if repo.raw().find_reference(tracking_ref.as_str()).is_err() {
+            crate::fetch(
+                repo.clone(),
+                FetchOpts::from_remote(remote.as_str())
+                    .add_refspec(format!("+refs/heads/{branch}:{tracking_ref}")),
+            )
+            .await?;
+        }
+
+        let mut repo = repo.clone_inner();
+        run_blocking(move || {
+            let target = repo
+                .find_reference(tracking_ref.as_str())
+                .map_err(|_| GitError::BranchNotFound(format!("{remote}/{branch}")))?
+                .into_fully_peeled_id()
+                .map_err(GitError::gix)?
+                .detach();
+
+            let committish = if opts.detach {
+                target.to_string()
+            } else {
+                tracking_branch(&mut repo, &remote, &branch, target)?
+            };
+            let opts = WorktreeAddOpts {
+                committish: Some(committish),
+                ..opts
+            };
+            worktree_add_impl(repo, opts)
+        })
+        .await
+    })
+}
+
+/// `(remote, branch)` if `committish` names a branch of a configured remote,
+/// like `origin/feature` or `refs/remotes/origin/feature`
+fn remote_branch_of(repo: &gix::Repository, committish: &str) -> Option<(String, String)> {
+    // A local branch of the same name is what the committish resolves to
+    if repo
+        .find_reference(format!("refs/heads/{committish}").as_str())
+        .is_ok()
+    {
+        return None;
+    }
+    let (remote, branch) = parse_remote_branch(committish)?;
+    repo.find_remote(remote.as_bytes().as_bstr()).ok()?;
+    Some((remote.to_string(), branch.to_string()))
+}
+
+/// Create the local branch `branch` at `target`, tracking `remote`'s branch of
+/// the same name, and return its full ref name.
+///
+/// An existing local branch is reused if it already tracks that branch, so
+/// retrying after a failed checkout doesn't fail on the branch made before.
+fn tracking_branch(
+    repo: &mut gix::Repository,
+    remote: &str,
+    branch: &str,
+    target: gix::ObjectId,
+) -> GitResult<String> {
+    let branch_ref = format!("refs/heads/{branch}");
+    if repo.find_reference(branch_ref.as_str()).is_ok() {
+        // Read the file, as tracking may be set up after this handle was opened
+        let config = gix::config::File::from_path_no_includes(
+            repo.common_dir().join("config"),
+            gix::config::Source::Local,
+        )
+        .map_err(GitError::gix)?;
+        let tracked_remote = config.string(format!("branch.{branch}.remote"));
+        let tracked_merge = config.string(format!("branch.{branch}.merge"));
+        if tracked_remote.as_deref() == Some(remote.as_bytes().as_bstr())
+            && tracked_merge.as_deref() == Some(branch_ref.as_bytes().as_bstr())
+        {
+            return Ok(branch_ref);
+        }
+        return Err(GitError::InvalidInput(format!(
+            "A branch named '{branch}' already exists and does not track {remote}/{branch}"
+        )));
+    }
+
+    repo.reference(
+        branch_ref.as_str(),
+        target,
+        PreviousValue::MustNotExist,
+        format!("branch: Created from {remote}/{branch}"),
+    )
+    .map_err(GitError::gix)?;
+    setup_tracking(repo, branch, remote, branch)?;
+    Ok(branch_ref)
+}
+
+/// Run `f` on the blocking pool
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> GitResult<T> + Send + 'static,
+) -> GitResult<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))?
 }
 
 fn worktree_add_impl(repo: gix::Repository, opts: WorktreeAddOpts) -> GitResult<PathBuf> {
@@ -226,7 +344,7 @@ fn worktree_add_impl(repo: gix::Repository, opts: WorktreeAddOpts) -> GitResult<
         Ok(r) => r,
         Err(e) => {
             cleanup();
-            return Err(GitError::Gix(Box::new(e)));
+            return Err(GitError::gix(e));
         }
     };
 
@@ -238,12 +356,12 @@ fn worktree_add_impl(repo: gix::Repository, opts: WorktreeAddOpts) -> GitResult<
             Ok(c) => c,
             Err(e) => {
                 cleanup();
-                return Err(GitError::Gix(Box::new(e)));
+                return Err(GitError::gix(e));
             }
         },
         Err(e) => {
             cleanup();
-            return Err(GitError::Gix(Box::new(e)));
+            return Err(GitError::gix(e));
         }
     };
 
@@ -251,7 +369,7 @@ fn worktree_add_impl(repo: gix::Repository, opts: WorktreeAddOpts) -> GitResult<
         Ok(id) => id,
         Err(e) => {
             cleanup();
-            return Err(GitError::Gix(Box::new(e)));
+            return Err(GitError::gix(e));
         }
     };
 
@@ -260,9 +378,7 @@ fn worktree_add_impl(repo: gix::Repository, opts: WorktreeAddOpts) -> GitResult<
         Ok(idx) => idx,
         Err(e) => {
             cleanup();
-            return Err(GitError::Gix(
-                format!("Failed to create index from tree: {e}").into(),
-            ));
+            return Err(GitError::gix(e));
         }
     };
 
@@ -273,9 +389,7 @@ fn worktree_add_impl(repo: gix::Repository, opts: WorktreeAddOpts) -> GitResult<
         Ok(opts) => opts,
         Err(e) => {
             cleanup();
-            return Err(GitError::Gix(
-                format!("Failed to create checkout options: {e}").into(),
-            ));
+            return Err(GitError::gix(e));
         }
     };
 
@@ -287,9 +401,7 @@ fn worktree_add_impl(repo: gix::Repository, opts: WorktreeAddOpts) -> GitResult<
         Ok(odb) => odb,
         Err(e) => {
             cleanup();
-            return Err(GitError::Gix(
-                format!("Failed to access object database: {e}").into(),
-            ));
+            return Err(GitError::gix(e));
         }
     };
 
@@ -305,30 +417,21 @@ fn worktree_add_impl(repo: gix::Repository, opts: WorktreeAddOpts) -> GitResult<
         Ok(o) => o,
         Err(e) => {
             cleanup();
-            return Err(GitError::Gix(
-                format!("Checkout operation failed: {e}").into(),
-            ));
+            return Err(GitError::gix(e));
         }
     };
 
     // Handle errors
     if !outcome.errors.is_empty() {
         cleanup();
-        return Err(GitError::Gix(
-            format!(
-                "Checkout failed with {} error(s): {}",
-                outcome.errors.len(),
-                outcome.errors[0].error
-            )
-            .into(),
-        ));
+        return Err(checkout_failed(outcome.errors));
     }
 
     // Write index to disk
     // Write index with proper locking and checksum
     if let Err(e) = index.write(gix::index::write::Options::default()) {
         cleanup();
-        return Err(GitError::Gix(format!("Failed to write index: {e}").into()));
+        return Err(GitError::gix(e));
     }
 
     // Phase 6: Return result
@@ -49,10 +49,11 @@ pub(super) fn read_head_info(
         Ok((commit_id, branch_name, false))
     } else {
         // Direct commit ID (detached HEAD)
-        // Parse the object ID
-        let commit_id = gix::hash::ObjectId::from_hex(head_content.as_bytes()).ok();
+        let commit_id = gix::hash::ObjectId::from_hex(head_content.as_bytes()).map_err(|e| {
+            GitError::Parse(format!("Invalid HEAD at {}: {e}", head_file.display()))
+        })?;
 
-        Ok((commit_id, None, true))
+        Ok((Some(commit_id), None, true))
     }
 }
 
@@ -85,7 +86,7 @@ pub(super) fn extract_branch_name(committish: &str) -> Option<&str> {
 }
 
 /// Check if a branch is already checked out in any worktree.
-pub(super) fn check_branch_not_in_use(repo: &gix::Repository, branch: &str) -> GitResult<()> {
+pub(crate) fn check_branch_not_in_use(repo: &gix::Repository, branch: &str) -> GitResult<()> {
     let branch_ref = format!("refs/heads/{branch}");
 
     // Check linked worktrees
@@ -102,8 +103,8 @@ pub(super) fn check_branch_not_in_use(repo: &gix::Repository, branch: &str) -> G
         }
     }
 
-    // Check main worktree
-    let main_head = repo.git_dir().join("HEAD");
+    // Check main worktree (common dir, so this also works from a linked worktree handle)
+    let main_head = repo.common_dir().join("HEAD");
     if let Ok(head_content) = std::fs::read_to_string(&main_head) {
         // Check for exact match to avoid false positives (e.g., "main-dev" matching "main")
         let head_trimmed = head_content.trim();
@@ -123,6 +124,21 @@ pub(super) fn cleanup_failed_worktree(worktree_path: &Path, worktree_git_dir: &P
     let _ = std::fs::remove_dir_all(worktree_git_dir);
 }
 
+/// Return the main repository for `repo`.
+///
+/// A handle opened on a linked worktree has its own git dir under
+/// `.git/worktrees/<name>`; this reopens the repository from the common dir so
+/// the main worktree is reported correctly.
+pub(super) fn main_repo_of(repo: gix::Repository) -> GitResult<gix::Repository> {
+    if repo.git_dir() == repo.common_dir() {
+        return Ok(repo);
+    }
+    // The common dir is `<git dir>/../..`; gix only takes `core.worktree`
+    // from a config under the git dir as written, so resolve it first
+    let common_dir = repo.common_dir().canonicalize()?;
+    gix::open_opts(common_dir, repo.open_options().clone()).map_err(GitError::gix)
+}
+
 /// Find a linked worktree by its checkout path.
 ///
 /// Returns None if not found or if the path is the main worktree.
@@ -144,3 +160,19 @@ pub(super) fn find_worktree_by_path<'a>(
         false
     })
 }
+
+/// Human-readable name of an in-progress operation.
+pub(super) fn in_progress_name(state: gix::state::InProgress) -> &'static str {
+    use gix::state::InProgress;
+
+    match state {
+        InProgress::ApplyMailbox => "am",
+        InProgress::ApplyMailboxRebase => "am-rebase",
+        InProgress::Bisect => "bisect",
+        InProgress::CherryPick | InProgress::CherryPickSequence => "cherry-pick",
+        InProgress::Merge => "merge",
+        InProgress::Rebase => "rebase",
+        InProgress::RebaseInteractive => "rebase-interactive",
+        InProgress::Revert | InProgress::RevertSequence => "revert",
+    }
+}
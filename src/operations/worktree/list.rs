@@ -2,10 +2,13 @@
 //!
 //! This module provides functionality to list all worktrees in a repository.
 
+use std::path::Path;
+
+use crate::operations::warnings::{OperationWarning, WarningKind};
 use crate::runtime::AsyncTask;
 use crate::{GitResult, RepoHandle};
 
-use super::helpers::read_head_info;
+use super::helpers::{main_repo_of, read_head_info};
 use super::types::WorktreeInfo;
 
 /// List all worktrees in the repository.
@@ -25,6 +28,8 @@ use super::types::WorktreeInfo;
 pub fn list_worktrees(repo: RepoHandle) -> AsyncTask<GitResult<Vec<WorktreeInfo>>> {
     let repo = repo.clone_inner();
     AsyncTask::spawn(move || {
+        // Always enumerate from the main repository, even for a worktree handle
+        let repo = main_repo_of(repo)?;
         let mut all_worktrees = Vec::new();
 
         // Check if bare repository
@@ -36,17 +41,9 @@ pub fn list_worktrees(repo: RepoHandle) -> AsyncTask<GitResult<Vec<WorktreeInfo>
             let path = main_worktree.base().to_path_buf();
 
             // Read HEAD information for main worktree (best effort)
+            let mut warnings = Vec::new();
             let (head_commit, head_branch, is_detached) =
-                if let Ok(info) = read_head_info(&git_dir, &repo) {
-                    info
-                } else {
-                    // HEAD file missing/corrupted - provide partial info
-                    eprintln!(
-                        "Warning: Failed to read HEAD for main worktree at {}",
-                        git_dir.display()
-                    );
-                    (None, None, false)
-                };
+                head_info(&git_dir, &repo, &path, &mut warnings);
 
             let main_info = WorktreeInfo {
                 path,
@@ -58,6 +55,7 @@ pub fn list_worktrees(repo: RepoHandle) -> AsyncTask<GitResult<Vec<WorktreeInfo>
                 is_locked: false, // Main worktree cannot be locked
                 lock_reason: None,
                 is_detached,
+                warnings,
             };
 
             all_worktrees.push(main_info);
@@ -72,17 +70,9 @@ pub fn list_worktrees(repo: RepoHandle) -> AsyncTask<GitResult<Vec<WorktreeInfo>
             let git_dir = proxy.git_dir().to_path_buf();
 
             // Read HEAD information for this worktree (best effort)
+            let mut warnings = Vec::new();
             let (head_commit, head_branch, is_detached) =
-                if let Ok(info) = read_head_info(&git_dir, &repo) {
-                    info
-                } else {
-                    // HEAD file missing/corrupted - provide partial info
-                    eprintln!(
-                        "Warning: Failed to read HEAD for worktree at {}",
-                        path.display()
-                    );
-                    (None, None, false)
-                };
+                head_info(&git_dir, &repo, &path, &mut warnings);
 
             // Get lock status
             let is_locked = proxy.is_locked();
@@ -105,6 +95,7 @@ pub fn list_worktrees(repo: RepoHandle) -> AsyncTask<GitResult<Vec<WorktreeInfo>
                 is_locked,
                 lock_reason,
                 is_detached,
+                warnings,
             };
 
             all_worktrees.push(worktree_info);
@@ -113,3 +104,23 @@ pub fn list_worktrees(repo: RepoHandle) -> AsyncTask<GitResult<Vec<WorktreeInfo>
         Ok(all_worktrees)
     })
 }
+
+/// HEAD information of the worktree at `path`, or none with a warning if
+/// its HEAD file is missing or corrupt
+fn head_info(
+    git_dir: &Path,
+    repo: &gix::Repository,
+    path: &Path,
+    warnings: &mut Vec<OperationWarning>,
+) -> (Option<gix::ObjectId>, Option<String>, bool) {
+    read_head_info(git_dir, repo).unwrap_or_else(|e| {
+        warnings.push(
+            OperationWarning::new(
+                WarningKind::UnreadableHead,
+                format!("HEAD could not be read ({e}); commit and branch left out"),
+            )
+            .subject(path.display().to_string()),
+        );
+        (None, None, false)
+    })
+}
@@ -1,22 +1,31 @@
 //! Git worktree operations with comprehensive options.
 //!
 //! This module provides worktree management functionality including creation,
-//! listing, locking, and removal of worktrees using the gix (Gitoxide) library.
+//! listing, locking, opening, and removal of worktrees using the gix (Gitoxide) library.
 
 mod add;
 mod helpers;
 mod list;
 mod lock;
+mod open;
 mod prune;
 mod remove;
+mod status;
 mod types;
 
 // Re-export public types
-pub use types::{WorktreeAddOpts, WorktreeInfo, WorktreeLockOpts, WorktreeRemoveOpts};
+pub use types::{
+    PruneReason, PrunedWorktree, SkippedWorktree, WorktreeAddOpts, WorktreeInfo,
+    WorktreeLockOpts, WorktreePruneOpts, WorktreePruneReport, WorktreeRemoveOpts, WorktreeStatus,
+};
 
 // Re-export public functions
 pub use add::worktree_add;
 pub use list::list_worktrees;
 pub use lock::{worktree_lock, worktree_unlock};
-pub use prune::worktree_prune;
+pub use open::open_worktree;
+pub use prune::{worktree_prune, worktree_prune_with};
 pub use remove::worktree_remove;
+pub use status::worktree_statuses;
+
+pub(crate) use helpers::check_branch_not_in_use;
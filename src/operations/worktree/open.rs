@@ -0,0 +1,71 @@
+//! Per-worktree repository handles.
+//!
+//! This module opens a linked worktree as its own `RepoHandle`, so index,
+//! HEAD and in-progress state resolve against that worktree rather than the
+//! main checkout.
+
+use std::path::Path;
+
+use crate::runtime::AsyncTask;
+use crate::{GitError, GitResult, RepoHandle};
+
+use super::helpers::{find_worktree_by_path, main_repo_of};
+
+/// Open a worktree of `repo` as its own repository handle.
+///
+/// `name_or_path` is either the worktree name (the directory under
+/// `.git/worktrees/`) or its checkout path. Passing the main worktree's path
+/// returns a handle to the main repository, so callers can hold a handle for
+/// any worktree regardless of which one `repo` was opened from.
+///
+/// The returned handle works with every operation that takes a `RepoHandle`;
+/// commits, checkouts and merges made through it update that worktree's HEAD
+/// and index.
+///
+/// # Examples
+///
+/// ```ignore
+/// let feature = open_worktree(repo, "feature-x").await??;
+/// add(feature.clone(), AddOpts::new(["src/lib.rs"])).await?;
+/// commit(feature, CommitOpts::message("Work in worktree")).await?;
+/// ```
+pub fn open_worktree(
+    repo: RepoHandle,
+    name_or_path: impl AsRef<Path>,
+) -> AsyncTask<GitResult<RepoHandle>> {
+    let repo = repo.clone_inner();
+    let name_or_path = name_or_path.as_ref().to_path_buf();
+    AsyncTask::spawn(move || open_worktree_impl(repo, &name_or_path))
+}
+
+fn open_worktree_impl(repo: gix::Repository, name_or_path: &Path) -> GitResult<RepoHandle> {
+    let main = main_repo_of(repo)?;
+
+    // Main worktree by path
+    if let Some(main_worktree) = main.worktree()
+        && let (Ok(base), Ok(search)) = (
+            main_worktree.base().canonicalize(),
+            name_or_path.canonicalize(),
+        )
+        && base == search
+    {
+        return Ok(RepoHandle::new(main));
+    }
+
+    let mut worktrees = main.worktrees().map_err(GitError::Io)?;
+
+    // Linked worktree by name first, then by checkout path
+    let by_name = name_or_path
+        .to_str()
+        .and_then(|name| worktrees.iter().position(|proxy| proxy.id() == name));
+    let index = by_name
+        .or_else(|| {
+            let found = find_worktree_by_path(&worktrees, name_or_path)?;
+            worktrees.iter().position(|proxy| proxy.git_dir() == found.git_dir())
+        })
+        .ok_or_else(|| GitError::WorktreeNotFound(name_or_path.display().to_string()))?;
+    let proxy = worktrees.swap_remove(index);
+
+    let worktree_repo = proxy.into_repo().map_err(GitError::gix)?;
+    Ok(RepoHandle::new(worktree_repo))
+}
@@ -1,12 +1,25 @@
 //! Worktree pruning operation.
 //!
-//! This module provides functionality to prune stale worktree administrative files.
+//! This module provides functionality to prune stale worktree administrative files
+//! and, optionally, to garbage-collect idle or merged worktrees.
+
+use std::path::Path;
+use std::time::SystemTime;
 
 use gix::bstr::ByteSlice;
+use gix::hash::ObjectId;
 
+use crate::operations::compare::resolve_commit;
 use crate::runtime::AsyncTask;
 use crate::{GitError, GitResult, RepoHandle};
 
+use super::helpers::{main_repo_of, read_head_info};
+use super::types::{
+    PruneReason, PrunedWorktree, SkippedWorktree, WorktreePruneOpts, WorktreePruneReport,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
 /// Prune stale worktree administrative files.
 ///
 /// Returns a list of pruned worktree names.
@@ -19,51 +32,221 @@ use crate::{GitError, GitResult, RepoHandle};
 /// ```
 pub fn worktree_prune(repo: RepoHandle) -> AsyncTask<GitResult<Vec<String>>> {
     let repo = repo.clone_inner();
-    AsyncTask::spawn(move || worktree_prune_impl(repo))
+    AsyncTask::spawn(move || {
+        let report = worktree_prune_impl(repo, &WorktreePruneOpts::new())?;
+        Ok(report.pruned.into_iter().map(|p| p.name).collect())
+    })
 }
 
-fn worktree_prune_impl(repo: gix::Repository) -> GitResult<Vec<String>> {
-    let mut pruned = Vec::new();
+/// Prune worktrees according to the given policies.
+///
+/// Worktrees whose directories are missing have their administrative files
+/// removed. With `older_than_days` or `merged_into` set, unlocked worktrees
+/// matching a policy are removed entirely (checkout directory included),
+/// unless they have uncommitted changes or untracked files that aren't
+/// ignored. Locked worktrees are never pruned.
+///
+/// A branch counts as merged when its tip is reachable from `merged_into`,
+/// matching `git branch --merged`, except that a branch pointing at the
+/// `merged_into` commit itself has no work of its own yet and is kept.
+///
+/// # Examples
+///
+/// ```ignore
+/// let opts = WorktreePruneOpts::new().older_than_days(30).merged_into("main");
+/// let report = worktree_prune_with(repo, opts).await??;
+/// for wt in &report.pruned {
+///     println!("{}: {:?}", wt.name, wt.reason);
+/// }
+/// ```
+pub fn worktree_prune_with(
+    repo: RepoHandle,
+    opts: WorktreePruneOpts,
+) -> AsyncTask<GitResult<WorktreePruneReport>> {
+    let repo = repo.clone_inner();
+    AsyncTask::spawn(move || worktree_prune_impl(repo, &opts))
+}
+
+fn worktree_prune_impl(
+    repo: gix::Repository,
+    opts: &WorktreePruneOpts,
+) -> GitResult<WorktreePruneReport> {
+    let repo = main_repo_of(repo)?;
+    let mut report = WorktreePruneReport {
+        dry_run: opts.dry_run,
+        ..WorktreePruneReport::default()
+    };
+
+    // Resolve the merge target once
+    let merge_target = match opts.merged_into {
+        Some(ref rev) => Some(resolve_commit(&repo, rev)?),
+        None => None,
+    };
 
     // Step 1: Get all linked worktrees
     let worktrees = repo.worktrees().map_err(GitError::Io)?;
 
-    // Step 2: Check each worktree for validity
+    // Step 2: Check each worktree against the policies
     for proxy in worktrees {
-        let should_prune = match proxy.base() {
+        let git_dir = proxy.git_dir().to_path_buf();
+        let name = proxy.id().to_str().unwrap_or("<unknown>").to_string();
+        let is_locked = proxy.is_locked();
+
+        let base = match proxy.base() {
+            // Prune if:
+            // - Worktree directory doesn't exist
+            // - .git file is missing or invalid
+            Ok(base) if base.exists() && base.join(".git").exists() => base,
             Ok(base) => {
-                // Prune if:
-                // - Worktree directory doesn't exist
-                // - .git file is missing or invalid
-                !base.exists() || !base.join(".git").exists()
+                prune_missing(&mut report, name, Some(base), &git_dir, is_locked);
+                continue;
             }
             Err(_) => {
                 // Prune if gitdir file is invalid/missing
-                true
+                prune_missing(&mut report, name, None, &git_dir, is_locked);
+                continue;
             }
         };
 
-        if should_prune {
-            let git_dir = proxy.git_dir().to_path_buf();
-            let name = proxy.id().to_str().unwrap_or("<unknown>").to_string();
-
-            // Remove stale worktree admin directory (best effort)
-            match std::fs::remove_dir_all(&git_dir) {
-                Ok(()) => {
-                    pruned.push(name);
-                }
-                Err(e) => {
-                    // Log warning but continue pruning others
-                    eprintln!(
-                        "Warning: Failed to prune worktree '{}' at {}: {}",
-                        name,
-                        git_dir.display(),
-                        e
-                    );
-                }
+        let Some(reason) = policy_match(&repo, &git_dir, opts, merge_target) else {
+            continue;
+        };
+
+        let skip = |reason: &str| SkippedWorktree {
+            name: name.clone(),
+            path: base.clone(),
+            reason: reason.to_string(),
+        };
+
+        if is_locked {
+            report.skipped.push(skip("locked"));
+            continue;
+        }
+
+        // Never discard uncommitted work, including files not added yet
+        let dirty = proxy.into_repo().map(|wt| {
+            let modified = wt.is_dirty().map_err(GitError::gix)?;
+            Ok::<_, GitError>(modified || crate::operations::fast_status::has_untracked(&wt)?)
+        });
+        match dirty {
+            Ok(Ok(false)) => {}
+            Ok(Ok(true)) => {
+                report.skipped.push(skip("has uncommitted changes"));
+                continue;
+            }
+            Ok(Err(e)) => {
+                report.skipped.push(skip(&format!("failed to compute dirty state: {e}")));
+                continue;
+            }
+            Err(e) => {
+                report.skipped.push(skip(&format!("failed to open worktree: {e}")));
+                continue;
             }
         }
+
+        if !opts.dry_run {
+            // Checkout first, then admin dir, so a failure never leaves an orphaned checkout
+            if let Err(e) = std::fs::remove_dir_all(&base) {
+                report.skipped.push(skip(&format!("failed to remove directory: {e}")));
+                continue;
+            }
+            if let Err(e) = std::fs::remove_dir_all(&git_dir) {
+                report.skipped.push(skip(&format!("failed to remove admin files: {e}")));
+                continue;
+            }
+        }
+
+        report.pruned.push(PrunedWorktree {
+            name,
+            path: Some(base),
+            reason,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Record (and unless dry-run, remove) a worktree whose checkout is gone.
+fn prune_missing(
+    report: &mut WorktreePruneReport,
+    name: String,
+    path: Option<std::path::PathBuf>,
+    git_dir: &Path,
+    is_locked: bool,
+) {
+    if is_locked {
+        // Locked worktrees may live on removable media; keep their admin files
+        report.skipped.push(SkippedWorktree {
+            name,
+            path: path.unwrap_or_else(|| git_dir.to_path_buf()),
+            reason: "locked".to_string(),
+        });
+        return;
+    }
+
+    if !report.dry_run
+        && let Err(e) = std::fs::remove_dir_all(git_dir)
+    {
+        // Keep pruning the others
+        report.skipped.push(SkippedWorktree {
+            name,
+            path: path.unwrap_or_else(|| git_dir.to_path_buf()),
+            reason: format!("failed to remove admin files: {e}"),
+        });
+        return;
     }
 
-    Ok(pruned)
+    report.pruned.push(PrunedWorktree {
+        name,
+        path,
+        reason: PruneReason::Missing,
+    });
+}
+
+/// First policy (merged, then stale) that selects this worktree.
+fn policy_match(
+    repo: &gix::Repository,
+    git_dir: &Path,
+    opts: &WorktreePruneOpts,
+    merge_target: Option<ObjectId>,
+) -> Option<PruneReason> {
+    if let Some(target) = merge_target
+        && let Ok((Some(head), Some(branch), false)) = read_head_info(git_dir, repo)
+        && is_merged(repo, head, target)
+    {
+        return Some(PruneReason::Merged { branch });
+    }
+
+    if let Some(days) = opts.older_than_days {
+        let idle_days = last_activity(git_dir)
+            .and_then(|t| SystemTime::now().duration_since(t).ok())
+            .map(|d| d.as_secs() / SECONDS_PER_DAY)?;
+        if idle_days >= days {
+            return Some(PruneReason::Stale { idle_days });
+        }
+    }
+
+    None
+}
+
+/// Whether `commit` is reachable from `target` without being `target` itself.
+fn is_merged(repo: &gix::Repository, commit: ObjectId, target: ObjectId) -> bool {
+    commit != target
+        && repo
+            .merge_base(commit, target)
+            .is_ok_and(|base| base.detach() == commit)
+}
+
+/// Most recent modification of the worktree's HEAD or index.
+fn last_activity(git_dir: &Path) -> Option<SystemTime> {
+    ["HEAD", "index"]
+        .iter()
+        .filter_map(|file| std::fs::metadata(git_dir.join(file)).ok())
+        .filter_map(|meta| meta.modified().ok())
+        .max()
+        .or_else(|| {
+            std::fs::metadata(git_dir)
+                .and_then(|meta| meta.modified())
+                .ok()
+        })
 }
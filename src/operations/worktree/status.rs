@@ -0,0 +1,122 @@
+//! Worktree status aggregation.
+//!
+//! This module reports branch, dirty state, ahead/behind and in-progress
+//! operations for every worktree of a repository in one call.
+
+use crate::runtime::AsyncTask;
+use crate::{GitResult, RepoHandle};
+
+use super::helpers::{in_progress_name, main_repo_of};
+use super::types::WorktreeStatus;
+
+/// Collect status for the main worktree and all linked worktrees.
+///
+/// Worktrees whose checkout can't be opened (e.g. missing directory) are still
+/// reported, with `is_dirty` set to `None` and the reason in `error`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let statuses = worktree_statuses(repo).await??;
+/// for st in statuses {
+///     println!("{}: dirty={:?} ahead={:?}", st.path.display(), st.is_dirty, st.ahead_count);
+/// }
+/// ```
+pub fn worktree_statuses(repo: RepoHandle) -> AsyncTask<GitResult<Vec<WorktreeStatus>>> {
+    let repo = repo.clone_inner();
+    AsyncTask::spawn(move || {
+        // Always enumerate from the main repository, even for a worktree handle
+        let repo = main_repo_of(repo)?;
+        let mut statuses = Vec::new();
+
+        // Main worktree (if not bare)
+        if !repo.is_bare()
+            && let Some(main_worktree) = repo.worktree()
+        {
+            let path = main_worktree.base().to_path_buf();
+            statuses.push(status_of(&repo, path, true, false));
+        }
+
+        // Linked worktrees
+        for proxy in repo.worktrees().map_err(crate::GitError::Io)? {
+            let path = proxy.base().map_err(crate::GitError::Io)?;
+            let is_locked = proxy.is_locked();
+
+            let status = match proxy.into_repo_with_possibly_inaccessible_worktree() {
+                Ok(wt_repo) if path.exists() => status_of(&wt_repo, path, false, is_locked),
+                Ok(_) => WorktreeStatus {
+                    error: Some("Worktree directory is missing".to_string()),
+                    ..empty_status(path, is_locked)
+                },
+                Err(e) => WorktreeStatus {
+                    error: Some(format!("Failed to open worktree: {e}")),
+                    ..empty_status(path, is_locked)
+                },
+            };
+            statuses.push(status);
+        }
+
+        Ok(statuses)
+    })
+}
+
+/// Status of a single opened worktree (best effort, errors are recorded)
+fn status_of(
+    repo: &gix::Repository,
+    path: std::path::PathBuf,
+    is_main: bool,
+    is_locked: bool,
+) -> WorktreeStatus {
+    let mut status = WorktreeStatus {
+        is_main,
+        in_progress: repo.state().map(|s| in_progress_name(s).to_string()),
+        ..empty_status(path, is_locked)
+    };
+
+    match repo.head() {
+        Ok(mut head) => {
+            status.branch = head
+                .referent_name()
+                .map(|name| name.shorten().to_string());
+            status.head_commit = head.id().map(gix::Id::detach);
+
+            match crate::operations::status::get_upstream_info(
+                repo,
+                &mut head,
+                &mut status.warnings,
+            ) {
+                Ok((upstream, ahead, behind)) => {
+                    status.upstream = upstream;
+                    status.ahead_count = ahead;
+                    status.behind_count = behind;
+                }
+                Err(e) => status.error = Some(format!("Failed to compute upstream: {e}")),
+            }
+        }
+        Err(e) => status.error = Some(format!("Failed to read HEAD: {e}")),
+    }
+
+    match repo.is_dirty() {
+        Ok(dirty) => status.is_dirty = Some(dirty),
+        Err(e) => status.error = Some(format!("Failed to compute dirty state: {e}")),
+    }
+
+    status
+}
+
+fn empty_status(path: std::path::PathBuf, is_locked: bool) -> WorktreeStatus {
+    WorktreeStatus {
+        path,
+        is_main: false,
+        branch: None,
+        head_commit: None,
+        is_dirty: None,
+        upstream: None,
+        ahead_count: None,
+        behind_count: None,
+        in_progress: None,
+        is_locked,
+        error: None,
+        warnings: Vec::new(),
+    }
+}
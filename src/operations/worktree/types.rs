@@ -7,6 +7,8 @@ use std::path::PathBuf;
 
 use gix::hash::ObjectId;
 
+use crate::operations::warnings::OperationWarning;
+
 /// Comprehensive metadata about a worktree.
 ///
 /// Contains all relevant information about a git worktree including its location,
@@ -31,6 +33,40 @@ pub struct WorktreeInfo {
     pub lock_reason: Option<String>,
     /// True if HEAD is detached
     pub is_detached: bool,
+    /// Why the HEAD information is missing, if it couldn't be read
+    pub warnings: Vec<OperationWarning>,
+}
+
+/// Status snapshot of a worktree.
+///
+/// Produced by [`worktree_statuses`](super::worktree_statuses) so callers don't
+/// have to open each worktree separately.
+#[derive(Debug, Clone)]
+pub struct WorktreeStatus {
+    /// Worktree checkout path
+    pub path: PathBuf,
+    /// True if this is the main worktree
+    pub is_main: bool,
+    /// Current branch name (None if detached)
+    pub branch: Option<String>,
+    /// Current HEAD commit ID
+    pub head_commit: Option<ObjectId>,
+    /// True if there are uncommitted changes (None if the checkout is inaccessible)
+    pub is_dirty: Option<bool>,
+    /// Tracking remote branch (if any)
+    pub upstream: Option<String>,
+    /// Number of commits ahead of upstream
+    pub ahead_count: Option<usize>,
+    /// Number of commits behind upstream
+    pub behind_count: Option<usize>,
+    /// In-progress operation, e.g. "merge", "rebase", "cherry-pick"
+    pub in_progress: Option<String>,
+    /// True if the worktree is locked
+    pub is_locked: bool,
+    /// Why status could not be fully determined, if applicable
+    pub error: Option<String>,
+    /// Why the upstream counts are missing although an upstream is configured
+    pub warnings: Vec<OperationWarning>,
 }
 
 /// Options for `worktree add` operation with builder pattern.
@@ -158,3 +194,101 @@ impl WorktreeRemoveOpts {
         self
     }
 }
+
+/// Options for `worktree prune` policies with builder pattern.
+///
+/// Worktrees whose directories are missing are always pruned. Age and merged
+/// policies are opt-in and only apply to unlocked, clean linked worktrees.
+///
+/// # Examples
+///
+/// ```ignore
+/// let opts = WorktreePruneOpts::new()
+///     .older_than_days(30)
+///     .merged_into("main")
+///     .dry_run(true);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WorktreePruneOpts {
+    /// Remove worktrees with no activity for at least this many days
+    pub older_than_days: Option<u64>,
+    /// Remove worktrees whose branch is fully merged into this revision
+    pub merged_into: Option<String>,
+    /// Report what would be pruned without removing anything
+    pub dry_run: bool,
+}
+
+impl WorktreePruneOpts {
+    /// Create prune options that only remove worktrees with missing directories.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also remove unlocked worktrees idle for at least `days` days.
+    #[inline]
+    #[must_use]
+    pub fn older_than_days(mut self, days: u64) -> Self {
+        self.older_than_days = Some(days);
+        self
+    }
+
+    /// Also remove unlocked worktrees whose branch is merged into `rev`.
+    #[inline]
+    pub fn merged_into(mut self, rev: impl Into<String>) -> Self {
+        self.merged_into = Some(rev.into());
+        self
+    }
+
+    /// Enable dry-run mode (report only).
+    #[inline]
+    #[must_use]
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+}
+
+/// Why a worktree was selected for pruning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PruneReason {
+    /// Checkout directory or its `.git` file no longer exists
+    Missing,
+    /// No activity for the given number of days
+    Stale { idle_days: u64 },
+    /// Branch is fully merged into the target revision
+    Merged { branch: String },
+}
+
+/// A worktree that was (or, in dry-run mode, would be) pruned.
+#[derive(Debug, Clone)]
+pub struct PrunedWorktree {
+    /// Worktree name (directory under `.git/worktrees/`)
+    pub name: String,
+    /// Checkout path, if it could be determined
+    pub path: Option<PathBuf>,
+    /// Policy that selected this worktree
+    pub reason: PruneReason,
+}
+
+/// A worktree matched by a policy but left in place.
+#[derive(Debug, Clone)]
+pub struct SkippedWorktree {
+    /// Worktree name (directory under `.git/worktrees/`)
+    pub name: String,
+    /// Checkout path
+    pub path: PathBuf,
+    /// Why it was kept (locked, dirty, removal failed, ...)
+    pub reason: String,
+}
+
+/// Report returned by `worktree_prune_with`.
+#[derive(Debug, Clone, Default)]
+pub struct WorktreePruneReport {
+    /// Worktrees removed (or that would be removed in dry-run mode)
+    pub pruned: Vec<PrunedWorktree>,
+    /// Worktrees a policy matched but that were kept
+    pub skipped: Vec<SkippedWorktree>,
+    /// Whether this was a dry run
+    pub dry_run: bool,
+}
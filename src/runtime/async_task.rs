@@ -83,9 +83,16 @@ impl<T> Future for AsyncTask<T> {
 
 /// A handle to an asynchronous stream that produces multiple results.
 ///
-/// Uses unbounded mpsc channel for true streaming without memory accumulation.
+/// Uses an mpsc channel for true streaming without memory accumulation. With a
+/// bounded channel the producer waits for the consumer, and dropping the stream
+/// tells the producer to stop.
 pub struct AsyncStream<T> {
-    rx: mpsc::UnboundedReceiver<T>,
+    rx: StreamReceiver<T>,
+}
+
+enum StreamReceiver<T> {
+    Unbounded(mpsc::UnboundedReceiver<T>),
+    Bounded(mpsc::Receiver<T>),
 }
 
 impl<T> AsyncStream<T> {
@@ -93,7 +100,18 @@ impl<T> AsyncStream<T> {
     #[inline]
     #[must_use]
     pub fn new(rx: mpsc::UnboundedReceiver<T>) -> Self {
-        Self { rx }
+        Self {
+            rx: StreamReceiver::Unbounded(rx),
+        }
+    }
+
+    /// Create from bounded receiver, applying backpressure to the producer.
+    #[inline]
+    #[must_use]
+    pub fn bounded(rx: mpsc::Receiver<T>) -> Self {
+        Self {
+            rx: StreamReceiver::Bounded(rx),
+        }
     }
 
     /// Create from a vector (for testing/simple cases).
@@ -121,7 +139,10 @@ impl<T> Stream for AsyncStream<T> {
 
     #[inline]
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.rx.poll_recv(cx)
+        match &mut self.rx {
+            StreamReceiver::Unbounded(rx) => rx.poll_recv(cx),
+            StreamReceiver::Bounded(rx) => rx.poll_recv(cx),
+        }
     }
 }
 
@@ -0,0 +1,690 @@
+//! Synthetic repository generation for tests and benchmarks
+//!
+//! Enabled with the `testkit` feature. Repositories are generated
+//! deterministically from a seed: the same [`RepoSpec`] always produces the
+//! same commit ids, so results are comparable across runs and machines.
+//!
+//! Objects are written straight to the object database, so generating
+//! thousands of commits takes seconds. The worktree and index are checked
+//! out from HEAD at the end.
+//!
+//! [`fabricate_merge_conflict`] adds a branch whose merge into HEAD, rebase
+//! onto HEAD, or cherry-pick onto HEAD is guaranteed to conflict, and
+//! [`fabricate_stash_conflict`] adds a stash entry whose pop conflicts, for
+//! testing conflict handling and resolution.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kodegen_tools_git::testkit::{RepoSpec, generate};
+//!
+//! # async fn example() -> kodegen_tools_git::GitResult<()> {
+//! let fixture = generate(RepoSpec::new(42).files(1_000).commits(200).branches(3, 10)).await?;
+//! println!("{} commits in {}", fixture.commits.len(), fixture.path().display());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::Write;
+use std::path::Path;
+
+use gix::objs::tree::EntryKind;
+use tempfile::TempDir;
+
+use crate::{CommitId, GitError, GitResult, RepoHandle};
+
+/// File rewritten by every mainline commit, for history tests
+pub const HOT_FILE: &str = "src/hot.rs";
+
+/// Seconds between generated commit timestamps
+const COMMIT_INTERVAL_SECS: i64 = 3600;
+
+/// Timestamp of the initial commit (2023-11-14)
+const EPOCH_SECS: i64 = 1_700_000_000;
+
+/// Shape of a generated repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSpec {
+    /// Seed for all generated content
+    pub seed: u64,
+    /// Text files in the initial commit
+    pub files: usize,
+    /// Mainline commits after the initial one
+    pub commits: usize,
+    /// Text files rewritten by each mainline commit (besides [`HOT_FILE`])
+    pub files_per_commit: usize,
+    /// Number of branches forked from evenly spaced mainline commits
+    pub branches: usize,
+    /// Commits on each branch
+    pub commits_per_branch: usize,
+    /// Merge each branch back into the mainline with a merge commit
+    pub merge_branches: bool,
+    /// Binary files in the initial commit; one is rewritten per mainline commit
+    pub binary_files: usize,
+    /// Size of each binary file in bytes
+    pub binary_size: usize,
+}
+
+impl RepoSpec {
+    /// A single commit with a handful of files
+    #[inline]
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            files: 10,
+            commits: 0,
+            files_per_commit: 1,
+            branches: 0,
+            commits_per_branch: 0,
+            merge_branches: false,
+            binary_files: 0,
+            binary_size: 64 * 1024,
+        }
+    }
+
+    /// Text files in the initial commit
+    #[inline]
+    #[must_use]
+    pub fn files(mut self, count: usize) -> Self {
+        self.files = count;
+        self
+    }
+
+    /// Mainline commits after the initial one
+    #[inline]
+    #[must_use]
+    pub fn commits(mut self, count: usize) -> Self {
+        self.commits = count;
+        self
+    }
+
+    /// Text files rewritten by each mainline commit
+    #[inline]
+    #[must_use]
+    pub fn files_per_commit(mut self, count: usize) -> Self {
+        self.files_per_commit = count;
+        self
+    }
+
+    /// Fork `count` branches with `commits_each` commits each
+    #[inline]
+    #[must_use]
+    pub fn branches(mut self, count: usize, commits_each: usize) -> Self {
+        self.branches = count;
+        self.commits_per_branch = commits_each;
+        self
+    }
+
+    /// Merge branches (with at least one commit) back into the mainline
+    #[inline]
+    #[must_use]
+    pub fn merge_branches(mut self, yes: bool) -> Self {
+        self.merge_branches = yes;
+        self
+    }
+
+    /// Add `count` binary files of `size` bytes
+    #[inline]
+    #[must_use]
+    pub fn binary_files(mut self, count: usize, size: usize) -> Self {
+        self.binary_files = count;
+        self.binary_size = size;
+        self
+    }
+
+    /// Mainline position (0 = initial commit) each branch forks from
+    fn fork_point(&self, branch: usize) -> usize {
+        (branch + 1) * self.commits / (self.branches + 1)
+    }
+
+    /// Mainline position after which each branch is merged
+    fn merge_point(&self, branch: usize) -> usize {
+        let spacing = self.commits / (self.branches + 1);
+        (self.fork_point(branch) + (spacing / 2).max(1)).min(self.commits)
+    }
+}
+
+/// A generated repository, deleted on drop
+#[derive(Debug)]
+pub struct SyntheticRepo {
+    /// Temporary directory holding the repository
+    pub dir: TempDir,
+    /// Handle to the repository
+    pub repo: RepoHandle,
+    /// Mainline (first-parent) commits, oldest first, including merge commits
+    pub commits: Vec<CommitId>,
+    /// Generated branch names
+    pub branches: Vec<String>,
+}
+
+impl SyntheticRepo {
+    /// Worktree root
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+/// Small deterministic PRNG (xorshift64*)
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    /// Create from a seed (0 is mapped to 1)
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    /// Next pseudo-random value
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Pseudo-random value in `0..n` (`n` must be non-zero)
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Path of the `i`th generated text file, spread over nested directories
+fn file_path(i: usize) -> String {
+    format!("src/m{}/d{}/f{i}.rs", i % 16, (i / 16) % 16)
+}
+
+/// Path of the `i`th generated binary file
+fn binary_path(i: usize) -> String {
+    format!("assets/bin{i}.dat")
+}
+
+/// Source-like text content of roughly 40 lines
+fn file_content(rng: &mut Rng, i: usize) -> String {
+    let mut content = format!("// file {i}\n");
+    for line in 0..40 {
+        content.push_str(&format!(
+            "pub fn f{line}() -> u64 {{ {} }}\n",
+            rng.next_u64() % 1000
+        ));
+    }
+    content
+}
+
+/// Binary content of exactly `size` bytes (contains NUL bytes)
+fn binary_content(rng: &mut Rng, size: usize) -> Vec<u8> {
+    let mut content = Vec::with_capacity(size + 8);
+    while content.len() < size {
+        content.extend_from_slice(&rng.next_u64().to_le_bytes());
+        content.push(0);
+    }
+    content.truncate(size);
+    content
+}
+
+/// Write `count` text files generated from `seed` below `root`
+pub fn write_files(root: &Path, seed: u64, count: usize) -> GitResult<()> {
+    let mut rng = Rng::new(seed);
+    for i in 0..count {
+        let path = root.join(file_path(i));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, file_content(&mut rng, i))?;
+    }
+    Ok(())
+}
+
+/// Generate a repository with the given shape
+pub async fn generate(spec: RepoSpec) -> GitResult<SyntheticRepo> {
+    let dir = TempDir::new()?;
+    let created = crate::init_repo(dir.path())
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    // Operations that commit with the configured identity (merge) need one
+    let mut config = std::fs::OpenOptions::new()
+        .append(true)
+        .open(created.raw().common_dir().join("config"))?;
+    config.write_all(b"[user]\n\tname = Testkit\n\temail = testkit@example.com\n")?;
+
+    let repo = crate::open_repo(dir.path())
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    let repo_clone = repo.clone_inner();
+    let build_spec = spec.clone();
+    let (commits, branches) = tokio::task::spawn_blocking(move || build(&repo_clone, &build_spec))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    crate::reset_hard(repo.clone(), "HEAD").await?;
+
+    Ok(SyntheticRepo {
+        dir,
+        repo,
+        commits,
+        branches,
+    })
+}
+
+/// Two lines of history that conflict on a single file
+///
+/// Merging [`theirs`](Self::theirs) into HEAD, rebasing it onto HEAD, and
+/// cherry-picking [`theirs_commit`](Self::theirs_commit) onto HEAD all
+/// conflict on [`path`](Self::path).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictScenario {
+    /// File changed differently on both sides
+    pub path: String,
+    /// Commit both sides start from
+    pub base: CommitId,
+    /// HEAD after its conflicting change
+    pub ours: CommitId,
+    /// Branch holding the other conflicting change
+    pub theirs: String,
+    /// Tip of [`theirs`](Self::theirs)
+    pub theirs_commit: CommitId,
+}
+
+/// Content of a conflict file with `middle` as its third line
+pub fn conflict_content(middle: &str) -> String {
+    format!("line 1\nline 2\n{middle}\nline 4\nline 5\n")
+}
+
+/// Make merging a new branch into HEAD conflict
+///
+/// On top of HEAD, commits `conflicts/<name>.txt` (the base), creates branch
+/// `<name>-theirs` changing its third line to `theirs`, and changes the same
+/// line to `ours` on HEAD. Merging `<name>-theirs`, rebasing it onto HEAD, or
+/// cherry-picking its tip then conflicts. The worktree is reset to the new
+/// HEAD, so it should be clean beforehand.
+pub async fn fabricate_merge_conflict(
+    repo: RepoHandle,
+    name: &str,
+) -> GitResult<ConflictScenario> {
+    let repo_clone = repo.clone_inner();
+    let name = name.to_string();
+    let scenario = tokio::task::spawn_blocking(move || build_conflict(&repo_clone, &name))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    crate::reset_hard(repo, "HEAD").await?;
+    Ok(scenario)
+}
+
+fn build_conflict(repo: &gix::Repository, name: &str) -> GitResult<ConflictScenario> {
+    let head = repo.head_commit().map_err(GitError::gix)?;
+    let head_time = head.time().map_err(GitError::gix)?.seconds;
+    let head_tree = head.tree_id().map_err(GitError::gix)?;
+    let mut clock = head_time + COMMIT_INTERVAL_SECS;
+
+    let path = format!("conflicts/{name}.txt");
+    let theirs = format!("{name}-theirs");
+    let mut main = repo.edit_tree(head_tree).map_err(GitError::gix)?;
+
+    upsert(&mut main, path.clone(), write_blob(repo, conflict_content("base").as_bytes())?)?;
+    let tree = write_tree(&mut main)?;
+    let message = format!("{name}: add base");
+    let base = commit(repo, "HEAD", &message, tree, &[head.id], &mut clock)?;
+
+    let mut other = repo.edit_tree(tree).map_err(GitError::gix)?;
+    upsert(
+        &mut other,
+        path.clone(),
+        write_blob(repo, conflict_content("theirs").as_bytes())?,
+    )?;
+    let tree = write_tree(&mut other)?;
+    let reference = format!("refs/heads/{theirs}");
+    let message = format!("{name}: change on {theirs}");
+    let theirs_commit = commit(repo, &reference, &message, tree, &[base], &mut clock)?;
+
+    upsert(&mut main, path.clone(), write_blob(repo, conflict_content("ours").as_bytes())?)?;
+    let tree = write_tree(&mut main)?;
+    let message = format!("{name}: change on HEAD");
+    let ours = commit(repo, "HEAD", &message, tree, &[base], &mut clock)?;
+
+    Ok(ConflictScenario {
+        path,
+        base,
+        ours,
+        theirs,
+        theirs_commit,
+    })
+}
+
+/// A branch whose rebase onto HEAD conflicts part way through
+///
+/// The first of [`commits`](Self::commits) applies cleanly onto HEAD, the
+/// second conflicts on [`path`](Self::path) and the third would apply
+/// cleanly again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebaseConflictScenario {
+    /// File changed differently on both sides
+    pub path: String,
+    /// Commit both sides start from
+    pub base: CommitId,
+    /// HEAD after its conflicting change
+    pub ours: CommitId,
+    /// Branch to rebase onto HEAD
+    pub branch: String,
+    /// Commits of [`branch`](Self::branch), oldest first
+    pub commits: Vec<CommitId>,
+    /// The commit of [`branch`](Self::branch) that conflicts
+    pub conflicting_commit: CommitId,
+}
+
+/// Make rebasing a new branch onto HEAD conflict at its second commit
+///
+/// On top of HEAD, commits `conflicts/<name>.txt` (the base) and creates
+/// branch `<name>-topic` with three commits: one adding
+/// `conflicts/<name>-1.txt`, one changing the third line of the base file to
+/// `theirs` and one adding `conflicts/<name>-3.txt`. HEAD changes the same
+/// line to `ours`. Rebasing `<name>-topic` onto HEAD then replays the first
+/// commit and stops at the second. The worktree is reset to the new HEAD, so
+/// it should be clean beforehand.
+pub async fn fabricate_rebase_conflict(
+    repo: RepoHandle,
+    name: &str,
+) -> GitResult<RebaseConflictScenario> {
+    let repo_clone = repo.clone_inner();
+    let name = name.to_string();
+    let scenario = tokio::task::spawn_blocking(move || build_rebase_conflict(&repo_clone, &name))
+        .await
+        .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    crate::reset_hard(repo, "HEAD").await?;
+    Ok(scenario)
+}
+
+fn build_rebase_conflict(repo: &gix::Repository, name: &str) -> GitResult<RebaseConflictScenario> {
+    let head = repo.head_commit().map_err(GitError::gix)?;
+    let head_time = head.time().map_err(GitError::gix)?.seconds;
+    let head_tree = head.tree_id().map_err(GitError::gix)?;
+    let mut clock = head_time + COMMIT_INTERVAL_SECS;
+
+    let path = format!("conflicts/{name}.txt");
+    let branch = format!("{name}-topic");
+    let reference = format!("refs/heads/{branch}");
+    let mut main = repo.edit_tree(head_tree).map_err(GitError::gix)?;
+
+    upsert(&mut main, path.clone(), write_blob(repo, conflict_content("base").as_bytes())?)?;
+    let tree = write_tree(&mut main)?;
+    let message = format!("{name}: add base");
+    let base = commit(repo, "HEAD", &message, tree, &[head.id], &mut clock)?;
+
+    let mut topic = repo.edit_tree(tree).map_err(GitError::gix)?;
+    let changes = [
+        (format!("conflicts/{name}-1.txt"), conflict_content("first")),
+        (path.clone(), conflict_content("theirs")),
+        (format!("conflicts/{name}-3.txt"), conflict_content("third")),
+    ];
+    let mut commits = Vec::with_capacity(changes.len());
+    let mut tip = base;
+    for (n, (file, content)) in changes.into_iter().enumerate() {
+        upsert(&mut topic, file, write_blob(repo, content.as_bytes())?)?;
+        let tree = write_tree(&mut topic)?;
+        let message = format!("{name}: change {} on {branch}", n + 1);
+        tip = commit(repo, &reference, &message, tree, &[tip], &mut clock)?;
+        commits.push(tip);
+    }
+
+    upsert(&mut main, path.clone(), write_blob(repo, conflict_content("ours").as_bytes())?)?;
+    let tree = write_tree(&mut main)?;
+    let message = format!("{name}: change on HEAD");
+    let ours = commit(repo, "HEAD", &message, tree, &[base], &mut clock)?;
+
+    Ok(RebaseConflictScenario {
+        path,
+        base,
+        ours,
+        branch,
+        conflicting_commit: commits[1],
+        commits,
+    })
+}
+
+/// A stash entry whose pop conflicts with HEAD on a single file
+#[cfg(not(feature = "pure-gix"))]
+#[derive(Debug, Clone)]
+pub struct StashConflictScenario {
+    /// File changed by both the stash and HEAD
+    pub path: String,
+    /// Commit the stash was taken on
+    pub base: CommitId,
+    /// HEAD after its conflicting change
+    pub ours: CommitId,
+    /// The conflicting entry, on top of the stash
+    pub stash: crate::StashInfo,
+}
+
+/// Make popping a new stash entry conflict
+///
+/// On top of HEAD, commits `conflicts/<name>.txt` (the base), stashes a
+/// change of its third line to `stashed`, and changes the same line to `ours`
+/// on HEAD. Popping the stash then conflicts. The worktree is reset to each
+/// new HEAD, so it should be clean beforehand.
+///
+/// Stashing runs the git CLI, so this is unavailable with the `pure-gix`
+/// feature.
+#[cfg(not(feature = "pure-gix"))]
+pub async fn fabricate_stash_conflict(
+    repo: RepoHandle,
+    name: &str,
+) -> GitResult<StashConflictScenario> {
+    let path = format!("conflicts/{name}.txt");
+    let base = commit_on_head(repo, &path, "base", &format!("{name}: add base")).await?;
+
+    let workdir = repo
+        .raw()
+        .workdir()
+        .ok_or_else(|| GitError::InvalidInput("Repository has no working directory".to_string()))?;
+    std::fs::write(workdir.join(&path), conflict_content("stashed"))?;
+    let opts = crate::StashOpts {
+        message: Some(format!("{name}: stashed change")),
+        include_untracked: false,
+    };
+    let stash = crate::stash_save(repo.clone(), opts).await?;
+
+    let ours = commit_on_head(repo, &path, "ours", &format!("{name}: change on HEAD")).await?;
+    Ok(StashConflictScenario {
+        path,
+        base,
+        ours,
+        stash,
+    })
+}
+
+/// Commit `path` with `middle` as its third line on HEAD and check it out
+#[cfg(not(feature = "pure-gix"))]
+async fn commit_on_head(
+    repo: RepoHandle,
+    path: &str,
+    middle: &str,
+    message: &str,
+) -> GitResult<CommitId> {
+    let repo_clone = repo.clone_inner();
+    let path = path.to_string();
+    let content = conflict_content(middle);
+    let message = message.to_string();
+    let id = tokio::task::spawn_blocking(move || {
+        let head = repo_clone.head_commit().map_err(GitError::gix)?;
+        let mut clock = head.time().map_err(GitError::gix)?.seconds + COMMIT_INTERVAL_SECS;
+        let mut editor = repo_clone
+            .edit_tree(head.tree_id().map_err(GitError::gix)?)
+            .map_err(GitError::gix)?;
+        upsert(&mut editor, path, write_blob(&repo_clone, content.as_bytes())?)?;
+        let tree = write_tree(&mut editor)?;
+        commit(&repo_clone, "HEAD", &message, tree, &[head.id], &mut clock)
+    })
+    .await
+    .map_err(|e| GitError::InvalidInput(format!("Task join error: {e}")))??;
+
+    crate::reset_hard(repo, "HEAD").await?;
+    Ok(id)
+}
+
+/// Write all objects and refs, returning mainline commits and branch names
+fn build(repo: &gix::Repository, spec: &RepoSpec) -> GitResult<(Vec<CommitId>, Vec<String>)> {
+    let mut rng = Rng::new(spec.seed);
+    let mut clock = EPOCH_SECS;
+    let mut main = repo
+        .edit_tree(gix::ObjectId::empty_tree(repo.object_hash()))
+        .map_err(GitError::gix)?;
+
+    let mut hot = String::from("// hot\n");
+    for i in 0..spec.files {
+        let blob = write_blob(repo, file_content(&mut rng, i).as_bytes())?;
+        upsert(&mut main, file_path(i), blob)?;
+    }
+    for i in 0..spec.binary_files {
+        let blob = write_blob(repo, &binary_content(&mut rng, spec.binary_size))?;
+        upsert(&mut main, binary_path(i), blob)?;
+    }
+    upsert(&mut main, HOT_FILE.to_string(), write_blob(repo, hot.as_bytes())?)?;
+
+    let tree = write_tree(&mut main)?;
+    let mut tip = commit(repo, "HEAD", "initial commit", tree, &[], &mut clock)?;
+    let mut commits = vec![tip];
+
+    let branch_names: Vec<String> = (0..spec.branches).map(|k| format!("branch-{k}")).collect();
+    let mut branch_tips = vec![None; spec.branches];
+
+    for n in 0..=spec.commits {
+        if n > 0 {
+            for _ in 0..spec.files_per_commit.min(spec.files) {
+                let i = rng.below(spec.files);
+                let blob = write_blob(repo, file_content(&mut rng, i).as_bytes())?;
+                upsert(&mut main, file_path(i), blob)?;
+            }
+            if spec.binary_files > 0 {
+                let i = rng.below(spec.binary_files);
+                let blob = write_blob(repo, &binary_content(&mut rng, spec.binary_size))?;
+                upsert(&mut main, binary_path(i), blob)?;
+            }
+            hot.push_str(&format!("pub const C{n}: u64 = {};\n", rng.next_u64() % 1000));
+            upsert(&mut main, HOT_FILE.to_string(), write_blob(repo, hot.as_bytes())?)?;
+
+            let tree = write_tree(&mut main)?;
+            tip = commit(repo, "HEAD", &format!("change {n}"), tree, &[tip], &mut clock)?;
+            commits.push(tip);
+        }
+
+        for (k, name) in branch_names.iter().enumerate() {
+            if spec.fork_point(k) == n {
+                let fork_tree = write_tree(&mut main)?;
+                let reference = format!("refs/heads/{name}");
+                let mut editor = repo.edit_tree(fork_tree).map_err(GitError::gix)?;
+                let mut branch_tip = tip;
+                for j in 0..spec.commits_per_branch {
+                    // Branches only touch their own directory, so merges never conflict
+                    let path = format!("branches/b{k}/f{j}.rs");
+                    let blob = write_blob(repo, file_content(&mut rng, j).as_bytes())?;
+                    upsert(&mut editor, path, blob)?;
+                    let tree = write_tree(&mut editor)?;
+                    let message = format!("{name}: change {j}");
+                    branch_tip = commit(repo, &reference, &message, tree, &[branch_tip], &mut clock)?;
+                }
+                if spec.commits_per_branch == 0 {
+                    set_branch(repo, &reference, tip)?;
+                }
+                branch_tips[k] = Some(branch_tip);
+            }
+
+            if spec.merge_branches
+                && spec.commits_per_branch > 0
+                && spec.merge_point(k) == n
+                && let Some(branch_tip) = branch_tips[k]
+            {
+                for j in 0..spec.commits_per_branch {
+                    let path = format!("branches/b{k}/f{j}.rs");
+                    let blob = branch_blob(repo, branch_tip, &path)?;
+                    upsert(&mut main, path, blob)?;
+                }
+                let tree = write_tree(&mut main)?;
+                let message = format!("Merge branch '{name}'");
+                tip = commit(repo, "HEAD", &message, tree, &[tip, branch_tip], &mut clock)?;
+                commits.push(tip);
+            }
+        }
+    }
+
+    Ok((commits, branch_names))
+}
+
+fn write_blob(repo: &gix::Repository, data: &[u8]) -> GitResult<gix::ObjectId> {
+    repo.write_blob(data)
+        .map(gix::Id::detach)
+        .map_err(GitError::gix)
+}
+
+fn upsert(
+    editor: &mut gix::object::tree::Editor<'_>,
+    path: String,
+    blob: gix::ObjectId,
+) -> GitResult<()> {
+    editor
+        .upsert(path, EntryKind::Blob, blob)
+        .map_err(GitError::gix)?;
+    Ok(())
+}
+
+fn write_tree(editor: &mut gix::object::tree::Editor<'_>) -> GitResult<gix::ObjectId> {
+    editor.write().map(gix::Id::detach).map_err(GitError::gix)
+}
+
+/// Id of the blob at `path` in `commit`'s tree
+fn branch_blob(repo: &gix::Repository, commit: CommitId, path: &str) -> GitResult<gix::ObjectId> {
+    let tree = repo
+        .find_commit(commit)
+        .map_err(GitError::gix)?
+        .tree()
+        .map_err(GitError::gix)?;
+    let entry = tree
+        .lookup_entry_by_path(path)
+        .map_err(GitError::gix)?
+        .ok_or_else(|| GitError::InvalidInput(format!("{path} missing from branch")))?;
+    Ok(entry.object_id())
+}
+
+/// Write a commit dated `clock` (then advanced) and move `reference` to it
+fn commit(
+    repo: &gix::Repository,
+    reference: &str,
+    message: &str,
+    tree: gix::ObjectId,
+    parents: &[CommitId],
+    clock: &mut i64,
+) -> GitResult<CommitId> {
+    let signature = gix::actor::Signature {
+        name: "Testkit".into(),
+        email: "testkit@example.com".into(),
+        time: gix::date::Time::new(*clock, 0),
+    };
+    *clock += COMMIT_INTERVAL_SECS;
+
+    let mut committer_time_buf = gix::date::parse::TimeBuf::default();
+    let mut author_time_buf = gix::date::parse::TimeBuf::default();
+    repo.commit_as(
+        signature.to_ref(&mut committer_time_buf),
+        signature.to_ref(&mut author_time_buf),
+        reference,
+        message,
+        tree,
+        parents.iter().copied(),
+    )
+    .map(gix::Id::detach)
+    .map_err(GitError::gix)
+}
+
+/// Point a new branch at `target`
+fn set_branch(repo: &gix::Repository, reference: &str, target: CommitId) -> GitResult<()> {
+    repo.reference(
+        reference,
+        target,
+        gix::refs::transaction::PreviousValue::MustNotExist,
+        "branch: created by testkit",
+    )
+    .map_err(GitError::gix)?;
+    Ok(())
+}
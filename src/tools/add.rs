@@ -1,8 +1,10 @@
 //! Git add (staging) tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitAddArgs, GitAddOutput, AddPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::AddPrompts;
+use super::schema::{GitAddArgs, GitAddOutput};
+
+use super::support;
 
 /// Tool for staging files in Git
 #[derive(Clone)]
@@ -18,7 +20,10 @@ impl Tool for GitAddTool {
 
     fn description() -> &'static str {
         "Stage file changes for commit in a Git repository. \
-         Specify paths to stage specific files."
+         Specify paths or glob patterns relative to the repository root to stage specific files. \
+         all=true also stages deletions (the whole worktree without paths); \
+         update=true stages only changes and deletions of tracked files; \
+         intent_to_add=true records new files without their content."
     }
 
     fn read_only() -> bool {
@@ -34,66 +39,85 @@ impl Tool for GitAddTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
-
-        // Determine which paths to stage
-        let paths_to_stage = if args.all {
-            // Use "." to stage all files (AddOpts supports glob patterns)
-            vec![".".to_string()]
-        } else if args.paths.is_empty() {
+        let repo = support::open_repo(&args.path).await?;
+
+        if args.paths.is_empty() && !args.all && !args.update {
             return Err(McpError::InvalidArguments(
-                "No paths specified to stage. Provide paths or use all=true.".to_string(),
+                "No paths specified to stage. Provide paths or use all=true or update=true."
+                    .to_string(),
             ));
-        } else {
-            args.paths.clone()
-        };
+        }
 
         // Build add options
-        let mut opts = crate::AddOpts::new(paths_to_stage.clone());
-        opts = opts.force(args.force);
+        let opts = crate::AddOpts::new(args.paths.clone())
+            .force(args.force)
+            .all(args.all)
+            .update_only(args.update)
+            .intent_to_add(args.intent_to_add);
 
         // Execute add
-        crate::add(repo, opts)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let result = crate::add(repo, opts).await.map_err(support::tool_error)?;
 
-        let count = paths_to_stage.len();
+        let count = result.added.len() + result.deleted.len();
 
         // Build pattern string for display
-        let pattern = if args.all {
-            "all".to_string()
+        let pattern = if args.paths.is_empty() {
+            if args.all { "all" } else { "tracked" }.to_string()
         } else {
             // Show first 3 paths, then "+N more" if exceeds
-            let shown = paths_to_stage
+            let shown = args
+                .paths
                 .iter()
                 .take(3)
                 .map(|p| p.as_str())
                 .collect::<Vec<_>>()
                 .join(", ");
-            if paths_to_stage.len() > 3 {
-                format!("{} +{} more", shown, paths_to_stage.len() - 3)
+            if args.paths.len() > 3 {
+                format!("{} +{} more", shown, args.paths.len() - 3)
             } else {
                 shown
             }
         };
 
         // Terminal summary with ANSI colors and Nerd Font icons
-        let summary = format!(
+        let mut summary = format!(
             "\x1b[32m✚ Staged Changes\x1b[0m\n  📄 Files: {} · Pattern: {}",
             count, pattern
         );
-
-        Ok(ToolResponse::new(summary, GitAddOutput {
-            success: true,
-            all: args.all,
-            paths: if args.all { vec![".".to_string()] } else { paths_to_stage },
-            count: if args.all { 1 } else { count },
-        }))
+        let missing: Vec<String> = result
+            .missing
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        if !result.ignored.is_empty() {
+            summary.push_str(&format!(
+                "\n  \x1b[33m⚠ Ignored: {} (force=true stages them)\x1b[0m",
+                result.ignored.len()
+            ));
+        }
+        if !missing.is_empty() {
+            summary.push_str(&format!(
+                "\n  \x1b[33m⚠ No match: {}\x1b[0m",
+                missing.join(", ")
+            ));
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            GitAddOutput {
+                success: true,
+                all: args.all,
+                paths: args.paths,
+                count,
+                update: args.update,
+                intent_to_add: args.intent_to_add,
+                added: result.added.iter().map(ToString::to_string).collect(),
+                deleted: result.deleted.iter().map(ToString::to_string).collect(),
+                ignored: result.ignored.iter().map(ToString::to_string).collect(),
+                force_added: result.force_added.iter().map(ToString::to_string).collect(),
+                missing,
+            },
+        ))
     }
 }
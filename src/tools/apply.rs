@@ -0,0 +1,82 @@
+//! Git apply tool - apply a patch to the index only
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+
+use super::schema::{ApplyPrompts, GitApplyArgs, GitApplyOutput, GIT_APPLY};
+use super::support;
+
+/// Tool for applying patches to the index
+#[derive(Clone)]
+pub struct GitApplyTool;
+
+impl Tool for GitApplyTool {
+    type Args = GitApplyArgs;
+    type Prompts = ApplyPrompts;
+
+    fn name() -> &'static str {
+        GIT_APPLY
+    }
+
+    fn description() -> &'static str {
+        "Apply a unified diff to the index without touching the working tree \
+         (like git apply --cached). Hunks must match the staged content but may have moved. \
+         Nothing is staged unless the whole patch applies. Set 'message' to commit the \
+         result, or 'check' to only test whether the patch applies."
+    }
+
+    fn read_only() -> bool {
+        false // Writes the index, optionally commits
+    }
+
+    fn destructive() -> bool {
+        false // The working tree is never touched
+    }
+
+    fn idempotent() -> bool {
+        false // Applying twice fails or applies the hunks again
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let mut opts = crate::ApplyOpts::new(args.patch.clone()).check(args.check);
+        if let Some(message) = &args.message {
+            let mut commit = crate::CommitOpts::message(message.clone());
+            if let (Some(name), Some(email)) = (args.author_name.clone(), args.author_email.clone())
+            {
+                commit = commit.author(crate::Signature::new(name, email));
+            }
+            opts = opts.commit(commit);
+        }
+
+        let result = support::with_repo(&args.path, |repo| {
+            crate::apply_to_index(repo, opts)
+        })
+        .await?;
+
+        let mut summary = format!(
+            "\x1b[36mApply{}\x1b[0m\n {}: {} file(s)",
+            if args.check { " (check)" } else { "" },
+            if args.check { "Applies to" } else { "Staged" },
+            result.files.len()
+        );
+        for file in &result.files {
+            summary.push_str(&format!("\n  \x1b[32m+\x1b[0m {file}"));
+        }
+        if let Some(commit) = &result.commit {
+            summary.push_str(&format!(
+                "\n Commit: \x1b[33m{}\x1b[0m",
+                &commit.id.to_string()[..7]
+            ));
+        }
+
+        Ok(ToolResponse::new(summary, GitApplyOutput {
+            success: true,
+            check: args.check,
+            files: result.files,
+            commit_id: result.commit.map(|commit| commit.id.to_string()),
+        }))
+    }
+}
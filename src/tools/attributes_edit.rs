@@ -0,0 +1,133 @@
+//! Git attributes edit tool - set, remove and list .gitattributes and track files with LFS
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+
+use super::schema::{
+    AttributesEditPrompts, GitAttributeRule, GitAttributesEditArgs, GitAttributesEditOutput,
+    GIT_ATTRIBUTES_EDIT,
+};
+use super::support;
+
+/// Tool for editing .gitattributes files
+#[derive(Clone)]
+pub struct GitAttributesEditTool;
+
+impl Tool for GitAttributesEditTool {
+    type Args = GitAttributesEditArgs;
+    type Prompts = AttributesEditPrompts;
+
+    fn name() -> &'static str {
+        GIT_ATTRIBUTES_EDIT
+    }
+
+    fn description() -> &'static str {
+        "Edit .gitattributes files. Operations: 'set' merges 'attributes' (e.g. 'eol=lf', \
+         '-diff', 'diff=python', 'linguist-generated') into the line of each of 'patterns', \
+         replacing attributes of the same name; 'remove' deletes the attributes named in \
+         'attributes' from those lines; 'list' shows the pattern lines; 'lfs_track' and \
+         'lfs_untrack' add or remove the Git LFS attributes in the root .gitattributes. \
+         'directory' selects a nested .gitattributes (default: the repository root). \
+         Comments and line order are kept; repeating an edit changes nothing."
+    }
+
+    fn read_only() -> bool {
+        false // Writes .gitattributes
+    }
+
+    fn destructive() -> bool {
+        false // Only attribute lines are touched, and every edit can be set back
+    }
+
+    fn idempotent() -> bool {
+        true // Setting present or removing absent attributes is a no-op
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let operation = args.operation.clone();
+        let dir = args.directory.clone().unwrap_or_default();
+        let patterns = args.patterns.clone();
+        let attributes = args.attributes.clone();
+        let lfs = operation.starts_with("lfs_");
+        let file = attributes_file(if lfs { None } else { args.directory.as_deref() });
+
+        let changed = match operation.as_str() {
+            "set" | "remove" | "lfs_track" | "lfs_untrack" => {
+                let op = operation.clone();
+                let edit = support::with_repo(&args.path, move |repo| async move {
+                    match op.as_str() {
+                        "set" => crate::set_attributes(repo, &dir, patterns, attributes).await,
+                        "remove" => {
+                            crate::remove_attributes(repo, &dir, patterns, attributes).await
+                        }
+                        "lfs_track" => crate::lfs_track(repo, patterns).await,
+                        _ => crate::lfs_untrack(repo, patterns).await,
+                    }
+                })
+                .await?;
+                edit.changed
+            }
+            "list" => {
+                let listed = support::with_repo(&args.path, |repo| {
+                    crate::list_attributes(repo, &dir)
+                })
+                .await?;
+                let mut summary = format!("\x1b[36m{file} ({} patterns)\x1b[0m", listed.len());
+                for rule in &listed {
+                    summary.push_str(&format!(
+                        "\n {:>4}  {} {}",
+                        rule.line,
+                        rule.pattern,
+                        rule.attributes.join(" ")
+                    ));
+                }
+                let rules = listed
+                    .into_iter()
+                    .map(|rule| GitAttributeRule {
+                        line: rule.line,
+                        pattern: rule.pattern,
+                        attributes: rule.attributes,
+                    })
+                    .collect();
+                return Ok(ToolResponse::new(summary, GitAttributesEditOutput {
+                    success: true,
+                    operation,
+                    file,
+                    changed: Vec::new(),
+                    rules,
+                }));
+            }
+            other => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Invalid attributes operation: {other}. \
+                     Use 'set', 'remove', 'list', 'lfs_track' or 'lfs_untrack'"
+                )));
+            }
+        };
+
+        let summary = if changed.is_empty() {
+            format!("\x1b[36m{file} already up to date\x1b[0m")
+        } else {
+            format!("\x1b[32m ✓ Updated {file}\x1b[0m\n {}", changed.join("\n "))
+        };
+
+        Ok(ToolResponse::new(summary, GitAttributesEditOutput {
+            success: true,
+            operation,
+            file,
+            changed,
+            rules: Vec::new(),
+        }))
+    }
+}
+
+/// The edited file as shown to the caller, e.g. `assets/.gitattributes`
+fn attributes_file(directory: Option<&str>) -> String {
+    match directory.map(|dir| dir.trim_matches('/')) {
+        Some(dir) if !dir.is_empty() && dir != "." => format!("{dir}/.gitattributes"),
+        _ => ".gitattributes".to_string(),
+    }
+}
@@ -0,0 +1,93 @@
+//! Git blame tool - per-line authorship of a file
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{BlamePrompts, GitBlameArgs, GitBlameLine, GitBlameOutput, GIT_BLAME};
+
+use super::support;
+
+/// Tool for showing which commit last changed each line of a file
+#[derive(Clone)]
+pub struct GitBlameTool;
+
+impl Tool for GitBlameTool {
+    type Args = GitBlameArgs;
+    type Prompts = BlamePrompts;
+
+    fn name() -> &'static str {
+        GIT_BLAME
+    }
+
+    fn description() -> &'static str {
+        "Show for each line of a file the commit that last changed it, with its author, \
+         author time and summary. Blames at HEAD unless `rev` is given; `start_line` and \
+         `end_line` (1-based, inclusive) limit the output to a range."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let mut opts = crate::BlameOpts::new(&args.file);
+        if let Some(rev) = args.rev {
+            opts = opts.rev(rev);
+        }
+        if args.start_line.is_some() || args.end_line.is_some() {
+            opts = opts.lines(args.start_line.unwrap_or(1), args.end_line.unwrap_or(u32::MAX));
+        }
+
+        let result = support::with_repo(&args.path, |repo| crate::blame(repo, opts)).await?;
+
+        let mut summary = format!(
+            "\x1b[36mBlame: {}\x1b[0m ({} lines at {})",
+            result.path,
+            result.total_lines,
+            &result.commit.to_string()[..7]
+        );
+        for line in &result.lines {
+            summary.push_str(&format!(
+                "\n {:>5} {} {:<16} {}",
+                line.line,
+                &line.commit.to_string()[..7],
+                line.author.name.chars().take(16).collect::<String>(),
+                line.content
+            ));
+        }
+
+        let lines = result
+            .lines
+            .into_iter()
+            .map(|line| GitBlameLine {
+                line: line.line,
+                commit: line.commit.to_string(),
+                time: line.author.local_time().to_rfc3339(),
+                author: line.author.name,
+                email: line.author.email,
+                summary: line.summary,
+                original_line: line.original_line,
+                original_path: line.original_path,
+                content: line.content,
+            })
+            .collect();
+
+        Ok(ToolResponse::new(summary, GitBlameOutput {
+            success: true,
+            file: result.path,
+            commit: result.commit.to_string(),
+            total_lines: result.total_lines,
+            lines,
+        }))
+    }
+}
@@ -0,0 +1,100 @@
+//! Git branch cleanup tool
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{
+    BranchCleanupPrompts, GitBranchCleanupArgs, GitBranchCleanupOutput, GitStaleBranch,
+    GIT_BRANCH_CLEANUP,
+};
+
+use crate::CleanupPolicy;
+
+use super::support;
+
+/// Tool for removing merged and orphaned branches
+#[derive(Clone)]
+pub struct GitBranchCleanupTool;
+
+impl Tool for GitBranchCleanupTool {
+    type Args = GitBranchCleanupArgs;
+    type Prompts = BranchCleanupPrompts;
+
+    fn name() -> &'static str {
+        GIT_BRANCH_CLEANUP
+    }
+
+    fn description() -> &'static str {
+        "Find local branches older than N days that are merged into the default branch or \
+         whose upstream was deleted on the remote. Reports them as a dry run unless delete \
+         is set; delete_remote also removes merged branches from the remote. The current \
+         branch and branches checked out in worktrees are skipped."
+    }
+
+    fn read_only() -> bool {
+        false // Deletes branches when asked
+    }
+
+    fn destructive() -> bool {
+        true
+    }
+
+    fn idempotent() -> bool {
+        true // A second run finds nothing left to delete
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let mut policy = CleanupPolicy::new()
+            .older_than_days(args.older_than_days)
+            .merged(args.merged)
+            .upstream_gone(args.upstream_gone)
+            .delete(args.delete)
+            .delete_remote(args.delete_remote);
+        if let Some(default_branch) = &args.default_branch {
+            policy = policy.default_branch(default_branch);
+        }
+        let report = support::with_repo(&args.path, |repo| {
+            crate::cleanup_branches(repo, policy)
+        })
+        .await?;
+
+        let title = if report.dry_run {
+            "Stale Branches (dry run)"
+        } else {
+            "Branch Cleanup"
+        };
+        let mut summary = format!("\x1b[33m{title}\x1b[0m: {} branch(es)", report.branches.len());
+        for branch in &report.branches {
+            let status = match (&branch.error, branch.deleted) {
+                (Some(error), _) => format!(" \x1b[31m{error}\x1b[0m"),
+                (None, true) => " deleted".to_string(),
+                (None, false) => String::new(),
+            };
+            summary.push_str(&format!("\n {} ({}){status}", branch.name, branch.reason));
+        }
+
+        let branches = report
+            .branches
+            .into_iter()
+            .map(|branch| GitStaleBranch {
+                name: branch.name,
+                tip: branch.tip.to_string(),
+                last_commit: branch.last_commit.to_rfc3339(),
+                reason: branch.reason.to_string(),
+                upstream: branch.upstream,
+                deleted: branch.deleted,
+                remote_deleted: branch.remote_deleted,
+                error: branch.error,
+            })
+            .collect();
+
+        Ok(ToolResponse::new(summary, GitBranchCleanupOutput {
+            success: true,
+            dry_run: report.dry_run,
+            default_branch: report.default_branch,
+            branches,
+        }))
+    }
+}
@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitBranchCreateArgs, GitBranchCreateOutput, BranchCreatePrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for creating Git branches
 #[derive(Clone)]
@@ -34,13 +35,8 @@ impl Tool for GitBranchCreateTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build branch options
         let opts = crate::BranchOpts {
@@ -54,8 +50,8 @@ impl Tool for GitBranchCreateTool {
         // Create branch
         crate::branch(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
 
         // Terminal summary with ANSI colors and Nerd Font icons
         let summary = format!(
@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitBranchDeleteArgs, GitBranchDeleteOutput, BranchDeletePrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for deleting Git branches
 #[derive(Clone)]
@@ -34,19 +35,14 @@ impl Tool for GitBranchDeleteTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Delete branch
         crate::delete_branch(repo, args.branch.clone(), args.force)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
 
         // Terminal summary with colored output and icons
         let force_str = if args.force { "yes" } else { "no" };
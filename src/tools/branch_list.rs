@@ -1,9 +1,9 @@
 //! Git branch listing tool
 
-use gix::bstr::ByteSlice;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitBranchListArgs, GitBranchListOutput, BranchListPrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for listing Git branches
 #[derive(Clone)]
@@ -34,40 +34,16 @@ impl Tool for GitBranchListTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
-
-        // Get current branch name
-        // We clone the inner repository to avoid holding a reference across await points
-        let repo_for_current = repo.clone();
-        let current_branch_name = {
-            let inner = repo_for_current.clone_inner();
-            tokio::task::spawn_blocking(move || {
-                let head = inner.head().ok()?;
-                head.referent_name()
-                    .and_then(|name| {
-                        name.shorten()
-                            .to_str()
-                            .ok()
-                            .map(std::string::ToString::to_string)
-                    })
-            })
-            .await
-            .ok()
-            .and_then(|x| x)
-            .unwrap_or_else(|| "unknown".to_string())
-        };
-
-        // List branches
-        let branches = crate::list_branches(repo)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        // Read the current branch and list branches on one repository
+        let (current_branch_name, branches) = support::with_repo(&args.path, |repo| async move {
+            let current = support::head_branch(repo.raw());
+            let branches = crate::list_branches(repo)
+                .await
+                .map_err(|e| crate::GitError::InvalidInput(format!("Task join error: {e}")))??;
+            Ok((current, branches))
+        })
+        .await?;
+        let current_branch_name = current_branch_name.unwrap_or_else(|| "unknown".to_string());
 
         // Terminal summary with ANSI colors and Nerd Font icons
         let summary = format!(
@@ -0,0 +1,86 @@
+//! Git capabilities tool - environment health check
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+
+use super::schema::{
+    CapabilitiesPrompts, GitCapabilitiesArgs, GitCapabilitiesOutput, GIT_CAPABILITIES,
+};
+
+use super::support;
+
+/// Tool for reporting git capabilities of the environment
+#[derive(Clone)]
+pub struct GitCapabilitiesTool;
+
+impl Tool for GitCapabilitiesTool {
+    type Args = GitCapabilitiesArgs;
+    type Prompts = CapabilitiesPrompts;
+
+    fn name() -> &'static str {
+        GIT_CAPABILITIES
+    }
+
+    fn description() -> &'static str {
+        "Report what this environment supports: gix version, whether the git CLI fallback \
+         is available (needed for push and stash) and its version, supported transports, \
+         Git LFS availability, and enabled feature flags."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let caps = crate::capabilities()
+            .await
+            .map_err(support::tool_error)?;
+
+        let yes_no = |available: bool| {
+            if available { "\x1b[32myes\x1b[0m" } else { "\x1b[31mno\x1b[0m" }
+        };
+
+        let summary = format!(
+            "\x1b[36mGit Capabilities\x1b[0m\n \
+             kodegen_tools_git: {}\n \
+             gix: {}\n \
+             git CLI: {}\n \
+             Git LFS: {}\n \
+             ssh: {}\n \
+             Transports: {}\n \
+             Features: {}",
+            caps.crate_version,
+            caps.gix_version,
+            caps.git_cli_version.as_deref().unwrap_or("\x1b[31mnot found\x1b[0m"),
+            caps.git_lfs_version.as_deref().unwrap_or("\x1b[31mnot found\x1b[0m"),
+            yes_no(caps.ssh_available),
+            caps.transports.join(", "),
+            if caps.features.is_empty() { "default".to_string() } else { caps.features.join(", ") },
+        );
+
+        Ok(ToolResponse::new(summary, GitCapabilitiesOutput {
+            success: true,
+            crate_version: caps.crate_version,
+            gix_version: caps.gix_version,
+            git_cli_available: caps.git_cli_version.is_some(),
+            git_cli_version: caps.git_cli_version,
+            git_lfs_available: caps.git_lfs_version.is_some(),
+            git_lfs_version: caps.git_lfs_version,
+            ssh_available: caps.ssh_available,
+            transports: caps.transports,
+            features: caps.features,
+            gix_features: caps.gix_features,
+        }))
+    }
+}
@@ -1,8 +1,32 @@
 //! Git checkout tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitCheckoutArgs, GitCheckoutOutput, GitCheckoutPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::GitCheckoutPrompts;
+use super::schema::{GitCheckoutArgs, GitCheckoutOutput};
+
+use super::support;
+
+/// Progress callback that forwards checkout/reset progress to the client
+///
+/// Updates are sent as MCP progress notifications from a background task that
+/// ends once the callback (and every clone of it) is dropped.
+pub(crate) fn forward_progress(ctx: &ToolExecutionContext, label: &'static str) -> crate::ProgressCallback {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<crate::CheckoutProgress>();
+    let ctx = ctx.clone();
+
+    tokio::spawn(async move {
+        while let Some(p) = rx.recv().await {
+            let message = format!("{label}: {}/{} files", p.files_processed, p.files_total);
+            let _ = ctx
+                .update(p.files_processed as f64, p.files_total as f64, message)
+                .await;
+        }
+    });
+
+    crate::ProgressCallback::new(move |p| {
+        let _ = tx.send(p);
+    })
+}
 
 /// Detect reference type from target string
 ///
@@ -44,7 +68,9 @@ impl Tool for GitCheckoutTool {
 
     fn description() -> &'static str {
         "Checkout a Git reference (branch, tag, or commit) or restore specific files. \
-         Without paths: switches branches/commits. With paths: restores files from the reference."
+         Without paths: switches branches/commits ('-' switches back to the previous branch). \
+         With paths: restores files from the reference. \
+         With require_up_to_date=true, refuses to switch to a branch that is behind its upstream."
     }
 
     fn read_only() -> bool {
@@ -59,58 +85,73 @@ impl Tool for GitCheckoutTool {
         true // Checking out same ref multiple times is safe
     }
 
-    async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
-
-        // If create flag is set, create the branch first
-        if args.create {
-            let branch_opts = crate::BranchOpts {
-                name: args.target.clone(),
-                start_point: None, // Use HEAD
-                force: false,
-                checkout: false, // We'll checkout separately
-                track: false,
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let (requested, create, require_up_to_date) =
+            (args.target.clone(), args.create, args.require_up_to_date);
+        let (force, autostash, paths) = (args.force, args.autostash, args.paths.clone());
+        let progress = forward_progress(&ctx, "Checkout");
+
+        let target = support::with_repo(&args.path, move |repo| async move {
+            // "-" switches back to the previously checked out branch
+            let target = if requested == "-" && !create {
+                crate::previous_branch(repo.clone()).await?.ok_or_else(|| {
+                    crate::GitError::InvalidInput(
+                        "No previous branch to switch back to".to_string(),
+                    )
+                })?
+            } else {
+                requested
             };
 
-            crate::branch(repo.clone(), branch_opts)
-                .await
-                .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-                .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
-        }
-
-        // Build checkout options
-        let mut opts = crate::CheckoutOpts::new(&args.target);
-        opts = opts.force(args.force);
-
-        // Add file paths if specified
-        if let Some(ref file_paths) = args.paths {
-            opts = opts.paths(file_paths.iter().map(std::string::String::as_str));
-        }
-
-        // Execute checkout
-        crate::checkout(repo, opts)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            // Refuse to switch to (or branch off) a branch that is behind its upstream
+            if require_up_to_date {
+                let branch = (paths.is_none() && !create).then_some(target.as_str());
+                crate::ensure_up_to_date(repo.clone(), branch).await?;
+            }
+
+            // If create flag is set, create the branch first
+            if create {
+                let branch_opts = crate::BranchOpts {
+                    name: target.clone(),
+                    start_point: None, // Use HEAD
+                    force: false,
+                    checkout: false, // We'll checkout separately
+                    track: false,
+                };
+
+                crate::branch(repo.clone(), branch_opts)
+                    .await
+                    .map_err(|_| crate::GitError::ChannelClosed)??;
+            }
+
+            // Build checkout options
+            let mut opts = crate::CheckoutOpts::new(&target);
+            opts = opts.force(force).autostash(autostash).progress(progress);
+
+            // Add file paths if specified
+            if let Some(ref file_paths) = paths {
+                opts = opts.paths(file_paths.iter().map(std::string::String::as_str));
+            }
+
+            // Execute checkout
+            crate::checkout(repo, opts).await?;
+            Ok(target)
+        })
+        .await?;
 
         let message = if args.create {
-            format!("Created and checked out branch '{}'", args.target)
+            format!("Created and checked out branch '{}'", target)
         } else if let Some(ref paths) = args.paths {
-            format!("Restored {} file(s) from '{}'", paths.len(), args.target)
+            format!("Restored {} file(s) from '{}'", paths.len(), target)
         } else {
-            format!("Checked out '{}'", args.target)
+            format!("Checked out '{}'", target)
         };
 
         // Detect reference type
         let ref_type = if args.create {
             "branch"
         } else {
-            detect_ref_type(&args.target)
+            detect_ref_type(&target)
         };
 
         let create_str = if args.create { "yes" } else { "no" };
@@ -119,12 +160,12 @@ impl Tool for GitCheckoutTool {
         let summary = format!(
             "\x1b[34m\u{E725} Checkout: {}\x1b[0m\n\
              \u{E948} Type: {} · Create: {}",
-            args.target, ref_type, create_str
+            target, ref_type, create_str
         );
 
         Ok(ToolResponse::new(summary, GitCheckoutOutput {
             success: true,
-            target: args.target.clone(),
+            target,
             created: args.create,
             paths: args.paths.clone(),
             message,
@@ -0,0 +1,91 @@
+//! Git cherry-pick tool - apply an existing commit on top of HEAD
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+
+use super::schema::{CherryPickPrompts, GitCherryPickArgs, GitCherryPickOutput, GIT_CHERRY_PICK};
+use super::support;
+use crate::GitError;
+
+/// Tool for cherry-picking commits
+#[derive(Clone)]
+pub struct GitCherryPickTool;
+
+impl Tool for GitCherryPickTool {
+    type Args = GitCherryPickArgs;
+    type Prompts = CherryPickPrompts;
+
+    fn name() -> &'static str {
+        GIT_CHERRY_PICK
+    }
+
+    fn description() -> &'static str {
+        "Apply the change a commit made on top of HEAD and commit it with the original \
+         author and message (like git cherry-pick). Set 'no_commit' to only stage the change, \
+         'mainline' to pick a merge commit relative to one of its parents. The working tree \
+         must be clean. On conflicts nothing is modified and the conflicting paths are returned."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false // Adds a commit; nothing is discarded
+    }
+
+    fn idempotent() -> bool {
+        false // Picking again fails as the change is already in HEAD
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let mut opts = crate::CherryPickOpts::new(&args.commit)
+            .no_commit(args.no_commit)
+            .record_origin(args.record_origin);
+        if let Some(mainline) = args.mainline {
+            opts = opts.mainline(mainline);
+        }
+
+        // Conflicts are reported in the output, so keep the GitError
+        let picked = support::with_repo(&args.path, |repo| async move {
+            Ok(crate::cherry_pick(repo, opts).await)
+        })
+        .await?;
+        let result = match picked {
+            Ok(result) => result,
+            Err(GitError::MergeConflict { message, paths }) => {
+                let mut summary = format!("\x1b[31mCherry-pick conflicts\x1b[0m\n {message}");
+                for path in &paths {
+                    summary.push_str(&format!("\n  \x1b[31m!\x1b[0m {path}"));
+                }
+                return Ok(ToolResponse::new(summary, GitCherryPickOutput {
+                    success: false,
+                    source: args.commit,
+                    commit_id: None,
+                    conflicts: paths,
+                }));
+            }
+            Err(e) => return Err(support::tool_error(e)),
+        };
+
+        let source = result.source.to_string();
+        let summary = match &result.commit_id {
+            Some(id) => format!(
+                "\x1b[32mCherry-picked\x1b[0m {} as \x1b[33m{}\x1b[0m",
+                &source[..7],
+                &id.to_string()[..7]
+            ),
+            None => format!("\x1b[32mStaged\x1b[0m the change of {}", &source[..7]),
+        };
+
+        Ok(ToolResponse::new(summary, GitCherryPickOutput {
+            success: true,
+            source,
+            commit_id: result.commit_id.map(|id| id.to_string()),
+            conflicts: Vec::new(),
+        }))
+    }
+}
@@ -1,7 +1,11 @@
 //! Git repository cloning tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitCloneArgs, GitCloneOutput, ClonePrompts};
+use kodegen_mcp_schema::git::ClonePrompts;
+
+use super::schema::{GitCloneArgs, GitCloneOutput};
+
+use super::support;
 
 /// Tool for cloning remote Git repositories
 #[derive(Clone)]
@@ -18,7 +22,9 @@ impl Tool for GitCloneTool {
     fn description() -> &'static str {
         "Clone a remote Git repository to a local path. \
          Supports shallow cloning (limited history) and branch-specific cloning. \
-         The destination path must not already exist."
+         The destination path must not already exist, its parent must be writable \
+         and it must not be inside another repository unless nested is set. \
+         Set preflight_only to run these checks and the disk space check without cloning."
     }
 
     fn read_only() -> bool {
@@ -48,23 +54,61 @@ impl Tool for GitCloneTool {
             opts = opts.branch(branch);
         }
 
-        let repo = crate::clone_repo(opts)
+        opts = opts.verify(args.verify).nested(args.nested);
+
+        if args.preflight_only {
+            let report = crate::clone_preflight(opts)
+                .await
+                .map_err(support::task_error)?
+                .map_err(support::tool_error)?;
+            let preflight_issues: Vec<String> =
+                report.issues.iter().map(ToString::to_string).collect();
+            let message = if report.is_ok() {
+                format!("{} is ready for a clone of {}", args.path, args.url)
+            } else {
+                format!("Clone would fail: {report}")
+            };
+            // Green when the clone can go ahead, red otherwise
+            let color = if report.is_ok() { 32 } else { 31 };
+            let summary = format!(
+                "\x1b[{color}mClone preflight: {}\x1b[0m\n{message}",
+                args.url
+            );
+            return Ok(ToolResponse::new(summary, GitCloneOutput {
+                success: report.is_ok(),
+                url: args.url.clone(),
+                path: args.path.clone(),
+                branch: args.branch.clone().unwrap_or_default(),
+                shallow: args.depth.is_some(),
+                depth: args.depth,
+                head_commit: None,
+                default_branch: None,
+                refs_fetched: 0,
+                objects: 0,
+                has_submodules: false,
+                duration_ms: 0,
+                size_on_disk: 0,
+                objects_verified: None,
+                preflight_only: true,
+                estimated_size: report.estimated_size,
+                available_space: report.available_space,
+                preflight_issues,
+                message,
+            }));
+        }
+
+        let result = crate::clone_repo(opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
 
         // Get the actual branch name from HEAD
         let branch_name = if let Some(ref b) = args.branch {
             b.clone()
         } else {
-            // Query the repository HEAD to get the default branch
-            repo.raw()
-                .head_name()
-                .ok()
-                .flatten()
-                .map(|name| name.shorten().to_string())
-                .unwrap_or_else(|| "HEAD".to_string())
+            result.default_branch.clone().unwrap_or_else(|| "HEAD".to_string())
         };
+        let head_commit = result.head.map(|id| id.to_string());
 
         // Build optional metadata
         let mut metadata_parts = vec![
@@ -76,13 +120,31 @@ impl Tool for GitCloneTool {
             metadata_parts.push(format!("Depth: {}", depth));
         }
 
+        let mut stats_parts = vec![
+            format!("HEAD: {}", head_commit.as_deref().map_or("(empty)", |id| &id[..7])),
+            format!("{} objects", result.objects_received),
+            format!("{} refs", result.refs_fetched),
+            format!("{:.1} MiB", result.size_on_disk as f64 / (1024.0 * 1024.0)),
+            format!("{:.1}s", result.duration.as_secs_f64()),
+        ];
+        if result.has_submodules {
+            stats_parts.push("has submodules".to_string());
+        }
+        let objects_verified = result.verification.as_ref().map(|report| report.objects_checked);
+        if let Some(checked) = objects_verified {
+            stats_parts.push(format!("{checked} objects verified"));
+        }
+
         // Line 1: Green colored clone action with URL
         // Line 2: White metadata line
+        // Line 3: Clone statistics
         let summary = format!(
             "\x1b[32mClone: {}\x1b[0m\n\
+             {}\n\
              {}",
             args.url,
-            metadata_parts.join(" · ")
+            metadata_parts.join(" · "),
+            stats_parts.join(" · ")
         );
 
         Ok(ToolResponse::new(summary, GitCloneOutput {
@@ -92,6 +154,18 @@ impl Tool for GitCloneTool {
             branch: branch_name,
             shallow: args.depth.is_some(),
             depth: args.depth,
+            head_commit,
+            default_branch: result.default_branch,
+            refs_fetched: result.refs_fetched,
+            objects: result.objects_received,
+            has_submodules: result.has_submodules,
+            duration_ms: result.duration.as_millis() as u64,
+            size_on_disk: result.size_on_disk,
+            objects_verified,
+            preflight_only: false,
+            estimated_size: result.preflight.estimated_size,
+            available_space: result.preflight.available_space,
+            preflight_issues: Vec::new(),
             message: format!("Cloned {} to {}", args.url, args.path),
         }))
     }
@@ -0,0 +1,115 @@
+//! Git codeowners tool - CODEOWNERS lookup for paths
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{
+    CodeownersPrompts, GitCodeownersArgs, GitCodeownersIssue, GitCodeownersMatch,
+    GitCodeownersOutput, GIT_CODEOWNERS,
+};
+
+use super::support;
+
+/// Tool for resolving CODEOWNERS entries for paths
+#[derive(Clone)]
+pub struct GitCodeownersTool;
+
+impl Tool for GitCodeownersTool {
+    type Args = GitCodeownersArgs;
+    type Prompts = CodeownersPrompts;
+
+    fn name() -> &'static str {
+        GIT_CODEOWNERS
+    }
+
+    fn description() -> &'static str {
+        "Resolve the owners of paths from the repository's CODEOWNERS file \
+         (.github/, root or docs/, GitHub precedence). Reports the deciding rule \
+         for each path (last match wins) and any lines GitHub would skip."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let repo = support::open_repo(&args.path).await?;
+
+        let codeowners = crate::read_codeowners(repo)
+            .await
+            .map_err(support::tool_error)?;
+
+        let Some(codeowners) = codeowners else {
+            return Ok(ToolResponse::new(
+                "\x1b[33mNo CODEOWNERS file found\x1b[0m",
+                GitCodeownersOutput {
+                    success: true,
+                    source: None,
+                    paths: Vec::new(),
+                    issues: Vec::new(),
+                },
+            ));
+        };
+
+        let paths: Vec<GitCodeownersMatch> = args
+            .paths
+            .iter()
+            .map(|path| match codeowners.rule_for(path) {
+                Some(rule) => GitCodeownersMatch {
+                    path: path.clone(),
+                    owners: rule.owners.clone(),
+                    pattern: Some(rule.pattern.clone()),
+                    line: Some(rule.line),
+                },
+                None => GitCodeownersMatch {
+                    path: path.clone(),
+                    owners: Vec::new(),
+                    pattern: None,
+                    line: None,
+                },
+            })
+            .collect();
+
+        let mut summary = format!("\x1b[36mCODEOWNERS\x1b[0m ({})", codeowners.source);
+        for entry in &paths {
+            match (&entry.pattern, entry.owners.is_empty()) {
+                (Some(pattern), false) => summary.push_str(&format!(
+                    "\n {} -> {} ({pattern})",
+                    entry.path,
+                    entry.owners.join(" ")
+                )),
+                _ => summary.push_str(&format!("\n {} -> (unowned)", entry.path)),
+            }
+        }
+        for issue in &codeowners.issues {
+            summary.push_str(&format!(
+                "\n \x1b[33mline {}: {}\x1b[0m",
+                issue.line, issue.message
+            ));
+        }
+
+        Ok(ToolResponse::new(summary, GitCodeownersOutput {
+            success: true,
+            source: Some(codeowners.source),
+            paths,
+            issues: codeowners
+                .issues
+                .into_iter()
+                .map(|issue| GitCodeownersIssue {
+                    line: issue.line,
+                    message: issue.message,
+                })
+                .collect(),
+        }))
+    }
+}
@@ -1,8 +1,10 @@
 //! Git commit tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitCommitArgs, GitCommitOutput, CommitPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::CommitPrompts;
+use super::schema::{GitCommitArgs, GitCommitOutput};
+
+use super::support;
 
 /// Tool for creating Git commits
 #[derive(Clone)]
@@ -18,7 +20,10 @@ impl Tool for GitCommitTool {
 
     fn description() -> &'static str {
         "Create a new commit in a Git repository. \
-         Optionally specify author information and stage all modified files."
+         Optionally specify author information and stage all modified files. \
+         author_date/commit_date backdate the commit (RFC 2822, ISO 8601 or '<unix> <offset>'). \
+         With require_up_to_date=true, fetches the upstream first and refuses to commit \
+         if the branch is behind it."
     }
 
     fn read_only() -> bool {
@@ -34,14 +39,6 @@ impl Tool for GitCommitTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
-
         // Build commit options
         let mut opts = crate::CommitOpts::message(&args.message);
         opts = opts.all(args.all);
@@ -52,10 +49,26 @@ impl Tool for GitCommitTool {
             opts = opts.author(author);
         }
 
+        // Backdate if requested (any format git accepts, e.g. RFC 2822 or ISO 8601)
+        if let Some(date) = &args.author_date {
+            let date = crate::parse_date(date).map_err(support::tool_error)?;
+            opts = opts.author_date(date);
+        }
+        if let Some(date) = &args.commit_date {
+            let date = crate::parse_date(date).map_err(support::tool_error)?;
+            opts = opts.commit_date(date);
+        }
+
         // Create commit
-        let commit_result = crate::commit(repo, opts)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let require_up_to_date = args.require_up_to_date;
+        let commit_result = support::with_repo(&args.path, move |repo| async move {
+            // Refuse to commit on top of a branch that is behind its upstream
+            if require_up_to_date {
+                crate::ensure_up_to_date(repo.clone(), None).await?;
+            }
+            crate::commit(repo, opts).await
+        })
+        .await?;
 
         let commit_id = commit_result.id;
         let file_count = commit_result.file_count;
@@ -74,6 +87,10 @@ impl Tool for GitCommitTool {
             commit_id: commit_id.to_string(),
             message: args.message.clone(),
             file_count,
+            tree_id: commit_result.tree.to_string(),
+            parent_ids: commit_result.parents.iter().map(ToString::to_string).collect(),
+            branch: commit_result.branch,
+            amended: commit_result.amended,
         }))
     }
 }
@@ -0,0 +1,110 @@
+//! Git compare tool - branch comparison for pull request summaries
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use kodegen_mcp_schema::git::GitAuthorInfo;
+use super::schema::{ComparePrompts, GitCommitInfo, GitCompareArgs, GitCompareOutput, GIT_COMPARE};
+
+use crate::{CommitInfo, Mergeability};
+use super::support;
+
+/// Tool for comparing two branches
+#[derive(Clone)]
+pub struct GitCompareTool;
+
+impl Tool for GitCompareTool {
+    type Args = GitCompareArgs;
+    type Prompts = ComparePrompts;
+
+    fn name() -> &'static str {
+        GIT_COMPARE
+    }
+
+    fn description() -> &'static str {
+        "Compare a head branch against a base branch in one call: commits ahead/behind, \
+         files changed since the merge base with line counts, and whether merging head \
+         into base would fast-forward, merge cleanly or conflict (with conflicting paths). \
+         Nothing is written to the repository."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let repo = support::open_repo(&args.path).await?;
+
+        let comparison = crate::compare_branches(repo, &args.base, &args.head)
+            .await
+            .map_err(support::tool_error)?;
+
+        let (mergeability, conflicts) = match comparison.mergeability {
+            Mergeability::UpToDate => ("up_to_date", Vec::new()),
+            Mergeability::FastForward => ("fast_forward", Vec::new()),
+            Mergeability::Clean => ("clean", Vec::new()),
+            Mergeability::Conflicts(paths) => ("conflicts", paths),
+            Mergeability::Unrelated => ("unrelated", Vec::new()),
+        };
+
+        let stats = &comparison.diffstat;
+        let mut summary = format!(
+            "\x1b[36mCompare: {} ← {}\x1b[0m\n \
+             Ahead: {} · Behind: {} · {} files (+{}, -{}) · Merge: {}",
+            args.base,
+            args.head,
+            comparison.ahead.len(),
+            comparison.behind.len(),
+            stats.total_files_changed,
+            stats.total_additions,
+            stats.total_deletions,
+            mergeability
+        );
+        for path in &conflicts {
+            summary.push_str(&format!("\n \x1b[31mconflict\x1b[0m {path}"));
+        }
+
+        let files = stats.files.iter().map(support::diff_file).collect();
+
+        Ok(ToolResponse::new(summary, GitCompareOutput {
+            success: true,
+            base: args.base,
+            head: args.head,
+            merge_base: comparison.merge_base.map(|id| id.to_string()),
+            ahead: comparison.ahead.into_iter().map(commit_info).collect(),
+            behind: comparison.behind.into_iter().map(commit_info).collect(),
+            files_changed: stats.total_files_changed as u32,
+            insertions: stats.total_additions as u32,
+            deletions: stats.total_deletions as u32,
+            files,
+            mergeability: mergeability.to_string(),
+            conflicts,
+        }))
+    }
+}
+
+fn commit_info(commit: CommitInfo) -> GitCommitInfo {
+    let author_time = commit.author.local_time().to_rfc3339();
+    GitCommitInfo {
+        id: commit.id.to_string(),
+        author: GitAuthorInfo {
+            name: commit.author.name,
+            email: commit.author.email,
+            time: author_time,
+        },
+        summary: commit.summary,
+        time: commit.time.to_rfc3339(),
+        refs: commit.decorations.iter().map(ToString::to_string).collect(),
+        warnings: commit.warnings.iter().map(ToString::to_string).collect(),
+    }
+}
@@ -0,0 +1,74 @@
+//! Git contains tool - which branches and tags include a commit
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{ContainsPrompts, GitContainsArgs, GitContainsOutput, GIT_CONTAINS};
+
+use super::support;
+
+/// Tool for finding the branches and tags that contain a commit
+#[derive(Clone)]
+pub struct GitContainsTool;
+
+impl Tool for GitContainsTool {
+    type Args = GitContainsArgs;
+    type Prompts = ContainsPrompts;
+
+    fn name() -> &'static str {
+        GIT_CONTAINS
+    }
+
+    fn description() -> &'static str {
+        "List the branches (local and remote-tracking) and tags whose history contains a \
+         commit, like git branch -a --contains and git tag --contains. Answers whether a \
+         fix has reached a release branch or shipped in a tagged release."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let commit = args.commit.clone();
+        let (branches, tags) = support::with_repo(&args.path, |repo| async move {
+            let branches = crate::branches_containing(repo.clone(), &commit).await?;
+            let tags = crate::tags_containing(repo, &commit).await?;
+            Ok((branches, tags))
+        })
+        .await?;
+
+        let list = |names: &[String]| {
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        };
+        let summary = format!(
+            "\x1b[36mContaining {}\x1b[0m\n Branches ({}): {}\n Tags ({}): {}",
+            args.commit,
+            branches.len(),
+            list(&branches),
+            tags.len(),
+            list(&tags)
+        );
+
+        Ok(ToolResponse::new(summary, GitContainsOutput {
+            success: true,
+            commit: args.commit,
+            branches,
+            tags,
+        }))
+    }
+}
@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitDiffArgs, GitDiffOutput, GitDiffFile, DiffPrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for displaying Git diffs
 #[derive(Clone)]
@@ -35,13 +36,8 @@ impl Tool for GitDiffTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build diff options
         let mut opts = crate::DiffOpts::new(&args.from);
@@ -52,18 +48,22 @@ impl Tool for GitDiffTool {
         // Execute diff
         let stats = crate::diff(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::tool_error)?;
 
         // Terminal summary
         let summary = format_diff_output(&stats, &args.from, &args.to);
 
         // Build output files
-        let files: Vec<GitDiffFile> = stats.files.iter().map(|f| GitDiffFile {
-            path: f.path.clone(),
-            change_type: format!("{:?}", f.change_type),
-            additions: f.additions as u32,
-            deletions: f.deletions as u32,
-        }).collect();
+        let files: Vec<GitDiffFile> = stats
+            .files
+            .iter()
+            .map(|f| GitDiffFile {
+                path: f.path.to_string(),
+                change_type: format!("{:?}", f.change_type),
+                additions: f.additions as u32,
+                deletions: f.deletions as u32,
+            })
+            .collect();
 
         Ok(ToolResponse::new(summary, GitDiffOutput {
             success: true,
@@ -105,9 +105,15 @@ fn format_diff_output(stats: &crate::DiffStats, from: &str, to: &Option<String>)
             crate::ChangeType::Renamed => "renamed",
         };
 
+        let kind = if file.is_symlink { "symlink " } else { "" };
+        let mode = file
+            .mode_change
+            .map(|change| format!(", mode {change}"))
+            .unwrap_or_default();
+
         output.push_str(&format!(
-            "  {} {} \x1b[90m({}: +{}, -{}\x1b[0m\n",
-            change_icon, file.path, change_label, file.additions, file.deletions
+            "  {} {} \x1b[90m({}{}: +{}, -{}{}\x1b[0m\n",
+            change_icon, file.path, kind, change_label, file.additions, file.deletions, mode
         ));
     }
 
@@ -1,8 +1,10 @@
 //! Git repository discovery tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitDiscoverArgs, GitDiscoverOutput, DiscoverPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::DiscoverPrompts;
+
+use super::schema::{GitDiscoverArgs, GitDiscoverOutput, GitNestedRepo};
+use super::support;
 
 /// Tool for discovering Git repositories by searching upward
 #[derive(Clone)]
@@ -19,7 +21,12 @@ impl Tool for GitDiscoverTool {
     fn description() -> &'static str {
         "Discover a Git repository by searching upward from the given path. \
          This will traverse parent directories until it finds a .git directory \
-         or reaches the filesystem root."
+         or reaches the filesystem root. Bound the search with ceiling_dirs or \
+         max_depth so an unrelated repository above the path (e.g. a home \
+         directory kept in git) isn't picked up; levels_up says how far it went. \
+         Set scan_nested to also list the repositories below the path, each classified \
+         as submodule, embedded_gitlink, worktree, independent, bare, committed_git_dir \
+         or broken."
     }
 
     fn read_only() -> bool {
@@ -35,32 +42,100 @@ impl Tool for GitDiscoverTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
+        // A registered repository name starts the search at its path
+        let path = crate::resolve_repo_path(&args.path)
+            .await
+            .map_err(support::tool_error)?;
 
-        let repo = crate::discover_repo(path)
+        let mut opts = crate::OpenOpts::new();
+        for dir in &args.ceiling_dirs {
+            opts = opts.ceiling_dir(dir);
+        }
+        if let Some(levels) = args.max_depth {
+            opts = opts.max_depth(levels);
+        }
+        // With a downward scan, a path in no repository is still worth scanning
+        let found = match crate::discover_repo_report(&path, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+        {
+            Ok(found) => Some(found),
+            Err(_) if args.scan_nested => None,
+            Err(e) => return Err(support::tool_error(e)),
+        };
+        let nested = if args.scan_nested {
+            let mut scan = crate::NestedScanOpts::new();
+            if let Some(levels) = args.nested_max_depth {
+                scan = scan.max_depth(levels);
+            }
+            crate::find_nested_repos(&path, scan)
+                .await
+                .map_err(support::tool_error)?
+        } else {
+            Vec::new()
+        };
 
         // Extract the working directory path from the discovered repository
-        let repo_root = repo.raw()
-            .workdir()
-            .ok_or_else(|| McpError::Other(anyhow::anyhow!("Repository has no working directory")))?
-            .display()
-            .to_string();
+        let repo_root = match &found {
+            Some(found) => found.repo.raw()
+                .workdir()
+                .ok_or_else(|| McpError::Other(anyhow::anyhow!("Repository has no working directory")))?
+                .display()
+                .to_string(),
+            None => String::new(),
+        };
+        let levels_up = found.as_ref().map_or(0, |found| found.levels_up);
+        let boundary = found
+            .as_ref()
+            .and_then(|found| found.boundary.as_ref())
+            .map(ToString::to_string);
 
         // Terminal summary with ANSI colors and Nerd Font icons
-        let summary = format!(
-            "\x1b[36m Discover Repository: {}\x1b[0m\n\
-              Started from: {} · Found: {}",
-            repo_root, args.path, repo_root
-        );
+        let mut summary = if found.is_some() {
+            format!(
+                "\x1b[36m Discover Repository: {}\x1b[0m\n\
+                  Started from: {} · Found: {} ({} levels up)",
+                repo_root, args.path, repo_root, levels_up
+            )
+        } else {
+            format!(
+                "\x1b[36m Discover Repository: none above {}\x1b[0m",
+                args.path
+            )
+        };
+        if args.scan_nested {
+            summary.push_str(&format!("\n Nested repositories: {}", nested.len()));
+            for repo in &nested {
+                summary.push_str(&format!(
+                    "\n  {} \x1b[33m{}\x1b[0m",
+                    repo.relative_path.display(),
+                    repo.kind.as_str()
+                ));
+            }
+        }
 
+        let message = if found.is_some() {
+            format!("Discovered Git repository from path {}", args.path)
+        } else {
+            format!("No Git repository contains {}", args.path)
+        };
         Ok(ToolResponse::new(summary, GitDiscoverOutput {
             success: true,
             searched_from: args.path.clone(),
             repo_root,
-            message: format!("Discovered Git repository from path {}", args.path),
+            message,
+            levels_up,
+            boundary,
+            nested: nested
+                .into_iter()
+                .map(|repo| GitNestedRepo {
+                    path: repo.path.display().to_string(),
+                    relative_path: repo.relative_path.display().to_string(),
+                    kind: repo.kind.as_str().to_string(),
+                    git_dir: repo.git_dir.map(|dir| dir.display().to_string()),
+                    container: repo.container.map(|dir| dir.display().to_string()),
+                })
+                .collect(),
         }))
     }
 }
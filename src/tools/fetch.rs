@@ -1,8 +1,10 @@
 //! Git fetch tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitFetchArgs, GitFetchOutput, FetchPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::FetchPrompts;
+use super::schema::{GitFetchArgs, GitFetchOutput, GitFetchRefUpdate};
+
+use super::support;
 
 /// Tool for fetching from remote repositories
 #[derive(Clone)]
@@ -18,7 +20,8 @@ impl Tool for GitFetchTool {
 
     fn description() -> &'static str {
         "Fetch updates from a remote repository. \
-         Downloads objects and refs from another repository."
+         Downloads objects and refs from another repository and reports which refs changed \
+         (with old and new commits), new tags, pruned refs and the size of the received pack."
     }
 
     fn read_only() -> bool {
@@ -34,38 +37,88 @@ impl Tool for GitFetchTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build fetch options
         let mut opts = crate::FetchOpts::from_remote(&args.remote);
         for refspec in &args.refspecs {
             opts = opts.add_refspec(refspec);
         }
-        opts = opts.prune(args.prune);
+        opts = opts.prune(args.prune).verify(args.verify);
 
         // Execute fetch
-        crate::fetch(repo, opts)
+        let result = crate::fetch(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::tool_error)?;
 
-        // Terminal summary (2 lines with ANSI formatting)
-        let prune_status = if args.prune { "yes" } else { "no" };
-
-        let summary = format!(
-            "\x1b[36mFetch: {}\x1b[0m\n Refs updated: synced · Prune: {}",
-            args.remote, prune_status
+        // Terminal summary: header, counts, then one line per changed ref
+        let short = |id: Option<crate::CommitId>| {
+            id.map_or_else(|| "(none)".to_string(), |id| id.to_hex_with_len(7).to_string())
+        };
+        let mut summary = format!(
+            "\x1b[36mFetch: {}\x1b[0m\n Refs updated: {} · New tags: {} · Pruned: {} · \
+             Received: {} objects ({} bytes)",
+            args.remote,
+            result.updated.len(),
+            result.new_tags.len(),
+            result.pruned.len(),
+            result.objects_received,
+            result.bytes_received
         );
+        let objects_verified = result.verification.as_ref().map(|report| report.objects_checked);
+        if let Some(checked) = objects_verified {
+            summary.push_str(&format!(" · Verified: {checked} objects"));
+        }
+        let up_to_date = result.is_up_to_date();
+        if up_to_date {
+            summary.push_str("\n Already up to date");
+        }
+        let mut updated_refs = Vec::with_capacity(result.updated.len());
+        for update in result.updated {
+            let (status, rejected) = match update.kind {
+                crate::RefUpdateKind::New => ("new".to_string(), false),
+                crate::RefUpdateKind::FastForward => ("fast-forward".to_string(), false),
+                crate::RefUpdateKind::Forced => ("forced".to_string(), false),
+                crate::RefUpdateKind::Rejected(reason) => (reason, true),
+            };
+            let color = if rejected { "\x1b[31m" } else { "" };
+            summary.push_str(&format!(
+                "\n {color}{} {}..{} ({status})\x1b[0m",
+                update.name,
+                short(update.old),
+                short(update.new)
+            ));
+            updated_refs.push(GitFetchRefUpdate {
+                name: update.name,
+                old: update.old.map(|id| id.to_string()),
+                new: update.new.map(|id| id.to_string()),
+                status,
+                rejected,
+            });
+        }
+        for tag in &result.new_tags {
+            summary.push_str(&format!("\n {tag} (new tag)"));
+        }
+        for pruned in &result.pruned {
+            summary.push_str(&format!("\n {pruned} (pruned)"));
+        }
+        for warning in &result.warnings {
+            summary.push_str(&format!("\n \x1b[33m⚠ {warning}\x1b[0m"));
+        }
 
         Ok(ToolResponse::new(summary, GitFetchOutput {
             success: true,
             remote: args.remote.clone(),
             pruned: args.prune,
+            up_to_date,
+            updated_refs,
+            new_tags: result.new_tags,
+            pruned_refs: result.pruned,
+            objects_received: result.objects_received,
+            bytes_received: result.bytes_received,
+            objects_verified,
+            warnings: result.warnings.iter().map(ToString::to_string).collect(),
         }))
     }
 }
@@ -0,0 +1,79 @@
+//! Git file info tool - yes/no questions about a path at a revision
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{FileInfoPrompts, GitFileInfoArgs, GitFileInfoOutput, GIT_FILE_INFO};
+
+use super::support;
+
+/// Tool for checking file existence at a revision and changes between revisions
+#[derive(Clone)]
+pub struct GitFileInfoTool;
+
+impl Tool for GitFileInfoTool {
+    type Args = GitFileInfoArgs;
+    type Prompts = FileInfoPrompts;
+
+    fn name() -> &'static str {
+        GIT_FILE_INFO
+    }
+
+    fn description() -> &'static str {
+        "Check whether a file exists at a revision, and optionally whether it changed \
+         between that revision and another one, without computing a diff."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let rev = args.rev.unwrap_or_else(|| "HEAD".to_string());
+
+        let (file, compare_rev, at) = (args.file.clone(), args.compare_rev.clone(), rev.clone());
+        let (exists, changed) = support::with_repo(&args.path, |repo| async move {
+            let exists = crate::file_exists_at(repo.clone(), &at, &file).await?;
+            let changed = match compare_rev {
+                Some(other) => Some(crate::file_changed_between(repo, &at, &other, &file).await?),
+                None => None,
+            };
+            Ok((exists, changed))
+        })
+        .await?;
+
+        let mut summary = format!(
+            "\x1b[36mFile Info: {}\x1b[0m\n Exists at {}: {}",
+            args.file,
+            rev,
+            if exists { "\x1b[32myes\x1b[0m" } else { "\x1b[31mno\x1b[0m" }
+        );
+        if let (Some(other), Some(changed)) = (args.compare_rev.as_ref(), changed) {
+            summary.push_str(&format!(
+                "\n Changed {} → {}: {}",
+                rev,
+                other,
+                if changed { "\x1b[33myes\x1b[0m" } else { "no" }
+            ));
+        }
+
+        Ok(ToolResponse::new(summary, GitFileInfoOutput {
+            success: true,
+            file: args.file,
+            rev,
+            exists,
+            compare_rev: args.compare_rev,
+            changed,
+        }))
+    }
+}
@@ -1,10 +1,10 @@
 //! Git history tool - investigate how code evolved
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{
-    GitHistoryArgs, GitHistoryCommit, GitHistoryOutput, GIT_HISTORY, HistoryPrompts,
-};
-use std::path::Path;
+use kodegen_mcp_schema::git::{GitHistoryCommit, GIT_HISTORY, HistoryPrompts};
+use super::schema::{GitHistoryArgs, GitHistoryOutput};
+
+use super::support;
 
 /// Tool for investigating file history with actual diffs
 #[derive(Clone)]
@@ -41,10 +41,7 @@ impl Tool for GitHistoryTool {
         args: Self::Args,
         _ctx: ToolExecutionContext,
     ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let repo = crate::open_repo(Path::new(&args.path))
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build options
         let mut opts = crate::HistoryOpts::new(&args.file).limit(args.limit);
@@ -52,7 +49,7 @@ impl Tool for GitHistoryTool {
         if let Some(ref search) = args.search {
             opts = opts
                 .search(search)
-                .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+                .map_err(support::tool_error)?;
         }
         if let Some(since) = args.since {
             opts = opts.since(since);
@@ -63,7 +60,7 @@ impl Tool for GitHistoryTool {
 
         let result = crate::history(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::tool_error)?;
 
         // Format output based on mode
         match result {
@@ -71,6 +68,7 @@ impl Tool for GitHistoryTool {
                 file,
                 total_examined,
                 commits,
+                reached_shallow_boundary,
             } => {
                 let search_note = if args.search.is_some() {
                     format!(" matching \"{}\"", args.search.as_ref().map_or("", |s| s.as_str()))
@@ -82,6 +80,20 @@ impl Tool for GitHistoryTool {
                     "\x1b[36mFile History: {}\x1b[0m\n Found: {} commits{}\n Examined: {} total commits\n\n",
                     file, commits.len(), search_note, total_examined
                 );
+                if reached_shallow_boundary {
+                    summary.push_str(
+                        " \x1b[33m⚠ Shallow clone: older history is not available locally\x1b[0m\n\n",
+                    );
+                }
+
+                let warnings: Vec<String> = commits
+                    .iter()
+                    .flat_map(|c| &c.warnings)
+                    .map(ToString::to_string)
+                    .collect();
+                for warning in &warnings {
+                    summary.push_str(&format!(" \x1b[33m⚠ {warning}\x1b[0m\n\n"));
+                }
 
                 for c in &commits {
                     summary.push_str(&format!(
@@ -100,9 +112,11 @@ impl Tool for GitHistoryTool {
 
                 let output = GitHistoryOutput {
                     success: true,
-                    file,
+                    file_is_utf8: file.is_utf8(),
+                    file: file.to_string(),
                     mode: "commits".to_string(),
                     total_examined: Some(total_examined),
+                    reached_shallow_boundary,
                     commits: Some(
                         commits
                             .into_iter()
@@ -116,6 +130,7 @@ impl Tool for GitHistoryTool {
                             })
                             .collect(),
                     ),
+                    warnings,
                     since: None,
                     until: None,
                     additions: None,
@@ -143,10 +158,13 @@ impl Tool for GitHistoryTool {
 
                 let output = GitHistoryOutput {
                     success: true,
-                    file,
+                    file_is_utf8: file.is_utf8(),
+                    file: file.to_string(),
                     mode: "range".to_string(),
                     total_examined: None,
+                    reached_shallow_boundary: false,
                     commits: None,
+                    warnings: Vec::new(),
                     since: Some(since),
                     until: Some(until),
                     additions: Some(additions),
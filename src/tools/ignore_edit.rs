@@ -0,0 +1,124 @@
+//! Git ignore edit tool - add, remove and list .gitignore patterns
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+
+use super::schema::{
+    GitIgnoreEditArgs, GitIgnoreEditOutput, GitIgnorePattern, IgnoreEditPrompts,
+    GIT_IGNORE_EDIT,
+};
+use super::support;
+
+/// Tool for editing .gitignore files
+#[derive(Clone)]
+pub struct GitIgnoreEditTool;
+
+impl Tool for GitIgnoreEditTool {
+    type Args = GitIgnoreEditArgs;
+    type Prompts = IgnoreEditPrompts;
+
+    fn name() -> &'static str {
+        GIT_IGNORE_EDIT
+    }
+
+    fn description() -> &'static str {
+        "Edit .gitignore files. Operations: 'add' appends 'patterns' not already present \
+         (optionally under a '# comment' line), 'remove' deletes exact pattern lines, \
+         'list' shows the patterns with their line numbers. 'directory' selects a nested \
+         .gitignore (default: the repository root). Comments, blank lines and pattern order \
+         are kept; repeating an edit changes nothing."
+    }
+
+    fn read_only() -> bool {
+        false // Writes .gitignore
+    }
+
+    fn destructive() -> bool {
+        false // Only pattern lines are touched, and removal is undone by adding them back
+    }
+
+    fn idempotent() -> bool {
+        true // Adding present or removing absent patterns is a no-op
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let operation = args.operation.clone();
+        let dir = args.directory.clone().unwrap_or_default();
+        let patterns = args.patterns.clone();
+        let comment = args.comment.clone();
+        let file = ignore_file(args.directory.as_deref());
+
+        let (summary, changed) = match operation.as_str() {
+            "add" | "remove" => {
+                let adding = operation == "add";
+                let edit = support::with_repo(&args.path, move |repo| async move {
+                    if adding {
+                        crate::add_patterns(repo, &dir, patterns, comment.as_deref()).await
+                    } else {
+                        crate::remove_patterns(repo, &dir, patterns).await
+                    }
+                })
+                .await?;
+                let verb = if adding { "Added to" } else { "Removed from" };
+                let summary = if edit.changed.is_empty() {
+                    format!("\x1b[36m{file} already up to date\x1b[0m")
+                } else {
+                    format!(
+                        "\x1b[32m ✓ {verb} {file}\x1b[0m\n {}",
+                        edit.changed.join("\n ")
+                    )
+                };
+                (summary, edit.changed)
+            }
+            "list" => {
+                let listed = support::with_repo(&args.path, |repo| {
+                    crate::list_patterns(repo, &dir)
+                })
+                .await?;
+                let mut summary = format!("\x1b[36m{file} ({} patterns)\x1b[0m", listed.len());
+                for pattern in &listed {
+                    summary.push_str(&format!("\n {:>4}  {}", pattern.line, pattern.pattern));
+                }
+                let patterns = listed
+                    .into_iter()
+                    .map(|pattern| GitIgnorePattern {
+                        line: pattern.line,
+                        pattern: pattern.pattern,
+                        negated: pattern.negated,
+                    })
+                    .collect();
+                return Ok(ToolResponse::new(summary, GitIgnoreEditOutput {
+                    success: true,
+                    operation,
+                    file,
+                    changed: Vec::new(),
+                    patterns,
+                }));
+            }
+            other => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Invalid ignore operation: {other}. Use 'add', 'remove' or 'list'"
+                )));
+            }
+        };
+
+        Ok(ToolResponse::new(summary, GitIgnoreEditOutput {
+            success: true,
+            operation,
+            file,
+            changed,
+            patterns: Vec::new(),
+        }))
+    }
+}
+
+/// The edited file as shown to the caller, e.g. `build/.gitignore`
+fn ignore_file(directory: Option<&str>) -> String {
+    match directory.map(|dir| dir.trim_matches('/')) {
+        Some(dir) if !dir.is_empty() && dir != "." => format!("{dir}/.gitignore"),
+        _ => ".gitignore".to_string(),
+    }
+}
@@ -0,0 +1,94 @@
+//! Git impact tool - map the paths a range changed to build/test targets
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{
+    GitImpactArgs, GitImpactOutput, GitImpactTarget, ImpactPrompts, GIT_IMPACT,
+};
+
+use super::support;
+
+/// Tool for selecting the targets affected by a range of commits
+#[derive(Clone)]
+pub struct GitImpactTool;
+
+impl Tool for GitImpactTool {
+    type Args = GitImpactArgs;
+    type Prompts = ImpactPrompts;
+
+    fn name() -> &'static str {
+        GIT_IMPACT
+    }
+
+    fn description() -> &'static str {
+        "Map the paths changed in a commit range (base..tip, or a single commit) to build \
+         or test targets through glob -> target rules, e.g. src/operations/** -> \
+         crate:kodegen-tools-git. Returns the affected targets with the paths that hit \
+         them and the paths no rule matched, to select CI jobs after a change."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let rules = args
+            .rules
+            .iter()
+            .map(|rule| crate::ImpactRule::new(rule.pattern.as_str(), rule.target.as_str()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(support::tool_error)?;
+
+        let range = args.range.clone();
+        let report = support::with_repo(&args.path, |repo| {
+            crate::impacted_paths(repo, &range, &rules)
+        })
+        .await?;
+
+        let mut summary = format!(
+            "\x1b[36mImpact of {}\x1b[0m: {} paths changed, {} targets",
+            args.range,
+            report.changed_paths.len(),
+            report.targets.len()
+        );
+        for target in &report.targets {
+            summary.push_str(&format!(
+                "\n {} ({} paths)",
+                target.target,
+                target.paths.len()
+            ));
+        }
+        if !report.unmatched_paths.is_empty() {
+            summary.push_str(&format!(
+                "\n \x1b[33mUnmatched: {}\x1b[0m",
+                report.unmatched_paths.join(", ")
+            ));
+        }
+
+        Ok(ToolResponse::new(summary, GitImpactOutput {
+            success: true,
+            range: args.range,
+            changed_paths: report.changed_paths,
+            targets: report
+                .targets
+                .into_iter()
+                .map(|target| GitImpactTarget {
+                    target: target.target,
+                    paths: target.paths,
+                })
+                .collect(),
+            unmatched_paths: report.unmatched_paths,
+        }))
+    }
+}
@@ -1,9 +1,12 @@
 //! Git repository initialization tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitInitArgs, GitInitOutput, InitPrompts};
+use kodegen_mcp_schema::git::InitPrompts;
 use std::path::Path;
 
+use super::schema::{GitInitArgs, GitInitOutput};
+use super::support;
+
 /// Tool for initializing Git repositories
 #[derive(Clone)]
 pub struct GitInitTool;
@@ -19,7 +22,9 @@ impl Tool for GitInitTool {
     fn description() -> &'static str {
         "Initialize a new Git repository at the specified path. \
          Supports both normal repositories (with working directory) and \
-         bare repositories (without working directory, typically for servers)."
+         bare repositories (without working directory, typically for servers). \
+         With separate_git_dir, the repository's files live in that directory and \
+         the working directory gets a .git file pointing at it."
     }
 
     fn read_only() -> bool {
@@ -37,18 +42,17 @@ impl Tool for GitInitTool {
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
         let path = Path::new(&args.path);
 
-        // Call appropriate function based on bare flag
-        let task = if args.bare {
-            crate::init_bare_repo(path)
-        } else {
-            crate::init_repo(path)
-        };
+        let mut opts = crate::InitOpts::new().bare(args.bare);
+        if let Some(git_dir) = &args.separate_git_dir {
+            opts = opts.separate_git_dir(git_dir);
+        }
 
         // Await AsyncTask, handle both layers of Result
-        let _repo = task
+        let repo = crate::init_repo_with(path, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
+        let git_dir = repo.raw().git_dir().display().to_string();
 
         // Terminal summary
         let repo_type = if args.bare { "bare" } else { "normal" };
@@ -67,6 +71,7 @@ impl Tool for GitInitTool {
             success: true,
             path: args.path.clone(),
             bare: args.bare,
+            git_dir,
             message: format!("Initialized {} Git repository at {}", repo_type, args.path),
         }))
     }
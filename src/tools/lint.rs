@@ -0,0 +1,124 @@
+//! Git lint tool - repository hygiene checks
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+
+use crate::operations::lint::{LintCheck, LintRules, LintSeverity};
+use super::schema::{GitLintArgs, GitLintFinding, GitLintOutput, LintPrompts, GIT_LINT};
+use super::support;
+
+/// Tool for running repository hygiene checks
+#[derive(Clone)]
+pub struct GitLintTool;
+
+impl Tool for GitLintTool {
+    type Args = GitLintArgs;
+    type Prompts = LintPrompts;
+
+    fn name() -> &'static str {
+        GIT_LINT
+    }
+
+    fn description() -> &'static str {
+        "Run repository hygiene checks and report findings with a severity. Checks: \
+         missing-license, missing-readme, large-file (above 'max_file_size' bytes), \
+         missing-signoff (among the last 'signoff_commits' commits), branch-behind (more \
+         than 'max_behind' commits behind the default branch), stale-branch (no commit for \
+         'stale_days'), broken-symlink, case-conflict (paths differing only by case or \
+         Unicode normalization) and non-portable-path (names Windows rejects). \
+         'checks' selects which to run (default: all). Nothing is modified."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let mut rules = LintRules::new();
+        if !args.checks.is_empty() {
+            let checks = args
+                .checks
+                .iter()
+                .map(|name| {
+                    LintCheck::from_name(name).ok_or_else(|| {
+                        McpError::Other(anyhow::anyhow!("Unknown lint check: {name}"))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rules = rules.only(checks);
+        }
+        if let Some(bytes) = args.max_file_size {
+            rules = rules.max_file_size(bytes);
+        }
+        if let Some(count) = args.signoff_commits {
+            rules = rules.signoff_commits(count);
+        }
+        if let Some(count) = args.max_behind {
+            rules = rules.max_behind(count);
+        }
+        if let Some(days) = args.stale_days {
+            rules = rules.stale_days(days);
+        }
+        if let Some(branch) = args.default_branch {
+            rules = rules.default_branch(branch);
+        }
+
+        let report = support::with_repo(&args.path, |repo| {
+            crate::operations::lint::check(repo, rules)
+        })
+        .await?;
+
+        let mut summary = if report.is_clean() {
+            format!("\x1b[32m ✓ {} checks passed\x1b[0m", report.checks.len())
+        } else {
+            format!(
+                "\x1b[33m{} findings from {} checks\x1b[0m",
+                report.findings.len(),
+                report.checks.len()
+            )
+        };
+        for finding in &report.findings {
+            let color = match finding.severity {
+                LintSeverity::Error => "31",
+                LintSeverity::Warning => "33",
+                LintSeverity::Info => "36",
+            };
+            let subject = if finding.subject.is_empty() {
+                String::new()
+            } else {
+                format!("{}: ", finding.subject)
+            };
+            summary.push_str(&format!(
+                "\n \x1b[{color}m{:<7}\x1b[0m [{}] {subject}{}",
+                finding.severity, finding.check, finding.message
+            ));
+        }
+
+        Ok(ToolResponse::new(summary, GitLintOutput {
+            success: true,
+            clean: report.is_clean(),
+            checks: report.checks.iter().map(|check| check.to_string()).collect(),
+            findings: report
+                .findings
+                .into_iter()
+                .map(|finding| GitLintFinding {
+                    check: finding.check.to_string(),
+                    severity: finding.severity.to_string(),
+                    subject: finding.subject,
+                    message: finding.message,
+                })
+                .collect(),
+        }))
+    }
+}
@@ -1,9 +1,10 @@
 //! Git log tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitLogArgs, GitLogOutput, GitCommitInfo, GitAuthorInfo, LogPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::{GitAuthorInfo, LogPrompts};
+use super::schema::{GitCommitEdge, GitCommitInfo, GitLogArgs, GitLogOutput};
 use tokio_stream::StreamExt;
+use super::support;
 
 /// Tool for listing Git commit history
 #[derive(Clone)]
@@ -19,7 +20,10 @@ impl Tool for GitLogTool {
 
     fn description() -> &'static str {
         "List commit history from a Git repository. \
-         Optionally filter by file path and limit the number of results."
+         Optionally filter by file path and limit the number of results. \
+         Set 'decorate' to list the branches and tags pointing at each commit \
+         (like git log --decorate), and 'graph' to get the parent/child edges of the \
+         listed commits plus an ASCII rendering like git log --graph --oneline."
     }
 
     fn read_only() -> bool {
@@ -35,60 +39,61 @@ impl Tool for GitLogTool {
     }
 
     async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build log options
         let mut opts = crate::LogOpts::new();
 
         if let Some(max_count) = args.max_count {
-            opts = opts.max_count(max_count + args.skip);
+            opts = opts.max_count(max_count);
         }
+        opts = opts.skip(args.skip);
 
         if let Some(path_filter) = args.path_filter {
             opts = opts.path(path_filter);
         }
 
-        // Get log stream
+        opts = opts.decorate(args.decorate).graph(args.graph);
+
+        // The walk skips and stops on its own; commits are read as it produces
+        // them, and dropping the stream (e.g. when the request is cancelled)
+        // ends the walk
         let mut stream = crate::log(repo, opts, ctx.pwd());
 
-        // Collect commits
         let mut commits = Vec::new();
-        let mut skipped = 0;
+        let mut graph_commits = Vec::new();
+        let mut reached_shallow_boundary = false;
 
         while let Some(result) = stream.next().await {
             match result {
                 Ok(commit_info) => {
-                    // Skip first N commits if requested
-                    if skipped < args.skip {
-                        skipped += 1;
-                        continue;
-                    }
+                    reached_shallow_boundary |= commit_info.shallow_boundary;
 
                     commits.push(GitCommitInfo {
                         id: commit_info.id.to_string(),
                         author: GitAuthorInfo {
                             name: commit_info.author.name.clone(),
                             email: commit_info.author.email.clone(),
-                            time: commit_info.author.time.to_rfc3339(),
+                            time: commit_info.author.local_time().to_rfc3339(),
                         },
                         summary: commit_info.summary.clone(),
                         time: commit_info.time.to_rfc3339(),
+                        refs: commit_info.decorations.iter().map(ToString::to_string).collect(),
+                        warnings: commit_info.warnings.iter().map(ToString::to_string).collect(),
                     });
+                    if args.graph {
+                        graph_commits.push(commit_info);
+                    }
                 }
                 Err(e) => {
-                    return Err(McpError::Other(anyhow::anyhow!("{e}")));
+                    return Err(support::tool_error(e));
                 }
             }
         }
 
         // Build summary
-        let summary = if commits.is_empty() {
+        let mut summary = if commits.is_empty() {
             "\x1b[36mCommit History\x1b[0m\n Commits: 0 · No commits found".to_string()
         } else {
             let latest_message = commits
@@ -103,12 +108,41 @@ impl Tool for GitLogTool {
             )
         };
 
+        if reached_shallow_boundary {
+            summary.push_str(
+                "\n \x1b[33m⚠ Shallow clone: older history is not available locally\x1b[0m",
+            );
+        }
+
+        for warning in commits.iter().flat_map(|commit| &commit.warnings) {
+            summary.push_str(&format!("\n \x1b[33m⚠ {warning}\x1b[0m"));
+        }
+
+        // Topology of the listed commits
+        let edges: Vec<GitCommitEdge> = graph_commits
+            .iter()
+            .flat_map(|commit| {
+                commit.parents.iter().map(|parent| GitCommitEdge {
+                    child: commit.id.to_string(),
+                    parent: parent.to_string(),
+                })
+            })
+            .collect();
+        let graph = args.graph.then(|| crate::render_graph(&graph_commits).join("\n"));
+        if let Some(graph) = &graph {
+            summary.push('\n');
+            summary.push_str(graph);
+        }
+
         let count = commits.len();
 
         Ok(ToolResponse::new(summary, GitLogOutput {
             success: true,
             commits,
             count,
+            reached_shallow_boundary,
+            edges,
+            graph,
         }))
     }
 }
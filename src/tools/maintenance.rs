@@ -0,0 +1,168 @@
+//! Git maintenance tool - stale lock file cleanup and line ending renormalization
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{
+    GitEolIssue, GitLockFile, GitMaintenanceArgs, GitMaintenanceOutput, MaintenancePrompts,
+    GIT_MAINTENANCE,
+};
+use std::time::Duration;
+
+use super::support;
+
+/// Default minimum age before a lock file is considered stale
+const DEFAULT_MAX_AGE_SECS: u64 = 600;
+
+/// Tool for detecting and removing stale lock files and renormalizing line endings
+#[derive(Clone)]
+pub struct GitMaintenanceTool;
+
+impl Tool for GitMaintenanceTool {
+    type Args = GitMaintenanceArgs;
+    type Prompts = MaintenancePrompts;
+
+    fn name() -> &'static str {
+        GIT_MAINTENANCE
+    }
+
+    fn description() -> &'static str {
+        "Detect and remove stale lock files (index.lock, HEAD.lock, ref locks) left by \
+         crashed git processes. Locks younger than max_age_secs (default 600) or held open \
+         by a running process are kept. Use dry_run to only list locks. Set auto_recover to \
+         clear stale locks and retry automatically when add/commit/checkout hit a lock. \
+         With operation \"eol\", list tracked files committed with CRLF line endings although \
+         .gitattributes or core.autocrlf make them text, and stage them again with LF line \
+         endings (like git add --renormalize .) unless dry_run is set."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true // Deletes lock files, restages files
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        match args.operation.as_deref().unwrap_or("locks") {
+            "locks" => {}
+            "eol" => return eol(&args.path, args.dry_run).await,
+            other => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Invalid maintenance operation: {other}. Valid operations: locks, eol"
+                )));
+            }
+        }
+
+        let repo = support::open_repo(&args.path).await?;
+        let max_age = Duration::from_secs(args.max_age_secs.unwrap_or(DEFAULT_MAX_AGE_SECS));
+
+        if let Some(enabled) = args.auto_recover {
+            crate::set_lock_recovery(enabled.then_some(max_age));
+        }
+
+        let (removed, kept): (Vec<crate::LockFile>, Vec<crate::LockFile>) = if args.dry_run {
+            let locks = crate::find_locks(repo)
+                .await
+                .map_err(support::tool_error)?;
+            locks.into_iter().partition(|lock| lock.is_stale(max_age))
+        } else {
+            let report = crate::clear_stale_locks(repo, max_age)
+                .await
+                .map_err(support::tool_error)?;
+            (report.removed, report.kept)
+        };
+
+        let mut summary = format!(
+            "\x1b[36mLock Cleanup{}\x1b[0m\n {}: {} stale lock(s)\n Kept: {}",
+            if args.dry_run { " (dry run)" } else { "" },
+            if args.dry_run { "Would remove" } else { "Removed" },
+            removed.len(),
+            kept.len()
+        );
+        for lock in &removed {
+            summary.push_str(&format!("\n  \x1b[31m-\x1b[0m {}", lock.path.display()));
+        }
+        for lock in &kept {
+            let why = match lock.owner_pid {
+                Some(pid) => format!("held by pid {pid}"),
+                None => "recent".to_string(),
+            };
+            summary.push_str(&format!("\n  \x1b[33m=\x1b[0m {} ({why})", lock.path.display()));
+        }
+
+        let to_output = |lock: crate::LockFile| GitLockFile {
+            path: lock.path.display().to_string(),
+            age_secs: lock.age.map(|age| age.as_secs()),
+            owner_pid: lock.owner_pid,
+        };
+
+        Ok(ToolResponse::new(summary, GitMaintenanceOutput {
+            success: true,
+            operation: "locks".to_string(),
+            dry_run: args.dry_run,
+            removed: removed.into_iter().map(to_output).collect(),
+            kept: kept.into_iter().map(to_output).collect(),
+            auto_recover: crate::operations::maintenance::lock_recovery().is_some(),
+            eol_issues: Vec::new(),
+            renormalized: Vec::new(),
+        }))
+    }
+}
+
+/// Audit line endings, and renormalize them unless `dry_run`
+async fn eol(path: &str, dry_run: bool) -> Result<ToolResponse<GitMaintenanceOutput>, McpError> {
+    let (issues, renormalized) = support::with_repo(path, move |repo| async move {
+        let issues = crate::eol_audit(repo.clone()).await?;
+        let renormalized = if dry_run || issues.is_empty() {
+            Vec::new()
+        } else {
+            crate::renormalize(repo).await?
+        };
+        Ok((issues, renormalized))
+    })
+    .await?;
+
+    let mut summary = format!(
+        "\x1b[36mLine Endings{}\x1b[0m\n Files with CRLF line endings: {}",
+        if dry_run { " (dry run)" } else { "" },
+        issues.len()
+    );
+    for issue in &issues {
+        summary.push_str(&format!(
+            "\n  \x1b[33m!\x1b[0m {} ({}, {})",
+            issue.path, issue.found, issue.setting
+        ));
+    }
+    if !dry_run {
+        summary.push_str(&format!("\n Renormalized: {}", renormalized.len()));
+        if !renormalized.is_empty() {
+            summary.push_str("\n Commit the staged files to finish");
+        }
+    }
+
+    Ok(ToolResponse::new(summary, GitMaintenanceOutput {
+        success: true,
+        operation: "eol".to_string(),
+        dry_run,
+        removed: Vec::new(),
+        kept: Vec::new(),
+        auto_recover: crate::operations::maintenance::lock_recovery().is_some(),
+        eol_issues: issues
+            .into_iter()
+            .map(|issue| GitEolIssue {
+                path: issue.path,
+                found: issue.found.to_string(),
+                setting: issue.setting,
+            })
+            .collect(),
+        renormalized,
+    }))
+}
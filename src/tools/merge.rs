@@ -1,8 +1,10 @@
 //! Git merge tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitMergeArgs, GitMergeOutput, MergePrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::MergePrompts;
+use super::schema::{GitMergeArgs, GitMergeOutput};
+
+use super::support;
 
 /// Tool for merging branches
 #[derive(Clone)]
@@ -18,7 +20,10 @@ impl Tool for GitMergeTool {
 
     fn description() -> &'static str {
         "Merge a branch or commit into the current branch. \
-         Joins two or more development histories together."
+         Joins two or more development histories together. \
+         With squash=true, folds the branch's changes into a single commit on `base` \
+         (default: current branch) without needing `base` checked out. \
+         With require_up_to_date=true, refuses if the target branch is behind its upstream."
     }
 
     fn read_only() -> bool {
@@ -34,23 +39,60 @@ impl Tool for GitMergeTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let require_up_to_date = args.require_up_to_date;
+
+        if args.squash {
+            let (base, branch, message) = (args.base, args.branch.clone(), args.message);
+            let (base, result) = support::with_repo(&args.path, move |repo| async move {
+                let base = match base {
+                    Some(base) => base,
+                    None => crate::current_branch(repo.clone()).await?.name,
+                };
+                if require_up_to_date {
+                    crate::ensure_up_to_date(repo.clone(), Some(&base)).await?;
+                }
+                let message = message.unwrap_or_else(|| format!("Squash merge '{branch}'"));
+
+                let result = crate::squash_merge(repo, &base, &branch, &message).await?;
+                Ok((base, result))
+            })
+            .await?;
+
+            let summary = format!(
+                "\x1b[33m\u{e727} Squash: {} → {}\x1b[0m\n\
+                 \u{2139} Commits: {} · New commit: {}",
+                args.branch,
+                base,
+                result.squashed_commits,
+                &result.commit_id.to_string()[..7]
+            );
+
+            return Ok(ToolResponse::new(summary, GitMergeOutput {
+                success: true,
+                merge_type: "squash".to_string(),
+                commit_id: Some(result.commit_id.to_string()),
+                message: format!(
+                    "Squashed {} commit(s) of '{}' onto '{}'",
+                    result.squashed_commits, args.branch, base
+                ),
+            }));
+        }
 
         // Build merge options (note inverse logic for no_ff)
         let mut opts = crate::MergeOpts::new(&args.branch);
         opts = opts.no_ff(!args.fast_forward); // Inverse logic
         opts = opts.commit(args.auto_commit);
+        opts = opts.autostash(args.autostash);
 
         // Execute merge
-        let outcome = crate::merge(repo, opts)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let outcome = support::with_repo(&args.path, move |repo| async move {
+            // Refuse to merge into a branch that is behind its upstream
+            if require_up_to_date {
+                crate::ensure_up_to_date(repo.clone(), None).await?;
+            }
+            crate::merge(repo, opts).await
+        })
+        .await?;
 
         let (merge_type, commit_id) = match outcome {
             crate::MergeOutcome::FastForward(id) => {
@@ -3,13 +3,21 @@
 //! This module provides Model Context Protocol (MCP) tool wrappers around
 //! the core Git operations for use in AI agent systems.
 
+// Shared tool plumbing
+pub(crate) mod schema;
+pub(crate) mod support;
+
 // Repository Operations
 pub mod clone;
 pub mod discover;
 pub mod init;
 pub mod open;
+pub mod repo_registry;
+pub mod scaffold;
+pub mod sync;
 
 // Branch Operations
+pub mod branch_cleanup;
 pub mod branch_create;
 pub mod branch_delete;
 pub mod branch_list;
@@ -17,17 +25,26 @@ pub mod branch_rename;
 
 // Commit & Staging Operations
 pub mod add;
+pub mod apply;
+pub mod attributes_edit;
+pub mod blame;
 pub mod checkout;
+pub mod cherry_pick;
 pub mod commit;
 pub mod diff;
+pub mod file_info;
 pub mod history;
+pub mod ignore_edit;
 pub mod log;
+pub mod search_commits;
+pub mod show_commit;
 
 // Remote Operations
 pub mod fetch;
 pub mod merge;
 pub mod pull;
 pub mod push;
+pub mod rebase;
 pub mod remote_add;
 pub mod remote_list;
 pub mod remote_remove;
@@ -45,16 +62,37 @@ pub mod worktree_unlock;
 pub mod status;
 
 // Stash Operations
+pub mod snapshot;
 pub mod stash;
 
 // Tag Operations
+pub mod release;
 pub mod tag;
 
+// Review Operations
+pub mod codeowners;
+pub mod compare;
+pub mod contains;
+pub mod impact;
+pub mod review_packet;
+pub mod suggest_reviewers;
+
+// Workspace Operations
+pub mod multi_repo;
+
+// Maintenance Operations
+pub mod capabilities;
+pub mod lint;
+pub mod maintenance;
+pub mod replace;
+pub mod subtree;
+
 // Re-export tools
 pub use clone::GitCloneTool;
 pub use discover::GitDiscoverTool;
 pub use init::GitInitTool;
 pub use open::GitOpenTool;
+pub use repo_registry::GitRepoRegistryTool;
 
 pub use branch_create::GitBranchCreateTool;
 pub use branch_delete::GitBranchDeleteTool;
@@ -63,15 +101,20 @@ pub use branch_rename::GitBranchRenameTool;
 
 pub use add::GitAddTool;
 pub use checkout::GitCheckoutTool;
+pub use cherry_pick::GitCherryPickTool;
 pub use commit::GitCommitTool;
 pub use diff::GitDiffTool;
+pub use file_info::GitFileInfoTool;
 pub use history::GitHistoryTool;
 pub use log::GitLogTool;
+pub use search_commits::GitSearchCommitsTool;
+pub use show_commit::GitShowCommitTool;
 
 pub use fetch::GitFetchTool;
 pub use merge::GitMergeTool;
 pub use pull::GitPullTool;
 pub use push::GitPushTool;
+pub use rebase::GitRebaseTool;
 pub use remote_add::GitRemoteAddTool;
 pub use remote_list::GitRemoteListTool;
 pub use remote_remove::GitRemoteRemoveTool;
@@ -86,4 +129,25 @@ pub use worktree_unlock::GitWorktreeUnlockTool;
 
 pub use status::GitStatusTool;
 pub use stash::GitStashTool;
+pub use snapshot::GitSnapshotTool;
 pub use tag::GitTagTool;
+pub use capabilities::GitCapabilitiesTool;
+pub use maintenance::GitMaintenanceTool;
+pub use suggest_reviewers::GitSuggestReviewersTool;
+pub use codeowners::GitCodeownersTool;
+pub use compare::GitCompareTool;
+pub use replace::GitReplaceTool;
+pub use contains::GitContainsTool;
+pub use impact::GitImpactTool;
+pub use subtree::GitSubtreeTool;
+pub use scaffold::GitScaffoldTool;
+pub use multi_repo::GitMultiRepoTool;
+pub use ignore_edit::GitIgnoreEditTool;
+pub use attributes_edit::GitAttributesEditTool;
+pub use blame::GitBlameTool;
+pub use lint::GitLintTool;
+pub use apply::GitApplyTool;
+pub use review_packet::GitReviewPacketTool;
+pub use release::GitReleaseTool;
+pub use branch_cleanup::GitBranchCleanupTool;
+pub use sync::GitSyncTool;
@@ -0,0 +1,233 @@
+//! Git multi-repo tool - run a read-only operation across registered repositories
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use tokio_stream::StreamExt;
+
+use crate::{GrepOpts, LogOpts, RepoAlias};
+use super::schema::{
+    GitGrepMatch, GitMultiRepoArgs, GitMultiRepoCommit, GitMultiRepoOutput, GitMultiRepoResult,
+    MultiRepoPrompts, GIT_MULTI_REPO,
+};
+use super::support;
+
+
+/// Entries listed per repository unless `limit` says otherwise
+const DEFAULT_LIMIT: usize = 10;
+
+/// Tool for broadcasting status, log, branch listing or grep over many repositories
+#[derive(Clone)]
+pub struct GitMultiRepoTool;
+
+/// What one repository answered
+enum Answer {
+    Status(crate::BranchInfo, bool),
+    Log(Vec<crate::CommitInfo>),
+    Branches(Vec<String>),
+    Grep(crate::GrepResult),
+}
+
+impl Tool for GitMultiRepoTool {
+    type Args = GitMultiRepoArgs;
+    type Prompts = MultiRepoPrompts;
+
+    fn name() -> &'static str {
+        GIT_MULTI_REPO
+    }
+
+    fn description() -> &'static str {
+        "Run a read-only operation across registered repositories (see git_repo_registry) \
+         in parallel and report the result per repository. Operations: 'status' (branch, \
+         upstream, clean/dirty), 'log' (recent commits), 'branches' (local branches), \
+         'grep' (lines of tracked files matching regex 'pattern'). 'repos' selects \
+         repositories by name; empty means all. A failing repository is reported with its \
+         error without failing the others."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let operation = args.operation.clone();
+        if !matches!(operation.as_str(), "status" | "log" | "branches" | "grep") {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Invalid multi-repo operation: {operation}. \
+                 Use 'status', 'log', 'branches' or 'grep'"
+            )));
+        }
+        let pattern = match (operation.as_str(), &args.pattern) {
+            ("grep", None) => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Multi-repo operation 'grep' requires 'pattern'"
+                )));
+            }
+            (_, pattern) => pattern.clone().unwrap_or_default(),
+        };
+        let limit = args.limit.unwrap_or(DEFAULT_LIMIT);
+
+        let repos = select_repos(&args.repos)?;
+        let answers = futures::future::join_all(repos.iter().map(|alias| {
+            let operation = operation.clone();
+            let opts = GrepOpts::new(pattern.clone())
+                .ignore_case(args.ignore_case)
+                .max_matches(limit);
+            support::with_repo(alias.name.clone(), move |repo| async move {
+                run(repo, &operation, opts, limit).await
+            })
+        }))
+        .await;
+
+        let mut summary = format!(
+            "\x1b[36mgit {operation} across {} repositories\x1b[0m",
+            repos.len()
+        );
+        let mut results = Vec::with_capacity(repos.len());
+        for (alias, answer) in repos.into_iter().zip(answers) {
+            summary.push_str(&format!("\n\x1b[1m{}\x1b[0m", alias.name));
+            let mut result = GitMultiRepoResult {
+                name: alias.name,
+                path: alias.path.display().to_string(),
+                success: answer.is_ok(),
+                ..Default::default()
+            };
+            match answer {
+                Err(e) => {
+                    summary.push_str(&format!(" \x1b[31m✗ {e}\x1b[0m"));
+                    result.error = Some(e.to_string());
+                }
+                Ok(Answer::Status(branch, is_clean)) => {
+                    let state = if is_clean {
+                        "\x1b[32m✓ clean\x1b[0m"
+                    } else {
+                        "\x1b[33m⚠ dirty\x1b[0m"
+                    };
+                    summary.push_str(&format!(" {} {state}", branch.name));
+                    if let (Some(upstream), Some(ahead), Some(behind)) =
+                        (&branch.upstream, branch.ahead_count, branch.behind_count)
+                    {
+                        summary.push_str(&format!(" [{upstream} ↑{ahead} ↓{behind}]"));
+                    }
+                    result.branch = Some(branch.name);
+                    result.commit = Some(branch.commit_hash);
+                    result.upstream = branch.upstream;
+                    result.ahead = branch.ahead_count;
+                    result.behind = branch.behind_count;
+                    result.is_clean = Some(is_clean);
+                    result.warnings = branch.warnings.iter().map(ToString::to_string).collect();
+                }
+                Ok(Answer::Log(commits)) => {
+                    for commit in &commits {
+                        summary.push_str(&format!(
+                            "\n  {} {}",
+                            commit.id.to_hex_with_len(7),
+                            commit.summary
+                        ));
+                    }
+                    result.commits = commits
+                        .into_iter()
+                        .map(|commit| GitMultiRepoCommit {
+                            id: commit.id.to_string(),
+                            summary: commit.summary,
+                            author: commit.author.name,
+                            time: commit.time.to_rfc3339(),
+                        })
+                        .collect();
+                }
+                Ok(Answer::Branches(branches)) => {
+                    summary.push_str(&format!(" {}", branches.join(", ")));
+                    result.branches = branches;
+                }
+                Ok(Answer::Grep(found)) => {
+                    for hit in &found.matches {
+                        summary.push_str(&format!(
+                            "\n  {}:{}: {}",
+                            hit.path, hit.line_number, hit.line
+                        ));
+                    }
+                    if found.truncated {
+                        summary.push_str(&format!("\n  … stopped after {limit} matches"));
+                    }
+                    result.truncated = found.truncated;
+                    result.matches = found
+                        .matches
+                        .into_iter()
+                        .map(|hit| GitGrepMatch {
+                            path: hit.path,
+                            line_number: hit.line_number,
+                            line: hit.line,
+                        })
+                        .collect();
+                }
+            }
+            results.push(result);
+        }
+
+        Ok(ToolResponse::new(summary, GitMultiRepoOutput {
+            success: results.iter().all(|result| result.success),
+            operation,
+            results,
+        }))
+    }
+}
+
+/// The registered repositories named in `names`, or all of them
+fn select_repos(names: &[String]) -> Result<Vec<RepoAlias>, McpError> {
+    if names.is_empty() {
+        let repos = crate::registered_repos();
+        if repos.is_empty() {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "No repositories registered; add some with git_repo_registry"
+            )));
+        }
+        return Ok(repos);
+    }
+    names
+        .iter()
+        .map(|name| {
+            crate::lookup_repo(name).ok_or_else(|| {
+                McpError::Other(anyhow::anyhow!("No repository registered as '{name}'"))
+            })
+        })
+        .collect()
+}
+
+async fn run(
+    repo: crate::RepoHandle,
+    operation: &str,
+    grep: GrepOpts,
+    limit: usize,
+) -> crate::GitResult<Answer> {
+    match operation {
+        "status" => {
+            let branch = crate::current_branch(repo.clone()).await?;
+            Ok(Answer::Status(branch, crate::is_clean(repo).await?))
+        }
+        "log" => {
+            let mut stream = crate::log(repo, LogOpts::new().max_count(limit), None);
+            let mut commits = Vec::new();
+            while let Some(commit) = stream.next().await {
+                commits.push(commit?);
+            }
+            Ok(Answer::Log(commits))
+        }
+        "branches" => {
+            let branches = crate::list_branches(repo)
+                .await
+                .map_err(|_| crate::GitError::ChannelClosed)??;
+            Ok(Answer::Branches(branches))
+        }
+        _ => Ok(Answer::Grep(crate::grep(repo, grep).await?)),
+    }
+}
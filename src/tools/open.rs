@@ -3,7 +3,8 @@
 use gix::bstr::ByteSlice;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitOpenArgs, GitOpenOutput, OpenPrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for opening existing Git repositories
 #[derive(Clone)]
@@ -35,18 +36,8 @@ impl Tool for GitOpenTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
-
-        // Get current branch information - fully inlined to avoid Send issues
-        let repo_for_branch = repo.clone();
-        let branch_name = tokio::task::spawn_blocking(move || {
-            let inner = repo_for_branch.clone_inner();
-
+        let (branch_name, is_clean) = support::with_repo_blocking(&args.path, |inner| {
+            // Current branch information
             let head = inner.head().map_err(|e| anyhow::anyhow!("Failed to get HEAD: {e}"))?;
 
             let branch = head
@@ -59,21 +50,14 @@ impl Tool for GitOpenTool {
                 })
                 .unwrap_or_else(|| "detached HEAD".to_string());
 
-            Ok::<_, anyhow::Error>(branch)
-        })
-        .await
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-        .map_err(McpError::Other)?;
+            let is_clean = inner
+                .is_dirty()
+                .map(|dirty| !dirty)
+                .map_err(|e| anyhow::anyhow!("Failed to check clean status: {e}"))?;
 
-        // Get is_clean status - inline to avoid Send issues
-        let repo_for_clean = repo.clone();
-        let is_clean = tokio::task::spawn_blocking(move || {
-            let inner = repo_for_clean.clone_inner();
-            inner.is_dirty().map(|dirty| !dirty)
+            Ok::<_, anyhow::Error>((branch, is_clean))
         })
-        .await
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to check clean status: {e}")))?;
+        .await?;
 
         // Terminal summary with ANSI colors and Nerd Font icons
         let status = if is_clean { "clean" } else { "dirty" };
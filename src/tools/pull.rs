@@ -1,9 +1,10 @@
 //! Git pull tool
 
-use gix::bstr::ByteSlice;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitPullArgs, GitPullOutput, PullPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::PullPrompts;
+
+use super::schema::{GitPullArgs, GitPullConflict, GitPullOutput};
+use super::support;
 
 /// Tool for pulling from remote repositories
 #[derive(Clone)]
@@ -20,7 +21,9 @@ impl Tool for GitPullTool {
     fn description() -> &'static str {
         "Pull changes from a remote repository. \
          Fetches and merges remote changes into the current branch. \
-         Equivalent to running 'git fetch' followed by 'git merge'."
+         Equivalent to running 'git fetch' followed by 'git merge'. \
+         If the merge conflicts, nothing is merged and the conflicted files are listed \
+         with their conflict kind (content, add/add, delete/modify, rename/delete, rename/rename)."
     }
 
     fn read_only() -> bool {
@@ -36,65 +39,62 @@ impl Tool for GitPullTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
-
-        // Get current branch name without holding a reference across await
-        // We clone the inner repository to avoid Send issues
-        let repo_for_current = repo.clone();
-        let branch_name = {
-            let inner = repo_for_current.clone_inner();
-            tokio::task::spawn_blocking(move || {
-                let head = inner.head().ok()?;
-                head.referent_name()
-                    .and_then(|name| {
-                        name.shorten()
-                            .to_str()
-                            .ok()
-                            .map(std::string::ToString::to_string)
-                    })
-            })
-            .await
-            .ok()
-            .and_then(|x| x)
-            .unwrap_or_else(|| "HEAD".to_string())
-        };
-
-        // Build pull options
-        let opts = crate::PullOpts {
-            remote: args.remote.clone(),
-            branch: branch_name,
-            fast_forward: args.fast_forward,
-            auto_commit: args.auto_commit,
-        };
-
-        // Execute pull
-        let result = crate::pull(repo, opts)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let remote = args.remote.clone();
+        let fast_forward = args.fast_forward;
+        let auto_commit = args.auto_commit;
+        let autostash = args.autostash;
+
+        // Pull into the branch HEAD is on, read from the same repository
+        let result = support::with_repo(&args.path, move |repo| async move {
+            let opts = crate::PullOpts {
+                remote,
+                branch: support::head_branch(repo.raw()).unwrap_or_else(|| "HEAD".to_string()),
+                fast_forward,
+                auto_commit,
+                autostash,
+            };
+            crate::pull(repo, opts).await
+        })
+        .await?;
 
         // Determine merge outcome string
         let merge_outcome_str = match &result.merge_outcome {
-            crate::MergeOutcome::FastForward(_) => "fast_forward",
-            crate::MergeOutcome::MergeCommit(_) => "merge_commit",
-            crate::MergeOutcome::AlreadyUpToDate => "already_up_to_date",
+            Some(crate::MergeOutcome::FastForward(_)) => "fast_forward",
+            Some(crate::MergeOutcome::MergeCommit(_)) => "merge_commit",
+            Some(crate::MergeOutcome::AlreadyUpToDate) => "already_up_to_date",
+            None => "conflicted",
         };
 
         // Terminal summary with ANSI colors and Nerd Font icons
-        let summary = format!(
+        let mut summary = format!(
             "\x1b[36m ⬇ Pull from {}\x1b[0m\n  ℹ Merge: {}",
             args.remote, merge_outcome_str
         );
+        for conflict in &result.conflicts {
+            summary.push_str(&format!(
+                "\n  \x1b[31m✗ {} ({})\x1b[0m",
+                conflict.path, conflict.kind
+            ));
+            if let Some(their_path) = &conflict.their_path {
+                summary.push_str(&format!(" → {their_path}"));
+            }
+        }
+
+        let conflicts = result
+            .conflicts
+            .into_iter()
+            .map(|conflict| GitPullConflict {
+                path: conflict.path,
+                their_path: conflict.their_path,
+                kind: conflict.kind.as_str().to_string(),
+            })
+            .collect::<Vec<_>>();
 
         Ok(ToolResponse::new(summary, GitPullOutput {
-            success: true,
+            success: conflicts.is_empty(),
             remote: args.remote.clone(),
             merge_outcome: merge_outcome_str.to_string(),
+            conflicts,
         }))
     }
 }
@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitPushArgs, GitPushOutput, PushPrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for pushing commits and tags to remote repositories
 #[derive(Clone)]
@@ -35,43 +36,15 @@ impl Tool for GitPushTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository and execute push in a spawn_blocking context
-        // to avoid Send issues with RepoHandle
-        let remote = args.remote.clone();
-        let refspecs = args.refspecs.clone();
-        let force = args.force;
-        let tags = args.tags;
-        let timeout_secs = args.timeout_secs;
-        let path_buf = path.to_path_buf();
-
-        let result = tokio::task::spawn_blocking(move || {
-            tokio::runtime::Handle::current().block_on(async move {
-                // Open repository
-                let repo = crate::open_repo(&path_buf)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Task execution failed: {e}"))?
-                    .map_err(|e| anyhow::anyhow!("{e}"))?;
-
-                // Build push options
-                let opts = crate::PushOpts {
-                    remote,
-                    refspecs,
-                    force,
-                    tags,
-                    timeout_secs,
-                };
-
-                // Execute push
-                crate::push(&repo, opts)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("{e}"))
-            })
-        })
-        .await
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-        .map_err(|e: anyhow::Error| McpError::Other(e))?;
+        let opts = crate::PushOpts {
+            remote: args.remote.clone(),
+            refspecs: args.refspecs.clone(),
+            force: args.force,
+            tags: args.tags,
+            timeout_secs: args.timeout_secs,
+            check_paths: false,
+        };
+        let result = support::with_repo(&args.path, |repo| crate::push(repo, opts)).await?;
 
         // Terminal summary
         let mut details = vec![
@@ -88,7 +61,8 @@ impl Tool for GitPushTool {
         }
 
         if !result.warnings.is_empty() {
-            details.push(format!("Warnings: {}", result.warnings.join("; ")));
+            let warnings: Vec<String> = result.warnings.iter().map(ToString::to_string).collect();
+            details.push(format!("Warnings: {}", warnings.join("; ")));
         }
 
         let summary = format!(
@@ -96,13 +70,16 @@ impl Tool for GitPushTool {
             details.join("\n")
         );
 
-        Ok(ToolResponse::new(summary, GitPushOutput {
-            success: true,
-            remote: args.remote.clone(),
-            refs_pushed: result.commits_pushed as u32,
-            tags_pushed: result.tags_pushed as u32,
-            force: args.force,
-            warnings: result.warnings,
-        }))
+        Ok(ToolResponse::new(
+            summary,
+            GitPushOutput {
+                success: true,
+                remote: args.remote.clone(),
+                refs_pushed: result.commits_pushed as u32,
+                tags_pushed: result.tags_pushed as u32,
+                force: args.force,
+                warnings: result.warnings.iter().map(ToString::to_string).collect(),
+            },
+        ))
     }
 }
@@ -0,0 +1,118 @@
+//! Git rebase tool - replay a branch onto a new base
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+
+use super::schema::{GitRebaseArgs, GitRebaseOutput, GitRebasedCommit, RebasePrompts, GIT_REBASE};
+use super::support;
+use crate::GitError;
+
+/// Tool for rebasing branches without an interactive session
+#[derive(Clone)]
+pub struct GitRebaseTool;
+
+impl Tool for GitRebaseTool {
+    type Args = GitRebaseArgs;
+    type Prompts = RebasePrompts;
+
+    fn name() -> &'static str {
+        GIT_REBASE
+    }
+
+    fn description() -> &'static str {
+        "Replay a branch's commits onto a new base (like git rebase --onto <onto> <upstream> \
+         <branch>) and return the rewritten commit ids. The branch defaults to the current one \
+         and 'upstream' to 'onto'; commits already in the new base are dropped. A branch \
+         checked out here must be clean unless 'autostash' is set. On conflicts the branch is \
+         left unchanged and the conflicting paths are returned."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true // Moves the branch off its old commits
+    }
+
+    fn idempotent() -> bool {
+        true // A branch already on the new base is left alone
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let repo = support::open_repo(&args.path).await?;
+
+        let mut opts = crate::RebaseOpts::new(&args.onto).autostash(args.autostash);
+        if let Some(upstream) = &args.upstream {
+            opts = opts.upstream(upstream);
+        }
+        if let Some(branch) = &args.branch {
+            opts = opts.branch(branch);
+        }
+
+        let result = match crate::rebase(repo, opts).await {
+            Ok(result) => result,
+            Err(GitError::MergeConflict { message, paths }) => {
+                let mut summary = format!("\x1b[31mRebase conflicts\x1b[0m\n {message}");
+                for path in &paths {
+                    summary.push_str(&format!("\n  \x1b[31m!\x1b[0m {path}"));
+                }
+                return Ok(ToolResponse::new(summary, GitRebaseOutput {
+                    success: false,
+                    branch: args.branch.unwrap_or_default(),
+                    old_tip: String::new(),
+                    new_tip: String::new(),
+                    rewritten: Vec::new(),
+                    dropped: Vec::new(),
+                    conflicts: paths,
+                }));
+            }
+            Err(e) => return Err(support::tool_error(e)),
+        };
+
+        let new_tip = result.new_tip.to_string();
+        let summary = if result.new_tip == result.old_tip {
+            format!(
+                "\x1b[32mUp to date\x1b[0m {} is already on {}",
+                result.branch_ref, args.onto
+            )
+        } else {
+            let mut summary = format!(
+                "\x1b[32mRebased\x1b[0m {} onto {} · {} rewritten, {} dropped · tip \x1b[33m{}\x1b[0m",
+                result.branch_ref,
+                args.onto,
+                result.rewritten.len(),
+                result.dropped.len(),
+                &new_tip[..7]
+            );
+            for (original, rewritten) in &result.rewritten {
+                summary.push_str(&format!(
+                    "\n  {} -> {}",
+                    &original.to_string()[..7],
+                    &rewritten.to_string()[..7]
+                ));
+            }
+            summary
+        };
+
+        Ok(ToolResponse::new(summary, GitRebaseOutput {
+            success: true,
+            branch: result.branch_ref,
+            old_tip: result.old_tip.to_string(),
+            new_tip,
+            rewritten: result
+                .rewritten
+                .iter()
+                .map(|(original, rewritten)| GitRebasedCommit {
+                    original: original.to_string(),
+                    rewritten: rewritten.to_string(),
+                })
+                .collect(),
+            dropped: result.dropped.iter().map(ToString::to_string).collect(),
+            conflicts: Vec::new(),
+        }))
+    }
+}
@@ -0,0 +1,111 @@
+//! Git release tool - tag, push and summarize a release in one call
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use kodegen_mcp_schema::git::GitAuthorInfo;
+use super::schema::{GitCommitInfo, GitReleaseArgs, GitReleaseOutput, ReleasePrompts, GIT_RELEASE};
+
+use crate::operations::release::{self, ReleaseOpts};
+
+use super::support;
+
+/// Tool for cutting a release tag
+#[derive(Clone)]
+pub struct GitReleaseTool;
+
+impl Tool for GitReleaseTool {
+    type Args = GitReleaseArgs;
+    type Prompts = ReleasePrompts;
+
+    fn name() -> &'static str {
+        GIT_RELEASE
+    }
+
+    fn description() -> &'static str {
+        "Cut a release: refuse if the working tree is dirty or the tag names another commit \
+         locally or on the remote, create an annotated (optionally signed) tag, push it and \
+         return the commits since the previous tag as a changelog. Safe to repeat: an \
+         existing tag at the same commit is reused and not pushed twice."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false // Never moves or replaces a tag
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let mut opts = ReleaseOpts::new()
+            .sign(args.sign)
+            .remote(args.remote.clone())
+            .push(args.push);
+        if let Some(target) = &args.target {
+            opts = opts.target(target);
+        }
+        if let Some(message) = &args.message {
+            opts = opts.message(message);
+        }
+        let version = args.version.clone();
+        let result = support::with_repo(&args.path, |repo| {
+            release::cut(repo, &version, opts)
+        })
+        .await?;
+
+        let mut summary = format!(
+            "\x1b[32mRelease {}\x1b[0m at {}\n \
+             {} commit(s) since {}",
+            args.version,
+            &result.tag.target_commit[..7.min(result.tag.target_commit.len())],
+            result.commits.len(),
+            result.previous_tag.as_deref().unwrap_or("the first commit")
+        );
+        if !result.created {
+            summary.push_str("\n Tag already existed at this commit");
+        }
+        if result.pushed {
+            summary.push_str(&format!("\n Pushed to {}", args.remote));
+        } else if args.push {
+            summary.push_str(&format!("\n Already on {}", args.remote));
+        }
+
+        let commits = result
+            .commits
+            .into_iter()
+            .map(|commit| {
+                let author_time = commit.author.local_time().to_rfc3339();
+                GitCommitInfo {
+                    id: commit.id.to_string(),
+                    author: GitAuthorInfo {
+                        name: commit.author.name,
+                        email: commit.author.email,
+                        time: author_time,
+                    },
+                    summary: commit.summary,
+                    time: commit.time.to_rfc3339(),
+                    refs: Vec::new(),
+                    warnings: commit.warnings.iter().map(ToString::to_string).collect(),
+                }
+            })
+            .collect();
+
+        Ok(ToolResponse::new(summary, GitReleaseOutput {
+            success: true,
+            version: args.version,
+            target_commit: result.tag.target_commit,
+            previous_tag: result.previous_tag,
+            commits,
+            changelog: result.changelog,
+            created: result.created,
+            pushed: result.pushed,
+        }))
+    }
+}
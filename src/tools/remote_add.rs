@@ -1,8 +1,11 @@
 //! Git remote add tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitRemoteAddArgs, GitRemoteAddOutput, RemoteAddPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::RemoteAddPrompts;
+
+use super::schema::{GitRemoteAddArgs, GitRemoteAddOutput};
+
+use super::support;
 
 /// Tool for adding remote repositories
 #[derive(Clone)]
@@ -18,7 +21,8 @@ impl Tool for GitRemoteAddTool {
 
     fn description() -> &'static str {
         "Add a new remote repository. \
-         Configures a named remote with fetch/push URLs for collaboration."
+         Configures a named remote with fetch/push URLs for collaboration. \
+         The URL is validated, stored in canonical form and returned with its parsed parts."
     }
 
     fn read_only() -> bool {
@@ -34,13 +38,8 @@ impl Tool for GitRemoteAddTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build add options
         let opts = crate::RemoteAddOpts {
@@ -50,21 +49,29 @@ impl Tool for GitRemoteAddTool {
         };
 
         // Execute add
-        crate::add_remote(repo, opts)
+        let url = crate::add_remote(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::tool_error)?;
 
         // Terminal summary with ANSI colors and Nerd Font icons
-        let summary = format!(
-            "\x1b[32m Add Remote\x1b[0m\n  {} -> {}",
-            args.name, args.url
-        );
+        let summary = format!("\x1b[32m Add Remote\x1b[0m\n  {} -> {}", args.name, url.url);
 
-        Ok(ToolResponse::new(summary, GitRemoteAddOutput {
-            success: true,
-            name: args.name.clone(),
-            url: args.url.clone(),
-            message: format!("Added remote '{}' with URL '{}'", args.name, args.url),
-        }))
+        Ok(ToolResponse::new(
+            summary,
+            GitRemoteAddOutput {
+                success: true,
+                name: args.name.clone(),
+                message: format!("Added remote '{}' with URL '{}'", args.name, url.url),
+                url: url.url,
+                scheme: url.scheme,
+                user: url.user,
+            host: url.host,
+            port: url.port,
+            host_kind: url.host_kind.as_str().to_string(),
+                repo_path: url.path,
+                owner: url.owner,
+                repo: url.repo,
+            },
+        ))
     }
 }
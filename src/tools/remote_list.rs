@@ -1,8 +1,10 @@
 //! Git remote list tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitRemoteListArgs, GitRemoteListOutput, GitRemoteInfo, RemoteListPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::RemoteListPrompts;
+
+use super::schema::{GitRemoteInfo, GitRemoteListArgs, GitRemoteListOutput};
+use super::support;
 
 /// Tool for listing remote repositories
 #[derive(Clone)]
@@ -18,7 +20,9 @@ impl Tool for GitRemoteListTool {
 
     fn description() -> &'static str {
         "List all configured remote repositories. \
-         Shows remote names and their fetch/push URLs."
+         Shows remote names, their fetch/push URLs and refspecs, which remote is the \
+         default for the current branch (use it when no remote is specified), and when \
+         each remote was last fetched."
     }
 
     fn read_only() -> bool {
@@ -34,29 +38,7 @@ impl Tool for GitRemoteListTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository and list remotes in a spawn_blocking context
-        // to avoid Send issues with RepoHandle
-        let path_buf = path.to_path_buf();
-
-        let remotes = tokio::task::spawn_blocking(move || {
-            tokio::runtime::Handle::current().block_on(async move {
-                // Open repository
-                let repo = crate::open_repo(&path_buf)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Task execution failed: {e}"))?
-                    .map_err(|e| anyhow::anyhow!("{e}"))?;
-
-                // List remotes
-                crate::list_remotes(&repo)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("{e}"))
-            })
-        })
-        .await
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-        .map_err(|e: anyhow::Error| McpError::Other(e))?;
+        let remotes = support::with_repo(&args.path, crate::list_remotes).await?;
 
         // Terminal summary with ANSI colors and Nerd Font icons
         let mut summary = format!(
@@ -73,7 +55,14 @@ impl Tool for GitRemoteListTool {
                 } else {
                     format!("fetch: {} | push: {}", remote.fetch_url, remote.push_url)
                 };
-                summary.push_str(&format!("\n  {} -> {}", remote.name, urls));
+                let default = if remote.is_default { " (default)" } else { "" };
+                summary.push_str(&format!("\n  {}{} -> {}", remote.name, default, urls));
+                if let Some(fetched) = remote.last_fetched {
+                    summary.push_str(&format!(" · fetched {}", fetched.to_rfc3339()));
+                }
+                for warning in &remote.warnings {
+                    summary.push_str(&format!("\n    \x1b[33m⚠ {warning}\x1b[0m"));
+                }
             }
         }
 
@@ -83,6 +72,11 @@ impl Tool for GitRemoteListTool {
                 name: r.name.clone(),
                 fetch_url: r.fetch_url.clone(),
                 push_url: r.push_url.clone(),
+                fetch_refspecs: r.fetch_refspecs.clone(),
+                push_refspecs: r.push_refspecs.clone(),
+                is_default: r.is_default,
+                last_fetched: r.last_fetched.map(|time| time.to_rfc3339()),
+                warnings: r.warnings.iter().map(ToString::to_string).collect(),
             })
             .collect();
 
@@ -0,0 +1,126 @@
+//! Git replace tool - replace refs and history grafts
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{
+    GitReplaceArgs, GitReplaceEntry, GitReplaceOutput, ReplacePrompts, GIT_REPLACE,
+};
+
+use crate::ReplaceRef;
+use super::support;
+
+/// Tool for managing replace refs
+#[derive(Clone)]
+pub struct GitReplaceTool;
+
+impl Tool for GitReplaceTool {
+    type Args = GitReplaceArgs;
+    type Prompts = ReplacePrompts;
+
+    fn name() -> &'static str {
+        GIT_REPLACE
+    }
+
+    fn description() -> &'static str {
+        "Manage replace refs (git replace). Operations: 'create' replaces object 'original' \
+         with 'replacement', 'graft' gives commit 'original' the given 'parents' (e.g. to \
+         attach the root of a shallow history to the full history it was cut from), \
+         'list' shows replacements, 'delete' removes the replacement of 'original'. \
+         No commit is rewritten."
+    }
+
+    fn read_only() -> bool {
+        false // Writes refs under refs/replace/
+    }
+
+    fn destructive() -> bool {
+        false // The original objects are kept; delete undoes a replacement
+    }
+
+    fn idempotent() -> bool {
+        false // Creating an existing replacement fails without force
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let required = |value: &Option<String>, field: &str| {
+            value.clone().ok_or_else(|| {
+                McpError::Other(anyhow::anyhow!(
+                    "Replace operation '{}' requires '{field}'",
+                    args.operation
+                ))
+            })
+        };
+
+        let (summary, replacements) = match args.operation.as_str() {
+            "create" => {
+                let original = required(&args.original, "original")?;
+                let replacement = required(&args.replacement, "replacement")?;
+                let repo = support::open_repo(&args.path).await?;
+                let created = crate::create_replace(repo, &original, &replacement, args.force)
+                    .await
+                    .map_err(support::tool_error)?;
+                let summary = format!(
+                    "\x1b[32m ✓ Replaced\x1b[0m {} {} → {}",
+                    created.kind, created.original, created.replacement
+                );
+                (summary, vec![entry(created)])
+            }
+            "graft" => {
+                let original = required(&args.original, "original")?;
+                let repo = support::open_repo(&args.path).await?;
+                let grafted = crate::graft(repo, &original, &args.parents, args.force)
+                    .await
+                    .map_err(support::tool_error)?;
+                let parents = if args.parents.is_empty() {
+                    "no parents".to_string()
+                } else {
+                    args.parents.join(", ")
+                };
+                let summary = format!(
+                    "\x1b[32m ✓ Grafted\x1b[0m {} onto {parents}\n Replacement: {}",
+                    grafted.original, grafted.replacement
+                );
+                (summary, vec![entry(grafted)])
+            }
+            "list" => {
+                let list = support::with_repo(&args.path, crate::list_replace).await?;
+                let mut summary = format!("\x1b[36mReplace refs ({})\x1b[0m", list.len());
+                for replace in &list {
+                    summary.push_str(&format!(
+                        "\n {} → {} ({})",
+                        replace.original, replace.replacement, replace.kind
+                    ));
+                }
+                (summary, list.into_iter().map(entry).collect())
+            }
+            "delete" => {
+                let original = required(&args.original, "original")?;
+                let target = original.clone();
+                support::with_repo(&args.path, |repo| crate::delete_replace(repo, &target)).await?;
+                (format!("\x1b[32m ✓ Replacement removed\x1b[0m {original}"), Vec::new())
+            }
+            other => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Invalid replace operation: {other}. Use 'create', 'graft', 'list' or 'delete'"
+                )));
+            }
+        };
+
+        Ok(ToolResponse::new(summary, GitReplaceOutput {
+            success: true,
+            operation: args.operation,
+            replacements,
+        }))
+    }
+}
+
+fn entry(replace: ReplaceRef) -> GitReplaceEntry {
+    GitReplaceEntry {
+        original: replace.original.to_string(),
+        replacement: replace.replacement.to_string(),
+        kind: replace.kind.to_string(),
+    }
+}
@@ -0,0 +1,117 @@
+//! Git repository registry tool - name repositories once, refer to them by name
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+
+use crate::RepoAlias;
+use super::schema::{
+    GitRegisteredRepo, GitRepoRegistryArgs, GitRepoRegistryOutput, RepoRegistryPrompts,
+    GIT_REPO_REGISTRY,
+};
+use super::support;
+
+/// Tool for registering repositories under logical names
+#[derive(Clone)]
+pub struct GitRepoRegistryTool;
+
+impl Tool for GitRepoRegistryTool {
+    type Args = GitRepoRegistryArgs;
+    type Prompts = RepoRegistryPrompts;
+
+    fn name() -> &'static str {
+        GIT_REPO_REGISTRY
+    }
+
+    fn description() -> &'static str {
+        "Give repositories logical names (e.g. 'backend', 'infra') that every git tool then \
+         accepts in place of its 'path'; there is no separate 'repo' argument, pass the name \
+         as 'path'. Operations: 'register' maps 'name' to 'path', optionally with a 'url' \
+         that is cloned into 'path' on first use; 'remove' forgets 'name' (the repository \
+         stays on disk); 'list' shows all registered repositories."
+    }
+
+    fn read_only() -> bool {
+        false // Changes the server's registry
+    }
+
+    fn destructive() -> bool {
+        false // Never touches repositories on disk
+    }
+
+    fn idempotent() -> bool {
+        true // Registering the same name again replaces the entry
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let required = |value: &Option<String>, field: &str| {
+            value.clone().ok_or_else(|| {
+                McpError::Other(anyhow::anyhow!(
+                    "Registry operation '{}' requires '{field}'",
+                    args.operation
+                ))
+            })
+        };
+
+        let (summary, repos) = match args.operation.as_str() {
+            "register" => {
+                let name = required(&args.name, "name")?;
+                let path = required(&args.path, "path")?;
+                let alias = crate::register_repo(name, path, args.url.clone())
+                    .map_err(support::tool_error)?;
+                let mut summary = format!(
+                    "\x1b[32m ✓ Registered\x1b[0m {} → {}",
+                    alias.name,
+                    alias.path.display()
+                );
+                if let Some(url) = &alias.url
+                    && !alias.exists()
+                {
+                    summary.push_str(&format!("\n Cloned from {url} on first use"));
+                }
+                (summary, vec![entry(alias)])
+            }
+            "remove" => {
+                let name = required(&args.name, "name")?;
+                let removed = crate::unregister_repo(&name).ok_or_else(|| {
+                    McpError::Other(anyhow::anyhow!("No repository registered as '{name}'"))
+                })?;
+                (format!("\x1b[32m ✓ Unregistered\x1b[0m {name}"), vec![entry(removed)])
+            }
+            "list" => {
+                let repos = crate::registered_repos();
+                let mut summary =
+                    format!("\x1b[36mRegistered repositories ({})\x1b[0m", repos.len());
+                for repo in &repos {
+                    summary.push_str(&format!("\n {} → {}", repo.name, repo.path.display()));
+                    if !repo.exists() {
+                        summary.push_str(" (not cloned yet)");
+                    }
+                }
+                (summary, repos.into_iter().map(entry).collect())
+            }
+            other => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Invalid registry operation: {other}. Use 'register', 'remove' or 'list'"
+                )));
+            }
+        };
+
+        Ok(ToolResponse::new(summary, GitRepoRegistryOutput {
+            success: true,
+            operation: args.operation,
+            repos,
+        }))
+    }
+}
+
+fn entry(alias: RepoAlias) -> GitRegisteredRepo {
+    GitRegisteredRepo {
+        exists: alias.exists(),
+        name: alias.name,
+        path: alias.path.display().to_string(),
+        url: alias.url,
+    }
+}
@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitResetArgs, GitResetOutput, ResetMode, ResetPrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for resetting repository to a specific commit
 #[derive(Clone)]
@@ -34,47 +35,21 @@ impl Tool for GitResetTool {
         true // Safe to reset to same target multiple times
     }
 
-    async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository and execute reset in a spawn_blocking context
-        // to avoid Send issues with RepoHandle
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
         let mode = args.mode;
         let target_for_output = args.target.clone();
-        let target = args.target;
-        let path_buf = path.to_path_buf();
-
-        tokio::task::spawn_blocking(move || {
-            tokio::runtime::Handle::current().block_on(async move {
-                // Open repository
-                let repo = crate::open_repo(&path_buf)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Task execution failed: {e}"))?
-                    .map_err(|e| anyhow::anyhow!("{e}"))?;
-
-                // Map schema ResetMode to operation ResetMode
-                let op_mode = match mode {
-                    ResetMode::Soft => crate::ResetMode::Soft,
-                    ResetMode::Mixed => crate::ResetMode::Mixed,
-                    ResetMode::Hard => crate::ResetMode::Hard,
-                };
-
-                // Build reset options
-                let opts = crate::ResetOpts {
-                    target,
-                    mode: op_mode,
-                    cancel_token: None,
-                };
-
-                // Execute reset
-                crate::reset(&repo, opts)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("{e}"))
-            })
-        })
-        .await
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-        .map_err(|e: anyhow::Error| McpError::Other(e))?;
+        let opts = crate::ResetOpts {
+            target: args.target,
+            // Map schema ResetMode to operation ResetMode
+            mode: match mode {
+                ResetMode::Soft => crate::ResetMode::Soft,
+                ResetMode::Mixed => crate::ResetMode::Mixed,
+                ResetMode::Hard => crate::ResetMode::Hard,
+            },
+            cancel_token: None,
+            progress: Some(super::checkout::forward_progress(&ctx, "Reset")),
+        };
+        support::with_repo(&args.path, |repo| crate::reset(repo, opts)).await?;
 
         // Terminal summary with ANSI colors and Nerd Font icons
         let mode_str = match mode {
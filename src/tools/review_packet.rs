@@ -0,0 +1,143 @@
+//! Git review packet tool - everything needed to review a branch in one call
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use kodegen_mcp_schema::git::GitAuthorInfo;
+use super::schema::{
+    GitOwnerCandidate, GitReviewCommit, GitReviewPacketArgs, GitReviewPacketOutput,
+    GitReviewRisk, ReviewPacketPrompts, GIT_REVIEW_PACKET,
+};
+
+use super::support;
+
+/// Tool for gathering a pull request style review packet
+#[derive(Clone)]
+pub struct GitReviewPacketTool;
+
+impl Tool for GitReviewPacketTool {
+    type Args = GitReviewPacketArgs;
+    type Prompts = ReviewPacketPrompts;
+
+    fn name() -> &'static str {
+        GIT_REVIEW_PACKET
+    }
+
+    fn description() -> &'static str {
+        "Gather a review packet for merging head into base: the commits with full messages, \
+         the unified diff since the merge base (capped at 256 KiB, whole files only), a tree \
+         of the changed files, suggested reviewers from blame, history and CODEOWNERS, and \
+         risk flags for large files, generated code and lockfile-only changes. \
+         Nothing is written to the repository."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let (base, head) = (args.base.clone(), args.head.clone());
+        let packet = support::with_repo(&args.path, |repo| {
+            crate::operations::review::packet(repo, &base, &head)
+        })
+        .await?;
+
+        let stats = &packet.diffstat;
+        let mut summary = format!(
+            "\x1b[36mReview: {} ← {}\x1b[0m\n \
+             {} commit(s) · {} files (+{}, -{})",
+            args.base,
+            args.head,
+            packet.commits.len(),
+            stats.total_files_changed,
+            stats.total_additions,
+            stats.total_deletions
+        );
+        for risk in &packet.risks {
+            summary.push_str(&format!("\n \x1b[33m{}\x1b[0m {}", risk.kind, risk.message));
+        }
+        if !packet.omitted_files.is_empty() {
+            summary.push_str(&format!(
+                "\n {} file(s) left out of the patch",
+                packet.omitted_files.len()
+            ));
+        }
+        for reviewer in &packet.reviewers {
+            summary.push_str(&format!(
+                "\n Reviewer: {} <{}> {:.0}%{}",
+                reviewer.name,
+                reviewer.email,
+                reviewer.score * 100.0,
+                if reviewer.in_codeowners { " \x1b[32m[CODEOWNERS]\x1b[0m" } else { "" }
+            ));
+        }
+
+        let files = stats.files.iter().map(support::diff_file).collect();
+        let commits = packet
+            .commits
+            .into_iter()
+            .map(|commit| {
+                let author_time = commit.info.author.local_time().to_rfc3339();
+                GitReviewCommit {
+                    id: commit.info.id.to_string(),
+                    author: GitAuthorInfo {
+                        name: commit.info.author.name,
+                        email: commit.info.author.email,
+                        time: author_time,
+                    },
+                    time: commit.info.time.to_rfc3339(),
+                    message: commit.message,
+                }
+            })
+            .collect();
+        let reviewers = packet
+            .reviewers
+            .into_iter()
+            .map(|candidate| GitOwnerCandidate {
+                name: candidate.name,
+                email: candidate.email,
+                blame_lines: candidate.blame_lines,
+                recent_commits: candidate.recent_commits,
+                score: candidate.score,
+                in_codeowners: candidate.in_codeowners,
+            })
+            .collect();
+        let risks = packet
+            .risks
+            .into_iter()
+            .map(|risk| GitReviewRisk {
+                kind: risk.kind.to_string(),
+                paths: risk.paths,
+                message: risk.message,
+            })
+            .collect();
+
+        Ok(ToolResponse::new(summary, GitReviewPacketOutput {
+            success: true,
+            base: args.base,
+            head: args.head,
+            merge_base: packet.merge_base.map(|id| id.to_string()),
+            commits,
+            files_changed: stats.total_files_changed as u32,
+            insertions: stats.total_additions as u32,
+            deletions: stats.total_deletions as u32,
+            files,
+            file_tree: packet.file_tree,
+            patch: packet.patch,
+            omitted_files: packet.omitted_files,
+            reviewers,
+            codeowners: packet.codeowners,
+            risks,
+        }))
+    }
+}
@@ -0,0 +1,98 @@
+//! Git scaffold tool - bootstrap a repository from a template
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{GitScaffoldArgs, GitScaffoldOutput, ScaffoldPrompts, GIT_SCAFFOLD};
+use super::support;
+
+/// Tool for creating repositories from template repositories
+#[derive(Clone)]
+pub struct GitScaffoldTool;
+
+impl Tool for GitScaffoldTool {
+    type Args = GitScaffoldArgs;
+    type Prompts = ScaffoldPrompts;
+
+    fn name() -> &'static str {
+        GIT_SCAFFOLD
+    }
+
+    fn description() -> &'static str {
+        "Create a new repository from a template repository (local path or URL). \
+         Replaces {{name}} placeholders in file contents and paths with 'variables', \
+         commits the result as a single initial commit without the template's history, \
+         then optionally creates 'branches', adds 'remote' as origin and pushes them. \
+         The destination must not exist or be empty."
+    }
+
+    fn read_only() -> bool {
+        false // Creates a repository and files
+    }
+
+    fn destructive() -> bool {
+        false // Only creates, doesn't delete
+    }
+
+    fn idempotent() -> bool {
+        false // Fails once the destination is populated
+    }
+
+    fn open_world() -> bool {
+        true // May clone the template and push to the remote
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let mut opts = crate::ScaffoldOpts::new(&args.template, &args.path).push(args.push);
+        if let Some(reference) = &args.reference {
+            opts = opts.reference(reference);
+        }
+        if let Some(message) = &args.message {
+            opts = opts.message(message);
+        }
+        for (name, value) in &args.variables {
+            opts = opts.variable(name, value);
+        }
+        for branch in &args.branches {
+            opts = opts.branch(branch);
+        }
+        if let Some(remote) = &args.remote {
+            opts = opts.remote(remote);
+        }
+
+        let result = crate::scaffold(opts).await.map_err(support::tool_error)?;
+
+        let mut summary = format!(
+            "\x1b[32m ✓ Scaffolded\x1b[0m {} from {}\n Commit: {} · {} files ({} rewritten)\n \
+             Branches: {}",
+            args.path,
+            args.template,
+            result.commit_id,
+            result.files,
+            result.rewritten_files,
+            result.branches.join(", ")
+        );
+        if result.pushed {
+            summary.push_str("\n Pushed to origin");
+        }
+        if !result.unresolved.is_empty() {
+            summary.push_str(&format!(
+                "\n \x1b[33mUnresolved placeholders: {}\x1b[0m",
+                result.unresolved.join(", ")
+            ));
+        }
+
+        Ok(ToolResponse::new(summary, GitScaffoldOutput {
+            success: true,
+            path: args.path,
+            commit: result.commit_id.to_string(),
+            files: result.files,
+            rewritten_files: result.rewritten_files,
+            unresolved: result.unresolved,
+            branches: result.branches,
+            pushed: result.pushed,
+        }))
+    }
+}
@@ -0,0 +1,68 @@
+//! Schema types for the git_add tool, extending `kodegen_mcp_schema::git::add`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_ADD};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::AddPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_add` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitAddArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Paths or glob patterns to stage, relative to the repository root
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    /// Stage new, modified and deleted files under `paths`, or the whole
+    /// worktree without paths
+    #[serde(default)]
+    pub all: bool,
+
+    /// Stage files even if they are ignored
+    #[serde(default)]
+    pub force: bool,
+
+    /// Stage modified and deleted tracked files only, under `paths` or the
+    /// whole worktree
+    #[serde(default)]
+    pub update: bool,
+
+    /// Record new files as intended to be added, without their content
+    #[serde(default)]
+    pub intent_to_add: bool,
+}
+
+/// Output from `git_add` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitAddOutput {
+    pub success: bool,
+    pub all: bool,
+    /// Paths given, empty when the whole worktree was staged
+    pub paths: Vec<String>,
+    /// Files staged, deletions included
+    pub count: usize,
+    pub update: bool,
+    pub intent_to_add: bool,
+    /// Files staged, or recorded as intended to add
+    pub added: Vec<String>,
+    /// Tracked files staged as deleted
+    pub deleted: Vec<String>,
+    /// Untracked files skipped because they are ignored
+    pub ignored: Vec<String>,
+    /// Ignored files staged because of `force`
+    pub force_added: Vec<String>,
+    /// Paths given that matched no file
+    pub missing: Vec<String>,
+}
+
+impl ToolArgs for GitAddArgs {
+    type Output = GitAddOutput;
+    type Prompts = AddPrompts;
+
+    const NAME: &'static str = GIT_ADD;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Stage file changes for commit";
+}
@@ -0,0 +1,87 @@
+//! Schema types for the git_apply tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_APPLY: &str = "git_apply";
+
+/// Arguments for `git_apply` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitApplyArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Unified diff to apply, as written by `git diff`
+    pub patch: String,
+
+    /// Only check that the patch applies
+    #[serde(default)]
+    pub check: bool,
+
+    /// Commit the result with this message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Author name (optional, uses git config if not provided)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_name: Option<String>,
+
+    /// Author email (optional, uses git config if not provided)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_email: Option<String>,
+}
+
+/// Output from `git_apply` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitApplyOutput {
+    pub success: bool,
+    pub check: bool,
+    /// Paths the patch changed
+    pub files: Vec<String>,
+    /// Commit made from the patched index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+}
+
+impl ToolArgs for GitApplyArgs {
+    type Output = GitApplyOutput;
+    type Prompts = ApplyPrompts;
+
+    const NAME: &'static str = GIT_APPLY;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Apply a unified diff to the index without touching the working tree";
+}
+
+/// Prompt arguments for `git_apply` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitApplyPromptArgs {}
+
+/// Prompt provider for `git_apply` tool
+pub struct ApplyPrompts;
+
+impl SealedPromptProvider for ApplyPrompts {}
+
+impl PromptProvider for ApplyPrompts {
+    type PromptArgs = GitApplyPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "I generated a fix as a diff. Can I commit it without touching the files the user is editing?",
+            "Apply it to the index and commit in one step:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"patch\": \"diff --git a/src/lib.rs b/src/lib.rs\\n...\", \
+             \"message\": \"Fix overflow in parser\"}\n\
+             ```\n\n\
+             The working tree is left alone, so the user's edits stay where they are. Add \
+             \"check\": true to only test whether the patch applies.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,97 @@
+//! Schema types for the git_attributes_edit tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_ATTRIBUTES_EDIT: &str = "git_attributes_edit";
+
+/// Arguments for `git_attributes_edit` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitAttributesEditArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Operation: "set", "remove", "list", "lfs_track" or "lfs_untrack"
+    pub operation: String,
+
+    /// Patterns whose attributes to edit
+    #[serde(default)]
+    pub patterns: Vec<String>,
+
+    /// Attributes to set (e.g. "eol=lf", "-diff", "linguist-generated"), or
+    /// names of attributes to remove
+    #[serde(default)]
+    pub attributes: Vec<String>,
+
+    /// Directory whose .gitattributes to edit (default: the repository root)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+}
+
+/// A pattern line of a .gitattributes file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitAttributeRule {
+    /// 1-based line number
+    pub line: usize,
+    pub pattern: String,
+    pub attributes: Vec<String>,
+}
+
+/// Output from `git_attributes_edit` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitAttributesEditOutput {
+    pub success: bool,
+    pub operation: String,
+    /// The .gitattributes file, relative to the repository root
+    pub file: String,
+    /// Patterns whose line was added, changed or removed
+    pub changed: Vec<String>,
+    /// Pattern lines of the file (list)
+    pub rules: Vec<GitAttributeRule>,
+}
+
+impl ToolArgs for GitAttributesEditArgs {
+    type Output = GitAttributesEditOutput;
+    type Prompts = AttributesEditPrompts;
+
+    const NAME: &'static str = GIT_ATTRIBUTES_EDIT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Set, remove and list .gitattributes, and track files with Git LFS";
+}
+
+/// Prompt arguments for `git_attributes_edit` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitAttributesEditPromptArgs {}
+
+/// Prompt provider for `git_attributes_edit` tool
+pub struct AttributesEditPrompts;
+
+impl SealedPromptProvider for AttributesEditPrompts {}
+
+impl PromptProvider for AttributesEditPrompts {
+    type PromptArgs = GitAttributesEditPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "How do I store the design files in Git LFS and keep shell scripts on LF?",
+            "Track the design files with LFS:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"operation\": \"lfs_track\", \"patterns\": [\"*.psd\"]}\n\
+             ```\n\n\
+             Then set the line ending of the scripts:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"operation\": \"set\", \"patterns\": [\"*.sh\"], \"attributes\": [\"text\", \"eol=lf\"]}\n\
+             ```\n\n\
+             Attributes merge into the pattern's existing line: \"eol=lf\" replaces \"eol=crlf\", \
+             and setting what is already there changes nothing.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
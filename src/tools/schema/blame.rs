@@ -0,0 +1,101 @@
+//! Schema types for the git_blame tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_BLAME: &str = "git_blame";
+
+/// Arguments for `git_blame` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitBlameArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// File to blame, relative to the repository root
+    pub file: String,
+
+    /// Revision to blame at (default: HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+
+    /// First line to report, 1-based (default: 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u32>,
+
+    /// Last line to report, inclusive (default: last line of the file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+}
+
+/// Authorship of one line
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitBlameLine {
+    pub line: u32,
+    /// Commit that last changed the line
+    pub commit: String,
+    pub author: String,
+    pub email: String,
+    /// Author time of that commit (RFC 3339)
+    pub time: String,
+    pub summary: String,
+    /// Line number in that commit's version of the file
+    pub original_line: u32,
+    /// Path in that commit, if the file was renamed since
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_path: Option<String>,
+    pub content: String,
+}
+
+/// Output from `git_blame` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitBlameOutput {
+    pub success: bool,
+    pub file: String,
+    /// Commit the file was blamed at
+    pub commit: String,
+    pub total_lines: u32,
+    pub lines: Vec<GitBlameLine>,
+}
+
+impl ToolArgs for GitBlameArgs {
+    type Output = GitBlameOutput;
+    type Prompts = BlamePrompts;
+
+    const NAME: &'static str = GIT_BLAME;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str =
+        "Show which commit, author and time last changed each line of a file";
+}
+
+/// Prompt arguments for `git_blame` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitBlamePromptArgs {}
+
+/// Prompt provider for `git_blame` tool
+pub struct BlamePrompts;
+
+impl SealedPromptProvider for BlamePrompts {}
+
+impl PromptProvider for BlamePrompts {
+    type PromptArgs = GitBlamePromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Who wrote lines 40 to 60 of src/parser.rs, and when?",
+            "Blame just that range:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"file\": \"src/parser.rs\", \"start_line\": 40, \"end_line\": 60}\n\
+             ```\n\n\
+             Each line comes with the commit that last changed it, its author and author \
+             time. Pass `rev` to blame an older version of the file.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
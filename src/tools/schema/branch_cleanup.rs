@@ -0,0 +1,116 @@
+//! Schema types for the git_branch_cleanup tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_BRANCH_CLEANUP: &str = "git_branch_cleanup";
+
+fn default_older_than_days() -> u32 {
+    30
+}
+
+/// Arguments for `git_branch_cleanup` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitBranchCleanupArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Only branches whose last commit is older than this many days (default: 30)
+    #[serde(default = "default_older_than_days")]
+    pub older_than_days: u32,
+
+    /// Match branches merged into the default branch (default: true)
+    #[serde(default = "super::default_true")]
+    pub merged: bool,
+
+    /// Match branches whose upstream was deleted on the remote (default: true)
+    #[serde(default = "super::default_true")]
+    pub upstream_gone: bool,
+
+    /// Branch to check merges against (default: the remote's HEAD, main or master)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+
+    /// Delete the matched branches; without it only a report is returned
+    #[serde(default)]
+    pub delete: bool,
+
+    /// Also delete the remote branch of merged branches
+    #[serde(default)]
+    pub delete_remote: bool,
+}
+
+/// A branch matched by the cleanup
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitStaleBranch {
+    pub name: String,
+    /// Tip commit hash
+    pub tip: String,
+    /// Time of the last commit in RFC3339 format
+    pub last_commit: String,
+    /// "merged" or "upstream-gone"
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    pub deleted: bool,
+    pub remote_deleted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Output from `git_branch_cleanup` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitBranchCleanupOutput {
+    pub success: bool,
+    /// True when nothing was deleted because `delete` was not set
+    pub dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    pub branches: Vec<GitStaleBranch>,
+}
+
+impl ToolArgs for GitBranchCleanupArgs {
+    type Output = GitBranchCleanupOutput;
+    type Prompts = BranchCleanupPrompts;
+
+    const NAME: &'static str = GIT_BRANCH_CLEANUP;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str =
+        "Find and optionally delete old branches that are merged or whose upstream is gone";
+}
+
+/// Prompt arguments for `git_branch_cleanup` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitBranchCleanupPromptArgs {}
+
+/// Prompt provider for `git_branch_cleanup` tool
+pub struct BranchCleanupPrompts;
+
+impl SealedPromptProvider for BranchCleanupPrompts {}
+
+impl PromptProvider for BranchCleanupPrompts {
+    type PromptArgs = GitBranchCleanupPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Clean up branches that were merged long ago.",
+            "First look at what would go, without deleting anything:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"older_than_days\": 60}\n\
+             ```\n\n\
+             Each branch comes with its reason: \"merged\" into the default branch, or \
+             \"upstream-gone\" when its remote branch was deleted. If the list looks right, \
+             repeat the call with \"delete\": true, and \"delete_remote\": true to remove the \
+             merged branches from the remote as well. The current branch and branches \
+             checked out in other worktrees are never touched.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
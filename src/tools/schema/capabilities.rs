@@ -0,0 +1,74 @@
+//! Schema types for the git_capabilities tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_CAPABILITIES: &str = "git_capabilities";
+
+/// Arguments for `git_capabilities` tool
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct GitCapabilitiesArgs {}
+
+/// Output from `git_capabilities` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCapabilitiesOutput {
+    pub success: bool,
+    pub crate_version: String,
+    pub gix_version: String,
+    pub git_cli_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_cli_version: Option<String>,
+    pub git_lfs_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_lfs_version: Option<String>,
+    pub ssh_available: bool,
+    /// URL schemes that can be cloned and fetched
+    pub transports: Vec<String>,
+    /// Enabled features of this crate
+    pub features: Vec<String>,
+    /// Enabled features of gix
+    pub gix_features: Vec<String>,
+}
+
+impl ToolArgs for GitCapabilitiesArgs {
+    type Output = GitCapabilitiesOutput;
+    type Prompts = CapabilitiesPrompts;
+
+    const NAME: &'static str = GIT_CAPABILITIES;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Report the git capabilities of this environment";
+}
+
+/// Prompt arguments for `git_capabilities` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCapabilitiesPromptArgs {}
+
+/// Prompt provider for `git_capabilities` tool
+pub struct CapabilitiesPrompts;
+
+impl SealedPromptProvider for CapabilitiesPrompts {}
+
+impl PromptProvider for CapabilitiesPrompts {
+    type PromptArgs = GitCapabilitiesPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Can I push from this environment?",
+            "Check the capabilities:\n\n\
+             ```json\n\
+             {}\n\
+             ```\n\n\
+             Push and stash need the git CLI, so look at \"git_cli_available\". The output \
+             also lists supported transports, whether ssh and Git LFS are there, and the \
+             enabled features.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,57 @@
+//! Schema types for the git_checkout tool, extending `kodegen_mcp_schema::git::checkout`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_CHECKOUT};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::GitCheckoutPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_checkout` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitCheckoutArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Target reference (branch, tag, or commit); "-" for the previous branch
+    pub target: String,
+
+    /// Specific file paths to restore from the target reference
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paths: Option<Vec<String>>,
+
+    /// Create new branch before checking out
+    #[serde(default)]
+    pub create: bool,
+
+    /// Force checkout (discard local changes)
+    #[serde(default)]
+    pub force: bool,
+
+    /// Refuse to switch to a branch that is behind its upstream
+    #[serde(default)]
+    pub require_up_to_date: bool,
+
+    /// Stash local changes before switching and reapply them afterwards
+    #[serde(default)]
+    pub autostash: bool,
+}
+
+/// Output from `git_checkout` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCheckoutOutput {
+    pub success: bool,
+    pub target: String,
+    pub created: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paths: Option<Vec<String>>,
+    pub message: String,
+}
+
+impl ToolArgs for GitCheckoutArgs {
+    type Output = GitCheckoutOutput;
+    type Prompts = GitCheckoutPrompts;
+
+    const NAME: &'static str = GIT_CHECKOUT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Switch branches or restore files from a specific revision";
+}
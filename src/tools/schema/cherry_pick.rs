@@ -0,0 +1,86 @@
+//! Schema types for the git_cherry_pick tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_CHERRY_PICK: &str = "git_cherry_pick";
+
+/// Arguments for `git_cherry_pick` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitCherryPickArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Commit to pick (hash, branch, tag or other revision)
+    pub commit: String,
+
+    /// Stage the change without committing it
+    #[serde(default)]
+    pub no_commit: bool,
+
+    /// For a merge commit, the parent (1-based) the change is taken relative to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mainline: Option<u32>,
+
+    /// Append "(cherry picked from commit <id>)" to the message
+    #[serde(default)]
+    pub record_origin: bool,
+}
+
+/// Output from `git_cherry_pick` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCherryPickOutput {
+    /// False if the pick conflicted; nothing was changed then
+    pub success: bool,
+    /// The picked commit
+    pub source: String,
+    /// The new commit, unless `no_commit` was set or the pick conflicted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+    /// Paths the pick conflicted in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+}
+
+impl ToolArgs for GitCherryPickArgs {
+    type Output = GitCherryPickOutput;
+    type Prompts = CherryPickPrompts;
+
+    const NAME: &'static str = GIT_CHERRY_PICK;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Apply the change of an existing commit on top of HEAD";
+}
+
+/// Prompt arguments for `git_cherry_pick` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCherryPickPromptArgs {}
+
+/// Prompt provider for `git_cherry_pick` tool
+pub struct CherryPickPrompts;
+
+impl SealedPromptProvider for CherryPickPrompts {}
+
+impl PromptProvider for CherryPickPrompts {
+    type PromptArgs = GitCherryPickPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Bring the fix from commit 4f2a9c1 onto my current branch.",
+            "Pick it:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"commit\": \"4f2a9c1\", \"record_origin\": true}\n\
+             ```\n\n\
+             The new commit keeps the original author and message. If the change conflicts, \
+             nothing is modified and `conflicts` lists the paths. A merge commit needs \
+             `mainline`, usually 1, to pick its changes relative to the first parent.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,94 @@
+//! Schema types for the git_clone tool, extending `kodegen_mcp_schema::git::clone`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_CLONE};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::ClonePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_clone` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitCloneArgs {
+    /// Git URL to clone from (https:// or git://)
+    pub url: String,
+
+    /// Local path to clone into
+    pub path: String,
+
+    /// Specific branch to checkout (defaults to repository default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// Shallow clone depth (minimum: 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+
+    /// Check the received pack and objects before checking out, removing the
+    /// clone if any are missing or corrupt (default: false)
+    #[serde(default)]
+    pub verify: bool,
+
+    /// Allow cloning into a directory inside another repository (default: false)
+    #[serde(default)]
+    pub nested: bool,
+
+    /// Only check that the clone can go ahead (destination writable and free,
+    /// enough disk space) without cloning (default: false)
+    #[serde(default)]
+    pub preflight_only: bool,
+}
+
+/// Output from `git_clone` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCloneOutput {
+    pub success: bool,
+    pub url: String,
+    pub path: String,
+    pub branch: String,
+    pub shallow: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+    /// Checked-out commit; absent for an empty repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_commit: Option<String>,
+    /// Default branch of the remote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub refs_fetched: usize,
+    /// Objects received
+    #[serde(default)]
+    pub objects: u32,
+    #[serde(default)]
+    pub has_submodules: bool,
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Size of the clone in bytes
+    #[serde(default)]
+    pub size_on_disk: u64,
+    /// Objects read back by verification, when it was asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objects_verified: Option<usize>,
+    /// Whether only the checks ran and nothing was cloned
+    #[serde(default)]
+    pub preflight_only: bool,
+    /// Estimated bytes the clone needs, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_size: Option<u64>,
+    /// Free bytes at the destination, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available_space: Option<u64>,
+    /// Problems that stop the clone, from a preflight-only run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preflight_issues: Vec<String>,
+    pub message: String,
+}
+
+impl ToolArgs for GitCloneArgs {
+    type Output = GitCloneOutput;
+    type Prompts = ClonePrompts;
+
+    const NAME: &'static str = GIT_CLONE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Clone a remote Git repository to a local path";
+}
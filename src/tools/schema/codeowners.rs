@@ -0,0 +1,91 @@
+//! Schema types for the git_codeowners tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_CODEOWNERS: &str = "git_codeowners";
+
+/// Arguments for `git_codeowners` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitCodeownersArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Paths to resolve, relative to the repository root
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+/// Owners of a path
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCodeownersMatch {
+    pub path: String,
+    /// Empty when no rule matches or the rule assigns no owners
+    pub owners: Vec<String>,
+    /// Pattern of the deciding rule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Line of the deciding rule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+/// A CODEOWNERS line GitHub would skip
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCodeownersIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Output from `git_codeowners` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCodeownersOutput {
+    pub success: bool,
+    /// The CODEOWNERS file used; absent when there is none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub paths: Vec<GitCodeownersMatch>,
+    pub issues: Vec<GitCodeownersIssue>,
+}
+
+impl ToolArgs for GitCodeownersArgs {
+    type Output = GitCodeownersOutput;
+    type Prompts = CodeownersPrompts;
+
+    const NAME: &'static str = GIT_CODEOWNERS;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Resolve the CODEOWNERS owners of paths";
+}
+
+/// Prompt arguments for `git_codeowners` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCodeownersPromptArgs {}
+
+/// Prompt provider for `git_codeowners` tool
+pub struct CodeownersPrompts;
+
+impl SealedPromptProvider for CodeownersPrompts {}
+
+impl PromptProvider for CodeownersPrompts {
+    type PromptArgs = GitCodeownersPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Who owns src/billing/invoice.rs?",
+            "Look it up in CODEOWNERS:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"paths\": [\"src/billing/invoice.rs\"]}\n\
+             ```\n\n\
+             Each path comes with its owners and the rule that decided them (the last \
+             matching one). \"issues\" lists lines GitHub would skip.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,66 @@
+//! Schema types for the git_commit tool, extending `kodegen_mcp_schema::git::commit`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_COMMIT};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::CommitPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_commit` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitCommitArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Commit message
+    pub message: String,
+
+    /// Author name (optional, uses git config if not provided)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_name: Option<String>,
+
+    /// Author email (optional, uses git config if not provided)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_email: Option<String>,
+
+    /// Stage all modified tracked files before committing
+    #[serde(default)]
+    pub all: bool,
+
+    /// Author date (RFC 2822, ISO 8601 or "<unix> <offset>")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_date: Option<String>,
+
+    /// Committer date, in the same formats as `author_date`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_date: Option<String>,
+
+    /// Fetch the upstream first and refuse to commit if the branch is behind it
+    #[serde(default)]
+    pub require_up_to_date: bool,
+}
+
+/// Output from `git_commit` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCommitOutput {
+    pub success: bool,
+    pub commit_id: String,
+    pub message: String,
+    pub file_count: usize,
+    pub tree_id: String,
+    pub parent_ids: Vec<String>,
+    /// Branch the commit was made on, absent on a detached HEAD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Whether the commit replaced HEAD instead of extending it
+    pub amended: bool,
+}
+
+impl ToolArgs for GitCommitArgs {
+    type Output = GitCommitOutput;
+    type Prompts = CommitPrompts;
+
+    const NAME: &'static str = GIT_COMMIT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Create a commit with staged changes";
+}
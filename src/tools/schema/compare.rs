@@ -0,0 +1,86 @@
+//! Schema types for the git_compare tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{GitCommitInfo, GitDiffFile};
+
+pub const GIT_COMPARE: &str = "git_compare";
+
+/// Arguments for `git_compare` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitCompareArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Branch merged into
+    pub base: String,
+
+    /// Branch being merged
+    pub head: String,
+}
+
+/// Output from `git_compare` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCompareOutput {
+    pub success: bool,
+    pub base: String,
+    pub head: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_base: Option<String>,
+    /// Commits in head but not in base
+    pub ahead: Vec<GitCommitInfo>,
+    /// Commits in base but not in head
+    pub behind: Vec<GitCommitInfo>,
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub files: Vec<GitDiffFile>,
+    /// "up_to_date", "fast_forward", "clean", "conflicts" or "unrelated"
+    pub mergeability: String,
+    /// Paths that would conflict
+    pub conflicts: Vec<String>,
+}
+
+impl ToolArgs for GitCompareArgs {
+    type Output = GitCompareOutput;
+    type Prompts = ComparePrompts;
+
+    const NAME: &'static str = GIT_COMPARE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Compare a head branch against a base branch";
+}
+
+/// Prompt arguments for `git_compare` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitComparePromptArgs {}
+
+/// Prompt provider for `git_compare` tool
+pub struct ComparePrompts;
+
+impl SealedPromptProvider for ComparePrompts {}
+
+impl PromptProvider for ComparePrompts {
+    type PromptArgs = GitComparePromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "What would merging my feature branch into main bring in?",
+            "Compare the branches:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"base\": \"main\", \"head\": \"feature\"}\n\
+             ```\n\n\
+             You get the commits ahead and behind, the files changed since the merge base, \
+             and \"mergeability\": up_to_date, fast_forward, clean, conflicts (with the \
+             conflicting paths) or unrelated.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
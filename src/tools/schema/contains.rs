@@ -0,0 +1,69 @@
+//! Schema types for the git_contains tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_CONTAINS: &str = "git_contains";
+
+/// Arguments for `git_contains` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitContainsArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Commit to look for (hash, branch or tag)
+    pub commit: String,
+}
+
+/// Output from `git_contains` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitContainsOutput {
+    pub success: bool,
+    pub commit: String,
+    /// Local and remote-tracking branches whose history contains the commit
+    pub branches: Vec<String>,
+    /// Tags whose history contains the commit
+    pub tags: Vec<String>,
+}
+
+impl ToolArgs for GitContainsArgs {
+    type Output = GitContainsOutput;
+    type Prompts = ContainsPrompts;
+
+    const NAME: &'static str = GIT_CONTAINS;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "List the branches and tags that contain a commit";
+}
+
+/// Prompt arguments for `git_contains` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitContainsPromptArgs {}
+
+/// Prompt provider for `git_contains` tool
+pub struct ContainsPrompts;
+
+impl SealedPromptProvider for ContainsPrompts {}
+
+impl PromptProvider for ContainsPrompts {
+    type PromptArgs = GitContainsPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Has the fix in commit 4f2a9c1 shipped yet?",
+            "Ask which branches and tags contain it:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"commit\": \"4f2a9c1\"}\n\
+             ```\n\n\
+             A release tag in \"tags\" means the fix shipped in that release; \"branches\" \
+             includes remote-tracking branches such as origin/release-2.x.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,79 @@
+//! Schema types for the git_discover tool, extending `kodegen_mcp_schema::git::discover`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_DISCOVER};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::DiscoverPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_discover` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitDiscoverArgs {
+    /// Path to start searching from
+    pub path: String,
+
+    /// Directories the search must not go above, e.g. the workspace root
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ceiling_dirs: Vec<String>,
+
+    /// Parent directories to search at most (0 checks only `path` itself)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+
+    /// Also list the repositories below `path` (submodules, vendored
+    /// checkouts, worktrees, committed .git directories) (default: false)
+    #[serde(default)]
+    pub scan_nested: bool,
+
+    /// Directory levels below `path` to scan for nested repositories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nested_max_depth: Option<usize>,
+}
+
+/// A repository found below the searched path
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitNestedRepo {
+    /// Directory of the repository
+    pub path: String,
+    /// Path relative to the searched path
+    pub relative_path: String,
+    /// What it is to the repository containing it: "submodule",
+    /// "embedded_gitlink" (added without .gitmodules), "worktree",
+    /// "independent" (untracked, e.g. vendored), "bare",
+    /// "committed_git_dir" (its .git files are tracked) or "broken"
+    pub kind: String,
+    /// Where its repository files are, absent when broken
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_dir: Option<String>,
+    /// Worktree of the repository containing it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+/// Output from `git_discover` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitDiscoverOutput {
+    pub success: bool,
+    pub searched_from: String,
+    /// Repository found upward; empty if none was and `scan_nested` is set
+    pub repo_root: String,
+    pub message: String,
+    /// Parent directories walked up from `searched_from` to `repo_root`
+    #[serde(default)]
+    pub levels_up: usize,
+    /// The nearest limit that bounded the search, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boundary: Option<String>,
+    /// Repositories below `searched_from`, with `scan_nested`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nested: Vec<GitNestedRepo>,
+}
+
+impl ToolArgs for GitDiscoverArgs {
+    type Output = GitDiscoverOutput;
+    type Prompts = DiscoverPrompts;
+
+    const NAME: &'static str = GIT_DISCOVER;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Discover a Git repository by searching upward from a path";
+}
@@ -0,0 +1,79 @@
+//! Schema types for the git_fetch tool, extending `kodegen_mcp_schema::git::fetch`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_FETCH};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::FetchPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_fetch` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitFetchArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Remote name (defaults to "origin")
+    #[serde(default = "kodegen_mcp_schema::git::fetch::default_remote")]
+    pub remote: String,
+
+    /// Refspecs to fetch (e.g., ["refs/heads/main:refs/remotes/origin/main"]).
+    /// If empty, uses repository's configured refspecs for the remote.
+    ///
+    /// Accepts both single string and array: `refspecs: "main"` or `refspecs: ["main", "develop"]`
+    #[serde(default, deserialize_with = "kodegen_mcp_schema::serde_helpers::string_or_vec")]
+    pub refspecs: Vec<String>,
+
+    /// Prune remote-tracking branches that no longer exist on remote (default: false)
+    #[serde(default)]
+    pub prune: bool,
+
+    /// Check the received pack and objects, undoing the fetch if any are
+    /// missing or corrupt (default: false)
+    #[serde(default)]
+    pub verify: bool,
+}
+
+/// A ref created or moved by a fetch
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitFetchRefUpdate {
+    pub name: String,
+    /// Previous commit, absent for new refs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+    /// "new", "fast-forward", "forced" or the reason of a rejection
+    pub status: String,
+    pub rejected: bool,
+}
+
+/// Output from `git_fetch` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitFetchOutput {
+    pub success: bool,
+    pub remote: String,
+    pub pruned: bool,
+    /// Whether the fetch left all local refs as they were
+    pub up_to_date: bool,
+    pub updated_refs: Vec<GitFetchRefUpdate>,
+    pub new_tags: Vec<String>,
+    /// Remote-tracking refs deleted by pruning
+    pub pruned_refs: Vec<String>,
+    pub objects_received: u32,
+    pub bytes_received: u64,
+    /// Objects read back by verification, when it was asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objects_verified: Option<usize>,
+    /// Problems worked around, e.g. a stale ref pruning couldn't delete
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl ToolArgs for GitFetchArgs {
+    type Output = GitFetchOutput;
+    type Prompts = FetchPrompts;
+
+    const NAME: &'static str = GIT_FETCH;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Download changes from a remote repository without merging";
+}
@@ -0,0 +1,81 @@
+//! Schema types for the git_file_info tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_FILE_INFO: &str = "git_file_info";
+
+/// Arguments for `git_file_info` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitFileInfoArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// File, relative to the repository root
+    pub file: String,
+
+    /// Revision to look at (default: HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+
+    /// Revision to compare the file with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compare_rev: Option<String>,
+}
+
+/// Output from `git_file_info` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitFileInfoOutput {
+    pub success: bool,
+    pub file: String,
+    pub rev: String,
+    /// Whether the file exists at `rev`
+    pub exists: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compare_rev: Option<String>,
+    /// Whether the file differs between `rev` and `compare_rev`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed: Option<bool>,
+}
+
+impl ToolArgs for GitFileInfoArgs {
+    type Output = GitFileInfoOutput;
+    type Prompts = FileInfoPrompts;
+
+    const NAME: &'static str = GIT_FILE_INFO;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Check whether a file exists at a revision and whether it changed between revisions";
+}
+
+/// Prompt arguments for `git_file_info` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitFileInfoPromptArgs {}
+
+/// Prompt provider for `git_file_info` tool
+pub struct FileInfoPrompts;
+
+impl SealedPromptProvider for FileInfoPrompts {}
+
+impl PromptProvider for FileInfoPrompts {
+    type PromptArgs = GitFileInfoPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Did Cargo.toml change since the last release?",
+            "Compare the file between the release and HEAD:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"file\": \"Cargo.toml\", \"rev\": \"HEAD\", \"compare_rev\": \"v1.4.0\"}\n\
+             ```\n\n\
+             \"exists\" tells whether the file is at \"rev\", and \"changed\" whether its \
+             content differs between the two revisions.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
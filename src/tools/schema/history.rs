@@ -0,0 +1,83 @@
+//! Schema types for the git_history tool, extending `kodegen_mcp_schema::git::history`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_HISTORY};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::{GitHistoryCommit, HistoryPrompts};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_history` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitHistoryArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// File path to investigate
+    pub file: String,
+
+    /// Maximum number of commits to return (default: 20)
+    #[serde(default = "kodegen_mcp_schema::git::history::default_history_limit")]
+    pub limit: usize,
+
+    /// Optional regex pattern to filter diffs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+
+    /// Start revision (default: HEAD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+
+    /// End revision (for range mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+}
+
+/// Output from `git_history` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitHistoryOutput {
+    pub success: bool,
+    /// File path that was analyzed; see `file_is_utf8`
+    pub file: String,
+    /// Whether `file` is the exact path; if not, the path isn't UTF-8 and its
+    /// other bytes are written as octal escapes like `\351`
+    #[serde(default = "super::default_true")]
+    pub file_is_utf8: bool,
+    /// Mode: "commits" or "range"
+    pub mode: String,
+    /// Total commits examined (commits mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_examined: Option<usize>,
+    /// Whether the walk stopped at the boundary of a shallow clone
+    #[serde(default)]
+    pub reached_shallow_boundary: bool,
+    /// List of commits with diffs (commits mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commits: Option<Vec<GitHistoryCommit>>,
+    /// Problems reading the listed commits, e.g. a time that fell back to the epoch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Start revision (range mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// End revision (range mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    /// Total additions (range mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additions: Option<u32>,
+    /// Total deletions (range mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletions: Option<u32>,
+    /// Cumulative diff (range mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+impl ToolArgs for GitHistoryArgs {
+    type Output = GitHistoryOutput;
+    type Prompts = HistoryPrompts;
+
+    const NAME: &'static str = GIT_HISTORY;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "View commit history and changes for a specific file";
+}
@@ -0,0 +1,93 @@
+//! Schema types for the git_ignore_edit tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_IGNORE_EDIT: &str = "git_ignore_edit";
+
+/// Arguments for `git_ignore_edit` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitIgnoreEditArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Operation: "add", "remove" or "list"
+    pub operation: String,
+
+    /// Patterns to add or remove
+    #[serde(default)]
+    pub patterns: Vec<String>,
+
+    /// Comment written above added patterns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    /// Directory whose .gitignore to edit (default: the repository root)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+}
+
+/// A pattern of a .gitignore file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitIgnorePattern {
+    /// 1-based line number
+    pub line: usize,
+    pub pattern: String,
+    /// Whether the pattern re-includes paths (`!pattern`)
+    pub negated: bool,
+}
+
+/// Output from `git_ignore_edit` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitIgnoreEditOutput {
+    pub success: bool,
+    pub operation: String,
+    /// The .gitignore file, relative to the repository root
+    pub file: String,
+    /// Patterns added or removed
+    pub changed: Vec<String>,
+    /// Patterns of the file (list)
+    pub patterns: Vec<GitIgnorePattern>,
+}
+
+impl ToolArgs for GitIgnoreEditArgs {
+    type Output = GitIgnoreEditOutput;
+    type Prompts = IgnoreEditPrompts;
+
+    const NAME: &'static str = GIT_IGNORE_EDIT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Add, remove and list .gitignore patterns";
+}
+
+/// Prompt arguments for `git_ignore_edit` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitIgnoreEditPromptArgs {}
+
+/// Prompt provider for `git_ignore_edit` tool
+pub struct IgnoreEditPrompts;
+
+impl SealedPromptProvider for IgnoreEditPrompts {}
+
+impl PromptProvider for IgnoreEditPrompts {
+    type PromptArgs = GitIgnoreEditPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "How do I stop committing build output?",
+            "Add its pattern to .gitignore:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"operation\": \"add\", \"patterns\": [\"/target\"], \"comment\": \"Build output\"}\n\
+             ```\n\n\
+             Patterns already present are left alone. Use \"list\" to see the file and \
+             \"directory\" to edit a nested .gitignore.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
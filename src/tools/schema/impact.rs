@@ -0,0 +1,92 @@
+//! Schema types for the git_impact tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_IMPACT: &str = "git_impact";
+
+/// A glob -> target rule
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitImpactRule {
+    /// Glob matched against changed paths, e.g. "src/**"
+    pub pattern: String,
+    /// Target selected by matching paths
+    pub target: String,
+}
+
+/// Arguments for `git_impact` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitImpactArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Commit range "base..tip", or a single commit
+    pub range: String,
+
+    /// Rules mapping paths to targets
+    #[serde(default)]
+    pub rules: Vec<GitImpactRule>,
+}
+
+/// A target affected by the range
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitImpactTarget {
+    pub target: String,
+    /// Changed paths that matched the target's rules
+    pub paths: Vec<String>,
+}
+
+/// Output from `git_impact` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitImpactOutput {
+    pub success: bool,
+    pub range: String,
+    pub changed_paths: Vec<String>,
+    pub targets: Vec<GitImpactTarget>,
+    /// Changed paths no rule matched
+    pub unmatched_paths: Vec<String>,
+}
+
+impl ToolArgs for GitImpactArgs {
+    type Output = GitImpactOutput;
+    type Prompts = ImpactPrompts;
+
+    const NAME: &'static str = GIT_IMPACT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Map the paths changed in a commit range to build or test targets";
+}
+
+/// Prompt arguments for `git_impact` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitImpactPromptArgs {}
+
+/// Prompt provider for `git_impact` tool
+pub struct ImpactPrompts;
+
+impl SealedPromptProvider for ImpactPrompts {}
+
+impl PromptProvider for ImpactPrompts {
+    type PromptArgs = GitImpactPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Which CI jobs does my branch need?",
+            "Map its changes to targets:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"range\": \"main..feature\", \"rules\": [\
+             {\"pattern\": \"api/**\", \"target\": \"api-tests\"}, \
+             {\"pattern\": \"web/**\", \"target\": \"web-tests\"}]}\n\
+             ```\n\n\
+             Each affected target lists the paths that hit it; \"unmatched_paths\" are \
+             changes no rule covers.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
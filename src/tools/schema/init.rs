@@ -0,0 +1,43 @@
+//! Schema types for the git_init tool, extending `kodegen_mcp_schema::git::init`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_INIT};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::InitPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_init` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitInitArgs {
+    /// Path where to initialize the repository
+    pub path: String,
+
+    /// Create a bare repository (no working directory)
+    #[serde(default)]
+    pub bare: bool,
+
+    /// Keep the repository's files in this directory instead of `.git`, and
+    /// make `.git` a file pointing at it (not with `bare`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub separate_git_dir: Option<String>,
+}
+
+/// Output from `git_init` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitInitOutput {
+    pub success: bool,
+    pub path: String,
+    pub bare: bool,
+    /// Directory holding the repository's files
+    pub git_dir: String,
+    pub message: String,
+}
+
+impl ToolArgs for GitInitArgs {
+    type Output = GitInitOutput;
+    type Prompts = InitPrompts;
+
+    const NAME: &'static str = GIT_INIT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Initialize a new Git repository";
+}
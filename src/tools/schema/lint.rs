@@ -0,0 +1,106 @@
+//! Schema types for the git_lint tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_LINT: &str = "git_lint";
+
+/// Arguments for `git_lint` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitLintArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Checks to run (default: all): "missing-license", "missing-readme",
+    /// "large-file", "missing-signoff", "branch-behind", "stale-branch",
+    /// "broken-symlink", "case-conflict", "non-portable-path"
+    #[serde(default)]
+    pub checks: Vec<String>,
+
+    /// Size in bytes above which tracked files are reported (default: 5 MiB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+
+    /// Recent commits checked for a sign-off (default: 20)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signoff_commits: Option<usize>,
+
+    /// Commits a branch may lack from the default branch (default: 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_behind: Option<usize>,
+
+    /// Days without a commit after which a branch is stale (default: 90)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_days: Option<u32>,
+
+    /// Branch the others are compared with (default: detected)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+}
+
+/// A problem found by a check
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitLintFinding {
+    pub check: String,
+    /// "info", "warning" or "error"
+    pub severity: String,
+    /// Path, branch or commit the finding is about; empty for the repository
+    pub subject: String,
+    pub message: String,
+}
+
+/// Output from `git_lint` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitLintOutput {
+    pub success: bool,
+    /// Whether no check found anything
+    pub clean: bool,
+    /// Checks that ran
+    pub checks: Vec<String>,
+    pub findings: Vec<GitLintFinding>,
+}
+
+impl ToolArgs for GitLintArgs {
+    type Output = GitLintOutput;
+    type Prompts = LintPrompts;
+
+    const NAME: &'static str = GIT_LINT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Run repository hygiene checks";
+}
+
+/// Prompt arguments for `git_lint` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitLintPromptArgs {}
+
+/// Prompt provider for `git_lint` tool
+pub struct LintPrompts;
+
+impl SealedPromptProvider for LintPrompts {}
+
+impl PromptProvider for LintPrompts {
+    type PromptArgs = GitLintPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Is this repository in good shape before I publish it?",
+            "Run the hygiene checks:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\"}\n\
+             ```\n\n\
+             Errors (paths differing only by case, or with names Windows rejects) break \
+             checkouts on macOS and Windows; \
+             warnings cover missing LICENSE/README, large files, broken symlinks and \
+             branches far behind. Pass \"checks\" to run only some, e.g. \
+             [\"large-file\"] with \"max_file_size\".",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,69 @@
+//! Schema types for the git_log tool, extending `kodegen_mcp_schema::git::log`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_LOG};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::LogPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::GitCommitInfo;
+
+/// Arguments for `git_log` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitLogArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Maximum number of commits to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<usize>,
+
+    /// Number of commits to skip
+    #[serde(default)]
+    pub skip: usize,
+
+    /// Filter commits by file path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_filter: Option<String>,
+
+    /// List the branches and tags pointing at each commit
+    #[serde(default)]
+    pub decorate: bool,
+
+    /// Include the parent/child edges of the listed commits and an ASCII graph
+    #[serde(default)]
+    pub graph: bool,
+}
+
+/// A parent link between two commits
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCommitEdge {
+    pub child: String,
+    pub parent: String,
+}
+
+/// Output from `git_log` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitLogOutput {
+    pub success: bool,
+    pub commits: Vec<GitCommitInfo>,
+    pub count: usize,
+    /// Whether the walk stopped at the boundary of a shallow clone
+    #[serde(default)]
+    pub reached_shallow_boundary: bool,
+    /// Parent links of the listed commits, when a graph was requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edges: Vec<GitCommitEdge>,
+    /// ASCII rendering like `git log --graph --oneline`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph: Option<String>,
+}
+
+impl ToolArgs for GitLogArgs {
+    type Output = GitLogOutput;
+    type Prompts = LogPrompts;
+
+    const NAME: &'static str = GIT_LOG;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "View commit history with optional filtering";
+}
@@ -0,0 +1,117 @@
+//! Schema types for the git_maintenance tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_MAINTENANCE: &str = "git_maintenance";
+
+/// Arguments for `git_maintenance` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitMaintenanceArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Operation: "locks" (default) to clean up stale lock files, or "eol" to
+    /// find and renormalize files committed with CRLF line endings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+
+    /// Only report what would be done
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Age in seconds before a lock counts as stale (default: 600)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_secs: Option<u64>,
+
+    /// Clear stale locks and retry when add, commit or checkout hit a lock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_recover: Option<bool>,
+}
+
+/// A lock file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitLockFile {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_secs: Option<u64>,
+    /// Process holding the lock open
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_pid: Option<u32>,
+}
+
+/// A tracked file whose staged line endings don't match its settings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitEolIssue {
+    pub path: String,
+    /// Line endings found: "crlf" or "mixed"
+    pub found: String,
+    /// Setting that makes the file text, e.g. "text=auto" or "core.autocrlf=true"
+    pub setting: String,
+}
+
+/// Output from `git_maintenance` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitMaintenanceOutput {
+    pub success: bool,
+    pub operation: String,
+    pub dry_run: bool,
+    /// Stale locks removed, or that would be
+    pub removed: Vec<GitLockFile>,
+    /// Locks left in place
+    pub kept: Vec<GitLockFile>,
+    /// Whether automatic lock recovery is enabled
+    pub auto_recover: bool,
+    /// Files whose staged line endings need normalizing (eol)
+    #[serde(default)]
+    pub eol_issues: Vec<GitEolIssue>,
+    /// Files staged again with normalized line endings (eol)
+    #[serde(default)]
+    pub renormalized: Vec<String>,
+}
+
+impl ToolArgs for GitMaintenanceArgs {
+    type Output = GitMaintenanceOutput;
+    type Prompts = MaintenancePrompts;
+
+    const NAME: &'static str = GIT_MAINTENANCE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Clean up stale lock files left behind by crashed git processes and renormalize line endings";
+}
+
+/// Prompt arguments for `git_maintenance` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitMaintenancePromptArgs {}
+
+/// Prompt provider for `git_maintenance` tool
+pub struct MaintenancePrompts;
+
+impl SealedPromptProvider for MaintenancePrompts {}
+
+impl PromptProvider for MaintenancePrompts {
+    type PromptArgs = GitMaintenancePromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Git says index.lock exists, but nothing is running. What now?",
+            "Clear the stale locks:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\"}\n\
+             ```\n\n\
+             Locks younger than 10 minutes or held by a running process are kept. Add \
+             \"dry_run\": true to only list them, or \"auto_recover\": true to clear stale \
+             locks automatically when add, commit or checkout hit one.\n\n\
+             Files that always show as modified after checkout are often committed with \
+             CRLF line endings. \"operation\": \"eol\" with \"dry_run\": true lists them; \
+             without dry_run they are staged again with LF line endings, ready to commit.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
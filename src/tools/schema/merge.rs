@@ -0,0 +1,66 @@
+//! Schema types for the git_merge tool, extending `kodegen_mcp_schema::git::merge`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_MERGE};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::MergePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_merge` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitMergeArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Branch or commit to merge into current branch
+    pub branch: String,
+
+    /// Allow fast-forward merges when possible (default: true).
+    /// When false, always creates a merge commit even if fast-forward is possible.
+    #[serde(default = "super::default_true")]
+    pub fast_forward: bool,
+
+    /// Automatically create merge commit (default: true).
+    /// When false, performs merge but leaves changes staged for manual commit.
+    #[serde(default = "super::default_true")]
+    pub auto_commit: bool,
+
+    /// Refuse if the target branch is behind its upstream
+    #[serde(default)]
+    pub require_up_to_date: bool,
+
+    /// Fold the branch's changes into a single commit on `base`
+    #[serde(default)]
+    pub squash: bool,
+
+    /// Branch receiving a squash merge (defaults to the current branch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+
+    /// Message of the squash commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Stash local changes before merging and reapply them afterwards
+    #[serde(default)]
+    pub autostash: bool,
+}
+
+/// Output from `git_merge` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitMergeOutput {
+    pub success: bool,
+    pub merge_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+    pub message: String,
+}
+
+impl ToolArgs for GitMergeArgs {
+    type Output = GitMergeOutput;
+    type Prompts = MergePrompts;
+
+    const NAME: &'static str = GIT_MERGE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Merge changes from one branch into another";
+}
@@ -0,0 +1,181 @@
+//! Tool schemas not yet published in `kodegen_mcp_schema`
+//!
+//! New tools, and tools whose arguments or output outgrew the published
+//! schema, take their types from here until the schema crate catches up. Each
+//! module mirrors `kodegen_mcp_schema::git::<tool>`: arguments, output, prompt
+//! arguments and prompts. Extended tools keep every published field and reuse
+//! the published prompts and tool name, so moving them back is a change of
+//! imports.
+
+use kodegen_mcp_schema::git::GitAuthorInfo;
+use rmcp::model::{PromptMessage, PromptMessageContent, PromptMessageRole};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+mod add;
+mod apply;
+mod attributes_edit;
+mod blame;
+mod branch_cleanup;
+mod capabilities;
+mod checkout;
+mod cherry_pick;
+mod clone;
+mod codeowners;
+mod commit;
+mod compare;
+mod contains;
+mod discover;
+mod fetch;
+mod file_info;
+mod history;
+mod ignore_edit;
+mod impact;
+mod init;
+mod lint;
+mod log;
+mod maintenance;
+mod merge;
+mod multi_repo;
+mod pull;
+mod rebase;
+mod release;
+mod remote_add;
+mod remote_list;
+mod replace;
+mod repo_registry;
+mod review_packet;
+mod scaffold;
+mod search_commits;
+mod show_commit;
+mod snapshot;
+mod status;
+mod subtree;
+mod suggest_reviewers;
+mod sync;
+mod worktree_list;
+mod worktree_prune;
+
+pub use add::*;
+pub use apply::*;
+pub use attributes_edit::*;
+pub use blame::*;
+pub use branch_cleanup::*;
+pub use capabilities::*;
+pub use checkout::*;
+pub use cherry_pick::*;
+pub use clone::*;
+pub use codeowners::*;
+pub use commit::*;
+pub use compare::*;
+pub use contains::*;
+pub use discover::*;
+pub use fetch::*;
+pub use file_info::*;
+pub use history::*;
+pub use ignore_edit::*;
+pub use impact::*;
+pub use init::*;
+pub use lint::*;
+pub use log::*;
+pub use maintenance::*;
+pub use merge::*;
+pub use multi_repo::*;
+pub use pull::*;
+pub use rebase::*;
+pub use release::*;
+pub use remote_add::*;
+pub use remote_list::*;
+pub use replace::*;
+pub use repo_registry::*;
+pub use review_packet::*;
+pub use scaffold::*;
+pub use search_commits::*;
+pub use show_commit::*;
+pub use snapshot::*;
+pub use status::*;
+pub use subtree::*;
+pub use suggest_reviewers::*;
+pub use sync::*;
+pub use worktree_list::*;
+pub use worktree_prune::*;
+
+/// Commit information, extending the published `GitCommitInfo`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCommitInfo {
+    /// Full commit hash
+    pub id: String,
+    /// Author information
+    pub author: GitAuthorInfo,
+    /// Commit summary (first line of message)
+    pub summary: String,
+    /// Commit timestamp in RFC3339 format
+    pub time: String,
+    /// Branches and tags pointing at the commit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub refs: Vec<String>,
+    /// Problems reading the commit, e.g. a time that fell back to the epoch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Remote repository information, extending the published `GitRemoteInfo`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRemoteInfo {
+    /// Remote name (e.g., "origin")
+    pub name: String,
+    /// Fetch URL
+    pub fetch_url: String,
+    /// Push URL
+    pub push_url: String,
+    /// Configured fetch refspecs
+    #[serde(default)]
+    pub fetch_refspecs: Vec<String>,
+    /// Configured push refspecs
+    #[serde(default)]
+    pub push_refspecs: Vec<String>,
+    /// Whether fetch and push use this remote when none is given
+    #[serde(default)]
+    pub is_default: bool,
+    /// Time of the last fetch (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_fetched: Option<String>,
+    /// Parts of the configuration that couldn't be read
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// A changed file, extending the published `GitDiffFile`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitDiffFile {
+    /// File path; see `path_is_utf8`
+    pub path: String,
+    /// Type of change (Added, Modified, Deleted, Renamed)
+    pub change_type: String,
+    /// Lines added
+    pub additions: u32,
+    /// Lines deleted
+    pub deletions: u32,
+    /// Whether `path` is the exact path; if not, the path isn't UTF-8 and its
+    /// other bytes are written as octal escapes like `\351`
+    #[serde(default = "default_true")]
+    pub path_is_utf8: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A teaching conversation of one question and its answer
+fn example(question: &str, answer: &str) -> Vec<PromptMessage> {
+    vec![
+        PromptMessage {
+            role: PromptMessageRole::User,
+            content: PromptMessageContent::text(question),
+        },
+        PromptMessage {
+            role: PromptMessageRole::Assistant,
+            content: PromptMessageContent::text(answer),
+        },
+    ]
+}
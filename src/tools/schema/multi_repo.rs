@@ -0,0 +1,135 @@
+//! Schema types for the git_multi_repo tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_MULTI_REPO: &str = "git_multi_repo";
+
+/// Arguments for `git_multi_repo` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitMultiRepoArgs {
+    /// Operation: "status", "log", "branches" or "grep"
+    pub operation: String,
+
+    /// Registered repositories to include (default: all)
+    #[serde(default)]
+    pub repos: Vec<String>,
+
+    /// Regex to search tracked files for (grep)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Match case-insensitively (grep)
+    #[serde(default)]
+    pub ignore_case: bool,
+
+    /// Entries per repository (log, grep; default: 10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// A commit listed by "log"
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitMultiRepoCommit {
+    pub id: String,
+    pub summary: String,
+    /// Author name
+    pub author: String,
+    /// Commit time (RFC 3339)
+    pub time: String,
+}
+
+/// A line found by "grep"
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitGrepMatch {
+    pub path: String,
+    /// 1-based line number
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Result for one repository; only the fields of the operation are set
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct GitMultiRepoResult {
+    pub name: String,
+    pub path: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ahead: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub behind: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_clean: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<GitMultiRepoCommit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<GitGrepMatch>,
+    /// Whether "grep" stopped at the limit
+    #[serde(default)]
+    pub truncated: bool,
+    /// Problems worked around, e.g. upstream counts that couldn't be computed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Output from `git_multi_repo` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitMultiRepoOutput {
+    /// Whether the operation succeeded in every repository
+    pub success: bool,
+    pub operation: String,
+    pub results: Vec<GitMultiRepoResult>,
+}
+
+impl ToolArgs for GitMultiRepoArgs {
+    type Output = GitMultiRepoOutput;
+    type Prompts = MultiRepoPrompts;
+
+    const NAME: &'static str = GIT_MULTI_REPO;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Run a read-only operation across registered repositories";
+}
+
+/// Prompt arguments for `git_multi_repo` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitMultiRepoPromptArgs {}
+
+/// Prompt provider for `git_multi_repo` tool
+pub struct MultiRepoPrompts;
+
+impl SealedPromptProvider for MultiRepoPrompts {}
+
+impl PromptProvider for MultiRepoPrompts {
+    type PromptArgs = GitMultiRepoPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Do any of our services have uncommitted changes?",
+            "Check the status of every registered repository:\n\n\
+             ```json\n\
+             {\"operation\": \"status\"}\n\
+             ```\n\n\
+             Each result has the branch, its upstream with ahead/behind counts and whether \
+             the working tree is clean. \"log\", \"branches\" and \"grep\" work the same \
+             way; \"repos\" picks repositories by name.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
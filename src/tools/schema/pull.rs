@@ -0,0 +1,60 @@
+//! Schema types for the git_pull tool, extending `kodegen_mcp_schema::git::pull`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_PULL};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::PullPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_pull` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitPullArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Remote name (defaults to "origin")
+    #[serde(default = "kodegen_mcp_schema::git::pull::default_remote")]
+    pub remote: String,
+
+    /// Allow fast-forward merges (default: true)
+    #[serde(default = "super::default_true")]
+    pub fast_forward: bool,
+
+    /// Automatically create merge commit (default: true)
+    #[serde(default = "super::default_true")]
+    pub auto_commit: bool,
+
+    /// Stash local changes before pulling and reapply them afterwards
+    #[serde(default)]
+    pub autostash: bool,
+}
+
+/// A file left conflicted by a pull
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitPullConflict {
+    pub path: String,
+    /// Path on the incoming side, when it was renamed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub their_path: Option<String>,
+    /// Kind of conflict, e.g. "content" or "modify/delete"
+    pub kind: String,
+}
+
+/// Output from `git_pull` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitPullOutput {
+    pub success: bool,
+    pub remote: String,
+    pub merge_outcome: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<GitPullConflict>,
+}
+
+impl ToolArgs for GitPullArgs {
+    type Output = GitPullOutput;
+    type Prompts = PullPrompts;
+
+    const NAME: &'static str = GIT_PULL;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Fetch and integrate changes from a remote repository";
+}
@@ -0,0 +1,103 @@
+//! Schema types for the git_rebase tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_REBASE: &str = "git_rebase";
+
+/// Arguments for `git_rebase` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitRebaseArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Revision to replay the branch onto, e.g. "origin/main"
+    pub onto: String,
+
+    /// Only replay the branch's commits not reachable from this revision
+    /// (defaults to `onto`), like `git rebase --onto <onto> <upstream>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+
+    /// Branch to rebase (defaults to the current branch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// Stash local changes while rebasing the current branch (default: false)
+    #[serde(default)]
+    pub autostash: bool,
+}
+
+/// A commit the rebase rewrote
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRebasedCommit {
+    /// The original commit
+    pub original: String,
+    /// Its replacement on the new base
+    pub rewritten: String,
+}
+
+/// Output from `git_rebase` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRebaseOutput {
+    /// False if the rebase conflicted; the branch was left unchanged then
+    pub success: bool,
+    /// Full name of the rebased branch
+    pub branch: String,
+    /// Branch tip before the rebase
+    pub old_tip: String,
+    /// Branch tip after the rebase, equal to `old_tip` if nothing changed
+    pub new_tip: String,
+    /// Commits rewritten, oldest first
+    pub rewritten: Vec<GitRebasedCommit>,
+    /// Commits dropped because the new base already has their changes, and merge commits
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<String>,
+    /// Paths the rebase conflicted in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+}
+
+impl ToolArgs for GitRebaseArgs {
+    type Output = GitRebaseOutput;
+    type Prompts = RebasePrompts;
+
+    const NAME: &'static str = GIT_REBASE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Replay a branch's commits onto a new base";
+}
+
+/// Prompt arguments for `git_rebase` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRebasePromptArgs {}
+
+/// Prompt provider for `git_rebase` tool
+pub struct RebasePrompts;
+
+impl SealedPromptProvider for RebasePrompts {}
+
+impl PromptProvider for RebasePrompts {
+    type PromptArgs = GitRebasePromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "My feature branch was cut from release-1.2 but should go into main instead.",
+            "Move only the feature's own commits onto main:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"branch\": \"feature\", \"onto\": \"main\", \"upstream\": \"release-1.2\"}\n\
+             ```\n\n\
+             `rewritten` maps each original commit to its replacement. To just catch up with \
+             main, leave out `upstream`. If a commit conflicts, the branch stays as it was and \
+             `conflicts` lists the paths; merging main into the branch with `git_merge` is the \
+             alternative then.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
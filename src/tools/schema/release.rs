@@ -0,0 +1,103 @@
+//! Schema types for the git_release tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::GitCommitInfo;
+
+pub const GIT_RELEASE: &str = "git_release";
+
+/// Arguments for `git_release` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitReleaseArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Release tag name, e.g. "v1.2.0"
+    pub version: String,
+
+    /// Commit to release (defaults to HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    /// Tag message (defaults to "Release <version>")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Sign the tag with the configured key
+    #[serde(default)]
+    pub sign: bool,
+
+    /// Remote checked for the tag and pushed to (defaults to "origin")
+    #[serde(default = "kodegen_mcp_schema::git::pull::default_remote")]
+    pub remote: String,
+
+    /// Push the tag (default: true)
+    #[serde(default = "super::default_true")]
+    pub push: bool,
+}
+
+/// Output from `git_release` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReleaseOutput {
+    pub success: bool,
+    pub version: String,
+    /// Released commit
+    pub target_commit: String,
+    /// Nearest tag on an ancestor of the released commit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_tag: Option<String>,
+    /// Commits since the previous tag, newest first, without merges
+    pub commits: Vec<GitCommitInfo>,
+    /// Markdown changelog of the commits
+    pub changelog: String,
+    /// False when the tag already existed at the released commit
+    pub created: bool,
+    /// False when the remote already had the tag or pushing was off
+    pub pushed: bool,
+}
+
+impl ToolArgs for GitReleaseArgs {
+    type Output = GitReleaseOutput;
+    type Prompts = ReleasePrompts;
+
+    const NAME: &'static str = GIT_RELEASE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str =
+        "Tag a release after checking the tag is free locally and remotely, push it and return a changelog";
+}
+
+/// Prompt arguments for `git_release` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReleasePromptArgs {}
+
+/// Prompt provider for `git_release` tool
+pub struct ReleasePrompts;
+
+impl SealedPromptProvider for ReleasePrompts {}
+
+impl PromptProvider for ReleasePrompts {
+    type PromptArgs = GitReleasePromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Release version 1.2.0 from main.",
+            "With main checked out and clean, tag and push in one call:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"version\": \"v1.2.0\"}\n\
+             ```\n\n\
+             The tool refuses to run with uncommitted changes or when \"v1.2.0\" already \
+             names another commit here or on the remote. If an earlier attempt created the \
+             tag but failed to push, calling it again only pushes. The \"changelog\" lists \
+             the commits since the previous tag and can go straight into release notes.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
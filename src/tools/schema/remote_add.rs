@@ -0,0 +1,68 @@
+//! Schema types for the git_remote_add tool, extending `kodegen_mcp_schema::git::remote_add`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_REMOTE_ADD};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::RemoteAddPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_remote_add` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitRemoteAddArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Remote name (e.g., "origin", "upstream")
+    pub name: String,
+
+    /// Remote URL (https, ssh, git, scp-like `user@host:path`, `file://` or an
+    /// existing local path)
+    pub url: String,
+
+    /// Overwrite the remote if it already exists (default: false)
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Output from `git_remote_add` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRemoteAddOutput {
+    pub success: bool,
+    pub name: String,
+    /// URL as stored, in canonical form
+    pub url: String,
+    /// URL scheme (https, ssh, git, file, ...)
+    #[serde(default)]
+    pub scheme: String,
+    /// User name from the URL, e.g. `git`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Host name, empty for local paths
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub host: String,
+    /// Port, if given in the URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Forge kind of the host: github, gitlab, bitbucket, azure or other
+    #[serde(default)]
+    pub host_kind: String,
+    /// Repository path on the host
+    #[serde(default)]
+    pub repo_path: String,
+    /// Owner (user, organization or nested group path like `group/subgroup`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Repository name, for `owner/repo` paths
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    pub message: String,
+}
+
+impl ToolArgs for GitRemoteAddArgs {
+    type Output = GitRemoteAddOutput;
+    type Prompts = RemoteAddPrompts;
+
+    const NAME: &'static str = GIT_REMOTE_ADD;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Add a new remote repository";
+}
@@ -0,0 +1,33 @@
+//! Schema types for the git_remote_list tool, extending `kodegen_mcp_schema::git::remote_list`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_REMOTE_LIST};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::RemoteListPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::GitRemoteInfo;
+
+/// Arguments for `git_remote_list` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitRemoteListArgs {
+    /// Path to repository
+    pub path: String,
+}
+
+/// Output from `git_remote_list` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRemoteListOutput {
+    pub success: bool,
+    pub count: usize,
+    pub remotes: Vec<GitRemoteInfo>,
+}
+
+impl ToolArgs for GitRemoteListArgs {
+    type Output = GitRemoteListOutput;
+    type Prompts = RemoteListPrompts;
+
+    const NAME: &'static str = GIT_REMOTE_LIST;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "List all remote repository connections";
+}
@@ -0,0 +1,91 @@
+//! Schema types for the git_replace tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_REPLACE: &str = "git_replace";
+
+/// Arguments for `git_replace` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitReplaceArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Operation: "create", "graft", "list" or "delete"
+    pub operation: String,
+
+    /// Object to replace (create, graft, delete)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original: Option<String>,
+
+    /// Object to use instead (create)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+
+    /// New parents of `original` (graft)
+    #[serde(default)]
+    pub parents: Vec<String>,
+
+    /// Overwrite an existing replacement
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// A replacement
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReplaceEntry {
+    pub original: String,
+    pub replacement: String,
+    /// Object kind, e.g. "commit"
+    pub kind: String,
+}
+
+/// Output from `git_replace` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReplaceOutput {
+    pub success: bool,
+    pub operation: String,
+    pub replacements: Vec<GitReplaceEntry>,
+}
+
+impl ToolArgs for GitReplaceArgs {
+    type Output = GitReplaceOutput;
+    type Prompts = ReplacePrompts;
+
+    const NAME: &'static str = GIT_REPLACE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Manage replace refs and history grafts";
+}
+
+/// Prompt arguments for `git_replace` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReplacePromptArgs {}
+
+/// Prompt provider for `git_replace` tool
+pub struct ReplacePrompts;
+
+impl SealedPromptProvider for ReplacePrompts {}
+
+impl PromptProvider for ReplacePrompts {
+    type PromptArgs = GitReplacePromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "How do I connect a shallow import to the full history it was cut from?",
+            "Graft the root of the import onto the commit it continues:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"operation\": \"graft\", \"original\": \"<root of import>\", \"parents\": [\"<last old commit>\"]}\n\
+             ```\n\n\
+             Log and blame then see one history, while no commit is rewritten. \"list\" \
+             shows replacements and \"delete\" undoes one.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
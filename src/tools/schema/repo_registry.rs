@@ -0,0 +1,87 @@
+//! Schema types for the git_repo_registry tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_REPO_REGISTRY: &str = "git_repo_registry";
+
+/// Arguments for `git_repo_registry` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitRepoRegistryArgs {
+    /// Operation: "register", "remove" or "list"
+    pub operation: String,
+
+    /// Logical name of the repository (register, remove)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Path of the repository (register)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// URL cloned into `path` on first use when it doesn't exist yet (register)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// A registered repository
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRegisteredRepo {
+    pub name: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Whether the repository exists on disk (false until cloned)
+    pub exists: bool,
+}
+
+/// Output from `git_repo_registry` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRepoRegistryOutput {
+    pub success: bool,
+    pub operation: String,
+    /// Repositories the operation touched; every registered one for "list"
+    pub repos: Vec<GitRegisteredRepo>,
+}
+
+impl ToolArgs for GitRepoRegistryArgs {
+    type Output = GitRepoRegistryOutput;
+    type Prompts = RepoRegistryPrompts;
+
+    const NAME: &'static str = GIT_REPO_REGISTRY;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Register repositories under logical names";
+}
+
+/// Prompt arguments for `git_repo_registry` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRepoRegistryPromptArgs {}
+
+/// Prompt provider for `git_repo_registry` tool
+pub struct RepoRegistryPrompts;
+
+impl SealedPromptProvider for RepoRegistryPrompts {}
+
+impl PromptProvider for RepoRegistryPrompts {
+    type PromptArgs = GitRepoRegistryPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "How do I refer to a repository by name?",
+            "Register it once:\n\n\
+             ```json\n\
+             {\"operation\": \"register\", \"name\": \"backend\", \"path\": \"/src/backend\"}\n\
+             ```\n\n\
+             Every git tool then accepts \"backend\" as its path. Add a \"url\" to clone \
+             the repository on first use, and use \"list\" or \"remove\" to manage names.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,116 @@
+//! Schema types for the git_review_packet tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::GitAuthorInfo;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{GitDiffFile, GitOwnerCandidate};
+
+pub const GIT_REVIEW_PACKET: &str = "git_review_packet";
+
+/// Arguments for `git_review_packet` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitReviewPacketArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Branch merged into
+    pub base: String,
+
+    /// Branch under review
+    pub head: String,
+}
+
+/// A commit of the branch under review
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReviewCommit {
+    /// Full commit hash
+    pub id: String,
+    pub author: GitAuthorInfo,
+    /// Commit timestamp in RFC3339 format
+    pub time: String,
+    /// Full commit message
+    pub message: String,
+}
+
+/// Something in the change that deserves a closer look
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReviewRisk {
+    /// "large-file", "generated-code" or "lockfile-only"
+    pub kind: String,
+    pub paths: Vec<String>,
+    pub message: String,
+}
+
+/// Output from `git_review_packet` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReviewPacketOutput {
+    pub success: bool,
+    pub base: String,
+    pub head: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_base: Option<String>,
+    /// Commits in head but not in base, oldest first
+    pub commits: Vec<GitReviewCommit>,
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub files: Vec<GitDiffFile>,
+    /// Changed files as an indented tree with line counts
+    pub file_tree: String,
+    /// Unified diff since the merge base, capped in size
+    pub patch: String,
+    /// Files left out of the patch for its size
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub omitted_files: Vec<String>,
+    /// Suggested reviewers, best first
+    pub reviewers: Vec<GitOwnerCandidate>,
+    /// Owners CODEOWNERS assigns to the changed files
+    pub codeowners: Vec<String>,
+    pub risks: Vec<GitReviewRisk>,
+}
+
+impl ToolArgs for GitReviewPacketArgs {
+    type Output = GitReviewPacketOutput;
+    type Prompts = ReviewPacketPrompts;
+
+    const NAME: &'static str = GIT_REVIEW_PACKET;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str =
+        "Gather commits, diff, changed files, reviewers and risks of a branch for review";
+}
+
+/// Prompt arguments for `git_review_packet` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitReviewPacketPromptArgs {}
+
+/// Prompt provider for `git_review_packet` tool
+pub struct ReviewPacketPrompts;
+
+impl SealedPromptProvider for ReviewPacketPrompts {}
+
+impl PromptProvider for ReviewPacketPrompts {
+    type PromptArgs = GitReviewPacketPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Review the feature branch before it is merged into main.",
+            "Get everything for the review in one call:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"base\": \"main\", \"head\": \"feature\"}\n\
+             ```\n\n\
+             The packet has the branch's commits with full messages, the diff since the merge \
+             base (files that don't fit the size cap are listed in \"omitted_files\"), a tree \
+             of the changed files, suggested reviewers, and risks such as large files, \
+             generated code or lockfile-only changes.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,103 @@
+//! Schema types for the git_scaffold tool
+
+use std::collections::BTreeMap;
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_SCAFFOLD: &str = "git_scaffold";
+
+/// Arguments for `git_scaffold` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitScaffoldArgs {
+    /// Template repository (local path or URL)
+    pub template: String,
+
+    /// Destination of the new repository; must not exist or be empty
+    pub path: String,
+
+    /// Branch, tag or commit of the template to use (default: its HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+
+    /// Message of the initial commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Values for `{{name}}` placeholders in file contents and paths
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+
+    /// Extra branches to create at the initial commit
+    #[serde(default)]
+    pub branches: Vec<String>,
+
+    /// URL added as `origin`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+
+    /// Push every branch to `origin` (requires `remote`)
+    #[serde(default)]
+    pub push: bool,
+}
+
+/// Output from `git_scaffold` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitScaffoldOutput {
+    pub success: bool,
+    pub path: String,
+    /// The initial commit
+    pub commit: String,
+    /// Files written from the template
+    pub files: usize,
+    /// Files whose content or path had placeholders replaced
+    pub rewritten_files: usize,
+    /// Placeholders without a value, left as they were
+    pub unresolved: Vec<String>,
+    /// All branches, the checked-out one first
+    pub branches: Vec<String>,
+    pub pushed: bool,
+}
+
+impl ToolArgs for GitScaffoldArgs {
+    type Output = GitScaffoldOutput;
+    type Prompts = ScaffoldPrompts;
+
+    const NAME: &'static str = GIT_SCAFFOLD;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Create a new repository from a template repository";
+}
+
+/// Prompt arguments for `git_scaffold` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitScaffoldPromptArgs {}
+
+/// Prompt provider for `git_scaffold` tool
+pub struct ScaffoldPrompts;
+
+impl SealedPromptProvider for ScaffoldPrompts {}
+
+impl PromptProvider for ScaffoldPrompts {
+    type PromptArgs = GitScaffoldPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "How do I start a new service from our template repository?",
+            "Scaffold it and fill in the placeholders:\n\n\
+             ```json\n\
+             {\"template\": \"https://github.com/acme/service-template\", \"path\": \"/src/billing\", \
+             \"variables\": {\"name\": \"billing\"}, \"branches\": [\"develop\"]}\n\
+             ```\n\n\
+             The result is a single initial commit without the template's history. \
+             Placeholders without a value are listed in \"unresolved\".",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,119 @@
+//! Schema types for the git_search_commits tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_SEARCH_COMMITS: &str = "git_search_commits";
+
+fn default_search_limit() -> usize {
+    20
+}
+
+/// Arguments for `git_search_commits` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitSearchCommitsArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Regex matched against commit messages (case-insensitive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Substring of the author name or email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Only commits at or after this date (RFC 3339 or YYYY-MM-DD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+
+    /// Only commits at or before this date (RFC 3339 or YYYY-MM-DD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+
+    /// String whose number of occurrences the commit changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pickaxe: Option<String>,
+
+    /// Only commits touching this path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+
+    /// Revision to search from (default: HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+
+    /// Maximum number of commits to return
+    #[serde(default = "default_search_limit")]
+    pub limit: usize,
+}
+
+/// A matching commit
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSearchCommit {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// Commit time (RFC 3339)
+    pub time: String,
+    /// Match strength; higher ranks first
+    pub score: u32,
+    /// Paths where the pickaxe string's count changed
+    pub pickaxe_paths: Vec<String>,
+}
+
+/// Output from `git_search_commits` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSearchCommitsOutput {
+    pub success: bool,
+    /// Commits examined
+    pub total_examined: usize,
+    /// Whether the scan stopped before reaching the root commits
+    pub truncated: bool,
+    pub count: usize,
+    pub commits: Vec<GitSearchCommit>,
+}
+
+impl ToolArgs for GitSearchCommitsArgs {
+    type Output = GitSearchCommitsOutput;
+    type Prompts = SearchCommitsPrompts;
+
+    const NAME: &'static str = GIT_SEARCH_COMMITS;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Find commits by message, author, date, path or content";
+}
+
+/// Prompt arguments for `git_search_commits` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSearchCommitsPromptArgs {}
+
+/// Prompt provider for `git_search_commits` tool
+pub struct SearchCommitsPrompts;
+
+impl SealedPromptProvider for SearchCommitsPrompts {}
+
+impl PromptProvider for SearchCommitsPrompts {
+    type PromptArgs = GitSearchCommitsPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Which commit removed the retry_limit setting?",
+            "Search for commits that added or removed the string:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"pickaxe\": \"retry_limit\"}\n\
+             ```\n\n\
+             Each hit lists the paths where the string's count changed. Combine with \
+             \"message\", \"author\", \"since\", \"until\" or \"file\" to narrow the search.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,92 @@
+//! Schema types for the git_show_commit tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::GitAuthorInfo;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::GitDiffFile;
+
+pub const GIT_SHOW_COMMIT: &str = "git_show_commit";
+
+/// Arguments for `git_show_commit` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitShowCommitArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Commit to show (default: HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// A `Token: value` trailer of a commit message
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCommitTrailer {
+    pub token: String,
+    pub value: String,
+}
+
+/// Output from `git_show_commit` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitShowCommitOutput {
+    pub success: bool,
+    pub id: String,
+    pub short_id: String,
+    /// First line of the message
+    pub title: String,
+    /// Message after the title, without trailers
+    pub body: String,
+    pub trailers: Vec<GitCommitTrailer>,
+    pub author: GitAuthorInfo,
+    pub committer: GitAuthorInfo,
+    pub parents: Vec<String>,
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub files: Vec<GitDiffFile>,
+    /// Branches and tags pointing at the commit
+    pub refs: Vec<String>,
+}
+
+impl ToolArgs for GitShowCommitArgs {
+    type Output = GitShowCommitOutput;
+    type Prompts = ShowCommitPrompts;
+
+    const NAME: &'static str = GIT_SHOW_COMMIT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Show the message, metadata, diffstat and refs of a commit";
+}
+
+/// Prompt arguments for `git_show_commit` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitShowCommitPromptArgs {}
+
+/// Prompt provider for `git_show_commit` tool
+pub struct ShowCommitPrompts;
+
+impl SealedPromptProvider for ShowCommitPrompts {}
+
+impl PromptProvider for ShowCommitPrompts {
+    type PromptArgs = GitShowCommitPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "What did the last commit change?",
+            "Show it:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\"}\n\
+             ```\n\n\
+             The output has the title, body and trailers of the message, author and \
+             committer, parents, per-file line counts and the refs pointing at the commit. \
+             Pass \"commit\" to show another one.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
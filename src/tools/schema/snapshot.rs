@@ -0,0 +1,98 @@
+//! Schema types for the git_snapshot tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_SNAPSHOT: &str = "git_snapshot";
+
+/// Arguments for `git_snapshot` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitSnapshotArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Operation: "create", "restore", "list" or "drop"
+    pub operation: String,
+
+    /// Snapshot id (restore, drop)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Description of the snapshot (create)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// A snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSnapshotEntry {
+    pub id: String,
+    /// Commit recording the snapshot
+    pub commit: String,
+    /// HEAD when the snapshot was taken
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    pub message: String,
+    /// Creation time (RFC 3339)
+    pub time: String,
+}
+
+/// Output from `git_snapshot` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSnapshotOutput {
+    pub success: bool,
+    pub operation: String,
+    pub snapshots: Vec<GitSnapshotEntry>,
+    /// Files written (restore)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_written: Option<u32>,
+    /// Files removed (restore)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_removed: Option<u32>,
+    /// Whether HEAD is still where it was when the snapshot was taken (restore)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head_matches: Option<bool>,
+}
+
+impl ToolArgs for GitSnapshotArgs {
+    type Output = GitSnapshotOutput;
+    type Prompts = SnapshotPrompts;
+
+    const NAME: &'static str = GIT_SNAPSHOT;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Checkpoint and restore uncommitted changes without touching the stash";
+}
+
+/// Prompt arguments for `git_snapshot` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSnapshotPromptArgs {}
+
+/// Prompt provider for `git_snapshot` tool
+pub struct SnapshotPrompts;
+
+impl SealedPromptProvider for SnapshotPrompts {}
+
+impl PromptProvider for SnapshotPrompts {
+    type PromptArgs = GitSnapshotPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "How do I keep a way back before a risky refactoring?",
+            "Take a snapshot first:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"operation\": \"create\", \"message\": \"before refactoring\"}\n\
+             ```\n\n\
+             It records staged, modified and untracked files and returns an id. \
+             \"restore\" with that id puts the index and working tree back; \"list\" and \
+             \"drop\" manage snapshots.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,53 @@
+//! Schema types for the git_status tool, extending `kodegen_mcp_schema::git::status`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_STATUS};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::StatusPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_status` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitStatusArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Cache results between calls and let a configured core.fsmonitor hook limit rescans
+    #[serde(default)]
+    pub fast_status: bool,
+}
+
+/// Output from `git_status` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitStatusOutput {
+    pub success: bool,
+    pub branch: String,
+    pub commit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ahead: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub behind: Option<u32>,
+    pub is_clean: bool,
+    pub is_detached: bool,
+    pub merge_in_progress: bool,
+    /// Commits being merged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merge_heads: Vec<String>,
+    /// Prepared message of the merge in progress
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_message: Option<String>,
+    /// Problems worked around, e.g. an upstream that has not been fetched
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl ToolArgs for GitStatusArgs {
+    type Output = GitStatusOutput;
+    type Prompts = StatusPrompts;
+
+    const NAME: &'static str = GIT_STATUS;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Check working tree status and branch information";
+}
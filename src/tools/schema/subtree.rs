@@ -0,0 +1,92 @@
+//! Schema types for the git_subtree tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_SUBTREE: &str = "git_subtree";
+
+/// Arguments for `git_subtree` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitSubtreeArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Operation: "add", "merge" or "split"
+    pub operation: String,
+
+    /// Directory of the subtree
+    pub prefix: String,
+
+    /// Commit to import or merge (add, merge)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// Merge commit message (add, merge)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// History to split (split; default: HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+
+    /// Branch to point at the split history (split)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
+
+/// Output from `git_subtree` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSubtreeOutput {
+    pub success: bool,
+    pub operation: String,
+    pub prefix: String,
+    /// Merge commit (add, merge) or tip of the split history (split)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commits in the split history (split)
+    pub split_commits: usize,
+}
+
+impl ToolArgs for GitSubtreeArgs {
+    type Output = GitSubtreeOutput;
+    type Prompts = SubtreePrompts;
+
+    const NAME: &'static str = GIT_SUBTREE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Vendor repositories under a prefix and split them out again";
+}
+
+/// Prompt arguments for `git_subtree` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSubtreePromptArgs {}
+
+/// Prompt provider for `git_subtree` tool
+pub struct SubtreePrompts;
+
+impl SealedPromptProvider for SubtreePrompts {}
+
+impl PromptProvider for SubtreePrompts {
+    type PromptArgs = GitSubtreePromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "How do I vendor a library under vendor/lib and keep it updatable?",
+            "Fetch the library, then add it as a subtree:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"operation\": \"add\", \"prefix\": \"vendor/lib\", \"commit\": \"lib/main\"}\n\
+             ```\n\n\
+             Later \"merge\" brings in a newer commit of the library, and \"split\" \
+             extracts the history of vendor/lib to send changes back upstream.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,94 @@
+//! Schema types for the git_suggest_reviewers tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_SUGGEST_REVIEWERS: &str = "git_suggest_reviewers";
+
+/// Arguments for `git_suggest_reviewers` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitSuggestReviewersArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Paths to find owners for, relative to the repository root
+    pub paths: Vec<String>,
+
+    /// Recent commits per path counted as activity (default: 50)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<usize>,
+}
+
+/// A possible owner or reviewer
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitOwnerCandidate {
+    pub name: String,
+    pub email: String,
+    /// Lines at HEAD last changed by the candidate
+    pub blame_lines: usize,
+    /// Commits among the recent ones
+    pub recent_commits: usize,
+    /// Ranking score between 0 and 1
+    pub score: f64,
+    /// Whether CODEOWNERS lists the candidate for the path
+    pub in_codeowners: bool,
+}
+
+/// Candidates for one path
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitPathOwners {
+    pub path: String,
+    pub candidates: Vec<GitOwnerCandidate>,
+    /// Owners CODEOWNERS assigns to the path
+    pub codeowners: Vec<String>,
+}
+
+/// Output from `git_suggest_reviewers` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSuggestReviewersOutput {
+    pub success: bool,
+    pub window: usize,
+    pub paths: Vec<GitPathOwners>,
+}
+
+impl ToolArgs for GitSuggestReviewersArgs {
+    type Output = GitSuggestReviewersOutput;
+    type Prompts = SuggestReviewersPrompts;
+
+    const NAME: &'static str = GIT_SUGGEST_REVIEWERS;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Suggest reviewers for paths from blame, history and CODEOWNERS";
+}
+
+/// Prompt arguments for `git_suggest_reviewers` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSuggestReviewersPromptArgs {}
+
+/// Prompt provider for `git_suggest_reviewers` tool
+pub struct SuggestReviewersPrompts;
+
+impl SealedPromptProvider for SuggestReviewersPrompts {}
+
+impl PromptProvider for SuggestReviewersPrompts {
+    type PromptArgs = GitSuggestReviewersPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Who should review my change to src/auth/session.rs?",
+            "Ask for candidates:\n\n\
+             ```json\n\
+             {\"path\": \"/src/app\", \"paths\": [\"src/auth/session.rs\"]}\n\
+             ```\n\n\
+             Candidates are ranked by the lines they wrote and their recent commits to the \
+             path; people listed in CODEOWNERS come first.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
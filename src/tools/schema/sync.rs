@@ -0,0 +1,97 @@
+//! Schema types for the git_sync tool
+
+use kodegen_config::{CATEGORY_GIT, Category};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::tool::{PromptProvider, SealedPromptProvider};
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const GIT_SYNC: &str = "git_sync";
+
+/// Arguments for `git_sync` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitSyncArgs {
+    /// Git URL of the repository
+    pub url: String,
+
+    /// Local checkout; cloned into if missing or empty
+    pub path: String,
+
+    /// Branch, tag or commit to check out (defaults to the current branch, or
+    /// the remote's default branch for a fresh clone)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+
+    /// Remote that must match the URL (defaults to "origin")
+    #[serde(default = "kodegen_mcp_schema::git::pull::default_remote")]
+    pub remote: String,
+
+    /// Discard uncommitted changes and local commits instead of failing
+    #[serde(default)]
+    pub force: bool,
+
+    /// Shallow clone depth, used only when cloning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+}
+
+/// Output from `git_sync` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSyncOutput {
+    pub success: bool,
+    pub url: String,
+    pub path: String,
+    /// One of "cloned", "up-to-date", "fast-forwarded", "switched", "reset"
+    pub action: String,
+    /// Commit checked out before the sync; absent for a fresh clone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_commit: Option<String>,
+    /// Commit checked out now; absent for an empty repository
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head_commit: Option<String>,
+    /// Branch checked out now; absent when HEAD is detached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
+
+impl ToolArgs for GitSyncArgs {
+    type Output = GitSyncOutput;
+    type Prompts = SyncPrompts;
+
+    const NAME: &'static str = GIT_SYNC;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str =
+        "Clone a repository or fetch and move an existing checkout to a branch, tag or commit";
+}
+
+/// Prompt arguments for `git_sync` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSyncPromptArgs {}
+
+/// Prompt provider for `git_sync` tool
+pub struct SyncPrompts;
+
+impl SealedPromptProvider for SyncPrompts {}
+
+impl PromptProvider for SyncPrompts {
+    type PromptArgs = GitSyncPromptArgs;
+
+    fn generate_prompts(_args: &Self::PromptArgs) -> Vec<PromptMessage> {
+        super::example(
+            "Make sure /srv/app is a checkout of our service at the latest main.",
+            "One call works whether or not /srv/app exists yet:\n\n\
+             ```json\n\
+             {\"url\": \"https://github.com/acme/service.git\", \"path\": \"/srv/app\", \"reference\": \"main\"}\n\
+             ```\n\n\
+             The first run clones; later runs fetch and fast-forward, and \"action\" says \
+             which happened. The call fails if /srv/app is a checkout of another repository, \
+             has uncommitted changes or has commits that aren't on origin/main; pass \
+             \"force\": true to throw local work away and match the remote exactly.",
+        )
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,90 @@
+//! Schema types for the git_worktree_list tool, extending `kodegen_mcp_schema::git::worktree_list`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_WORKTREE_LIST};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::WorktreeListPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_worktree_list` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitWorktreeListArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Also report dirty state, ahead/behind counts and in-progress operations
+    #[serde(default)]
+    pub include_status: bool,
+}
+
+/// Worktree information, extending the published `GitWorktreeInfo`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitWorktreeInfo {
+    /// Path to the worktree
+    pub path: String,
+    /// Path to the .git directory
+    pub git_dir: String,
+    /// Whether this is the main worktree
+    pub is_main: bool,
+    /// Whether this is a bare repository
+    pub is_bare: bool,
+    /// HEAD commit hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head_commit: Option<String>,
+    /// Checked-out branch name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head_branch: Option<String>,
+    /// Whether the worktree is locked
+    pub is_locked: bool,
+    /// Lock reason if locked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    /// Whether HEAD is detached
+    pub is_detached: bool,
+    /// Why the HEAD information is missing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Working state, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<GitWorktreeStatus>,
+}
+
+/// Working state of a worktree
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitWorktreeStatus {
+    /// Uncommitted changes; absent if the checkout is inaccessible
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_dirty: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ahead: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub behind: Option<usize>,
+    /// In-progress operation, e.g. "merge" or "rebase"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_progress: Option<String>,
+    /// Why the status is incomplete
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Problems worked around, e.g. upstream counts that couldn't be computed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Output from `git_worktree_list` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitWorktreeListOutput {
+    pub success: bool,
+    pub worktrees: Vec<GitWorktreeInfo>,
+    pub count: usize,
+}
+
+impl ToolArgs for GitWorktreeListArgs {
+    type Output = GitWorktreeListOutput;
+    type Prompts = WorktreeListPrompts;
+
+    const NAME: &'static str = GIT_WORKTREE_LIST;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "List all working trees in a repository";
+}
@@ -0,0 +1,64 @@
+//! Schema types for the git_worktree_prune tool, extending `kodegen_mcp_schema::git::worktree_prune`
+
+use kodegen_config::{CATEGORY_GIT, Category, GIT_WORKTREE_PRUNE};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::git::WorktreePrunePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Arguments for `git_worktree_prune` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitWorktreePruneArgs {
+    /// Path to repository
+    pub path: String,
+
+    /// Report what would be removed without removing anything
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Also remove unlocked, clean worktrees idle for this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub older_than_days: Option<u64>,
+
+    /// Also remove unlocked, clean worktrees whose branch is merged into this revision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged_into: Option<String>,
+}
+
+/// A worktree removed (or, in a dry run, to be removed) by pruning
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitPrunedWorktree {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// "missing", "idle N days" or "branch 'x' merged"
+    pub reason: String,
+}
+
+/// A worktree matching a prune policy but kept
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitSkippedWorktree {
+    pub name: String,
+    pub path: String,
+    pub reason: String,
+}
+
+/// Output from `git_worktree_prune` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitWorktreePruneOutput {
+    pub success: bool,
+    pub pruned_count: usize,
+    pub message: String,
+    pub dry_run: bool,
+    pub pruned: Vec<GitPrunedWorktree>,
+    pub skipped: Vec<GitSkippedWorktree>,
+}
+
+impl ToolArgs for GitWorktreePruneArgs {
+    type Output = GitWorktreePruneOutput;
+    type Prompts = WorktreePrunePrompts;
+
+    const NAME: &'static str = GIT_WORKTREE_PRUNE;
+    const CATEGORY: &'static Category = CATEGORY_GIT;
+    const DESCRIPTION: &'static str = "Remove stale administrative files for deleted working trees";
+}
@@ -0,0 +1,142 @@
+//! Git commit search tool - find the commit that did X
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{
+    GitSearchCommitsArgs, GitSearchCommitsOutput, GitSearchCommit, GIT_SEARCH_COMMITS,
+    SearchCommitsPrompts,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::support;
+
+/// Tool for searching commits by message, author, date and content
+#[derive(Clone)]
+pub struct GitSearchCommitsTool;
+
+impl Tool for GitSearchCommitsTool {
+    type Args = GitSearchCommitsArgs;
+    type Prompts = SearchCommitsPrompts;
+
+    fn name() -> &'static str {
+        GIT_SEARCH_COMMITS
+    }
+
+    fn description() -> &'static str {
+        "Find commits by message regex, author, date range, touched path, or a string \
+         whose occurrences were added or removed (pickaxe, like git log -S). \
+         Criteria are combined and results are ranked by match strength."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let repo = support::open_repo(&args.path).await?;
+
+        // Build query
+        let mut query = crate::CommitQuery::new().limit(args.limit);
+
+        if let Some(ref message) = args.message {
+            query = query
+                .message(message)
+                .map_err(support::tool_error)?;
+        }
+        if let Some(author) = args.author {
+            query = query.author(author);
+        }
+        if let Some(ref since) = args.since {
+            query = query.since(parse_date(since)?);
+        }
+        if let Some(ref until) = args.until {
+            query = query.until(parse_date(until)?);
+        }
+        if let Some(pickaxe) = args.pickaxe {
+            query = query.pickaxe(pickaxe);
+        }
+        if let Some(file) = args.file {
+            query = query.path(file);
+        }
+        if let Some(rev) = args.rev {
+            query = query.rev(rev);
+        }
+
+        let result = crate::operations::search::commits(repo, query)
+            .await
+            .map_err(support::tool_error)?;
+
+        let mut summary = format!(
+            "\x1b[36mCommit Search\x1b[0m\n Found: {} commits\n Examined: {} commits{}\n",
+            result.commits.len(),
+            result.total_examined,
+            if result.truncated { " (scan limit reached)" } else { "" }
+        );
+
+        for c in &result.commits {
+            summary.push_str(&format!(
+                "\n\x1b[33m{}\x1b[0m · {} · {} <{}>\n {}",
+                c.short_id,
+                c.time.format("%Y-%m-%d %H:%M"),
+                c.author.name,
+                c.author.email,
+                c.summary
+            ));
+            if !c.pickaxe_paths.is_empty() {
+                summary.push_str(&format!("\n \x1b[32mpickaxe:\x1b[0m {}", c.pickaxe_paths.join(", ")));
+            }
+        }
+
+        let count = result.commits.len();
+        let output = GitSearchCommitsOutput {
+            success: true,
+            total_examined: result.total_examined,
+            truncated: result.truncated,
+            count,
+            commits: result
+                .commits
+                .into_iter()
+                .map(|c| GitSearchCommit {
+                    id: c.id,
+                    short_id: c.short_id,
+                    summary: c.summary,
+                    author_name: c.author.name,
+                    author_email: c.author.email,
+                    time: c.time.to_rfc3339(),
+                    score: c.score,
+                    pickaxe_paths: c.pickaxe_paths,
+                })
+                .collect(),
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a plain `YYYY-MM-DD` date (midnight UTC)
+fn parse_date(value: &str) -> Result<DateTime<Utc>, McpError> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+        .ok_or_else(|| {
+            McpError::InvalidArguments(format!(
+                "Invalid date '{value}': expected RFC 3339 or YYYY-MM-DD"
+            ))
+        })
+}
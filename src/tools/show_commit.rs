@@ -0,0 +1,119 @@
+//! Git show commit tool - full details of a single commit
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use kodegen_mcp_schema::git::GitAuthorInfo;
+use super::schema::{
+    GitCommitTrailer, GitShowCommitArgs, GitShowCommitOutput, ShowCommitPrompts, GIT_SHOW_COMMIT,
+};
+
+use crate::Signature;
+use super::support;
+
+/// Tool for showing a commit's message, metadata, diffstat and refs
+#[derive(Clone)]
+pub struct GitShowCommitTool;
+
+impl Tool for GitShowCommitTool {
+    type Args = GitShowCommitArgs;
+    type Prompts = ShowCommitPrompts;
+
+    fn name() -> &'static str {
+        GIT_SHOW_COMMIT
+    }
+
+    fn description() -> &'static str {
+        "Show a single commit like git show --stat: author, committer, the full message \
+         with its trailers (Signed-off-by, Co-authored-by, ...), per-file line counts \
+         against the first parent, and the branches and tags pointing at it. \
+         'commit' accepts any revision (hash, branch, tag, HEAD~2)."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let commit = args.commit.unwrap_or_else(|| "HEAD".to_string());
+        let info = support::with_repo(&args.path, |repo| {
+            crate::get_commit_details(repo, &commit)
+        })
+        .await?;
+
+        let refs: Vec<String> = info.decorations.iter().map(ToString::to_string).collect();
+        let stats = &info.diffstat;
+
+        let mut summary = format!("\x1b[33mcommit {}\x1b[0m", info.id);
+        if !refs.is_empty() {
+            summary.push_str(&format!(" ({})", refs.join(", ")));
+        }
+        summary.push_str(&format!(
+            "\n Author: {} <{}> · {}\n {}",
+            info.author.name,
+            info.author.email,
+            info.author.local_time().to_rfc3339(),
+            info.message
+        ));
+        for file in &stats.files {
+            summary.push_str(&format!(
+                "\n  {} \x1b[32m+{}\x1b[0m \x1b[31m-{}\x1b[0m",
+                file.path, file.additions, file.deletions
+            ));
+            if let Some(change) = file.mode_change {
+                summary.push_str(&format!(" \x1b[90mmode {change}\x1b[0m"));
+            }
+            if file.is_symlink {
+                summary.push_str(" \x1b[90msymlink\x1b[0m");
+            }
+        }
+        summary.push_str(&format!(
+            "\n {} files changed, {} insertions(+), {} deletions(-)",
+            stats.total_files_changed, stats.total_additions, stats.total_deletions
+        ));
+
+        let files = stats.files.iter().map(support::diff_file).collect();
+
+        Ok(ToolResponse::new(summary, GitShowCommitOutput {
+            success: true,
+            id: info.id.to_string(),
+            short_id: info.short_id,
+            title: info.message,
+            body: info.body,
+            trailers: info
+                .trailers
+                .into_iter()
+                .map(|trailer| GitCommitTrailer {
+                    token: trailer.token,
+                    value: trailer.value,
+                })
+                .collect(),
+            author: author_info(info.author),
+            committer: author_info(info.committer),
+            parents: info.parent_ids.iter().map(ToString::to_string).collect(),
+            files_changed: stats.total_files_changed as u32,
+            insertions: stats.total_additions as u32,
+            deletions: stats.total_deletions as u32,
+            files,
+            refs,
+        }))
+    }
+}
+
+fn author_info(signature: Signature) -> GitAuthorInfo {
+    GitAuthorInfo {
+        name: signature.name,
+        email: signature.email,
+        time: signature.time.to_rfc3339(),
+    }
+}
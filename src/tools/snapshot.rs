@@ -0,0 +1,148 @@
+//! Git snapshot tool - checkpoint uncommitted changes outside the stash
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{
+    GitSnapshotArgs, GitSnapshotEntry, GitSnapshotOutput, SnapshotPrompts, GIT_SNAPSHOT,
+};
+
+use crate::SnapshotInfo;
+use super::support;
+
+/// Tool for creating and restoring worktree snapshots
+#[derive(Clone)]
+pub struct GitSnapshotTool;
+
+impl Tool for GitSnapshotTool {
+    type Args = GitSnapshotArgs;
+    type Prompts = SnapshotPrompts;
+
+    fn name() -> &'static str {
+        GIT_SNAPSHOT
+    }
+
+    fn description() -> &'static str {
+        "Checkpoint uncommitted work (staged, modified and untracked files) before a risky \
+         sequence, without touching the working tree or the stash. \
+         Operations: 'create' records a snapshot and returns its id, 'restore' puts the \
+         index and working tree back to a snapshot (HEAD is not moved), 'list' shows \
+         snapshots, 'drop' deletes one."
+    }
+
+    fn read_only() -> bool {
+        false // Creates refs; restore rewrites the working tree
+    }
+
+    fn destructive() -> bool {
+        true // Restore discards changes made since the snapshot
+    }
+
+    fn idempotent() -> bool {
+        false // Each create records a new snapshot
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let required_id = || {
+            args.id.clone().ok_or_else(|| {
+                McpError::Other(anyhow::anyhow!(
+                    "Snapshot operation '{}' requires an id",
+                    args.operation
+                ))
+            })
+        };
+
+        match args.operation.as_str() {
+            "create" => {
+                let repo = support::open_repo(&args.path).await?;
+                let snapshot = crate::snapshot_worktree(repo, args.message.as_deref())
+                    .await
+                    .map_err(support::tool_error)?;
+
+                let summary = format!(
+                    "\x1b[36m 📸 Snapshot Created\x1b[0m\n \
+                     Id: {}\n \
+                     ℹ {}",
+                    snapshot.id, snapshot.message
+                );
+                Ok(ToolResponse::new(summary, output("create", vec![entry(snapshot)])))
+            }
+            "restore" => {
+                let id = required_id()?;
+                let repo = support::open_repo(&args.path).await?;
+                let result = crate::restore_snapshot(repo, &id)
+                    .await
+                    .map_err(support::tool_error)?;
+
+                let mut summary = format!(
+                    "\x1b[32m ✓ Snapshot Restored\x1b[0m\n \
+                     Id: {} · {} files written · {} removed",
+                    result.snapshot.id, result.files_written, result.files_removed
+                );
+                if !result.head_matches {
+                    summary.push_str("\n \x1b[33mHEAD moved since this snapshot was taken\x1b[0m");
+                }
+
+                let mut out = output("restore", vec![entry(result.snapshot)]);
+                out.files_written = Some(result.files_written as u32);
+                out.files_removed = Some(result.files_removed as u32);
+                out.head_matches = Some(result.head_matches);
+                Ok(ToolResponse::new(summary, out))
+            }
+            "list" => {
+                let snapshots = support::with_repo(&args.path, |repo| {
+                    crate::list_snapshots(repo)
+                })
+                .await?;
+
+                let mut summary = format!("\x1b[36mSnapshots ({})\x1b[0m", snapshots.len());
+                for snapshot in &snapshots {
+                    summary.push_str(&format!(
+                        "\n {} {} {}",
+                        snapshot.id,
+                        snapshot.time.format("%Y-%m-%d %H:%M"),
+                        snapshot.message
+                    ));
+                }
+                Ok(ToolResponse::new(
+                    summary,
+                    output("list", snapshots.into_iter().map(entry).collect()),
+                ))
+            }
+            "drop" => {
+                let id = required_id()?;
+                let target = id.clone();
+                support::with_repo(&args.path, |repo| crate::drop_snapshot(repo, &target)).await?;
+
+                let summary = format!("\x1b[32m ✓ Snapshot Dropped\x1b[0m\n Id: {id}");
+                Ok(ToolResponse::new(summary, output("drop", Vec::new())))
+            }
+            other => Err(McpError::Other(anyhow::anyhow!(
+                "Invalid snapshot operation: {other}. Use 'create', 'restore', 'list' or 'drop'"
+            ))),
+        }
+    }
+}
+
+fn output(operation: &str, snapshots: Vec<GitSnapshotEntry>) -> GitSnapshotOutput {
+    GitSnapshotOutput {
+        success: true,
+        operation: operation.to_string(),
+        snapshots,
+        files_written: None,
+        files_removed: None,
+        head_matches: None,
+    }
+}
+
+fn entry(snapshot: SnapshotInfo) -> GitSnapshotEntry {
+    GitSnapshotEntry {
+        id: snapshot.id,
+        commit: snapshot.commit_id.to_string(),
+        head: snapshot.head.map(|id| id.to_string()),
+        message: snapshot.message,
+        time: snapshot.time.to_rfc3339(),
+    }
+}
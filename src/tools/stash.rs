@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitStashArgs, GitStashOutput, StashPrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for stashing changes
 #[derive(Clone)]
@@ -34,13 +35,8 @@ impl Tool for GitStashTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {}", e)))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{}", e)))?;
+        let repo = support::open_repo(&args.path).await?;
 
         if args.operation.as_str() == "save" {
             // Save stash
@@ -51,7 +47,7 @@ impl Tool for GitStashTool {
 
             let stash_info = crate::stash_save(repo.clone(), opts)
                 .await
-                .map_err(|e| McpError::Other(anyhow::anyhow!("{}", e)))?;
+                .map_err(support::tool_error)?;
 
             // Terminal summary
             let commit_short = &stash_info.commit_hash[..7.min(stash_info.commit_hash.len())];
@@ -74,7 +70,7 @@ impl Tool for GitStashTool {
             // Pop stash
             crate::stash_pop(repo, None)
                 .await
-                .map_err(|e| McpError::Other(anyhow::anyhow!("{}", e)))?;
+                .map_err(support::tool_error)?;
 
             // Terminal summary
             let summary = "\x1b[32m ✓ Stash Popped\x1b[0m\n\
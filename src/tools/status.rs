@@ -2,8 +2,10 @@
 
 use gix::bstr::ByteSlice;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitStatusArgs, GitStatusOutput, StatusPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::StatusPrompts;
+use super::schema::{GitStatusArgs, GitStatusOutput};
+
+use super::support;
 
 /// Tool for checking repository status
 #[derive(Clone)]
@@ -18,8 +20,11 @@ impl Tool for GitStatusTool {
     }
 
     fn description() -> &'static str {
-        "Show repository status including current branch, upstream tracking, \
-         and working directory state."
+        "Show repository status including current branch, upstream tracking with \
+         ahead/behind counts, working directory state, and any merge in progress \
+         (the commits being merged and the prepared message). Set fast_status when \
+         polling repeatedly: results are cached between calls and a configured \
+         core.fsmonitor hook limits rescans to changed files."
     }
 
     fn read_only() -> bool {
@@ -35,127 +40,32 @@ impl Tool for GitStatusTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
-        // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
-
-        // Get is_clean status - inline to avoid Send issues
-        let repo_for_clean = repo.clone();
-        let is_clean = tokio::task::spawn_blocking(move || {
-            let inner = repo_for_clean.clone_inner();
-            inner.is_dirty().map(|dirty| !dirty)
-        })
-        .await
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to check clean status: {e}")))?;
-
-        // Get branch information - fully inlined
-        let repo_for_branch = repo.clone();
-        let (branch_name, commit_hash, upstream, is_detached) = tokio::task::spawn_blocking(move || {
-            let inner = repo_for_branch.clone_inner();
-
-            let mut head = inner.head().map_err(|e| anyhow::anyhow!("Failed to get HEAD: {e}"))?;
-
-            let is_detached_head = head.referent_name().is_none();
-
-            let branch_name = head
-                .referent_name()
-                .and_then(|name| {
-                    name.shorten()
-                        .to_str()
-                        .ok()
-                        .map(std::string::ToString::to_string)
-                })
-                .unwrap_or_else(|| "detached HEAD".to_string());
-
-            let commit = head
-                .peel_to_commit()
-                .map_err(|e| anyhow::anyhow!("Failed to get commit: {e}"))?;
-
-            let commit_hash = commit.id().to_string();
-
-            // Get upstream information
-            let config = inner.config_snapshot();
-            let upstream = if let Some(branch_ref) = head.referent_name() {
-                let branch_short = branch_ref.shorten();
-                let branch_section = format!("branch.{branch_short}");
-
-                let remote_name = config
-                    .string(format!("{branch_section}.remote"))
-                    .map(|s| s.to_string());
-
-                let merge_ref = config
-                    .string(format!("{branch_section}.merge"))
-                    .map(|s| s.to_string());
-
-                if let (Some(remote), Some(merge)) = (remote_name, merge_ref) {
-                    Some(format!(
-                        "{}/{}",
-                        remote,
-                        merge.trim_start_matches("refs/heads/")
-                    ))
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-
-            Ok::<_, anyhow::Error>((branch_name, commit_hash, upstream, is_detached_head))
-        })
-        .await
-        .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-        .map_err(McpError::Other)?;
-
-        // Calculate ahead/behind counts if upstream exists
-        let (ahead_count, behind_count) = if let Some(ref upstream_ref) = upstream {
-            let repo_for_counts = repo.clone();
-            let upstream_clone = upstream_ref.clone();
-            let commit_hash_clone = commit_hash.clone();
-
-            tokio::task::spawn_blocking(move || {
-                let inner = repo_for_counts.clone_inner();
-
-                // Parse local commit ID using rev_parse
-                let local_commit_id = match inner.rev_parse_single(commit_hash_clone.as_bytes()) {
-                    Ok(obj) => obj.detach(),
-                    Err(_) => return (None, None),
-                };
-
-                // Convert upstream ref string to full reference path
-                let upstream_ref_path = if upstream_clone.starts_with("refs/") {
-                    upstream_clone.clone()
+        let fast_status = args.fast_status;
+        let (is_clean, (branch_name, commit_hash, is_detached), tracking, merge, warnings) =
+            support::with_repo(&args.path, move |repo| async move {
+                let inner = repo.clone_inner();
+                let is_clean = if fast_status {
+                    !crate::operations::fast_status::is_dirty_fast(&inner)?
                 } else {
-                    format!("refs/remotes/{}", upstream_clone)
+                    !inner.is_dirty().map_err(crate::GitError::gix)?
                 };
-
-                // Try to find the upstream reference
-                let upstream_commit_id = match inner.try_find_reference(upstream_ref_path.as_bytes().as_bstr()) {
-                    Ok(Some(mut r)) => match r.peel_to_id() {
-                        Ok(id) => id.detach(),
-                        Err(_) => return (None, None),
-                    },
-                    _ => return (None, None),
-                };
-
-                // If both commits are the same, return (0, 0)
-                if local_commit_id == upstream_commit_id {
-                    return (Some(0), Some(0));
+                let head = head_info(&inner)?;
+                // Upstream tracking, against the remote-tracking branch as last fetched
+                let tracking = crate::upstream_status(repo.clone(), None).await?;
+                let mut warnings = Vec::new();
+                if tracking.is_none() {
+                    warnings.extend(crate::operations::upstream::unfetched_upstream(
+                        &inner, None,
+                    )?);
                 }
-
-                // For simplicity, we'll skip the ahead/behind calculation
-                // as it requires merge-base computation which is complex
-                (None, None)
+                let merge = crate::merge_state(repo).await?;
+                Ok((is_clean, head, tracking, merge, warnings))
             })
-            .await
-            .unwrap_or((None, None))
-        } else {
-            (None, None)
-        };
+            .await?;
+
+        let upstream = tracking.as_ref().map(|status| status.upstream.clone());
+        let ahead_count = tracking.as_ref().map(|status| status.ahead);
+        let behind_count = tracking.as_ref().map(|status| status.behind);
 
         // Terminal summary with ANSI colors and Nerd Font icons
         let mut summary = String::from("\x1b[36mRepository Status\x1b[0m\n");
@@ -185,15 +95,60 @@ impl Tool for GitStatusTool {
         };
         summary.push_str(&format!("  State: {}", state_indicator));
 
-        Ok(ToolResponse::new(summary, GitStatusOutput {
-            success: true,
-            branch: branch_name,
-            commit: commit_hash,
-            upstream,
-            ahead: ahead_count,
-            behind: behind_count,
-            is_clean,
-            is_detached,
-        }))
+        // A merge stopped before committing, e.g. on conflicts
+        let merge_heads: Vec<String> = merge
+            .as_ref()
+            .map(|merge| merge.heads.iter().map(ToString::to_string).collect())
+            .unwrap_or_default();
+        if !merge_heads.is_empty() {
+            let short: Vec<&str> = merge_heads.iter().map(|id| &id[..7.min(id.len())]).collect();
+            summary.push_str(&format!(
+                "\n  \x1b[33mMerging: {} (commit or abort to finish)\x1b[0m",
+                short.join(", ")
+            ));
+        }
+
+        for warning in &warnings {
+            summary.push_str(&format!("\n  \x1b[33m⚠ {warning}\x1b[0m"));
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            GitStatusOutput {
+                success: true,
+                branch: branch_name,
+                commit: commit_hash,
+                upstream,
+                ahead: ahead_count.map(|ahead| ahead as u32),
+                behind: behind_count.map(|behind| behind as u32),
+                is_clean,
+                is_detached,
+                merge_in_progress: merge.is_some(),
+                merge_heads,
+                merge_message: merge.and_then(|merge| merge.message),
+                warnings: warnings.iter().map(ToString::to_string).collect(),
+            },
+        ))
     }
 }
+
+/// Branch name, commit hash and whether `HEAD` is detached
+fn head_info(repo: &gix::Repository) -> crate::GitResult<(String, String, bool)> {
+    let mut head = repo.head().map_err(crate::GitError::gix)?;
+
+    let is_detached_head = head.referent_name().is_none();
+
+    let branch_name = head
+        .referent_name()
+        .and_then(|name| {
+            name.shorten()
+                .to_str()
+                .ok()
+                .map(std::string::ToString::to_string)
+        })
+        .unwrap_or_else(|| "detached HEAD".to_string());
+
+    let commit = head.peel_to_commit().map_err(crate::GitError::gix)?;
+
+    Ok((branch_name, commit.id().to_string(), is_detached_head))
+}
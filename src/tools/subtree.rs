@@ -0,0 +1,124 @@
+//! Git subtree tool - vendor repositories under a prefix and split them out
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{GitSubtreeArgs, GitSubtreeOutput, SubtreePrompts, GIT_SUBTREE};
+
+use super::support;
+
+/// Tool for subtree add, merge and split
+#[derive(Clone)]
+pub struct GitSubtreeTool;
+
+impl Tool for GitSubtreeTool {
+    type Args = GitSubtreeArgs;
+    type Prompts = SubtreePrompts;
+
+    fn name() -> &'static str {
+        GIT_SUBTREE
+    }
+
+    fn description() -> &'static str {
+        "Manage vendored directories (git subtree). Operations: 'add' imports 'commit' \
+         (e.g. a fetched remote branch) under 'prefix' as a merge, 'merge' merges a newer \
+         'commit' of that history into 'prefix', 'split' builds a history containing only \
+         'prefix' from 'range' (default HEAD) and optionally points 'branch' at it. \
+         Add and merge need a clean working tree."
+    }
+
+    fn read_only() -> bool {
+        false // Creates commits and branches
+    }
+
+    fn destructive() -> bool {
+        false // Only adds commits; split leaves existing history alone
+    }
+
+    fn idempotent() -> bool {
+        false // Add and merge create a new commit each time
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let commit = || {
+            args.commit.clone().ok_or_else(|| {
+                McpError::Other(anyhow::anyhow!(
+                    "Subtree operation '{}' requires 'commit'",
+                    args.operation
+                ))
+            })
+        };
+
+        let (summary, commit_id, split_commits) = match args.operation.as_str() {
+            "add" | "merge" => {
+                let source = commit()?;
+                let add = args.operation == "add";
+                let (prefix, rev, message) =
+                    (args.prefix.clone(), source.clone(), args.message.clone());
+                let result = support::with_repo(&args.path, move |repo| async move {
+                    if add {
+                        crate::subtree_add(repo, &prefix, &rev, message.as_deref()).await
+                    } else {
+                        crate::subtree_merge(repo, &prefix, &rev, message.as_deref()).await
+                    }
+                })
+                .await?;
+                let summary = format!(
+                    "\x1b[32m ✓ Subtree {}\x1b[0m {source} → {}/\n Commit: {}",
+                    args.operation, args.prefix, result.commit_id
+                );
+                (summary, Some(result.commit_id.to_string()), 0)
+            }
+            "split" => {
+                let range = args.range.as_deref().unwrap_or("HEAD");
+                let (prefix, split_range, branch) =
+                    (args.prefix.clone(), range.to_string(), args.branch.clone());
+                let split = support::with_repo(&args.path, move |repo| async move {
+                    let split = crate::subtree_split(repo.clone(), &prefix, &split_range).await?;
+                    if let (Some(tip), Some(branch)) = (split.tip, branch) {
+                        let opts = crate::BranchOpts::new(branch)
+                            .start_point(tip.to_string())
+                            .force(true);
+                        crate::branch(repo, opts)
+                            .await
+                            .map_err(|_| crate::GitError::ChannelClosed)??;
+                    }
+                    Ok(split)
+                })
+                .await?;
+                let Some(tip) = split.tip else {
+                    return Err(McpError::Other(anyhow::anyhow!(
+                        "'{}' does not exist at {range}",
+                        args.prefix
+                    )));
+                };
+
+                let mut summary = format!(
+                    "\x1b[32m ✓ Split\x1b[0m {}/ ({} commits)\n Tip: {tip}",
+                    args.prefix,
+                    split.commit_map.len()
+                );
+                if let Some(branch) = &args.branch {
+                    summary.push_str(&format!("\n Branch: {branch}"));
+                }
+                (summary, Some(tip.to_string()), split.commit_map.len())
+            }
+            other => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Invalid subtree operation: {other}. Use 'add', 'merge' or 'split'"
+                )));
+            }
+        };
+
+        Ok(ToolResponse::new(summary, GitSubtreeOutput {
+            success: true,
+            operation: args.operation,
+            prefix: args.prefix,
+            commit: commit_id,
+            branch: args.branch,
+            split_commits,
+        }))
+    }
+}
@@ -0,0 +1,105 @@
+//! Git suggest reviewers tool - ownership inference from blame and history
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{
+    GitOwnerCandidate, GitPathOwners, GitSuggestReviewersArgs, GitSuggestReviewersOutput,
+    SuggestReviewersPrompts, GIT_SUGGEST_REVIEWERS,
+};
+
+use super::support;
+
+/// Default number of recent commits per path counted as activity
+const DEFAULT_WINDOW: usize = 50;
+
+/// Candidates shown per path in the summary
+const SUMMARY_CANDIDATES: usize = 3;
+
+/// Tool for suggesting reviewers for a set of paths
+#[derive(Clone)]
+pub struct GitSuggestReviewersTool;
+
+impl Tool for GitSuggestReviewersTool {
+    type Args = GitSuggestReviewersArgs;
+    type Prompts = SuggestReviewersPrompts;
+
+    fn name() -> &'static str {
+        GIT_SUGGEST_REVIEWERS
+    }
+
+    fn description() -> &'static str {
+        "Suggest reviewers/owners for paths, ranked by blame (who wrote the lines at HEAD) \
+         and recent history (who committed to the path lately, last `window` commits, \
+         default 50). Candidates listed in CODEOWNERS for the path are ranked first."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let repo = support::open_repo(&args.path).await?;
+
+        let window = args.window.unwrap_or(DEFAULT_WINDOW);
+        let owners = crate::suggest_owners(repo, args.paths, window)
+            .await
+            .map_err(support::tool_error)?;
+
+        let mut summary = String::from("\x1b[36mSuggested Reviewers\x1b[0m");
+        for path in &owners {
+            summary.push_str(&format!("\n {}", path.path));
+            if path.candidates.is_empty() {
+                summary.push_str("\n   (no history)");
+            }
+            for candidate in path.candidates.iter().take(SUMMARY_CANDIDATES) {
+                summary.push_str(&format!(
+                    "\n   {} <{}> {:.0}%{}",
+                    candidate.name,
+                    candidate.email,
+                    candidate.score * 100.0,
+                    if candidate.in_codeowners { " \x1b[32m[CODEOWNERS]\x1b[0m" } else { "" }
+                ));
+            }
+            if !path.codeowners.is_empty() {
+                summary.push_str(&format!("\n   CODEOWNERS: {}", path.codeowners.join(" ")));
+            }
+        }
+
+        let paths = owners
+            .into_iter()
+            .map(|path| GitPathOwners {
+                path: path.path,
+                candidates: path
+                    .candidates
+                    .into_iter()
+                    .map(|candidate| GitOwnerCandidate {
+                        name: candidate.name,
+                        email: candidate.email,
+                        blame_lines: candidate.blame_lines,
+                        recent_commits: candidate.recent_commits,
+                        score: candidate.score,
+                        in_codeowners: candidate.in_codeowners,
+                    })
+                    .collect(),
+                codeowners: path.codeowners,
+            })
+            .collect();
+
+        Ok(ToolResponse::new(summary, GitSuggestReviewersOutput {
+            success: true,
+            window,
+            paths,
+        }))
+    }
+}
@@ -0,0 +1,109 @@
+//! Shared plumbing for the git tools.
+//!
+//! Every tool opens a repository, runs operations off the async worker
+//! threads and maps failures into [`McpError`]. The helpers here do that one
+//! way:
+//!
+//! - [`open_repo`] opens the repository at the tool's `path` argument, which
+//!   may also name a repository registered with
+//!   [`register_repo`](crate::register_repo).
+//! - [`with_repo`] opens it and awaits an operation that takes the
+//!   repository by value.
+//! - [`with_repo_blocking`] opens it and runs synchronous gix code on the
+//!   blocking pool.
+//!
+//! # Keeping `execute` `Send`
+//!
+//! [`RepoHandle`] is `Send` but not `Sync`, so a future holding `&RepoHandle`
+//! across an `.await` is not `Send` and cannot be returned from
+//! [`Tool::execute`](kodegen_mcp_schema::Tool::execute). Operations called
+//! from tools therefore take an owned [`RepoHandle`] and do their work on the
+//! blocking pool, with `.clone()` when one tool calls several:
+//!
+//! ```ignore
+//! let result = support::with_repo(&args.path, |repo| crate::blame(repo, opts)).await?;
+//! ```
+//!
+//! A tool awaiting an operation on `&repo` fails to build with "future cannot
+//! be sent between threads safely" (E0277), pointing at that `.await`.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::path::Path;
+
+use kodegen_mcp_schema::McpError;
+use gix::bstr::ByteSlice;
+
+use crate::{GitResult, RepoHandle};
+
+/// Map any error into a tool error, keeping its message.
+pub(crate) fn tool_error(e: impl Display) -> McpError {
+    McpError::Other(anyhow::anyhow!("{e}"))
+}
+
+/// Map a failure to run or join a background task into a tool error.
+pub(crate) fn task_error(e: impl Display) -> McpError {
+    tool_error(format!("Task execution failed: {e}"))
+}
+
+/// Open the repository at `path`, or the registered repository of that name.
+pub(crate) async fn open_repo(path: impl AsRef<Path>) -> Result<RepoHandle, McpError> {
+    let path = crate::resolve_repo_path(path).await.map_err(tool_error)?;
+    crate::open_repo(path)
+        .await
+        .map_err(task_error)?
+        .map_err(tool_error)
+}
+
+/// Open the repository at `path` and run the operation `f` returns on it.
+///
+/// Operations take the repository by value, so their futures are `Send` and
+/// can be awaited from [`Tool::execute`](kodegen_mcp_schema::Tool::execute)
+/// directly. Dropping the returned future (for example when the request is
+/// cancelled) stops the operation at its next `.await`.
+pub(crate) async fn with_repo<T, F, Fut>(path: impl AsRef<Path>, f: F) -> Result<T, McpError>
+where
+    F: FnOnce(RepoHandle) -> Fut,
+    Fut: Future<Output = GitResult<T>>,
+{
+    let repo = open_repo(path).await?;
+    f(repo).await.map_err(tool_error)
+}
+
+/// Open the repository at `path` and run the synchronous `f` on the blocking
+/// pool.
+///
+/// Unlike [`with_repo`], `f` cannot be interrupted and always runs to
+/// completion, even if the tool's future is dropped.
+pub(crate) async fn with_repo_blocking<T, E, F>(
+    path: impl AsRef<Path>,
+    f: F,
+) -> Result<T, McpError>
+where
+    F: FnOnce(&gix::Repository) -> Result<T, E> + Send + 'static,
+    T: Send + 'static,
+    E: Display,
+{
+    let repo = open_repo(path).await?.clone_inner();
+    tokio::task::spawn_blocking(move || f(&repo).map_err(tool_error))
+        .await
+        .map_err(task_error)?
+}
+
+/// Short name of the branch `HEAD` points at, if it is on a branch.
+pub(crate) fn head_branch(repo: &gix::Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    head.referent_name()
+        .and_then(|name| name.shorten().to_str().ok().map(ToString::to_string))
+}
+
+/// A file of a diffstat as tool output, with its path escaped if it isn't UTF-8
+pub(crate) fn diff_file(file: &crate::FileDiffStats) -> super::schema::GitDiffFile {
+    super::schema::GitDiffFile {
+        path: file.path.to_string(),
+        change_type: format!("{:?}", file.change_type),
+        additions: file.additions as u32,
+        deletions: file.deletions as u32,
+        path_is_utf8: file.path.is_utf8(),
+    }
+}
@@ -0,0 +1,82 @@
+//! Git sync tool - clone or update a checkout in one call
+
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use super::schema::{GitSyncArgs, GitSyncOutput, SyncPrompts, GIT_SYNC};
+
+use crate::operations::sync::{SyncAction, SyncOpts, sync_repo};
+
+use super::support;
+
+/// Tool for keeping a checkout in sync with a remote ref
+#[derive(Clone)]
+pub struct GitSyncTool;
+
+impl Tool for GitSyncTool {
+    type Args = GitSyncArgs;
+    type Prompts = SyncPrompts;
+
+    fn name() -> &'static str {
+        GIT_SYNC
+    }
+
+    fn description() -> &'static str {
+        "Make a path a checkout of a repository at a branch, tag or commit: clone it if the \
+         path is missing or empty, otherwise check the remote URL matches, fetch and \
+         fast-forward or switch to the ref. Fails on uncommitted changes or local commits \
+         unless force is set, which discards them. Reports what was done."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true // force discards local changes and commits
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        true // Clones and fetches over the network
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let mut opts = SyncOpts::new()
+            .remote(args.remote.clone())
+            .force(args.force);
+        if let Some(depth) = args.depth {
+            opts = opts.shallow(depth);
+        }
+        let result = sync_repo(&args.url, &args.path, args.reference.as_deref(), opts)
+            .await
+            .map_err(support::tool_error)?;
+
+        let head_commit = result.head.map(|id| id.to_string());
+        let previous_commit = result.previous.map(|id| id.to_string());
+        let short = |id: &Option<String>| id.as_deref().map_or("(empty)", |id| &id[..7]).to_string();
+        let at = result.branch.clone().unwrap_or_else(|| "detached HEAD".to_string());
+        let mut summary = format!(
+            "\x1b[32mSync: {}\x1b[0m ({})\n {} at {}",
+            args.path, result.action, at, short(&head_commit)
+        );
+        if matches!(result.action, SyncAction::FastForwarded | SyncAction::Switched | SyncAction::Reset) {
+            summary.push_str(&format!(" (was {})", short(&previous_commit)));
+        }
+
+        Ok(ToolResponse::new(summary, GitSyncOutput {
+            success: true,
+            url: args.url,
+            path: args.path,
+            action: result.action.as_str().to_string(),
+            previous_commit,
+            head_commit,
+            branch: result.branch,
+        }))
+    }
+}
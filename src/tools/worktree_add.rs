@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitWorktreeAddArgs, GitWorktreeAddOutput, WorktreeAddPrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for adding worktrees
 #[derive(Clone)]
@@ -18,7 +19,9 @@ impl Tool for GitWorktreeAddTool {
 
     fn description() -> &'static str {
         "Create a new worktree linked to the repository. \
-         Allows working on multiple branches simultaneously."
+         Allows working on multiple branches simultaneously. \
+         A remote branch like 'origin/feature' is fetched if needed and checked out \
+         on a local branch 'feature' tracking it."
     }
 
     fn read_only() -> bool {
@@ -34,13 +37,8 @@ impl Tool for GitWorktreeAddTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build worktree add options
         let mut opts = crate::WorktreeAddOpts::new(&args.worktree_path);
@@ -52,8 +50,8 @@ impl Tool for GitWorktreeAddTool {
         // Execute worktree add
         let created_path = crate::worktree_add(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
 
         // Terminal summary with ANSI colors and icons
         let branch_display = args.branch.as_deref().unwrap_or("(detached)");
@@ -1,8 +1,10 @@
 //! Git worktree list tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitWorktreeListArgs, GitWorktreeListOutput, GitWorktreeInfo, WorktreeListPrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::WorktreeListPrompts;
+use super::schema::{GitWorktreeInfo, GitWorktreeListArgs, GitWorktreeListOutput, GitWorktreeStatus};
+
+use super::support;
 
 /// Tool for listing worktrees
 #[derive(Clone)]
@@ -19,7 +21,8 @@ impl Tool for GitWorktreeListTool {
     fn description() -> &'static str {
         "List all worktrees in the repository with detailed status. \
          Returns main worktree and all linked worktrees with their paths, branches, \
-         lock status, and HEAD information."
+         lock status, and HEAD information. Set include_status to also report dirty \
+         state, ahead/behind counts and in-progress operations for each worktree."
     }
 
     fn read_only() -> bool {
@@ -35,19 +38,26 @@ impl Tool for GitWorktreeListTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
+
+        let repo_for_status = repo.clone();
 
         // Execute worktree list
         let worktrees = crate::list_worktrees(repo)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
+
+        // Optional per-worktree status (opens each worktree)
+        let statuses = if args.include_status {
+            crate::worktree_statuses(repo_for_status)
+                .await
+                .map_err(support::task_error)?
+                .map_err(support::tool_error)?
+        } else {
+            Vec::new()
+        };
 
         let worktrees_output: Vec<GitWorktreeInfo> = worktrees
             .iter()
@@ -61,6 +71,19 @@ impl Tool for GitWorktreeListTool {
                 is_locked: wt.is_locked,
                 lock_reason: wt.lock_reason.clone(),
                 is_detached: wt.is_detached,
+                warnings: wt.warnings.iter().map(ToString::to_string).collect(),
+                status: statuses
+                    .iter()
+                    .find(|st| st.path == wt.path)
+                    .map(|st| GitWorktreeStatus {
+                        is_dirty: st.is_dirty,
+                        upstream: st.upstream.clone(),
+                        ahead: st.ahead_count,
+                        behind: st.behind_count,
+                        in_progress: st.in_progress.clone(),
+                        error: st.error.clone(),
+                        warnings: st.warnings.iter().map(ToString::to_string).collect(),
+                    }),
             })
             .collect();
 
@@ -71,12 +94,21 @@ impl Tool for GitWorktreeListTool {
             .map(|wt| wt.path.display().to_string())
             .unwrap_or_else(|| "none".to_string());
 
-        let summary = format!(
+        let mut summary = format!(
             "\x1b[36m Worktrees\x1b[0m\n\
               Total: {} · Main: {}",
             count, main_path
         );
 
+        if args.include_status {
+            let dirty = statuses.iter().filter(|st| st.is_dirty == Some(true)).count();
+            let busy = statuses.iter().filter(|st| st.in_progress.is_some()).count();
+            summary.push_str(&format!(" · Dirty: {dirty} · In progress: {busy}"));
+        }
+        for warning in worktrees.iter().flat_map(|wt| &wt.warnings) {
+            summary.push_str(&format!("\n \x1b[33m⚠ {warning}\x1b[0m"));
+        }
+
         Ok(ToolResponse::new(summary, GitWorktreeListOutput {
             success: true,
             worktrees: worktrees_output,
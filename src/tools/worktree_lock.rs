@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitWorktreeLockArgs, GitWorktreeLockOutput, WorktreeLockPrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for locking worktrees
 #[derive(Clone)]
@@ -34,13 +35,8 @@ impl Tool for GitWorktreeLockTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build worktree lock options
         let mut opts = crate::WorktreeLockOpts::new(&args.worktree_path);
@@ -51,8 +47,8 @@ impl Tool for GitWorktreeLockTool {
         // Execute worktree lock
         crate::worktree_lock(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
 
         // Terminal summary
         let summary = format!(
@@ -1,8 +1,12 @@
 //! Git worktree prune tool
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
-use kodegen_mcp_schema::git::{GitWorktreePruneArgs, GitWorktreePruneOutput, WorktreePrunePrompts};
-use std::path::Path;
+use kodegen_mcp_schema::git::WorktreePrunePrompts;
+use super::schema::{
+    GitPrunedWorktree, GitSkippedWorktree, GitWorktreePruneArgs, GitWorktreePruneOutput,
+};
+
+use super::support;
 
 /// Tool for pruning stale worktrees
 #[derive(Clone)]
@@ -19,7 +23,9 @@ impl Tool for GitWorktreePruneTool {
     fn description() -> &'static str {
         "Remove stale worktree administrative files. \
          Cleans up .git/worktrees/ entries for worktrees whose directories have been manually deleted. \
-         Returns list of pruned worktree names."
+         Optionally also removes unlocked, clean worktrees idle for older_than_days or whose \
+         branch is merged into merged_into. Use dry_run to preview. \
+         Returns a report of pruned and skipped worktrees."
     }
 
     fn read_only() -> bool {
@@ -35,31 +41,78 @@ impl Tool for GitWorktreePruneTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
+
+        // Build prune policies
+        let mut opts = crate::WorktreePruneOpts::new().dry_run(args.dry_run);
+        if let Some(days) = args.older_than_days {
+            opts = opts.older_than_days(days);
+        }
+        if let Some(rev) = args.merged_into {
+            opts = opts.merged_into(rev);
+        }
 
         // Execute worktree prune
-        let pruned = crate::worktree_prune(repo)
+        let report = crate::worktree_prune_with(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
+
+        let pruned: Vec<GitPrunedWorktree> = report
+            .pruned
+            .into_iter()
+            .map(|wt| GitPrunedWorktree {
+                name: wt.name,
+                path: wt.path.map(|p| p.display().to_string()),
+                reason: match wt.reason {
+                    crate::PruneReason::Missing => "missing".to_string(),
+                    crate::PruneReason::Stale { idle_days } => format!("idle {idle_days} days"),
+                    crate::PruneReason::Merged { branch } => format!("branch '{branch}' merged"),
+                },
+            })
+            .collect();
+        let skipped: Vec<GitSkippedWorktree> = report
+            .skipped
+            .into_iter()
+            .map(|wt| GitSkippedWorktree {
+                name: wt.name,
+                path: wt.path.display().to_string(),
+                reason: wt.reason,
+            })
+            .collect();
 
         // Terminal summary
-        let summary = format!(
-            "\x1b[31m Worktrees Pruned\x1b[0m\n\
-              Removed: {} stale worktrees",
+        let verb = if report.dry_run { "Would remove" } else { "Removed" };
+        let mut summary = format!(
+            "\x1b[31m Worktrees Pruned{}\x1b[0m\n\
+              {}: {} worktrees",
+            if report.dry_run { " (dry run)" } else { "" },
+            verb,
             pruned.len()
         );
+        for wt in &pruned {
+            summary.push_str(&format!("\n  - {} ({})", wt.name, wt.reason));
+        }
+        if !skipped.is_empty() {
+            summary.push_str(&format!("\n Skipped: {}", skipped.len()));
+            for wt in &skipped {
+                summary.push_str(&format!("\n  - {} ({})", wt.name, wt.reason));
+            }
+        }
 
         Ok(ToolResponse::new(summary, GitWorktreePruneOutput {
             success: true,
             pruned_count: pruned.len(),
-            message: format!("Pruned {} stale worktree(s)", pruned.len()),
+            message: format!(
+                "{} {} worktree(s), skipped {}",
+                if report.dry_run { "Would prune" } else { "Pruned" },
+                pruned.len(),
+                skipped.len()
+            ),
+            dry_run: report.dry_run,
+            pruned,
+            skipped,
         }))
     }
 }
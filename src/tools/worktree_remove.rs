@@ -2,7 +2,8 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitWorktreeRemoveArgs, GitWorktreeRemoveOutput, WorktreeRemovePrompts};
-use std::path::Path;
+
+use super::support;
 
 /// Tool for removing worktrees
 #[derive(Clone)]
@@ -34,13 +35,8 @@ impl Tool for GitWorktreeRemoveTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Build worktree remove options
         let opts = crate::WorktreeRemoveOpts::new(&args.worktree_path).force(args.force);
@@ -48,8 +44,8 @@ impl Tool for GitWorktreeRemoveTool {
         // Execute worktree remove
         crate::worktree_remove(repo, opts)
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
 
         // Terminal summary
         let force_display = if args.force { "yes" } else { "no" };
@@ -2,7 +2,9 @@
 
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use kodegen_mcp_schema::git::{GitWorktreeUnlockArgs, GitWorktreeUnlockOutput, WorktreeUnlockPrompts};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+
+use super::support;
 
 /// Tool for unlocking worktrees
 #[derive(Clone)]
@@ -34,19 +36,14 @@ impl Tool for GitWorktreeUnlockTool {
     }
 
     async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let path = Path::new(&args.path);
-
         // Open repository
-        let repo = crate::open_repo(path)
-            .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+        let repo = support::open_repo(&args.path).await?;
 
         // Execute worktree unlock
         crate::worktree_unlock(repo, PathBuf::from(&args.worktree_path))
             .await
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Task execution failed: {e}")))?
-            .map_err(|e| McpError::Other(anyhow::anyhow!("{e}")))?;
+            .map_err(support::task_error)?
+            .map_err(support::tool_error)?;
 
         // Terminal summary
         let summary = format!(
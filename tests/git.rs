@@ -1,13 +1,71 @@
 //! Integration tests for git operations.
 
 mod git {
+    mod support;
+
     mod test_add;
+    mod test_ancestry;
+    mod test_apply;
+    mod test_attributes;
+    mod test_auth;
+    mod test_backport;
+    mod test_blame;
     mod test_branch;
+    mod test_capabilities;
     mod test_checkout;
+    mod test_cherry;
+    mod test_cherry_pick;
     mod test_clone;
+    mod test_codeowners;
+    mod test_collisions;
     mod test_commit;
+    mod test_compare;
+    mod test_conflicts;
+    mod test_credentials;
+    mod test_diff;
+    mod test_eol;
+    mod test_errors;
+    mod test_export;
     mod test_fetch;
+    mod test_file_info;
+    mod test_grep;
+    mod test_ignore;
+    mod test_impact;
+    mod test_index;
+    mod test_lint;
     mod test_log;
+    mod test_maintenance;
     mod test_merge;
+    mod test_merge_diff;
+    #[cfg(not(feature = "pure-gix"))]
+    mod test_midx;
+    mod test_nested;
     mod test_open;
+    mod test_ownership;
+    mod test_patch_id;
+    mod test_push;
+    mod test_reachability;
+    mod test_rebase;
+    mod test_release;
+    mod test_remote;
+    mod test_replace;
+    mod test_review;
+    mod test_rewrite;
+    mod test_scaffold;
+    mod test_search;
+    mod test_shallow;
+    mod test_show_commit;
+    mod test_snapshot;
+    #[cfg(not(feature = "pure-gix"))]
+    mod test_sparse;
+    mod test_status;
+    mod test_subtree;
+    mod test_sync;
+    mod test_tag;
+    #[cfg(feature = "testkit")]
+    mod test_testkit;
+    mod test_update_branch;
+    mod test_upstream;
+    mod test_workspace;
+    mod test_worktree;
 }
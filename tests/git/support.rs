@@ -0,0 +1,193 @@
+//! Helpers shared by the git integration tests.
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use kodegen_tools_git::operations::auth::{self, UrlRewrite};
+use kodegen_tools_git::operations::credentials::{self, TokenSource};
+use kodegen_tools_git::{AddOpts, CommitOpts, RepoHandle, Signature, add, commit};
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Initialize a repository at `path` with a local user identity
+///
+/// See [`with_identity`].
+pub(crate) async fn init_test_repo(path: impl AsRef<Path>) -> RepoHandle {
+    let repo = kodegen_tools_git::init_repo(path).await.unwrap().unwrap();
+    with_identity(&repo).await
+}
+
+/// Set `user.name` and `user.email` in `repo`'s own config and reopen it
+///
+/// Commits and reflog entries need a committer; configuring one per
+/// repository keeps tests independent of the machine's global git config.
+pub(crate) async fn with_identity(repo: &RepoHandle) -> RepoHandle {
+    let raw = repo.raw();
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(raw.common_dir().join("config"))
+        .unwrap()
+        .write_all(b"[user]\n\tname = Test\n\temail = test@example.com\n")
+        .unwrap();
+    let path = raw.workdir().unwrap_or_else(|| raw.git_dir()).to_path_buf();
+    kodegen_tools_git::open_repo(path).await.unwrap().unwrap()
+}
+
+/// Write `content` to `path` in `repo`'s worktree and commit it as "update <path>"
+///
+/// Authored like [`commit_files`].
+pub(crate) async fn commit_file(repo: &RepoHandle, path: &str, content: &str) -> gix::ObjectId {
+    commit_files(repo, &[(path, content)], &format!("update {path}")).await
+}
+
+/// Write `files` (path and content) to `repo`'s worktree, creating
+/// directories as needed, and commit them with `message`
+///
+/// Commits are authored by `Author <author@example.com>`, so tests can tell
+/// authors from committers.
+pub(crate) async fn commit_files(
+    repo: &RepoHandle,
+    files: &[(&str, &str)],
+    message: &str,
+) -> gix::ObjectId {
+    let author = Signature::new("Author", "author@example.com");
+    commit_files_as(repo, author, files, message).await
+}
+
+/// [`commit_files`] with another author
+pub(crate) async fn commit_files_as(
+    repo: &RepoHandle,
+    author: Signature,
+    files: &[(&str, &str)],
+    message: &str,
+) -> gix::ObjectId {
+    let workdir = repo.raw().workdir().unwrap().to_path_buf();
+    for (path, content) in files {
+        let file = workdir.join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, content).unwrap();
+    }
+    add(repo.clone(), AddOpts::new(files.iter().map(|(path, _)| *path)))
+        .await
+        .unwrap();
+    commit(repo.clone(), CommitOpts::message(message).author(author))
+        .await
+        .unwrap()
+        .id
+}
+
+/// Write a commit of `files` on top of the tree of the first of `parents`
+/// straight to the object database, leaving worktree and references alone
+///
+/// For histories the worktree can't easily produce, like hand-made merges.
+pub(crate) fn write_commit(
+    repo: &gix::Repository,
+    parents: &[gix::ObjectId],
+    files: &[(&str, &str)],
+    message: &str,
+) -> gix::ObjectId {
+    let changes: Vec<_> = files.iter().map(|(path, content)| (*path, Some(*content))).collect();
+    write_change(repo, parents, &changes, message)
+}
+
+/// [`write_commit`] where a `None` content deletes the path
+pub(crate) fn write_change(
+    repo: &gix::Repository,
+    parents: &[gix::ObjectId],
+    changes: &[(&str, Option<&str>)],
+    message: &str,
+) -> gix::ObjectId {
+    let base_tree = parents.first().map_or_else(
+        || gix::ObjectId::empty_tree(repo.object_hash()),
+        |parent| repo.find_commit(*parent).unwrap().tree_id().unwrap().detach(),
+    );
+    let mut editor = repo.edit_tree(base_tree).unwrap();
+    for (path, content) in changes {
+        match content {
+            Some(content) => {
+                let blob = repo.write_blob(content.as_bytes()).unwrap().detach();
+                editor
+                    .upsert(*path, gix::object::tree::EntryKind::Blob, blob)
+                    .unwrap();
+            }
+            None => {
+                editor.remove(*path).unwrap();
+            }
+        }
+    }
+    let tree = editor.write().unwrap().detach();
+
+    let signature = gix::actor::Signature {
+        name: "Author".into(),
+        email: "author@example.com".into(),
+        time: gix::date::Time::new(1_700_000_000, 0),
+    };
+    let commit = gix::objs::Commit {
+        tree,
+        parents: parents.iter().copied().collect(),
+        author: signature.clone(),
+        committer: signature,
+        encoding: None,
+        message: message.into(),
+        extra_headers: Vec::new(),
+    };
+    repo.write_object(&commit).unwrap().detach()
+}
+
+/// Held by every test that changes process-wide credential or remote settings
+static GLOBALS: Mutex<()> = Mutex::const_new(());
+
+/// Take the settings lock for a test changing settings without a guard below
+pub(crate) async fn lock_globals() -> MutexGuard<'static, ()> {
+    GLOBALS.lock().await
+}
+
+/// Registers a token source until dropped, then restores the previous one
+///
+/// Holds the settings lock for its whole lifetime, so tests that register
+/// sources never observe each other's.
+pub(crate) struct TokenSourceGuard {
+    previous: Option<Arc<dyn TokenSource>>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TokenSourceGuard {
+    pub(crate) async fn set(source: Arc<dyn TokenSource>) -> Self {
+        let lock = GLOBALS.lock().await;
+        let previous = credentials::set_token_source(source);
+        Self { previous, _lock: lock }
+    }
+}
+
+impl Drop for TokenSourceGuard {
+    fn drop(&mut self) {
+        match self.previous.take() {
+            Some(previous) => {
+                credentials::set_token_source(previous);
+            }
+            None => credentials::clear_token_source(),
+        }
+    }
+}
+
+/// Registers URL rewrite rules until dropped, then restores the previous ones
+pub(crate) struct UrlRewriteGuard {
+    previous: Vec<UrlRewrite>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl UrlRewriteGuard {
+    pub(crate) async fn add(rules: impl IntoIterator<Item = UrlRewrite>) -> Self {
+        let lock = GLOBALS.lock().await;
+        let previous = auth::registered_url_rewrites();
+        rules.into_iter().for_each(auth::add_url_rewrite);
+        Self { previous, _lock: lock }
+    }
+}
+
+impl Drop for UrlRewriteGuard {
+    fn drop(&mut self) {
+        auth::clear_url_rewrites();
+        self.previous.drain(..).for_each(auth::add_url_rewrite);
+    }
+}
@@ -1,7 +1,10 @@
 //! Tests for git add operation.
 
+use super::support::{commit_file, commit_files, init_test_repo};
 use kodegen_tools_git::git::add::AddOpts;
+use kodegen_tools_git::{CommitOpts, InitOpts, add, commit, init_repo, init_repo_with};
 use std::path::PathBuf;
+use tempfile::TempDir;
 
 #[test]
 fn test_add_opts_builder() {
@@ -54,3 +57,198 @@ fn test_add_opts_mixed_types() {
     assert_eq!(opts.paths[1], PathBuf::from("dir/file.rs"));
     assert_eq!(opts.paths[2], PathBuf::from("another.md"));
 }
+
+#[test]
+fn test_add_opts_jobs() {
+    assert_eq!(AddOpts::new(["src"]).jobs, 0);
+    assert_eq!(AddOpts::new(["src"]).jobs(1).jobs, 1);
+}
+
+#[tokio::test]
+async fn test_parallel_add_matches_serial_add() {
+    let mut indexes = Vec::new();
+    for jobs in [1, 4] {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+        for i in 0..1_000 {
+            let dir = temp_dir.path().join(format!("src/d{}", i % 10));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(format!("f{i}.txt")), format!("file {i}\n")).unwrap();
+        }
+
+        add(repo.clone(), AddOpts::new(["src"]).jobs(jobs))
+            .await
+            .unwrap();
+
+        let index = repo.raw().open_index().unwrap();
+        let entries: Vec<_> = index
+            .entries()
+            .iter()
+            .map(|entry| (entry.path(&index).to_owned(), entry.id, entry.mode))
+            .collect();
+        assert_eq!(entries.len(), 1_000);
+        assert!(entries.iter().all(|(_, id, _)| repo.raw().has_object(id)));
+        indexes.push(entries);
+    }
+    assert_eq!(indexes[0], indexes[1]);
+}
+
+/// Paths in the index of `repo`, in order
+fn index_paths(repo: &kodegen_tools_git::RepoHandle) -> Vec<String> {
+    let index = repo.raw().open_index().unwrap();
+    index
+        .entries()
+        .iter()
+        .map(|entry| entry.path(&index).to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_add_update_and_all_stage_deletions() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    commit_files(&repo, &[("a.txt", "a\n"), ("b.txt", "b\n")], "initial").await;
+    std::fs::remove_file(temp_dir.path().join("a.txt")).unwrap();
+    std::fs::write(temp_dir.path().join("new.txt"), "new\n").unwrap();
+
+    // Without update or all, paths are required
+    assert!(
+        add(repo.clone(), AddOpts::new(Vec::<String>::new()))
+            .await
+            .is_err()
+    );
+    assert!(
+        add(
+            repo.clone(),
+            AddOpts::new(["."]).all(true).update_only(true)
+        )
+        .await
+        .is_err()
+    );
+
+    add(
+        repo.clone(),
+        AddOpts::new(Vec::<String>::new()).update_only(true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(index_paths(&repo), ["b.txt"]);
+
+    add(repo.clone(), AddOpts::new(Vec::<String>::new()).all(true))
+        .await
+        .unwrap();
+    assert_eq!(index_paths(&repo), ["b.txt", "new.txt"]);
+}
+
+#[tokio::test]
+async fn test_add_intent_to_add() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_files(&repo, &[("a.txt", "a\n")], "initial").await;
+    std::fs::write(temp_dir.path().join("a.txt"), "changed\n").unwrap();
+    std::fs::write(temp_dir.path().join("new.txt"), "new\n").unwrap();
+
+    add(
+        repo.clone(),
+        AddOpts::new(["a.txt", "new.txt"]).intent_to_add(true),
+    )
+    .await
+    .unwrap();
+
+    let index = repo.raw().open_index().unwrap();
+    let tracked = index.entry_by_path("a.txt".into()).unwrap();
+    assert!(
+        !tracked
+            .flags
+            .contains(gix::index::entry::Flags::INTENT_TO_ADD)
+    );
+    let intended = index.entry_by_path("new.txt".into()).unwrap();
+    assert!(
+        intended
+            .flags
+            .contains(gix::index::entry::Flags::INTENT_TO_ADD)
+    );
+    assert_eq!(
+        intended.id,
+        gix::ObjectId::empty_blob(gix::hash::Kind::Sha1)
+    );
+
+    // Intended files stay out of commits
+    let id = commit(repo.clone(), CommitOpts::message("second"))
+        .await
+        .unwrap()
+        .id;
+    let tree = repo.raw().find_commit(id).unwrap().tree().unwrap();
+    assert!(tree.find_entry("a.txt").is_some());
+    assert!(tree.find_entry("new.txt").is_none());
+}
+
+#[tokio::test]
+async fn test_add_glob_is_relative_to_repo_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    for path in ["src/lib.rs", "src/nested/mod.rs", "src/notes.md", "lib.rs"] {
+        let file = temp_dir.path().join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, "x\n").unwrap();
+    }
+
+    add(repo.clone(), AddOpts::new(["./src/*.rs"]))
+        .await
+        .unwrap();
+    assert_eq!(index_paths(&repo), ["src/lib.rs", "src/nested/mod.rs"]);
+}
+
+#[tokio::test]
+async fn test_add_reports_ignored_and_missing_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    // Tracked before the ignore rule exists, as add refuses ignored paths
+    commit_file(&repo, "kept.log", "tracked\n").await;
+    commit_file(&repo, ".gitignore", "*.log\n").await;
+    for (path, content) in [
+        ("kept.log", "changed\n"),
+        ("debug.log", "noise\n"),
+        ("a.txt", "a\n"),
+    ] {
+        std::fs::write(temp_dir.path().join(path), content).unwrap();
+    }
+
+    // Tracked files are staged even if they match an ignore rule
+    let result = add(repo.clone(), AddOpts::new(["."])).await.unwrap();
+    assert_eq!(result.added, [".gitignore", "a.txt", "kept.log"]);
+    assert_eq!(result.ignored, ["debug.log"]);
+    assert!(result.force_added.is_empty());
+
+    let result = add(
+        repo.clone(),
+        AddOpts::new(["debug.log", "gone.txt"]).force(true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.added, ["debug.log"]);
+    assert_eq!(result.force_added, ["debug.log"]);
+    assert_eq!(result.missing, [PathBuf::from("gone.txt")]);
+
+    // Nothing matched at all is still an error
+    assert!(add(repo.clone(), AddOpts::new(["gone.txt"])).await.is_err());
+}
+
+#[tokio::test]
+async fn test_add_skips_gitfile() {
+    let temp_dir = TempDir::new().unwrap();
+    let work = temp_dir.path().join("work");
+    let repo = init_repo_with(
+        &work,
+        InitOpts::new().separate_git_dir(temp_dir.path().join("store")),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    std::fs::write(work.join("a.txt"), "a\n").unwrap();
+
+    // The worktree's `.git` is a file here, and never content
+    let result = add(repo.clone(), AddOpts::new(["."])).await.unwrap();
+    assert_eq!(result.added, ["a.txt"]);
+    assert_eq!(index_paths(&repo), ["a.txt"]);
+}
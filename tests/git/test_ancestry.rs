@@ -0,0 +1,111 @@
+//! Tests for ancestry path and children queries
+
+use kodegen_tools_git::{
+    RepoHandle, ancestry_path, branches_containing, children_of, tags_containing,
+};
+
+use super::support::{init_test_repo, write_commit};
+
+/// Point `branch` at `id`
+fn set_branch(repo: &gix::Repository, branch: &str, id: gix::ObjectId) {
+    repo.reference(
+        format!("refs/heads/{branch}").as_str(),
+        id,
+        gix::refs::transaction::PreviousValue::Any,
+        "test",
+    )
+    .unwrap();
+}
+
+/// `base` with an unrelated commit and a fix branch merged on top:
+///
+/// ```text
+/// base ─ other ─ merge   (main)
+///    └── fix ───┘        (fix)
+/// ```
+async fn merged_fix() -> (tempfile::TempDir, RepoHandle, [gix::ObjectId; 4]) {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+
+    let ids = {
+        let raw = repo.raw();
+        let base = write_commit(raw, &[], &[], "base");
+        let fix = write_commit(raw, &[base], &[], "fix");
+        let other = write_commit(raw, &[base], &[], "other");
+        let merge = write_commit(raw, &[other, fix], &[], "merge fix");
+        set_branch(raw, "fix", fix);
+        set_branch(raw, "main", merge);
+        [base, fix, other, merge]
+    };
+
+    (temp_dir, repo, ids)
+}
+
+#[tokio::test]
+async fn test_ancestry_path_follows_descendants() {
+    let (_temp_dir, repo, [base, fix, other, merge]) = merged_fix().await;
+
+    // Only the merge brought the fix into main
+    let path = ancestry_path(repo.clone(), &fix.to_string(), "main").await.unwrap();
+    let ids: Vec<_> = path.iter().map(|c| c.id).collect();
+    assert_eq!(ids, [merge]);
+    assert_eq!(path[0].summary, "merge fix");
+
+    let mut ids: Vec<_> = ancestry_path(repo.clone(), &base.to_string(), &merge.to_string())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    ids.sort();
+    let mut expected = vec![fix, other, merge];
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    // `other` is not an ancestor of the fix branch
+    assert!(
+        ancestry_path(repo.clone(), &other.to_string(), "fix")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_children_of() {
+    let (_temp_dir, repo, [base, fix, other, merge]) = merged_fix().await;
+
+    let mut children = children_of(repo.clone(), &base.to_string()).await.unwrap();
+    children.sort();
+    let mut expected = vec![fix, other];
+    expected.sort();
+    assert_eq!(children, expected);
+
+    assert_eq!(children_of(repo.clone(), "fix").await.unwrap(), [merge]);
+    assert!(children_of(repo.clone(), "main").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_branches_and_tags_containing() {
+    let (_temp_dir, repo, [base, fix, other, _merge]) = merged_fix().await;
+    {
+        let raw = repo.raw();
+        raw.tag_reference("v1.0", other, gix::refs::transaction::PreviousValue::MustNotExist)
+            .unwrap();
+        let release = write_commit(raw, &[base], &[], "release");
+        set_branch(raw, "release", release);
+    }
+
+    // The fix reached main through the merge, but not the release branch or tag
+    assert_eq!(
+        branches_containing(repo.clone(), &fix.to_string()).await.unwrap(),
+        ["fix", "main"]
+    );
+    assert!(tags_containing(repo.clone(), &fix.to_string()).await.unwrap().is_empty());
+
+    assert_eq!(
+        branches_containing(repo.clone(), &base.to_string()).await.unwrap(),
+        ["fix", "main", "release"]
+    );
+    assert_eq!(tags_containing(repo.clone(), &base.to_string()).await.unwrap(), ["v1.0"]);
+}
@@ -0,0 +1,125 @@
+//! Tests for applying patches to the index
+
+use gix::bstr::ByteSlice;
+use kodegen_tools_git::{ApplyOpts, CommitOpts, RepoHandle, apply_to_index, parse_patch};
+use tempfile::TempDir;
+
+use super::support::{commit_files, init_test_repo};
+
+/// Content staged at `path`, `None` if it isn't staged
+fn staged(repo: &RepoHandle, path: &str) -> Option<String> {
+    let index = repo.raw().index_or_empty().unwrap();
+    let entry = index.entry_by_path(path.as_bytes().as_bstr())?;
+    let blob = repo.raw().find_blob(entry.id).unwrap();
+    Some(blob.data.to_str_lossy().into_owned())
+}
+
+const PATCH: &str = "\
+diff --git a/a.txt b/a.txt
+index 0000000..1111111 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,3 +1,3 @@
+ one
+-two
++2
+ three
+diff --git a/new.txt b/new.txt
+new file mode 100644
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++created
+\\ No newline at end of file
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+";
+
+#[tokio::test]
+async fn test_apply_to_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_files(
+        &repo,
+        &[("a.txt", "zero\none\ntwo\nthree\n"), ("old.txt", "gone\n")],
+        "Initial",
+    )
+    .await;
+    // Unstaged edits stay in the working tree
+    std::fs::write(temp_dir.path().join("a.txt"), "edited\n").unwrap();
+
+    let files = parse_patch(PATCH).unwrap();
+    assert_eq!(files.len(), 3);
+    assert_eq!(files[1].old_path, None);
+    assert_eq!(files[2].new_path, None);
+
+    let checked = apply_to_index(repo.clone(), ApplyOpts::new(PATCH).check(true))
+        .await
+        .unwrap();
+    assert_eq!(checked.files, ["a.txt", "new.txt", "old.txt"]);
+    assert_eq!(staged(&repo, "new.txt"), None);
+
+    // The hunk moved down a line and is applied where its context is
+    let result = apply_to_index(
+        repo.clone(),
+        ApplyOpts::new(PATCH).commit(CommitOpts::message("Patch")),
+    )
+    .await
+    .unwrap();
+    assert!(result.commit.is_some());
+    assert_eq!(staged(&repo, "a.txt").unwrap(), "zero\none\n2\nthree\n");
+    assert_eq!(staged(&repo, "new.txt").unwrap(), "created");
+    assert_eq!(staged(&repo, "old.txt"), None);
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("a.txt")).unwrap(),
+        "edited\n"
+    );
+    assert!(!temp_dir.path().join("new.txt").exists());
+    assert!(temp_dir.path().join("old.txt").exists());
+
+    // Applying again fails and leaves the index alone
+    assert!(apply_to_index(repo.clone(), ApplyOpts::new(PATCH)).await.is_err());
+    assert_eq!(staged(&repo, "a.txt").unwrap(), "zero\none\n2\nthree\n");
+}
+
+#[tokio::test]
+async fn test_apply_rename_and_copy() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_files(
+        &repo,
+        &[("a.txt", "one\ntwo\n"), ("b.txt", "three\n")],
+        "Initial",
+    )
+    .await;
+
+    let patch = "\
+diff --git a/a.txt b/copy.txt
+similarity index 50%
+copy from a.txt
+copy to copy.txt
+--- a/a.txt
++++ b/copy.txt
+@@ -1,2 +1,2 @@
+ one
+-two
++2
+diff --git a/b.txt b/moved.txt
+similarity index 100%
+rename from b.txt
+rename to moved.txt
+";
+    let files = parse_patch(patch).unwrap();
+    assert!(files[0].copy);
+    assert!(!files[1].copy);
+
+    apply_to_index(repo.clone(), ApplyOpts::new(patch)).await.unwrap();
+    assert_eq!(staged(&repo, "a.txt").unwrap(), "one\ntwo\n");
+    assert_eq!(staged(&repo, "copy.txt").unwrap(), "one\n2\n");
+    assert_eq!(staged(&repo, "b.txt"), None);
+    assert_eq!(staged(&repo, "moved.txt").unwrap(), "three\n");
+}
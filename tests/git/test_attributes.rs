@@ -0,0 +1,62 @@
+//! Tests for .gitattributes editing
+
+use kodegen_tools_git::{
+    init_repo, lfs_track, lfs_untrack, list_attributes, remove_attributes, set_attributes,
+};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_set_attributes_merges_into_existing_line() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let gitattributes = temp_dir.path().join(".gitattributes");
+    std::fs::write(&gitattributes, "# scripts\n*.sh text eol=crlf").unwrap();
+
+    let edit = set_attributes(repo.clone(), "", ["*.sh", "dist/**"], ["eol=lf"]).await.unwrap();
+    assert_eq!(edit.changed, ["*.sh", "dist/**"]);
+    assert_eq!(
+        std::fs::read_to_string(&gitattributes).unwrap(),
+        "# scripts\n*.sh text eol=lf\ndist/** eol=lf\n"
+    );
+
+    // Setting again changes nothing
+    let again = set_attributes(repo.clone(), "", ["*.sh"], ["text", "eol=lf"]).await.unwrap();
+    assert!(again.changed.is_empty());
+
+    set_attributes(repo.clone(), "", ["dist/**"], ["linguist-generated", "-diff"]).await.unwrap();
+    let removed = remove_attributes(repo.clone(), "", ["dist/**", "*.md"], ["eol", "diff"])
+        .await
+        .unwrap();
+    assert_eq!(removed.changed, ["dist/**"]);
+
+    let rules = list_attributes(repo.clone(), "").await.unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!((rules[1].line, rules[1].pattern.as_str()), (3, "dist/**"));
+    assert_eq!(rules[1].attributes, ["linguist-generated"]);
+
+    assert!(set_attributes(repo.clone(), "", ["!*.sh"], ["text"]).await.is_err());
+    assert!(set_attributes(repo.clone(), "", ["*.sh"], ["-eol=lf"]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_lfs_track_and_untrack() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let gitattributes = temp_dir.path().join(".gitattributes");
+
+    let tracked = lfs_track(repo.clone(), ["*.psd", "*.zip"]).await.unwrap();
+    assert_eq!(tracked.changed, ["*.psd", "*.zip"]);
+    assert_eq!(
+        std::fs::read_to_string(&gitattributes).unwrap(),
+        "*.psd filter=lfs diff=lfs merge=lfs -text\n*.zip filter=lfs diff=lfs merge=lfs -text\n"
+    );
+    assert!(lfs_track(repo.clone(), ["*.psd"]).await.unwrap().changed.is_empty());
+
+    set_attributes(repo.clone(), "", ["*.zip"], ["linguist-vendored"]).await.unwrap();
+    let untracked = lfs_untrack(repo.clone(), ["*.psd", "*.zip"]).await.unwrap();
+    assert_eq!(untracked.changed, ["*.psd", "*.zip"]);
+    assert_eq!(
+        std::fs::read_to_string(&gitattributes).unwrap(),
+        "*.zip linguist-vendored\n"
+    );
+}
@@ -0,0 +1,297 @@
+//! Tests for auth configuration helpers.
+
+use gix::remote::Direction;
+use kodegen_tools_git::operations::auth::{
+    HostKeyPolicy, SshOptions, UrlRewrite, apply_url_rewrites,
+};
+
+#[test]
+fn test_url_rewrite_insteadof() {
+    let rules = vec![UrlRewrite::new(
+        "https://mirror.internal/github/",
+        "https://github.com/",
+    )];
+
+    let rewritten = apply_url_rewrites(&rules, "https://github.com/owner/repo.git", Direction::Fetch);
+    assert_eq!(
+        rewritten,
+        Some("https://mirror.internal/github/owner/repo.git".to_string())
+    );
+
+    // Push falls back to insteadOf when no pushInsteadOf rule matches
+    let rewritten = apply_url_rewrites(&rules, "https://github.com/owner/repo.git", Direction::Push);
+    assert_eq!(
+        rewritten,
+        Some("https://mirror.internal/github/owner/repo.git".to_string())
+    );
+
+    assert_eq!(
+        apply_url_rewrites(&rules, "https://gitlab.com/owner/repo.git", Direction::Fetch),
+        None
+    );
+}
+
+#[test]
+fn test_url_rewrite_longest_prefix_wins() {
+    let rules = vec![
+        UrlRewrite::new("https://mirror-a/", "https://github.com/"),
+        UrlRewrite::new("https://mirror-b/", "https://github.com/cyrup-ai/"),
+    ];
+
+    let rewritten = apply_url_rewrites(
+        &rules,
+        "https://github.com/cyrup-ai/kodegen.git",
+        Direction::Fetch,
+    );
+    assert_eq!(rewritten, Some("https://mirror-b/kodegen.git".to_string()));
+}
+
+#[test]
+fn test_url_rewrite_push_only() {
+    let rules = vec![
+        UrlRewrite::new("https://mirror/", "https://github.com/"),
+        UrlRewrite::new("git@github.com:", "https://github.com/").push_only(true),
+    ];
+
+    let url = "https://github.com/owner/repo.git";
+    assert_eq!(
+        apply_url_rewrites(&rules, url, Direction::Fetch),
+        Some("https://mirror/owner/repo.git".to_string())
+    );
+    assert_eq!(
+        apply_url_rewrites(&rules, url, Direction::Push),
+        Some("git@github.com:owner/repo.git".to_string())
+    );
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_push_prefers_registered_url_rewrite() {
+    use kodegen_tools_git::{
+        AddOpts, CloneOpts, CommitOpts, PushOpts, Signature, add, clone_repo, commit, init_repo,
+        push,
+    };
+
+    use super::support::UrlRewriteGuard;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_repo(&upstream_dir).await.unwrap().unwrap();
+    std::fs::write(upstream_dir.join("a.txt"), "one\n").unwrap();
+    add(upstream.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    commit(
+        upstream,
+        CommitOpts::message("one").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    let url = format!("file://{}", upstream_dir.display());
+    let clone_dir = temp_dir.path().join("clone");
+    let repo = clone_repo(CloneOpts::new(url.as_str(), &clone_dir))
+        .await
+        .unwrap()
+        .unwrap()
+        .repo;
+    std::fs::write(clone_dir.join("b.txt"), "two\n").unwrap();
+    add(repo.clone(), AddOpts::new(["b.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("two").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    // A config rule and a registered rule with the same prefix: git itself
+    // would apply the config rule
+    let config_path = clone_dir.join(".git/config");
+    let config = std::fs::read_to_string(&config_path)
+        .unwrap()
+        .replace(&url, "https://example.invalid/upstream");
+    std::fs::write(
+        &config_path,
+        format!("{config}[url \"file:///nonexistent/\"]\n\tinsteadOf = https://example.invalid/\n"),
+    )
+    .unwrap();
+    let parent = format!("file://{}/", temp_dir.path().display());
+    let _rules =
+        UrlRewriteGuard::add([UrlRewrite::new(parent.as_str(), "https://example.invalid/")]).await;
+
+    let opts = PushOpts {
+        refspecs: vec!["HEAD:refs/heads/topic".to_string()],
+        ..PushOpts::default()
+    };
+    let result = push(repo.clone(), opts).await.unwrap();
+    assert_eq!(result.commits_pushed, 1);
+}
+
+#[test]
+fn test_ssh_options_command() {
+    assert_eq!(SshOptions::new().ssh_command(None), None);
+
+    let opts = SshOptions::new().host_key_policy(HostKeyPolicy::AcceptNew);
+    assert_eq!(
+        opts.ssh_command(None),
+        Some("ssh -o StrictHostKeyChecking=accept-new".to_string())
+    );
+
+    let opts = SshOptions::new()
+        .host_key_policy(HostKeyPolicy::Strict)
+        .known_hosts("/etc/ci/known_hosts");
+    assert_eq!(
+        opts.ssh_command(Some("ssh -i ~/.ssh/deploy")),
+        Some(
+            "ssh -i ~/.ssh/deploy -o StrictHostKeyChecking=yes -o 'UserKnownHostsFile=\"/etc/ci/known_hosts\"'"
+                .to_string()
+        )
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ssh_options_command_quotes_known_hosts() {
+    let path = r#"/tmp/ci hosts/it's "known"\hosts"#;
+    let opts = SshOptions::new().known_hosts(path);
+
+    // git runs the command through sh; print the arguments ssh would get
+    let command = opts.ssh_command(Some("printf '%s\\n'")).unwrap();
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "-o\nUserKnownHostsFile=\"/tmp/ci hosts/it's \\\"known\\\"\\\\hosts\"\n"
+    );
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_run_git_command_extends_repo_ssh_command() {
+    use kodegen_tools_git::init_repo;
+    use kodegen_tools_git::operations::auth::{GitCommandOpts, run_git_command};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let config = temp_dir.path().join(".git/config");
+    let mut content = std::fs::read_to_string(&config).unwrap();
+    content.push_str("[core]\n\tsshCommand = ssh -i /keys/deploy\n");
+    std::fs::write(&config, content).unwrap();
+
+    // The repository's deploy key survives the host key policy
+    let ssh = SshOptions::new().host_key_policy(HostKeyPolicy::Strict);
+    let opts = GitCommandOpts::new(temp_dir.path().to_path_buf()).with_ssh_options(ssh);
+    let output = run_git_command(&["-c", "alias.x=!echo \"$GIT_SSH_COMMAND\"", "x"], opts)
+        .await
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "ssh -i /keys/deploy -o StrictHostKeyChecking=yes\n"
+    );
+
+    // So does a GIT_SSH_COMMAND given to the command, which git prefers
+    let ssh = SshOptions::new().host_key_policy(HostKeyPolicy::Strict);
+    let opts = GitCommandOpts::new(temp_dir.path().to_path_buf())
+        .with_ssh_options(ssh)
+        .with_env("GIT_SSH_COMMAND", "ssh -i /keys/ci");
+    let output = run_git_command(&["-c", "alias.x=!echo \"$GIT_SSH_COMMAND\"", "x"], opts)
+        .await
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "ssh -i /keys/ci -o StrictHostKeyChecking=yes\n"
+    );
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_run_git_command_streams_lines() {
+    use std::sync::{Arc, Mutex};
+
+    use kodegen_tools_git::operations::auth::{
+        GitCommandOpts, LineCallback, OutputStream, run_git_command,
+    };
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&lines);
+    let opts = GitCommandOpts::new(temp_dir.path().to_path_buf())
+        .with_line_callback(LineCallback::new(move |stream, line| {
+            seen.lock().unwrap().push((stream, line.to_string()));
+        }))
+        .with_max_output(4);
+    let error = run_git_command(
+        &["-c", "alias.x=!echo one; echo two; echo three >&2", "x"],
+        opts,
+    )
+    .await
+    .unwrap_err();
+
+    // Output beyond the cap fails the command, after every line was streamed
+    assert!(error.to_string().contains("4 bytes"), "{error}");
+    let lines = lines.lock().unwrap().clone();
+    assert_eq!(lines.len(), 3);
+    for expected in [
+        (OutputStream::Stdout, "one"),
+        (OutputStream::Stdout, "two"),
+        (OutputStream::Stderr, "three"),
+    ] {
+        assert!(
+            lines
+                .iter()
+                .any(|(stream, line)| (*stream, line.as_str()) == expected),
+            "{lines:?}"
+        );
+    }
+
+    // A timeout reports what was printed before it
+    let opts = GitCommandOpts::new(temp_dir.path().to_path_buf()).with_timeout(1);
+    let error = run_git_command(&["-c", "alias.x=!echo started; sleep 10", "x"], opts)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("timed out"), "{error}");
+    assert!(error.contains("started"), "{error}");
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_run_git_command_binary_and_env() {
+    use kodegen_tools_git::operations::auth::{
+        GitCliOptions, GitCommandOpts, git_cli_options, run_git_command, set_git_cli_options,
+    };
+
+    /// Puts the previous server-wide options back, even if an assertion fails
+    struct CliOptionsGuard(GitCliOptions);
+
+    impl Drop for CliOptionsGuard {
+        fn drop(&mut self) {
+            set_git_cli_options(std::mem::take(&mut self.0));
+        }
+    }
+
+    let _lock = super::support::lock_globals().await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let echo = [
+        "-c",
+        "alias.x=!echo \"$KODEGEN_SERVER_VAR/$KODEGEN_CALL_VAR\"",
+        "x",
+    ];
+
+    // Server-wide variables apply to every command, per-command ones on top
+    let _restore = CliOptionsGuard(git_cli_options());
+    set_git_cli_options(GitCliOptions::new().env("KODEGEN_SERVER_VAR", "server"));
+    assert_eq!(git_cli_options().env.len(), 1);
+    let opts =
+        GitCommandOpts::new(temp_dir.path().to_path_buf()).with_env("KODEGEN_CALL_VAR", "call");
+    let output = run_git_command(&echo, opts).await.unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "server/call");
+
+    // A per-command binary replaces `git` from PATH
+    let missing = temp_dir.path().join("no-such-git");
+    let opts = GitCommandOpts::new(temp_dir.path().to_path_buf()).with_git_binary(&missing);
+    assert!(run_git_command(&["--version"], opts).await.is_err());
+}
@@ -0,0 +1,135 @@
+//! Tests for backporting commits onto release branches
+
+use gix::refs::transaction::PreviousValue;
+use kodegen_tools_git::{BackportOpts, BackportOutcome, GitError, backport};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo, write_commit};
+
+#[tokio::test]
+async fn test_backport() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "one\n").await;
+    let raw = repo.raw();
+    raw.reference(
+        "refs/heads/release-1",
+        base,
+        PreviousValue::MustNotExist,
+        "test",
+    )
+    .unwrap();
+    let diverged = write_commit(raw, &[base], &[("a.txt", "release\n")], "release fix");
+    raw.reference(
+        "refs/heads/release-2",
+        diverged,
+        PreviousValue::MustNotExist,
+        "test",
+    )
+    .unwrap();
+    let fix = commit_file(&repo, "a.txt", "fixed\n").await;
+    let extra = commit_file(&repo, "b.txt", "new\n").await;
+
+    let fix_hex = fix.to_string();
+    let extra_hex = extra.to_string();
+    let commits = [fix_hex.as_str(), extra_hex.as_str()];
+    let results = backport(
+        repo.clone(),
+        &commits,
+        &["release-1", "release-2", "HEAD"],
+        BackportOpts::new(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 3);
+
+    let picked = &results[0];
+    let short = fix.to_hex_with_len(7).to_string();
+    assert_eq!(picked.outcome, BackportOutcome::Created);
+    assert_eq!(picked.branch, format!("backport/release-1/{short}"));
+    assert_eq!(picked.picked.len(), 2);
+    let tip = raw
+        .find_reference(format!("refs/heads/{}", picked.branch).as_str())
+        .unwrap()
+        .into_fully_peeled_id()
+        .unwrap();
+    assert_eq!(Some(tip.detach()), picked.tip);
+    let commit = raw.find_commit(picked.picked[0].1).unwrap();
+    assert_eq!(commit.parent_ids().next().unwrap(), base);
+    assert!(
+        commit
+            .message_raw_sloppy()
+            .to_string()
+            .ends_with(&format!("(cherry picked from commit {fix})\n"))
+    );
+    // The release branch itself doesn't move
+    let release = raw
+        .find_reference("refs/heads/release-1")
+        .unwrap()
+        .into_fully_peeled_id()
+        .unwrap();
+    assert_eq!(release, base);
+
+    let conflicted = &results[1];
+    assert_eq!(conflicted.outcome, BackportOutcome::Conflicts);
+    assert_eq!(conflicted.conflicting_commit, Some(fix));
+    assert_eq!(conflicted.conflicts[0].path, "a.txt");
+    assert!(conflicted.tip.is_none());
+
+    let applied = &results[2];
+    assert_eq!(applied.outcome, BackportOutcome::AlreadyApplied);
+    assert_eq!(applied.skipped, [fix, extra]);
+
+    // Running again leaves the existing branch alone
+    let again = backport(repo.clone(), &commits, &["release-1"], BackportOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(again[0].outcome, BackportOutcome::BranchExists);
+    assert_eq!(again[0].tip, picked.tip);
+
+    let error = backport(repo.clone(), &[], &["release-1"], BackportOpts::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+}
+
+#[tokio::test]
+async fn test_backport_conflict_creates_no_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "one\n").await;
+    let raw = repo.raw();
+    let release = write_commit(raw, &[base], &[("a.txt", "release\n")], "release fix");
+    raw.reference(
+        "refs/heads/release",
+        release,
+        PreviousValue::MustNotExist,
+        "test",
+    )
+    .unwrap();
+    // The first commit applies cleanly, the second conflicts
+    let clean = commit_file(&repo, "b.txt", "new\n").await;
+    let fix = commit_file(&repo, "a.txt", "fixed\n").await;
+
+    let commits = [clean.to_string(), fix.to_string()];
+    let commits: Vec<&str> = commits.iter().map(String::as_str).collect();
+    let results = backport(repo.clone(), &commits, &["release"], BackportOpts::new())
+        .await
+        .unwrap();
+    let conflicted = &results[0];
+    assert_eq!(conflicted.outcome, BackportOutcome::Conflicts);
+    assert_eq!(conflicted.conflicting_commit, Some(fix));
+    let paths: Vec<_> = conflicted
+        .conflicts
+        .iter()
+        .map(|c| c.path.as_str())
+        .collect();
+    assert_eq!(paths, ["a.txt"]);
+    assert!(conflicted.picked.is_empty());
+    assert!(conflicted.tip.is_none());
+    assert!(
+        raw.try_find_reference(format!("refs/heads/{}", conflicted.branch).as_str())
+            .unwrap()
+            .is_none()
+    );
+}
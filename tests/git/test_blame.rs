@@ -0,0 +1,94 @@
+//! Tests for per-line blame.
+
+use kodegen_tools_git::{BlameOpts, Signature, blame, init_repo};
+
+use super::support::commit_files_as;
+
+#[tokio::test]
+async fn test_blame_attributes_lines_to_commits() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    let alice = Signature::new("Alice", "alice@example.com");
+    let first = commit_files_as(
+        &repo,
+        alice,
+        &[("file.txt", "one\ntwo\nthree\n")],
+        "Add file",
+    )
+    .await;
+    let bob = Signature::new("Bob", "bob@example.com");
+    let second = commit_files_as(
+        &repo,
+        bob,
+        &[("file.txt", "one\nTWO\nthree\nfour\n")],
+        "Shout",
+    )
+    .await;
+
+    let result = blame(repo.clone(), BlameOpts::new("file.txt")).await.unwrap();
+    assert_eq!(result.commit, second);
+    assert_eq!(result.total_lines, 4);
+    let owners: Vec<_> = result
+        .lines
+        .iter()
+        .map(|line| {
+            (
+                line.line,
+                line.commit,
+                line.author.name.as_str(),
+                line.content.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        owners,
+        [
+            (1, first, "Alice", "one"),
+            (2, second, "Bob", "TWO"),
+            (3, first, "Alice", "three"),
+            (4, second, "Bob", "four"),
+        ]
+    );
+    assert_eq!(result.lines[1].summary, "Shout");
+    assert_eq!(result.lines[2].original_line, 3);
+
+    // A range is cut to the end of the file
+    let result = blame(repo.clone(), BlameOpts::new("file.txt").lines(3, 10))
+        .await
+        .unwrap();
+    let lines: Vec<u32> = result.lines.iter().map(|line| line.line).collect();
+    assert_eq!(lines, [3, 4]);
+
+    // Older revisions see the file as it was
+    let result = blame(repo.clone(), BlameOpts::new("file.txt").rev(first.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(result.total_lines, 3);
+    assert!(result.lines.iter().all(|line| line.commit == first));
+}
+
+#[tokio::test]
+async fn test_blame_rejects_missing_files_and_bad_ranges() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let alice = Signature::new("Alice", "alice@example.com");
+    commit_files_as(&repo, alice, &[("file.txt", "one\ntwo\n")], "Add file").await;
+
+    assert!(blame(repo.clone(), BlameOpts::new("missing.txt")).await.is_err());
+    assert!(
+        blame(repo.clone(), BlameOpts::new("file.txt").lines(0, 1))
+            .await
+            .is_err()
+    );
+    assert!(
+        blame(repo.clone(), BlameOpts::new("file.txt").lines(2, 1))
+            .await
+            .is_err()
+    );
+    assert!(
+        blame(repo.clone(), BlameOpts::new("file.txt").lines(3, 4))
+            .await
+            .is_err()
+    );
+}
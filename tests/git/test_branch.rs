@@ -1,6 +1,11 @@
 //! Tests for git branch operation.
 
+use gix::refs::transaction::PreviousValue;
 use kodegen_tools_git::git::branch::BranchOpts;
+use kodegen_tools_git::{CleanupPolicy, CleanupReason, cleanup_branches, open_repo};
+use tempfile::TempDir;
+
+use super::support::{init_test_repo, write_commit};
 
 #[test]
 fn test_branch_opts_builder() {
@@ -69,3 +74,82 @@ fn test_branch_opts_complex_name() {
 
     assert_eq!(opts.name, "feature/user-auth/oauth2-integration");
 }
+
+#[tokio::test]
+async fn test_cleanup_branches() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let raw = repo.raw();
+    let set = |name: &str, id| {
+        raw.reference(name, id, PreviousValue::MustNotExist, "test")
+            .unwrap();
+    };
+    // `write_commit` dates its commits in 2023
+    let base = write_commit(raw, &[], &[("a.txt", "one\n")], "base");
+    set(
+        "refs/heads/trunk",
+        write_commit(raw, &[base], &[("a.txt", "two\n")], "trunk"),
+    );
+    set("refs/heads/merged", base);
+    let unmerged = |name: &str| write_commit(raw, &[base], &[("b.txt", name)], name);
+    set("refs/heads/gone", unmerged("gone"));
+    let tracked = unmerged("tracked");
+    set("refs/heads/tracked", tracked);
+    set("refs/remotes/origin/tracked", tracked);
+    set("refs/heads/local", unmerged("local"));
+
+    let config = temp_dir.path().join(".git/config");
+    let mut text = std::fs::read_to_string(&config).unwrap();
+    text.push_str(
+        "[remote \"origin\"]\n\turl = /nonexistent\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n",
+    );
+    for branch in ["gone", "tracked"] {
+        text.push_str(&format!(
+            "[branch \"{branch}\"]\n\tremote = origin\n\tmerge = refs/heads/{branch}\n"
+        ));
+    }
+    std::fs::write(&config, text).unwrap();
+    let repo = open_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    // Nothing is a century old
+    let policy = CleanupPolicy::new()
+        .default_branch("trunk")
+        .older_than_days(36_500);
+    let report = cleanup_branches(repo.clone(), policy).await.unwrap();
+    assert!(report.branches.is_empty());
+
+    let policy = CleanupPolicy::new().default_branch("trunk");
+    let report = cleanup_branches(repo.clone(), policy).await.unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.default_branch.as_deref(), Some("trunk"));
+    let found: Vec<_> = report
+        .branches
+        .iter()
+        .map(|branch| (branch.name.as_str(), branch.reason, branch.deleted))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("gone", CleanupReason::UpstreamGone, false),
+            ("merged", CleanupReason::Merged, false),
+        ]
+    );
+    assert_eq!(report.branches[0].upstream.as_deref(), Some("origin/gone"));
+
+    let policy = CleanupPolicy::new().default_branch("trunk").delete(true);
+    let report = cleanup_branches(repo.clone(), policy).await.unwrap();
+    assert!(!report.dry_run);
+    assert!(report.branches.iter().all(|branch| branch.deleted));
+    for (name, exists) in [
+        ("gone", false),
+        ("merged", false),
+        ("tracked", true),
+        ("local", true),
+    ] {
+        let found = repo
+            .raw()
+            .try_find_reference(format!("refs/heads/{name}").as_str())
+            .unwrap();
+        assert_eq!(found.is_some(), exists, "{name}");
+    }
+}
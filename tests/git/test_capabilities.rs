@@ -0,0 +1,46 @@
+//! Tests for the environment capability report.
+
+use kodegen_tools_git::capabilities;
+
+#[tokio::test]
+async fn test_capabilities_report() {
+    let caps = capabilities().await.unwrap();
+
+    assert_eq!(caps.crate_version, env!("CARGO_PKG_VERSION"));
+    assert!(!caps.gix_version.is_empty());
+    assert!(caps.transports.iter().any(|t| t == "https"));
+    assert!(caps.transports.iter().any(|t| t == "file"));
+    assert_eq!(caps.transports.iter().any(|t| t == "ssh"), caps.ssh_available);
+    assert_eq!(caps.git_cli_available(), caps.git_cli_version.is_some());
+}
+
+#[cfg(all(unix, not(feature = "pure-gix")))]
+#[tokio::test]
+async fn test_capabilities_use_configured_git() {
+    use std::os::unix::fs::PermissionsExt;
+
+    use kodegen_tools_git::operations::auth::{GitCliOptions, set_git_cli_options};
+
+    use super::support::lock_globals;
+
+    // Other commands pass through to git, so concurrent tests keep working
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let wrapper = temp_dir.path().join("git-wrapper");
+    std::fs::write(
+        &wrapper,
+        "#!/bin/sh\n\
+         if [ \"$1\" = --version ]; then echo \"git version 0.0.0-wrapper\"; exit 0; fi\n\
+         exec git \"$@\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&wrapper, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let _lock = lock_globals().await;
+    set_git_cli_options(GitCliOptions::new().binary(&wrapper));
+    let caps = capabilities().await;
+    set_git_cli_options(GitCliOptions::default());
+    assert_eq!(
+        caps.unwrap().git_cli_version.as_deref(),
+        Some("git version 0.0.0-wrapper")
+    );
+}
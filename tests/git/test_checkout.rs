@@ -33,3 +33,175 @@ fn test_checkout_opts_with_tag() {
     assert_eq!(opts.reference, "v1.0.0");
     assert!(!opts.force);
 }
+
+#[tokio::test]
+async fn test_checkout_reports_progress() {
+    use std::sync::{Arc, Mutex};
+
+    use kodegen_tools_git::{
+        AddOpts, CommitOpts, ProgressCallback, Signature, add, checkout, commit,
+    };
+
+    use super::support::init_test_repo;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+
+    let names: Vec<String> = (0..5).map(|i| format!("f{i}.txt")).collect();
+    for name in &names {
+        std::fs::write(temp_dir.path().join(name), name).unwrap();
+    }
+    add(repo.clone(), AddOpts::new(names.iter().map(String::as_str)))
+        .await
+        .unwrap();
+    let first = commit(
+        repo.clone(),
+        CommitOpts::message("five files").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    let updates = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&updates);
+    let opts = CheckoutOpts::new(first.id.to_string())
+        .force(true)
+        .progress(ProgressCallback::new(move |p| sink.lock().unwrap().push(p)));
+    checkout(repo, opts).await.unwrap();
+
+    let updates = updates.lock().unwrap();
+    let last = updates.last().expect("at least the final update");
+    assert_eq!(last.files_total, 5);
+    assert!(last.files_processed <= last.files_total);
+}
+
+/// Repository with `a.txt` and `b.txt` on the default branch and a `feature`
+/// branch that changes `a.txt`; the default branch is checked out.
+#[cfg(not(feature = "pure-gix"))]
+async fn autostash_fixture() -> (tempfile::TempDir, kodegen_tools_git::RepoHandle) {
+    use kodegen_tools_git::{
+        AddOpts, BranchOpts, CommitOpts, Signature, add, branch, checkout, commit, current_branch,
+    };
+
+    use super::support::init_test_repo;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    // The stash CLI needs an identity
+    let repo = init_test_repo(temp_dir.path()).await;
+
+    let commit_all = |message: &'static str| {
+        let repo = repo.clone();
+        async move {
+            add(repo.clone(), AddOpts::new(["a.txt", "b.txt"])).await.unwrap();
+            commit(
+                repo,
+                CommitOpts::message(message).author(Signature::new("Test", "test@example.com")),
+            )
+            .await
+            .unwrap();
+        }
+    };
+
+    std::fs::write(temp_dir.path().join("a.txt"), "a\n").unwrap();
+    std::fs::write(temp_dir.path().join("b.txt"), "b\n").unwrap();
+    commit_all("initial").await;
+    let main = current_branch(repo.clone()).await.unwrap().name;
+
+    branch(repo.clone(), BranchOpts::new("feature").checkout(true))
+        .await
+        .unwrap()
+        .unwrap();
+    std::fs::write(temp_dir.path().join("a.txt"), "a from feature\n").unwrap();
+    commit_all("change a").await;
+    checkout(repo.clone(), CheckoutOpts::new(&main)).await.unwrap();
+
+    (temp_dir, repo)
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_checkout_autostash_carries_changes() {
+    use kodegen_tools_git::{checkout, current_branch};
+
+    let (temp_dir, repo) = autostash_fixture().await;
+    std::fs::write(temp_dir.path().join("b.txt"), "b changed locally\n").unwrap();
+
+    checkout(repo.clone(), CheckoutOpts::new("feature").autostash(true))
+        .await
+        .unwrap();
+
+    assert_eq!(current_branch(repo.clone()).await.unwrap().name, "feature");
+    let read = |name: &str| std::fs::read_to_string(temp_dir.path().join(name)).unwrap();
+    assert_eq!(read("a.txt"), "a from feature\n");
+    assert_eq!(read("b.txt"), "b changed locally\n");
+
+    let stashes = std::process::Command::new("git")
+        .args(["stash", "list"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(stashes.stdout.is_empty(), "autostash entry should be dropped");
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_checkout_autostash_reports_conflicts() {
+    use kodegen_tools_git::{GitError, checkout, current_branch};
+
+    let (temp_dir, repo) = autostash_fixture().await;
+    std::fs::write(temp_dir.path().join("a.txt"), "a changed locally\n").unwrap();
+
+    let result = checkout(repo.clone(), CheckoutOpts::new("feature").autostash(true)).await;
+    let Err(GitError::AutostashConflict { stash, paths }) = result else {
+        panic!("expected an autostash conflict, got {result:?}");
+    };
+    assert_eq!(stash, "stash@{0}");
+    assert_eq!(paths, ["a.txt"]);
+
+    // The checkout itself went through
+    assert_eq!(current_branch(repo.clone()).await.unwrap().name, "feature");
+}
+
+#[tokio::test]
+async fn test_checkout_previous_branch() {
+    use kodegen_tools_git::{
+        AddOpts, BranchOpts, CommitOpts, Signature, add, branch, checkout, commit, current_branch,
+        previous_branch,
+    };
+
+    use super::support::init_test_repo;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    std::fs::write(temp_dir.path().join("a.txt"), "a\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    let first = commit(
+        repo.clone(),
+        CommitOpts::message("initial").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+    let main = current_branch(repo.clone()).await.unwrap().name;
+    assert_eq!(previous_branch(repo.clone()).await.unwrap(), None);
+    assert!(checkout(repo.clone(), CheckoutOpts::new("-")).await.is_err());
+
+    branch(repo.clone(), BranchOpts::new("feature"))
+        .await
+        .unwrap()
+        .unwrap();
+    checkout(repo.clone(), CheckoutOpts::new("feature")).await.unwrap();
+    assert_eq!(previous_branch(repo.clone()).await.unwrap(), Some(main.clone()));
+
+    // Toggle back and forth
+    checkout(repo.clone(), CheckoutOpts::new("-")).await.unwrap();
+    assert_eq!(current_branch(repo.clone()).await.unwrap().name, main);
+    checkout(repo.clone(), CheckoutOpts::new("@{-1}")).await.unwrap();
+    assert_eq!(current_branch(repo.clone()).await.unwrap().name, "feature");
+
+    // Leaving a detached HEAD records its commit
+    checkout(repo.clone(), CheckoutOpts::new(first.id.to_string()))
+        .await
+        .unwrap();
+    checkout(repo.clone(), CheckoutOpts::new(&main)).await.unwrap();
+    assert_eq!(previous_branch(repo.clone()).await.unwrap(), Some(first.id.to_string()));
+    assert_eq!(repo.raw().rev_parse_single("@{-1}").unwrap().detach(), first.id);
+}
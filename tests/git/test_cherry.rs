@@ -0,0 +1,43 @@
+//! Tests for cherry detection
+
+use kodegen_tools_git::cherry;
+
+use super::support::{init_test_repo, write_commit};
+
+#[tokio::test]
+async fn test_cherry_finds_picked_commits() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+
+    let (picked, local) = {
+        let raw = repo.raw();
+        let base = write_commit(raw, &[], &[("a.txt", "1\n2\n3\n")], "base");
+
+        let fix = [("a.txt", "1\n2\nthree\n")];
+        let picked = write_commit(raw, &[base], &fix, "fix");
+        let local = write_commit(raw, &[picked], &[("b.txt", "b\n")], "local");
+
+        // Upstream moved on, then picked the fix with a different message
+        let other = write_commit(raw, &[base], &[("c.txt", "c\n")], "other");
+        let main = write_commit(raw, &[other], &fix, "fix (picked)");
+
+        for (branch, tip) in [("main", main), ("feature", local)] {
+            raw.reference(
+                format!("refs/heads/{branch}").as_str(),
+                tip,
+                gix::refs::transaction::PreviousValue::Any,
+                "test",
+            )
+            .unwrap();
+        }
+        (picked, local)
+    };
+
+    let commits = cherry(repo.clone(), "main", "feature").await.unwrap();
+    let marks: Vec<_> = commits.iter().map(|c| (c.id, c.in_upstream)).collect();
+    assert_eq!(marks, [(picked, true), (local, false)]);
+    assert_eq!(commits[1].summary, "local");
+
+    // Nothing on main is missing from main
+    assert!(cherry(repo.clone(), "main", "main").await.unwrap().is_empty());
+}
@@ -0,0 +1,133 @@
+//! Tests for cherry-picking commits onto HEAD.
+
+use kodegen_tools_git::{CherryPickOpts, GitError, cherry_pick, head_commit, is_clean};
+
+use super::support::{commit_file, init_test_repo, write_commit};
+
+#[tokio::test]
+async fn test_cherry_pick_commits_onto_head() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "a\n").await;
+    let side = write_commit(repo.raw(), &[base], &[("b.txt", "b\n")], "Add b\n");
+    let head = commit_file(&repo, "c.txt", "c\n").await;
+
+    let result = cherry_pick(
+        repo.clone(),
+        CherryPickOpts::new(side.to_string()).record_origin(true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.source, side);
+    assert_eq!(result.previous_head, head);
+    let id = result.commit_id.unwrap();
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), id.to_string());
+
+    let picked = repo.raw().find_commit(id).unwrap();
+    assert_eq!(
+        picked.parent_ids().map(|p| p.detach()).collect::<Vec<_>>(),
+        [head]
+    );
+    assert_eq!(picked.author().unwrap().name, "Author");
+    assert_eq!(
+        picked.message_raw().unwrap(),
+        format!("Add b\n\n(cherry picked from commit {side})\n").as_str()
+    );
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("b.txt")).unwrap(),
+        "b\n"
+    );
+    assert!(is_clean(repo.clone()).await.unwrap());
+
+    // The change is in HEAD now
+    let again = cherry_pick(repo.clone(), CherryPickOpts::new(side.to_string())).await;
+    assert!(matches!(again, Err(GitError::InvalidInput(_))));
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), id.to_string());
+}
+
+#[tokio::test]
+async fn test_cherry_pick_merge_needs_mainline() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "a\n").await;
+    let other = write_commit(repo.raw(), &[base], &[("e.txt", "e\n")], "Add e");
+    let merge = write_commit(repo.raw(), &[base, other], &[("d.txt", "d\n")], "Merge");
+    let head = commit_file(&repo, "c.txt", "c\n").await;
+
+    for opts in [
+        CherryPickOpts::new(merge.to_string()),
+        CherryPickOpts::new(merge.to_string()).mainline(3),
+        CherryPickOpts::new(other.to_string()).mainline(1),
+    ] {
+        let result = cherry_pick(repo.clone(), opts).await;
+        assert!(
+            matches!(result, Err(GitError::InvalidInput(_))),
+            "{result:?}"
+        );
+    }
+
+    // Relative to the first parent the merge only adds d.txt; staged, not committed
+    let result = cherry_pick(
+        repo.clone(),
+        CherryPickOpts::new(merge.to_string())
+            .mainline(1)
+            .no_commit(true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.commit_id, None);
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), head.to_string());
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("d.txt")).unwrap(),
+        "d\n"
+    );
+    assert!(!temp_dir.path().join("e.txt").exists());
+    let index = repo.raw().index().unwrap();
+    assert!(index.entry_by_path("d.txt".into()).is_some());
+}
+
+#[tokio::test]
+async fn test_cherry_pick_keeps_untracked_files() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "a\n").await;
+    let side = write_commit(repo.raw(), &[base], &[("b.txt", "b\n")], "Add b");
+    let head = commit_file(&repo, "c.txt", "c\n").await;
+
+    let file = temp_dir.path().join("b.txt");
+    std::fs::write(&file, "mine\n").unwrap();
+    for no_commit in [false, true] {
+        let opts = CherryPickOpts::new(side.to_string()).no_commit(no_commit);
+        let err = cherry_pick(repo.clone(), opts).await.unwrap_err().to_string();
+        assert!(err.contains("b.txt"), "{err}");
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "mine\n");
+        assert_eq!(head_commit(repo.clone()).await.unwrap(), head.to_string());
+    }
+}
+
+#[cfg(feature = "testkit")]
+#[tokio::test]
+async fn test_cherry_pick_conflict_reports_paths() {
+    use kodegen_tools_git::testkit::{RepoSpec, fabricate_merge_conflict, generate};
+
+    let fixture = generate(RepoSpec::new(7).commits(2)).await.unwrap();
+    let scenario = fabricate_merge_conflict(fixture.repo.clone(), "pick")
+        .await
+        .unwrap();
+
+    let result = cherry_pick(
+        fixture.repo.clone(),
+        CherryPickOpts::new(scenario.theirs_commit.to_string()),
+    )
+    .await;
+    let Err(GitError::MergeConflict { message, paths }) = result else {
+        panic!("expected a merge conflict, got {result:?}");
+    };
+    assert_eq!(paths, std::slice::from_ref(&scenario.path));
+    assert!(message.contains(&scenario.path));
+    assert_eq!(
+        head_commit(fixture.repo.clone()).await.unwrap(),
+        scenario.ours.to_string()
+    );
+    assert!(is_clean(fixture.repo.clone()).await.unwrap());
+}
@@ -27,3 +27,143 @@ fn test_clone_opts_defaults() {
     assert_eq!(opts.shallow, None);
     assert!(!opts.bare);
 }
+
+#[tokio::test]
+async fn test_clone_reports_head_and_stats() {
+    use kodegen_tools_git::{AddOpts, CommitOpts, Signature, add, clone_repo, commit, init_repo};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream).unwrap();
+    let repo = init_repo(&upstream).await.unwrap().unwrap();
+    std::fs::write(upstream.join("file.txt"), "content\n").unwrap();
+    add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+    let head = commit(
+        repo.clone(),
+        CommitOpts::message("initial").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap()
+    .id;
+
+    let url = format!("file://{}", upstream.display());
+    let result = clone_repo(CloneOpts::new(url, temp_dir.path().join("clone")))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.head, Some(head));
+    assert!(result.default_branch.is_some());
+    assert!(result.refs_fetched >= 1);
+    // Commit, tree and blob
+    assert_eq!(result.objects_received, 3);
+    assert!(!result.has_submodules);
+    assert!(result.size_on_disk > 0);
+}
+
+#[tokio::test]
+async fn test_clone_preflight_estimates_local_size() {
+    use kodegen_tools_git::{clone_preflight, init_repo};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream = temp_dir.path().join("upstream");
+    let repo = init_repo(&upstream).await.unwrap().unwrap();
+    super::support::commit_file(&repo, "big.txt", &"line\n".repeat(10_000)).await;
+
+    // A plain path is a local remote too; a checkout adds its HEAD blobs
+    let url = upstream.display().to_string();
+    let dest = temp_dir.path().join("clone");
+    let bare = clone_preflight(CloneOpts::new(&url, &dest).bare(true))
+        .await
+        .unwrap()
+        .unwrap();
+    let checkout = clone_preflight(CloneOpts::new(&url, &dest))
+        .await
+        .unwrap()
+        .unwrap();
+    let bare_size = bare.estimated_size.unwrap();
+    assert!(bare_size > 0);
+    assert_eq!(checkout.estimated_size, Some(bare_size + 50_000));
+
+    // Nothing is estimated for a path that isn't a repository
+    let missing = temp_dir.path().join("missing").display().to_string();
+    let report = clone_preflight(CloneOpts::new(&missing, &dest))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(report.estimated_size, None);
+}
+
+#[tokio::test]
+async fn test_clone_preflight() {
+    use kodegen_tools_git::{GitError, PreflightIssue, clone_preflight, clone_repo, init_repo};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream).unwrap();
+    let repo = init_repo(&upstream).await.unwrap().unwrap();
+    super::support::commit_file(&repo, "file.txt", "content\n").await;
+    let url = format!("file://{}", upstream.display());
+
+    // A free destination next to the remote passes, sized from the remote
+    let report = clone_preflight(CloneOpts::new(&url, temp_dir.path().join("clone")))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(report.is_ok(), "{report}");
+    assert!(report.estimated_size.is_some_and(|size| size > 0));
+    assert!(report.enclosing_repo.is_none());
+
+    let report = clone_preflight(CloneOpts::new(&url, &upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        report.issues,
+        [PreflightIssue::DestinationExists(upstream.clone())]
+    );
+
+    let missing = temp_dir.path().join("no/such/dir");
+    let report = clone_preflight(CloneOpts::new(&url, &missing))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        report.issues,
+        [PreflightIssue::ParentMissing(
+            missing.parent().unwrap().to_path_buf()
+        )]
+    );
+
+    // Inside the remote's worktree only with nested clones allowed
+    let inner = upstream.join("vendor");
+    let report = clone_preflight(CloneOpts::new(&url, &inner))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        report.issues.as_slice(),
+        [PreflightIssue::InsideRepository(_)]
+    ));
+    let report = clone_preflight(CloneOpts::new(&url, &inner).nested(true))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(report.is_ok(), "{report}");
+
+    // Fails before fetching, leaving nothing behind
+    let dest = temp_dir.path().join("huge");
+    let result = clone_repo(CloneOpts::new(&url, &dest).expected_size(u64::MAX))
+        .await
+        .unwrap();
+    let Err(GitError::PreflightFailed(report)) = result else {
+        panic!("expected a preflight failure, got {result:?}");
+    };
+    assert!(matches!(
+        report.issues.as_slice(),
+        [PreflightIssue::InsufficientSpace {
+            needed: u64::MAX,
+            ..
+        }]
+    ));
+    assert!(!dest.exists());
+}
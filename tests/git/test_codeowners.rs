@@ -0,0 +1,73 @@
+//! Tests for CODEOWNERS parsing and matching.
+
+use kodegen_tools_git::{Codeowners, init_repo, read_codeowners};
+
+const CODEOWNERS: &str = "\
+# Default owners
+*       @org/everyone
+
+*.js    @js-owner # inline comment
+/build/logs/ @doctocat
+docs/*  docs@example.com
+apps/   @octocat
+/scripts/ @doctocat @octocat
+/scripts/generated
+
+!*.md   @nobody
+*.txt   not-an-owner
+";
+
+#[test]
+fn test_codeowners_last_match_wins() {
+    let codeowners = Codeowners::parse("CODEOWNERS", CODEOWNERS);
+
+    assert_eq!(codeowners.owners_for("README"), ["@org/everyone"]);
+    assert_eq!(codeowners.owners_for("src/app.js"), ["@js-owner"]);
+    assert_eq!(codeowners.owners_for("build/logs/today.log"), ["@doctocat"]);
+    assert_eq!(codeowners.owners_for("scripts/run.sh"), ["@doctocat", "@octocat"]);
+    assert_eq!(codeowners.rule_for("scripts/run.sh").unwrap().line, 8);
+}
+
+#[test]
+fn test_codeowners_directory_semantics() {
+    let codeowners = Codeowners::parse("CODEOWNERS", CODEOWNERS);
+
+    // `docs/*` covers direct children only
+    assert_eq!(codeowners.owners_for("docs/intro.md"), ["docs@example.com"]);
+    assert_eq!(codeowners.owners_for("docs/guides/setup.md"), ["@org/everyone"]);
+
+    // `apps/` matches an apps directory at any depth
+    assert_eq!(codeowners.owners_for("apps/web/index.html"), ["@octocat"]);
+    assert_eq!(codeowners.owners_for("services/apps/api.rs"), ["@octocat"]);
+
+    // Anchored patterns only match at the root
+    assert_eq!(codeowners.owners_for("tools/build/logs/x.log"), ["@org/everyone"]);
+
+    // A rule without owners leaves the path unowned
+    assert!(codeowners.owners_for("scripts/generated/out.sh").is_empty());
+}
+
+#[test]
+fn test_codeowners_reports_skipped_lines() {
+    let codeowners = Codeowners::parse("CODEOWNERS", CODEOWNERS);
+
+    let lines: Vec<usize> = codeowners.issues.iter().map(|issue| issue.line).collect();
+    assert_eq!(lines, [11, 12]);
+    assert_eq!(codeowners.rules.len(), 7);
+}
+
+#[tokio::test]
+async fn test_read_codeowners_location_precedence() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    assert!(read_codeowners(repo.clone()).await.unwrap().is_none());
+
+    std::fs::write(temp_dir.path().join("CODEOWNERS"), "* @root\n").unwrap();
+    std::fs::create_dir(temp_dir.path().join(".github")).unwrap();
+    std::fs::write(temp_dir.path().join(".github/CODEOWNERS"), "* @github\n").unwrap();
+
+    let codeowners = read_codeowners(repo).await.unwrap().unwrap();
+    assert_eq!(codeowners.source, ".github/CODEOWNERS");
+    assert_eq!(codeowners.owners_for("any/file.rs"), ["@github"]);
+}
@@ -0,0 +1,72 @@
+//! Tests for path collision and portability checks
+
+use kodegen_tools_git::{PathIssueKind, detect_case_collisions, init_repo};
+use tempfile::TempDir;
+
+use super::support::write_commit;
+
+#[tokio::test]
+async fn test_detect_case_collisions() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let commit = write_commit(
+        repo.raw(),
+        &[],
+        &[
+            ("README.md", "a\n"),
+            ("readme.md", "b\n"),
+            ("Src/lib.rs", "c\n"),
+            ("src/main.rs", "d\n"),
+            ("caf\u{e9}.txt", "e\n"),
+            ("cafe\u{301}.txt", "f\n"),
+            ("ok/file.rs", "g\n"),
+        ],
+        "Colliding paths",
+    );
+
+    let issues = detect_case_collisions(repo.clone(), &commit.to_string()).await.unwrap();
+    let found: Vec<(&str, PathIssueKind)> = issues
+        .iter()
+        .map(|issue| (issue.path.as_str(), issue.kind))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("README.md", PathIssueKind::CaseCollision),
+            ("Src", PathIssueKind::CaseCollision),
+            ("cafe\u{301}.txt", PathIssueKind::UnicodeCollision),
+        ]
+    );
+    assert_eq!(issues[0].conflicts_with, ["readme.md"]);
+}
+
+#[tokio::test]
+async fn test_detect_windows_invalid_names() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let commit = write_commit(
+        repo.raw(),
+        &[],
+        &[
+            ("notes/what?.md", "a\n"),
+            ("src/aux.rs", "b\n"),
+            ("dist./index.js", "c\n"),
+            ("docs/console.md", "d\n"),
+        ],
+        "Windows-hostile paths",
+    );
+
+    let issues = detect_case_collisions(repo.clone(), &commit.to_string()).await.unwrap();
+    let found: Vec<(&str, PathIssueKind)> = issues
+        .iter()
+        .map(|issue| (issue.path.as_str(), issue.kind))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("dist.", PathIssueKind::TrailingDotOrSpace),
+            ("notes/what?.md", PathIssueKind::InvalidCharacter),
+            ("src/aux.rs", PathIssueKind::ReservedName),
+        ]
+    );
+}
@@ -2,6 +2,7 @@
 
 use chrono::{DateTime, Utc};
 use kodegen_tools_git::git::commit::{CommitOpts, Signature};
+use kodegen_tools_git::{AddOpts, add, commit, get_commit_details, init_repo, parse_date};
 
 #[test]
 fn test_signature_creation() {
@@ -70,3 +71,129 @@ fn test_commit_opts_chaining() {
     assert!(!opts.amend);
     assert!(!opts.all);
 }
+
+#[test]
+fn test_parse_date_formats() {
+    let iso = parse_date("2020-01-02T03:04:05+02:00").unwrap();
+    assert_eq!(iso.timestamp(), 1_577_927_045);
+    assert_eq!(iso.offset().local_minus_utc(), 7200);
+
+    let raw = parse_date("1577927045 +0200").unwrap();
+    assert_eq!(raw, iso);
+
+    let rfc2822 = parse_date("Thu, 2 Jan 2020 03:04:05 +0200").unwrap();
+    assert_eq!(rfc2822, iso);
+
+    assert!(parse_date("not a date").is_err());
+}
+
+#[tokio::test]
+async fn test_commit_with_date_overrides() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    std::fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+    add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+
+    let authored = parse_date("2020-01-02T03:04:05+02:00").unwrap();
+    let committed = parse_date("2021-06-07T08:09:10-05:00").unwrap();
+    let opts = CommitOpts::message("Imported")
+        .author(Signature::new("Test", "test@example.com"))
+        .author_date(authored)
+        .commit_date(committed);
+    let result = commit(repo.clone(), opts).await.unwrap();
+
+    let commit = repo.raw().find_commit(result.id).unwrap();
+    let author = commit.author().unwrap().time().unwrap();
+    assert_eq!(author.seconds, authored.timestamp());
+    assert_eq!(author.offset, 7200);
+    let committer = commit.committer().unwrap().time().unwrap();
+    assert_eq!(committer.seconds, committed.timestamp());
+    assert_eq!(committer.offset, -5 * 3600);
+}
+
+#[tokio::test]
+async fn test_signature_offset_round_trips() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    std::fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+    add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+
+    let date = parse_date("2020-01-02T03:04:05+05:30").unwrap();
+    let author = Signature::with_date("Test", "test@example.com", date);
+    assert_eq!(author.local_time(), date);
+    let result = commit(
+        repo.clone(),
+        CommitOpts::message("Offset").author(author.clone()),
+    )
+    .await
+    .unwrap();
+
+    let details = get_commit_details(repo.clone(), &result.id.to_string())
+        .await
+        .unwrap();
+    assert_eq!(details.author.local_time(), date);
+    assert_eq!(details.author.offset.local_minus_utc(), 19_800);
+    assert_eq!(details.author.raw_time.as_deref(), Some("1577914445 +0530"));
+    assert_eq!(details.author.git_time(), author.git_time());
+}
+
+#[tokio::test]
+async fn test_commit_result_details() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    std::fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+    add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+
+    let author = Signature::new("Test", "test@example.com");
+    let first = commit(repo.clone(), CommitOpts::message("First").author(author.clone()))
+        .await
+        .unwrap();
+    assert!(first.parents.is_empty());
+    assert!(!first.amended);
+    let branch = first.branch.clone().unwrap();
+    let stored = repo.raw().find_commit(first.id).unwrap();
+    assert_eq!(stored.tree_id().unwrap().detach(), first.tree);
+
+    let second = commit(repo.clone(), CommitOpts::message("Second").author(author.clone()))
+        .await
+        .unwrap();
+    assert_eq!(second.parents, [first.id]);
+    assert_eq!(second.tree, first.tree);
+
+    let amended = commit(repo.clone(), CommitOpts::message("Amended").amend(true).author(author))
+        .await
+        .unwrap();
+    assert!(amended.amended);
+    assert_eq!(amended.parents, [first.id]);
+    assert_eq!(repo.raw().head_id().unwrap(), amended.id);
+    assert_eq!(amended.branch.as_deref(), Some(branch.as_str()));
+}
+
+#[tokio::test]
+async fn test_commit_message_encoding() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    std::fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+    add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+
+    let opts = CommitOpts::message("Café crème")
+        .author(Signature::new("Test", "test@example.com"))
+        .encoding("ISO-8859-1");
+    let result = commit(repo.clone(), opts).await.unwrap();
+
+    let stored = repo.raw().find_commit(result.id).unwrap();
+    let decoded = stored.decode().unwrap();
+    assert_eq!(
+        decoded.encoding.map(|e| e.to_vec()),
+        Some(b"ISO-8859-1".to_vec())
+    );
+    assert_eq!(decoded.message.to_vec(), b"Caf\xe9 cr\xe8me".to_vec());
+
+    let details = get_commit_details(repo.clone(), &result.id.to_string())
+        .await
+        .unwrap();
+    assert_eq!(details.message, "Café crème");
+
+    let unmappable = CommitOpts::message("日本").encoding("ISO-8859-1");
+    assert!(commit(repo.clone(), unmappable).await.is_err());
+}
@@ -0,0 +1,58 @@
+//! Tests for branch comparison.
+
+use kodegen_tools_git::{BranchOpts, Mergeability, branch, compare_branches, current_branch};
+
+use super::support::{commit_file, init_test_repo};
+
+#[tokio::test]
+async fn test_compare_branches_fast_forward() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+
+    commit_file(&repo, "a.txt", "one\n").await;
+    let main = current_branch(repo.clone()).await.unwrap().name;
+    branch(repo.clone(), BranchOpts::new("feature").checkout(true))
+        .await
+        .unwrap()
+        .unwrap();
+    commit_file(&repo, "a.txt", "one\ntwo\nthree\n").await;
+    commit_file(&repo, "b.txt", "new\n").await;
+
+    let comparison = compare_branches(repo.clone(), &main, "feature").await.unwrap();
+    assert_eq!(comparison.ahead.len(), 2);
+    assert_eq!(comparison.ahead[0].summary, "update b.txt");
+    assert!(comparison.behind.is_empty());
+    assert_eq!(comparison.merge_base, Some(comparison.base_id));
+    assert_eq!(comparison.mergeability, Mergeability::FastForward);
+
+    let stats = &comparison.diffstat;
+    assert_eq!(stats.total_files_changed, 2);
+    assert_eq!(stats.total_additions, 3);
+    assert_eq!(stats.total_deletions, 0);
+
+    // The other way around nothing is missing
+    let reverse = compare_branches(repo, "feature", &main).await.unwrap();
+    assert!(reverse.ahead.is_empty());
+    assert_eq!(reverse.behind.len(), 2);
+    assert_eq!(reverse.mergeability, Mergeability::UpToDate);
+    assert_eq!(reverse.diffstat.total_files_changed, 0);
+}
+
+#[cfg(feature = "testkit")]
+#[tokio::test]
+async fn test_compare_branches_predicts_conflicts() {
+    use kodegen_tools_git::testkit::{RepoSpec, fabricate_merge_conflict, generate};
+
+    let fixture = generate(RepoSpec::new(5).commits(2)).await.unwrap();
+    let main = current_branch(fixture.repo.clone()).await.unwrap().name;
+    let scenario = fabricate_merge_conflict(fixture.repo.clone(), "compare").await.unwrap();
+
+    let comparison = compare_branches(fixture.repo.clone(), &main, &scenario.theirs)
+        .await
+        .unwrap();
+    assert_eq!(comparison.merge_base, Some(scenario.base));
+    assert_eq!(comparison.ahead.len(), 1);
+    assert_eq!(comparison.behind.len(), 1);
+    assert_eq!(comparison.mergeability, Mergeability::Conflicts(vec![scenario.path.clone()]));
+    assert_eq!(comparison.diffstat.files[0].path, scenario.path);
+}
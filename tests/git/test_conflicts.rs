@@ -0,0 +1,97 @@
+//! Tests for merge conflict detail and pull conflict reporting
+
+use kodegen_tools_git::{
+    CloneOpts, ConflictKind, PullOpts, clone_repo, head_commit, init_repo, is_clean,
+    merge_conflicts, pull,
+};
+
+use super::support::{commit_file, init_test_repo, write_change};
+
+#[tokio::test]
+async fn test_merge_conflicts_classifies_files() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let raw = repo.raw();
+
+    let base = write_change(raw, &[], &[
+        ("content.txt", Some("1\n2\n3\n")),
+        ("deleted.txt", Some("a\nb\nc\n")),
+        ("same.txt", Some("same\n")),
+    ], "base");
+    let ours = write_change(raw, &[base], &[
+        ("content.txt", Some("1\nours\n3\n")),
+        ("deleted.txt", None),
+        ("added.txt", Some("ours\n")),
+    ], "ours");
+    let theirs = write_change(raw, &[base], &[
+        ("content.txt", Some("1\ntheirs\n3\n")),
+        ("deleted.txt", Some("a\nb\nchanged\n")),
+        ("added.txt", Some("theirs\n")),
+        ("same.txt", Some("same, but theirs\n")),
+    ], "theirs");
+    raw.reference(
+        "refs/heads/theirs",
+        theirs,
+        gix::refs::transaction::PreviousValue::Any,
+        "test",
+    )
+    .unwrap();
+    raw.reference(
+        "HEAD",
+        ours,
+        gix::refs::transaction::PreviousValue::Any,
+        "test",
+    )
+    .unwrap();
+
+    let conflicts = merge_conflicts(repo.clone(), "theirs").await.unwrap();
+    let found: Vec<_> = conflicts
+        .iter()
+        .map(|conflict| (conflict.path.as_str(), conflict.kind))
+        .collect();
+    assert_eq!(found, [
+        ("added.txt", ConflictKind::AddAdd),
+        ("content.txt", ConflictKind::Content),
+        ("deleted.txt", ConflictKind::DeleteModify),
+    ]);
+    assert_eq!(ConflictKind::DeleteModify.to_string(), "delete/modify");
+
+    // Merging an ancestor never conflicts
+    assert!(merge_conflicts(repo.clone(), &base.to_string()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_pull_reports_conflicted_files() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_repo(&upstream_dir).await.unwrap().unwrap();
+    commit_file(&upstream, "file.txt", "1\n2\n3\n").await;
+
+    let clone_dir = temp_dir.path().join("clone");
+    let url = format!("file://{}", upstream_dir.display());
+    let cloned = clone_repo(CloneOpts::new(url, &clone_dir)).await.unwrap().unwrap();
+    let branch = cloned.default_branch.unwrap();
+    let repo = cloned.repo;
+
+    commit_file(&upstream, "file.txt", "1\nupstream\n3\n").await;
+    commit_file(&repo, "file.txt", "1\nlocal\n3\n").await;
+    let before = head_commit(repo.clone()).await.unwrap();
+
+    let opts = PullOpts {
+        remote: "origin".to_string(),
+        branch,
+        fast_forward: true,
+        auto_commit: true,
+        autostash: false,
+    };
+    let result = pull(repo.clone(), opts).await.unwrap();
+    assert!(result.merge_outcome.is_none());
+    assert_eq!(result.conflicts.len(), 1);
+    assert_eq!(result.conflicts[0].path, "file.txt");
+    assert_eq!(result.conflicts[0].kind, ConflictKind::Content);
+
+    // Nothing was merged
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), before);
+    assert!(is_clean(repo.clone()).await.unwrap());
+}
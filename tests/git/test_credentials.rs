@@ -0,0 +1,141 @@
+//! Tests for the credential cache.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use kodegen_tools_git::operations::credentials::{
+    AccessToken, CredentialCache, TokenFuture, TokenSource, disable_persistence,
+    enable_persistence, host_key_for_url, invalidate_credentials,
+};
+use kodegen_tools_git::git::fetch::FetchOpts;
+use kodegen_tools_git::{CloneOpts, clone_repo, fetch, init_repo};
+
+use super::support::{TokenSourceGuard, lock_globals};
+
+#[test]
+fn test_credential_cache_insert_and_invalidate() {
+    let mut cache = CredentialCache::new(Duration::from_secs(60));
+    cache.insert("https://github.com", "user".to_string(), "token".to_string());
+
+    let hit = cache.get("https://github.com").expect("credential should be cached");
+    assert_eq!(hit.username, "user");
+    assert_eq!(hit.password, "token");
+    assert_eq!(cache.hosts(), vec!["https://github.com".to_string()]);
+
+    assert!(cache.invalidate("https://github.com"));
+    assert!(cache.get("https://github.com").is_none());
+    assert!(!cache.invalidate("https://github.com"));
+}
+
+#[test]
+fn test_credential_cache_expiry() {
+    let mut cache = CredentialCache::new(Duration::ZERO);
+    cache.insert("https://github.com", "user".to_string(), "token".to_string());
+
+    assert!(cache.get("https://github.com").is_none());
+    assert!(cache.hosts().is_empty());
+}
+
+#[test]
+fn test_host_key_for_url() {
+    assert_eq!(
+        host_key_for_url("https://github.com/owner/repo.git"),
+        Some("https://github.com".to_string())
+    );
+    assert_eq!(
+        host_key_for_url("https://git.example.com:8443/repo.git"),
+        Some("https://git.example.com:8443".to_string())
+    );
+}
+
+#[test]
+fn test_credential_cache_token_expiry_caps_ttl() {
+    let mut cache = CredentialCache::new(Duration::from_secs(3600));
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock after epoch")
+        .as_secs();
+
+    // Token already expired: never served even though the TTL is long
+    cache.insert_until(
+        "https://github.com",
+        "x-access-token".to_string(),
+        "ghs_expired".to_string(),
+        Some(now.saturating_sub(1)),
+    );
+    assert!(cache.get("https://github.com").is_none());
+
+    cache.insert_until(
+        "https://github.com",
+        "x-access-token".to_string(),
+        "ghs_valid".to_string(),
+        Some(now + 600),
+    );
+    let hit = cache.get("https://github.com").expect("token should be cached");
+    assert_eq!(hit.password, "ghs_valid");
+    assert_eq!(hit.expires_at, now + 600);
+}
+
+/// Counts the tokens it mints
+struct CountingSource(AtomicUsize);
+
+impl TokenSource for CountingSource {
+    fn token<'a>(&'a self, host: &'a str) -> TokenFuture<'a> {
+        Box::pin(async move {
+            assert_eq!(host, "https://127.0.0.1:1");
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(AccessToken {
+                username: "x-access-token".to_string(),
+                token: "minted".to_string(),
+                expires_at: None,
+            }))
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_token_minted_before_connecting() {
+    // Nothing listens on port 1, so connecting fails without an auth error
+    let url = "https://127.0.0.1:1/repo.git";
+    let source = Arc::new(CountingSource(AtomicUsize::new(0)));
+    let _source = TokenSourceGuard::set(source.clone()).await;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path().join("repo")).await.unwrap().unwrap();
+    assert!(fetch(repo.clone(), FetchOpts::from_remote(url)).await.is_err());
+    assert_eq!(source.0.load(Ordering::SeqCst), 1);
+
+    // Served from the cache until invalidated
+    assert!(fetch(repo.clone(), FetchOpts::from_remote(url)).await.is_err());
+    assert_eq!(source.0.load(Ordering::SeqCst), 1);
+    invalidate_credentials(url);
+    let cloned = clone_repo(CloneOpts::new(url, temp_dir.path().join("clone"))).await.unwrap();
+    assert!(cloned.is_err());
+    assert_eq!(source.0.load(Ordering::SeqCst), 2);
+
+    invalidate_credentials(url);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_persisted_cache_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _lock = lock_globals().await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("credentials.bin");
+    let key = [7; 32];
+    let mode = |path: &std::path::Path| {
+        std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+    };
+
+    enable_persistence(&path, key).unwrap();
+    assert_eq!(mode(&path), 0o600);
+
+    // A file whose permissions were widened is replaced, not rewritten in place
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    enable_persistence(&path, key).unwrap();
+    disable_persistence();
+    assert_eq!(mode(&path), 0o600);
+}
@@ -0,0 +1,96 @@
+//! Tests for diff hunks
+
+use kodegen_tools_git::{DiffOpts, LineOrigin, diff, init_repo};
+use tempfile::TempDir;
+
+use super::support::{commit_file, write_commit};
+
+#[tokio::test]
+async fn test_diff_hunks() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    let lines: Vec<String> = (1..=20).map(|n| format!("line {n}\n")).collect();
+    let first = commit_file(&repo, "a.txt", &lines.concat()).await;
+    let mut changed = lines.clone();
+    changed[1] = "second\n".to_string();
+    changed[17] = "eighteenth\n".to_string();
+    let second = commit_file(&repo, "a.txt", &changed.concat()).await;
+
+    let stats = diff(
+        repo.clone(),
+        DiffOpts::new(first.to_string())
+            .to(second.to_string())
+            .hunks(true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(stats.files.len(), 1);
+    let file = &stats.files[0];
+    assert_eq!((file.additions, file.deletions), (2, 2));
+    assert_eq!(file.hunks.len(), 2);
+
+    let hunk = &file.hunks[0];
+    assert_eq!(hunk.header(), "@@ -1,5 +1,5 @@");
+    let origins: Vec<LineOrigin> = hunk.lines.iter().map(|line| line.origin).collect();
+    assert_eq!(
+        origins,
+        [
+            LineOrigin::Context,
+            LineOrigin::Deletion,
+            LineOrigin::Addition,
+            LineOrigin::Context,
+            LineOrigin::Context,
+            LineOrigin::Context,
+        ]
+    );
+    assert_eq!(hunk.lines[1].content, "line 2\n");
+    assert_eq!(hunk.lines[2].content, "second\n");
+    assert_eq!(file.hunks[1].header(), "@@ -15,6 +15,6 @@");
+    assert!(file.hunks[1].to_patch().contains("\n-line 18\n+eighteenth\n"));
+
+    // Hunks are only computed when asked for
+    let stats = diff(repo, DiffOpts::new(first.to_string()).to(second.to_string()))
+        .await
+        .unwrap();
+    assert!(stats.files[0].hunks.is_empty());
+}
+
+#[tokio::test]
+async fn test_diff_keeps_non_utf8_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let raw = repo.raw();
+    let first = write_commit(raw, &[], &[("a.txt", "a\n")], "first");
+
+    // A Latin-1 file name, as a legacy locale writes it
+    let name = gix::bstr::BStr::new(b"caf\xe9.txt");
+    let mut editor = raw
+        .edit_tree(raw.find_commit(first).unwrap().tree_id().unwrap())
+        .unwrap();
+    let blob = raw.write_blob(b"latin-1\n").unwrap().detach();
+    editor
+        .upsert(name, gix::object::tree::EntryKind::Blob, blob)
+        .unwrap();
+    let mut commit = raw
+        .find_commit(first)
+        .unwrap()
+        .decode()
+        .unwrap()
+        .into_owned();
+    commit.tree = editor.write().unwrap().detach();
+    commit.parents = vec![first].into();
+    let second = raw.write_object(&commit).unwrap().detach();
+
+    let stats = diff(
+        repo.clone(),
+        DiffOpts::new(first.to_string()).to(second.to_string()),
+    )
+    .await
+    .unwrap();
+    let file = &stats.files[0];
+    assert_eq!(file.path.as_bstr(), name);
+    assert!(!file.path.is_utf8());
+    assert_eq!(file.path.to_str(), None);
+    assert_eq!(file.path.to_string(), "caf\\351.txt");
+}
@@ -0,0 +1,50 @@
+//! Tests for line ending audit and renormalization
+
+use kodegen_tools_git::{LineEnding, eol_audit, init_repo, renormalize};
+use tempfile::TempDir;
+
+use super::support::commit_files;
+
+#[tokio::test]
+async fn test_eol_audit_and_renormalize() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    commit_files(
+        &repo,
+        &[
+            ("windows.txt", "one\r\ntwo\r\n"),
+            ("mixed.txt", "one\r\ntwo\n"),
+            ("unix.txt", "one\ntwo\n"),
+            ("data.bin", "\0\r\n"),
+        ],
+        "Files before .gitattributes",
+    )
+    .await;
+
+    // Without settings nothing is text
+    assert!(eol_audit(repo.clone()).await.unwrap().is_empty());
+
+    std::fs::write(temp_dir.path().join(".gitattributes"), "* text=auto\n").unwrap();
+    let issues = eol_audit(repo.clone()).await.unwrap();
+    let found: Vec<(&str, LineEnding, &str)> = issues
+        .iter()
+        .map(|issue| (issue.path.as_str(), issue.found, issue.setting.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("mixed.txt", LineEnding::Mixed, "text=auto"),
+            ("windows.txt", LineEnding::Crlf, "text=auto"),
+        ]
+    );
+
+    let renormalized = renormalize(repo.clone()).await.unwrap();
+    assert_eq!(renormalized, ["mixed.txt", "windows.txt"]);
+    assert!(eol_audit(repo.clone()).await.unwrap().is_empty());
+    // The working tree keeps its line endings
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("windows.txt")).unwrap(),
+        "one\r\ntwo\r\n"
+    );
+    assert!(renormalize(repo.clone()).await.unwrap().is_empty());
+}
@@ -0,0 +1,67 @@
+//! Tests for the classification of gix errors.
+
+use kodegen_tools_git::{BranchOpts, FetchOpts, GitError, branch, fetch, init_repo};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo};
+
+#[tokio::test]
+async fn test_missing_object_keeps_gix_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let missing = gix::ObjectId::from_hex(b"1111111111111111111111111111111111111111").unwrap();
+
+    let error = GitError::gix(repo.raw().find_object(missing).unwrap_err());
+    match &error {
+        GitError::ObjectNotFound { id, .. } => assert_eq!(*id, missing),
+        other => panic!("expected ObjectNotFound, got {other:?}"),
+    }
+    assert!(
+        error
+            .gix_source()
+            .and_then(|source| source.downcast_ref::<gix::object::find::existing::Error>())
+            .is_some()
+    );
+
+    // Already classified errors pass through unchanged
+    let error = GitError::gix(error);
+    assert!(matches!(error, GitError::ObjectNotFound { .. }), "{error}");
+    let error = GitError::gix(GitError::InvalidInput("bad".to_string()));
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+}
+
+#[tokio::test]
+async fn test_held_ref_lock_is_locked() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_file(&repo, "a.txt", "one\n").await;
+
+    let lock = repo.raw().git_dir().join("refs/heads/topic.lock");
+    std::fs::write(&lock, "").unwrap();
+    let error = branch(repo.clone(), BranchOpts::new("topic"))
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert!(matches!(error, GitError::Locked(_)), "{error:?}");
+
+    std::fs::remove_file(&lock).unwrap();
+    branch(repo, BranchOpts::new("topic"))
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_fetch_unknown_remote_is_remote_not_found() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_file(&repo, "a.txt", "one\n").await;
+
+    let error = fetch(repo, FetchOpts::from_remote("upstream"))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&error, GitError::RemoteNotFound(name) if name == "upstream"),
+        "{error:?}"
+    );
+}
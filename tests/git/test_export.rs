@@ -0,0 +1,94 @@
+//! Tests for exporting a revision into a plain directory
+
+use kodegen_tools_git::{ExportOverwrite, GitError, export_tree, init_repo};
+use tempfile::TempDir;
+
+use super::support::{commit_file, commit_files};
+
+#[tokio::test]
+async fn test_export_tree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_dir = temp_dir.path().join("repo");
+    std::fs::create_dir(&repo_dir).unwrap();
+    let repo = init_repo(&repo_dir).await.unwrap().unwrap();
+    let first = commit_files(
+        &repo,
+        &[("README.md", "v1\n"), ("src/main.rs", "fn main() {}\n")],
+        "initial",
+    )
+    .await;
+    commit_file(&repo, "README.md", "v2\n").await;
+
+    let dest = temp_dir.path().join("out");
+    let result = export_tree(
+        repo.clone(),
+        &first.to_string(),
+        &dest,
+        &[],
+        ExportOverwrite::Refuse,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.commit, Some(first));
+    assert_eq!(result.files, 2);
+    assert_eq!(
+        std::fs::read_to_string(dest.join("README.md")).unwrap(),
+        "v1\n"
+    );
+    assert!(dest.join("src/main.rs").is_file());
+    assert!(!dest.join(".git").exists());
+
+    // Existing files are refused unless overwriting
+    let error = export_tree(repo.clone(), "HEAD", &dest, &[], ExportOverwrite::Refuse)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+    assert_eq!(
+        std::fs::read_to_string(dest.join("README.md")).unwrap(),
+        "v1\n"
+    );
+
+    std::fs::write(dest.join("extra.txt"), "x").unwrap();
+    export_tree(
+        repo.clone(),
+        "HEAD",
+        &dest,
+        &["README.md"],
+        ExportOverwrite::Overwrite,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dest.join("README.md")).unwrap(),
+        "v2\n"
+    );
+    assert!(dest.join("extra.txt").exists());
+
+    let result = export_tree(repo.clone(), "HEAD", &dest, &["src/"], ExportOverwrite::Clean)
+        .await
+        .unwrap();
+    assert_eq!(result.files, 1);
+    assert!(dest.join("src/main.rs").is_file());
+    assert!(!dest.join("README.md").exists());
+    assert!(!dest.join("extra.txt").exists());
+
+    // Unknown paths and the repository itself are rejected
+    let other = temp_dir.path().join("other");
+    assert!(
+        export_tree(repo.clone(), "HEAD", &other, &["missing"], ExportOverwrite::Refuse)
+            .await
+            .is_err()
+    );
+    assert!(
+        export_tree(repo.clone(), "HEAD", &repo_dir, &[], ExportOverwrite::Overwrite)
+            .await
+            .is_err()
+    );
+    // So is a directory containing it, which cleaning would delete
+    assert!(
+        export_tree(repo.clone(), "HEAD", temp_dir.path(), &[], ExportOverwrite::Clean)
+            .await
+            .is_err()
+    );
+    assert!(repo_dir.join(".git").is_dir());
+}
@@ -1,6 +1,9 @@
 //! Tests for git fetch operation.
 
 use kodegen_tools_git::git::fetch::FetchOpts;
+use kodegen_tools_git::operations::auth::UrlRewrite;
+
+use super::support::{UrlRewriteGuard, commit_file, init_test_repo, with_identity};
 
 #[test]
 fn test_fetch_opts_builder() {
@@ -21,6 +24,7 @@ fn test_fetch_opts_default() {
     assert_eq!(opts.remote, "origin");
     assert!(opts.refspecs.is_empty());
     assert!(!opts.prune);
+    assert!(opts.deepen.is_none());
 }
 
 #[test]
@@ -32,3 +36,220 @@ fn test_fetch_opts_multiple_refspecs() {
     assert_eq!(opts.remote, "upstream");
     assert_eq!(opts.refspecs.len(), 2);
 }
+
+#[tokio::test]
+async fn test_fetch_reports_ref_updates() {
+    use kodegen_tools_git::{
+        BranchOpts, CloneOpts, RefUpdateKind, TagOpts, branch, clone_repo, create_tag,
+        delete_branch, fetch,
+    };
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_test_repo(&upstream_dir).await;
+    let first = commit_file(&upstream, "file.txt", "1\n").await;
+    branch(upstream.clone(), BranchOpts::new("topic".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let url = format!("file://{}", upstream_dir.display());
+    let cloned = clone_repo(CloneOpts::new(url, temp_dir.path().join("clone")))
+        .await
+        .unwrap()
+        .unwrap();
+    let main = cloned.default_branch.unwrap();
+    let repo = with_identity(&cloned.repo).await;
+
+    // Nothing changed upstream
+    let result = fetch(repo.clone(), FetchOpts::from_remote("origin")).await.unwrap();
+    assert!(result.is_up_to_date());
+
+    let second = commit_file(&upstream, "file.txt", "2\n").await;
+    create_tag(upstream.clone(), TagOpts::new("v1")).await.unwrap();
+    delete_branch(upstream.clone(), "topic".to_string(), true)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let opts = FetchOpts::from_remote("origin")
+        .add_refspec("refs/tags/*:refs/tags/*")
+        .prune(true);
+    let result = fetch(repo.clone(), opts).await.unwrap();
+    assert_eq!(result.updated.len(), 1);
+    let update = &result.updated[0];
+    assert_eq!(update.name, format!("refs/remotes/origin/{main}"));
+    assert_eq!(update.kind, RefUpdateKind::FastForward);
+    assert_eq!(update.old, Some(first));
+    assert_eq!(update.new, Some(second));
+    assert_eq!(result.new_tags, ["refs/tags/v1"]);
+    assert_eq!(result.pruned, ["refs/remotes/origin/topic"]);
+    assert!(result.objects_received > 0);
+    assert!(result.bytes_received > 0);
+}
+
+#[tokio::test]
+async fn test_fetch_applies_one_url_rewrite() {
+    use kodegen_tools_git::{
+        AddOpts, CloneOpts, CommitOpts, Signature, add, clone_repo, commit, fetch, init_repo,
+        open_repo,
+    };
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_repo(&upstream_dir).await.unwrap().unwrap();
+    std::fs::write(upstream_dir.join("file.txt"), "1\n").unwrap();
+    add(upstream.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+    commit(
+        upstream.clone(),
+        CommitOpts::message("1").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    let url = format!("file://{}", upstream_dir.display());
+    let clone_dir = temp_dir.path().join("clone");
+    clone_repo(CloneOpts::new(url.as_str(), &clone_dir)).await.unwrap().unwrap();
+
+    // Point origin at a URL that only resolves through a rewrite
+    let parent = format!("file://{}/", temp_dir.path().display());
+    let config_path = clone_dir.join(".git/config");
+    let config = std::fs::read_to_string(&config_path)
+        .unwrap()
+        .replace(&url, "https://example.invalid/upstream");
+    let with_rule = |base: &str| {
+        let rule = format!("[url \"{base}\"]\n\tinsteadOf = https://example.invalid/\n");
+        std::fs::write(&config_path, format!("{config}{rule}")).unwrap();
+    };
+
+    // Same prefix in config and registered: the registered rule wins
+    with_rule("file:///nonexistent/");
+    {
+        let _rules = UrlRewriteGuard::add([UrlRewrite::new(
+            parent.as_str(),
+            "https://example.invalid/",
+        )])
+        .await;
+        let repo = open_repo(&clone_dir).await.unwrap().unwrap();
+        fetch(repo, FetchOpts::from_remote("origin")).await.unwrap();
+    }
+
+    // A registered rule never rewrites the URL a config rule produced
+    with_rule(&parent);
+    let _rules =
+        UrlRewriteGuard::add([UrlRewrite::new("file:///nonexistent/", parent.as_str())]).await;
+    let repo = open_repo(&clone_dir).await.unwrap().unwrap();
+    fetch(repo, FetchOpts::from_remote("origin")).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_fetch_and_clone_verify() {
+    use kodegen_tools_git::{CloneOpts, clone_repo, fetch, init_repo};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_repo(&upstream_dir).await.unwrap().unwrap();
+    commit_file(&upstream, "file.txt", "1\n").await;
+
+    let url = format!("file://{}", upstream_dir.display());
+    let cloned = clone_repo(CloneOpts::new(url, temp_dir.path().join("clone")).verify(true))
+        .await
+        .unwrap()
+        .unwrap();
+    let report = cloned.verification.unwrap();
+    assert!(report.is_ok(), "{report}");
+    assert!(report.pack_checked);
+    // Commit, tree and blob
+    assert_eq!(report.objects_checked, 3);
+
+    commit_file(&upstream, "file.txt", "2\n").await;
+    commit_file(&upstream, "other.txt", "3\n").await;
+    let opts = FetchOpts::from_remote("origin").verify(true);
+    let result = fetch(cloned.repo.clone(), opts).await.unwrap();
+    let report = result.verification.unwrap();
+    assert!(report.is_ok(), "{report}");
+    assert!(report.withheld_refs.is_empty());
+    assert!(report.objects_checked >= 2);
+
+    // Without verification there is no report
+    let result = fetch(cloned.repo.clone(), FetchOpts::from_remote("origin"))
+        .await
+        .unwrap();
+    assert!(result.verification.is_none());
+}
+
+#[tokio::test]
+async fn test_fetch_verify_failure_changes_nothing() {
+    use gix::refs::transaction::PreviousValue;
+    use kodegen_tools_git::{GitError, fetch};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_test_repo(&upstream_dir).await;
+    let first = commit_file(&upstream, "a.txt", "a\n").await;
+
+    // The local copy of the first commit lacks its blob, which the server
+    // then assumes it has
+    let local_dir = temp_dir.path().join("local");
+    std::fs::create_dir(&local_dir).unwrap();
+    let local = init_test_repo(&local_dir).await;
+    let commit = upstream.raw().find_commit(first).unwrap();
+    let tree = commit.tree().unwrap();
+    let blob = tree.find_entry("a.txt").unwrap().oid().to_owned();
+    let raw = local.raw();
+    raw.write_object(tree.decode().unwrap()).unwrap();
+    raw.write_object(commit.decode().unwrap()).unwrap();
+    let branch = upstream.raw().head_name().unwrap().unwrap();
+    let tracking = format!("refs/remotes/origin/{}", branch.shorten());
+    raw.reference(
+        tracking.as_str(),
+        first,
+        PreviousValue::MustNotExist,
+        "test",
+    )
+    .unwrap();
+    let config_path = local_dir.join(".git/config");
+    let mut config = std::fs::read_to_string(&config_path).unwrap();
+    config.push_str(&format!(
+        "[remote \"origin\"]\n\turl = file://{}\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n",
+        upstream_dir.display()
+    ));
+    std::fs::write(&config_path, config).unwrap();
+
+    let second = commit_file(&upstream, "b.txt", "b\n").await;
+    let repo = kodegen_tools_git::open_repo(&local_dir)
+        .await
+        .unwrap()
+        .unwrap();
+    let result = fetch(repo.clone(), FetchOpts::from_remote("origin").verify(true)).await;
+    let Err(GitError::VerificationFailed(report)) = result else {
+        panic!("expected verification to fail, got {result:?}");
+    };
+    assert_eq!(report.missing, [blob]);
+    assert_eq!(report.withheld_refs, std::slice::from_ref(&tracking));
+
+    // Neither the pack nor the ref update made it into the repository
+    let reopened = kodegen_tools_git::open_repo(&local_dir)
+        .await
+        .unwrap()
+        .unwrap();
+    let raw = reopened.raw();
+    assert!(!raw.has_object(second));
+    let mut reference = raw.find_reference(tracking.as_str()).unwrap();
+    assert_eq!(reference.peel_to_id().unwrap(), first);
+    let leftovers: Vec<_> = std::fs::read_dir(local_dir.join(".git/objects"))
+        .unwrap()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("tmp_objdir")
+        })
+        .collect();
+    assert!(leftovers.is_empty());
+}
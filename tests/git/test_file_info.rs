@@ -0,0 +1,35 @@
+//! Tests for file existence and change checks at revisions.
+
+use kodegen_tools_git::{
+    AddOpts, CommitOpts, Signature, add, commit, file_changed_between, file_exists_at, init_repo,
+};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_file_exists_and_changed() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let author = Signature::new("Test", "test@example.com");
+
+    std::fs::write(temp_dir.path().join("a.txt"), "one\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    let first = commit(repo.clone(), CommitOpts::message("first").author(author.clone()))
+        .await
+        .unwrap();
+
+    std::fs::write(temp_dir.path().join("b.txt"), "two\n").unwrap();
+    add(repo.clone(), AddOpts::new(["b.txt"])).await.unwrap();
+    commit(repo.clone(), CommitOpts::message("second").author(author))
+        .await
+        .unwrap();
+
+    let first = first.id.to_string();
+
+    assert!(file_exists_at(repo.clone(), "HEAD", "a.txt").await.unwrap());
+    assert!(file_exists_at(repo.clone(), "HEAD", "b.txt").await.unwrap());
+    assert!(!file_exists_at(repo.clone(), &first, "b.txt").await.unwrap());
+
+    assert!(!file_changed_between(repo.clone(), &first, "HEAD", "a.txt").await.unwrap());
+    assert!(file_changed_between(repo.clone(), &first, "HEAD", "b.txt").await.unwrap());
+    assert!(!file_changed_between(repo.clone(), &first, "HEAD", "missing.txt").await.unwrap());
+}
@@ -0,0 +1,55 @@
+//! Tests for content search across tracked files
+
+use kodegen_tools_git::{GrepOpts, grep, init_repo};
+use tempfile::TempDir;
+
+use super::support::commit_files;
+
+#[tokio::test]
+async fn test_grep_worktree_and_revision() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let files = [
+        ("src/lib.rs", "fn main() {}\n// TODO: tests\n"),
+        ("README.md", "todo list\n"),
+        ("data.bin", "TODO\0binary"),
+    ];
+    commit_files(&repo, &files, "Add files").await;
+    // Untracked files are never searched
+    std::fs::write(temp_dir.path().join("notes.txt"), "TODO\n").unwrap();
+    // Uncommitted edits are
+    std::fs::write(temp_dir.path().join("README.md"), "TODO: update\n").unwrap();
+
+    let found = grep(repo.clone(), GrepOpts::new("TODO")).await.unwrap();
+    let hits: Vec<_> = found
+        .matches
+        .iter()
+        .map(|hit| (hit.path.as_str(), hit.line_number))
+        .collect();
+    assert_eq!(hits, [("README.md", 1), ("src/lib.rs", 2)]);
+    assert_eq!(found.files_searched, 2);
+
+    // The committed README still says "todo"
+    let committed = grep(repo.clone(), GrepOpts::new("todo").ignore_case(true).rev("HEAD"))
+        .await
+        .unwrap();
+    assert_eq!(committed.matches.len(), 2);
+    assert_eq!(committed.matches[0].line, "todo list");
+
+    let scoped = grep(repo.clone(), GrepOpts::new("TODO").path("src")).await.unwrap();
+    assert_eq!(scoped.matches.len(), 1);
+    assert_eq!(scoped.matches[0].path, "src/lib.rs");
+}
+
+#[tokio::test]
+async fn test_grep_stops_at_max_matches() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    commit_files(&repo, &[("many.txt", "x\nx\nx\n")], "Add files").await;
+
+    let found = grep(repo.clone(), GrepOpts::new("x").max_matches(2)).await.unwrap();
+    assert_eq!(found.matches.len(), 2);
+    assert!(found.truncated);
+
+    assert!(grep(repo.clone(), GrepOpts::new("(")).await.is_err());
+}
@@ -0,0 +1,56 @@
+//! Tests for .gitignore editing
+
+use kodegen_tools_git::{add_patterns, init_repo, list_patterns, remove_patterns};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_add_and_remove_patterns_keep_comments() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let gitignore = temp_dir.path().join(".gitignore");
+    std::fs::write(&gitignore, "# editor files\n*.swp").unwrap();
+
+    let edit = add_patterns(repo.clone(), "", ["target/", "*.swp", "target/"], Some("Build output"))
+        .await
+        .unwrap();
+    assert_eq!(edit.changed, ["target/"]);
+    assert_eq!(
+        std::fs::read_to_string(&gitignore).unwrap(),
+        "# editor files\n*.swp\n\n# Build output\ntarget/\n"
+    );
+
+    // Adding again changes nothing
+    let again = add_patterns(repo.clone(), "", ["target/"], Some("Build output")).await.unwrap();
+    assert!(again.changed.is_empty());
+
+    let removed = remove_patterns(repo.clone(), "", ["*.swp", "missing"]).await.unwrap();
+    assert_eq!(removed.changed, ["*.swp"]);
+    assert_eq!(
+        std::fs::read_to_string(&gitignore).unwrap(),
+        "# editor files\n\n# Build output\ntarget/\n"
+    );
+
+    let patterns = list_patterns(repo.clone(), "").await.unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!((patterns[0].line, patterns[0].pattern.as_str()), (4, "target/"));
+}
+
+#[tokio::test]
+async fn test_nested_gitignore() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    std::fs::create_dir(temp_dir.path().join("web")).unwrap();
+
+    let edit = add_patterns(repo.clone(), "web", ["node_modules/", "!keep.js"], None)
+        .await
+        .unwrap();
+    assert_eq!(edit.file, std::path::Path::new("web/.gitignore"));
+    assert!(!temp_dir.path().join(".gitignore").exists());
+
+    let patterns = list_patterns(repo.clone(), "web").await.unwrap();
+    assert!(patterns[1].negated);
+
+    assert!(add_patterns(repo.clone(), "../outside", ["x"], None).await.is_err());
+    assert!(add_patterns(repo.clone(), "missing", ["x"], None).await.is_err());
+    assert!(add_patterns(repo.clone(), "web", ["#not-a-pattern"], None).await.is_err());
+}
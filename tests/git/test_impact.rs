@@ -0,0 +1,55 @@
+//! Tests for commit impact analysis
+
+use kodegen_tools_git::{ImpactRule, impacted_paths, init_repo};
+
+use super::support::commit_files;
+
+#[tokio::test]
+async fn test_impacted_paths_maps_rules() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    let base = commit_files(&repo, &[("README.md", "readme\n")], "readme").await;
+    commit_files(&repo, &[("src/operations/log.rs", "log\n")], "operation").await;
+    let files = [
+        ("src/tools/log.rs", "tool\n"),
+        ("tests/git/test_log.rs", "test\n"),
+        ("x.txt", "x\n"),
+    ];
+    let tip = commit_files(&repo, &files, "tool and test").await;
+
+    let rules = [
+        ImpactRule::new("src/operations/**", "crate:kodegen-tools-git").unwrap(),
+        ImpactRule::new("src/", "crate:kodegen-tools-git").unwrap(),
+        ImpactRule::new("tests/", "job:integration").unwrap(),
+        ImpactRule::new("*.md", "job:docs").unwrap(),
+    ];
+
+    let report = impacted_paths(repo.clone(), &format!("{base}..{tip}"), &rules)
+        .await
+        .unwrap();
+    assert_eq!(
+        report.changed_paths,
+        [
+            "src/operations/log.rs",
+            "src/tools/log.rs",
+            "tests/git/test_log.rs",
+            "x.txt"
+        ]
+    );
+    assert_eq!(
+        report.target_names(),
+        ["crate:kodegen-tools-git", "job:integration"]
+    );
+    assert_eq!(
+        report.targets[0].paths,
+        ["src/operations/log.rs", "src/tools/log.rs"]
+    );
+    assert_eq!(report.unmatched_paths, ["x.txt"]);
+
+    // A single revision is the change of that commit alone
+    let report = impacted_paths(repo.clone(), &base.to_string(), &rules).await.unwrap();
+    assert_eq!(report.target_names(), ["job:docs"]);
+
+    assert!(ImpactRule::new("src/**", "").is_err());
+}
@@ -0,0 +1,46 @@
+//! Tests for temporary index files
+
+use gix::bstr::ByteSlice;
+use kodegen_tools_git::{AddOpts, CommitOpts, IndexSeed, add, commit, temporary_index};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo};
+
+#[tokio::test]
+async fn test_temporary_indexes_stage_independently() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_file(&repo, "a.txt", "a\n").await;
+    std::fs::write(temp_dir.path().join("b.txt"), "b\n").unwrap();
+    std::fs::write(temp_dir.path().join("c.txt"), "c\n").unwrap();
+
+    let first = temporary_index(repo.clone(), IndexSeed::Head).await.unwrap();
+    let second = temporary_index(repo.clone(), IndexSeed::Head).await.unwrap();
+    assert_ne!(first.path(), second.path());
+
+    add(first.repo().clone(), AddOpts::new(["b.txt"]))
+        .await
+        .unwrap();
+    add(second.repo().clone(), AddOpts::new(["c.txt"]))
+        .await
+        .unwrap();
+    let result = commit(first.repo().clone(), CommitOpts::message("Add b"))
+        .await
+        .unwrap();
+
+    let tree = repo.raw().find_tree(result.tree).unwrap();
+    let names: Vec<String> = tree
+        .iter()
+        .map(|entry| entry.unwrap().filename().to_str_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["a.txt", "b.txt"]);
+
+    // Neither the repository's index nor the other temporary index saw b.txt
+    let index = repo.raw().index_or_empty().unwrap();
+    assert!(index.entry_by_path("b.txt".as_bytes().as_bstr()).is_none());
+    assert!(index.entry_by_path("c.txt".as_bytes().as_bstr()).is_none());
+
+    let path = first.path().to_path_buf();
+    drop(first);
+    assert!(!path.exists());
+}
@@ -0,0 +1,62 @@
+//! Tests for repository hygiene checks
+
+use kodegen_tools_git::operations::lint::check;
+use kodegen_tools_git::{LintCheck, LintRules, LintSeverity, init_repo};
+use tempfile::TempDir;
+
+use super::support::{commit_file, commit_files};
+
+#[tokio::test]
+async fn test_lint_reports_hygiene_problems() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    commit_files(
+        &repo,
+        &[("README.md", "# app\n"), ("Docs/a.md", "a\n"), ("docs/b.md", "b\n")],
+        "Add docs",
+    )
+    .await;
+    commit_file(&repo, "big.bin", &"x".repeat(2048)).await;
+
+    let rules = LintRules::new()
+        .only([
+            LintCheck::MissingLicense,
+            LintCheck::MissingReadme,
+            LintCheck::LargeFile,
+            LintCheck::MissingSignoff,
+            LintCheck::CaseConflict,
+        ])
+        .max_file_size(1024);
+    let report = check(repo.clone(), rules).await.unwrap();
+
+    let found: Vec<(LintCheck, &str)> = report
+        .findings
+        .iter()
+        .map(|finding| (finding.check, finding.subject.as_str()))
+        .collect();
+    assert!(found.contains(&(LintCheck::MissingLicense, "")));
+    assert!(!found.iter().any(|(check, _)| *check == LintCheck::MissingReadme));
+    assert!(found.contains(&(LintCheck::LargeFile, "big.bin")));
+    assert!(found.contains(&(LintCheck::CaseConflict, "Docs")));
+    assert_eq!(
+        found.iter().filter(|(check, _)| *check == LintCheck::MissingSignoff).count(),
+        2
+    );
+    assert_eq!(report.at_least(LintSeverity::Error).count(), 1);
+}
+
+#[tokio::test]
+async fn test_lint_clean_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    commit_files(
+        &repo,
+        &[("LICENSE", "MIT\n"), ("README.md", "# app\n")],
+        "Initial commit\n\nSigned-off-by: Author <author@example.com>",
+    )
+    .await;
+
+    let report = check(repo.clone(), LintRules::new()).await.unwrap();
+    assert!(report.is_clean(), "{:?}", report.findings);
+    assert_eq!(report.checks.len(), LintCheck::ALL.len());
+}
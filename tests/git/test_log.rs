@@ -60,3 +60,276 @@ fn test_log_opts_with_pathbuf() {
 
     assert_eq!(opts.path, Some(path));
 }
+
+#[tokio::test]
+async fn test_history_with_shared_object_cache() {
+    use kodegen_tools_git::{
+        AddOpts, CommitOpts, HistoryOpts, HistoryResult, Signature, add, commit, history,
+        init_repo,
+    };
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path())
+        .await
+        .unwrap()
+        .unwrap()
+        .with_object_cache(1024 * 1024)
+        .with_pack_cache(1024 * 1024);
+
+    for content in ["one\n", "one\ntwo\n"] {
+        std::fs::write(temp_dir.path().join("file.txt"), content).unwrap();
+        add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+        commit(
+            repo.clone(),
+            CommitOpts::message(content.trim()).author(Signature::new("Test", "test@example.com")),
+        )
+        .await
+        .unwrap();
+    }
+
+    // Run twice: the second walk reads from the cache filled by the first
+    for _ in 0..2 {
+        let result = history(repo.clone(), HistoryOpts::new("file.txt")).await.unwrap();
+        let HistoryResult::Commits { commits, .. } = result else {
+            panic!("expected per-commit history");
+        };
+        assert_eq!(commits.len(), 2);
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_history_keeps_non_utf8_paths() {
+    use std::os::unix::ffi::OsStrExt;
+
+    use kodegen_tools_git::{HistoryOpts, HistoryResult, history, init_repo};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let raw = repo.raw();
+    let first = super::support::write_commit(raw, &[], &[("a.txt", "a\n")], "first");
+
+    // A Latin-1 file name, as a legacy locale writes it
+    let name = b"caf\xe9.txt";
+    let mut editor = raw
+        .edit_tree(raw.find_commit(first).unwrap().tree_id().unwrap())
+        .unwrap();
+    let blob = raw.write_blob(b"latin-1\n").unwrap().detach();
+    editor
+        .upsert(
+            gix::bstr::BStr::new(name),
+            gix::object::tree::EntryKind::Blob,
+            blob,
+        )
+        .unwrap();
+    let mut commit = raw
+        .find_commit(first)
+        .unwrap()
+        .decode()
+        .unwrap()
+        .into_owned();
+    commit.tree = editor.write().unwrap().detach();
+    commit.parents = vec![first].into();
+    let second = raw.write_object(&commit).unwrap().detach();
+
+    let path = std::ffi::OsStr::from_bytes(name);
+    let opts = HistoryOpts::new(path).since(second.to_string());
+    let HistoryResult::Commits { file, commits, .. } = history(repo.clone(), opts).await.unwrap()
+    else {
+        panic!("expected per-commit history");
+    };
+    assert_eq!(commits.len(), 1);
+    assert_eq!(file.as_bstr(), gix::bstr::BStr::new(name));
+    assert!(!file.is_utf8());
+    assert_eq!(file.to_string(), "caf\\351.txt");
+}
+
+#[tokio::test]
+async fn test_log_decorations() {
+    use kodegen_tools_git::{
+        AddOpts, CommitOpts, DecorationKind, Signature, TagOpts, add, commit, create_tag, log,
+    };
+    use tokio_stream::StreamExt;
+
+    use super::support::init_test_repo;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+
+    for content in ["one\n", "one\ntwo\n"] {
+        std::fs::write(temp_dir.path().join("file.txt"), content).unwrap();
+        add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+        commit(
+            repo.clone(),
+            CommitOpts::message(content.trim()).author(Signature::new("Test", "test@example.com")),
+        )
+        .await
+        .unwrap();
+        if content == "one\n" {
+            create_tag(repo.clone(), TagOpts::new("v1").message("first release")).await.unwrap();
+        }
+    }
+
+    let mut stream = log(repo.clone(), LogOpts::new().decorate(true), None);
+    let mut commits = Vec::new();
+    while let Some(entry) = stream.next().await {
+        commits.push(entry.unwrap());
+    }
+    assert_eq!(commits.len(), 2);
+    let head: Vec<_> = commits[0].decorations.iter().map(|d| d.kind).collect();
+    assert_eq!(head, [DecorationKind::Head, DecorationKind::LocalBranch]);
+    let tagged: Vec<_> = commits[1].decorations.iter().map(ToString::to_string).collect();
+    assert_eq!(tagged, ["tag: v1"]);
+
+    // Not decorated unless asked for
+    let mut stream = log(repo, LogOpts::new(), None);
+    while let Some(entry) = stream.next().await {
+        assert!(entry.unwrap().decorations.is_empty());
+    }
+}
+
+#[test]
+fn test_render_graph_merge() {
+    use kodegen_tools_git::{CommitInfo, Signature, render_graph};
+
+    let id = |n: u8| gix::ObjectId::from_hex(format!("{n:040}").as_bytes()).unwrap();
+    let info = |n: u8, parents: &[u8]| CommitInfo {
+        id: id(n),
+        parents: parents.iter().map(|&p| id(p)).collect(),
+        author: Signature::new("Test", "test@example.com"),
+        summary: format!("c{n}"),
+        time: Utc::now(),
+        offset: chrono::FixedOffset::east_opt(0).unwrap(),
+        shallow_boundary: false,
+        decorations: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    // 4 merges 3 into 2; both branched off 1
+    let commits = [info(4, &[2, 3]), info(2, &[1]), info(3, &[1]), info(1, &[])];
+    let lines = render_graph(&commits);
+    let short = |n: u8| id(n).to_hex_with_len(7).to_string();
+    assert_eq!(lines, [
+        format!("* {} c4", short(4)),
+        "|\\".to_string(),
+        format!("* | {} c2", short(2)),
+        format!("| * {} c3", short(3)),
+        "|/".to_string(),
+        format!("* {} c1", short(1)),
+    ]);
+}
+
+#[tokio::test]
+async fn test_log_skip_and_max_count() {
+    use kodegen_tools_git::{AddOpts, CommitOpts, Signature, add, commit, init_repo, log};
+    use tokio_stream::StreamExt;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    for n in 1..=5 {
+        std::fs::write(temp_dir.path().join("file.txt"), n.to_string()).unwrap();
+        add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+        commit(
+            repo.clone(),
+            CommitOpts::message(format!("c{n}")).author(Signature::new("Test", "test@example.com")),
+        )
+        .await
+        .unwrap();
+    }
+
+    let mut stream = log(repo.clone(), LogOpts::new().skip(1).max_count(2), None);
+    let mut summaries = Vec::new();
+    while let Some(entry) = stream.next().await {
+        summaries.push(entry.unwrap().summary);
+    }
+    assert_eq!(summaries, ["c4", "c3"]);
+
+    // Reading only the first commit and dropping the stream ends the walk
+    let mut stream = log(repo, LogOpts::new(), None);
+    assert_eq!(stream.next().await.unwrap().unwrap().summary, "c5");
+    drop(stream);
+}
+
+#[tokio::test]
+async fn test_log_path_filter() {
+    use kodegen_tools_git::{AddOpts, CommitOpts, Signature, add, commit, init_repo, log};
+    use tokio_stream::StreamExt;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    let changes: [(&str, &[(&str, &str)]); 3] = [
+        ("add both", &[("a.txt", "a1\n"), ("b.txt", "b1\n")]),
+        ("change b", &[("b.txt", "b2\n")]),
+        ("change both", &[("a.txt", "a2\n"), ("b.txt", "b3\n")]),
+    ];
+    for (message, files) in changes {
+        for (name, content) in files {
+            std::fs::write(temp_dir.path().join(name), content).unwrap();
+        }
+        add(repo.clone(), AddOpts::new(files.iter().map(|(name, _)| *name)))
+            .await
+            .unwrap();
+        commit(
+            repo.clone(),
+            CommitOpts::message(message).author(Signature::new("Test", "test@example.com")),
+        )
+        .await
+        .unwrap();
+    }
+
+    // The diff stops at the first matching change; that must still count
+    for (path, expected) in [
+        ("a.txt", vec!["change both", "add both"]),
+        ("b.txt", vec!["change both", "change b", "add both"]),
+    ] {
+        let opts = LogOpts::new().path(path);
+        let mut stream = log(repo.clone(), opts, Some(temp_dir.path()));
+        let mut summaries = Vec::new();
+        while let Some(entry) = stream.next().await {
+            summaries.push(entry.unwrap().summary);
+        }
+        assert_eq!(summaries, expected, "{path}");
+    }
+}
+
+#[tokio::test]
+async fn test_log_reports_unreadable_time_as_warning() {
+    use kodegen_tools_git::{WarningKind, log};
+    use tokio_stream::StreamExt;
+
+    use super::support::{commit_file, init_test_repo};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let parent = commit_file(&repo, "a.txt", "one\n").await;
+
+    // An offset beyond a day can't be represented, so the time can't be read
+    let raw = repo.raw();
+    let parent_commit = raw.find_commit(parent).unwrap();
+    let mut signature = parent_commit.author().unwrap().to_owned().unwrap();
+    signature.time = gix::date::Time::new(1_700_000_000, 100_000);
+    let commit = gix::objs::Commit {
+        tree: parent_commit.tree_id().unwrap().detach(),
+        parents: [parent].into_iter().collect(),
+        author: signature.clone(),
+        committer: signature,
+        encoding: None,
+        message: "far offset".into(),
+        extra_headers: Vec::new(),
+    };
+    let id = raw.write_object(&commit).unwrap().detach();
+    raw.reference("HEAD", id, gix::refs::transaction::PreviousValue::Any, "far offset")
+        .unwrap();
+
+    // The commit is still listed, with the problem next to it
+    let commits: Vec<_> = log(repo.clone(), LogOpts::new(), None).collect().await;
+    let newest = commits[0].as_ref().unwrap();
+    assert_eq!(newest.id, id);
+    assert_eq!(newest.time, DateTime::<Utc>::default());
+    assert_eq!(newest.warnings.len(), 2, "{:?}", newest.warnings);
+    assert_eq!(newest.warnings[0].kind, WarningKind::MissingTimestamp);
+    assert_eq!(newest.warnings[1].kind, WarningKind::UnparsableSignature);
+    assert_eq!(newest.author.name, "");
+    assert!(commits[1].as_ref().unwrap().warnings.is_empty());
+}
@@ -0,0 +1,38 @@
+//! Tests for stale lock cleanup.
+
+use std::time::{Duration, SystemTime};
+
+use kodegen_tools_git::{clear_stale_locks, find_locks, init_repo};
+use tempfile::TempDir;
+
+fn write_lock(path: &std::path::Path, age: Duration) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let file = std::fs::File::create(path).unwrap();
+    file.set_modified(SystemTime::now() - age).unwrap();
+}
+
+#[tokio::test]
+async fn test_clear_stale_locks() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let git_dir = temp_dir.path().join(".git");
+
+    let old_index = git_dir.join("index.lock");
+    let old_ref = git_dir.join("refs/heads/main.lock");
+    let fresh_head = git_dir.join("HEAD.lock");
+    write_lock(&old_index, Duration::from_secs(3600));
+    write_lock(&old_ref, Duration::from_secs(3600));
+    write_lock(&fresh_head, Duration::ZERO);
+
+    let locks = find_locks(repo.clone()).await.unwrap();
+    assert_eq!(locks.len(), 3);
+
+    let report = clear_stale_locks(repo.clone(), Duration::from_secs(600))
+        .await
+        .unwrap();
+    assert_eq!(report.removed.len(), 2);
+    assert_eq!(report.kept.len(), 1);
+    assert!(!old_index.exists());
+    assert!(!old_ref.exists());
+    assert!(fresh_head.exists());
+}
@@ -1,8 +1,11 @@
 //! Tests for git merge operation.
 
+use gix::refs::transaction::PreviousValue;
 use kodegen_tools_git::CommitId;
 use kodegen_tools_git::git::merge::{MergeOpts, MergeOutcome};
 
+use super::support::{commit_file, init_test_repo, write_commit};
+
 #[test]
 fn test_merge_outcome_equality() {
     let commit_id = CommitId::null(gix::hash::Kind::Sha1);
@@ -80,3 +83,232 @@ fn test_merge_opts_with_tag() {
     assert!(opts.squash);
     assert!(!opts.commit);
 }
+
+#[cfg(feature = "testkit")]
+#[tokio::test]
+async fn test_merge_conflict_leaves_repo_untouched() {
+    use kodegen_tools_git::testkit::{RepoSpec, fabricate_merge_conflict, generate};
+    use kodegen_tools_git::{GitError, head_commit, is_clean, merge};
+
+    let fixture = generate(RepoSpec::new(3).commits(2)).await.unwrap();
+    let scenario = fabricate_merge_conflict(fixture.repo.clone(), "readme").await.unwrap();
+    assert_eq!(head_commit(fixture.repo.clone()).await.unwrap(), scenario.ours.to_string());
+
+    let result = merge(fixture.repo.clone(), MergeOpts::new(&scenario.theirs)).await;
+    let Err(GitError::MergeConflict { paths, .. }) = result else {
+        panic!("expected a merge conflict");
+    };
+    assert_eq!(paths, std::slice::from_ref(&scenario.path));
+
+    assert_eq!(head_commit(fixture.repo.clone()).await.unwrap(), scenario.ours.to_string());
+    assert!(is_clean(fixture.repo.clone()).await.unwrap());
+}
+
+#[cfg(feature = "testkit")]
+#[tokio::test]
+async fn test_merge_conflict_resolved_on_branch() {
+    use kodegen_tools_git::testkit::{
+        RepoSpec, conflict_content, fabricate_merge_conflict, generate,
+    };
+    use kodegen_tools_git::{
+        AddOpts, CheckoutOpts, CommitOpts, Signature, add, checkout, commit, current_branch,
+        merge,
+    };
+
+    let fixture = generate(RepoSpec::new(4).commits(2)).await.unwrap();
+    let main = current_branch(fixture.repo.clone()).await.unwrap().name;
+    let scenario = fabricate_merge_conflict(fixture.repo.clone(), "config").await.unwrap();
+
+    // Resolve by taking HEAD's version on the other branch
+    checkout(fixture.repo.clone(), CheckoutOpts::new(&scenario.theirs))
+        .await
+        .unwrap();
+    let file = fixture.path().join(&scenario.path);
+    std::fs::write(&file, conflict_content("ours")).unwrap();
+    add(fixture.repo.clone(), AddOpts::new([scenario.path.as_str()]))
+        .await
+        .unwrap();
+    commit(
+        fixture.repo.clone(),
+        CommitOpts::message("take ours").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+    checkout(fixture.repo.clone(), CheckoutOpts::new(&main))
+        .await
+        .unwrap();
+
+    let outcome = merge(fixture.repo.clone(), MergeOpts::new(&scenario.theirs))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, MergeOutcome::MergeCommit(_)));
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), conflict_content("ours"));
+}
+
+#[tokio::test]
+async fn test_squash_merge_onto_checked_out_branch() {
+    use kodegen_tools_git::{
+        BranchOpts, CheckoutOpts, branch, checkout, current_branch, head_commit, is_clean,
+        squash_merge,
+    };
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_file(&repo, "base.txt", "base\n").await;
+    let main = current_branch(repo.clone()).await.unwrap().name;
+
+    branch(repo.clone(), BranchOpts::new("feature").checkout(true))
+        .await
+        .unwrap()
+        .unwrap();
+    commit_file(&repo, "feature.txt", "one\n").await;
+    commit_file(&repo, "feature.txt", "one\ntwo\n").await;
+
+    checkout(repo.clone(), CheckoutOpts::new(&main)).await.unwrap();
+    commit_file(&repo, "main.txt", "main\n").await;
+    let previous = head_commit(repo.clone()).await.unwrap();
+
+    let result = squash_merge(repo.clone(), &main, "feature", "Squash feature")
+        .await
+        .unwrap();
+    assert_eq!(result.squashed_commits, 2);
+    assert_eq!(result.previous_base.to_string(), previous);
+    assert!(result.worktree_updated);
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), result.commit_id.to_string());
+
+    let commit = repo.raw().find_commit(result.commit_id).unwrap();
+    assert_eq!(commit.parent_ids().count(), 1);
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("feature.txt")).unwrap(),
+        "one\ntwo\n"
+    );
+    assert!(temp_dir.path().join("main.txt").exists());
+    assert!(is_clean(repo.clone()).await.unwrap());
+
+    // Squashing again has nothing left to add
+    assert!(squash_merge(repo.clone(), &main, "feature", "again").await.is_err());
+}
+
+#[tokio::test]
+async fn test_squash_merge_onto_other_branch_leaves_worktree() {
+    use kodegen_tools_git::{BranchOpts, branch, current_branch, head_commit, squash_merge};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_file(&repo, "base.txt", "base\n").await;
+    branch(repo.clone(), BranchOpts::new("release")).await.unwrap().unwrap();
+    commit_file(&repo, "work.txt", "work\n").await;
+    let main = current_branch(repo.clone()).await.unwrap().name;
+    let head = head_commit(repo.clone()).await.unwrap();
+
+    let result = squash_merge(repo.clone(), "release", &main, "Squash into release")
+        .await
+        .unwrap();
+    assert_eq!(result.base_ref, "refs/heads/release");
+    assert_eq!(result.squashed_commits, 1);
+    assert!(!result.worktree_updated);
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), head);
+
+    let release = repo
+        .raw()
+        .find_reference("refs/heads/release")
+        .unwrap()
+        .peel_to_id()
+        .unwrap()
+        .detach();
+    assert_eq!(release, result.commit_id);
+}
+
+#[tokio::test]
+async fn test_squash_merge_keeps_untracked_files() {
+    use kodegen_tools_git::{current_branch, head_commit, squash_merge};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "base.txt", "base\n").await;
+    let main = current_branch(repo.clone()).await.unwrap().name;
+    let head = head_commit(repo.clone()).await.unwrap();
+
+    let feature = write_commit(
+        repo.raw(),
+        &[base],
+        &[("feature.txt", "feature\n"), ("docs/guide.md", "guide\n")],
+        "feature",
+    );
+    repo.raw()
+        .reference(
+            "refs/heads/feature",
+            feature,
+            PreviousValue::MustNotExist,
+            "test",
+        )
+        .unwrap();
+
+    // Untracked files where the squashed tree has a file, and where it has a directory
+    let file = temp_dir.path().join("feature.txt");
+    std::fs::write(&file, "mine\n").unwrap();
+    std::fs::write(temp_dir.path().join("docs"), "notes\n").unwrap();
+
+    let err = squash_merge(repo.clone(), &main, "feature", "Squash feature")
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("feature.txt"), "{err}");
+    assert!(err.contains("docs"), "{err}");
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "mine\n");
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), head);
+}
+
+#[cfg(feature = "testkit")]
+#[tokio::test]
+async fn test_squash_merge_conflict_reports_path() {
+    use kodegen_tools_git::testkit::{RepoSpec, fabricate_merge_conflict, generate};
+    use kodegen_tools_git::{GitError, current_branch, head_commit, squash_merge};
+
+    let fixture = generate(RepoSpec::new(6).commits(2)).await.unwrap();
+    let main = current_branch(fixture.repo.clone()).await.unwrap().name;
+    let scenario = fabricate_merge_conflict(fixture.repo.clone(), "squash").await.unwrap();
+
+    let result = squash_merge(fixture.repo.clone(), &main, &scenario.theirs, "squash").await;
+    let Err(GitError::MergeConflict { message, paths }) = result else {
+        panic!("expected a merge conflict");
+    };
+    assert!(message.contains(&scenario.path));
+    assert_eq!(paths, std::slice::from_ref(&scenario.path));
+    assert_eq!(head_commit(fixture.repo.clone()).await.unwrap(), scenario.ours.to_string());
+}
+
+#[tokio::test]
+async fn test_uncommitted_merge_reports_merge_state() {
+    use kodegen_tools_git::{
+        BranchOpts, CheckoutOpts, branch, checkout, current_branch, fetch_head, head_commit, merge,
+        merge_state,
+    };
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_file(&repo, "base.txt", "base\n").await;
+    let main = current_branch(repo.clone()).await.unwrap().name;
+    assert!(merge_state(repo.clone()).await.unwrap().is_none());
+
+    branch(repo.clone(), BranchOpts::new("feature").checkout(true))
+        .await
+        .unwrap()
+        .unwrap();
+    commit_file(&repo, "feature.txt", "feature\n").await;
+    let feature = head_commit(repo.clone()).await.unwrap();
+
+    checkout(repo.clone(), CheckoutOpts::new(&main)).await.unwrap();
+    commit_file(&repo, "main.txt", "main\n").await;
+    merge(repo.clone(), MergeOpts::new("feature").commit(false))
+        .await
+        .unwrap();
+
+    let state = merge_state(repo.clone()).await.unwrap().expect("merge in progress");
+    let heads: Vec<String> = state.heads.iter().map(ToString::to_string).collect();
+    assert_eq!(heads, [feature]);
+    assert!(state.message.unwrap().contains("feature"));
+
+    // Nothing was fetched in this repository
+    assert!(fetch_head(repo.clone()).await.unwrap().is_empty());
+}
@@ -0,0 +1,68 @@
+//! Tests for merge commit diffs
+
+use kodegen_tools_git::{ChangeType, init_repo, merge_diff};
+
+use super::support::write_commit;
+
+#[tokio::test]
+async fn test_merge_diff_shows_resolution() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    let (main, side, merge) = {
+        let raw = repo.raw();
+        let base = write_commit(raw, &[], &[("a.txt", "1\n2\n3\n4\n5\n")], "base");
+        let main = write_commit(raw, &[base], &[("a.txt", "1\ndeux\n3\n4\n5\n")], "main");
+        let side = write_commit(
+            raw,
+            &[base],
+            &[("a.txt", "1\ntwo\n3\n4\n5\n"), ("b.txt", "b\n")],
+            "side",
+        );
+        // Take b.txt from side and resolve the conflict in a.txt by hand
+        let files = [("a.txt", "1\nresolved\n3\n4\n5\n"), ("b.txt", "b\n")];
+        let merge = write_commit(raw, &[main, side], &files, "merge");
+        (main, side, merge)
+    };
+
+    let diff = merge_diff(repo.clone(), &merge.to_string(), true).await.unwrap();
+    assert_eq!(diff.merge, merge);
+    assert_eq!(diff.parents, [main, side]);
+
+    let files: Vec<_> = diff
+        .stats
+        .files
+        .iter()
+        .map(|f| {
+            (
+                f.path.to_str().unwrap(),
+                f.change_type,
+                f.additions,
+                f.deletions,
+            )
+        })
+        .collect();
+    assert_eq!(
+        files,
+        [
+            ("a.txt", ChangeType::Modified, 1, 1),
+            ("b.txt", ChangeType::Added, 1, 0),
+        ]
+    );
+    assert!(diff.patch.contains("-deux\n+resolved\n"));
+    assert!(diff.patch.contains("--- /dev/null\n+++ b/b.txt\n"));
+
+    // Only a.txt differs from both parents
+    assert_eq!(diff.combined.len(), 1);
+    assert_eq!(diff.combined[0].path, "a.txt");
+    assert_eq!(
+        diff.combined[0].diff,
+        "diff --cc a.txt\n@@@ -1,5 -1,5 +1,5 @@@\n  1\n- deux\n -two\n++resolved\n  3\n  4\n  5\n"
+    );
+
+    let without = merge_diff(repo.clone(), &merge.to_string(), false).await.unwrap();
+    assert!(without.combined.is_empty());
+    assert_eq!(without.patch, diff.patch);
+
+    assert!(merge_diff(repo.clone(), &main.to_string(), false).await.is_err());
+}
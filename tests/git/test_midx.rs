@@ -0,0 +1,98 @@
+//! Tests for multi-pack indexes and bitmaps; packs are made with the git CLI.
+
+use std::path::Path;
+use std::process::Command;
+
+use kodegen_tools_git::{
+    CloneOpts, GitError, MidxOpts, branches_containing, clone_repo, init_repo, reachable_count,
+    tags_containing, upstream_status, write_midx,
+};
+use tempfile::TempDir;
+
+use super::support::commit_file;
+
+fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?}: {output:?}");
+}
+
+#[tokio::test]
+async fn test_write_midx_requires_packs() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    commit_file(&repo, "a.txt", "a").await;
+
+    let result = write_midx(repo.clone(), MidxOpts::new()).await;
+    assert!(matches!(result, Err(GitError::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn test_bitmap_counts_match_walk() {
+    let temp_dir = TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_repo(&upstream_dir).await.unwrap().unwrap();
+    let mut commits = Vec::new();
+    for i in 0..5 {
+        commits.push(commit_file(&upstream, &format!("{i}.txt"), &format!("{i}")).await);
+    }
+
+    let clone_dir = temp_dir.path().join("clone");
+    let url = format!("file://{}", upstream_dir.display());
+    let repo = clone_repo(CloneOpts::new(url, &clone_dir))
+        .await
+        .unwrap()
+        .unwrap()
+        .repo;
+    let local = commit_file(&repo, "local.txt", "local").await;
+    git(&clone_dir, &["branch", "old", &commits[1].to_string()]);
+    let identity = ["-c", "user.name=Test", "-c", "user.email=test@example.com"];
+    let tagged = commits[2].to_string();
+    git(&clone_dir, &[&identity[..], &["tag", "-am", "v1", "v1", &tagged]].concat());
+    // Two packs: the cloned history and the local commit
+    git(&clone_dir, &["repack", "-dq"]);
+
+    let walked = reachable_count(repo.clone()).await.unwrap();
+    let containing = async |commit: gix::ObjectId| {
+        branches_containing(repo.clone(), &commit.to_string())
+            .await
+            .unwrap()
+    };
+    let walked_containing = [
+        containing(commits[0]).await,
+        containing(commits[3]).await,
+        containing(local).await,
+    ];
+    assert_eq!(walked_containing[0].len(), 3);
+    assert_eq!(walked_containing[1].len(), 2);
+    assert_eq!(walked_containing[2].len(), 1);
+    let walked_tags = tags_containing(repo.clone(), &commits[1].to_string()).await.unwrap();
+    assert_eq!(walked_tags, ["v1"]);
+    let status = upstream_status(repo.clone(), None).await.unwrap().unwrap();
+    assert_eq!((status.ahead, status.behind), (1, 0));
+
+    let result = write_midx(repo.clone(), MidxOpts::new().bitmap(true).commit_graph(true))
+        .await
+        .unwrap();
+    assert!(result.path.is_file());
+    assert_eq!(result.packs, 2);
+    assert!(result.objects as usize >= walked);
+    assert!(result.bitmap.is_some_and(|path| path.is_file()));
+    assert!(clone_dir.join(".git/objects/info/commit-graph").is_file());
+
+    assert_eq!(reachable_count(repo.clone()).await.unwrap(), walked);
+    let counted_containing = [
+        containing(commits[0]).await,
+        containing(commits[3]).await,
+        containing(local).await,
+    ];
+    assert_eq!(counted_containing, walked_containing);
+    let counted_tags = tags_containing(repo.clone(), &commits[1].to_string()).await.unwrap();
+    assert_eq!(counted_tags, walked_tags);
+    let status = upstream_status(repo.clone(), None).await.unwrap().unwrap();
+    assert_eq!((status.ahead, status.behind), (1, 0));
+}
@@ -0,0 +1,147 @@
+//! Tests for finding repositories nested below a directory
+
+use std::path::Path;
+
+use gix::index::entry::{Flags, Mode, Stat};
+use kodegen_tools_git::{
+    NestedRepoKind, NestedScanOpts, RepoHandle, WorktreeAddOpts, find_nested_repos, init_bare_repo,
+    init_repo, worktree_add,
+};
+use tempfile::TempDir;
+
+use super::support::commit_file;
+
+/// Stage `path` in `repo`'s index with `mode`, without touching the worktree
+fn stage(repo: &RepoHandle, path: &str, mode: Mode, id: gix::ObjectId) {
+    let mut index = repo.raw().open_index().unwrap();
+    index.dangerously_push_entry(Stat::default(), id, Flags::empty(), mode, path.into());
+    index.sort_entries();
+    index.write(gix::index::write::Options::default()).unwrap();
+}
+
+fn kinds(found: &[kodegen_tools_git::NestedRepo]) -> Vec<(String, NestedRepoKind)> {
+    found
+        .iter()
+        .map(|repo| (repo.relative_path.display().to_string(), repo.kind))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_find_nested_repos_classifies_against_container() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let outer = init_repo(root).await.unwrap().unwrap();
+    commit_file(
+        &outer,
+        ".gitmodules",
+        "[submodule \"libs/sub\"]\n\tpath = libs/sub\n\turl = https://example.com/sub.git\n",
+    )
+    .await;
+
+    let sub = init_repo(root.join("libs/sub")).await.unwrap().unwrap();
+    let sub_head = commit_file(&sub, "s.txt", "s\n").await;
+    let embedded = init_repo(root.join("embedded")).await.unwrap().unwrap();
+    let embedded_head = commit_file(&embedded, "e.txt", "e\n").await;
+    init_repo(root.join("vendor/lib")).await.unwrap().unwrap();
+    init_repo(root.join("committed")).await.unwrap().unwrap();
+    stage(&outer, "libs/sub", Mode::COMMIT, sub_head);
+    stage(&outer, "embedded", Mode::COMMIT, embedded_head);
+    let blob = outer
+        .raw()
+        .write_blob(b"ref: refs/heads/main\n")
+        .unwrap()
+        .detach();
+    stage(&outer, "committed/.git/HEAD", Mode::FILE, blob);
+
+    let found = find_nested_repos(root, NestedScanOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(
+        kinds(&found),
+        [
+            ("committed".to_string(), NestedRepoKind::CommittedGitDir),
+            ("embedded".to_string(), NestedRepoKind::EmbeddedGitlink),
+            ("libs/sub".to_string(), NestedRepoKind::Submodule),
+            ("vendor/lib".to_string(), NestedRepoKind::Independent),
+        ]
+    );
+    let canonical_root = root.canonicalize().unwrap();
+    for repo in &found {
+        assert_eq!(repo.container.as_deref(), Some(canonical_root.as_path()));
+        assert_eq!(
+            repo.git_dir.as_deref(),
+            Some(repo.path.join(".git").as_path())
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_find_nested_repos_bare_worktree_and_broken() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let main = init_repo(root.join("main")).await.unwrap().unwrap();
+    let head = commit_file(&main, "a.txt", "one\n").await;
+    worktree_add(
+        main.clone(),
+        WorktreeAddOpts::new(root.join("linked"))
+            .committish(head.to_string())
+            .detach(true),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    init_bare_repo(root.join("mirror.git"))
+        .await
+        .unwrap()
+        .unwrap();
+    std::fs::create_dir_all(root.join("stale")).unwrap();
+    std::fs::write(
+        root.join("stale/.git"),
+        "gitdir: /nonexistent/modules/stale\n",
+    )
+    .unwrap();
+
+    let found = find_nested_repos(root, NestedScanOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(
+        kinds(&found),
+        [
+            ("linked".to_string(), NestedRepoKind::Worktree),
+            ("main".to_string(), NestedRepoKind::Independent),
+            ("mirror.git".to_string(), NestedRepoKind::Bare),
+            ("stale".to_string(), NestedRepoKind::Broken),
+        ]
+    );
+    let linked = &found[0];
+    assert!(
+        linked
+            .git_dir
+            .as_deref()
+            .is_some_and(|dir| dir.ends_with(Path::new("worktrees/linked")))
+    );
+    assert!(found.iter().all(|repo| repo.container.is_none()));
+    assert_eq!(found[3].git_dir, None);
+}
+
+#[tokio::test]
+async fn test_find_nested_repos_max_depth() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    init_repo(root.join("a")).await.unwrap().unwrap();
+    init_repo(root.join("x/y/deep")).await.unwrap().unwrap();
+
+    let shallow = find_nested_repos(root, NestedScanOpts::new().max_depth(2))
+        .await
+        .unwrap();
+    assert_eq!(
+        kinds(&shallow),
+        [("a".to_string(), NestedRepoKind::Independent)]
+    );
+
+    let all = find_nested_repos(root, NestedScanOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[1].relative_path, Path::new("x/y/deep"));
+}
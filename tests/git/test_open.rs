@@ -1,8 +1,9 @@
 //! Tests for git repository open and initialization operations.
 
 use kodegen_tools_git::git::open::{
-    RepositoryInfo, discover_repo, init_bare_repo, init_repo, is_repository, open_repo,
-    probe_repository,
+    DiscoveryBoundary, GitEnvironment, InitOpts, OpenOpts, RepositoryInfo, discover_repo,
+    discover_repo_report, discover_repo_with, init_bare_repo, init_repo, init_repo_with,
+    is_repository, open_repo, open_repo_with, probe_repository, probe_repository_with,
 };
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -61,6 +62,7 @@ async fn test_init_repo() {
     let info = probe_repository(&repo_path).await.unwrap().unwrap();
     assert!(!info.is_bare);
     assert!(info.work_dir.is_some());
+    assert!(!info.is_shallow);
 }
 
 #[tokio::test]
@@ -140,13 +142,254 @@ async fn test_discover_repo_integration() {
     assert!(!repo_handle.raw().is_bare());
 }
 
+#[tokio::test]
+async fn test_init_separate_git_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    let work = temp_dir.path().join("work");
+    let store = temp_dir.path().join("store.git");
+
+    let repo = init_repo_with(&work, InitOpts::new().separate_git_dir(&store))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!repo.raw().is_bare());
+    assert!(work.join(".git").is_file());
+    assert!(store.join("HEAD").is_file());
+
+    // Opened from the worktree, a subdirectory, or the git dir itself, the
+    // repository is the same and so is its worktree
+    std::fs::create_dir_all(work.join("src")).unwrap();
+    let opened = open_repo(&work).await.unwrap().unwrap();
+    let discovered = discover_repo(work.join("src")).await.unwrap().unwrap();
+    let direct = open_repo(&store).await.unwrap().unwrap();
+    let work = work.canonicalize().unwrap();
+    for handle in [&opened, &discovered, &direct] {
+        assert_eq!(
+            handle.raw().git_dir().canonicalize().unwrap(),
+            store.canonicalize().unwrap()
+        );
+        assert_eq!(
+            handle.raw().workdir().unwrap().canonicalize().unwrap(),
+            work
+        );
+    }
+
+    let info = probe_repository(&work).await.unwrap().unwrap();
+    assert!(!info.is_bare);
+    assert_eq!(info.gitfile, Some(work.join(".git")));
+    assert_eq!(
+        info.common_dir.canonicalize().unwrap(),
+        store.canonicalize().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_init_separate_git_dir_rejects_bare() {
+    let temp_dir = TempDir::new().unwrap();
+    let result = init_repo_with(
+        temp_dir.path().join("work"),
+        InitOpts::new()
+            .bare(true)
+            .separate_git_dir(temp_dir.path().join("store")),
+    )
+    .await
+    .unwrap();
+    assert!(matches!(
+        result,
+        Err(kodegen_tools_git::GitError::InvalidInput(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_open_dangling_gitfile() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(
+        temp_dir.path().join(".git"),
+        "gitdir: ../gone/modules/lib\n",
+    )
+    .unwrap();
+
+    match open_repo(temp_dir.path()).await.unwrap() {
+        Err(kodegen_tools_git::GitError::InvalidInput(msg)) => {
+            assert!(msg.contains("gone"), "{msg}");
+            assert!(msg.contains("does not exist"), "{msg}");
+        }
+        other => panic!("Expected InvalidInput error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_open_with_git_dir_environment() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("repo");
+    let elsewhere = temp_dir.path().join("elsewhere");
+    std::fs::create_dir_all(&elsewhere).unwrap();
+    init_repo(&repo_path).await.unwrap().unwrap();
+
+    // GIT_DIR wins over the path, which isn't a repository
+    let environment = GitEnvironment {
+        git_dir: Some(repo_path.join(".git")),
+        ..Default::default()
+    };
+    let opts = OpenOpts::new().environment(environment.clone());
+    let repo = open_repo_with(&elsewhere, opts.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        repo.raw().git_dir().canonicalize().unwrap(),
+        repo_path.join(".git").canonicalize().unwrap()
+    );
+    let discovered = discover_repo_with(&elsewhere, opts.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(discovered.raw().git_dir(), repo.raw().git_dir());
+
+    let info = probe_repository_with(&elsewhere, opts)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.environment, environment);
+
+    // Without opting in, the environment plays no part
+    assert!(open_repo(&elsewhere).await.unwrap().is_err());
+    let info = probe_repository(&repo_path).await.unwrap().unwrap();
+    assert!(info.environment.is_empty());
+}
+
+#[tokio::test]
+async fn test_open_with_work_tree_environment() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("repo");
+    let work_tree = temp_dir.path().join("checkout");
+    std::fs::create_dir_all(&work_tree).unwrap();
+    init_repo(&repo_path).await.unwrap().unwrap();
+
+    let environment = GitEnvironment {
+        work_tree: Some(work_tree.clone()),
+        ..Default::default()
+    };
+    let repo = open_repo_with(&repo_path, OpenOpts::new().environment(environment))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        repo.raw().workdir().unwrap().canonicalize().unwrap(),
+        work_tree.canonicalize().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_discover_stops_at_ceiling_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("repo");
+    let deep = repo_path.join("sub").join("deep");
+    init_repo(&repo_path).await.unwrap().unwrap();
+    std::fs::create_dir_all(&deep).unwrap();
+
+    let environment = GitEnvironment {
+        ceiling_directories: vec![repo_path.join("sub")],
+        ..Default::default()
+    };
+    let result = discover_repo_with(&deep, OpenOpts::new().environment(environment))
+        .await
+        .unwrap();
+    assert!(result.is_err());
+
+    // A ceiling above the repository doesn't hide it
+    let environment = GitEnvironment {
+        ceiling_directories: vec![temp_dir.path().to_path_buf()],
+        ..Default::default()
+    };
+    let repo = discover_repo_with(&deep, OpenOpts::new().environment(environment))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!repo.raw().is_bare());
+}
+
+#[tokio::test]
+async fn test_discover_report_levels_and_boundary() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("repo");
+    let deep = repo_path.join("a").join("b");
+    init_repo(&repo_path).await.unwrap().unwrap();
+    std::fs::create_dir_all(&deep).unwrap();
+
+    let found = discover_repo_report(&deep, OpenOpts::new())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.levels_up, 2);
+    assert_eq!(
+        found.root.canonicalize().unwrap(),
+        repo_path.canonicalize().unwrap()
+    );
+    assert!(found.boundary.is_none());
+
+    // Two levels are enough, and the depth is reported as the limit
+    let found = discover_repo_report(&deep, OpenOpts::new().max_depth(2))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.levels_up, 2);
+    assert_eq!(found.boundary, Some(DiscoveryBoundary::MaxDepth(2)));
+
+    // One is not, and the error says what stopped the search
+    match discover_repo_report(&deep, OpenOpts::new().max_depth(1))
+        .await
+        .unwrap()
+    {
+        Err(kodegen_tools_git::GitError::InvalidInput(msg)) => {
+            assert!(msg.contains("maximum depth of 1"), "{msg}");
+        }
+        other => panic!("Expected InvalidInput error, got {other:?}"),
+    }
+
+    // Zero levels only looks at the start
+    let found = discover_repo_report(&repo_path, OpenOpts::new().max_depth(0))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.levels_up, 0);
+    let result = discover_repo_report(repo_path.join("a"), OpenOpts::new().max_depth(0))
+        .await
+        .unwrap();
+    assert!(result.is_err());
+
+    // The nearer of a ceiling and the depth is the boundary
+    let ceiling = repo_path.join("a");
+    let opts = OpenOpts::new().max_depth(5).ceiling_dir(&ceiling);
+    match discover_repo_report(&deep, opts).await.unwrap() {
+        Err(kodegen_tools_git::GitError::InvalidInput(msg)) => {
+            assert!(msg.contains("ceiling directory"), "{msg}");
+        }
+        other => panic!("Expected InvalidInput error, got {other:?}"),
+    }
+    let found = discover_repo_report(&deep, OpenOpts::new().ceiling_dir(temp_dir.path()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        found.boundary,
+        Some(DiscoveryBoundary::Ceiling(
+            temp_dir.path().canonicalize().unwrap()
+        ))
+    );
+}
+
 #[test]
 fn test_repository_info() {
     let info = RepositoryInfo {
         path: PathBuf::from("/test/repo"),
         is_bare: false,
         git_dir: PathBuf::from("/test/repo/.git"),
+        common_dir: PathBuf::from("/test/repo/.git"),
         work_dir: Some(PathBuf::from("/test/repo")),
+        gitfile: None,
+        is_shallow: false,
+        environment: Default::default(),
     };
 
     assert_eq!(info.path, PathBuf::from("/test/repo"));
@@ -161,7 +404,11 @@ fn test_repository_info_bare() {
         path: PathBuf::from("/test/bare.git"),
         is_bare: true,
         git_dir: PathBuf::from("/test/bare.git"),
+        common_dir: PathBuf::from("/test/bare.git"),
         work_dir: None,
+        gitfile: None,
+        is_shallow: false,
+        environment: Default::default(),
     };
 
     assert_eq!(info.path, PathBuf::from("/test/bare.git"));
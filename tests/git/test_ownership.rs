@@ -0,0 +1,40 @@
+//! Tests for reviewer suggestions.
+
+use kodegen_tools_git::{Signature, init_repo, suggest_owners};
+
+use super::support::commit_files_as;
+
+#[tokio::test]
+async fn test_suggest_owners_ranks_by_blame_and_history() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    let alice = Signature::new("Alice", "alice@example.com");
+    commit_files_as(&repo, alice, &[("file.txt", "one\ntwo\nthree\nfour\n")], "edit").await;
+    let bob = Signature::new("Bob", "Bob@Example.com");
+    commit_files_as(&repo, bob, &[("file.txt", "one\ntwo\nthree\nFOUR\n")], "edit").await;
+
+    let owners = suggest_owners(repo.clone(), vec!["file.txt".to_string()], 50)
+        .await
+        .unwrap();
+    assert_eq!(owners.len(), 1);
+
+    let candidates = &owners[0].candidates;
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].email, "alice@example.com");
+    assert_eq!(candidates[0].blame_lines, 3);
+    assert_eq!(candidates[0].recent_commits, 1);
+    assert_eq!(candidates[1].email, "bob@example.com");
+    assert_eq!(candidates[1].blame_lines, 1);
+    assert!(candidates[0].score > candidates[1].score);
+    assert!(owners[0].codeowners.is_empty());
+
+    // CODEOWNERS entries take precedence over the score
+    std::fs::write(temp_dir.path().join("CODEOWNERS"), "*.txt bob@example.com\n").unwrap();
+    let owners = suggest_owners(repo, vec!["file.txt".to_string()], 50).await.unwrap();
+    let candidates = &owners[0].candidates;
+    assert_eq!(candidates[0].email, "bob@example.com");
+    assert!(candidates[0].in_codeowners);
+    assert!(!candidates[1].in_codeowners);
+    assert_eq!(owners[0].codeowners, vec!["bob@example.com".to_string()]);
+}
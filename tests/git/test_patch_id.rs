@@ -0,0 +1,89 @@
+//! Tests for patch id computation
+
+use kodegen_tools_git::{init_repo, patch_id};
+
+use super::support::commit_files;
+
+#[tokio::test]
+async fn test_patch_id_ignores_position_and_whitespace() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_repo(dir).await.unwrap().unwrap();
+
+    commit_files(&repo, &[("a.txt", "one\ntwo\nthree\n")], "base").await;
+    let first = commit_files(&repo, &[("a.txt", "one\n2\nthree\n")], "change two").await;
+
+    // The same edit to a file that differs elsewhere, with other indentation
+    let moved = "zero\n\n\n\n\n\none\n  two\nthree\n";
+    commit_files(&repo, &[("a.txt", moved)], "move things").await;
+    let again = "zero\n\n\n\n\n\none\n  2\nthree\n";
+    let second = commit_files(&repo, &[("a.txt", again)], "change two again").await;
+    let (first, second) = (first.to_string(), second.to_string());
+
+    let first_id = patch_id(repo.clone(), &first).await.unwrap().unwrap();
+    assert_eq!(patch_id(repo.clone(), &second).await.unwrap(), Some(first_id));
+    assert_ne!(
+        patch_id(repo.clone(), &format!("{second}~1")).await.unwrap(),
+        Some(first_id)
+    );
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_patch_id_matches_git() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_repo(dir).await.unwrap().unwrap();
+
+    let lines: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+    let root = commit_files(&repo, &[("a.txt", &lines), ("b.txt", "b\n")], "root").await;
+
+    // Two separate hunks in a.txt, new files and a new mode; d.txt is empty,
+    // so like the mode change it has no hunks
+    let changed = lines
+        .replace("line 2\n", "line two\n")
+        .replace("line 18\n", "line 18\nextra\n");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::Permissions::from_mode(0o755);
+        std::fs::set_permissions(dir.join("b.txt"), permissions).unwrap();
+    }
+    let files = [
+        ("a.txt", changed.as_str()),
+        ("b.txt", "b\n"),
+        ("c.txt", "c\nno newline"),
+        ("d.txt", ""),
+    ];
+    let change = commit_files(&repo, &files, "change").await;
+
+    for commit in [root.to_string(), change.to_string()] {
+        let show = Command::new("git")
+            .args(["show", "--no-renames", &commit])
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        let mut patch_id_cmd = Command::new("git")
+            .args(["patch-id", "--stable"])
+            .current_dir(dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        patch_id_cmd
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(&show.stdout)
+            .unwrap();
+        let output = patch_id_cmd.wait_with_output().unwrap();
+        let expected = String::from_utf8(output.stdout).unwrap();
+        let expected = expected.split_whitespace().next().unwrap().to_string();
+
+        let id = patch_id(repo.clone(), &commit).await.unwrap().unwrap();
+        assert_eq!(id.to_string(), expected, "patch id of {commit}");
+    }
+}
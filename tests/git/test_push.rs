@@ -5,8 +5,6 @@
 
 #[cfg(test)]
 mod tests {
-    use kodegen_tools_git::{open_repo, push, push_current_branch, push_tags, PushOpts};
-
     #[tokio::test]
     #[ignore] // Requires remote repository
     async fn test_push_current_branch() {
@@ -20,4 +18,53 @@ mod tests {
         // This test requires a real remote repository
         // Run with: cargo test test_push_with_tags -- --ignored
     }
+
+    #[cfg(not(feature = "pure-gix"))]
+    #[tokio::test]
+    async fn test_push_counts_refs_from_porcelain() {
+        use kodegen_tools_git::{
+            CloneOpts, PushOpts, TagOpts, clone_repo, create_tag, init_repo, push,
+        };
+
+        use super::super::support::commit_file;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let upstream_dir = temp_dir.path().join("upstream");
+        std::fs::create_dir(&upstream_dir).unwrap();
+        let upstream = init_repo(&upstream_dir).await.unwrap().unwrap();
+        commit_file(&upstream, "a.txt", "one\n").await;
+
+        let url = format!("file://{}", upstream_dir.display());
+        let repo = clone_repo(CloneOpts::new(url, temp_dir.path().join("clone")))
+            .await
+            .unwrap()
+            .unwrap()
+            .repo;
+        commit_file(&repo, "b.txt", "two\n").await;
+        create_tag(repo.clone(), TagOpts::new("v1")).await.unwrap();
+
+        let opts = || PushOpts {
+            refspecs: vec![
+                "HEAD:refs/heads/topic".to_string(),
+                "refs/tags/v1:refs/tags/v1".to_string(),
+            ],
+            ..PushOpts::default()
+        };
+        let result = push(repo.clone(), opts()).await.unwrap();
+        assert_eq!(result.commits_pushed, 2);
+        assert_eq!(result.tags_pushed, 1);
+
+        // Refs already on the remote don't count
+        let result = push(repo.clone(), opts()).await.unwrap();
+        assert_eq!(result.commits_pushed, 0);
+        assert_eq!(result.tags_pushed, 0);
+
+        // Moving the branch backwards is rejected, naming the ref
+        let opts = PushOpts {
+            refspecs: vec!["HEAD~1:refs/heads/topic".to_string()],
+            ..PushOpts::default()
+        };
+        let error = push(repo.clone(), opts).await.unwrap_err().to_string();
+        assert!(error.contains("refs/heads/topic"), "{error}");
+    }
 }
@@ -0,0 +1,57 @@
+//! Tests for object existence and reachability queries
+
+use gix::refs::transaction::PreviousValue;
+use kodegen_tools_git::{is_reachable, object_exists, reachable_count};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo, write_commit};
+
+#[tokio::test]
+async fn test_object_reachability() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let first = commit_file(&repo, "a.txt", "1\n").await;
+    let second = commit_file(&repo, "a.txt", "2\n").await;
+    let dangling = write_commit(repo.raw(), &[second], &[("b.txt", "orphan\n")], "dangling");
+    let blob_of = |commit: gix::ObjectId, path: &str| {
+        repo.raw()
+            .find_commit(commit)
+            .unwrap()
+            .tree()
+            .unwrap()
+            .lookup_entry_by_path(path)
+            .unwrap()
+            .unwrap()
+            .object_id()
+    };
+    let orphan_blob = blob_of(dangling, "b.txt");
+    let first_blob = blob_of(first, "a.txt");
+    let missing = gix::ObjectId::from_hex(b"0123456789abcdef0123456789abcdef01234567").unwrap();
+
+    assert!(object_exists(repo.clone(), dangling).await.unwrap());
+    assert!(object_exists(repo.clone(), orphan_blob).await.unwrap());
+    assert!(!object_exists(repo.clone(), missing).await.unwrap());
+
+    assert!(is_reachable(repo.clone(), first, &[]).await.unwrap());
+    assert!(is_reachable(repo.clone(), first_blob, &[]).await.unwrap());
+    assert!(!is_reachable(repo.clone(), dangling, &[]).await.unwrap());
+    assert!(!is_reachable(repo.clone(), orphan_blob, &[]).await.unwrap());
+    assert!(!is_reachable(repo.clone(), missing, &[]).await.unwrap());
+    let first_hex = first.to_string();
+    assert!(
+        !is_reachable(repo.clone(), second, &[first_hex.as_str()])
+            .await
+            .unwrap()
+    );
+    assert!(is_reachable(repo.clone(), first, &["HEAD"]).await.unwrap());
+    assert!(is_reachable(repo.clone(), first, &["no-such-ref"]).await.is_err());
+
+    // Two commits, two trees, two blobs
+    assert_eq!(reachable_count(repo.clone()).await.unwrap(), 6);
+
+    repo.raw()
+        .reference("refs/heads/keep", dangling, PreviousValue::Any, "keep")
+        .unwrap();
+    assert!(is_reachable(repo.clone(), orphan_blob, &[]).await.unwrap());
+    assert_eq!(reachable_count(repo.clone()).await.unwrap(), 9);
+}
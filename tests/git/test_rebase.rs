@@ -0,0 +1,136 @@
+//! Tests for rebasing a branch onto a new base
+
+use gix::refs::transaction::PreviousValue;
+use kodegen_tools_git::{GitError, RebaseOpts, RepoHandle, is_clean, rebase};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo, write_commit};
+
+fn set_branch(repo: &RepoHandle, branch: &str, id: gix::ObjectId) {
+    repo.raw()
+        .reference(
+            format!("refs/heads/{branch}").as_str(),
+            id,
+            PreviousValue::Any,
+            "test",
+        )
+        .unwrap();
+}
+
+fn tip(repo: &RepoHandle, branch: &str) -> gix::ObjectId {
+    repo.raw()
+        .find_reference(format!("refs/heads/{branch}").as_str())
+        .unwrap()
+        .into_fully_peeled_id()
+        .unwrap()
+        .detach()
+}
+
+#[tokio::test]
+async fn test_rebase_onto_skips_upstream_commits() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "one\n").await;
+    let release = write_commit(repo.raw(), &[base], &[("r.txt", "r\n")], "release fix");
+    let first = write_commit(repo.raw(), &[release], &[("f.txt", "1\n")], "feature 1");
+    let second = write_commit(repo.raw(), &[first], &[("f.txt", "2\n")], "feature 2");
+    set_branch(&repo, "release", release);
+    set_branch(&repo, "feature", second);
+    let main = commit_file(&repo, "m.txt", "m\n").await;
+
+    // git rebase --onto <main> release feature
+    let opts = RebaseOpts::new(main.to_string())
+        .upstream("release")
+        .branch("feature");
+    let result = rebase(repo.clone(), opts.clone()).await.unwrap();
+    assert_eq!(result.branch_ref, "refs/heads/feature");
+    assert_eq!(result.onto, main);
+    assert_eq!(result.old_tip, second);
+    assert_eq!(tip(&repo, "feature"), result.new_tip);
+    assert!(!result.worktree_updated);
+    let originals: Vec<_> = result
+        .rewritten
+        .iter()
+        .map(|(original, _)| *original)
+        .collect();
+    assert_eq!(originals, [first, second]);
+    assert!(result.dropped.is_empty());
+
+    // Only the feature's commits moved: no release fix, main's file is there
+    let commit = repo.raw().find_commit(result.new_tip).unwrap();
+    assert_eq!(commit.message_raw_sloppy(), "feature 2");
+    assert_eq!(
+        commit
+            .parent_ids()
+            .map(|id| id.detach())
+            .collect::<Vec<_>>(),
+        [result.rewritten[0].1]
+    );
+    let tree = commit.tree().unwrap();
+    assert!(tree.find_entry("m.txt").is_some());
+    assert!(tree.find_entry("r.txt").is_none());
+
+    // Already on the new base
+    let again = rebase(repo.clone(), opts.upstream(main.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(again.new_tip, result.new_tip);
+    assert!(again.rewritten.is_empty());
+}
+
+#[tokio::test]
+async fn test_rebase_current_branch_updates_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "one\n").await;
+    let side = write_commit(repo.raw(), &[base], &[("s.txt", "s\n")], "side");
+    set_branch(&repo, "side", side);
+    // The same change as on the side branch is dropped, the other replayed
+    commit_file(&repo, "s.txt", "s\n").await;
+    let own = commit_file(&repo, "b.txt", "b\n").await;
+
+    let result = rebase(repo.clone(), RebaseOpts::new("side")).await.unwrap();
+    assert!(result.worktree_updated);
+    assert_eq!(result.rewritten.len(), 1);
+    assert_eq!(result.rewritten[0].0, own);
+    assert_eq!(result.dropped.len(), 1);
+    assert_eq!(repo.raw().head_id().unwrap().detach(), result.new_tip);
+    assert!(temp_dir.path().join("s.txt").is_file());
+    assert!(is_clean(repo.clone()).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_rebase_conflict_leaves_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "one\n").await;
+    let theirs = write_commit(repo.raw(), &[base], &[("a.txt", "theirs\n")], "change a");
+    set_branch(&repo, "topic", theirs);
+    commit_file(&repo, "a.txt", "ours\n").await;
+
+    match rebase(repo.clone(), RebaseOpts::new("HEAD").branch("topic")).await {
+        Err(GitError::MergeConflict { paths, .. }) => assert_eq!(paths, ["a.txt"]),
+        other => panic!("Expected MergeConflict, got {other:?}"),
+    }
+    assert_eq!(tip(&repo, "topic"), theirs);
+}
+
+#[tokio::test]
+async fn test_rebase_keeps_untracked_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "one\n").await;
+    let side = write_commit(repo.raw(), &[base], &[("s.txt", "s\n")], "side");
+    set_branch(&repo, "side", side);
+    let own = commit_file(&repo, "b.txt", "b\n").await;
+
+    let file = temp_dir.path().join("s.txt");
+    std::fs::write(&file, "mine\n").unwrap();
+    let err = rebase(repo.clone(), RebaseOpts::new("side"))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("s.txt"), "{err}");
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "mine\n");
+    assert_eq!(repo.raw().head_id().unwrap().detach(), own);
+}
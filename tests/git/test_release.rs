@@ -0,0 +1,100 @@
+//! Tests for cutting releases
+
+use kodegen_tools_git::operations::release::{self, ReleaseOpts};
+use kodegen_tools_git::{GitError, Signature, init_repo, list_tags};
+use tempfile::TempDir;
+
+use super::support::commit_file;
+
+fn tagger() -> Signature {
+    Signature::new("Releaser", "releaser@example.com")
+}
+
+#[tokio::test]
+async fn test_release_without_push() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let first = commit_file(&repo, "a.txt", "one\n").await;
+    commit_file(&repo, "a.txt", "two\n").await;
+
+    let opts = ReleaseOpts::new()
+        .push(false)
+        .target(first.to_string())
+        .tagger(tagger());
+    let result = release::cut(repo.clone(), "v1.0.0", opts).await.unwrap();
+    assert!(result.created);
+    assert!(!result.pushed);
+    assert_eq!(result.previous_tag, None);
+    assert_eq!(result.commits.len(), 1);
+    assert_eq!(result.tag.target_commit, first.to_string());
+    assert!(result.tag.message.unwrap().starts_with("Release v1.0.0"));
+    assert!(
+        result
+            .changelog
+            .starts_with("## v1.0.0\n\n- update a.txt (")
+    );
+
+    let opts = ReleaseOpts::new().push(false).tagger(tagger());
+    let result = release::cut(repo.clone(), "v1.1.0", opts).await.unwrap();
+    assert_eq!(result.previous_tag.as_deref(), Some("v1.0.0"));
+    assert_eq!(result.commits.len(), 1);
+
+    // Taken by another commit
+    let opts = ReleaseOpts::new().push(false).tagger(tagger());
+    let error = release::cut(repo.clone(), "v1.0.0", opts).await.unwrap_err();
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+
+    // Uncommitted changes
+    std::fs::write(temp_dir.path().join("a.txt"), "dirty\n").unwrap();
+    let opts = ReleaseOpts::new().push(false).tagger(tagger());
+    let error = release::cut(repo.clone(), "v1.2.0", opts).await.unwrap_err();
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+    assert_eq!(list_tags(repo.clone()).await.unwrap().len(), 2);
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_release_pushes_once() {
+    use kodegen_tools_git::{CloneOpts, clone_repo, delete_tag};
+
+    let temp_dir = TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_repo(&upstream_dir).await.unwrap().unwrap();
+    commit_file(&upstream, "a.txt", "one\n").await;
+
+    let url = format!("file://{}", upstream_dir.display());
+    let clone_dir = temp_dir.path().join("clone");
+    let repo = clone_repo(CloneOpts::new(url, &clone_dir))
+        .await
+        .unwrap()
+        .unwrap()
+        .repo;
+    commit_file(&repo, "b.txt", "two\n").await;
+
+    let opts = ReleaseOpts::new().tagger(tagger());
+    let result = release::cut(repo.clone(), "v1.0.0", opts).await.unwrap();
+    assert!(result.created);
+    assert!(result.pushed);
+    let pushed = upstream
+        .raw()
+        .find_reference("refs/tags/v1.0.0")
+        .unwrap()
+        .into_fully_peeled_id()
+        .unwrap();
+    assert_eq!(pushed.to_string(), result.tag.target_commit);
+
+    // Repeating the release changes nothing
+    let opts = ReleaseOpts::new().tagger(tagger());
+    let again = release::cut(repo.clone(), "v1.0.0", opts).await.unwrap();
+    assert!(!again.created);
+    assert!(!again.pushed);
+    assert_eq!(again.tag.target_commit, result.tag.target_commit);
+
+    // The remote has the tag on another commit
+    delete_tag(repo.clone(), "v1.0.0").await.unwrap();
+    commit_file(&repo, "c.txt", "three\n").await;
+    let opts = ReleaseOpts::new().tagger(tagger());
+    let error = release::cut(repo.clone(), "v1.0.0", opts).await.unwrap_err();
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+}
@@ -0,0 +1,143 @@
+//! Tests for adding and removing remotes.
+
+use kodegen_tools_git::{
+    BranchOpts, CloneOpts, GitError, RemoteAddOpts, add_remote, branch, clone_repo, init_repo,
+    list_remotes, open_repo, remote_default_branch,
+};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo};
+
+fn remote(name: &str, url: &str) -> RemoteAddOpts {
+    RemoteAddOpts {
+        name: name.to_string(),
+        url: url.to_string(),
+        force: false,
+    }
+}
+
+#[tokio::test]
+async fn test_add_remote_stores_canonical_url() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_dir = temp_dir.path().join("repo");
+    std::fs::create_dir(&repo_dir).unwrap();
+    let repo = init_repo(&repo_dir).await.unwrap().unwrap();
+
+    let url = add_remote(
+        repo.clone(),
+        remote("origin", "git@GitHub.com:owner/repo.git"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(url.url, "git@github.com:owner/repo.git");
+    assert_eq!(url.host, "github.com");
+    assert_eq!(url.owner.as_deref(), Some("owner"));
+    assert_eq!(url.repo.as_deref(), Some("repo"));
+
+    // Stored in the config file, so a fresh handle sees it
+    let reopened = open_repo(&repo_dir).await.unwrap().unwrap();
+    let remotes = list_remotes(reopened.clone()).await.unwrap();
+    let origin = remotes.iter().find(|r| r.name == "origin").unwrap();
+    assert_eq!(origin.fetch_url, "git@github.com:owner/repo.git");
+
+    // The same handle sees the remote it added
+    let result = add_remote(
+        repo.clone(),
+        remote("origin", "https://example.com/a/b.git"),
+    )
+    .await;
+    assert!(matches!(result, Err(GitError::InvalidInput(_))));
+
+    // Existing local paths work, relative to the worktree
+    std::fs::create_dir(temp_dir.path().join("upstream")).unwrap();
+    let url = add_remote(repo.clone(), remote("local", "../upstream"))
+        .await
+        .unwrap();
+    assert_eq!(url.scheme, "file");
+}
+
+#[tokio::test]
+async fn test_add_remote_rejects_invalid_urls() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    for url in [
+        "not a url",
+        "garbage",
+        "https://",
+        "git@github.com:",
+        "ftp//host",
+    ] {
+        let result = add_remote(repo.clone(), remote("origin", url)).await;
+        assert!(
+            matches!(result, Err(GitError::Parse(_) | GitError::InvalidInput(_))),
+            "{url:?} was accepted: {result:?}"
+        );
+    }
+    let reopened = open_repo(temp_dir.path()).await.unwrap().unwrap();
+    assert!(list_remotes(reopened.clone()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_remote_default_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_test_repo(&upstream_dir).await;
+    commit_file(&upstream, "README.md", "hello\n").await;
+    branch(upstream.clone(), BranchOpts::new("trunk").checkout(true))
+        .await
+        .unwrap()
+        .unwrap();
+    let url = format!("file://{}", upstream_dir.display());
+
+    // Asked of a configured remote over the transport
+    let repo_dir = temp_dir.path().join("repo");
+    std::fs::create_dir(&repo_dir).unwrap();
+    let repo = init_repo(&repo_dir).await.unwrap().unwrap();
+    add_remote(repo.clone(), remote("origin", &url))
+        .await
+        .unwrap();
+    let repo = open_repo(&repo_dir).await.unwrap().unwrap();
+    assert_eq!(
+        remote_default_branch(Some(&repo), "origin")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("trunk")
+    );
+    assert!(remote_default_branch(Some(&repo), "missing").await.is_err());
+
+    // Clones record it as origin/HEAD
+    let cloned = clone_repo(CloneOpts::new(&url, temp_dir.path().join("clone")))
+        .await
+        .unwrap()
+        .unwrap();
+    let remote_head = cloned
+        .repo
+        .raw()
+        .find_reference("refs/remotes/origin/HEAD")
+        .unwrap();
+    assert_eq!(
+        remote_head.target().try_name().unwrap().as_bstr(),
+        "refs/remotes/origin/trunk"
+    );
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_remote_default_branch_of_url() {
+    let temp_dir = TempDir::new().unwrap();
+    let upstream = init_test_repo(temp_dir.path()).await;
+    commit_file(&upstream, "README.md", "hello\n").await;
+    branch(upstream.clone(), BranchOpts::new("trunk").checkout(true))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let url = format!("file://{}", temp_dir.path().display());
+    assert_eq!(
+        remote_default_branch(None, &url).await.unwrap().as_deref(),
+        Some("trunk")
+    );
+}
@@ -0,0 +1,95 @@
+//! Tests for replace refs and grafts
+
+use kodegen_tools_git::{
+    AddOpts, CommitOpts, RepoHandle, Signature, add, commit, create_replace, delete_replace, graft,
+    head_commit, init_repo, list_replace,
+};
+
+/// Repository with three commits on a straight line, oldest first
+async fn linear_history() -> (tempfile::TempDir, RepoHandle, Vec<String>) {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    let mut commits = Vec::new();
+    for n in 1..=3 {
+        std::fs::write(temp_dir.path().join("file.txt"), format!("{n}\n")).unwrap();
+        add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+        commit(
+            repo.clone(),
+            CommitOpts::message(format!("commit {n}"))
+                .author(Signature::new("Test", "test@example.com")),
+        )
+        .await
+        .unwrap();
+        commits.push(head_commit(repo.clone()).await.unwrap());
+    }
+
+    (temp_dir, repo, commits)
+}
+
+fn history_len(repo: &RepoHandle) -> usize {
+    let raw = repo.raw();
+    let head = raw.head_id().unwrap().detach();
+    raw.rev_walk([head]).all().unwrap().count()
+}
+
+#[tokio::test]
+async fn test_replace_create_list_delete() {
+    let (_temp_dir, repo, commits) = linear_history().await;
+
+    let created = create_replace(repo.clone(), &commits[1], &commits[0], false)
+        .await
+        .unwrap();
+    assert_eq!(created.original.to_string(), commits[1]);
+    assert_eq!(created.replacement.to_string(), commits[0]);
+    assert_eq!(created.kind, gix::object::Kind::Commit);
+
+    assert_eq!(list_replace(repo.clone()).await.unwrap(), [created]);
+
+    // Replacing again needs force
+    assert!(
+        create_replace(repo.clone(), &commits[1], &commits[2], false)
+            .await
+            .is_err()
+    );
+    create_replace(repo.clone(), &commits[1], &commits[2], true)
+        .await
+        .unwrap();
+
+    delete_replace(repo.clone(), &commits[1]).await.unwrap();
+    assert!(list_replace(repo.clone()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_replace_rejects_kind_mismatch() {
+    let (_temp_dir, repo, commits) = linear_history().await;
+
+    let tree = format!("{}^{{tree}}", commits[0]);
+    assert!(
+        create_replace(repo.clone(), &commits[1], &tree, false)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_graft_is_opt_in() {
+    let (temp_dir, repo, commits) = linear_history().await;
+
+    // Skip the middle commit
+    let grafted = graft(repo.clone(), &commits[2], &[commits[0].clone()], false)
+        .await
+        .unwrap();
+    assert_ne!(grafted.replacement.to_string(), commits[2]);
+
+    // Handles ignore replacements unless asked to honor them
+    let plain = RepoHandle::new(gix::open(temp_dir.path()).unwrap())
+        .with_replace_refs(false)
+        .unwrap();
+    assert_eq!(history_len(&plain), 3);
+
+    let honoring = RepoHandle::new(gix::open(temp_dir.path()).unwrap())
+        .with_replace_refs(true)
+        .unwrap();
+    assert_eq!(history_len(&honoring), 2);
+}
@@ -0,0 +1,69 @@
+//! Tests for review packets
+
+use kodegen_tools_git::operations::review::packet;
+use kodegen_tools_git::{RiskKind, Signature, init_repo};
+use tempfile::TempDir;
+
+use super::support::commit_files_as;
+
+#[tokio::test]
+async fn test_review_packet() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let alice = Signature::new("Alice", "alice@example.com");
+    let bob = Signature::new("Bob", "bob@example.com");
+
+    let base = commit_files_as(
+        &repo,
+        alice,
+        &[("src/lib.rs", "fn one() {}\nfn two() {}\n")],
+        "Add lib",
+    )
+    .await;
+    commit_files_as(
+        &repo,
+        bob.clone(),
+        &[
+            ("src/lib.rs", "fn one() {}\nfn three() {}\n"),
+            ("api.pb.go", "package api\n"),
+        ],
+        "Replace two with three\n\nTwo was never called.",
+    )
+    .await;
+    let head = commit_files_as(&repo, bob.clone(), &[("Cargo.lock", "v2\n")], "Lock").await;
+
+    let review = packet(repo.clone(), &base.to_string(), "HEAD").await.unwrap();
+    assert_eq!(review.head_id, head);
+    assert_eq!(review.merge_base, Some(base));
+    let messages: Vec<&str> = review.commits.iter().map(|c| c.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        ["Replace two with three\n\nTwo was never called.", "Lock"]
+    );
+
+    assert_eq!(review.diffstat.total_files_changed, 3);
+    assert!(
+        review
+            .patch
+            .contains("--- a/src/lib.rs\n+++ b/src/lib.rs\n")
+    );
+    assert!(review.patch.contains("-fn two() {}\n+fn three() {}\n"));
+    assert!(review.patch.contains("--- /dev/null\n+++ b/Cargo.lock\n"));
+    assert!(review.omitted_files.is_empty());
+    assert!(review.file_tree.contains("src/\n  lib.rs +1 -1\n"));
+    assert!(review.file_tree.contains("Cargo.lock +1 -0 (added)\n"));
+
+    // Alice wrote the changed lines; Bob is the branch's author
+    let emails: Vec<&str> = review.reviewers.iter().map(|r| r.email.as_str()).collect();
+    assert_eq!(emails, ["alice@example.com"]);
+
+    let kinds: Vec<RiskKind> = review.risks.iter().map(|r| r.kind).collect();
+    assert_eq!(kinds, [RiskKind::GeneratedCode]);
+    assert_eq!(review.risks[0].paths, ["api.pb.go"]);
+
+    // The last commit alone only touches a lockfile
+    let previous = review.commits[0].info.id.to_string();
+    let review = packet(repo.clone(), &previous, "HEAD").await.unwrap();
+    let kinds: Vec<RiskKind> = review.risks.iter().map(|r| r.kind).collect();
+    assert_eq!(kinds, [RiskKind::LockfileOnly]);
+}
@@ -0,0 +1,224 @@
+//! Tests for history rewriting
+
+use kodegen_tools_git::{
+    AddOpts, AuthorMapping, CommitOpts, IdentityRule, RepoHandle, Signature, add, commit,
+    head_commit, remove_paths, rewrite_authors,
+};
+
+use super::support::{commit_files, init_test_repo};
+
+/// Paths in the tree of every commit reachable from HEAD
+fn paths_in_history(repo: &RepoHandle) -> Vec<String> {
+    let raw = repo.raw();
+    let head = raw.head_id().unwrap().detach();
+    let mut paths = Vec::new();
+    for info in raw.rev_walk([head]).all().unwrap() {
+        let tree = raw.find_commit(info.unwrap().id).unwrap().tree().unwrap();
+        for entry in tree.iter() {
+            paths.push(entry.unwrap().filename().to_string());
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+#[tokio::test]
+async fn test_remove_paths_rewrites_history() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_test_repo(dir).await;
+
+    commit_files(&repo, &[("a.txt", "a\n"), ("secret.txt", "hunter2\n")], "initial").await;
+    commit_files(&repo, &[("a.txt", "a2\n")], "change a").await;
+    commit_files(&repo, &[("secret.txt", "hunter3\n")], "rotate secret").await;
+    let old_head = head_commit(repo.clone()).await.unwrap();
+
+    let result = remove_paths(repo.clone(), vec!["secret.txt".to_string()], "HEAD")
+        .await
+        .unwrap();
+
+    // The secret-only commit disappears, the others are recreated
+    assert_eq!(result.dropped_commits, 1);
+    assert_eq!(result.commit_map.len(), 3);
+    assert_eq!(result.updated_refs.len(), 1);
+    let new_head = head_commit(repo.clone()).await.unwrap();
+    assert_ne!(new_head, old_head);
+    assert_eq!(
+        result.new_id(old_head.parse().unwrap()).unwrap().to_string(),
+        new_head
+    );
+
+    assert_eq!(paths_in_history(&repo), ["a.txt"]);
+    let messages: Vec<String> = {
+        let raw = repo.raw();
+        let head = raw.head_id().unwrap().detach();
+        raw.rev_walk([head])
+            .all()
+            .unwrap()
+            .map(|info| {
+                let commit = raw.find_commit(info.unwrap().id).unwrap();
+                commit.message().unwrap().summary().to_string()
+            })
+            .collect()
+    };
+    assert_eq!(messages, ["change a", "initial"]);
+
+    // The working tree follows the new HEAD, so the file is gone from disk too
+    assert!(!dir.join("secret.txt").exists());
+    assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "a2\n");
+    let index = repo.raw().open_index().unwrap();
+    assert!(index.entry_by_path("secret.txt".into()).is_none());
+    assert!(index.entry_by_path("a.txt".into()).is_some());
+}
+
+#[tokio::test]
+async fn test_remove_paths_respects_range_base() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_test_repo(dir).await;
+
+    commit_files(&repo, &[("big.bin", "old\n")], "initial").await;
+    let base = head_commit(repo.clone()).await.unwrap();
+    commit_files(&repo, &[("a.txt", "a\n"), ("big.bin", "new\n")], "add a").await;
+
+    let range = format!("{base}..HEAD");
+    let result = remove_paths(repo.clone(), vec!["big.bin".to_string()], &range)
+        .await
+        .unwrap();
+    assert_eq!(result.commit_map.len(), 1);
+
+    // The base commit is kept, so big.bin is deleted by the rewritten commit
+    let raw = repo.raw();
+    let head = raw.head_commit().unwrap();
+    assert_eq!(head.parent_ids().next().unwrap().to_string(), base);
+    assert!(head.tree().unwrap().find_entry("big.bin").is_none());
+    assert!(head.tree().unwrap().find_entry("a.txt").is_some());
+}
+
+#[tokio::test]
+async fn test_remove_paths_without_matches_changes_nothing() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_test_repo(dir).await;
+
+    commit_files(&repo, &[("a.txt", "a\n")], "initial").await;
+    let head = head_commit(repo.clone()).await.unwrap();
+
+    let result = remove_paths(repo.clone(), vec!["missing.txt".to_string()], "HEAD")
+        .await
+        .unwrap();
+    assert!(result.commit_map.is_empty());
+    assert!(result.updated_refs.is_empty());
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), head);
+}
+
+#[tokio::test]
+async fn test_rewrite_authors_replaces_identities() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_test_repo(dir).await;
+
+    commit_files(&repo, &[("a.txt", "a\n")], "initial").await;
+    let human = head_commit(repo.clone()).await.unwrap();
+
+    std::fs::write(dir.join("a.txt"), "a2\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    let bot = Signature::new("root", "root@localhost");
+    commit(
+        repo.clone(),
+        CommitOpts::message("bot change").author(bot.clone()).committer(bot),
+    )
+    .await
+    .unwrap();
+
+    let mapping = AuthorMapping::parse_mailmap(
+        "# fix the release bot\nRelease Bot <bot@example.com> <ROOT@localhost>\n",
+    )
+    .unwrap();
+    let result = rewrite_authors(repo.clone(), mapping, "HEAD").await.unwrap();
+
+    // Only the bot commit changes; the first commit keeps its id
+    assert_eq!(result.commit_map.len(), 1);
+    assert!(result.new_id(human.parse().unwrap()).is_none());
+
+    let raw = repo.raw();
+    let head = raw.head_commit().unwrap();
+    assert_eq!(head.parent_ids().next().unwrap().to_string(), human);
+    let author = head.author().unwrap();
+    assert_eq!(author.name, "Release Bot");
+    assert_eq!(author.email, "bot@example.com");
+    let committer = head.committer().unwrap();
+    assert_eq!(committer.email, "bot@example.com");
+}
+
+#[tokio::test]
+async fn test_rewrite_authors_keeps_non_utf8_names() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    {
+        // A Latin-1 name, as old imported history has
+        let raw = repo.raw();
+        let signature = gix::actor::Signature {
+            name: b"Jos\xe9".as_slice().into(),
+            email: "jose@old.example.com".into(),
+            time: gix::date::Time::new(1_700_000_000, 0),
+        };
+        let commit = gix::objs::Commit {
+            tree: gix::ObjectId::empty_tree(raw.object_hash()),
+            parents: Default::default(),
+            author: signature.clone(),
+            committer: signature,
+            encoding: None,
+            message: "imported".into(),
+            extra_headers: Vec::new(),
+        };
+        let id = raw.write_object(&commit).unwrap().detach();
+        let branch = raw.head_name().unwrap().unwrap();
+        raw.reference(branch, id, gix::refs::transaction::PreviousValue::Any, "test")
+            .unwrap();
+    }
+
+    let mapping = AuthorMapping::new()
+        .rule(IdentityRule::for_email("jose@old.example.com").email("jose@example.com"));
+    let result = rewrite_authors(repo.clone(), mapping, "HEAD").await.unwrap();
+    assert_eq!(result.commit_map.len(), 1);
+
+    let head = repo.raw().head_commit().unwrap();
+    let author = head.author().unwrap();
+    assert_eq!(author.name, b"Jos\xe9".as_slice());
+    assert_eq!(author.email, "jose@example.com");
+}
+
+#[test]
+fn test_author_mapping_mailmap_forms() {
+    let mapping = AuthorMapping::parse_mailmap(
+        "Proper Name <old@example.com>\n\
+         <new@example.com> <email-only@example.com>\n\
+         Full <full@example.com> Ci Bot <ci@example.com> # by name\n",
+    )
+    .unwrap();
+    assert_eq!(mapping.rules().len(), 3);
+
+    assert_eq!(
+        mapping.resolve("whoever", "old@example.com"),
+        Some(("Proper Name".to_string(), "old@example.com".to_string()))
+    );
+    assert_eq!(
+        mapping.resolve("Someone", "email-only@example.com"),
+        Some(("Someone".to_string(), "new@example.com".to_string()))
+    );
+    assert_eq!(
+        mapping.resolve("ci bot", "ci@example.com"),
+        Some(("Full".to_string(), "full@example.com".to_string()))
+    );
+    assert_eq!(mapping.resolve("Other", "ci@example.com"), None);
+
+    let mapping = AuthorMapping::new().rule(IdentityRule::for_email("a@b").email("c@d"));
+    assert_eq!(
+        mapping.resolve("A", "A@B"),
+        Some(("A".to_string(), "c@d".to_string()))
+    );
+
+    assert!(AuthorMapping::parse_mailmap("<lonely@example.com>").is_err());
+}
@@ -0,0 +1,67 @@
+//! Tests for repository scaffolding
+
+use kodegen_tools_git::{
+    AddOpts, CommitOpts, ScaffoldOpts, Signature, add, commit, init_repo, list_branches, scaffold,
+};
+
+#[tokio::test]
+async fn test_scaffold_from_local_template() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("template");
+    std::fs::create_dir_all(template_dir.join("src")).unwrap();
+    let template = init_repo(&template_dir).await.unwrap().unwrap();
+
+    std::fs::write(
+        template_dir.join("README.md"),
+        "# {{ project }}\nBy {{author}}, see {{unknown}}\n",
+    )
+    .unwrap();
+    std::fs::write(template_dir.join("src/{{project}}.rs"), "// {{project}}\n").unwrap();
+    std::fs::write(template_dir.join("logo.bin"), b"\0{{project}}").unwrap();
+    add(template.clone(), AddOpts::new(["README.md", "src", "logo.bin"]))
+        .await
+        .unwrap();
+    for message in ["template", "template update"] {
+        std::fs::write(template_dir.join("notes.txt"), message).unwrap();
+        add(template.clone(), AddOpts::new(["notes.txt"])).await.unwrap();
+        commit(
+            template.clone(),
+            CommitOpts::message(message).author(Signature::new("Test", "test@example.com")),
+        )
+        .await
+        .unwrap();
+    }
+
+    let destination = temp_dir.path().join("demo");
+    let opts = ScaffoldOpts::new(template_dir.to_string_lossy(), &destination)
+        .variable("project", "demo")
+        .variable("author", "Jane")
+        .author(Signature::new("Jane", "jane@example.com"))
+        .branch("develop");
+    let result = scaffold(opts).await.unwrap();
+
+    let read = |path: &str| std::fs::read(destination.join(path)).unwrap();
+    assert_eq!(read("README.md"), b"# demo\nBy Jane, see {{unknown}}\n");
+    assert_eq!(read("src/demo.rs"), b"// demo\n");
+    assert_eq!(read("logo.bin"), b"\0{{project}}");
+    assert_eq!(result.files, 4);
+    assert_eq!(result.rewritten_files, 2);
+    assert_eq!(result.unresolved, ["unknown"]);
+
+    // A single commit, without the template's history
+    let head = result.repo.raw().find_commit(result.commit_id).unwrap();
+    assert_eq!(head.parent_ids().count(), 0);
+    assert_eq!(head.message().unwrap().summary().to_string(), "Initial commit");
+
+    assert_eq!(result.branches.len(), 2);
+    assert_eq!(result.branches[1], "develop");
+    let mut branches = list_branches(result.repo.clone()).await.unwrap().unwrap();
+    branches.sort();
+    let mut expected = result.branches.clone();
+    expected.sort();
+    assert_eq!(branches, expected);
+
+    // The destination is now populated
+    let again = ScaffoldOpts::new(template_dir.to_string_lossy(), &destination);
+    assert!(scaffold(again).await.is_err());
+}
@@ -0,0 +1,47 @@
+//! Tests for commit search.
+
+use kodegen_tools_git::{CommitQuery, Signature, init_repo, operations::search};
+use tempfile::TempDir;
+
+use super::support::commit_files_as;
+
+fn by(name: &str) -> Signature {
+    Signature::new(name, format!("{name}@example.com"))
+}
+
+#[tokio::test]
+async fn test_search_commits() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    commit_files_as(&repo, by("alice"), &[("a.rs", "fn main() {}\n")], "Initial commit").await;
+    let retry = [("a.rs", "fn main() { retry(); }\n")];
+    commit_files_as(&repo, by("bob"), &retry, "Add retry loop").await;
+    let notes = [("b.rs", "// notes\n")];
+    commit_files_as(&repo, by("alice"), &notes, "Docs\n\nMentions retry in the body").await;
+
+    // Summary matches rank above body matches
+    let result = search::commits(repo.clone(), CommitQuery::new().message("retry").unwrap())
+        .await
+        .unwrap();
+    assert_eq!(result.commits.len(), 2);
+    assert_eq!(result.commits[0].summary, "Add retry loop");
+    assert!(result.commits[0].summary_match);
+
+    // Criteria are combined
+    let result = search::commits(
+        repo.clone(),
+        CommitQuery::new().message("retry").unwrap().author("alice"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.commits.len(), 1);
+    assert_eq!(result.commits[0].summary, "Docs");
+
+    // Pickaxe finds the commit that introduced the call
+    let result = search::commits(repo, CommitQuery::new().pickaxe("retry()"))
+        .await
+        .unwrap();
+    assert_eq!(result.commits.len(), 1);
+    assert_eq!(result.commits[0].pickaxe_paths, vec!["a.rs".to_string()]);
+}
@@ -0,0 +1,90 @@
+//! Tests for shallow repository detection and deepening
+
+use kodegen_tools_git::{
+    AddOpts, CloneOpts, CommitOpts, Deepen, HistoryOpts, HistoryResult, LogOpts, RepoHandle,
+    Signature, add, clone_repo, commit, deepen, head_commit, history, init_repo, log,
+    probe_repository,
+};
+use tokio_stream::StreamExt;
+
+/// Repository with three commits to `file.txt`, oldest first
+async fn linear_history(dir: &std::path::Path) -> (RepoHandle, Vec<String>) {
+    let repo = init_repo(dir).await.unwrap().unwrap();
+
+    let mut commits = Vec::new();
+    for n in 1..=3 {
+        std::fs::write(dir.join("file.txt"), format!("{n}\n")).unwrap();
+        add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+        commit(
+            repo.clone(),
+            CommitOpts::message(format!("commit {n}"))
+                .author(Signature::new("Test", "test@example.com")),
+        )
+        .await
+        .unwrap();
+        commits.push(head_commit(repo.clone()).await.unwrap());
+    }
+
+    (repo, commits)
+}
+
+#[tokio::test]
+async fn test_log_and_history_report_shallow_boundary() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (_, commits) = linear_history(temp_dir.path()).await;
+
+    // Cut the history below the second commit, as a depth-2 clone would
+    std::fs::write(temp_dir.path().join(".git/shallow"), format!("{}\n", commits[1])).unwrap();
+    let info = probe_repository(temp_dir.path()).await.unwrap().unwrap();
+    assert!(info.is_shallow);
+
+    let repo = RepoHandle::new(gix::open(temp_dir.path()).unwrap());
+    let mut stream = log(repo.clone(), LogOpts::new(), None);
+    let mut entries = Vec::new();
+    while let Some(entry) = stream.next().await {
+        entries.push(entry.unwrap());
+    }
+    let flags: Vec<bool> = entries.iter().map(|c| c.shallow_boundary).collect();
+    assert_eq!(flags, [false, true]);
+    assert_eq!(entries[1].id.to_string(), commits[1]);
+
+    let file = temp_dir.path().join("file.txt");
+    let HistoryResult::Commits {
+        commits: changes,
+        reached_shallow_boundary,
+        ..
+    } = history(repo, HistoryOpts::new(file)).await.unwrap()
+    else {
+        panic!("expected per-commit history");
+    };
+    assert!(reached_shallow_boundary);
+    // The boundary commit is shown as adding the file
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[1].deletions, 0);
+}
+
+#[tokio::test]
+async fn test_deepen_shallow_clone() {
+    let upstream = tempfile::TempDir::new().unwrap();
+    linear_history(upstream.path()).await;
+
+    let clone_dir = tempfile::TempDir::new().unwrap();
+    let url = format!("file://{}", upstream.path().display());
+    let repo = clone_repo(CloneOpts::new(url, clone_dir.path().join("clone")).shallow(1))
+        .await
+        .unwrap()
+        .unwrap()
+        .repo;
+    assert!(repo.raw().is_shallow());
+
+    let boundary = deepen(repo.clone(), "origin", Deepen::By(1)).await.unwrap();
+    assert_eq!(boundary.len(), 1);
+
+    let boundary = deepen(repo.clone(), "origin", Deepen::Unshallow)
+        .await
+        .unwrap();
+    assert!(boundary.is_empty());
+
+    // A complete repository cannot be deepened
+    assert!(deepen(repo, "origin", Deepen::By(1)).await.is_err());
+}
@@ -0,0 +1,109 @@
+//! Tests for detailed commit information.
+
+use kodegen_tools_git::{
+    AddOpts, ChangeType, CommitOpts, DecorationKind, Signature, TagOpts, add, commit, create_tag,
+    get_commit_details,
+};
+use tempfile::TempDir;
+
+use super::support::init_test_repo;
+
+#[tokio::test]
+async fn test_commit_details_body_stats_and_refs() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let author = Signature::new("Test", "test@example.com");
+
+    std::fs::write(temp_dir.path().join("a.txt"), "one\ntwo\nthree\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    let first = commit(repo.clone(), CommitOpts::message("first").author(author.clone()))
+        .await
+        .unwrap();
+
+    std::fs::write(temp_dir.path().join("a.txt"), "one\n2\nthree\nfour\n").unwrap();
+    std::fs::write(temp_dir.path().join("b.txt"), "new\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt", "b.txt"])).await.unwrap();
+    let message =
+        "Change a and add b\n\nLonger explanation.\n\nSigned-off-by: Test <test@example.com>\n";
+    commit(repo.clone(), CommitOpts::message(message).author(author))
+        .await
+        .unwrap();
+    create_tag(repo.clone(), TagOpts::new("v1").message("release")).await.unwrap();
+
+    let info = get_commit_details(repo.clone(), "HEAD").await.unwrap();
+    assert_eq!(info.message, "Change a and add b");
+    assert!(info.body.starts_with("Longer explanation."));
+    assert_eq!(info.trailers.len(), 1);
+    assert_eq!(info.trailers[0].token, "Signed-off-by");
+    assert_eq!(info.trailers[0].value, "Test <test@example.com>");
+    assert_eq!(info.parent_ids, [first.id]);
+
+    let stats = &info.diffstat;
+    assert_eq!(stats.total_files_changed, 2);
+    assert_eq!(stats.files[0].path, "a.txt");
+    assert_eq!(stats.files[0].change_type, ChangeType::Modified);
+    assert_eq!((stats.files[0].additions, stats.files[0].deletions), (2, 1));
+    assert_eq!(stats.files[1].change_type, ChangeType::Added);
+
+    let kinds: Vec<_> = info.decorations.iter().map(|d| d.kind).collect();
+    assert_eq!(kinds, [
+        DecorationKind::Head,
+        DecorationKind::LocalBranch,
+        DecorationKind::Tag
+    ]);
+    assert_eq!(info.decorations[2].name, "v1");
+
+    // Root commits are diffed against the empty tree
+    let root = get_commit_details(repo.clone(), &first.id.to_string()).await.unwrap();
+    assert!(root.body.is_empty());
+    assert!(root.trailers.is_empty());
+    assert!(root.decorations.is_empty());
+    assert_eq!(root.diffstat.total_additions, 3);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_commit_details_mode_and_symlink_changes() {
+    use std::os::unix::fs::{PermissionsExt, symlink};
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let author = Signature::new("Test", "test@example.com");
+    let workdir = temp_dir.path();
+
+    std::fs::write(workdir.join("run.sh"), "echo hi\n").unwrap();
+    std::fs::write(workdir.join("a.txt"), "a\n").unwrap();
+    std::fs::write(workdir.join("b.txt"), "b\n").unwrap();
+    symlink("a.txt", workdir.join("link")).unwrap();
+    add(repo.clone(), AddOpts::new(["run.sh", "a.txt", "b.txt", "link"]))
+        .await
+        .unwrap();
+    commit(repo.clone(), CommitOpts::message("first").author(author.clone()))
+        .await
+        .unwrap();
+
+    std::fs::set_permissions(workdir.join("run.sh"), std::fs::Permissions::from_mode(0o755))
+        .unwrap();
+    std::fs::remove_file(workdir.join("link")).unwrap();
+    symlink("b.txt", workdir.join("link")).unwrap();
+    add(repo.clone(), AddOpts::new(["run.sh", "link"])).await.unwrap();
+    commit(repo.clone(), CommitOpts::message("second").author(author))
+        .await
+        .unwrap();
+
+    let info = get_commit_details(repo.clone(), "HEAD").await.unwrap();
+    let files = &info.diffstat.files;
+    assert_eq!(files.len(), 2);
+
+    let link = files.iter().find(|f| f.path == "link").unwrap();
+    assert!(link.is_symlink);
+    assert_eq!(link.change_type, ChangeType::Modified);
+    assert_eq!((link.additions, link.deletions), (0, 0));
+    assert_eq!(link.mode_change, None);
+
+    let script = files.iter().find(|f| f.path == "run.sh").unwrap();
+    assert!(!script.is_symlink);
+    let mode_change = script.mode_change.unwrap();
+    assert_eq!((mode_change.from, mode_change.to), (0o100644, 0o100755));
+    assert_eq!((script.additions, script.deletions), (0, 0));
+}
@@ -0,0 +1,93 @@
+//! Tests for worktree snapshots
+
+use kodegen_tools_git::{
+    AddOpts, CommitOpts, RepoHandle, Signature, add, commit, drop_snapshot, init_repo,
+    list_snapshots, restore_snapshot, snapshot_worktree,
+};
+
+/// Repository with `a.txt` and `b.txt` committed
+async fn fixture() -> (tempfile::TempDir, RepoHandle) {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    std::fs::write(temp_dir.path().join("a.txt"), "a\n").unwrap();
+    std::fs::write(temp_dir.path().join("b.txt"), "b\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt", "b.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("initial").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    (temp_dir, repo)
+}
+
+#[tokio::test]
+async fn test_snapshot_restore_roundtrip() {
+    let (temp_dir, repo) = fixture().await;
+    let path = |name: &str| temp_dir.path().join(name);
+
+    // Staged and unstaged edits to a.txt, b.txt deleted, c.txt untracked
+    std::fs::write(path("a.txt"), "a staged\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    std::fs::write(path("a.txt"), "a on disk\n").unwrap();
+    std::fs::remove_file(path("b.txt")).unwrap();
+    std::fs::write(path("c.txt"), "c\n").unwrap();
+
+    let snapshot = snapshot_worktree(repo.clone(), Some("before refactor"))
+        .await
+        .unwrap();
+    assert_eq!(snapshot.message, "before refactor");
+    assert!(snapshot.head.is_some());
+
+    // Taking the snapshot leaves everything in place and the stash alone
+    assert_eq!(std::fs::read_to_string(path("a.txt")).unwrap(), "a on disk\n");
+    assert!(path("c.txt").exists());
+    assert!(
+        repo.raw()
+            .try_find_reference("refs/stash")
+            .unwrap()
+            .is_none()
+    );
+
+    // Make a mess
+    std::fs::write(path("a.txt"), "clobbered\n").unwrap();
+    std::fs::write(path("b.txt"), "b is back\n").unwrap();
+    std::fs::remove_file(path("c.txt")).unwrap();
+    std::fs::write(path("d.txt"), "d\n").unwrap();
+
+    let result = restore_snapshot(repo.clone(), &snapshot.id).await.unwrap();
+    assert!(result.head_matches);
+    assert_eq!(result.snapshot.id, snapshot.id);
+
+    assert_eq!(std::fs::read_to_string(path("a.txt")).unwrap(), "a on disk\n");
+    assert!(!path("b.txt").exists());
+    assert_eq!(std::fs::read_to_string(path("c.txt")).unwrap(), "c\n");
+    assert!(!path("d.txt").exists());
+
+    // The staged content comes back in the index
+    let raw = repo.raw();
+    let index = raw.open_index().unwrap();
+    let entry = index.entry_by_path("a.txt".into()).unwrap();
+    let blob = raw.find_blob(entry.id).unwrap();
+    assert_eq!(blob.data, b"a staged\n");
+    assert!(index.entry_by_path("b.txt".into()).is_some());
+    assert!(index.entry_by_path("c.txt".into()).is_none());
+}
+
+#[tokio::test]
+async fn test_snapshot_list_and_drop() {
+    let (temp_dir, repo) = fixture().await;
+
+    std::fs::write(temp_dir.path().join("a.txt"), "changed\n").unwrap();
+    let snapshot = snapshot_worktree(repo.clone(), None).await.unwrap();
+    assert_eq!(snapshot.message, "snapshot");
+
+    let listed = list_snapshots(repo.clone()).await.unwrap();
+    assert_eq!(listed, std::slice::from_ref(&snapshot));
+
+    drop_snapshot(repo.clone(), &snapshot.id).await.unwrap();
+    assert!(list_snapshots(repo.clone()).await.unwrap().is_empty());
+    assert!(restore_snapshot(repo.clone(), &snapshot.id).await.is_err());
+}
@@ -0,0 +1,92 @@
+//! Tests for sparse index handling; sparse checkouts are set up with the git CLI.
+
+use std::path::Path;
+use std::process::Command;
+
+use kodegen_tools_git::{AddOpts, CommitOpts, add, commit, is_clean, open_repo};
+use tempfile::TempDir;
+
+use super::support::{commit_files, init_test_repo};
+
+fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?}: {output:?}");
+}
+
+/// A repository with `a/` checked out and `b/` collapsed in a sparse index
+async fn sparse_fixture() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_files(
+        &repo,
+        &[("a/x.txt", "x\n"), ("b/y.txt", "y\n"), ("b/c/z.txt", "z\n")],
+        "initial",
+    )
+    .await;
+    git(
+        temp_dir.path(),
+        &["sparse-checkout", "set", "--cone", "--sparse-index", "a"],
+    );
+    temp_dir
+}
+
+#[tokio::test]
+async fn test_sparse_index_stays_collapsed() {
+    let temp_dir = sparse_fixture().await;
+    let repo = open_repo(temp_dir.path())
+        .await
+        .unwrap()
+        .unwrap()
+        .with_sparse_index(true);
+    assert!(repo.raw().open_index().unwrap().is_sparse());
+    assert!(is_clean(repo.clone()).await.unwrap());
+
+    std::fs::write(temp_dir.path().join("a/x.txt"), "x2\n").unwrap();
+    assert!(!is_clean(repo.clone()).await.unwrap());
+
+    // Collapsed directories aren't missing files to delete
+    let result = add(repo.clone(), AddOpts::new(Vec::<String>::new()).all(true))
+        .await
+        .unwrap();
+    assert_eq!(result.added, ["a/x.txt"]);
+    assert!(result.deleted.is_empty());
+
+    let index = repo.raw().open_index().unwrap();
+    assert!(index.is_sparse());
+    assert!(index.entry_by_path("b/".into()).is_some());
+    assert!(index.entry_by_path("b/y.txt".into()).is_none());
+
+    let id = commit(repo.clone(), CommitOpts::message("change a"))
+        .await
+        .unwrap()
+        .id;
+    let tree = repo.raw().find_commit(id).unwrap().tree().unwrap();
+    assert!(tree.lookup_entry_by_path("b/c/z.txt").unwrap().is_some());
+    assert!(is_clean(repo.clone()).await.unwrap());
+
+    // git reads the index as written
+    git(temp_dir.path(), &["diff", "--cached", "--quiet"]);
+}
+
+#[tokio::test]
+async fn test_sparse_index_is_expanded_by_default() {
+    let temp_dir = sparse_fixture().await;
+    let repo = open_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    std::fs::write(temp_dir.path().join("a/x.txt"), "x2\n").unwrap();
+    let result = add(repo.clone(), AddOpts::new(["a/x.txt"])).await.unwrap();
+    assert_eq!(result.added, ["a/x.txt"]);
+
+    let index = repo.raw().open_index().unwrap();
+    assert!(index.entry_by_path("b/".into()).is_none());
+    let expanded = index.entry_by_path("b/c/z.txt".into()).unwrap();
+    assert!(
+        expanded
+            .flags
+            .contains(gix::index::entry::Flags::SKIP_WORKTREE)
+    );
+}
@@ -0,0 +1,181 @@
+//! Tests for git status operations.
+
+use kodegen_tools_git::{
+    AddOpts, CommitOpts, Signature, WarningKind, add, commit, current_branch, init_repo, is_clean,
+    is_clean_fast, list_remotes, open_repo, workspace_status,
+};
+
+use super::support::{commit_file, init_test_repo};
+
+#[tokio::test]
+async fn test_is_clean_fast_matches_full_check() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let file = temp_dir.path().join("file.txt");
+
+    std::fs::write(&file, "one").unwrap();
+    add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("initial").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    assert!(is_clean(repo.clone()).await.unwrap());
+    assert!(is_clean_fast(repo.clone()).await.unwrap());
+    // Served from the cache
+    assert!(is_clean_fast(repo.clone()).await.unwrap());
+
+    std::fs::write(&file, "two, longer").unwrap();
+    assert!(!is_clean(repo.clone()).await.unwrap());
+    assert!(!is_clean_fast(repo.clone()).await.unwrap());
+
+    // Staging invalidates the cached index state
+    add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+    assert!(!is_clean_fast(repo.clone()).await.unwrap());
+
+    commit(
+        repo.clone(),
+        CommitOpts::message("second").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+    assert!(is_clean_fast(repo.clone()).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_untracked_cache_notices_changed_directories() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    commit_file(&repo, "file.txt", "one\n").await;
+    let sub = temp_dir.path().join("sub");
+    std::fs::create_dir(&sub).unwrap();
+    std::fs::write(sub.join("new.txt"), "new\n").unwrap();
+    let path = temp_dir.path().to_string_lossy().into_owned();
+
+    // Old enough for the result to be cached
+    std::thread::sleep(std::time::Duration::from_millis(2100));
+    assert!(workspace_status([path.as_str()]).await.unwrap()[0].has_untracked);
+    assert!(workspace_status([path.as_str()]).await.unwrap()[0].has_untracked);
+
+    // Removing the file changes the directory's modification time
+    std::fs::remove_file(sub.join("new.txt")).unwrap();
+    assert!(!workspace_status([path.as_str()]).await.unwrap()[0].has_untracked);
+}
+
+#[tokio::test]
+async fn test_list_remotes_details() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let branch = repo.raw().head_name().unwrap().unwrap().as_ref().shorten().to_string();
+
+    let git_dir = temp_dir.path().join(".git");
+    let mut config = std::fs::read_to_string(git_dir.join("config")).unwrap();
+    config.push_str(
+        "[remote \"origin\"]\n\
+         \turl = https://example.com/origin.git\n\
+         \tfetch = +refs/heads/*:refs/remotes/origin/*\n\
+         [remote \"upstream\"]\n\
+         \turl = https://example.com/upstream.git\n\
+         \tfetch = +refs/heads/*:refs/remotes/upstream/*\n\
+         \tpush = refs/heads/main:refs/heads/main\n",
+    );
+    std::fs::write(git_dir.join("config"), &config).unwrap();
+    let fetch_head = format!(
+        "{}\t\tbranch 'main' of https://example.com/upstream\n",
+        "1".repeat(40)
+    );
+    std::fs::write(git_dir.join("FETCH_HEAD"), fetch_head).unwrap();
+
+    let repo = open_repo(temp_dir.path()).await.unwrap().unwrap();
+    let remotes = list_remotes(repo.clone()).await.unwrap();
+    let [origin, upstream] = remotes.as_slice() else {
+        panic!("expected two remotes, got {remotes:?}");
+    };
+    assert_eq!(origin.name, "origin");
+    assert_eq!(origin.fetch_refspecs, ["+refs/heads/*:refs/remotes/origin/*"]);
+    assert!(origin.push_refspecs.is_empty());
+    // Without branch configuration, origin is the default
+    assert!(origin.is_default);
+    assert!(origin.last_fetched.is_none());
+
+    assert_eq!(upstream.push_refspecs, ["refs/heads/main:refs/heads/main"]);
+    assert!(!upstream.is_default);
+    assert!(upstream.last_fetched.is_some());
+
+    // The remote of the current branch takes precedence
+    config.push_str(&format!(
+        "[branch \"{branch}\"]\n\tremote = upstream\n\tmerge = refs/heads/main\n"
+    ));
+    std::fs::write(git_dir.join("config"), &config).unwrap();
+    let repo = open_repo(temp_dir.path()).await.unwrap().unwrap();
+    let remotes = list_remotes(repo.clone()).await.unwrap();
+    let defaults: Vec<_> = remotes
+        .iter()
+        .filter(|remote| remote.is_default)
+        .map(|remote| remote.name.as_str())
+        .collect();
+    assert_eq!(defaults, ["upstream"]);
+}
+
+#[tokio::test]
+async fn test_unfetched_upstream_is_a_warning() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    super::support::commit_file(&repo, "file.txt", "one").await;
+    let branch = repo
+        .raw()
+        .head_name()
+        .unwrap()
+        .unwrap()
+        .as_ref()
+        .shorten()
+        .to_string();
+
+    let info = current_branch(repo.clone()).await.unwrap();
+    assert!(info.upstream.is_none());
+    assert!(info.warnings.is_empty());
+
+    let config_path = temp_dir.path().join(".git").join("config");
+    let mut config = std::fs::read_to_string(&config_path).unwrap();
+    config.push_str(&format!(
+        "[remote \"origin\"]\n\
+         \turl = https://example.com/origin.git\n\
+         \tfetch = +refs/heads/*:refs/remotes/origin/*\n\
+         [branch \"{branch}\"]\n\tremote = origin\n\tmerge = refs/heads/{branch}\n"
+    ));
+    std::fs::write(&config_path, config).unwrap();
+
+    let repo = open_repo(temp_dir.path()).await.unwrap().unwrap();
+    let info = current_branch(repo.clone()).await.unwrap();
+    assert_eq!(info.upstream, Some(format!("origin/{branch}")));
+    assert_eq!(info.ahead_count, None);
+    let [warning] = info.warnings.as_slice() else {
+        panic!("expected one warning, got {:?}", info.warnings);
+    };
+    assert_eq!(warning.kind, WarningKind::UpstreamUnavailable);
+    assert_eq!(warning.subject, Some(format!("origin/{branch}")));
+}
+
+#[tokio::test]
+async fn test_list_remotes_keeps_unreadable_remote() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    init_repo(temp_dir.path()).await.unwrap().unwrap();
+    let config = temp_dir.path().join(".git/config");
+    let mut content = std::fs::read_to_string(&config).unwrap();
+    content.push_str("[remote \"good\"]\n\turl = https://example.com/good.git\n");
+    content.push_str("[remote \"broken\"]\n\turl = https://example.com/broken.git\n");
+    content.push_str("\tfetch = refs/heads/*:refs/remotes/broken/*:extra\n");
+    content.push_str("[remote \"pushonly\"]\n\tpushurl = https://example.com/push.git\n");
+    std::fs::write(&config, content).unwrap();
+
+    let repo = open_repo(temp_dir.path()).await.unwrap().unwrap();
+    let remotes = list_remotes(repo.clone()).await.unwrap();
+    let find = |name: &str| remotes.iter().find(|r| r.name == name).unwrap();
+    assert!(find("good").warnings.is_empty());
+    let broken = find("broken");
+    assert_eq!(broken.warnings.len(), 1, "{:?}", broken.warnings);
+    assert_eq!(broken.warnings[0].kind, WarningKind::UnreadableRemote);
+    assert_eq!(find("pushonly").warnings[0].kind, WarningKind::UnreadableRemote);
+}
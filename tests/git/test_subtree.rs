@@ -0,0 +1,104 @@
+//! Tests for subtree add, merge and split
+
+use kodegen_tools_git::{head_commit, is_clean, subtree_add, subtree_merge, subtree_split};
+
+use super::support::{commit_files, init_test_repo, write_commit};
+
+#[tokio::test]
+async fn test_subtree_split_extracts_directory() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_test_repo(dir).await;
+
+    commit_files(&repo, &[("README.md", "readme\n")], "change").await;
+    let first = commit_files(&repo, &[("lib/a.txt", "a\n")], "change").await;
+    commit_files(&repo, &[("README.md", "readme 2\n")], "change").await;
+    let second = commit_files(&repo, &[("lib/b.txt", "b\n")], "change").await;
+
+    let split = subtree_split(repo.clone(), "lib/", "HEAD").await.unwrap();
+    let tip = split.tip.unwrap();
+    let raw = repo.raw();
+    let tip_commit = raw.find_commit(tip).unwrap();
+    let tree = tip_commit.tree().unwrap();
+    assert!(tree.lookup_entry_by_path("b.txt").unwrap().is_some());
+    assert!(tree.lookup_entry_by_path("README.md").unwrap().is_none());
+
+    // The README-only commit is skipped
+    let first_split = split.split_id(first).unwrap();
+    assert_eq!(split.split_id(second), Some(tip));
+    let parents: Vec<_> = tip_commit.parent_ids().map(gix::Id::detach).collect();
+    assert_eq!(parents, [first_split]);
+    assert_eq!(raw.find_commit(first_split).unwrap().parent_ids().count(), 0);
+
+    // Splitting again after more work extends the same history
+    commit_files(&repo, &[("lib/a.txt", "a 2\n")], "change").await;
+    let again = subtree_split(repo.clone(), "lib", "HEAD").await.unwrap();
+    let new_tip = raw.find_commit(again.tip.unwrap()).unwrap();
+    let parents: Vec<_> = new_tip.parent_ids().map(gix::Id::detach).collect();
+    assert_eq!(parents, [tip]);
+
+    assert!(subtree_split(repo.clone(), "../lib", "HEAD").await.is_err());
+}
+
+#[tokio::test]
+async fn test_subtree_add_and_merge() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_test_repo(dir).await;
+    commit_files(&repo, &[("README.md", "readme\n")], "change").await;
+
+    // Commits at the root of a separate history, as a fetched repository
+    let v1_files = [("lib.txt", "1\n2\n3\n"), ("x.txt", "x\n")];
+    let v1 = write_commit(repo.raw(), &[], &v1_files, "upstream");
+    let added = subtree_add(repo.clone(), "vendor/lib", &v1.to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(added.source, v1);
+    let read = |path: &str| std::fs::read_to_string(dir.join(path)).unwrap();
+    assert_eq!(read("vendor/lib/lib.txt"), "1\n2\n3\n");
+    assert!(is_clean(repo.clone()).await.unwrap());
+
+    // Adding over an existing prefix is refused
+    assert!(subtree_add(repo.clone(), "vendor/lib", &v1.to_string(), None).await.is_err());
+
+    // Local edit below the prefix, upstream edit elsewhere in the same file
+    commit_files(&repo, &[("vendor/lib/lib.txt", "one\n2\n3\n")], "change").await;
+    let v2 = write_commit(repo.raw(), &[v1], &[("lib.txt", "1\n2\nthree\n")], "upstream");
+    let merged = subtree_merge(repo.clone(), "vendor/lib", &v2.to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), merged.commit_id.to_string());
+    assert_eq!(read("vendor/lib/lib.txt"), "one\n2\nthree\n");
+    assert_eq!(read("README.md"), "readme\n");
+    assert!(is_clean(repo.clone()).await.unwrap());
+
+    assert!(subtree_merge(repo.clone(), "vendor/lib", &v2.to_string(), None).await.is_err());
+
+    // Splitting reuses the upstream commits
+    let split = subtree_split(repo.clone(), "vendor/lib", "HEAD").await.unwrap();
+    let tip = repo.raw().find_commit(split.tip.unwrap()).unwrap();
+    let parents: Vec<_> = tip.parent_ids().map(gix::Id::detach).collect();
+    assert_eq!(parents.len(), 2);
+    assert!(parents.contains(&v2));
+}
+
+#[tokio::test]
+async fn test_subtree_add_keeps_untracked_files() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let repo = init_test_repo(dir).await;
+    commit_files(&repo, &[("README.md", "readme\n")], "change").await;
+    let head = head_commit(repo.clone()).await.unwrap();
+    let upstream = write_commit(repo.raw(), &[], &[("lib.txt", "lib\n")], "upstream");
+
+    let file = dir.join("vendor/lib.txt");
+    std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+    std::fs::write(&file, "mine\n").unwrap();
+    let err = subtree_add(repo.clone(), "vendor", &upstream.to_string(), None)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("vendor/lib.txt"), "{err}");
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "mine\n");
+    assert_eq!(head_commit(repo.clone()).await.unwrap(), head);
+}
@@ -0,0 +1,138 @@
+//! Tests for clone-or-update sync
+
+use kodegen_tools_git::{GitError, SyncAction, SyncOpts, TagOpts, create_tag, sync_repo};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo, with_identity};
+
+#[tokio::test]
+async fn test_sync_clones_then_updates() {
+    let temp_dir = TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_test_repo(&upstream_dir).await;
+    let first = commit_file(&upstream, "app.txt", "1\n").await;
+    let url = format!("file://{}", upstream_dir.display());
+    let checkout = temp_dir.path().join("checkout");
+
+    let result = sync_repo(&url, &checkout, None, SyncOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(result.action, SyncAction::Cloned);
+    assert_eq!(result.previous, None);
+    assert_eq!(result.head, Some(first));
+    let branch = result.branch.clone().unwrap();
+    with_identity(&result.repo).await;
+
+    let result = sync_repo(&url, &checkout, Some(&branch), SyncOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(result.action, SyncAction::UpToDate);
+    assert_eq!(result.head, Some(first));
+
+    let second = commit_file(&upstream, "app.txt", "2\n").await;
+    let result = sync_repo(&url, &checkout, None, SyncOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(result.action, SyncAction::FastForwarded);
+    assert_eq!(result.previous, Some(first));
+    assert_eq!(result.head, Some(second));
+    assert_eq!(
+        std::fs::read_to_string(checkout.join("app.txt")).unwrap(),
+        "2\n"
+    );
+
+    // Tags are checked out detached
+    create_tag(
+        upstream.clone(),
+        TagOpts {
+            name: "v1".to_string(),
+            target: Some(first.to_string()),
+            message: None,
+            force: false,
+            tagger: None,
+        },
+    )
+    .await
+    .unwrap();
+    let result = sync_repo(&url, &checkout, Some("v1"), SyncOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(result.action, SyncAction::Switched);
+    assert_eq!(result.head, Some(first));
+    assert_eq!(result.branch, None);
+
+    let result = sync_repo(&url, &checkout, Some(&branch), SyncOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(result.action, SyncAction::Switched);
+    assert_eq!(result.head, Some(second));
+    assert_eq!(result.branch.as_deref(), Some(branch.as_str()));
+}
+
+#[tokio::test]
+async fn test_sync_refuses_other_repository_and_local_work() {
+    let temp_dir = TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_test_repo(&upstream_dir).await;
+    let tip = commit_file(&upstream, "app.txt", "1\n").await;
+    let url = format!("file://{}", upstream_dir.display());
+    let checkout = temp_dir.path().join("checkout");
+
+    let clone = sync_repo(&url, &checkout, None, SyncOpts::new())
+        .await
+        .unwrap()
+        .repo;
+    let clone = with_identity(&clone).await;
+
+    let other = format!("file://{}", temp_dir.path().join("other").display());
+    let error = sync_repo(&other, &checkout, None, SyncOpts::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+
+    // Same repository with a trailing `.git` still matches
+    let result = sync_repo(&format!("{url}.git"), &checkout, None, SyncOpts::new())
+        .await
+        .unwrap();
+    assert_eq!(result.action, SyncAction::UpToDate);
+
+    // Uncommitted changes
+    std::fs::write(checkout.join("app.txt"), "local\n").unwrap();
+    let error = sync_repo(&url, &checkout, None, SyncOpts::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+    let result = sync_repo(&url, &checkout, None, SyncOpts::new().force(true))
+        .await
+        .unwrap();
+    assert_eq!(result.action, SyncAction::Reset);
+    assert_eq!(
+        std::fs::read_to_string(checkout.join("app.txt")).unwrap(),
+        "1\n"
+    );
+
+    // Local commits
+    let local = commit_file(&clone, "app.txt", "local\n").await;
+    let error = sync_repo(&url, &checkout, None, SyncOpts::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, GitError::InvalidInput(_)), "{error}");
+    let result = sync_repo(&url, &checkout, None, SyncOpts::new().force(true))
+        .await
+        .unwrap();
+    assert_eq!(result.action, SyncAction::Reset);
+    assert_eq!(result.previous, Some(local));
+    assert_eq!(result.head, Some(tip));
+
+    // Not a repository
+    let plain = temp_dir.path().join("plain");
+    std::fs::create_dir(&plain).unwrap();
+    std::fs::write(plain.join("file"), "x").unwrap();
+    assert!(
+        sync_repo(&url, &plain, None, SyncOpts::new())
+            .await
+            .is_err()
+    );
+}
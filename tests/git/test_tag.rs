@@ -1,77 +1,116 @@
 //! Tests for Git tag operations
 
-use kodegen_tools_git::{create_tag, delete_tag, tag_exists, list_tags, TagOpts, init_repo};
+use chrono::TimeZone;
+use kodegen_tools_git::{
+    AddOpts, CommitOpts, RepoHandle, Signature, TagOpts, WarningKind, add, commit, create_tag,
+    delete_tag, init_repo, list_tags, parse_date, tag_exists,
+};
 use tempfile::TempDir;
 
+async fn repo_with_commit(temp_dir: &TempDir) -> RepoHandle {
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    std::fs::write(temp_dir.path().join("test.txt"), "test").unwrap();
+    add(repo.clone(), AddOpts::new(["test.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("Initial commit").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+    repo
+}
+
+fn tagger(seconds: i64) -> Signature {
+    let time = chrono::Utc.timestamp_opt(seconds, 0).unwrap();
+    Signature::with_time("Tagger", "tagger@example.com", time)
+}
+
 #[tokio::test]
-async fn test_create_and_list_tags() -> Result<(), Box<dyn std::error::Error>> {
-    let temp_dir = TempDir::new()?;
-    let repo = init_repo(temp_dir.path())?;
-    
-    // Create initial commit
-    std::fs::write(temp_dir.path().join("test.txt"), "test")?;
-    kodegen_tools_git::add(&repo, kodegen_tools_git::AddOpts {
-        paths: vec![temp_dir.path().join("test.txt")],
-        update: false,
-    }).await?;
-    
-    kodegen_tools_git::commit(&repo, kodegen_tools_git::CommitOpts {
-        message: "Initial commit".to_string(),
-        ..Default::default()
-    }).await?;
-    
-    // Create tag
-    let tag_info = create_tag(&repo, TagOpts {
-        name: "v1.0.0".to_string(),
-        message: Some("Release v1.0.0".to_string()),
-        target: None,
-        force: false,
-    }).await?;
-    
+async fn test_create_and_list_tags() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = repo_with_commit(&temp_dir).await;
+
+    let opts = TagOpts::new("v1.0.0").message("Release v1.0.0").tagger(tagger(1_700_000_000));
+    let tag_info = create_tag(repo.clone(), opts).await.unwrap();
     assert_eq!(tag_info.name, "v1.0.0");
     assert!(tag_info.is_annotated);
-    
-    // Check tag exists
-    assert!(tag_exists(&repo, "v1.0.0").await?);
-    
-    // List tags
-    let tags = list_tags(&repo).await?;
+    assert_eq!(tag_info.timestamp.unwrap().timestamp(), 1_700_000_000);
+
+    assert!(tag_exists(repo.clone(), "v1.0.0").await.unwrap());
+
+    let tags = list_tags(repo.clone()).await.unwrap();
     assert_eq!(tags.len(), 1);
     assert_eq!(tags[0].name, "v1.0.0");
-    
-    Ok(())
+    assert_eq!(tags[0].target_commit, tag_info.target_commit);
 }
 
 #[tokio::test]
-async fn test_delete_tag() -> Result<(), Box<dyn std::error::Error>> {
-    let temp_dir = TempDir::new()?;
-    let repo = init_repo(temp_dir.path())?;
-    
-    // Create initial commit
-    std::fs::write(temp_dir.path().join("test.txt"), "test")?;
-    kodegen_tools_git::add(&repo, kodegen_tools_git::AddOpts {
-        paths: vec![temp_dir.path().join("test.txt")],
-        update: false,
-    }).await?;
-    
-    kodegen_tools_git::commit(&repo, kodegen_tools_git::CommitOpts {
-        message: "Initial commit".to_string(),
-        ..Default::default()
-    }).await?;
-    
-    // Create and delete tag
-    create_tag(&repo, TagOpts {
-        name: "v1.0.0".to_string(),
-        message: Some("Release".to_string()),
-        target: None,
-        force: false,
-    }).await?;
-    
-    assert!(tag_exists(&repo, "v1.0.0").await?);
-    
-    delete_tag(&repo, "v1.0.0").await?;
-    
-    assert!(!tag_exists(&repo, "v1.0.0").await?);
-    
-    Ok(())
+async fn test_list_tags_annotation_and_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = repo_with_commit(&temp_dir).await;
+
+    let old = TagOpts::new("v1").message("first").tagger(tagger(1_600_000_000));
+    create_tag(repo.clone(), old).await.unwrap();
+    let new = TagOpts::new("v2").message("second\n").tagger(tagger(1_900_000_000));
+    create_tag(repo.clone(), new).await.unwrap();
+    create_tag(repo.clone(), TagOpts::new("light")).await.unwrap();
+
+    let tags = list_tags(repo.clone()).await.unwrap();
+    let names: Vec<_> = tags.iter().map(|tag| tag.name.as_str()).collect();
+    // Newest first: v2 is tagged in the future, the lightweight tag uses the commit time
+    assert_eq!(names, ["v2", "light", "v1"]);
+
+    assert!(tags[0].is_annotated);
+    assert_eq!(tags[0].message.as_deref().map(str::trim), Some("second"));
+    assert_eq!(tags[0].tagger.as_ref().unwrap().email, "tagger@example.com");
+    assert!(!tags[1].is_annotated);
+    assert!(tags[1].tagger.is_none());
+    // Annotated tags report the commit they point at, not the tag object
+    assert_eq!(tags[0].target_commit, tags[1].target_commit);
+}
+
+#[tokio::test]
+async fn test_tag_timestamps_keep_offset_or_are_missing() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+    std::fs::write(temp_dir.path().join("test.txt"), "test").unwrap();
+    add(repo.clone(), AddOpts::new(["test.txt"])).await.unwrap();
+    let date = parse_date("2020-01-02T03:04:05+02:00").unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("Initial commit")
+            .author(Signature::new("Test", "test@example.com"))
+            .commit_date(date),
+    )
+    .await
+    .unwrap();
+
+    create_tag(repo.clone(), TagOpts::new("on-commit")).await.unwrap();
+    let on_tree = create_tag(repo.clone(), TagOpts::new("on-tree").target("HEAD^{tree}"))
+        .await
+        .unwrap();
+    assert_eq!(on_tree.timestamp, None);
+    assert_eq!(on_tree.warnings[0].kind, WarningKind::MissingTimestamp);
+
+    let tags = list_tags(repo.clone()).await.unwrap();
+    let names: Vec<_> = tags.iter().map(|tag| tag.name.as_str()).collect();
+    // Tags without a timestamp sort last instead of as the newest
+    assert_eq!(names, ["on-commit", "on-tree"]);
+    assert_eq!(tags[0].timestamp, Some(date));
+    assert_eq!(tags[0].timestamp.unwrap().offset().local_minus_utc(), 7200);
+    assert!(tags[0].warnings.is_empty());
+}
+
+#[tokio::test]
+async fn test_delete_tag() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = repo_with_commit(&temp_dir).await;
+
+    let opts = TagOpts::new("v1.0.0").message("Release").tagger(tagger(1_700_000_000));
+    create_tag(repo.clone(), opts).await.unwrap();
+    assert!(tag_exists(repo.clone(), "v1.0.0").await.unwrap());
+
+    delete_tag(repo.clone(), "v1.0.0").await.unwrap();
+
+    assert!(!tag_exists(repo.clone(), "v1.0.0").await.unwrap());
 }
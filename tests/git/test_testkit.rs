@@ -0,0 +1,79 @@
+//! Tests for synthetic repository generation.
+
+use kodegen_tools_git::testkit::{HOT_FILE, RepoSpec, generate};
+use kodegen_tools_git::{is_clean, list_branches};
+
+#[tokio::test]
+async fn test_generate_is_deterministic() {
+    let spec = RepoSpec::new(7)
+        .files(50)
+        .commits(12)
+        .files_per_commit(3)
+        .binary_files(2, 4096)
+        .branches(2, 3)
+        .merge_branches(true);
+
+    let first = generate(spec.clone()).await.unwrap();
+    let second = generate(spec).await.unwrap();
+
+    // 1 initial + 12 changes + 2 merges
+    assert_eq!(first.commits.len(), 15);
+    assert_eq!(first.commits, second.commits);
+    assert_eq!(first.branches, vec!["branch-0", "branch-1"]);
+
+    assert!(first.path().join(HOT_FILE).is_file());
+    assert!(first.path().join("assets/bin1.dat").is_file());
+    assert!(first.path().join("branches/b1/f2.rs").is_file());
+    assert!(is_clean(first.repo.clone()).await.unwrap());
+
+    let branches = list_branches(first.repo.clone()).await.unwrap().unwrap();
+    assert!(branches.contains(&"branch-0".to_string()));
+}
+
+#[cfg(not(feature = "pure-gix"))]
+#[tokio::test]
+async fn test_stash_conflict_scenario() {
+    use kodegen_tools_git::{head_commit, stash_pop};
+    use kodegen_tools_git::testkit::{conflict_content, fabricate_stash_conflict};
+
+    let fixture = generate(RepoSpec::new(6).commits(2)).await.unwrap();
+    let scenario = fabricate_stash_conflict(&fixture.repo, "stash").await.unwrap();
+    assert_eq!(head_commit(&fixture.repo).await.unwrap(), scenario.ours.to_string());
+    assert!(scenario.stash.message.ends_with("stash: stashed change"));
+    assert!(is_clean(&fixture.repo).await.unwrap());
+
+    let file = fixture.path().join(&scenario.path);
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), conflict_content("ours"));
+    assert!(stash_pop(fixture.repo.clone(), None).await.is_err());
+    let content = std::fs::read_to_string(&file).unwrap();
+    assert!(content.contains("<<<<<<<") && content.contains("stashed"), "{content}");
+}
+
+#[tokio::test]
+async fn test_rebase_conflict_scenario() {
+    use kodegen_tools_git::testkit::fabricate_rebase_conflict;
+    use kodegen_tools_git::{GitError, RebaseOpts, head_commit, rebase};
+
+    let fixture = generate(RepoSpec::new(8).commits(2)).await.unwrap();
+    let scenario = fabricate_rebase_conflict(fixture.repo.clone(), "rebase").await.unwrap();
+    assert_eq!(head_commit(fixture.repo.clone()).await.unwrap(), scenario.ours.to_string());
+    assert_eq!(scenario.branch, "rebase-topic");
+    assert_eq!(scenario.commits.len(), 3);
+    assert_eq!(scenario.conflicting_commit, scenario.commits[1]);
+    assert!(is_clean(fixture.repo.clone()).await.unwrap());
+
+    // Only the middle commit conflicts: the branch's other changes apply
+    let topic = RebaseOpts::new("HEAD").branch(&scenario.branch);
+    match rebase(fixture.repo.clone(), topic).await {
+        Err(GitError::MergeConflict { paths, .. }) => {
+            assert_eq!(paths, std::slice::from_ref(&scenario.path));
+        }
+        other => panic!("Expected MergeConflict, got {other:?}"),
+    }
+    let clean = RebaseOpts::new("HEAD")
+        .upstream(scenario.commits[1].to_string())
+        .branch(&scenario.branch);
+    let result = rebase(fixture.repo.clone(), clean).await.unwrap();
+    assert_eq!(result.rewritten.len(), 1);
+    assert_eq!(result.rewritten[0].0, scenario.commits[2]);
+}
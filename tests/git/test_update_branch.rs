@@ -0,0 +1,103 @@
+//! Tests for updating a branch from its base
+
+use gix::refs::transaction::PreviousValue;
+use kodegen_tools_git::{
+    RepoHandle, UpdateBranchOpts, UpdateOutcome, UpdateStrategy, current_branch, update_branch,
+};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo, write_commit};
+
+/// Create `branch` with one commit of `files` on top of `parent`
+fn branch_with(
+    repo: &RepoHandle,
+    branch: &str,
+    parent: gix::ObjectId,
+    files: &[(&str, &str)],
+) -> gix::ObjectId {
+    let id = write_commit(repo.raw(), &[parent], files, &format!("work on {branch}"));
+    repo.raw()
+        .reference(
+            format!("refs/heads/{branch}").as_str(),
+            id,
+            PreviousValue::MustNotExist,
+            "test",
+        )
+        .unwrap();
+    id
+}
+
+fn tip(repo: &RepoHandle, branch: &str) -> gix::ObjectId {
+    repo.raw()
+        .find_reference(format!("refs/heads/{branch}").as_str())
+        .unwrap()
+        .into_fully_peeled_id()
+        .unwrap()
+        .detach()
+}
+
+#[tokio::test]
+async fn test_update_branch_strategies() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "one\n").await;
+    let merged = branch_with(&repo, "merged", base, &[("c.txt", "c\n")]);
+    let rebased = branch_with(&repo, "rebased", base, &[("d.txt", "d\n")]);
+    let conflicting = branch_with(&repo, "conflicting", base, &[("a.txt", "theirs\n")]);
+    let main = commit_file(&repo, "a.txt", "main\n").await;
+
+    let opts = UpdateBranchOpts::new("merged", "HEAD");
+    let result = update_branch(repo.clone(), opts).await.unwrap();
+    assert_eq!(result.outcome, UpdateOutcome::Merged);
+    assert!(!result.fetched);
+    assert!(!result.worktree_updated);
+    assert_eq!(tip(&repo, "merged"), result.new_tip);
+    let commit = repo.raw().find_commit(result.new_tip).unwrap();
+    let parents: Vec<_> = commit.parent_ids().map(|id| id.detach()).collect();
+    assert_eq!(parents, [merged, main]);
+
+    // Nothing left to bring in
+    let opts = UpdateBranchOpts::new("merged", "HEAD");
+    let result = update_branch(repo.clone(), opts).await.unwrap();
+    assert_eq!(result.outcome, UpdateOutcome::UpToDate);
+
+    let opts = UpdateBranchOpts::new("rebased", "HEAD").strategy(UpdateStrategy::Rebase);
+    let result = update_branch(repo.clone(), opts).await.unwrap();
+    assert_eq!(result.outcome, UpdateOutcome::Rebased);
+    assert_eq!(result.rebased.len(), 1);
+    assert_eq!(result.rebased[0].0, rebased);
+    let commit = repo.raw().find_commit(tip(&repo, "rebased")).unwrap();
+    let parents: Vec<_> = commit.parent_ids().map(|id| id.detach()).collect();
+    assert_eq!(parents, [main]);
+    assert_eq!(commit.message_raw_sloppy(), "work on rebased");
+
+    // Conflicts are reported and leave the branch alone
+    for strategy in [UpdateStrategy::Merge, UpdateStrategy::Rebase] {
+        let opts = UpdateBranchOpts::new("conflicting", "HEAD").strategy(strategy);
+        let result = update_branch(repo.clone(), opts).await.unwrap();
+        assert_eq!(result.outcome, UpdateOutcome::Conflicts);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "a.txt");
+        assert_eq!(result.new_tip, conflicting);
+        assert_eq!(tip(&repo, "conflicting"), conflicting);
+    }
+}
+
+#[tokio::test]
+async fn test_update_branch_keeps_untracked_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_test_repo(temp_dir.path()).await;
+    let base = commit_file(&repo, "a.txt", "one\n").await;
+    branch_with(&repo, "upstream", base, &[("b.txt", "b\n")]);
+
+    let file = temp_dir.path().join("b.txt");
+    std::fs::write(&file, "mine\n").unwrap();
+    let current = current_branch(repo.clone()).await.unwrap().name;
+    let err = update_branch(repo.clone(), UpdateBranchOpts::new(&current, "upstream"))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("b.txt"), "{err}");
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "mine\n");
+    assert_eq!(tip(&repo, &current), base);
+}
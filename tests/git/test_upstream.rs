@@ -0,0 +1,53 @@
+//! Tests for upstream divergence checks
+
+use kodegen_tools_git::{
+    CloneOpts, GitError, clone_repo, ensure_up_to_date, init_repo, upstream_status,
+};
+
+use super::support::commit_file;
+
+#[tokio::test]
+async fn test_upstream_divergence() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_repo(&upstream_dir).await.unwrap().unwrap();
+    commit_file(&upstream, "a.txt", "a.txt").await;
+
+    // Without an upstream there is nothing to compare against
+    assert!(upstream_status(upstream.clone(), None).await.unwrap().is_none());
+    assert!(ensure_up_to_date(upstream.clone(), None).await.unwrap().is_none());
+
+    let clone_dir = temp_dir.path().join("clone");
+    let url = format!("file://{}", upstream_dir.display());
+    let cloned = clone_repo(CloneOpts::new(url, &clone_dir)).await.unwrap().unwrap();
+    let branch = cloned.default_branch.unwrap();
+    let repo = cloned.repo;
+
+    let status = upstream_status(repo.clone(), None).await.unwrap().unwrap();
+    assert_eq!(status.branch, branch);
+    assert_eq!(status.upstream, format!("origin/{branch}"));
+    assert_eq!(status.remote, "origin");
+    assert_eq!((status.ahead, status.behind), (0, 0));
+
+    commit_file(&repo, "local.txt", "local.txt").await;
+    let status = ensure_up_to_date(repo.clone(), Some(&branch)).await.unwrap().unwrap();
+    assert!(status.fetched);
+    assert_eq!((status.ahead, status.behind), (1, 0));
+
+    // A new upstream commit is only seen after fetching
+    commit_file(&upstream, "b.txt", "b.txt").await;
+    let status = upstream_status(repo.clone(), None).await.unwrap().unwrap();
+    assert!(!status.is_behind());
+
+    let result = ensure_up_to_date(repo.clone(), None).await;
+    let Err(GitError::BehindUpstream { ahead, behind, .. }) = result else {
+        panic!("expected the branch to be behind, got {result:?}");
+    };
+    assert_eq!((ahead, behind), (1, 1));
+    let status = upstream_status(repo.clone(), None).await.unwrap().unwrap();
+    assert!(status.is_behind());
+
+    // Names that are not local branches are not checked
+    assert!(upstream_status(repo.clone(), Some("no-such-branch")).await.unwrap().is_none());
+}
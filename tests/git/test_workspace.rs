@@ -0,0 +1,79 @@
+//! Tests for coordinated commits across repositories
+
+use kodegen_tools_git::{
+    AddOpts, CommitOpts, RepoHandle, Signature, WorkspaceCommitOutcome, WorkspaceCommitPolicy,
+    add, commit, head_commit, init_repo, workspace_commit, workspace_status,
+};
+use tempfile::TempDir;
+
+async fn repo_with_commit(dir: &TempDir) -> RepoHandle {
+    let repo = init_repo(dir.path()).await.unwrap().unwrap();
+    std::fs::write(dir.path().join("file.txt"), "one\n").unwrap();
+    add(repo.clone(), AddOpts::new(["file.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("Initial commit").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+    repo
+}
+
+fn path(dir: &TempDir) -> String {
+    dir.path().display().to_string()
+}
+
+#[tokio::test]
+async fn test_workspace_commit_shares_message() {
+    let backend = TempDir::new().unwrap();
+    let infra = TempDir::new().unwrap();
+    let docs = TempDir::new().unwrap();
+    let backend_repo = repo_with_commit(&backend).await;
+    let infra_repo = repo_with_commit(&infra).await;
+    repo_with_commit(&docs).await;
+    std::fs::write(backend.path().join("file.txt"), "two\n").unwrap();
+    std::fs::write(infra.path().join("new.txt"), "new\n").unwrap();
+
+    let repos = [path(&backend), path(&infra), path(&docs)];
+    let status = workspace_status(repos.clone()).await.unwrap();
+    assert!(!status[0].is_clean);
+    assert!(status[1].is_clean && status[1].has_untracked);
+    assert!(status[2].is_clean && !status[2].has_untracked);
+
+    let policy = WorkspaceCommitPolicy::new()
+        .include_untracked(true)
+        .trailer("Refs", "#42")
+        .change_id(true)
+        .author(Signature::new("Test", "test@example.com"));
+    let result = workspace_commit(repos, "Rename the API", policy).await.unwrap();
+
+    let change_id = result.change_id.clone().unwrap();
+    assert!(result.message.ends_with(&format!("Refs: #42\nChange-Id: {change_id}\n")));
+    assert_eq!(result.commits().count(), 2);
+    assert_eq!(result.repos[2].outcome, WorkspaceCommitOutcome::Clean);
+    assert!(!result.has_failures());
+
+    for repo in [&backend_repo, &infra_repo] {
+        let head = head_commit(repo.clone()).await.unwrap();
+        let commit = repo.raw().find_commit(gix::ObjectId::from_hex(head.as_bytes()).unwrap());
+        let message = commit.unwrap().message_raw().unwrap().to_string();
+        assert!(message.starts_with("Rename the API\n\n"));
+        assert!(message.contains(&change_id));
+    }
+}
+
+#[tokio::test]
+async fn test_workspace_commit_all_or_nothing() {
+    let backend = TempDir::new().unwrap();
+    let backend_repo = repo_with_commit(&backend).await;
+    let before = head_commit(backend_repo.clone()).await.unwrap();
+    std::fs::write(backend.path().join("file.txt"), "two\n").unwrap();
+    let missing = backend.path().join("no-such-repo").display().to_string();
+
+    let policy = WorkspaceCommitPolicy::new().all_or_nothing(true);
+    let result = workspace_commit([path(&backend), missing], "Change", policy).await.unwrap();
+
+    assert_eq!(result.repos[0].outcome, WorkspaceCommitOutcome::Skipped);
+    assert!(matches!(result.repos[1].outcome, WorkspaceCommitOutcome::Failed(_)));
+    assert_eq!(head_commit(backend_repo.clone()).await.unwrap(), before);
+}
@@ -0,0 +1,358 @@
+//! Tests for worktree operations.
+
+use kodegen_tools_git::{
+    AddOpts, BranchOpts, CloneOpts, CommitOpts, GitError, InitOpts, PruneReason, RepoHandle,
+    Signature, WarningKind, WorktreeAddOpts, WorktreePruneOpts, add, branch, clone_repo, commit,
+    init_repo, init_repo_with, list_worktrees, open_worktree, worktree_add, worktree_prune_with,
+    worktree_statuses,
+};
+use tempfile::TempDir;
+
+use super::support::{commit_file, init_test_repo, with_identity, write_commit};
+
+#[tokio::test]
+async fn test_worktree_statuses_main() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_repo(temp_dir.path()).await.unwrap().unwrap();
+
+    std::fs::write(temp_dir.path().join("a.txt"), "one\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("first").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    let statuses = worktree_statuses(repo.clone()).await.unwrap().unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert!(statuses[0].is_main);
+    assert_eq!(statuses[0].is_dirty, Some(false));
+    assert!(statuses[0].in_progress.is_none());
+
+    std::fs::write(temp_dir.path().join("a.txt"), "two\n").unwrap();
+    let statuses = worktree_statuses(repo).await.unwrap().unwrap();
+    assert_eq!(statuses[0].is_dirty, Some(true));
+}
+
+#[tokio::test]
+async fn test_open_worktree_commits_to_its_own_head() {
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main");
+    let repo = init_repo(&main_path).await.unwrap().unwrap();
+
+    std::fs::write(main_path.join("a.txt"), "one\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    let first = commit(
+        repo.clone(),
+        CommitOpts::message("first").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    let wt_path = temp_dir.path().join("linked");
+    worktree_add(
+        repo.clone(),
+        WorktreeAddOpts::new(&wt_path)
+            .committish(first.id.to_string())
+            .detach(true),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    // By name and by path resolve to the same worktree
+    let by_name = open_worktree(repo.clone(), "linked").await.unwrap().unwrap();
+    let by_path = open_worktree(repo.clone(), &wt_path).await.unwrap().unwrap();
+    assert_eq!(by_name.raw().git_dir(), by_path.raw().git_dir());
+
+    std::fs::write(wt_path.join("b.txt"), "two\n").unwrap();
+    add(by_name.clone(), AddOpts::new(["b.txt"])).await.unwrap();
+    let second = commit(
+        by_name.clone(),
+        CommitOpts::message("second").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    // Worktree HEAD moved, main HEAD did not
+    assert_eq!(by_name.raw().head_id().unwrap().detach(), second.id);
+    assert_eq!(repo.raw().head_id().unwrap().detach(), first.id);
+
+    // Listing from the worktree handle still reports the real main worktree
+    let statuses = worktree_statuses(by_name).await.unwrap().unwrap();
+    assert_eq!(statuses.len(), 2);
+    assert!(statuses[0].is_main);
+    assert_eq!(
+        statuses[0].path.canonicalize().unwrap(),
+        main_path.canonicalize().unwrap()
+    );
+
+    assert!(open_worktree(repo, "missing").await.unwrap().is_err());
+}
+
+#[tokio::test]
+async fn test_worktree_of_separate_git_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main");
+    let repo = init_repo_with(
+        &main_path,
+        InitOpts::new().separate_git_dir(temp_dir.path().join("store.git")),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let first = commit_file(&repo, "a.txt", "one\n").await;
+
+    let wt_path = temp_dir.path().join("linked");
+    worktree_add(
+        repo.clone(),
+        WorktreeAddOpts::new(&wt_path)
+            .committish(first.to_string())
+            .detach(true),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert!(wt_path.join("a.txt").is_file());
+
+    // The main worktree is found through the git dir, not next to it
+    let linked = open_worktree(repo, "linked").await.unwrap().unwrap();
+    let statuses = worktree_statuses(linked).await.unwrap().unwrap();
+    assert_eq!(statuses.len(), 2);
+    assert!(statuses[0].is_main);
+    assert_eq!(
+        statuses[0].path.canonicalize().unwrap(),
+        main_path.canonicalize().unwrap()
+    );
+    assert_eq!(statuses[0].is_dirty, Some(false));
+}
+
+/// Commit on top of `repo`'s HEAD, so branches created earlier are behind it
+async fn advance(repo: &RepoHandle, dir: &std::path::Path) {
+    std::fs::write(dir.join("b.txt"), "two\n").unwrap();
+    add(repo.clone(), AddOpts::new(["b.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("second").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_worktree_prune_policies() {
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main");
+    let repo = init_test_repo(&main_path).await;
+
+    std::fs::write(main_path.join("a.txt"), "one\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("first").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+
+    // One merged branch worktree, one whose directory gets deleted
+    branch(repo.clone(), BranchOpts::new("done")).await.unwrap().unwrap();
+    let merged_path = temp_dir.path().join("merged");
+    worktree_add(repo.clone(), WorktreeAddOpts::new(&merged_path).committish("done"))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let gone_path = temp_dir.path().join("gone");
+    worktree_add(repo.clone(), WorktreeAddOpts::new(&gone_path).committish("HEAD").detach(true))
+        .await
+        .unwrap()
+        .unwrap();
+    std::fs::remove_dir_all(&gone_path).unwrap();
+
+    // A branch still at the target tip has no work of its own yet
+    let opts = WorktreePruneOpts::new().merged_into("HEAD").dry_run(true);
+    let report = worktree_prune_with(repo.clone(), opts).await.unwrap().unwrap();
+    assert!(report.pruned.iter().all(|wt| wt.name != "merged"));
+
+    advance(&repo, &main_path).await;
+
+    // Dry run reports both but removes nothing
+    let opts = WorktreePruneOpts::new().merged_into("HEAD").dry_run(true);
+    let report = worktree_prune_with(repo.clone(), opts).await.unwrap().unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.pruned.len(), 2);
+    assert!(merged_path.exists());
+
+    let reason_of = |name: &str| {
+        report
+            .pruned
+            .iter()
+            .find(|wt| wt.name == name)
+            .map(|wt| wt.reason.clone())
+    };
+    assert_eq!(reason_of("gone"), Some(PruneReason::Missing));
+    assert_eq!(
+        reason_of("merged"),
+        Some(PruneReason::Merged { branch: "done".to_string() })
+    );
+
+    // Dirty worktrees are kept
+    std::fs::write(merged_path.join("a.txt"), "changed\n").unwrap();
+    let opts = WorktreePruneOpts::new().merged_into("HEAD");
+    let report = worktree_prune_with(repo.clone(), opts).await.unwrap().unwrap();
+    assert_eq!(report.pruned.len(), 1);
+    assert_eq!(report.skipped.len(), 1);
+    assert!(merged_path.exists());
+
+    // Clean again: removed for real
+    std::fs::write(merged_path.join("a.txt"), "one\n").unwrap();
+    let opts = WorktreePruneOpts::new().merged_into("HEAD");
+    let report = worktree_prune_with(repo, opts).await.unwrap().unwrap();
+    assert_eq!(report.pruned.len(), 1);
+    assert!(!merged_path.exists());
+}
+
+#[tokio::test]
+async fn test_worktree_prune_keeps_untracked_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main");
+    let repo = init_test_repo(&main_path).await;
+
+    std::fs::write(main_path.join("a.txt"), "one\n").unwrap();
+    add(repo.clone(), AddOpts::new(["a.txt"])).await.unwrap();
+    commit(
+        repo.clone(),
+        CommitOpts::message("first").author(Signature::new("Test", "test@example.com")),
+    )
+    .await
+    .unwrap();
+    branch(repo.clone(), BranchOpts::new("done")).await.unwrap().unwrap();
+    let merged_path = temp_dir.path().join("merged");
+    worktree_add(repo.clone(), WorktreeAddOpts::new(&merged_path).committish("done"))
+        .await
+        .unwrap()
+        .unwrap();
+    advance(&repo, &main_path).await;
+
+    // New work that was never staged is still work
+    std::fs::write(merged_path.join("notes.txt"), "draft\n").unwrap();
+    let opts = WorktreePruneOpts::new().merged_into("HEAD");
+    let report = worktree_prune_with(repo.clone(), opts).await.unwrap().unwrap();
+    assert!(report.pruned.is_empty());
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].name, "merged");
+    assert!(merged_path.join("notes.txt").exists());
+
+    // Without it, the worktree goes
+    std::fs::remove_file(merged_path.join("notes.txt")).unwrap();
+    let opts = WorktreePruneOpts::new().merged_into("HEAD");
+    let report = worktree_prune_with(repo, opts).await.unwrap().unwrap();
+    assert_eq!(report.pruned.len(), 1);
+    assert!(!merged_path.exists());
+}
+
+#[tokio::test]
+async fn test_worktree_add_fetches_remote_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let upstream_dir = temp_dir.path().join("upstream");
+    std::fs::create_dir(&upstream_dir).unwrap();
+    let upstream = init_test_repo(&upstream_dir).await;
+    let base = commit_file(&upstream, "a.txt", "one\n").await;
+
+    let url = format!("file://{}", upstream_dir.display());
+    let clone_dir = temp_dir.path().join("clone");
+    let cloned = clone_repo(CloneOpts::new(url, &clone_dir))
+        .await
+        .unwrap()
+        .unwrap();
+    let repo = with_identity(&cloned.repo).await;
+
+    // Pushed after the clone, so not fetched yet
+    let feature = write_commit(upstream.raw(), &[base], &[("b.txt", "two\n")], "feature");
+    upstream
+        .raw()
+        .reference(
+            "refs/heads/pr/feature",
+            feature,
+            gix::refs::transaction::PreviousValue::MustNotExist,
+            "test",
+        )
+        .unwrap();
+
+    let wt_path = temp_dir.path().join("review");
+    worktree_add(
+        repo.clone(),
+        WorktreeAddOpts::new(&wt_path).committish("origin/pr/feature"),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(wt_path.join("b.txt")).unwrap(),
+        "two\n"
+    );
+
+    let worktree = gix::open(&wt_path).unwrap();
+    let head = worktree.head_name().unwrap().unwrap();
+    assert_eq!(head.as_bstr(), "refs/heads/pr/feature");
+    assert_eq!(worktree.head_id().unwrap().detach(), feature);
+
+    let clone = gix::open(&clone_dir).unwrap();
+    let config = clone.config_snapshot();
+    assert_eq!(
+        config.string("branch.pr/feature.remote").unwrap().as_ref(),
+        "origin"
+    );
+    assert_eq!(
+        config.string("branch.pr/feature.merge").unwrap().as_ref(),
+        "refs/heads/pr/feature"
+    );
+
+    // The branch now exists and is checked out in the new worktree
+    let other_path = temp_dir.path().join("other");
+    let result = worktree_add(
+        repo.clone(),
+        WorktreeAddOpts::new(&other_path).committish("origin/pr/feature"),
+    )
+    .await
+    .unwrap();
+    assert!(
+        matches!(result, Err(GitError::BranchInUse(_))),
+        "{result:?}"
+    );
+
+    // Missing upstream branches are reported as such
+    let result = worktree_add(
+        repo,
+        WorktreeAddOpts::new(&other_path).committish("origin/missing"),
+    )
+    .await
+    .unwrap();
+    assert!(result.is_err());
+    assert!(!other_path.exists());
+}
+
+#[tokio::test]
+async fn test_list_worktrees_warns_about_unreadable_head() {
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main");
+    let repo = init_test_repo(&main_path).await;
+    commit_file(&repo, "a.txt", "one\n").await;
+
+    let linked_path = temp_dir.path().join("linked");
+    worktree_add(repo.clone(), WorktreeAddOpts::new(&linked_path).committish("HEAD").detach(true))
+        .await
+        .unwrap()
+        .unwrap();
+    std::fs::write(main_path.join(".git/worktrees/linked/HEAD"), "garbage\n").unwrap();
+
+    // The worktree is still listed, with the problem instead of its HEAD
+    let worktrees = list_worktrees(repo).await.unwrap().unwrap();
+    assert_eq!(worktrees.len(), 2);
+    assert!(worktrees[0].warnings.is_empty());
+    let linked = &worktrees[1];
+    assert!(linked.head_commit.is_none());
+    assert_eq!(linked.warnings.len(), 1);
+    assert_eq!(linked.warnings[0].kind, WarningKind::UnreadableHead);
+}
@@ -18,7 +18,11 @@ fn test_repository_info() {
         path: PathBuf::from("/test"),
         is_bare: false,
         git_dir: PathBuf::from("/test/.git"),
+        common_dir: PathBuf::from("/test/.git"),
         work_dir: Some(PathBuf::from("/test")),
+        gitfile: None,
+        is_shallow: false,
+        environment: Default::default(),
     };
 
     assert_eq!(info.path, PathBuf::from("/test"));